@@ -0,0 +1,60 @@
+use std::time::Duration;
+
+use iai_callgrind::{binary_benchmark, binary_benchmark_group, main, Command, InputScript};
+
+#[binary_benchmark]
+#[bench::add(InputScript::new().wait_for("> ").write_line("add 1 2").wait_for("3\n").build())]
+#[bench::echo(InputScript::new().wait_for("> ").write_line("hello").wait_for("hello\n").build())]
+#[bench::two_prompts(
+    InputScript::new()
+        .wait_for("> ")
+        .write_line("add 1 2")
+        .wait_for("> ")
+        .sleep(Duration::from_millis(10))
+        .write_line("add 3 4")
+        .wait_for("7\n")
+        .build()
+)]
+#[bench::without_wait(InputScript::new().write_line("add 1 2").build())]
+fn repl(mut input_script: InputScript) -> Command {
+    Command::new(env!("CARGO_BIN_EXE_repl"))
+        .input_script(input_script.write_line("quit").wait_for("bye\n"))
+        .build()
+}
+
+// The repl never prints `unexpected`, so the benchmark fails after the timeout and the repl is
+// killed
+#[binary_benchmark]
+fn repl_timeout() -> Command {
+    Command::new(env!("CARGO_BIN_EXE_repl"))
+        .input_script(
+            InputScript::new()
+                .timeout(Duration::from_millis(500))
+                .wait_for("> ")
+                .write_line("hello")
+                .wait_for("unexpected")
+                .build(),
+        )
+        .build()
+}
+
+// The repl exits and closes its stdout before it prints `bye`, so the benchmark fails without
+// waiting for the timeout
+#[binary_benchmark]
+fn repl_closed() -> Command {
+    Command::new(env!("CARGO_BIN_EXE_repl"))
+        .input_script(
+            InputScript::new()
+                .timeout(Duration::from_secs(300))
+                .wait_for("> ")
+                .write_line("exit")
+                .wait_for("bye\n")
+                .build(),
+        )
+        .build()
+}
+
+binary_benchmark_group!(name = input_script; benchmarks = repl);
+binary_benchmark_group!(name = input_script_errors; benchmarks = repl_timeout, repl_closed);
+
+main!(binary_benchmark_groups = input_script, input_script_errors);
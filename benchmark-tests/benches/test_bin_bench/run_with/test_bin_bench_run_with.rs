@@ -0,0 +1,60 @@
+//! Run a binary benchmark in-process with `run_with` instead of the `main!` macro
+
+use iai_callgrind_runner::api::{
+    BinaryBenchmark, BinaryBenchmarkBench, BinaryBenchmarkConfig, BinaryBenchmarkGroup,
+    BinaryBenchmarkGroups, CommandKind, ValgrindTool,
+};
+use iai_callgrind_runner::runner::{run_with, BenchmarkGroups, RunConfig};
+
+fn main() {
+    let command = iai_callgrind::Command::new(env!("CARGO_BIN_EXE_echo"))
+        .arg("run_with")
+        .build();
+
+    let groups = BinaryBenchmarkGroups {
+        command_line_args: std::env::args().skip(1).collect(),
+        config: BinaryBenchmarkConfig::default(),
+        default_tool: ValgrindTool::Callgrind,
+        groups: vec![BinaryBenchmarkGroup {
+            binary_benchmarks: vec![BinaryBenchmark {
+                benches: vec![BinaryBenchmarkBench {
+                    args: None,
+                    command: CommandKind::Default(Box::new(command.into())),
+                    config: None,
+                    function_name: "echo_bench".to_owned(),
+                    has_setup: false,
+                    has_teardown: false,
+                    id: Some("run_with".to_owned()),
+                }],
+                config: None,
+            }],
+            compare_by_id: None,
+            config: None,
+            has_setup: false,
+            has_teardown: false,
+            id: "my_group".to_owned(),
+        }],
+        has_setup: false,
+        has_teardown: false,
+        metadata: vec![],
+    };
+
+    let config = RunConfig::new(
+        env!("CARGO_PKG_NAME"),
+        env!("CARGO_MANIFEST_DIR"),
+        file!(),
+        std::env::current_exe().unwrap(),
+        BenchmarkGroups::Binary(groups),
+    );
+    let summaries = run_with(config).unwrap();
+
+    let is_list = std::env::args().any(|arg| arg == "--list");
+    assert_eq!(summaries.summaries.len(), usize::from(!is_list));
+    assert!(!summaries.is_regressed());
+    assert_eq!(summaries.num_errored, 0);
+
+    for summary in &summaries.summaries {
+        assert_eq!(summary.function_name, "echo_bench");
+        assert_eq!(summary.id.as_deref(), Some("run_with"));
+    }
+}
@@ -0,0 +1,23 @@
+use benchmark_tests::{bubble_sort, fibonacci, setup_worst_case_array};
+use iai_callgrind::criterion_compat::{black_box, criterion_group, criterion_main, Criterion};
+
+fn bench_fibonacci(c: &mut Criterion) {
+    c.bench_function("fib 8", |b| b.iter(|| fibonacci(black_box(8))));
+    c.bench_function("fib 16", |b| b.iter(|| fibonacci(black_box(16))));
+}
+
+fn bench_bubble_sort(c: &mut Criterion) {
+    c.bench_function("bubble sort", |b| {
+        let array = setup_worst_case_array(100);
+        b.iter(move || bubble_sort(black_box(array.clone())));
+    });
+}
+
+criterion_group!(benches, bench_fibonacci);
+criterion_group!(
+    name = other_benches;
+    config = Criterion::default();
+    targets = bench_bubble_sort
+);
+
+criterion_main!(benches, other_benches);
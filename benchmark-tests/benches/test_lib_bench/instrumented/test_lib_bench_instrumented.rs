@@ -0,0 +1,30 @@
+use std::hint::black_box;
+
+use benchmark_tests::bubble_sort;
+use iai_callgrind::{
+    instrumented, library_benchmark, library_benchmark_group, main, Cachegrind, Callgrind,
+    LibraryBenchmarkConfig,
+};
+
+#[library_benchmark(
+    config = LibraryBenchmarkConfig::default()
+        .tool(Callgrind::with_args(["instr-atstart=no"]))
+)]
+#[bench::callgrind(vec![3, 2, 1])]
+#[bench::callgrind_and_cachegrind(
+    args = (vec![3, 2, 1]),
+    config = LibraryBenchmarkConfig::default()
+        .tool(Cachegrind::with_args(["instr-at-start=no"]))
+)]
+fn bench_instrumented(array: Vec<i32>) -> Vec<i32> {
+    instrumented! {
+        black_box(bubble_sort(array))
+    }
+}
+
+library_benchmark_group!(
+    name = my_group;
+    benchmarks = bench_instrumented
+);
+
+main!(library_benchmark_groups = my_group);
@@ -0,0 +1,28 @@
+use std::hint::black_box;
+
+use benchmark_tests::fibonacci;
+use iai_callgrind::{library_benchmark, library_benchmark_group, main};
+
+fn produce(num: u64) -> u64 {
+    println!("produced: {num}");
+    num
+}
+
+// The elements are produced with a `map`, so the output shows how often the `iter` expression is
+// evaluated. The size hint is exact, so the elements are not produced to count them. All elements
+// run in the benchmark process of the first element, so each element is produced exactly once.
+fn produced_elements(num: u64) -> impl Iterator<Item = u64> {
+    (1..=num).map(produce)
+}
+
+#[library_benchmark]
+#[benches::lazy(iter = produced_elements(3))]
+fn bench_lazy(num: u64) -> u64 {
+    black_box(fibonacci(num))
+}
+
+library_benchmark_group!(
+    name = my_group;
+    benchmarks = bench_lazy
+);
+main!(library_benchmark_groups = my_group);
@@ -1,13 +1,13 @@
 use std::hint::black_box;
 
-use iai_callgrind::{library_benchmark, library_benchmark_group, main};
+use iai_callgrind::{library_benchmark, library_benchmark_group, main, LibraryBenchmarkConfig};
 
 #[library_benchmark]
 fn minimal_bench() -> u64 {
     black_box(42)
 }
 
-#[library_benchmark]
+#[library_benchmark(config = LibraryBenchmarkConfig::default().tags(["other", "list"]))]
 fn other_bench() -> u64 {
     black_box(42)
 }
@@ -19,7 +19,11 @@ library_benchmark_group!(
 
 library_benchmark_group!(
     name = group_2;
+    config = LibraryBenchmarkConfig::default().tags(["group_2"]);
     benchmarks = other_bench, minimal_bench
 );
 
-main!(library_benchmark_groups = group_1, group_2);
+main!(
+    config = LibraryBenchmarkConfig::default().tags(["list"]);
+    library_benchmark_groups = group_1, group_2
+);
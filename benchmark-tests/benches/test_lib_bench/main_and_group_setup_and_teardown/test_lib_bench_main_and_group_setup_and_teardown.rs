@@ -1,13 +1,17 @@
 use std::fs::File;
 use std::io::{Read, Write};
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 use iai_callgrind::{library_benchmark, library_benchmark_group, main};
 
 const GROUP_SETUP_FILE: &str = "/tmp/iai-callgrind.group_setup.tmp";
 
+static PROCESS_SETUP_DONE: AtomicBool = AtomicBool::new(false);
+
 #[library_benchmark]
 fn simple_bench() {
+    assert!(PROCESS_SETUP_DONE.load(Ordering::SeqCst));
     let mut file = File::open(GROUP_SETUP_FILE).unwrap();
     let mut actual = String::new();
     file.read_to_string(&mut actual).unwrap();
@@ -83,9 +87,19 @@ fn main_teardown() {
     println!("MAIN TEARDOWN");
 }
 
+fn process_setup() {
+    PROCESS_SETUP_DONE.store(true, Ordering::SeqCst);
+}
+
+fn process_teardown() {
+    assert!(PROCESS_SETUP_DONE.load(Ordering::SeqCst));
+}
+
 main!(
     setup = main_setup();
     teardown = main_teardown();
+    process_setup = process_setup();
+    process_teardown = process_teardown();
     library_benchmark_groups = simple_group_with_setup,
     // Check group is supposed to run directory after `simple_group_with_setup`
     check_group,
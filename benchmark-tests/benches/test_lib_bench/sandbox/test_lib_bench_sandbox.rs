@@ -0,0 +1,50 @@
+use std::hint::black_box;
+use std::path::PathBuf;
+
+use iai_callgrind::{
+    library_benchmark, library_benchmark_group, main, LibraryBenchmarkConfig, Sandbox,
+};
+
+fn check_file_exists(path: &str, should_exist: bool) -> PathBuf {
+    let path = PathBuf::from(path);
+    assert_eq!(path.is_file(), should_exist);
+    path
+}
+
+fn remove_file(path: PathBuf) {
+    if path.exists() {
+        std::fs::remove_file(path).unwrap();
+    }
+}
+
+#[library_benchmark(setup = check_file_exists, teardown = remove_file)]
+#[bench::with_fixture(
+    args = ("one_line.fix", true),
+    config = LibraryBenchmarkConfig::default()
+        .sandbox(Sandbox::new(true)
+            .fixtures(["benchmark-tests/benches/fixtures/one_line.fix"])
+        )
+)]
+#[bench::without_fixture(
+    args = ("one_line.fix", false),
+    config = LibraryBenchmarkConfig::default().sandbox(Sandbox::new(true))
+)]
+fn with_sandbox(path: PathBuf) -> PathBuf {
+    if path.exists() {
+        black_box(std::fs::read_to_string(&path).unwrap());
+    }
+    path
+}
+
+#[library_benchmark]
+#[bench::check_file(
+    args = ("benches/fixtures/one_line.fix", true),
+    config = LibraryBenchmarkConfig::default().sandbox(Sandbox::new(false)),
+    setup = check_file_exists
+)]
+fn without_sandbox(path: PathBuf) -> usize {
+    black_box(std::fs::read_to_string(path).unwrap().len())
+}
+
+library_benchmark_group!(name = my_group; benchmarks = with_sandbox, without_sandbox);
+main!(library_benchmark_groups = my_group);
@@ -1,6 +1,6 @@
 use std::hint::black_box;
 
-use iai_callgrind::{library_benchmark, library_benchmark_group, main};
+use iai_callgrind::{library_benchmark, library_benchmark_group, main, BenchContext};
 
 fn setup_two_arguments(first: u64, second: u64) -> u64 {
     first + second
@@ -34,6 +34,17 @@ fn teardown_other((result, expected): (u64, u64)) {
     }
 }
 
+fn setup_with_context(context: BenchContext, value: u64) -> (BenchContext, u64) {
+    (context, value)
+}
+
+fn teardown_with_context(context: BenchContext, (other, expected_id): (BenchContext, &str)) {
+    assert_eq!(context, other);
+    assert_eq!(context.group, "bench_fibonacci_group");
+    assert_eq!(context.function, "bench_with_context");
+    assert_eq!(context.id, Some(expected_id));
+}
+
 #[library_benchmark]
 #[bench::no_argument(args = (), setup = setup_no_argument)]
 #[bench::one_argument(args = (3), setup = setup_one_argument)]
@@ -91,6 +102,18 @@ fn benches_global_setup_and_teardown((value, expected): (u64, u64)) -> (u64, u64
     black_box((black_box(value * value), expected))
 }
 
+#[library_benchmark(context = true, setup = setup_with_context, teardown = teardown_with_context)]
+#[bench::single(1)]
+#[benches::multiple(2, 3)]
+fn bench_with_context((context, value): (BenchContext, u64)) -> (BenchContext, &'static str) {
+    let id = match black_box(value) {
+        1 => "single",
+        2 => "multiple_0",
+        _ => "multiple_1",
+    };
+    (context, id)
+}
+
 library_benchmark_group!(
     name = bench_fibonacci_group;
     benchmarks =
@@ -98,7 +121,8 @@ library_benchmark_group!(
         bench_only_teardown,
         benches_only_setup,
         benches_only_teardown,
-        benches_global_setup_and_teardown
+        benches_global_setup_and_teardown,
+        bench_with_context
 );
 
 main!(library_benchmark_groups = bench_fibonacci_group);
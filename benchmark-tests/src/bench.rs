@@ -83,7 +83,18 @@ lazy_static! {
         Regex::new(r"^(##(?: \S+: \S+)+)(\s*)([|].*)$").expect("Regex should compile");
     static ref ABSOLUTE_PATH_RE: Regex =
         Regex::new(r"(\s+)([/][^/]*)+").expect("Regex should compile");
-    // Iai-Callgrind result: Success. 2 completed without regressions; 0 regressed; 2 benchmarks finished in 0.296s
+    // The json output of `--list`:
+    // "bench_bin": "/home/user/target/release/deps/test_lib_bench_list_arg-f75f9466e1cab6c2",
+    static ref JSON_BENCH_BIN_RE: Regex =
+        Regex::new(r#"^(\s*"bench_bin":\s*")[^"]*("[,]?)$"#).expect("Regex should compile");
+    // "package_dir": "/home/user/iai-callgrind/benchmark-tests",
+    static ref JSON_ABSOLUTE_PATH_RE: Regex =
+        Regex::new(r#"^(\s*"[a-z_]+":\s*")([/][^/"]*)+("[,]?)$"#).expect("Regex should compile");
+    // Iai-Callgrind environment: valgrind 3.23.0 | rustc 1.80.0 | x86_64-unknown-linux-gnu | ...
+    // Iai-Callgrind tools: callgrind, dhat
+    static ref ENVIRONMENT_RE: Regex =
+        Regex::new(r"^Iai-Callgrind (environment|tools):").expect("Regex should compile");
+    // Iai-Callgrind result: Ok. 2 passed, 0 regressed, 0 errored, 0 skipped; 2 benchmarks finished in 0.296s
     static ref SUMMARY_LINE_RE: Regex =
         Regex::new(r"^(Iai-Callgrind result:.*finished in\s*)([0-9.]+)(s)$").expect("Regex should compile");
     static ref THREAD_PANICKED: Regex =
@@ -612,6 +623,12 @@ impl BenchmarkOutput {
             //   Command:            target/release/sort
             //   Details: <__DETAILS__>
             // ```
+            // The environment header and the tools after the summary line depend on the system
+            // and the date of the benchmark run, so they are filtered completely
+            if ENVIRONMENT_RE.is_match(line) {
+                continue;
+            }
+
             if details {
                 if NOT_DETAILS_RE.is_match(line) {
                     details = false;
@@ -725,6 +742,12 @@ impl BenchmarkOutput {
                     // Filter the benchmark command of library benchmarks because it has a random
                     // hash in it's name
                     COMMAND_RE.replace(line, "$1 <__COMMAND__>")
+                } else if JSON_BENCH_BIN_RE.is_match(line) {
+                    // Same as the benchmark command above
+                    JSON_BENCH_BIN_RE.replace(line, "$1<__BENCH_BIN__>$2")
+                } else if JSON_ABSOLUTE_PATH_RE.is_match(line) {
+                    // Replace absolute paths in the json output
+                    JSON_ABSOLUTE_PATH_RE.replace(line, "$1<__ABS_PATH__>$2$3")
                 } else {
                     // Replace absolute paths
                     ABSOLUTE_PATH_RE.replace_all(line, "$1<__ABS_PATH__>$2")
@@ -1146,6 +1169,42 @@ mod tests {
         );
     }
 
+    #[rstest]
+    #[case::bench_bin(
+        r#""bench_bin": "/some/target/release/deps/test_lib_bench_list_arg-f75f9466e1cab6c2","#,
+        r#""bench_bin": "<__BENCH_BIN__>","#
+    )]
+    #[case::bench_bin_with_indent(
+        r#"  "bench_bin": "/some/test_lib_bench_list_arg-f75f9466e1cab6c2""#,
+        r#"  "bench_bin": "<__BENCH_BIN__>""#
+    )]
+    fn test_json_bench_bin_re(#[case] haystack: &str, #[case] replaced: &str) {
+        assert_eq!(
+            JSON_BENCH_BIN_RE.replace(haystack, "$1<__BENCH_BIN__>$2"),
+            replaced
+        );
+    }
+
+    #[rstest]
+    #[case::not_absolute(r#""bench_file": "benches/bench.rs","#, None)]
+    #[case::no_path(r#""--toggle-collect=*::__iai_callgrind_wrapper_mod::*""#, None)]
+    #[case::absolute(
+        r#""output_dir": "/some/target/iai/group_1/minimal_bench","#,
+        Some(r#""output_dir": "<__ABS_PATH__>/minimal_bench","#)
+    )]
+    #[case::absolute_without_comma(
+        r#"    "package_dir": "/some/benchmark-tests""#,
+        Some(r#"    "package_dir": "<__ABS_PATH__>/benchmark-tests""#)
+    )]
+    fn test_json_absolute_path_re(#[case] haystack: &str, #[case] replaced: Option<&str>) {
+        assert_eq!(
+            JSON_ABSOLUTE_PATH_RE
+                .is_match(haystack)
+                .then(|| JSON_ABSOLUTE_PATH_RE.replace(haystack, "$1<__ABS_PATH__>$2$3")),
+            replaced.map(Into::into)
+        );
+    }
+
     #[rstest]
     #[case::instructions_positive_when_0_allowed(
         "Performance has regressed: Instructions (133 -> 196) regressed by +47.3684% (>+0.00000%)",
@@ -0,0 +1,30 @@
+use std::io::{stdin, stdout, BufRead, Write};
+
+fn main() {
+    let mut stdout = stdout().lock();
+    let mut lines = stdin().lock().lines();
+    loop {
+        write!(stdout, "> ").expect("Writing the prompt should succeed");
+        stdout.flush().expect("Flushing stdout should succeed");
+
+        let Some(line) = lines.next() else {
+            break;
+        };
+        let line = line.expect("Reading from stdin should succeed");
+        let mut words = line.split_whitespace();
+        match words.next() {
+            Some("add") => {
+                let sum = words
+                    .map(|word| word.parse::<u64>().expect("The summand should be a number"))
+                    .sum::<u64>();
+                writeln!(stdout, "{sum}").expect("Writing to stdout should succeed");
+            }
+            Some("exit") => std::process::exit(0),
+            Some("quit") => {
+                writeln!(stdout, "bye").expect("Writing to stdout should succeed");
+                break;
+            }
+            _ => writeln!(stdout, "{line}").expect("Writing to stdout should succeed"),
+        }
+    }
+}
@@ -5,6 +5,7 @@ use iai_callgrind::{
     client_requests, valgrind_printf, valgrind_printf_backtrace,
     valgrind_printf_backtrace_unchecked, valgrind_printf_unchecked, valgrind_println,
     valgrind_println_backtrace, valgrind_println_backtrace_unchecked, valgrind_println_unchecked,
+    vg_print_backtrace, vg_println,
 };
 
 #[allow(unused_variables)]
@@ -54,5 +55,10 @@ fn main() {
         valgrind_println_backtrace_unchecked!();
     }
 
+    vg_println!("vg_println: {valid_cstring}").unwrap();
+    vg_println!("vg_println (invalid): {invalid_cstring}").unwrap_err();
+    vg_print_backtrace!("vg_print_backtrace: {valid_cstring}").unwrap();
+    vg_print_backtrace!("vg_print_backtrace (invalid): {invalid_cstring}").unwrap_err();
+
     std::process::exit(client_requests::valgrind::running_on_valgrind() as i32);
 }
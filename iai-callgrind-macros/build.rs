@@ -4,6 +4,7 @@ use rustc_version::version;
 
 fn main() {
     let version = version().expect("The rustc version should be present");
+    println!("cargo:rustc-env=IAI_CALLGRIND_MACROS_RUSTC_VERSION={version}");
     if version.major >= 1 && version.minor >= 82 {
         println!("cargo:rustc-cfg=unsafe_keyword_needed");
         println!("cargo:rustc-check-cfg=cfg(unsafe_keyword_needed)");
@@ -0,0 +1,235 @@
+//! The `#[library_benchmark_main]` attribute
+//!
+//! The attribute is an alternative to the `main!` macro for library benchmarks. It is put on an
+//! inline module, collects all `#[library_benchmark]` functions and `library_benchmark_group!`
+//! macros in this module and expands to the `main!` macro with all the groups.
+
+use proc_macro2::{TokenStream, TokenTree};
+use proc_macro_error2::{abort, emit_error};
+use quote::{format_ident, quote};
+use syn::parse::Parser;
+use syn::punctuated::Punctuated;
+use syn::{parse_quote, Expr, Ident, Item, ItemMacro, ItemMod, MetaNameValue, Token};
+
+/// The content of a `library_benchmark_group!` in the annotated module
+#[derive(Debug)]
+struct Group {
+    benchmarks: Vec<Ident>,
+    name: Ident,
+}
+
+/// The parameters of the `#[library_benchmark_main]` attribute
+///
+/// All parameters are passed on to the `main!` macro as they are.
+#[derive(Debug, Default)]
+struct MainArgs {
+    config: Option<Expr>,
+    process_setup: Option<Expr>,
+    process_teardown: Option<Expr>,
+    setup: Option<Expr>,
+    teardown: Option<Expr>,
+}
+
+impl Group {
+    /// Parse the `name` and `benchmarks` of a `library_benchmark_group!` macro
+    ///
+    /// Return `None` if the macro is not a `library_benchmark_group!`.
+    fn from_item_macro(item_macro: &ItemMacro) -> Option<Self> {
+        let last = item_macro.mac.path.segments.last()?;
+        if last.ident != "library_benchmark_group" {
+            return None;
+        }
+
+        let mut name = None;
+        let mut benchmarks = vec![];
+
+        // The parameters are separated by `;` and each parameter has the form `key = value`
+        let tokens = item_macro
+            .mac
+            .tokens
+            .clone()
+            .into_iter()
+            .collect::<Vec<_>>();
+        for parameter in tokens.split(|token| is_punct(token, ';')) {
+            let (Some(TokenTree::Ident(key)), Some(equal)) = (parameter.first(), parameter.get(1))
+            else {
+                continue;
+            };
+            if !is_punct(equal, '=') {
+                continue;
+            }
+
+            if key == "name" {
+                if let [TokenTree::Ident(ident)] = &parameter[2..] {
+                    name = Some(ident.clone());
+                }
+            } else if key == "benchmarks" {
+                benchmarks = parameter[2..]
+                    .iter()
+                    .filter_map(|token| match token {
+                        TokenTree::Ident(ident) => Some(ident.clone()),
+                        _ => None,
+                    })
+                    .collect();
+            } else {
+                // Other parameters are not of interest
+            }
+        }
+
+        let Some(name) = name else {
+            abort!(
+                item_macro, "A library_benchmark_group! needs a `name`";
+                help = "library_benchmark_group!(name = my_group; benchmarks = my_bench);"
+            );
+        };
+
+        Some(Self { benchmarks, name })
+    }
+}
+
+impl MainArgs {
+    fn parse(args: TokenStream) -> syn::Result<Self> {
+        let mut main_args = Self::default();
+        let pairs = Punctuated::<MetaNameValue, Token![,]>::parse_terminated.parse2(args)?;
+        for pair in pairs {
+            let value = Some(pair.value.clone());
+            let slot = if pair.path.is_ident("config") {
+                &mut main_args.config
+            } else if pair.path.is_ident("setup") {
+                &mut main_args.setup
+            } else if pair.path.is_ident("teardown") {
+                &mut main_args.teardown
+            } else if pair.path.is_ident("process_setup") {
+                &mut main_args.process_setup
+            } else if pair.path.is_ident("process_teardown") {
+                &mut main_args.process_teardown
+            } else {
+                abort!(
+                    pair, "Invalid argument: {}", pair.path.require_ident()?;
+                    help = "Valid arguments are: `config`, `setup`, `teardown`, `process_setup`, `process_teardown`"
+                );
+            };
+
+            if slot.is_some() {
+                emit_error!(
+                    pair, "Duplicate argument: `{}`", pair.path.require_ident()?;
+                    help = "Each argument is allowed only once"
+                );
+            }
+            *slot = value;
+        }
+
+        Ok(main_args)
+    }
+
+    /// Render the parameters of the `main!` macro without the `library_benchmark_groups`
+    fn render_as_code(&self) -> TokenStream {
+        let mut tokens = TokenStream::new();
+        for (key, value) in [
+            ("config", &self.config),
+            ("setup", &self.setup),
+            ("teardown", &self.teardown),
+            ("process_setup", &self.process_setup),
+            ("process_teardown", &self.process_teardown),
+        ] {
+            if let Some(value) = value {
+                let key = format_ident!("{key}");
+                tokens.extend(quote! { #key = #value; });
+            }
+        }
+        tokens
+    }
+}
+
+/// Return true if the `token` is the punctuation character `ch`
+fn is_punct(token: &TokenTree, ch: char) -> bool {
+    matches!(token, TokenTree::Punct(punct) if punct.as_char() == ch)
+}
+
+/// Return the name of the function if it is annotated with `#[library_benchmark]`
+fn library_benchmark_ident(item: &Item) -> Option<&Ident> {
+    if let Item::Fn(item_fn) = item {
+        item_fn
+            .attrs
+            .iter()
+            .any(|attr| {
+                attr.path()
+                    .segments
+                    .last()
+                    .is_some_and(|segment| segment.ident == "library_benchmark")
+            })
+            .then_some(&item_fn.sig.ident)
+    } else {
+        None
+    }
+}
+
+pub fn render(args: TokenStream, input: &TokenStream) -> syn::Result<TokenStream> {
+    let main_args = MainArgs::parse(args)?;
+    let Ok(mut item_mod) = syn::parse2::<ItemMod>(input.clone()) else {
+        abort!(
+            input, "The `#[library_benchmark_main]` attribute is only allowed on an inline module";
+            help = "Put all library benchmarks into a module and annotate the module";
+            note = "#[library_benchmark_main] mod benches { ... }"
+        );
+    };
+
+    let mod_ident = item_mod.ident.clone();
+    let Some((_, items)) = item_mod.content.as_mut() else {
+        abort!(
+            mod_ident, "The `#[library_benchmark_main]` attribute needs an inline module";
+            help = "Modules in other files cannot be scanned for benchmarks";
+            note = "#[library_benchmark_main] mod benches { ... }"
+        );
+    };
+
+    let groups = items
+        .iter()
+        .filter_map(|item| match item {
+            Item::Macro(item_macro) => Group::from_item_macro(item_macro),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    let mut group_names = groups
+        .iter()
+        .map(|group| group.name.clone())
+        .collect::<Vec<_>>();
+
+    // All library benchmarks which are not part of any group are collected in an extra group
+    let ungrouped = items
+        .iter()
+        .filter_map(library_benchmark_ident)
+        .filter(|ident| !groups.iter().any(|group| group.benchmarks.contains(ident)))
+        .cloned()
+        .collect::<Vec<_>>();
+    if !ungrouped.is_empty() {
+        let name = format_ident!("{mod_ident}_group");
+        items.push(parse_quote! {
+            iai_callgrind::library_benchmark_group!(
+                name = #name;
+                benchmarks = #(#ungrouped),*
+            );
+        });
+        group_names.push(name);
+    }
+
+    if group_names.is_empty() {
+        abort!(
+            mod_ident, "No library benchmarks found in module `{}`", mod_ident;
+            help = "Annotate at least one function with `#[library_benchmark]`"
+        );
+    }
+
+    let main_args = main_args.render_as_code();
+    Ok(quote! {
+        #item_mod
+
+        use #mod_ident::{#(#group_names),*};
+
+        iai_callgrind::main!(
+            #main_args
+            library_benchmark_groups = #(#group_names),*
+        );
+    })
+}
@@ -9,7 +9,7 @@ use syn::parse::Parse;
 use syn::punctuated::Punctuated;
 use syn::{parse2, parse_quote, Attribute, Expr, Ident, ItemFn, MetaNameValue, Token};
 
-use crate::common::{self, format_ident, truncate_str_utf8, BenchesArgs, File};
+use crate::common::{self, format_ident, truncate_str_utf8, BenchesArgs, BenchesPair, File};
 use crate::{defaults, CargoMetadata};
 
 #[derive(Debug)]
@@ -217,25 +217,28 @@ impl Bench {
         let mut iter = common::Iter::default();
 
         if let Ok(pairs) =
-            meta.parse_args_with(Punctuated::<MetaNameValue, Token![,]>::parse_terminated)
+            meta.parse_args_with(Punctuated::<BenchesPair, Token![,]>::parse_terminated)
         {
-            for pair in pairs {
+            for benches_pair in pairs {
+                let pair = &benches_pair.pair;
                 if pair.path.is_ident("args") {
-                    args.parse_pair(&pair)?;
+                    args.parse_benches_pair(&benches_pair)?;
                 } else if pair.path.is_ident("config") {
-                    config.parse_pair(&pair);
+                    config.parse_pair(pair);
                 } else if pair.path.is_ident("setup") {
-                    setup.parse_pair(&pair);
+                    setup.parse_pair(pair);
                 } else if pair.path.is_ident("teardown") {
-                    teardown.parse_pair(&pair);
+                    teardown.parse_pair(pair);
                 } else if pair.path.is_ident("file") {
-                    file.parse_pair(&pair)?;
+                    file.parse_pair(pair)?;
+                } else if pair.path.is_ident("file_env") {
+                    file.parse_env_pair(pair);
                 } else if pair.path.is_ident("iter") {
-                    iter.parse_pair(&pair);
+                    iter.parse_pair(pair);
                 } else {
                     abort!(
                         pair, "Invalid argument: {}", pair.path.require_ident()?;
-                        help = "Valid arguments are: `args`, `file`, `iter`, `config`, `setup`, `teardown`"
+                        help = "Valid arguments are: `args`, `file`, `file_env`, `iter`, `config`, `setup`, `teardown`"
                     );
                 }
             }
@@ -260,7 +263,10 @@ impl Bench {
         .map(|b| Self {
             id: b.id,
             mode: b.mode.into(),
-            config: config.clone(),
+            config: BenchConfig(config.update_with_element(
+                b.config,
+                &quote! { iai_callgrind::__internal::InternalBinaryBenchmarkConfig },
+            )),
             setup: setup.clone(),
             teardown: teardown.clone(),
         })
@@ -306,8 +312,10 @@ impl Bench {
                 let config = self.config.render_as_code(Some(id));
                 let setup = self.setup.render_as_code(Some(id), args);
                 let teardown = self.teardown.render_as_code(Some(id), args);
+                let env = args.render_tracked();
 
                 quote! {
+                    #env
                     #config
                     #setup
                     #teardown
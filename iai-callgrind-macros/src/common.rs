@@ -7,11 +7,12 @@ use std::path::PathBuf;
 use proc_macro2::{Span, TokenStream};
 use proc_macro_error2::{abort, emit_error};
 use quote::{format_ident, quote_spanned, ToTokens, TokenStreamExt};
-use syn::parse::Parse;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
 use syn::{
-    parse2, parse_quote_spanned, Expr, ExprArray, ExprPath, Ident, LitStr, MetaList, MetaNameValue,
-    Pat, Token,
+    bracketed, parse2, parse_quote_spanned, token, Expr, ExprArray, ExprLit, ExprPath, Ident, Lit,
+    LitStr, Macro, MetaList, MetaNameValue, Pat, Path, Token,
 };
 
 use crate::CargoMetadata;
@@ -23,11 +24,16 @@ pub enum BenchMode {
 }
 
 /// This struct reflects the `args` parameter of the `#[bench]` attribute
+///
+/// The second field are the inputs read by the proc-macro to build these `Args`, for example the
+/// environment variable of `args = env!("NAME")`.
 #[derive(Debug, Default, Clone)]
-pub struct Args(Option<(Span, Vec<Expr>)>);
+pub struct Args(Option<(Span, Vec<Expr>)>, Vec<Tracked>);
 
 #[derive(Debug, Clone)]
 pub struct Bench {
+    /// The config of an element of the `args` array of the `#[benches]` attribute
+    pub config: Option<Expr>,
     pub id: Ident,
     pub mode: BenchMode,
 }
@@ -37,12 +43,27 @@ pub struct Bench {
 pub struct BenchConfig(pub Option<Expr>);
 
 /// This struct stores multiple `Args` as needed by the `#[benches]` attribute
+///
+/// Each `Args` can have its own config (`args = [small(), big() => config]`).
 #[derive(Debug, Clone, Default)]
-pub struct BenchesArgs(pub Option<Vec<Args>>);
+pub struct BenchesArgs(pub Option<Vec<(Args, Option<Expr>)>>);
 
-/// The `file` parameter of the `#[benches]` attribute
+/// A `name = value` pair of the `#[benches]` attribute
+///
+/// In contrast to a [`MetaNameValue`], each element of an `args` array can be followed by `=>` and
+/// the config of this element. The `configs` are in the same order as the elements of the array.
+#[derive(Debug, Clone)]
+pub struct BenchesPair {
+    pub configs: Vec<Option<Expr>>,
+    pub pair: MetaNameValue,
+}
+
+/// The `file` or `file_env` parameter of the `#[benches]` attribute
+///
+/// The path is resolved at macro expansion time, so it's always a literal string. The second field
+/// are the names of the environment variables read to resolve the path.
 #[derive(Debug, Default, Clone)]
-pub struct File(pub Option<LitStr>);
+pub struct File(pub Option<LitStr>, pub Vec<LitStr>);
 
 #[derive(Debug, Clone, Default)]
 pub struct Iter(pub Option<Expr>);
@@ -55,9 +76,52 @@ pub struct Setup(pub Option<ExprPath>);
 #[derive(Debug, Default, Clone)]
 pub struct Teardown(pub Option<ExprPath>);
 
+/// An input of the proc-macro which is invisible to cargo
+#[derive(Debug, Clone)]
+pub enum Tracked {
+    /// The name of an environment variable
+    Env(LitStr),
+    /// The absolute path of a file
+    File(LitStr),
+}
+
 impl Args {
     pub fn new(span: Span, data: Vec<Expr>) -> Self {
-        Self(Some((span, data)))
+        Self(Some((span, data)), vec![])
+    }
+
+    /// Set the name of the environment variable from which these `Args` are read
+    pub fn with_env(mut self, name: LitStr) -> Self {
+        self.1.push(Tracked::Env(name));
+        self
+    }
+
+    /// Set the inputs from which these `Args` are read
+    pub fn with_tracked(mut self, tracked: Vec<Tracked>) -> Self {
+        self.1 = tracked;
+        self
+    }
+
+    /// Render the environment variables and files read for these `Args` as `env!` and
+    /// `include_bytes!` macro calls
+    ///
+    /// The inputs are read by the proc-macro, which is invisible to cargo. With the macro calls in
+    /// the expanded code, cargo rebuilds the benchmark if one of the inputs changes.
+    pub fn render_tracked(&self) -> TokenStream {
+        let mut tokens = TokenStream::new();
+        for tracked in &self.1 {
+            tokens.append_all(match tracked {
+                Tracked::Env(name) => {
+                    quote_spanned! { name.span() => const _: &str = ::core::env!(#name); }
+                }
+                Tracked::File(path) => {
+                    quote_spanned! { path.span() =>
+                        const _: &[u8] = ::core::include_bytes!(#path);
+                    }
+                }
+            });
+        }
+        tokens
     }
 
     pub fn len(&self) -> usize {
@@ -184,7 +248,11 @@ impl ToTokens for Args {
 
 impl Bench {
     pub fn new(id: Ident, mode: BenchMode) -> Self {
-        Self { id, mode }
+        Self {
+            config: None,
+            id,
+            mode,
+        }
     }
 
     /// Return a vector of [`Bench`] parsing the [`File`] or [`BenchesArgs`] if present
@@ -212,10 +280,7 @@ impl Bench {
                 "Only one parameter of `file`, `args` or `iter` can be present"
             );
         } else if check_sum == 0 {
-            vec![Self {
-                id: id.clone(),
-                mode: BenchMode::Args(Args::default()),
-            }]
+            vec![Self::new(id.clone(), BenchMode::Args(Args::default()))]
         // check_sum == 1
         } else if let Some(literal) = file.literal() {
             if !(expected_num_args == 1 || has_setup) {
@@ -231,6 +296,9 @@ impl Bench {
                 abort!(literal, "The provided file '{}' was empty", literal.value());
             }
 
+            // The inputs need to be tracked only once and not for each line. Especially the
+            // `include_bytes!` of the whole file for each line would be expensive for large files.
+            let mut tracked = Some(file.tracked(cargo_meta));
             let mut benches = vec![];
             for (index, string) in strings.iter().enumerate() {
                 let id = format_indexed_ident(id, index);
@@ -239,7 +307,8 @@ impl Bench {
                 } else {
                     parse_quote_spanned! { literal.span() => String::from(#string) }
                 };
-                let args = Args::new(literal.span(), vec![expr]);
+                let args = Args::new(literal.span(), vec![expr])
+                    .with_tracked(tracked.take().unwrap_or_default());
                 benches.push(Self::new(id, BenchMode::Args(args)));
             }
 
@@ -262,10 +331,13 @@ impl Bench {
         } else {
             args.finalize()
                 .enumerate()
-                .map(|(index, args)| {
+                .map(|(index, (args, config))| {
                     args.check_num_arguments(expected_num_args, has_setup);
                     let id = format_indexed_ident(id, index);
-                    Self::new(id, BenchMode::Args(args))
+                    Self {
+                        config,
+                        ..Self::new(id, BenchMode::Args(args))
+                    }
                 })
                 .collect()
         }
@@ -290,9 +362,27 @@ impl BenchesArgs {
         Ok(())
     }
 
+    /// Parse the `args` of a [`BenchesPair`] and attach the configs to the elements
+    pub fn parse_benches_pair(&mut self, benches_pair: &BenchesPair) -> syn::Result<()> {
+        self.parse_pair(&benches_pair.pair)?;
+        if let Some(values) = self.0.as_mut() {
+            for ((_, config), other) in values.iter_mut().zip(&benches_pair.configs) {
+                config.clone_from(other);
+            }
+        }
+
+        Ok(())
+    }
+
     pub fn from_expr(expr: &Expr) -> syn::Result<Self> {
+        if let Expr::Macro(expr_macro) = expr {
+            if expr_macro.mac.path.is_ident("env") {
+                return Ok(Self::from_env(&expr_macro.mac));
+            }
+        }
+
         let expr_array = parse2::<ExprArray>(expr.to_token_stream())?;
-        let mut values: Vec<Args> = vec![];
+        let mut values: Vec<(Args, Option<Expr>)> = vec![];
         for elem in expr_array.elems {
             let span = elem.span();
             let args = match elem {
@@ -305,11 +395,65 @@ impl BenchesArgs {
                 _ => Args::new(span, vec![elem]),
             };
 
-            values.push(args);
+            values.push((args, None));
         }
         Ok(Self(Some(values)))
     }
 
+    /// Read the environment variable of the `env!("NAME")` macro at compile time
+    ///
+    /// The value of the environment variable is a comma-separated list of expressions which are
+    /// parsed here, so an invalid value is a compile error pointing to the `env!` macro. Each
+    /// expression is an argument of the benchmark function.
+    pub fn from_env(mac: &Macro) -> Self {
+        let Ok(name) = mac.parse_body::<LitStr>() else {
+            abort!(
+                mac.tokens, "Invalid argument for `env!`";
+                help = "The `env!` macro needs a literal string with the name of the environment variable";
+                note = "`args = env!(\"BENCH_SIZES\")`"
+            );
+        };
+
+        let value = std::env::var(name.value()).unwrap_or_else(|error| {
+            abort!(
+                name, "Error reading the environment variable '{}': {}", name.value(), error;
+                help = "The environment variable has to be set when the benchmarks are compiled";
+                note = "`BENCH_SIZES=\"10,20,30\" cargo bench`"
+            )
+        });
+
+        // All spans of the parsed expressions point to the literal string of the `env!` macro
+        let exprs = LitStr::new(&value, name.span())
+            .parse_with(Punctuated::<Expr, Token![,]>::parse_terminated)
+            .unwrap_or_else(|error| {
+                abort!(
+                    name, "Invalid value '{}' of the environment variable '{}': {}",
+                    value, name.value(), error;
+                    help = "The values have to be valid rust expressions separated by commas";
+                    note = "`BENCH_SIZES=\"10,20,30\" cargo bench` or \
+                            `BENCH_INPUTS='\"a\",\"b\"' cargo bench`"
+                )
+            });
+
+        if exprs.is_empty() {
+            abort!(
+                name, "The environment variable '{}' contains no values", name.value();
+                help = "The values have to be separated by commas";
+                note = "`BENCH_SIZES=\"10,20,30\" cargo bench`"
+            );
+        }
+
+        let values = exprs
+            .into_iter()
+            .map(|expr| {
+                let args = Args::new(name.span(), vec![expr]).with_env(name.clone());
+                (args, None)
+            })
+            .collect();
+
+        Self(Some(values))
+    }
+
     pub fn from_meta_list(meta: &MetaList) -> syn::Result<Self> {
         let list = &meta.tokens;
         let expr = parse2::<Expr>(quote_spanned! { list.span() => [#list] })?;
@@ -320,19 +464,64 @@ impl BenchesArgs {
     //
     // `#[benches::id()]`, `#[benches::id(args = [])]` have to result in a single Bench with
     // an empty Args.
-    pub fn finalize(self) -> impl Iterator<Item = Args> {
+    pub fn finalize(self) -> impl Iterator<Item = (Args, Option<Expr>)> {
         if let Some(args) = self.0 {
             if args.is_empty() {
-                vec![Args::default()].into_iter()
+                vec![(Args::default(), None)].into_iter()
             } else {
                 args.into_iter()
             }
         } else {
-            vec![Args::default()].into_iter()
+            vec![(Args::default(), None)].into_iter()
         }
     }
 }
 
+impl Parse for BenchesPair {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let path = input.parse::<Path>()?;
+        let eq_token = input.parse::<Token![=]>()?;
+
+        let mut configs = vec![];
+        let value = if path.is_ident("args") && input.peek(token::Bracket) {
+            let content;
+            let bracket_token = bracketed!(content in input);
+            let mut elems = Punctuated::new();
+            while !content.is_empty() {
+                elems.push_value(content.parse::<Expr>()?);
+                if content.peek(Token![=>]) {
+                    content.parse::<Token![=>]>()?;
+                    configs.push(Some(content.parse::<Expr>()?));
+                } else {
+                    configs.push(None);
+                }
+
+                if content.is_empty() {
+                    break;
+                }
+                elems.push_punct(content.parse::<Token![,]>()?);
+            }
+
+            Expr::Array(ExprArray {
+                attrs: vec![],
+                bracket_token,
+                elems,
+            })
+        } else {
+            input.parse::<Expr>()?
+        };
+
+        Ok(Self {
+            configs,
+            pair: MetaNameValue {
+                path,
+                eq_token,
+                value,
+            },
+        })
+    }
+}
+
 impl BenchConfig {
     pub fn ident(id: &Ident) -> Ident {
         format_ident("__get_config", Some(id))
@@ -352,6 +541,20 @@ impl BenchConfig {
     pub fn is_some(&self) -> bool {
         self.0.is_some()
     }
+
+    /// Return this config updated with the `config` of an element of the `args` array
+    ///
+    /// The `internal` type is the internal config type of the library or binary benchmark which is
+    /// used to merge both configs when the benchmark is run.
+    pub fn update_with_element(&self, config: Option<Expr>, internal: &TokenStream) -> Self {
+        match (&self.0, config) {
+            (_, None) => self.clone(),
+            (None, Some(config)) => Self(Some(config)),
+            (Some(this), Some(config)) => Self(Some(parse_quote_spanned! { config.span() =>
+                #internal::from(#this).update_from_all([Some(&#internal::from(#config))])
+            })),
+        }
+    }
 }
 
 impl File {
@@ -363,10 +566,16 @@ impl File {
         self.0.is_some()
     }
 
+    /// Parse the `file` parameter
+    ///
+    /// Besides a literal string, the path can be composed with the `concat!` and `env!` macros,
+    /// for example `concat!(env!("OUT_DIR"), "/cases.txt")` for files generated by a build script.
     pub fn parse_pair(&mut self, pair: &MetaNameValue) -> syn::Result<()> {
         if self.0.is_none() {
             if let Expr::Lit(literal) = &pair.value {
                 self.0 = Some(parse2::<LitStr>(literal.to_token_stream())?);
+            } else if let Some(path) = Self::resolve(&pair.value, &mut self.1) {
+                self.0 = Some(LitStr::new(&path, pair.value.span()));
             } else {
                 abort!(
                     pair.value, "Invalid value for `file`";
@@ -384,22 +593,113 @@ impl File {
         Ok(())
     }
 
-    /// Read this [`File`] and return all its lines
+    /// Parse the `file_env` parameter with the name of the environment variable containing the
+    /// path
+    pub fn parse_env_pair(&mut self, pair: &MetaNameValue) {
+        if self.0.is_some() {
+            emit_error!(
+                pair, "Duplicate argument: `file_env`";
+                help = "Only one of `file` or `file_env` is allowed"
+            );
+            return;
+        }
+
+        let Expr::Lit(ExprLit {
+            lit: Lit::Str(name),
+            ..
+        }) = &pair.value
+        else {
+            abort!(
+                pair.value, "Invalid value for `file_env`";
+                help = "The `file_env` argument needs a literal string with the name of an environment variable containing the path to an existing file at compile time";
+                note = "`file_env = \"CASES_FILE\"`"
+            );
+        };
+
+        self.0 = Some(LitStr::new(&read_env(name), pair.value.span()));
+        self.1.push(name.clone());
+    }
+
+    /// Resolve the literal strings and the `concat!` and `env!` macros of the `expr`
+    ///
+    /// The names of the environment variables are added to `envs`. Returns `None` if the `expr`
+    /// contains anything else.
+    fn resolve(expr: &Expr, envs: &mut Vec<LitStr>) -> Option<String> {
+        match expr {
+            Expr::Lit(ExprLit {
+                lit: Lit::Str(literal),
+                ..
+            }) => Some(literal.value()),
+            Expr::Macro(expr_macro) if expr_macro.mac.path.is_ident("concat") => expr_macro
+                .mac
+                .parse_body_with(Punctuated::<Expr, Token![,]>::parse_terminated)
+                .ok()?
+                .iter()
+                .map(|expr| Self::resolve(expr, envs))
+                .collect(),
+            Expr::Macro(expr_macro) if expr_macro.mac.path.is_ident("env") => expr_macro
+                .mac
+                .parse_body_with(Punctuated::<LitStr, Token![,]>::parse_terminated)
+                .ok()?
+                .first()
+                .map(|name| {
+                    envs.push(name.clone());
+                    read_env(name)
+                }),
+            _ => None,
+        }
+    }
+
+    /// Return the path of this [`File`], relative paths joined with the workspace root
     ///
     /// # Panics
     ///
     /// Panics if there is no path present
-    pub(crate) fn read(&self, cargo_meta: Option<&CargoMetadata>) -> Vec<String> {
+    fn path(&self, cargo_meta: Option<&CargoMetadata>) -> PathBuf {
         let expr = self.0.as_ref().expect("A file should be present");
-        let string = expr.value();
-        let mut path = PathBuf::from(&string);
+        let path = PathBuf::from(expr.value());
 
-        if path.is_relative() {
-            if let Some(cargo_meta) = cargo_meta {
-                let root = PathBuf::from(&cargo_meta.workspace_root);
-                path = root.join(path);
+        match cargo_meta {
+            Some(cargo_meta) if path.is_relative() => {
+                PathBuf::from(&cargo_meta.workspace_root).join(path)
             }
+            _ => path,
         }
+    }
+
+    /// Return the environment variables and the file read to build the benchmarks of this
+    /// [`File`]
+    ///
+    /// The path of the file is absolute because `include_bytes!` resolves relative paths relative
+    /// to the benchmark file and not to the workspace root.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no path present
+    pub(crate) fn tracked(&self, cargo_meta: Option<&CargoMetadata>) -> Vec<Tracked> {
+        let expr = self.0.as_ref().expect("A file should be present");
+        let path = self.path(cargo_meta);
+        let path = std::fs::canonicalize(&path).unwrap_or(path);
+
+        self.1
+            .iter()
+            .cloned()
+            .map(Tracked::Env)
+            .chain(std::iter::once(Tracked::File(LitStr::new(
+                &path.to_string_lossy(),
+                expr.span(),
+            ))))
+            .collect()
+    }
+
+    /// Read this [`File`] and return all its lines
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no path present
+    pub(crate) fn read(&self, cargo_meta: Option<&CargoMetadata>) -> Vec<String> {
+        let expr = self.0.as_ref().expect("A file should be present");
+        let path = self.path(cargo_meta);
 
         let file = StdFile::open(&path)
             .unwrap_or_else(|error| abort!(expr, "Error opening '{}': {}", path.display(), error));
@@ -613,3 +913,94 @@ pub fn pattern_to_single_function_ident(
         _ => None,
     }
 }
+
+/// Read the environment variable `name` at compile time
+fn read_env(name: &LitStr) -> String {
+    std::env::var(name.value()).unwrap_or_else(|error| {
+        abort!(
+            name, "Error reading the environment variable '{}': {}", name.value(), error;
+            help = "The environment variable has to be set when the benchmarks are compiled";
+            note = "Cargo sets `OUT_DIR` for packages with a build script"
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use quote::quote;
+    use syn::parse_quote;
+
+    use super::*;
+
+    fn exprs_of(benches_args: &BenchesArgs) -> Vec<String> {
+        benches_args
+            .0
+            .as_ref()
+            .unwrap()
+            .iter()
+            .flat_map(|(args, _)| args.0.as_ref().unwrap().1.iter())
+            .map(|expr| expr.to_token_stream().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_benches_args_from_env() {
+        std::env::set_var("IAI_CALLGRIND_MACROS_TEST_SIZES", "1, 2 + 3, (4), \"a\",");
+
+        let mac: Macro = parse_quote!(env!("IAI_CALLGRIND_MACROS_TEST_SIZES"));
+        let benches_args = BenchesArgs::from_env(&mac);
+
+        assert_eq!(exprs_of(&benches_args), vec!["1", "2 + 3", "(4)", "\"a\""]);
+
+        let expected = quote! { const _: &str = ::core::env!("IAI_CALLGRIND_MACROS_TEST_SIZES"); };
+        for (args, _) in benches_args.0.as_ref().unwrap() {
+            assert_eq!(args.render_tracked().to_string(), expected.to_string());
+        }
+    }
+
+    #[test]
+    fn test_bench_from_file_renders_tracked_once() {
+        let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+        let path = std::fs::canonicalize(PathBuf::from(manifest_dir).join("Cargo.toml")).unwrap();
+
+        let mut file = File::default();
+        file.parse_pair(&parse_quote!(
+            file = concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml")
+        ))
+        .unwrap();
+
+        let benches = Bench::from_benches_attribute(
+            Span::call_site(),
+            &format_ident!("some_id"),
+            BenchesArgs::default(),
+            &file,
+            &Iter::default(),
+            None,
+            false,
+            1,
+        );
+        assert!(benches.len() > 1);
+
+        let path = path.to_str().unwrap();
+        let expected = quote! {
+            const _: &str = ::core::env!("CARGO_MANIFEST_DIR");
+            const _: &[u8] = ::core::include_bytes!(#path);
+        };
+        let rendered = benches
+            .iter()
+            .map(|bench| match &bench.mode {
+                BenchMode::Args(args) => args.render_tracked().to_string(),
+                BenchMode::Iter(_) => panic!("Expected args"),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(rendered[0], expected.to_string());
+        assert!(rendered[1..].iter().all(String::is_empty));
+    }
+
+    #[test]
+    fn test_args_render_tracked_when_nothing_tracked() {
+        let args = Args::new(Span::call_site(), vec![parse_quote!(1)]);
+        assert!(args.render_tracked().is_empty());
+    }
+}
@@ -4,6 +4,7 @@
 #![doc(test(attr(warn(unused))))]
 #![doc(test(attr(allow(unused_extern_crates))))]
 
+mod bench_main;
 mod bin_bench;
 mod common;
 pub(crate) mod defaults;
@@ -34,7 +35,26 @@ impl CargoMetadata {
 /// use in the `library_benchmark_groups!` macro.
 ///
 /// This attribute accepts the following parameters:
+/// * `black_box`: If `false`, the arguments of the benchmark function and the return value of the
+///   `setup` function are not wrapped in `std::hint::black_box`, so the compiler can constant-fold
+///   them, for example to benchmark a const-propagated fast path. The default is `true`. The
+///   [`#[bench]`][bench] and [`#[benches]`][benches] attributes inherit this parameter if they
+///   don't specify their own `black_box`.
 /// * `config`: Accepts a `LibraryBenchmarkConfig`
+/// * `context`: If `true`, all `setup` and `teardown` functions of this benchmark receive an
+///   `iai_callgrind::BenchContext` with the group, function name and id of the benchmark as first
+///   argument.
+/// * `description`: A string literal describing the benchmark which is shown in the terminal
+///   output, the json summary and the html report. If not present, the first paragraph of the doc
+///   comment of the benchmark function is used.
+/// * `key`: A stable key (`key = "parser/hot"`) which replaces the function name in the name used
+///   to store and look up the output files and baselines. Renaming the benchmark function doesn't
+///   orphan the former results as long as the key stays the same.
+/// * `rust_version`: A comparison operator (`>`, `>=`, `<`, `<=`, `=`, `!=`) followed by a version
+///   (`rust_version = ">=1.75"`). If the version of the rustc compiling the benchmark doesn't
+///   match, the benchmark function is not compiled and all its benchmarks are skipped with the
+///   status `skipped (rust version)`. The [`#[bench]`][bench] and [`#[benches]`][benches]
+///   attributes inherit this parameter if they don't specify their own `rust_version`.
 /// * `setup`: A global setup function which is applied to all following [`#[bench]`][bench] and
 ///   [`#[benches]`][benches] attributes if not overwritten by a `setup` parameter of these
 ///   attributes.
@@ -55,10 +75,18 @@ impl CargoMetadata {
 /// # pub struct InternalMacroLibBench {
 /// #   pub id_display: Option<&'static str>,
 /// #   pub args_display: Option<&'static str>,
+/// #   pub description: Option<&'static str>,
+/// #   pub expect_regression: Option<&'static str>,
 /// #   pub func: InternalLibFunctionKind,
-/// #   pub config: Option<fn() -> InternalLibraryBenchmarkConfig>
+/// #   pub config: Option<fn() -> InternalLibraryBenchmarkConfig>,
+/// #   pub key: Option<&'static str>,
+/// #   pub runs_on: Option<&'static str>,
+/// #   pub skip: Option<&'static str>
 /// # }
 /// # pub struct InternalLibraryBenchmarkConfig {}
+/// # pub enum InternalPhase { Setup, Teardown }
+/// # pub fn phase_start(_: InternalPhase) {}
+/// # pub fn phase_end(_: InternalPhase) {}
 /// # }
 /// # }
 /// fn my_setup(value: u64) -> String {
@@ -100,7 +128,20 @@ impl CargoMetadata {
 /// * __`args`__: A tuple with a list of arguments which are passed to the benchmark function. The
 ///   parentheses also need to be present if there is only a single argument (`#[bench::my_id(args =
 ///   (10))]`).
+/// * __`black_box`__: Like the `black_box` of the `#[library_benchmark]` attribute but only for
+///   this benchmark.
 /// * __`config`__: Accepts a `LibraryBenchmarkConfig`
+/// * __`expect_regression`__: The reason why a performance regression of this benchmark is
+///   expected (`expect_regression = "refactor increases Ir ~8%, tracked in #123"`). The regressions
+///   are still checked but reported as warning instead of failing the benchmark.
+/// * __`key`__: A stable key which replaces the id of this benchmark in the name used to store and
+///   look up the output files and baselines. See also the `key` of the `#[library_benchmark]`
+///   attribute.
+/// * __`runs_on`__: A target triple like `"x86_64-unknown-linux-gnu"`. The benchmark is run only on
+///   this target and skipped with the status `skipped (target)` on all other targets. If prefixed
+///   with `!` (`"!x86_64-unknown-freebsd"`), the benchmark runs on all targets except this one.
+/// * __`rust_version`__: Like the `rust_version` of the `#[library_benchmark]` attribute but only
+///   the arguments of this benchmark are not compiled if the rustc version doesn't match.
 /// * __`setup`__: A function which takes the arguments specified in the `args` parameter and passes
 ///   its return value to the benchmark function.
 /// * __`teardown`__: A function which takes the return value of the benchmark function.
@@ -122,10 +163,18 @@ impl CargoMetadata {
 /// # pub struct InternalMacroLibBench {
 /// #   pub id_display: Option<&'static str>,
 /// #   pub args_display: Option<&'static str>,
+/// #   pub description: Option<&'static str>,
+/// #   pub expect_regression: Option<&'static str>,
 /// #   pub func: InternalLibFunctionKind,
-/// #   pub config: Option<fn() -> InternalLibraryBenchmarkConfig>
+/// #   pub config: Option<fn() -> InternalLibraryBenchmarkConfig>,
+/// #   pub key: Option<&'static str>,
+/// #   pub runs_on: Option<&'static str>,
+/// #   pub skip: Option<&'static str>
 /// # }
 /// # pub struct InternalLibraryBenchmarkConfig {}
+/// # pub enum InternalPhase { Setup, Teardown }
+/// # pub fn phase_start(_: InternalPhase) {}
+/// # pub fn phase_end(_: InternalPhase) {}
 /// # }
 /// # }
 /// // Assume this is a function in your library which you want to benchmark
@@ -144,10 +193,12 @@ impl CargoMetadata {
 /// # The `#[benches]` attribute
 ///
 /// The `#[benches]` attribute lets you define multiple benchmarks in one go. This attribute accepts
-/// the same parameters as the [`#[bench]`][bench] attribute: `args`, `config`, `setup` and
-/// `teardown` and additionally the `file` parameter. In contrast to the `args` parameter in
-/// [`#[bench]`][bench], `args` takes an array of arguments. The id (`#[benches::id(*/ parameters
-/// */)]`) is getting suffixed with the index of the current element of the `args` array.
+/// the same parameters as the [`#[bench]`][bench] attribute: `args`, `black_box`, `config`,
+/// `expect_regression`, `key`, `runs_on`, `rust_version`, `setup` and `teardown` and additionally
+/// the `file` (or `file_env`) parameter.
+/// In contrast to the `args` parameter in [`#[bench]`][bench], `args` takes an array of arguments.
+/// The id (`#[benches::id(*/ parameters */)]`) is getting suffixed with the index of the current
+/// element of the `args` array.
 ///
 /// ```rust
 /// # use iai_callgrind_macros::library_benchmark;
@@ -163,10 +214,18 @@ impl CargoMetadata {
 /// # pub struct InternalMacroLibBench {
 /// #   pub id_display: Option<&'static str>,
 /// #   pub args_display: Option<&'static str>,
+/// #   pub description: Option<&'static str>,
+/// #   pub expect_regression: Option<&'static str>,
 /// #   pub func: InternalLibFunctionKind,
-/// #   pub config: Option<fn() -> InternalLibraryBenchmarkConfig>
+/// #   pub config: Option<fn() -> InternalLibraryBenchmarkConfig>,
+/// #   pub key: Option<&'static str>,
+/// #   pub runs_on: Option<&'static str>,
+/// #   pub skip: Option<&'static str>
 /// # }
 /// # pub struct InternalLibraryBenchmarkConfig {}
+/// # pub enum InternalPhase { Setup, Teardown }
+/// # pub fn phase_start(_: InternalPhase) {}
+/// # pub fn phase_end(_: InternalPhase) {}
 /// # }
 /// # }
 /// use std::hint::black_box;
@@ -206,10 +265,18 @@ impl CargoMetadata {
 /// # pub struct InternalMacroLibBench {
 /// #   pub id_display: Option<&'static str>,
 /// #   pub args_display: Option<&'static str>,
+/// #   pub description: Option<&'static str>,
+/// #   pub expect_regression: Option<&'static str>,
 /// #   pub func: InternalLibFunctionKind,
-/// #   pub config: Option<fn() -> InternalLibraryBenchmarkConfig>
+/// #   pub config: Option<fn() -> InternalLibraryBenchmarkConfig>,
+/// #   pub key: Option<&'static str>,
+/// #   pub runs_on: Option<&'static str>,
+/// #   pub skip: Option<&'static str>
 /// # }
 /// # pub struct InternalLibraryBenchmarkConfig {}
+/// # pub enum InternalPhase { Setup, Teardown }
+/// # pub fn phase_start(_: InternalPhase) {}
+/// # pub fn phase_end(_: InternalPhase) {}
 /// # }
 /// # }
 /// # fn bubble_sort(_: Vec<i32>) -> Vec<i32> { vec![] }
@@ -256,10 +323,18 @@ impl CargoMetadata {
 /// # pub struct InternalMacroLibBench {
 /// #   pub id_display: Option<&'static str>,
 /// #   pub args_display: Option<&'static str>,
+/// #   pub description: Option<&'static str>,
+/// #   pub expect_regression: Option<&'static str>,
 /// #   pub func: InternalLibFunctionKind,
-/// #   pub config: Option<fn() -> InternalLibraryBenchmarkConfig>
+/// #   pub config: Option<fn() -> InternalLibraryBenchmarkConfig>,
+/// #   pub key: Option<&'static str>,
+/// #   pub runs_on: Option<&'static str>,
+/// #   pub skip: Option<&'static str>
 /// # }
 /// # pub struct InternalLibraryBenchmarkConfig {}
+/// # pub enum InternalPhase { Setup, Teardown }
+/// # pub fn phase_start(_: InternalPhase) {}
+/// # pub fn phase_end(_: InternalPhase) {}
 /// # }
 /// # }
 /// # mod my_lib { pub fn string_to_u64(_line: String) -> Result<u64, String> { Ok(0) } }
@@ -283,10 +358,18 @@ impl CargoMetadata {
 /// # pub struct InternalMacroLibBench {
 /// #   pub id_display: Option<&'static str>,
 /// #   pub args_display: Option<&'static str>,
+/// #   pub description: Option<&'static str>,
+/// #   pub expect_regression: Option<&'static str>,
 /// #   pub func: InternalLibFunctionKind,
-/// #   pub config: Option<fn() -> InternalLibraryBenchmarkConfig>
+/// #   pub config: Option<fn() -> InternalLibraryBenchmarkConfig>,
+/// #   pub key: Option<&'static str>,
+/// #   pub runs_on: Option<&'static str>,
+/// #   pub skip: Option<&'static str>
 /// # }
 /// # pub struct InternalLibraryBenchmarkConfig {}
+/// # pub enum InternalPhase { Setup, Teardown }
+/// # pub fn phase_start(_: InternalPhase) {}
+/// # pub fn phase_end(_: InternalPhase) {}
 /// # }
 /// # }
 /// # mod my_lib { pub fn string_to_u64(_line: String) -> Result<u64, String> { Ok(0) } }
@@ -299,6 +382,21 @@ impl CargoMetadata {
 /// # fn main() {}
 /// ```
 ///
+/// The `args` parameter also accepts the `env!` macro, for example `args = env!("BENCH_SIZES")`.
+/// The environment variable is read when the benchmarks are compiled and its comma-separated values
+/// (like `BENCH_SIZES="10,100,1000"`) create a benchmark each. Every value is parsed as rust
+/// expression, so the benchmark function or the `setup` function has to take exactly one argument.
+/// A change of the environment variable recompiles the benchmarks.
+///
+/// Each element of the `args` array can be followed by `=>` and a config which applies only to the
+/// benchmark of this element, for example `args = [small(), big() => some_config()]`. The config of
+/// the element is applied on top of the `config` parameter.
+///
+/// Files generated by a build script can be used with the `concat!` and `env!` macros in the
+/// `file` parameter, for example `file = concat!(env!("OUT_DIR"), "/cases.txt")`, or with the
+/// `file_env` parameter and the name of an environment variable containing the path to the file
+/// (`file_env = "CASES_FILE"`). Both are resolved when the benchmarks are compiled.
+///
 /// # More Examples
 ///
 /// The `#[library_benchmark]` attribute as a standalone
@@ -316,10 +414,18 @@ impl CargoMetadata {
 /// # pub struct InternalMacroLibBench {
 /// #   pub id_display: Option<&'static str>,
 /// #   pub args_display: Option<&'static str>,
+/// #   pub description: Option<&'static str>,
+/// #   pub expect_regression: Option<&'static str>,
 /// #   pub func: InternalLibFunctionKind,
-/// #   pub config: Option<fn() -> InternalLibraryBenchmarkConfig>
+/// #   pub config: Option<fn() -> InternalLibraryBenchmarkConfig>,
+/// #   pub key: Option<&'static str>,
+/// #   pub runs_on: Option<&'static str>,
+/// #   pub skip: Option<&'static str>
 /// # }
 /// # pub struct InternalLibraryBenchmarkConfig {}
+/// # pub enum InternalPhase { Setup, Teardown }
+/// # pub fn phase_start(_: InternalPhase) {}
+/// # pub fn phase_end(_: InternalPhase) {}
 /// # }
 /// # }
 /// fn some_func() -> u64 {
@@ -353,10 +459,18 @@ impl CargoMetadata {
 /// # pub struct InternalMacroLibBench {
 /// #   pub id_display: Option<&'static str>,
 /// #   pub args_display: Option<&'static str>,
+/// #   pub description: Option<&'static str>,
+/// #   pub expect_regression: Option<&'static str>,
 /// #   pub func: InternalLibFunctionKind,
-/// #   pub config: Option<fn() -> InternalLibraryBenchmarkConfig>
+/// #   pub config: Option<fn() -> InternalLibraryBenchmarkConfig>,
+/// #   pub key: Option<&'static str>,
+/// #   pub runs_on: Option<&'static str>,
+/// #   pub skip: Option<&'static str>
 /// # }
 /// # pub struct InternalLibraryBenchmarkConfig {}
+/// # pub enum InternalPhase { Setup, Teardown }
+/// # pub fn phase_start(_: InternalPhase) {}
+/// # pub fn phase_end(_: InternalPhase) {}
 /// # }
 /// # }
 /// // Our function we want to test
@@ -415,6 +529,75 @@ pub fn library_benchmark(args: TokenStream, input: TokenStream) -> TokenStream {
     }
 }
 
+/// An alternative to the `main!` macro for library benchmarks
+///
+/// The `#[library_benchmark_main]` attribute is put on an inline module containing the library
+/// benchmarks. All functions in this module which are annotated with
+/// [`#[library_benchmark]`][macro@crate::library_benchmark] and which are not already part of a
+/// `library_benchmark_group!` in the same module are collected in a group named after the module
+/// with a `_group` suffix (e.g. `benches_group`). Together with all `library_benchmark_group!`
+/// macros of the module, these groups are passed to the `main!` macro, so there's no need to list
+/// every benchmark function and group by hand. Like the `main!` macro, this attribute creates the
+/// `main` function of the benchmark.
+///
+/// The attribute accepts the same optional parameters as the `main!` macro (`config`, `setup`,
+/// `teardown`, `process_setup` and `process_teardown`) separated by a comma.
+///
+/// The scope of this attribute is narrower than the scope of the `main!` macro. It can only be put
+/// on an inline module (`mod benches { ... }`) and not on a `fn main` or a module in another file,
+/// and only library benchmarks are collected. Binary benchmarks still need the `main!` macro.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use iai_callgrind::{library_benchmark_main, LibraryBenchmarkConfig};
+///
+/// #[library_benchmark_main(config = LibraryBenchmarkConfig::default())]
+/// mod benches {
+///     use iai_callgrind::library_benchmark;
+///     use std::hint::black_box;
+///
+///     #[library_benchmark]
+///     #[bench::short(10)]
+///     fn bench_fibonacci(value: u64) -> u64 {
+///         black_box(my_lib::fibonacci(value))
+///     }
+///
+///     #[library_benchmark]
+///     fn bench_bubble_sort() -> Vec<i32> {
+///         black_box(my_lib::bubble_sort(vec![3, 2, 1]))
+///     }
+/// }
+/// ```
+///
+/// is equivalent to
+///
+/// ```rust,ignore
+/// mod benches {
+///     // ... the benchmark functions from above
+///
+///     iai_callgrind::library_benchmark_group!(
+///         name = benches_group;
+///         benchmarks = bench_fibonacci, bench_bubble_sort
+///     );
+/// }
+///
+/// use benches::benches_group;
+///
+/// iai_callgrind::main!(
+///     config = LibraryBenchmarkConfig::default();
+///     library_benchmark_groups = benches_group
+/// );
+/// ```
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn library_benchmark_main(args: TokenStream, input: TokenStream) -> TokenStream {
+    match bench_main::render(args.into(), &input.into()) {
+        Ok(stream) => stream.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
 /// Used to annotate functions building the to be benchmarked `iai_callgrind::Command`
 ///
 /// This macro works almost the same way as the [`macro@crate::library_benchmark`] attribute. Please
@@ -2,18 +2,20 @@ use std::ops::Deref;
 
 use derive_more::{Deref as DerefDerive, DerefMut as DerefMutDerive};
 use proc_macro2::TokenStream;
-use proc_macro_error2::abort;
+use proc_macro_error2::{abort, emit_error};
 use quote::{format_ident, quote, quote_spanned, ToTokens, TokenStreamExt};
 use syn::parse::Parse;
 use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
 use syn::{
-    parse2, parse_quote, parse_quote_spanned, Attribute, Expr, ExprPath, FnArg, Ident, ItemFn,
-    MetaNameValue, Pat, PatType, Signature, Token,
+    parse2, parse_quote, parse_quote_spanned, Attribute, Expr, ExprLit, ExprPath, FnArg, Ident,
+    ItemFn, Lit, Meta, MetaNameValue, Pat, PatType, Signature, Token,
 };
+use version_compare::Cmp;
 
 use crate::common::{
-    self, format_ident, pattern_to_single_function_ident, truncate_str_utf8, BenchesArgs, File,
+    self, format_ident, pattern_to_single_function_ident, truncate_str_utf8, BenchesArgs,
+    BenchesPair, File,
 };
 use crate::{defaults, CargoMetadata};
 
@@ -33,9 +35,14 @@ struct Args(common::Args);
 /// The `#[benches]` attribute is also parsed into this structure.
 #[derive(Debug)]
 struct Bench {
+    black_box: BlackBox,
     config: BenchConfig,
+    expect_regression: ExpectRegression,
     id: Ident,
+    key: Key,
     mode: BenchMode,
+    runs_on: RunsOn,
+    rust_version: RustVersion,
     setup: Setup,
     teardown: Teardown,
 }
@@ -43,17 +50,56 @@ struct Bench {
 #[derive(Debug, Default, Clone, DerefDerive, DerefMutDerive)]
 struct BenchConfig(common::BenchConfig);
 
+/// The `black_box` parameter of the `#[library_benchmark]`, `#[bench]` and `#[benches]` attributes
+///
+/// If false, the arguments of the benchmark function and the return value of the `setup` function
+/// are not wrapped in `std::hint::black_box`, so the compiler is free to constant-fold them. If not
+/// present, the inputs are black boxed.
+#[derive(Debug, Default, Clone, Copy)]
+struct BlackBox(Option<bool>);
+
 #[derive(Debug, Clone, DerefDerive, DerefMutDerive)]
 struct Callee<'a>(&'a Signature);
 
+/// The `context` parameter of the `#[library_benchmark]` attribute
+///
+/// If true, the `setup` and `teardown` functions receive the `BenchContext` as first argument.
+#[derive(Debug, Default, Clone, Copy)]
+struct Context(bool);
+
+/// The `description` parameter of the `#[library_benchmark]` attribute
+///
+/// If not present, the description is harvested from the doc comments of the benchmark function.
+#[derive(Debug, Default, Clone)]
+struct Description(Option<String>);
+
+/// The `expect_regression` parameter of the `#[bench]` and `#[benches]` attributes
+///
+/// The reason why a performance regression of this benchmark is expected. An expected regression
+/// is reported as warning instead of failing the benchmark run.
+#[derive(Debug, Default, Clone)]
+struct ExpectRegression(Option<String>);
+
 #[derive(Debug, Clone)]
 struct Iter(Expr);
 
+/// The `key` parameter of the `#[library_benchmark]`, `#[bench]` and `#[benches]` attributes
+///
+/// The stable key replaces the function name (`#[library_benchmark]`) or the id (`#[bench]`,
+/// `#[benches]`) in the name used to store and look up the output files and baselines.
+#[derive(Debug, Default, Clone)]
+struct Key(Option<String>);
+
 /// This is the counterpart to the `#[library_benchmark]` attribute.
 #[derive(Debug, Default)]
 struct LibraryBenchmark {
     benches: Vec<Bench>,
+    black_box: BlackBox,
     config: LibraryBenchmarkConfig,
+    context: Context,
+    description: Description,
+    key: Key,
+    rust_version: RustVersion,
     setup: Setup,
     teardown: Teardown,
 }
@@ -68,6 +114,21 @@ struct LibraryBenchmark {
 #[derive(Debug, Default, Clone, DerefDerive, DerefMutDerive)]
 struct LibraryBenchmarkConfig(common::BenchConfig);
 
+/// The `runs_on` parameter of the `#[bench]` and `#[benches]` attributes
+///
+/// The target triple on which the benchmark is run. If prefixed with `!`, the benchmark runs on all
+/// targets except this one.
+#[derive(Debug, Default, Clone)]
+struct RunsOn(Option<String>);
+
+/// The `rust_version` parameter of the `#[library_benchmark]`, `#[bench]` and `#[benches]`
+/// attributes
+///
+/// A comparison operator (`>`, `>=`, `<`, `<=`, `=`, `!=`) followed by a version like `>=1.75`. The
+/// version is compared against the version of the rustc compiling the benchmark.
+#[derive(Debug, Default, Clone)]
+struct RustVersion(Option<(Cmp, String)>);
+
 #[derive(Debug, Default, Clone, DerefDerive, DerefMutDerive)]
 struct Setup(common::Setup);
 
@@ -85,6 +146,8 @@ impl Bench {
         item_fn: &ItemFn,
         attr: &Attribute,
         id: Ident,
+        other_black_box: BlackBox,
+        other_rust_version: &RustVersion,
         other_setup: &Setup,
         other_teardown: &Teardown,
     ) -> syn::Result<Self> {
@@ -92,7 +155,12 @@ impl Bench {
         let meta = attr.meta.require_list()?;
 
         let mut args = Args::default();
+        let mut black_box = BlackBox::default();
         let mut config = BenchConfig::default();
+        let mut expect_regression = ExpectRegression::default();
+        let mut key = Key::default();
+        let mut runs_on = RunsOn::default();
+        let mut rust_version = RustVersion::default();
         let mut setup = Setup::default();
         let mut teardown = Teardown::default();
 
@@ -102,8 +170,18 @@ impl Bench {
             for pair in pairs {
                 if pair.path.is_ident("args") {
                     args.parse_pair(&pair)?;
+                } else if pair.path.is_ident("black_box") {
+                    black_box.parse_pair(&pair);
                 } else if pair.path.is_ident("config") {
                     config.parse_pair(&pair);
+                } else if pair.path.is_ident("expect_regression") {
+                    expect_regression.parse_pair(&pair);
+                } else if pair.path.is_ident("key") {
+                    key.parse_pair(&pair);
+                } else if pair.path.is_ident("runs_on") {
+                    runs_on.parse_pair(&pair);
+                } else if pair.path.is_ident("rust_version") {
+                    rust_version.parse_pair(&pair);
                 } else if pair.path.is_ident("setup") {
                     setup.parse_pair(&pair);
                 } else if pair.path.is_ident("teardown") {
@@ -111,7 +189,7 @@ impl Bench {
                 } else {
                     abort!(
                         pair, "Invalid argument: {}", pair.path.require_ident()?;
-                        help = "Valid arguments are: `args`, `config`, `setup`, teardown`"
+                        help = "Valid arguments are: `args`, `black_box`, `config`, `expect_regression`, `key`, `runs_on`, `rust_version`, `setup`, teardown`"
                     );
                 }
             }
@@ -119,6 +197,8 @@ impl Bench {
             args.parse_meta_list(meta)?;
         }
 
+        black_box.update(other_black_box);
+        rust_version.update(other_rust_version);
         setup.update(other_setup);
         teardown.update(other_teardown);
 
@@ -127,7 +207,12 @@ impl Bench {
         Ok(Self {
             id,
             mode: BenchMode::Args(args),
+            black_box,
             config,
+            expect_regression,
+            key,
+            runs_on,
+            rust_version,
             setup,
             teardown,
         })
@@ -137,6 +222,8 @@ impl Bench {
         item_fn: &ItemFn,
         attr: &Attribute,
         id: &Ident,
+        other_black_box: BlackBox,
+        other_rust_version: &RustVersion,
         other_setup: &Setup,
         other_teardown: &Teardown,
         cargo_meta: Option<&CargoMetadata>,
@@ -144,7 +231,12 @@ impl Bench {
         let expected_num_args = item_fn.sig.inputs.len();
         let meta = attr.meta.require_list()?;
 
+        let mut black_box = BlackBox::default();
         let mut config = BenchConfig::default();
+        let mut expect_regression = ExpectRegression::default();
+        let mut key = Key::default();
+        let mut runs_on = RunsOn::default();
+        let mut rust_version = RustVersion::default();
         let mut setup = Setup::default();
         let mut teardown = Teardown::default();
         let mut args = BenchesArgs::default();
@@ -152,25 +244,38 @@ impl Bench {
         let mut iter = common::Iter::default();
 
         if let Ok(pairs) =
-            meta.parse_args_with(Punctuated::<MetaNameValue, Token![,]>::parse_terminated)
+            meta.parse_args_with(Punctuated::<BenchesPair, Token![,]>::parse_terminated)
         {
-            for pair in pairs {
+            for benches_pair in pairs {
+                let pair = &benches_pair.pair;
                 if pair.path.is_ident("args") {
-                    args.parse_pair(&pair)?;
+                    args.parse_benches_pair(&benches_pair)?;
+                } else if pair.path.is_ident("black_box") {
+                    black_box.parse_pair(pair);
                 } else if pair.path.is_ident("config") {
-                    config.parse_pair(&pair);
+                    config.parse_pair(pair);
+                } else if pair.path.is_ident("expect_regression") {
+                    expect_regression.parse_pair(pair);
                 } else if pair.path.is_ident("setup") {
-                    setup.parse_pair(&pair);
+                    setup.parse_pair(pair);
                 } else if pair.path.is_ident("teardown") {
-                    teardown.parse_pair(&pair);
+                    teardown.parse_pair(pair);
                 } else if pair.path.is_ident("file") {
-                    file.parse_pair(&pair)?;
+                    file.parse_pair(pair)?;
+                } else if pair.path.is_ident("file_env") {
+                    file.parse_env_pair(pair);
                 } else if pair.path.is_ident("iter") {
-                    iter.parse_pair(&pair);
+                    iter.parse_pair(pair);
+                } else if pair.path.is_ident("key") {
+                    key.parse_pair(pair);
+                } else if pair.path.is_ident("runs_on") {
+                    runs_on.parse_pair(pair);
+                } else if pair.path.is_ident("rust_version") {
+                    rust_version.parse_pair(pair);
                 } else {
                     abort!(
                         pair, "Invalid argument: {}", pair.path.require_ident()?;
-                        help = "Valid arguments are: `args`, `file`, `iter`, `config`, `setup`, `teardown`"
+                        help = "Valid arguments are: `args`, `black_box`, `file`, `file_env`, `iter`, `config`, `expect_regression`, `key`, `runs_on`, `rust_version`, `setup`, `teardown`"
                     );
                 }
             }
@@ -178,6 +283,8 @@ impl Bench {
             args = BenchesArgs::from_meta_list(meta)?;
         }
 
+        black_box.update(other_black_box);
+        rust_version.update(other_rust_version);
         setup.update(other_setup);
         teardown.update(other_teardown);
 
@@ -193,9 +300,17 @@ impl Bench {
         )
         .into_iter()
         .map(|b| Self {
+            key: key.with_indexed_suffix(id, &b.id),
             id: b.id,
             mode: b.mode.into(),
-            config: config.clone(),
+            black_box,
+            config: BenchConfig(config.update_with_element(
+                b.config,
+                &quote! { iai_callgrind::__internal::InternalLibraryBenchmarkConfig },
+            )),
+            expect_regression: expect_regression.clone(),
+            runs_on: runs_on.clone(),
+            rust_version: rust_version.clone(),
             setup: setup.clone(),
             teardown: teardown.clone(),
         })
@@ -205,12 +320,24 @@ impl Bench {
     }
 
     #[allow(clippy::too_many_lines)]
-    fn render_as_code(&self, callee: &Callee) -> TokenStream {
+    fn render_as_code(&self, callee: &Callee, context: Context) -> TokenStream {
         let bench_id = &self.id;
         let elem_ident = format_ident!("__elem");
         let run_func_id = format_ident("__run", Some(bench_id));
         let callee_ident = &callee.ident;
         let export = generate_export_name(callee, &run_func_id);
+        let context_binding = context.render_as_code(callee_ident, Some(bench_id));
+
+        // The benchmark is skipped by the runner, so the arguments and the benchmark function are
+        // not allowed to end up in the compiled code
+        if !self.rust_version.is_match() {
+            let config = self.config.render_as_code(bench_id);
+            return quote! {
+                #config
+
+                pub fn #run_func_id() {}
+            };
+        }
 
         let func = match &self.mode {
             // The amount of input arguments of the benchmark function is already verified to be
@@ -220,8 +347,9 @@ impl Bench {
 
                 let index_ident = Iter::index_ident();
                 let iter_ident = Iter::iter_ident();
+                let iter_elem_ident = Iter::elem_ident();
 
-                let (iter_count, iter_elem) = iter.render_as_code(&self.setup);
+                let (iter_count, iter_elem, iter_setup) = iter.render_as_code(&self.setup, context);
 
                 let (bench_id_func, pats) = callee.to_caller_signature(&elem_ident, bench_id);
                 let call_bench_func = quote_spanned! { callee_ident.span() =>
@@ -230,9 +358,10 @@ impl Bench {
                     )
                 };
 
-                let call_bench_id = self
-                    .teardown
-                    .render_as_code(quote_spanned! { bench_id.span() => #bench_id(#elem_ident) });
+                let call_bench_id = self.teardown.render_as_code(
+                    quote_spanned! { bench_id.span() => #bench_id(#elem_ident) },
+                    context,
+                );
 
                 quote!(
                    #[inline(never)]
@@ -241,37 +370,65 @@ impl Bench {
                    }
                    #[inline(never)]
                    #export
-                   pub fn #run_func_id(#index_ident: Option<usize>) -> usize {
+                   pub fn #run_func_id(
+                       __iter_run: iai_callgrind::__internal::InternalIterRun
+                   ) -> usize {
+                       #context_binding
                        let #iter_ident = #iter_expr;
 
-                       if let Some(#index_ident) = #index_ident {
-                           #[allow(clippy::useless_conversion)]
-                           let #elem_ident = #iter_elem;
-                           #[allow(clippy::let_unit_value)]
-                           let _ = #call_bench_id;
-                           0
-                       } else {
-                           #[allow(clippy::useless_conversion)]
-                           #[allow(clippy::iter_count)]
-                           #iter_count
+                       match __iter_run {
+                           // All elements run in the same process, so the iterator is evaluated
+                           // only once. The runner splits the metrics at the separators.
+                           iai_callgrind::__internal::InternalIterRun::All => {
+                               #[allow(clippy::useless_conversion)]
+                               for (#index_ident, #iter_elem_ident) in
+                                   #iter_ident.into_iter().enumerate()
+                               {
+                                   if #index_ident > 0 {
+                                       iai_callgrind::__internal::__iai_callgrind_iter_separator(
+                                           #index_ident
+                                       );
+                                   }
+                                   let #elem_ident = #iter_setup;
+                                   #[allow(clippy::let_unit_value)]
+                                   let _ = #call_bench_id;
+                               }
+                               0
+                           }
+                           iai_callgrind::__internal::InternalIterRun::Index(#index_ident) => {
+                               #[allow(clippy::useless_conversion)]
+                               let #elem_ident = #iter_elem;
+                               #[allow(clippy::let_unit_value)]
+                               let _ = #call_bench_id;
+                               0
+                           }
+                           iai_callgrind::__internal::InternalIterRun::Count => {
+                               #[allow(clippy::useless_conversion)]
+                               #[allow(clippy::iter_count)]
+                               #iter_count
+                           }
                        }
                    }
                 )
             }
             BenchMode::Args(args) => {
-                let inner = self.setup.render_as_code(args);
+                let inner = self.setup.render_as_code(args, context, self.black_box);
                 let call_bench_id = if self.setup.is_some() {
-                    self.teardown.render_as_code(quote_spanned! {
-                        bench_id.span() => {
-                            #[allow(clippy::let_unit_value)]
-                            let __setup = #inner;
-                            std::hint::black_box(#bench_id(__setup))
-                        }
-                    })
+                    self.teardown.render_as_code(
+                        quote_spanned! {
+                            bench_id.span() => {
+                                #[allow(clippy::let_unit_value)]
+                                let __setup = #inner;
+                                std::hint::black_box(#bench_id(__setup))
+                            }
+                        },
+                        context,
+                    )
                 } else {
                     self.teardown.render_as_code(
                         quote_spanned! { bench_id.span() => std::hint::black_box(#bench_id(#inner))
                         },
+                        context,
                     )
                 };
 
@@ -281,8 +438,10 @@ impl Bench {
                             __iai_callgrind_wrapper_mod::#callee_ident(#(#pats),*)
                         )
                 };
+                let env = args.render_tracked();
 
                 quote!(
+                   #env
                    #[inline(never)]
                    #bench_id_func {
                        #call_bench_func
@@ -290,6 +449,7 @@ impl Bench {
                    #[inline(never)]
                    #export
                    pub fn #run_func_id() {
+                       #context_binding
                        #[allow(clippy::let_unit_value)]
                        let _ = #call_bench_id;
                    }
@@ -304,13 +464,36 @@ impl Bench {
         }
     }
 
-    fn render_as_member(&self) -> TokenStream {
+    fn render_as_member(&self, description: &Description, key: &Key) -> TokenStream {
         let id = &self.id;
+        let description = description.render_as_member();
+        let key = key.render_as_member();
         let id_display = self.id.to_string();
         let config = self.config.render_as_member(id);
+        let expect_regression = self.expect_regression.render_as_member();
+        let runs_on = self.runs_on.render_as_member();
+        let skip = self.rust_version.render_as_member();
         let run_id = format_ident("__run", Some(id));
 
         match &self.mode {
+            // A skipped benchmark has no iterator which could be evaluated
+            BenchMode::Iter(iter) if !self.rust_version.is_match() => {
+                let args_string = self.setup.to_string_with_iter(&iter.0);
+                let args_display = truncate_str_utf8(&args_string, defaults::MAX_BYTES_ARGS);
+                quote! {
+                    iai_callgrind::__internal::InternalMacroLibBench {
+                        id_display: Some(#id_display),
+                        args_display: Some(#args_display),
+                        description: #description,
+                        func: iai_callgrind::__internal::InternalLibFunctionKind::Default(#run_id),
+                        config: #config,
+                        expect_regression: #expect_regression,
+                        key: #key,
+                        runs_on: #runs_on,
+                        skip: #skip
+                    }
+                }
+            }
             BenchMode::Iter(iter) => {
                 let args_string = self.setup.to_string_with_iter(&iter.0);
                 let args_display = truncate_str_utf8(&args_string, defaults::MAX_BYTES_ARGS);
@@ -318,8 +501,13 @@ impl Bench {
                     iai_callgrind::__internal::InternalMacroLibBench {
                         id_display: Some(#id_display),
                         args_display: Some(#args_display),
+                        description: #description,
                         func: iai_callgrind::__internal::InternalLibFunctionKind::Iter(#run_id),
-                        config: #config
+                        config: #config,
+                        expect_regression: #expect_regression,
+                        key: #key,
+                        runs_on: #runs_on,
+                        skip: #skip
                     }
                 }
             }
@@ -330,8 +518,13 @@ impl Bench {
                     iai_callgrind::__internal::InternalMacroLibBench {
                         id_display: Some(#id_display),
                         args_display: Some(#args_display),
+                        description: #description,
                         func: iai_callgrind::__internal::InternalLibFunctionKind::Default(#run_id),
-                        config: #config
+                        config: #config,
+                        expect_regression: #expect_regression,
+                        key: #key,
+                        runs_on: #runs_on,
+                        skip: #skip
                     }
                 }
             }
@@ -373,6 +566,41 @@ impl From<common::BenchMode> for BenchMode {
     }
 }
 
+impl BlackBox {
+    fn parse_pair(&mut self, pair: &MetaNameValue) {
+        if self.0.is_some() {
+            emit_error!(
+                pair, "Duplicate argument: `black_box`";
+                help = "`black_box` is allowed only once"
+            );
+        } else if let Expr::Lit(ExprLit {
+            lit: Lit::Bool(value),
+            ..
+        }) = &pair.value
+        {
+            self.0 = Some(value.value);
+        } else {
+            abort!(
+                pair.value, "Invalid value for `black_box`";
+                help = "`black_box` has to be a boolean literal";
+                note = "#[bench::my_id(args = (1), black_box = false)]"
+            );
+        }
+    }
+
+    /// Return true if the inputs of the benchmark function are black boxed (the default)
+    fn is_enabled(self) -> bool {
+        self.0.unwrap_or(true)
+    }
+
+    /// Use the `other` value if this value is not present
+    fn update(&mut self, other: Self) {
+        if self.0.is_none() {
+            self.0 = other.0;
+        }
+    }
+}
+
 impl Callee<'_> {
     #[allow(unused)]
     fn len_inputs(&self) -> usize {
@@ -427,6 +655,134 @@ impl Callee<'_> {
     }
 }
 
+impl Context {
+    fn ident() -> Ident {
+        format_ident!("__context")
+    }
+
+    fn parse_pair(&mut self, pair: &MetaNameValue) {
+        if let Expr::Lit(ExprLit {
+            lit: Lit::Bool(value),
+            ..
+        }) = &pair.value
+        {
+            self.0 = value.value;
+        } else {
+            abort!(
+                pair.value, "Invalid value for `context`";
+                help = "`context` has to be a boolean literal";
+                note = "#[library_benchmark(context = true, setup = my_setup)]"
+            );
+        }
+    }
+
+    /// Render the argument to the `setup` and `teardown` functions if the context is enabled
+    fn render_as_arg(self) -> TokenStream {
+        if self.0 {
+            let ident = Self::ident();
+            quote! { #ident, }
+        } else {
+            TokenStream::new()
+        }
+    }
+
+    /// Render the binding of the `BenchContext` if enabled
+    fn render_as_code(self, function: &Ident, id: Option<&Ident>) -> TokenStream {
+        if self.0 {
+            let ident = Self::ident();
+            let function = function.to_string();
+            let id = id.map_or_else(
+                || quote! { None },
+                |id| {
+                    let id = id.to_string();
+                    quote! { Some(#id) }
+                },
+            );
+            quote! {
+                let #ident = iai_callgrind::__internal::bench_context(#function, #id);
+            }
+        } else {
+            TokenStream::new()
+        }
+    }
+}
+
+impl Description {
+    /// Harvest the description from the `#[doc]` attributes (doc comments) of the benchmark
+    /// function
+    ///
+    /// Only the first paragraph is used and the lines are joined with a single space.
+    fn from_doc_attributes(attrs: &[Attribute]) -> Self {
+        let mut lines = vec![];
+        for attr in attrs {
+            if let Meta::NameValue(MetaNameValue {
+                path,
+                value:
+                    Expr::Lit(ExprLit {
+                        lit: Lit::Str(doc), ..
+                    }),
+                ..
+            }) = &attr.meta
+            {
+                if !path.is_ident("doc") {
+                    continue;
+                }
+                let value = doc.value();
+                let line = value.trim();
+                if line.is_empty() {
+                    if lines.is_empty() {
+                        continue;
+                    }
+                    break;
+                }
+                lines.push(line.to_owned());
+            }
+        }
+
+        if lines.is_empty() {
+            Self(None)
+        } else {
+            Self(Some(lines.join(" ")))
+        }
+    }
+
+    fn parse_pair(&mut self, pair: &MetaNameValue) {
+        if self.0.is_some() {
+            emit_error!(
+                pair, "Duplicate argument: `description`";
+                help = "`description` is allowed only once"
+            );
+        } else if let Expr::Lit(ExprLit {
+            lit: Lit::Str(description),
+            ..
+        }) = &pair.value
+        {
+            self.0 = Some(description.value());
+        } else {
+            abort!(
+                pair.value, "Invalid value for `description`";
+                help = "`description` has to be a string literal";
+                note = "#[library_benchmark(description = \"some description\")]"
+            );
+        }
+    }
+
+    fn render_as_member(&self) -> TokenStream {
+        if let Some(description) = &self.0 {
+            quote! { Some(#description) }
+        } else {
+            quote! { None }
+        }
+    }
+
+    /// Use the `other` description if this description is not present
+    fn update(&mut self, other: Self) {
+        if self.0.is_none() {
+            self.0 = other.0;
+        }
+    }
+}
+
 impl Iter {
     fn iter_ident() -> Ident {
         format_ident!("__iter")
@@ -436,34 +792,66 @@ impl Iter {
         format_ident!("__index")
     }
 
+    fn elem_ident() -> Ident {
+        format_ident!("__iter_elem")
+    }
+
     fn expr(&self) -> &Expr {
         &self.0
     }
 
-    fn render_as_code(&self, setup: &Setup) -> (TokenStream, TokenStream) {
+    /// Return the code to count the elements, to produce the element at the index and to apply
+    /// the `setup` to an element of the iterator
+    fn render_as_code(
+        &self,
+        setup: &Setup,
+        context: Context,
+    ) -> (TokenStream, TokenStream, TokenStream) {
         let iter_span = self.0.span();
         let iter_ident = Self::iter_ident();
         let index_ident = Self::index_ident();
-
-        let iter_count = quote_spanned! { iter_span => #iter_ident.into_iter().count() };
-        let iter_elem = if let Some(setup) = setup.expr() {
-            quote_spanned! { setup.span() =>
-                #iter_ident
-                    .into_iter()
-                    .nth(#index_ident)
-                    .map(#setup)
-                    .expect("The iterator index should be withing bounds")
+        let elem_ident = Self::elem_ident();
+
+        // An exact size hint avoids producing all elements just to count them, for example if the
+        // elements are generated with an expensive `.map(...)`
+        let iter_count = quote_spanned! { iter_span => {
+            let #iter_ident = #iter_ident.into_iter();
+            match #iter_ident.size_hint() {
+                (lower, Some(upper)) if lower == upper => lower,
+                _ => #iter_ident.count(),
             }
+        }};
+        let (iter_elem, iter_setup) = if let Some(setup) = setup.expr() {
+            let context_arg = context.render_as_arg();
+            (
+                render_phase(
+                    "Setup",
+                    &quote_spanned! { setup.span() =>
+                        #iter_ident
+                            .into_iter()
+                            .nth(#index_ident)
+                            .map(|__elem| #setup(#context_arg __elem))
+                            .expect("The iterator index should be withing bounds")
+                    },
+                ),
+                render_phase(
+                    "Setup",
+                    &quote_spanned! { setup.span() => #setup(#context_arg #elem_ident) },
+                ),
+            )
         } else {
-            quote_spanned! { iter_span =>
-                #iter_ident
-                    .into_iter()
-                    .nth(#index_ident)
-                    .expect("The iterator index should be within bounds")
-            }
+            (
+                quote_spanned! { iter_span =>
+                    #iter_ident
+                        .into_iter()
+                        .nth(#index_ident)
+                        .expect("The iterator index should be within bounds")
+                },
+                elem_ident.to_token_stream(),
+            )
         };
 
-        (iter_count, iter_elem)
+        (iter_count, iter_elem, iter_setup)
     }
 }
 
@@ -477,6 +865,11 @@ impl LibraryBenchmark {
         let benches: syn::PathSegment = parse_quote!(benches);
 
         for attr in &item_fn.attrs {
+            // Doc comments are harvested as description of the benchmark
+            if attr.path().is_ident("doc") {
+                continue;
+            }
+
             let mut path_segments = attr.path().segments.iter();
             match path_segments.next() {
                 Some(segment) if segment == &bench => {
@@ -499,6 +892,8 @@ impl LibraryBenchmark {
                         item_fn,
                         attr,
                         id,
+                        self.black_box,
+                        &self.rust_version,
                         &self.setup,
                         &self.teardown,
                     )?);
@@ -523,6 +918,8 @@ impl LibraryBenchmark {
                         item_fn,
                         attr,
                         &id,
+                        self.black_box,
+                        &self.rust_version,
                         &self.setup,
                         &self.teardown,
                         cargo_meta,
@@ -546,6 +943,44 @@ impl LibraryBenchmark {
         Ok(())
     }
 
+    /// Render the `#[library_benchmark]` attribute without any other attribute as skipped
+    /// benchmark
+    ///
+    /// The benchmark function is not compiled if the rust version doesn't match.
+    fn render_standalone_skipped(&self, callee_ident: &Ident, run_func_id: &Ident) -> TokenStream {
+        let config = self.config.render_as_code();
+        let description = self.description.render_as_member();
+        let key = self.key.render_as_member();
+        let skip = self.rust_version.render_as_member();
+
+        quote! {
+            pub mod #callee_ident {
+                #[allow(unused_imports)]
+                use super::*;
+
+                pub const __BENCHES: &[iai_callgrind::__internal::InternalMacroLibBench]= &[
+                    iai_callgrind::__internal::InternalMacroLibBench {
+                        id_display: None,
+                        args_display: None,
+                        description: #description,
+                        func: iai_callgrind::__internal::InternalLibFunctionKind::Default(
+                            #run_func_id
+                        ),
+                        config: None,
+                        expect_regression: None,
+                        key: #key,
+                        runs_on: None,
+                        skip: #skip
+                    },
+                ];
+
+                #config
+
+                pub fn #run_func_id() {}
+            }
+        }
+    }
+
     /// Render the `#[library_benchmark]` attribute when no outer attribute was present
     ///
     /// ```ignore
@@ -564,22 +999,37 @@ impl LibraryBenchmark {
         let wrapper_ident = format_ident!("wrapper");
         let run_func_id = format_ident("__run", Some(&wrapper_ident));
 
+        if !self.rust_version.is_match() {
+            return self.render_standalone_skipped(callee_ident, &run_func_id);
+        }
+
         let config = self.config.render_as_code();
+        let description = self.description.render_as_member();
+        let key = self.key.render_as_member();
+        let context_binding = self.context.render_as_code(callee_ident, None);
 
-        let inner = self.setup.render_as_code(&Args::default());
+        let inner = self
+            .setup
+            .render_as_code(&Args::default(), self.context, self.black_box);
         let call_wrapper = if self.setup.is_some() {
-            self.teardown.render_as_code(quote_spanned! {
-                self.setup.expr().span() => {
-                    #[allow(clippy::let_unit_value)]
-                    let __setup = #inner;
-                    std::hint::black_box(#wrapper_ident(__setup))
-                }
-            })
+            self.teardown.render_as_code(
+                quote_spanned! {
+                    self.setup.expr().span() => {
+                        #[allow(clippy::let_unit_value)]
+                        let __setup = #inner;
+                        std::hint::black_box(#wrapper_ident(__setup))
+                    }
+                },
+                self.context,
+            )
         } else {
-            self.teardown.render_as_code(quote_spanned! {
-                inner.span() =>
-                    std::hint::black_box(#wrapper_ident(#inner))
-            })
+            self.teardown.render_as_code(
+                quote_spanned! {
+                    inner.span() =>
+                        std::hint::black_box(#wrapper_ident(#inner))
+                },
+                self.context,
+            )
         };
 
         let (wrapper_func, pats) = callee.to_caller_signature(&elem_ident, &wrapper_ident);
@@ -609,8 +1059,13 @@ impl LibraryBenchmark {
                     iai_callgrind::__internal::InternalMacroLibBench {
                         id_display: None,
                         args_display: None,
+                        description: #description,
                         func: #func,
-                        config: None
+                        config: None,
+                        expect_regression: None,
+                        key: #key,
+                        runs_on: None,
+                        skip: None
                     },
                 ];
 
@@ -624,6 +1079,7 @@ impl LibraryBenchmark {
                #[inline(never)]
                #export
                pub fn #run_func_id() {
+                   #context_binding
                    #[allow(clippy::let_unit_value)]
                    let _ = #call_wrapper;
                }
@@ -683,22 +1139,37 @@ impl LibraryBenchmark {
         let mod_name = &item_fn.sig.ident;
         let mut funcs = TokenStream::new();
         let mut lib_benches = vec![];
-        for bench in self.benches {
-            funcs.append_all(bench.render_as_code(&Callee(&item_fn.sig)));
-            lib_benches.push(bench.render_as_member());
+        for bench in &self.benches {
+            funcs.append_all(bench.render_as_code(&Callee(&item_fn.sig), self.context));
+            let key = self.key.to_bench_key(mod_name, &bench.id, &bench.key);
+            lib_benches.push(bench.render_as_member(&self.description, &key));
         }
 
-        let config = self.config.render_as_code();
-        quote! {
-            pub mod #mod_name {
-                use super::*;
-
+        // If all benchmarks are skipped because of the rust version, the benchmark function itself
+        // might not compile
+        let wrapper_mod = if self
+            .benches
+            .iter()
+            .any(|bench| bench.rust_version.is_match())
+        {
+            quote! {
                 mod __iai_callgrind_wrapper_mod {
                     use super::*;
 
                     #[inline(never)]
                     #new_item_fn
                 }
+            }
+        } else {
+            TokenStream::new()
+        };
+
+        let config = self.config.render_as_code();
+        quote! {
+            pub mod #mod_name {
+                use super::*;
+
+                #wrapper_mod
 
                 pub const __BENCHES: &[iai_callgrind::__internal::InternalMacroLibBench] = &[
                     #(#lib_benches,)*
@@ -717,14 +1188,29 @@ impl Parse for LibraryBenchmark {
         if input.is_empty() {
             Ok(Self::default())
         } else {
+            let mut black_box = BlackBox::default();
             let mut config = LibraryBenchmarkConfig::default();
+            let mut context = Context::default();
+            let mut description = Description::default();
+            let mut key = Key::default();
+            let mut rust_version = RustVersion::default();
             let mut setup = Setup::default();
             let mut teardown = Teardown::default();
 
             let pairs = input.parse_terminated(MetaNameValue::parse, Token![,])?;
             for pair in pairs {
-                if pair.path.is_ident("config") {
+                if pair.path.is_ident("black_box") {
+                    black_box.parse_pair(&pair);
+                } else if pair.path.is_ident("config") {
                     config.parse_pair(&pair);
+                } else if pair.path.is_ident("context") {
+                    context.parse_pair(&pair);
+                } else if pair.path.is_ident("description") {
+                    description.parse_pair(&pair);
+                } else if pair.path.is_ident("key") {
+                    key.parse_pair(&pair);
+                } else if pair.path.is_ident("rust_version") {
+                    rust_version.parse_pair(&pair);
                 } else if pair.path.is_ident("setup") {
                     setup.parse_pair(&pair);
                 } else if pair.path.is_ident("teardown") {
@@ -732,13 +1218,18 @@ impl Parse for LibraryBenchmark {
                 } else {
                     abort!(
                         pair, "Invalid argument: {}", pair.path.require_ident()?;
-                        help = "Valid arguments are: `config`, `setup`, `teardown`"
+                        help = "Valid arguments are: `black_box`, `config`, `context`, `description`, `key`, `rust_version`, `setup`, `teardown`"
                     );
                 }
             }
 
             let library_benchmark = Self {
+                black_box,
                 config,
+                context,
+                description,
+                key,
+                rust_version,
                 setup,
                 teardown,
                 benches: vec![],
@@ -748,6 +1239,71 @@ impl Parse for LibraryBenchmark {
     }
 }
 
+impl Key {
+    fn parse_pair(&mut self, pair: &MetaNameValue) {
+        if self.0.is_some() {
+            emit_error!(
+                pair, "Duplicate argument: `key`";
+                help = "`key` is allowed only once"
+            );
+        } else if let Expr::Lit(ExprLit {
+            lit: Lit::Str(key), ..
+        }) = &pair.value
+        {
+            let value = key.value();
+            let key = value.trim();
+            if key.is_empty() {
+                abort!(
+                    pair.value, "Invalid value for `key`: The key is empty";
+                    help = "`key` has to be a non-empty string";
+                    note = "#[library_benchmark(key = \"parser/hot\")]"
+                );
+            }
+            self.0 = Some(key.to_owned());
+        } else {
+            abort!(
+                pair.value, "Invalid value for `key`";
+                help = "`key` has to be a string literal";
+                note = "#[library_benchmark(key = \"parser/hot\")]"
+            );
+        }
+    }
+
+    /// Append the index of the `#[benches]` attribute `indexed_id` (like `my_id_0`) to this key
+    fn with_indexed_suffix(&self, id: &Ident, indexed_id: &Ident) -> Self {
+        let indexed_id = indexed_id.to_string();
+        Self(self.0.as_ref().map(|key| {
+            let suffix = indexed_id.strip_prefix(&id.to_string()).unwrap_or_default();
+            format!("{key}{suffix}")
+        }))
+    }
+
+    /// Create the key of a `#[bench]` or `#[benches]` from this key of the `#[library_benchmark]`
+    ///
+    /// If neither key is present, there is no key and the name of the benchmark is
+    /// `function_name.id` as usual. Otherwise, this key replaces the `function_name` and the
+    /// `bench_key` replaces the `id`.
+    fn to_bench_key(&self, function_name: &Ident, id: &Ident, bench_key: &Self) -> Self {
+        if self.0.is_none() && bench_key.0.is_none() {
+            return Self(None);
+        }
+
+        Self(Some(format!(
+            "{}.{}",
+            self.0.clone().unwrap_or_else(|| function_name.to_string()),
+            bench_key.0.clone().unwrap_or_else(|| id.to_string())
+        )))
+    }
+
+    fn render_as_member(&self) -> TokenStream {
+        if let Some(key) = &self.0 {
+            quote! { Some(#key) }
+        } else {
+            quote! { None }
+        }
+    }
+}
+
 impl LibraryBenchmarkConfig {
     fn ident() -> Ident {
         format_ident("__get_config", None)
@@ -776,6 +1332,162 @@ impl LibraryBenchmarkConfig {
     }
 }
 
+impl ExpectRegression {
+    fn parse_pair(&mut self, pair: &MetaNameValue) {
+        if self.0.is_some() {
+            emit_error!(
+                pair, "Duplicate argument: `expect_regression`";
+                help = "`expect_regression` is allowed only once"
+            );
+        } else if let Expr::Lit(ExprLit {
+            lit: Lit::Str(reason),
+            ..
+        }) = &pair.value
+        {
+            let value = reason.value();
+            let reason = value.trim();
+            if reason.is_empty() {
+                abort!(
+                    pair.value, "Invalid value for `expect_regression`: The reason is empty";
+                    help = "`expect_regression` has to describe why the regression is expected";
+                    note = "#[bench::my_id(args = (1), expect_regression = \"tracked in #123\")]"
+                );
+            }
+            self.0 = Some(reason.to_owned());
+        } else {
+            abort!(
+                pair.value, "Invalid value for `expect_regression`";
+                help = "`expect_regression` has to be a string literal";
+                note = "#[bench::my_id(args = (1), expect_regression = \"tracked in #123\")]"
+            );
+        }
+    }
+
+    fn render_as_member(&self) -> TokenStream {
+        if let Some(reason) = &self.0 {
+            quote! { Some(#reason) }
+        } else {
+            quote! { None }
+        }
+    }
+}
+
+impl RunsOn {
+    fn parse_pair(&mut self, pair: &MetaNameValue) {
+        if self.0.is_some() {
+            emit_error!(
+                pair, "Duplicate argument: `runs_on`";
+                help = "`runs_on` is allowed only once"
+            );
+        } else if let Expr::Lit(ExprLit {
+            lit: Lit::Str(runs_on),
+            ..
+        }) = &pair.value
+        {
+            let value = runs_on.value();
+            let target = value.trim();
+            if target.strip_prefix('!').unwrap_or(target).trim().is_empty() {
+                abort!(
+                    pair.value, "Invalid value for `runs_on`: The target triple is empty";
+                    help = "`runs_on` has to be a target triple optionally prefixed with `!`";
+                    note = "#[bench::my_id(args = (1), runs_on = \"x86_64-unknown-linux-gnu\")]"
+                );
+            }
+            self.0 = Some(target.to_owned());
+        } else {
+            abort!(
+                pair.value, "Invalid value for `runs_on`";
+                help = "`runs_on` has to be a string literal";
+                note = "#[bench::my_id(args = (1), runs_on = \"!x86_64-unknown-freebsd\")]"
+            );
+        }
+    }
+
+    fn render_as_member(&self) -> TokenStream {
+        if let Some(runs_on) = &self.0 {
+            quote! { Some(#runs_on) }
+        } else {
+            quote! { None }
+        }
+    }
+}
+
+impl RustVersion {
+    /// Return true if the version of the rustc compiling the benchmark matches this version
+    ///
+    /// If not present, any rustc version matches.
+    fn is_match(&self) -> bool {
+        self.0.as_ref().map_or(true, |(cmp, version)| {
+            version_compare::compare_to(env!("IAI_CALLGRIND_MACROS_RUSTC_VERSION"), version, *cmp)
+                .unwrap_or(false)
+        })
+    }
+
+    fn parse_pair(&mut self, pair: &MetaNameValue) {
+        if self.0.is_some() {
+            emit_error!(
+                pair, "Duplicate argument: `rust_version`";
+                help = "`rust_version` is allowed only once"
+            );
+        } else if let Expr::Lit(ExprLit {
+            lit: Lit::Str(rust_version),
+            ..
+        }) = &pair.value
+        {
+            let value = rust_version.value();
+            let value = value.trim();
+            let comparator = [
+                (">=", Cmp::Ge),
+                (">", Cmp::Gt),
+                ("<=", Cmp::Le),
+                ("<", Cmp::Lt),
+                ("=", Cmp::Eq),
+                ("!=", Cmp::Ne),
+            ]
+            .into_iter()
+            .find_map(|(prefix, cmp)| {
+                value
+                    .strip_prefix(prefix)
+                    .map(|suffix| (cmp, suffix.trim_start().to_owned()))
+            });
+
+            match comparator {
+                Some((cmp, version)) if version_compare::Version::from(&version).is_some() => {
+                    self.0 = Some((cmp, version));
+                }
+                _ => abort!(
+                    pair.value, "Invalid value for `rust_version`: '{}'", value;
+                    help = "`rust_version` has to be one of `>`, `>=`, `<`, `<=`, `=`, `!=` \
+                        followed by a version";
+                    note = "#[library_benchmark(rust_version = \">=1.75\")]"
+                ),
+            }
+        } else {
+            abort!(
+                pair.value, "Invalid value for `rust_version`";
+                help = "`rust_version` has to be a string literal";
+                note = "#[bench::my_id(args = (1), rust_version = \">=1.75\")]"
+            );
+        }
+    }
+
+    /// Render the reason to skip the benchmark if the rustc version doesn't match
+    fn render_as_member(&self) -> TokenStream {
+        if self.is_match() {
+            quote! { None }
+        } else {
+            quote! { Some("rust version") }
+        }
+    }
+
+    /// Use the `other` rust version if this rust version is not present
+    fn update(&mut self, other: &Self) {
+        if self.0.is_none() {
+            self.0.clone_from(&other.0);
+        }
+    }
+}
+
 impl Setup {
     fn is_some(&self) -> bool {
         self.0 .0.is_some()
@@ -785,22 +1497,43 @@ impl Setup {
         self.0 .0.as_ref()
     }
 
-    fn render_as_code(&self, args: &Args) -> TokenStream {
+    fn render_as_code(&self, args: &Args, context: Context, black_box: BlackBox) -> TokenStream {
+        let args_tokens = if black_box.is_enabled() {
+            args.to_token_stream()
+        } else {
+            args.to_tokens_without_black_box()
+        };
         if let Some(setup) = &self.deref().0 {
-            quote_spanned! { setup.span() => std::hint::black_box(#setup(#args)) }
+            let context_arg = context.render_as_arg();
+            let call_setup = quote_spanned! { setup.span() => #setup(#context_arg #args_tokens) };
+            if black_box.is_enabled() {
+                render_phase(
+                    "Setup",
+                    &quote_spanned! { setup.span() => std::hint::black_box(#call_setup) },
+                )
+            } else {
+                render_phase("Setup", &call_setup)
+            }
         } else {
-            quote_spanned! { args.span() => #args }
+            quote_spanned! { args.span() => #args_tokens }
         }
     }
 }
 
 impl Teardown {
-    fn render_as_code(&self, tokens: TokenStream) -> TokenStream {
+    fn render_as_code(&self, tokens: TokenStream, context: Context) -> TokenStream {
         if let Some(teardown) = &self.deref().0 {
+            let context_arg = context.render_as_arg();
+            let call_teardown = render_phase(
+                "Teardown",
+                &quote_spanned! { teardown.span() =>
+                    std::hint::black_box(#teardown(#context_arg __result))
+                },
+            );
             quote_spanned! { teardown.span() => {
                     #[allow(clippy::let_unit_value)]
                     let __result = #tokens;
-                    std::hint::black_box(#teardown(__result))
+                    #call_teardown
                 }
             }
         } else {
@@ -850,12 +1583,33 @@ fn generate_export_name(callee: &Callee, run_func_id: &Ident) -> TokenStream {
     }
 }
 
+/// Render the `tokens` surrounded by the start and end markers of the `phase`
+///
+/// The `tokens` need to be an expression. The value of the expression is returned.
+fn render_phase(phase: &str, tokens: &TokenStream) -> TokenStream {
+    let phase = format_ident!("{phase}");
+    quote! {
+        {
+            iai_callgrind::__internal::phase_start(
+                iai_callgrind::__internal::InternalPhase::#phase
+            );
+            #[allow(clippy::let_unit_value)]
+            let __phase_result = #tokens;
+            iai_callgrind::__internal::phase_end(iai_callgrind::__internal::InternalPhase::#phase);
+            __phase_result
+        }
+    }
+}
+
 pub fn render(args: TokenStream, input: TokenStream) -> syn::Result<TokenStream> {
     let mut library_benchmark = parse2::<LibraryBenchmark>(args)?;
     let item_fn = parse2::<ItemFn>(input)?;
 
     let cargo_meta = CargoMetadata::try_new();
 
+    library_benchmark
+        .description
+        .update(Description::from_doc_attributes(&item_fn.attrs));
     library_benchmark.extract_benches(&item_fn, cargo_meta.as_ref())?;
     if library_benchmark.benches.is_empty() {
         Ok(library_benchmark.render_standalone(&item_fn))
@@ -30,6 +30,12 @@ use crate::runner;
 use crate::runner::metrics::Summarize;
 #[cfg(feature = "runner")]
 use crate::runner::metrics::TypeChecker;
+#[cfg(feature = "runner")]
+use crate::util::find_similar;
+
+/// The aliases of the callgrind and cachegrind metrics in addition to their names
+#[cfg(feature = "runner")]
+const METRIC_ALIASES: [&str; 6] = ["instructions", "l1", "ll", "ram", "mem", "cycles"];
 
 /// All metrics which cachegrind produces and additionally some derived events
 ///
@@ -515,6 +521,28 @@ pub enum CallgrindMetrics {
     SingleEvent(EventKind),
 }
 
+/// The value of the callgrind option `--collect-systime`
+///
+/// Collects the number of system calls and the time spent in system calls. The
+/// [`CollectSystime::Usec`] and [`CollectSystime::Nsec`] values require valgrind >= 3.15.
+/// [`CollectSystime::Nsec`] additionally collects the cpu time spent in system calls
+/// ([`EventKind::SysCpuTime`]), so the time a benchmark waited for the kernel can be separated
+/// from the cpu work done in the system calls. See the [callgrind
+/// documentation](https://valgrind.org/docs/manual/cl-manual.html#opt.collect-systime) for the
+/// details.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CollectSystime {
+    /// Don't collect system call metrics (`no`)
+    No,
+    /// Collect the system call metrics with the time in milliseconds (`msec`)
+    Msec,
+    /// Collect the system call metrics with the time in microseconds (`usec`)
+    Usec,
+    /// Collect the system call metrics with the time in nanoseconds including the cpu time
+    /// (`nsec`)
+    Nsec,
+}
+
 /// The kind of `Delay`
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -529,6 +557,20 @@ pub enum DelayKind {
     PathExists(PathBuf),
 }
 
+/// The style of the differences between the new and the old metrics in the terminal output
+///
+/// For example, `(-0.06475%)` is the difference as percentage and `[-1.00065x]` as factor.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DeltaStyle {
+    /// Show only the percentage like `(-0.06475%)`
+    Percent,
+    /// Show only the factor like `[-1.00065x]`
+    Factor,
+    /// Show the percentage and the factor like `(-0.06475%) [-1.00065x]`. This is the default.
+    #[default]
+    Both,
+}
+
 /// The metrics collected by DHAT
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "schema", derive(JsonSchema))]
@@ -784,6 +826,8 @@ pub enum ExitWith {
     Failure,
     /// The exact `ExitCode` of the benchmark run
     Code(i32),
+    /// The benchmark run is terminated by this [`Signal`]
+    Signal(Signal),
 }
 
 /// The kind of `Flamegraph` which is going to be constructed
@@ -800,6 +844,19 @@ pub enum FlamegraphKind {
     None,
 }
 
+/// A single step of an [`InputScript`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InputStep {
+    /// Write the bytes to the stdin of the [`Command`]
+    Write(Vec<u8>),
+    /// Wait until the stdout of the [`Command`] contains the string
+    ///
+    /// Only the output after the match of the previous `WaitFor` step is searched.
+    WaitFor(String),
+    /// Wait for the duration before the next step
+    Sleep(Duration),
+}
+
 /// A `Limit` which can be either an integer or a float
 ///
 /// Depending on the metric the type of the hard limit is a float or an integer. For example
@@ -816,6 +873,34 @@ pub enum Limit {
     Float(f64),
 }
 
+/// How a bare program name (like `echo`) in a binary benchmark [`Command`] is resolved
+///
+/// Paths with more than one component (like `target/release/my-foo` or `/usr/bin/echo`) are not
+/// searched, but [`PathResolution::Absolute`] requires them to be absolute.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PathResolution {
+    /// Search the program in the directories of the `PATH` of the runner. This is the default.
+    #[default]
+    Inherit,
+    /// Search the program only in these directories in the given order
+    Dirs(Vec<PathBuf>),
+    /// Don't search the program at all. All paths have to be absolute.
+    Absolute,
+}
+
+/// The setup and teardown phases of a benchmark which are not part of the benchmarked code
+///
+/// The benchmark process prints the [`Phase::start_marker`] and [`Phase::end_marker`] lines to
+/// `stdout` and `stderr` around the output of a phase. The runner uses these markers to attribute
+/// the captured output to the phase it was produced in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    /// The `setup` function
+    Setup,
+    /// The `teardown` function
+    Teardown,
+}
+
 /// Configure the `Stream` which should be used as pipe in [`Stdin::Setup`]
 ///
 /// The default is [`Pipe::Stdout`]
@@ -828,6 +913,57 @@ pub enum Pipe {
     Stderr,
 }
 
+/// A signal which can be sent to the [`Command`] of a binary benchmark
+///
+/// Only the most common signals are available which have the same meaning on all supported
+/// platforms.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Signal {
+    /// `SIGHUP`: The controlling terminal was closed
+    Hup,
+    /// `SIGINT`: Interrupt from the keyboard (`Ctrl-C`)
+    Int,
+    /// `SIGQUIT`: Quit from the keyboard
+    Quit,
+    /// `SIGKILL`: Kill the process. This signal cannot be caught or ignored.
+    Kill,
+    /// `SIGUSR1`: User-defined signal 1
+    Usr1,
+    /// `SIGUSR2`: User-defined signal 2
+    Usr2,
+    /// `SIGALRM`: A timer expired
+    Alrm,
+    /// `SIGTERM`: Request the termination of the process
+    Term,
+}
+
+/// The value of the valgrind core option `--smc-check`
+///
+/// Controls the detection of self-modifying code. See the [valgrind
+/// documentation](https://valgrind.org/docs/manual/manual-core.html#opt.smc-check) for the
+/// details.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SmcCheck {
+    /// Don't check for self-modifying code (`none`)
+    None,
+    /// Check only code on the stack (`stack`)
+    Stack,
+    /// Check all code (`all`)
+    All,
+    /// Check all code which is not from file-backed mappings (`all-non-file`)
+    AllNonFile,
+}
+
+/// The order of the benchmarks in the summary of the regressions after all benchmarks
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Sort {
+    /// Keep the order in which the benchmarks were run. This is the default.
+    #[default]
+    Declaration,
+    /// Show the benchmarks with the worst regression first
+    DeltaDesc,
+}
+
 /// This is a special `Stdio` for the stdin method of [`Command`]
 ///
 /// Contains all the standard [`Stdio`] options and the [`Stdin::Setup`] option
@@ -875,6 +1011,22 @@ pub(crate) enum Stream {
     Stdout,
 }
 
+/// The threads of a benchmark which are included in the total of callgrind
+///
+/// The metrics of all threads are recorded and shown, but only the metrics of the selected threads
+/// are summed up in the total.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ThreadSelection {
+    /// Include all threads in the total. This is the default.
+    #[default]
+    All,
+    /// Include only the thread with this thread number (starting with `1` for the main thread)
+    Only(usize),
+    /// Include only the threads in which a function matching this glob pattern was executed. The
+    /// same wildcards as in [`EntryPoint::Custom`] are allowed.
+    Function(String),
+}
+
 /// The tool specific flamegraph configuration
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum ToolFlamegraphConfig {
@@ -916,6 +1068,34 @@ pub enum ToolRegressionConfig {
     None,
 }
 
+/// How the total is computed from the metrics of the processes, parts and threads of a benchmark
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TotalAggregation {
+    /// Sum up the metrics. This is the default.
+    #[default]
+    Sum,
+    /// Use the maximum of each metric
+    Max,
+    /// Sum up the metrics but exclude the main thread (thread `1`)
+    ExcludeMainThread,
+}
+
+/// The position at which the description is truncated in the terminal output
+///
+/// The ellipsis replaces the removed part of the description. Descriptions which only differ at
+/// their end, like argument lists, stay distinguishable with [`TruncatePosition::Start`] or
+/// [`TruncatePosition::Middle`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TruncatePosition {
+    /// Remove the start of the description (`...d, e, f`)
+    Start,
+    /// Remove the middle of the description (`a, b...e, f`)
+    Middle,
+    /// Remove the end of the description (`a, b, c...`). This is the default.
+    #[default]
+    End,
+}
+
 /// The valgrind tools which can be run
 ///
 /// Note the default changes from `Callgrind` to `Cachegrind` if the `cachegrind` feature is
@@ -941,6 +1121,54 @@ pub enum ValgrindTool {
     BBV,
 }
 
+/// The value of the valgrind core option `--vgdb`
+///
+/// Controls the gdbserver of valgrind. See the [valgrind
+/// documentation](https://valgrind.org/docs/manual/manual-core.html#opt.vgdb) for the details.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Vgdb {
+    /// Disable the gdbserver (`no`)
+    No,
+    /// Enable the gdbserver (`yes`)
+    Yes,
+    /// Enable the gdbserver with precise breakpoints and single stepping (`full`)
+    Full,
+}
+
+/// The mode to run a benchmark under the gdbserver of valgrind to debug it with gdb
+///
+/// The benchmark is run with `--vgdb=yes` or `--vgdb=full` and `--vgdb-error` set to the number of
+/// errors after which valgrind waits for gdb to connect. A value of `0` waits for gdb before the
+/// benchmark starts. Benchmarks run in this mode are not parsed or compared.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VgdbMode {
+    /// Run with `--vgdb=yes` and wait for gdb after this number of errors
+    At(u64),
+    /// Run with `--vgdb=full` and wait for gdb after this number of errors
+    ///
+    /// In contrast to [`VgdbMode::At`], breakpoints are precise and single stepping works in all
+    /// cases but valgrind runs slower.
+    FullAt(u64),
+}
+
+/// The outcome of a finished benchmark
+///
+/// The outcomes are passed to the `teardown` of the groups and the `main!` macro which run after
+/// the benchmarks have finished.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BenchmarkOutcome {
+    /// The name of the benchmark function
+    pub function_name: String,
+    /// The id of the benchmark as in `#[bench::id]` if present
+    pub id: Option<String>,
+    /// The metrics of the total of all tools which were run
+    pub metrics: Vec<OutcomeMetric>,
+    /// The module path of the benchmark in the form `bench_file::group::function_name`
+    pub module_path: String,
+    /// True if the benchmark has regressed (an expected regression doesn't count)
+    pub regressed: bool,
+}
+
 /// The model for the `#[binary_benchmark]` attribute or the equivalent from the low level api
 ///
 /// For internal use only
@@ -1000,16 +1228,24 @@ pub struct BinaryBenchmarkConfig {
     pub exit_with: Option<ExitWith>,
     /// The configuration of the output format
     pub output_format: Option<OutputFormat>,
+    /// How bare program names of the [`Command`] are resolved
+    pub path_resolution: Option<PathResolution>,
+    /// The named profiles which can be selected with `--profile`
+    pub profiles: Vec<(String, Self)>,
     /// Run the benchmarked binary in a [`Sandbox`] or not
     pub sandbox: Option<Sandbox>,
     /// Run the `setup` function parallel to the benchmarked binary
     pub setup_parallel: Option<bool>,
+    /// The tags of the benchmarks, for example shown in the json output of `--list`
+    pub tags: Vec<String>,
     /// The valgrind tools to run in addition to the default tool
     pub tools: Tools,
     /// The tool override at this configuration level
     pub tools_override: Option<Tools>,
     /// The arguments to pass to all tools
     pub valgrind_args: RawArgs,
+    /// Run the benchmarks under the gdbserver of valgrind
+    pub vgdb: Option<VgdbMode>,
 }
 
 /// The model for the `binary_benchmark_group` macro
@@ -1044,6 +1280,8 @@ pub struct BinaryBenchmarkGroups {
     pub has_setup: bool,
     /// True if there is a `teardown` function
     pub has_teardown: bool,
+    /// The user metadata as in `main!(metadata = [("key", "value")])`
+    pub metadata: Vec<(String, String)>,
 }
 
 /// The model for the regression check configuration of Cachegrind
@@ -1068,6 +1306,15 @@ pub struct CallgrindRegressionConfig {
     pub soft_limits: Vec<(CallgrindMetrics, f64)>,
 }
 
+/// The thresholds of the differences in percent at which the terminal output changes the color
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ColorThresholds {
+    /// Differences up to this value (inclusive) are not colored
+    pub neutral: f64,
+    /// Regressions above the `neutral` threshold up to this value (inclusive) are yellow
+    pub warning: f64,
+}
+
 /// The model for the command returned by the binary benchmark function
 #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct Command {
@@ -1077,6 +1324,8 @@ pub struct Command {
     pub config: BinaryBenchmarkConfig,
     /// If present the command is delayed as configured in [`Delay`]
     pub delay: Option<Delay>,
+    /// If present, the stdin of the command is driven by the [`InputScript`]
+    pub input_script: Option<InputScript>,
     /// The path to the executable
     pub path: PathBuf,
     /// The command's stderr
@@ -1085,6 +1334,10 @@ pub struct Command {
     pub stdin: Option<Stdin>,
     /// The command's stdout
     pub stdout: Option<Stdio>,
+    /// The commands which are run sequentially after this command as steps of the same benchmark
+    pub steps: Vec<Self>,
+    /// If present, the command is terminated as configured in [`Terminate`]
+    pub terminate: Option<Terminate>,
 }
 
 /// The delay of the [`Command`]
@@ -1139,6 +1392,15 @@ pub struct FlamegraphConfig {
     pub title: Option<String>,
 }
 
+/// The script driving the stdin of the [`Command`]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InputScript {
+    /// The steps in the order they are executed
+    pub steps: Vec<InputStep>,
+    /// The timeout of each [`InputStep::WaitFor`] step
+    pub timeout: Option<Duration>,
+}
+
 /// The model for the `#[library_benchmark]` attribute
 #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct LibraryBenchmark {
@@ -1155,12 +1417,24 @@ pub struct LibraryBenchmarkBench {
     pub args: Option<String>,
     /// The configuration at this level
     pub config: Option<LibraryBenchmarkConfig>,
+    /// The description of the benchmark as in `#[library_benchmark(description = "...")]`
+    pub description: Option<String>,
+    /// The reason why a regression is expected as in `#[bench::id(expect_regression = "...")]`
+    pub expect_regression: Option<String>,
     /// The name of the function
     pub function_name: String,
     /// The id of the attribute as in `#[bench::id]`
     pub id: Option<String>,
     /// The amount of elements in the iterator of the `#[benches::id(iter = ITERATOR)]` if present
     pub iter_count: Option<usize>,
+    /// The ids of the benchmarks of the iterator replacing the `id` if present
+    pub iter_ids: Option<Vec<String>>,
+    /// The stable key replacing `function_name.id` as in `#[library_benchmark(key = "...")]`
+    pub key: Option<String>,
+    /// The target triple as in `#[bench::id(runs_on = "target")]` maybe prefixed with `!`
+    pub runs_on: Option<String>,
+    /// The reason if the benchmark was skipped at compile time like with `rust_version`
+    pub skip: Option<String>,
 }
 
 /// The model for the configuration in library benchmarks
@@ -1177,12 +1451,20 @@ pub struct LibraryBenchmarkConfig {
     pub envs: Vec<(OsString, Option<OsString>)>,
     /// The configuration of the output format
     pub output_format: Option<OutputFormat>,
+    /// The named profiles which can be selected with `--profile`
+    pub profiles: Vec<(String, Self)>,
+    /// Run the benchmark in a [`Sandbox`] or not
+    pub sandbox: Option<Sandbox>,
+    /// The tags of the benchmarks, for example shown in the json output of `--list`
+    pub tags: Vec<String>,
     /// The valgrind tools to run in addition to the default tool
     pub tools: Tools,
     /// The tool override at this configuration level
     pub tools_override: Option<Tools>,
     /// The arguments to pass to all tools
     pub valgrind_args: RawArgs,
+    /// Run the benchmarks under the gdbserver of valgrind
+    pub vgdb: Option<VgdbMode>,
 }
 
 /// The model for the `library_benchmark_group` macro
@@ -1200,6 +1482,8 @@ pub struct LibraryBenchmarkGroup {
     pub id: String,
     /// The actual data and the benchmarks of this group
     pub library_benchmarks: Vec<LibraryBenchmark>,
+    /// The regression budget in percent of the totals of this group
+    pub regression_budget: Option<f64>,
 }
 
 /// The model for the `main` macro
@@ -1217,19 +1501,66 @@ pub struct LibraryBenchmarkGroups {
     pub has_setup: bool,
     /// True if there is a `teardown` function
     pub has_teardown: bool,
+    /// The user metadata as in `main!(metadata = [("key", "value")])`
+    pub metadata: Vec<(String, String)>,
+}
+
+/// A single metric of a [`BenchmarkOutcome`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OutcomeMetric {
+    /// The name of the metric like `Ir` or `TotalBytes`
+    pub kind: String,
+    /// The value of the current run if present
+    pub new: Option<f64>,
+    /// The value of the old run or the baseline if present
+    pub old: Option<f64>,
+    /// The valgrind tool which recorded this metric
+    pub tool: ValgrindTool,
 }
 
 /// The configuration values for the output format
 #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct OutputFormat {
+    /// Restrict the terminal output to ASCII characters
+    pub ascii: Option<bool>,
+    /// The thresholds at which the differences change the color
+    pub color_thresholds: Option<ColorThresholds>,
+    /// The style of the differences between the new and the old metrics
+    pub delta_style: Option<DeltaStyle>,
+    /// Hide the output of benchmarks with unchanged metrics
+    pub hide_unchanged: Option<bool>,
+    /// The maximum number of fractional-part digits of the differences
+    pub precision: Option<usize>,
+    /// Show the absolute difference between the new and the old metrics
+    pub show_absolute_diff: Option<bool>,
+    /// Show the call tree of the functions with the inclusive instructions
+    pub show_call_tree: Option<bool>,
     /// Show a grid instead of spaces in the terminal output
     pub show_grid: Option<bool>,
+    /// Show the summed up primary metrics of the benchmarks of a library benchmark group
+    pub show_group_totals: Option<bool>,
     /// Show intermediate results, for example in benchmarks for multi-threaded applications
     pub show_intermediate: Option<bool>,
+    /// Show the lines of the entry point with the most instructions
+    pub show_lines: Option<bool>,
+    /// Show the page faults and context switches of the valgrind processes
+    pub show_resource_usage: Option<bool>,
+    /// Show the valgrind version and the resolved arguments of each tool
+    pub show_tool_args: Option<bool>,
+    /// Show this amount of functions with the most instructions
+    pub show_top_functions: Option<usize>,
+    /// The order of the benchmarks in the summary of the regressions
+    pub sort_by: Option<Sort>,
+    /// Subtract the overhead of the benchmark harness from the callgrind metrics
+    pub subtract_overhead: Option<bool>,
     /// Don't show differences within the tolerance margin
     pub tolerance: Option<f64>,
     /// If set, truncate the description
     pub truncate_description: Option<Option<usize>>,
+    /// The ellipsis which replaces the truncated part of the description
+    pub truncate_ellipsis: Option<String>,
+    /// The position at which the description is truncated
+    pub truncate_position: Option<TruncatePosition>,
 }
 
 /// The raw arguments to pass to a valgrind tool
@@ -1247,6 +1578,15 @@ pub struct Sandbox {
     pub follow_symlinks: Option<bool>,
 }
 
+/// The termination of the [`Command`] with a [`Signal`] sent by the runner
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Terminate {
+    /// The time after the start of the [`Command`] when the signal is sent
+    pub after: Duration,
+    /// The signal to send
+    pub signal: Signal,
+}
+
 /// The tool configuration
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Tool {
@@ -1254,10 +1594,14 @@ pub struct Tool {
     pub enable: Option<bool>,
     /// The entry point for the tool
     pub entry_point: Option<EntryPoint>,
+    /// If true, the errors of error checking tools fail the benchmark run after all benchmarks
+    pub fail_on_errors: Option<bool>,
     /// The configuration for flamegraphs
     pub flamegraph_config: Option<ToolFlamegraphConfig>,
     /// Any frames in the call stack which should be considered in addition to the entry point
     pub frames: Option<Vec<String>>,
+    /// If true, generate the suppressions of all errors with `--gen-suppressions=all`
+    pub gen_suppressions: Option<bool>,
     /// The valgrind tool this configuration is for
     pub kind: ValgrindTool,
     /// The configuration of the output format
@@ -1268,12 +1612,27 @@ pub struct Tool {
     pub regression_config: Option<ToolRegressionConfig>,
     /// If true show the logging output of Valgrind (not Iai-Callgrind)
     pub show_log: Option<bool>,
+    /// The suppression files passed with `--suppressions`
+    pub suppressions: Option<Vec<PathBuf>>,
+    /// The threads which are included in the total
+    pub thread_selection: Option<ThreadSelection>,
+    /// How the total is computed
+    pub total_aggregation: Option<TotalAggregation>,
 }
 
 /// The configurations of all tools to run in addition to the default tool
 #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct Tools(pub Vec<Tool>);
 
+impl BenchmarkOutcome {
+    /// Return the [`OutcomeMetric`] of the `tool` with the name `kind` (like `Ir`) if present
+    pub fn metric(&self, tool: ValgrindTool, kind: &str) -> Option<&OutcomeMetric> {
+        self.metrics
+            .iter()
+            .find(|metric| metric.tool == tool && metric.kind == kind)
+    }
+}
+
 impl BinaryBenchmarkConfig {
     /// Update this configuration with all other configurations in the given order
     #[must_use]
@@ -1286,11 +1645,13 @@ impl BinaryBenchmarkConfig {
             self.env_clear = update_option(&self.env_clear, &other.env_clear);
             self.current_dir = update_option(&self.current_dir, &other.current_dir);
             self.exit_with = update_option(&self.exit_with, &other.exit_with);
+            self.path_resolution = update_option(&self.path_resolution, &other.path_resolution);
 
             self.valgrind_args
                 .extend_ignore_flag(other.valgrind_args.0.iter());
 
             self.envs.extend_from_slice(&other.envs);
+            update_tags(&mut self.tags, &other.tags);
 
             if let Some(other_tools) = &other.tools_override {
                 self.tools = other_tools.clone();
@@ -1303,6 +1664,7 @@ impl BinaryBenchmarkConfig {
             self.sandbox = update_option(&self.sandbox, &other.sandbox);
             self.setup_parallel = update_option(&self.setup_parallel, &other.setup_parallel);
             self.output_format = update_option(&self.output_format, &other.output_format);
+            self.vgdb = update_option(&self.vgdb, &other.vgdb);
         }
         self
     }
@@ -1420,11 +1782,11 @@ impl FromStr for CachegrindMetric {
             "bcm" => Self::Bcm,
             "bi" => Self::Bi,
             "bim" => Self::Bim,
-            "l1hits" => Self::L1hits,
-            "llhits" => Self::LLhits,
-            "ramhits" => Self::RamHits,
-            "totalrw" => Self::TotalRW,
-            "estimatedcycles" => Self::EstimatedCycles,
+            "l1hits" | "l1" => Self::L1hits,
+            "llhits" | "ll" => Self::LLhits,
+            "ramhits" | "ram" => Self::RamHits,
+            "totalrw" | "mem" => Self::TotalRW,
+            "estimatedcycles" | "cycles" => Self::EstimatedCycles,
             "i1missrate" => Self::I1MissRate,
             "d1missrate" => Self::D1MissRate,
             "llimissrate" => Self::LLiMissRate,
@@ -1433,7 +1795,15 @@ impl FromStr for CachegrindMetric {
             "l1hitrate" => Self::L1HitRate,
             "llhitrate" => Self::LLHitRate,
             "ramhitrate" => Self::RamHitRate,
-            _ => return Err(anyhow!("Unknown cachegrind metric: '{string}'")),
+            _ => {
+                return Err(unknown_metric_error(
+                    "Unknown cachegrind metric",
+                    string,
+                    Self::iter()
+                        .map(|metric| format!("{metric:?}"))
+                        .chain(METRIC_ALIASES.iter().map(|alias| (*alias).to_owned())),
+                ))
+            }
         };
 
         Ok(metric)
@@ -1542,6 +1912,17 @@ impl FromStr for CallgrindMetrics {
     }
 }
 
+impl Display for CollectSystime {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::No => "no",
+            Self::Msec => "msec",
+            Self::Usec => "usec",
+            Self::Nsec => "nsec",
+        })
+    }
+}
+
 impl Default for DelayKind {
     fn default() -> Self {
         Self::DurationElapse(Duration::from_secs(60))
@@ -1588,7 +1969,13 @@ impl FromStr for DhatMetric {
             "totallifetimes" | "tl" => Self::TotalLifetimes,
             "maximumbytes" | "mb" => Self::MaximumBytes,
             "maximumblocks" | "mbk" => Self::MaximumBlocks,
-            _ => return Err(anyhow!("Unknown dhat metric: '{string}'")),
+            _ => {
+                return Err(unknown_metric_error(
+                    "Unknown dhat metric",
+                    string,
+                    Self::iter().map(|metric| format!("{metric:?}")),
+                ))
+            }
         };
 
         Ok(metric)
@@ -1811,11 +2198,11 @@ impl FromStr for EventKind {
             "accost2" => Self::AcCost2,
             "sploss1" => Self::SpLoss1,
             "sploss2" => Self::SpLoss2,
-            "l1hits" => Self::L1hits,
-            "llhits" => Self::LLhits,
-            "ramhits" => Self::RamHits,
-            "totalrw" => Self::TotalRW,
-            "estimatedcycles" => Self::EstimatedCycles,
+            "l1hits" | "l1" => Self::L1hits,
+            "llhits" | "ll" => Self::LLhits,
+            "ramhits" | "ram" => Self::RamHits,
+            "totalrw" | "mem" => Self::TotalRW,
+            "estimatedcycles" | "cycles" => Self::EstimatedCycles,
             "i1missrate" => Self::I1MissRate,
             "d1missrate" => Self::D1MissRate,
             "llimissrate" => Self::LLiMissRate,
@@ -1824,7 +2211,15 @@ impl FromStr for EventKind {
             "l1hitrate" => Self::L1HitRate,
             "llhitrate" => Self::LLHitRate,
             "ramhitrate" => Self::RamHitRate,
-            _ => return Err(anyhow!("Unknown event kind: '{string}'")),
+            _ => {
+                return Err(unknown_metric_error(
+                    "Unknown event kind",
+                    string,
+                    Self::iter()
+                        .map(|event_kind| format!("{event_kind:?}"))
+                        .chain(METRIC_ALIASES.iter().map(|alias| (*alias).to_owned())),
+                ))
+            }
         };
 
         Ok(event_kind)
@@ -2049,6 +2444,16 @@ impl From<CallgrindMetrics> for IndexSet<EventKind> {
     }
 }
 
+impl InputScript {
+    /// Return true if the script contains [`InputStep::WaitFor`] steps
+    #[cfg(feature = "runner")]
+    pub(crate) fn has_wait_steps(&self) -> bool {
+        self.steps
+            .iter()
+            .any(|step| matches!(step, InputStep::WaitFor(_)))
+    }
+}
+
 impl LibraryBenchmarkConfig {
     /// Update this configuration with all other configurations in the given order
     #[must_use]
@@ -2064,6 +2469,8 @@ impl LibraryBenchmarkConfig {
                 .extend_ignore_flag(other.valgrind_args.0.iter());
 
             self.envs.extend_from_slice(&other.envs);
+            update_tags(&mut self.tags, &other.tags);
+
             if let Some(other_tools) = &other.tools_override {
                 self.tools = other_tools.clone();
             } else if !other.tools.is_empty() {
@@ -2073,6 +2480,8 @@ impl LibraryBenchmarkConfig {
             }
 
             self.output_format = update_option(&self.output_format, &other.output_format);
+            self.sandbox = update_option(&self.sandbox, &other.sandbox);
+            self.vgdb = update_option(&self.vgdb, &other.vgdb);
         }
         self
     }
@@ -2123,6 +2532,32 @@ impl From<u64> for Limit {
     }
 }
 
+impl Phase {
+    /// Return the line which marks the end of this phase
+    pub fn end_marker(self) -> &'static str {
+        match self {
+            Self::Setup => "[iai-callgrind] end of setup",
+            Self::Teardown => "[iai-callgrind] end of teardown",
+        }
+    }
+
+    /// Return the id of this phase
+    pub fn id(self) -> &'static str {
+        match self {
+            Self::Setup => "setup",
+            Self::Teardown => "teardown",
+        }
+    }
+
+    /// Return the line which marks the start of this phase
+    pub fn start_marker(self) -> &'static str {
+        match self {
+            Self::Setup => "[iai-callgrind] setup",
+            Self::Teardown => "[iai-callgrind] teardown",
+        }
+    }
+}
+
 impl RawArgs {
     /// Create new arguments for a valgrind tool
     pub fn new<I, T>(args: T) -> Self
@@ -2184,6 +2619,55 @@ where
     }
 }
 
+impl Signal {
+    /// Return the name of the signal without the `SIG` prefix (like `TERM`)
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Hup => "HUP",
+            Self::Int => "INT",
+            Self::Quit => "QUIT",
+            Self::Kill => "KILL",
+            Self::Usr1 => "USR1",
+            Self::Usr2 => "USR2",
+            Self::Alrm => "ALRM",
+            Self::Term => "TERM",
+        }
+    }
+
+    /// Return the number of the signal on the current platform
+    pub fn number(self) -> i32 {
+        match self {
+            Self::Hup => 1,
+            Self::Int => 2,
+            Self::Quit => 3,
+            Self::Kill => 9,
+            Self::Usr1 if cfg!(target_os = "linux") => 10,
+            Self::Usr1 => 30,
+            Self::Usr2 if cfg!(target_os = "linux") => 12,
+            Self::Usr2 => 31,
+            Self::Alrm => 14,
+            Self::Term => 15,
+        }
+    }
+}
+
+impl Display for Signal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "SIG{}", self.name())
+    }
+}
+
+impl Display for SmcCheck {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::None => "none",
+            Self::Stack => "stack",
+            Self::All => "all",
+            Self::AllNonFile => "all-non-file",
+        })
+    }
+}
+
 impl Stdin {
     #[cfg(feature = "runner")]
     pub(crate) fn apply(
@@ -2329,6 +2813,11 @@ impl Tool {
             output_format: None,
             entry_point: None,
             frames: None,
+            gen_suppressions: None,
+            suppressions: None,
+            fail_on_errors: None,
+            thread_selection: None,
+            total_aggregation: None,
         }
     }
 
@@ -2355,6 +2844,12 @@ impl Tool {
             self.output_format = update_option(&self.output_format, &other.output_format);
             self.entry_point = update_option(&self.entry_point, &other.entry_point);
             self.frames = update_option(&self.frames, &other.frames);
+            self.gen_suppressions = update_option(&self.gen_suppressions, &other.gen_suppressions);
+            self.suppressions = update_option(&self.suppressions, &other.suppressions);
+            self.fail_on_errors = update_option(&self.fail_on_errors, &other.fail_on_errors);
+            self.thread_selection = update_option(&self.thread_selection, &other.thread_selection);
+            self.total_aggregation =
+                update_option(&self.total_aggregation, &other.total_aggregation);
 
             self.raw_args.extend_ignore_flag(other.raw_args.0.iter());
         }
@@ -2468,11 +2963,56 @@ impl TryFrom<&str> for ValgrindTool {
     }
 }
 
+impl Display for Vgdb {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::No => "no",
+            Self::Yes => "yes",
+            Self::Full => "full",
+        })
+    }
+}
+
+impl VgdbMode {
+    /// Return the valgrind arguments of this mode
+    pub fn to_args(&self) -> [String; 2] {
+        let (vgdb, num_errors) = match self {
+            Self::At(num_errors) => (Vgdb::Yes, num_errors),
+            Self::FullAt(num_errors) => (Vgdb::Full, num_errors),
+        };
+        [
+            format!("--vgdb={vgdb}"),
+            format!("--vgdb-error={num_errors}"),
+        ]
+    }
+}
+
+/// Create the error for an unknown metric suggesting the most similar of the `names` if any
+#[cfg(feature = "runner")]
+fn unknown_metric_error<I>(message: &str, string: &str, names: I) -> anyhow::Error
+where
+    I: IntoIterator<Item = String>,
+{
+    match find_similar(string, names) {
+        Some(name) => anyhow!("{message}: '{string}'. Did you mean '{name}'?"),
+        None => anyhow!("{message}: '{string}'"),
+    }
+}
+
 /// Update the value of an [`Option`]
 pub fn update_option<T: Clone>(first: &Option<T>, other: &Option<T>) -> Option<T> {
     other.clone().or_else(|| first.clone())
 }
 
+/// Add the `other` tags to the `tags` if they are not present yet
+fn update_tags(tags: &mut Vec<String>, other: &[String]) {
+    for tag in other {
+        if !tags.contains(tag) {
+            tags.push(tag.clone());
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use indexmap::indexset;
@@ -2491,6 +3031,14 @@ mod tests {
         }
     }
 
+    #[rstest]
+    #[case::sys_count(SysCount, "SysCount")]
+    #[case::sys_time(SysTime, "SysTime")]
+    #[case::sys_cpu_time(SysCpuTime, "SysCpuTime")]
+    fn test_event_kind_display_system_calls(#[case] event_kind: EventKind, #[case] expected: &str) {
+        assert_eq!(event_kind.to_string(), expected);
+    }
+
     #[test]
     fn test_event_kind_from_str_ignore_case() {
         for event_kind in EventKind::iter() {
@@ -2500,6 +3048,35 @@ mod tests {
         }
     }
 
+    #[rstest]
+    #[case::instructions("instructions", Ir)]
+    #[case::cycles("cycles", EstimatedCycles)]
+    #[case::l1("L1", L1hits)]
+    #[case::ll("ll", LLhits)]
+    #[case::ram("ram", RamHits)]
+    #[case::mem("MEM", TotalRW)]
+    fn test_event_kind_from_str_alias(#[case] alias: &str, #[case] expected: EventKind) {
+        assert_eq!(EventKind::from_str(alias).unwrap(), expected);
+        assert_eq!(
+            EventKind::from(CachegrindMetric::from_str(alias).unwrap()),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case::typo(
+        "EstimatedCylces",
+        "Unknown event kind: 'EstimatedCylces'. Did you mean 'EstimatedCycles'?"
+    )]
+    #[case::alias_typo("cycels", "Unknown event kind: 'cycels'. Did you mean 'cycles'?")]
+    #[case::no_suggestion("WRONG", "Unknown event kind: 'WRONG'")]
+    fn test_event_kind_from_str_when_unknown(#[case] string: &str, #[case] expected: &str) {
+        assert_eq!(
+            EventKind::from_str(string).unwrap_err().to_string(),
+            expected
+        );
+    }
+
     #[test]
     fn test_library_benchmark_config_update_from_all_when_default() {
         assert_eq!(
@@ -2530,10 +3107,19 @@ mod tests {
                 entry_point: Some(EntryPoint::default()),
                 output_format: Some(ToolOutputFormat::None),
                 frames: Some(vec!["some::frame".to_owned()]),
+                gen_suppressions: None,
+                suppressions: None,
+                fail_on_errors: None,
+                thread_selection: None,
+                total_aggregation: None,
             }]),
             tools_override: None,
             output_format: None,
             default_tool: Some(ValgrindTool::BBV),
+            profiles: vec![],
+            sandbox: None,
+            tags: vec!["tag".to_owned()],
+            vgdb: None,
         };
 
         assert_eq!(base.update_from_all([Some(&other.clone())]), other);
@@ -2560,10 +3146,19 @@ mod tests {
                 entry_point: Some(EntryPoint::default()),
                 output_format: Some(ToolOutputFormat::None),
                 frames: Some(vec!["some::frame".to_owned()]),
+                gen_suppressions: None,
+                suppressions: None,
+                fail_on_errors: None,
+                thread_selection: None,
+                total_aggregation: None,
             }]),
             tools_override: Some(Tools(vec![])),
             output_format: Some(OutputFormat::default()),
             default_tool: Some(ValgrindTool::BBV),
+            profiles: vec![],
+            sandbox: None,
+            tags: vec!["tag".to_owned()],
+            vgdb: None,
         };
         let expected = LibraryBenchmarkConfig {
             tools: other.tools_override.as_ref().unwrap().clone(),
@@ -2588,6 +3183,31 @@ mod tests {
         assert_eq!(actual, config);
     }
 
+    #[test]
+    fn test_library_benchmark_config_update_from_all_tags() {
+        let base = LibraryBenchmarkConfig {
+            tags: vec!["a".to_owned(), "b".to_owned()],
+            ..Default::default()
+        };
+        let other = LibraryBenchmarkConfig {
+            tags: vec!["c".to_owned(), "a".to_owned()],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            base.update_from_all([None, Some(&other), Some(&other)])
+                .tags,
+            vec!["a".to_owned(), "b".to_owned(), "c".to_owned()]
+        );
+    }
+
+    #[rstest]
+    #[case::at(VgdbMode::At(1), ["--vgdb=yes", "--vgdb-error=1"])]
+    #[case::full_at(VgdbMode::FullAt(0), ["--vgdb=full", "--vgdb-error=0"])]
+    fn test_vgdb_mode_to_args(#[case] mode: VgdbMode, #[case] expected: [&str; 2]) {
+        assert_eq!(mode.to_args(), expected);
+    }
+
     #[rstest]
     #[case::all_none(None, None, None)]
     #[case::some_and_none(Some(true), None, Some(true))]
@@ -2727,6 +3347,11 @@ mod tests {
             output_format: Some(ToolOutputFormat::None),
             entry_point: Some(EntryPoint::Default),
             frames: Some(vec!["some::frame".to_owned()]),
+            gen_suppressions: Some(true),
+            suppressions: Some(vec![PathBuf::from("some.supp")]),
+            fail_on_errors: Some(true),
+            thread_selection: Some(ThreadSelection::Only(2)),
+            total_aggregation: Some(TotalAggregation::Max),
         };
         let expected = other.clone();
         base.update(&other);
@@ -2746,6 +3371,11 @@ mod tests {
             output_format: Some(ToolOutputFormat::None),
             entry_point: Some(EntryPoint::Default),
             frames: Some(vec!["some::frame".to_owned()]),
+            gen_suppressions: Some(true),
+            suppressions: Some(vec![PathBuf::from("some.supp")]),
+            fail_on_errors: Some(true),
+            thread_selection: Some(ThreadSelection::Only(2)),
+            total_aggregation: Some(TotalAggregation::Max),
         };
 
         let expected = base.clone();
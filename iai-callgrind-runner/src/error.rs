@@ -12,6 +12,7 @@ use crate::api::ValgrindTool;
 use crate::runner::common::ModulePath;
 use crate::runner::format::Header;
 use crate::runner::tool::path::ToolOutputPath;
+use crate::runner::tool::run::strip_phase_markers;
 use crate::util::write_all_to_stderr;
 
 /// The main Iai-Callgrind error type
@@ -21,10 +22,21 @@ pub enum Error {
     ///
     /// `BenchmarkError(ValgrindTool, ModulePath, message)`
     BenchmarkError(ValgrindTool, ModulePath, String),
+    /// The installed valgrind lacks a tool or feature required by the benchmarks
+    ///
+    /// `CapabilityError(message)`
+    CapabilityError(String),
     /// An error within the UI configuration structs but transpiring in the runner
     ///
     /// `ConfigurationError(ModulePath, benchmark_id, message)`
     ConfigurationError(ModulePath, Option<String>, String),
+    /// The benchmark run finished with an outcome which maps to this exit code
+    ///
+    /// The outcome classes and their exit codes are configured with `--exit-codes`. If the
+    /// benchmark run was aborted, the error causing the abort is the source of this error.
+    ///
+    /// `ExitCodeError(exit_code)`
+    ExitCodeError(u8),
     /// An error during the initialization of the runner
     ///
     /// `InitError(message)`
@@ -61,6 +73,11 @@ pub enum Error {
     ///
     /// `SandboxError(message)`
     SandboxError(String),
+    /// The platform is not supported, for example because valgrind is not available, and
+    /// `--allow-unsupported` was given
+    ///
+    /// `UnsupportedPlatform(reason)`
+    UnsupportedPlatform(String),
     /// A version mismatch between the runner and the UI
     ///
     /// `VersionMismatch(Cmp, runner_version, library_version)`
@@ -111,7 +128,7 @@ impl Display for Error {
                     let _ = output_path.dump_log(log::Level::Error, &mut stderr());
                 }
                 if let Some(output) = output {
-                    write_all_to_stderr(&output.stderr);
+                    write_all_to_stderr(&strip_phase_markers(&output.stderr));
                 }
 
                 if let Some(code) = status.code() {
@@ -144,12 +161,19 @@ impl Display for Error {
                     write!(f, "Performance has regressed.",)
                 }
             }
+            Self::ExitCodeError(exit_code) => {
+                write!(f, "The benchmark run finished with exit code {exit_code}")
+            }
             Self::SandboxError(message) => {
                 write!(f, "Error in sandbox: {message}")
             }
+            Self::UnsupportedPlatform(reason) => {
+                write!(f, "Unsupported platform: {reason}")
+            }
             Self::BenchmarkError(tool, module_path, message) => {
                 write!(f, "Error in {tool} benchmark {module_path}: {message}")
             }
+            Self::CapabilityError(message) => write!(f, "{message}"),
             Self::ConfigurationError(module_path, id, message) => {
                 let header = Header::without_description(module_path, id.clone());
                 write!(f, "Misconfiguration in: {header}\nCaused by:\n  {message}",)
@@ -10,7 +10,9 @@ pub mod api;
 pub mod error;
 #[cfg(feature = "runner")]
 pub mod runner;
-#[cfg(feature = "runner")]
+#[cfg(feature = "summary")]
 pub mod serde;
+#[cfg(feature = "summary")]
+pub mod summary;
 #[cfg(feature = "runner")]
 pub mod util;
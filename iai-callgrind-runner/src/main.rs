@@ -5,13 +5,17 @@ use colored::{control, Colorize};
 use env_logger::Env;
 use iai_callgrind_runner::error::Error;
 use iai_callgrind_runner::runner::envs;
+use iai_callgrind_runner::runner::run_log::Logger;
 use log::{error, warn};
 
 /// The main function of the `iai-callgrind-runner` binary
 ///
 /// We initialize the logging interface and configure the usage of colors as early as possible here.
 /// Then we're printing warnings with [`print_warnings`] and finally call the main
-/// [`iai_callgrind_runner::runner::run`] library function catching and printing
+/// [`iai_callgrind_runner::runner::run`] library function (or
+/// [`iai_callgrind_runner::runner::diff::run`] if the first argument is `--diff`,
+/// [`iai_callgrind_runner::runner::store::run`] if it is `--query` and
+/// [`iai_callgrind_runner::runner::report::run`] if it is `--report`) catching and printing
 /// [`iai_callgrind_runner::error::Error`]s.
 fn main() {
     // Configure the colored crate to respect IAI_CALLGRIND_COLOR and CARGO_TERM_COLOR
@@ -30,7 +34,7 @@ fn main() {
     }
 
     // Configure the env_logger crate to respect IAI_CALLGRIND_COLOR and CARGO_TERM_COLOR
-    env_logger::Builder::from_env(
+    let logger = env_logger::Builder::from_env(
         Env::default()
             .filter_or(envs::IAI_CALLGRIND_LOG, "warn")
             .write_style(
@@ -55,13 +59,29 @@ fn main() {
             record.args()
         )
     })
-    .init();
+    .build();
+    Logger::init(logger).expect("The logger should be initialized only once");
 
     print_warnings();
-    match iai_callgrind_runner::runner::run() {
+    let result = match std::env::args_os().nth(1) {
+        Some(arg) if arg == "--diff" => iai_callgrind_runner::runner::diff::run(),
+        Some(arg) if arg == "--query" => iai_callgrind_runner::runner::store::run(),
+        Some(arg) if arg.to_string_lossy().starts_with("--report") => {
+            iai_callgrind_runner::runner::report::run()
+        }
+        _ => iai_callgrind_runner::runner::run(),
+    };
+
+    match result {
         Ok(()) => {}
         Err(error) => {
-            if let Some(Error::RegressionError(is_fatal)) = error.downcast_ref::<Error>() {
+            if let Some(Error::ExitCodeError(exit_code)) = error.downcast_ref::<Error>() {
+                // If the benchmark run was aborted, the error which caused the abort is the source
+                if let Some(source) = error.chain().nth(1) {
+                    error!("{source}");
+                }
+                std::process::exit((*exit_code).into())
+            } else if let Some(Error::RegressionError(is_fatal)) = error.downcast_ref::<Error>() {
                 if *is_fatal {
                     error!("{error}");
                 }
@@ -15,13 +15,16 @@ use strum::IntoEnumIterator;
 use super::cachegrind::regression::CachegrindRegressionConfig;
 use super::callgrind::regression::CallgrindRegressionConfig;
 use super::dhat::regression::DhatRegressionConfig;
+use super::envs;
 use super::format::OutputFormatKind;
 use super::metrics::{Metric, TypeChecker};
+use super::retention::BaselineRetention;
+use super::store::StoreKind;
 use super::summary::{BaselineName, SummaryFormat};
 use super::tool::regression::ToolRegressionConfig;
 use crate::api::{
-    CachegrindMetric, CachegrindMetrics, CallgrindMetrics, DhatMetric, DhatMetrics, ErrorMetric,
-    EventKind, RawArgs, ValgrindTool,
+    CachegrindMetric, CachegrindMetrics, CallgrindMetrics, ColorThresholds, DeltaStyle, DhatMetric,
+    DhatMetrics, ErrorMetric, EventKind, RawArgs, Sort, ValgrindTool, VgdbMode,
 };
 
 // Utility for complex types intended to be used during the parsing of the command-line arguments
@@ -40,6 +43,29 @@ pub enum BenchmarkFilter {
     Name(String),
 }
 
+/// The options of the command-line argument --keep-sandbox
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum KeepSandbox {
+    /// Always delete the sandbox directory
+    #[default]
+    Never,
+    /// Keep the sandbox directory if running the benchmark failed
+    OnFailure,
+    /// Always keep the sandbox directory
+    Always,
+}
+
+/// The measurement profiles of the command-line argument --profile
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MeasurementProfile {
+    /// Run callgrind without cache simulation and flamegraphs and show only the instructions
+    Fast,
+    /// The default behavior with cache simulation
+    Full,
+    /// A named profile of the configuration file or the benchmark configuration
+    Named(String),
+}
+
 /// The `NoCapture` options for the command-line argument --nocapture
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum NoCapture {
@@ -62,6 +88,19 @@ pub enum TruncateDescription {
     None,
 }
 
+/// The exit codes of the outcome classes of a benchmark run for the argument --exit-codes
+///
+/// Successful benchmarks without regressions always result in the exit code `0`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExitCodes {
+    /// The exit code if a benchmark failed with an error
+    pub errored: u8,
+    /// The exit code if one or more benchmarks regressed
+    pub regressed: u8,
+    /// The exit code if benchmarks were skipped because the benchmark run was aborted
+    pub skipped: u8,
+}
+
 /// The command line arguments the user provided after `--` when running cargo bench
 ///
 /// These arguments are not the command line arguments passed to `iai-callgrind-runner`. We collect
@@ -77,14 +116,15 @@ pub enum TruncateDescription {
 Boolish command line arguments take also one of `y`, `yes`, `t`, `true`, `on`, `1`
 instead of `true` and one of `n`, `no`, `f`, `false`, `off`, and `0` instead of
 `false`",
-    after_help = "  Exit codes:
+    after_help = "  Exit codes (see also --exit-codes):
       0: Success
-      1: All other errors
+      1: A benchmark failed with an error and all other errors
       2: Parsing command-line arguments failed
       3: One or more regressions occurred
     ",
     long_about = None,
     no_binary_name = true,
+    args_override_self = true,
     override_usage= "cargo bench ... [BENCHNAME] -- [OPTIONS]",
     max_term_width = 101
 )]
@@ -172,6 +212,45 @@ pub struct CommandLineArgs {
     )]
     pub allow_aslr: Option<bool>,
 
+    #[rustfmt::skip]
+    /// Skip all benchmarks instead of failing if the platform is not supported (Default: false)
+    ///
+    /// If valgrind is not available, for example on Windows or in a CI job without valgrind
+    /// installed, all benchmarks are reported as skipped with the reason and the benchmark run
+    /// exits with `0`. This way, the bench job of a multi-platform CI matrix doesn't need special
+    /// handling. If the `iai-callgrind-runner` can't be started at all, this option is only
+    /// recognized on the command-line or as environment variable.
+    #[arg(
+        long = "allow-unsupported",
+        default_missing_value = "true",
+        default_value = "false",
+        num_args = 0..=1,
+        require_equals = true,
+        value_parser = BoolishValueParser::new(),
+        action = ArgAction::Set,
+        env = "IAI_CALLGRIND_ALLOW_UNSUPPORTED",
+        display_order = 100
+    )]
+    pub allow_unsupported: bool,
+
+    #[rustfmt::skip]
+    /// Restrict the terminal output to ASCII characters (Default: false)
+    ///
+    /// All non-ASCII characters, for example in the descriptions of the benchmarks, the ellipsis
+    /// of truncated descriptions or file names, are replaced. The unicode ellipsis `…` is replaced
+    /// with `...` and all other non-ASCII characters with `?`. This is useful in environments or
+    /// with log processors which don't handle unicode characters well.
+    #[arg(
+        long = "ascii",
+        default_missing_value = "true",
+        num_args = 0..=1,
+        require_equals = true,
+        value_parser = BoolishValueParser::new(),
+        env = "IAI_CALLGRIND_ASCII",
+        display_order = 300
+    )]
+    pub ascii: Option<bool>,
+
     #[rustfmt::skip]
     /// Compare against this baseline if present but do not overwrite it
     #[arg(
@@ -184,6 +263,51 @@ pub struct CommandLineArgs {
     )]
     pub baseline: Option<BaselineName>,
 
+    #[rustfmt::skip]
+    /// Prune the saved baselines after a successful benchmark run
+    ///
+    /// If the value is a number, the given amount of the most recently saved baselines of each
+    /// benchmark is kept. If the value is an age with one of the units `s` (seconds), `m`
+    /// (minutes), `h` (hours), `d` (days) or `w` (weeks), the baselines saved within this age are
+    /// kept. With an age, the runs older than this age are also removed from the result store of
+    /// `--store`. The time a baseline was saved is the modification time of its files. The
+    /// baselines of `--save-baseline`, `--baseline` and `--load-baseline` are never pruned and a
+    /// benchmark run with regressions doesn't prune any baselines.
+    ///
+    /// Examples:
+    ///   * --baseline-keep=10 keeps the 10 most recently saved baselines
+    ///   * --baseline-keep=30d keeps the baselines saved within the last 30 days
+    #[arg(
+        long = "baseline-keep",
+        value_parser = parse_baseline_keep,
+        num_args = 1,
+        verbatim_doc_comment,
+        env = "IAI_CALLGRIND_BASELINE_KEEP",
+        display_order = 200
+    )]
+    pub baseline_keep: Option<BaselineRetention>,
+
+    #[rustfmt::skip]
+    /// The template of the baseline name of `--save-baseline=auto`
+    ///
+    /// The placeholders `{branch}` (the branch name), `{pr}` (the pull request or merge request
+    /// number) and `{commit}` (the short commit hash) are replaced with the values detected from
+    /// the environment variables of the CI (GitHub Actions, GitLab CI, Bitbucket Pipelines,
+    /// Buildkite, CircleCI, Travis CI and Jenkins) or from the git repository. All characters not
+    /// allowed in a baseline name are replaced with `_`.
+    ///
+    /// Examples:
+    ///   * --baseline-template='{branch}'
+    ///   * --baseline-template='pr_{pr}'
+    ///   * --baseline-template='{branch}_{commit}'
+    #[arg(
+        long = "baseline-template",
+        default_value = "{branch}",
+        env = "IAI_CALLGRIND_BASELINE_TEMPLATE",
+        display_order = 200
+    )]
+    pub baseline_template: String,
+
     #[rustfmt::skip]
     /// The command-line arguments to pass through to the experimental BBV
     ///
@@ -246,6 +370,7 @@ pub struct CommandLineArgs {
     ///               | ("branchsim" | "bs")
     ///               )
     /// event ::= CachegrindMetric
+    ///         | "instructions" | "cycles" | "l1" | "ll" | "ram" | "mem"
     ///
     /// Examples:
     /// * --cachegrind-limits='ir=0.0%'
@@ -336,6 +461,10 @@ pub struct CommandLineArgs {
     ///               | ("writebackbehaviour" | "writeback" | "wb")
     ///               )
     /// event ::= EventKind
+    ///         | "instructions" | "cycles" | "l1" | "ll" | "ram" | "mem"
+    ///
+    /// The event kinds are case-insensitive. The aliases stand for `Ir`, `EstimatedCycles`,
+    /// `L1hits`, `LLhits`, `RamHits` and `TotalRW`.
     ///
     /// See the guide (https://iai-callgrind.github.io/iai-callgrind/latest/html/regressions.html)
     /// for more details, the docs of `CallgrindMetrics`
@@ -352,6 +481,7 @@ pub struct CommandLineArgs {
     /// * --callgrind-limits='ir=5.0%'
     /// * --callgrind-limits='ir=10000,EstimatedCycles=10%'
     /// * --callgrind-limits='@all=10%,ir=5%|10000'
+    /// * --callgrind-limits='cycles=5%,mem=10%'
     #[arg(
         long = "callgrind-limits",
         num_args = 1,
@@ -397,6 +527,69 @@ pub struct CommandLineArgs {
     )]
     pub callgrind_metrics: Option<IndexSet<EventKind>>,
 
+    #[rustfmt::skip]
+    /// Hide the output of benchmarks with unchanged metrics (Default: false)
+    ///
+    /// The terminal output of benchmarks whose metrics are identical to the metrics of the old or
+    /// baseline run is not shown. Instead, the number of these benchmarks is shown in a single
+    /// line after all benchmarks have run. Benchmarks without an old run to compare with are
+    /// always shown. This makes it easy to review runs in which only a handful of benchmarks
+    /// changed.
+    #[arg(
+        long = "changed-only",
+        default_missing_value = "true",
+        num_args = 0..=1,
+        require_equals = true,
+        value_parser = BoolishValueParser::new(),
+        env = "IAI_CALLGRIND_CHANGED_ONLY",
+        display_order = 300
+    )]
+    pub changed_only: Option<bool>,
+
+    #[rustfmt::skip]
+    /// Run the library benchmarks in a mode compatible with CodSpeed
+    ///
+    /// In this mode, the benchmarks are not run under valgrind by Iai-Callgrind. Instead, the
+    /// benchmark functions are executed directly and surrounded with the instrumentation markers
+    /// expected by the valgrind instance of the CodSpeed runner, which also collects the results.
+    /// Binary benchmarks are skipped. The `codspeed` feature of iai-callgrind is required.
+    ///
+    /// If not given, the CodSpeed mode is enabled if the `CODSPEED_ENV` environment variable is
+    /// present (as is the case within the CodSpeed runner).
+    #[arg(
+        long = "codspeed",
+        default_missing_value = "true",
+        num_args = 0..=1,
+        require_equals = true,
+        value_parser = BoolishValueParser::new(),
+        env = "IAI_CALLGRIND_CODSPEED",
+        display_order = 100
+    )]
+    pub codspeed: Option<bool>,
+
+    #[rustfmt::skip]
+    /// The thresholds in percent at which the differences change the color (Default: 0,0)
+    ///
+    /// The format is `NEUTRAL,WARNING`. Differences up to the NEUTRAL threshold (inclusive) are
+    /// not colored. Regressions above the NEUTRAL threshold up to the WARNING threshold
+    /// (inclusive) are yellow and regressions above the WARNING threshold are red. Improvements
+    /// above the NEUTRAL threshold are green. Negative values are converted to their absolute
+    /// value and a WARNING threshold below the NEUTRAL threshold is raised to the NEUTRAL
+    /// threshold.
+    ///
+    /// Examples:
+    ///   * --color-thresholds=1,5 (no color up to 1%, yellow up to 5%, red above 5%)
+    ///   * --color-thresholds=0,10 (yellow up to 10%, red above 10%)
+    #[arg(
+        long = "color-thresholds",
+        num_args = 1,
+        verbatim_doc_comment,
+        value_parser = parse_color_thresholds,
+        env = "IAI_CALLGRIND_COLOR_THRESHOLDS",
+        display_order = 300
+    )]
+    pub color_thresholds: Option<ColorThresholds>,
+
     #[rustfmt::skip]
     /// The default tool used to run the benchmarks
     ///
@@ -426,6 +619,27 @@ pub struct CommandLineArgs {
     )]
     pub default_tool: Option<ValgrindTool>,
 
+    #[rustfmt::skip]
+    /// The style of the differences between the new and the old metrics (Default: both)
+    ///
+    /// Possible values are:
+    ///   * percent: Show only the difference in percent like `(-0.06475%)`
+    ///   * factor: Show only the factor like `[-1.00065x]`
+    ///   * both: Show the difference in percent and the factor
+    ///
+    /// This option applies to the metrics of a benchmark, the comparisons with other benchmarks
+    /// and the soft regressions in the summary after all benchmarks. If not given, the summary
+    /// shows only the difference in percent.
+    #[arg(
+        long = "delta-style",
+        num_args = 1,
+        verbatim_doc_comment,
+        value_parser = parse_delta_style,
+        env = "IAI_CALLGRIND_DELTA_STYLE",
+        display_order = 300
+    )]
+    pub delta_style: Option<DeltaStyle>,
+
     #[rustfmt::skip]
     /// The command-line arguments to pass through to DHAT
     ///
@@ -444,6 +658,27 @@ pub struct CommandLineArgs {
     )]
     pub dhat_args: Option<RawArgs>,
 
+    #[rustfmt::skip]
+    /// Write a summary of the heap for each DHAT benchmark
+    ///
+    /// The heap summary is an svg file next to the DHAT output files (`dhat.*.heap-summary.svg`).
+    /// It shows the total bytes allocated and the heap size at the time of the global maximum
+    /// (t-gmax) and at the end of the program (t-end) as bars and marks t-gmax on the time axis.
+    /// DHAT doesn't record the heap size in between, so this is not a timeline of the heap usage.
+    /// A heap summary is only written if DHAT runs in heap mode.
+    #[arg(
+        long = "dhat-heap-summary",
+        default_missing_value = "true",
+        default_value = "false",
+        num_args = 0..=1,
+        require_equals = true,
+        value_parser = BoolishValueParser::new(),
+        action = ArgAction::Set,
+        env = "IAI_CALLGRIND_DHAT_HEAP_SUMMARY",
+        display_order = 300
+    )]
+    pub dhat_heap_summary: bool,
+
     #[rustfmt::skip]
     #[allow(clippy::doc_markdown)]
     /// Set performance regression limits for specific dhat metrics
@@ -564,6 +799,28 @@ pub struct CommandLineArgs {
     )]
     pub drd_metrics: Option<IndexSet<ErrorMetric>>,
 
+    #[rustfmt::skip]
+    /// Map the outcome classes of the benchmark run to exit codes
+    ///
+    /// This is a `,` separated list of CLASS=CODE pairs. The classes are `errored` (a benchmark
+    /// failed with an error), `regressed` (one or more benchmarks regressed) and `skipped`
+    /// (benchmarks weren't run because the benchmark run was aborted by an error or a regression
+    /// with --regression-fail-fast). Classes which are not specified keep their default exit code.
+    /// If there are benchmarks of multiple classes, the first class with a non-zero exit code in
+    /// the order `errored`, `regressed`, `skipped` determines the exit code.
+    ///
+    /// Examples:
+    ///   * --exit-codes='regressed=0' to not fail on regressions
+    ///   * --exit-codes='errored=10,regressed=11,skipped=12'
+    #[arg(
+        long = "exit-codes",
+        default_value = "errored=1,regressed=3,skipped=0",
+        value_parser = parse_exit_codes,
+        env = "IAI_CALLGRIND_EXIT_CODES",
+        display_order = 600
+    )]
+    pub exit_codes: ExitCodes,
+
     #[rustfmt::skip]
     /// If specified, only run benches containing this string in their names
     ///
@@ -571,6 +828,28 @@ pub struct CommandLineArgs {
     #[arg(name = "BENCHNAME", num_args = 0..=1, env = "IAI_CALLGRIND_FILTER")]
     pub filter: Option<BenchmarkFilter>,
 
+    #[rustfmt::skip]
+    /// The regression budget in percent of the totals of a library benchmark group
+    ///
+    /// After all benchmarks of a `library_benchmark_group!` have run, the primary metrics of the
+    /// benchmarks (Instructions for callgrind and cachegrind, Total bytes for DHAT) are summed up
+    /// and compared with the summed up metrics of the old or baseline run. If the difference
+    /// exceeds the budget, the group is reported as regressed like a benchmark exceeding a soft
+    /// limit. This complements the limits of the single benchmarks and overrides the
+    /// `regression_budget` of all groups. A trailing `%` is optional.
+    ///
+    /// Examples:
+    ///   * --group-regression-budget=3
+    ///   * --group-regression-budget='2.5%'
+    #[arg(
+        long = "group-regression-budget",
+        num_args = 1,
+        value_parser = parse_group_regression_budget,
+        env = "IAI_CALLGRIND_GROUP_REGRESSION_BUDGET",
+        display_order = 600
+    )]
+    pub group_regression_budget: Option<f64>,
+
     #[rustfmt::skip]
     /// The command-line arguments to pass through to Helgrind
     ///
@@ -629,6 +908,29 @@ pub struct CommandLineArgs {
     )]
     pub home: Option<PathBuf>,
 
+    #[rustfmt::skip]
+    /// Keep the sandbox directory of a benchmark for debugging
+    ///
+    /// Possible values are one of [never, on-failure, always].
+    ///
+    /// Per default, the temporary sandbox directory is deleted after the benchmark has run. If no
+    /// value is given, the default missing value is `on-failure` and the sandbox directory of a
+    /// benchmark is kept if the `setup`, the command or the `teardown` failed. With `always`, the
+    /// sandbox directories of all benchmarks are kept. The path of a kept sandbox directory is
+    /// printed to stderr.
+    #[arg(
+        long = "keep-sandbox",
+        required = false,
+        default_missing_value = "on-failure",
+        default_value = "never",
+        num_args = 0..=1,
+        require_equals = true,
+        value_parser = parse_keep_sandbox,
+        env = "IAI_CALLGRIND_KEEP_SANDBOX",
+        display_order = 100
+    )]
+    pub keep_sandbox: KeepSandbox,
+
     #[rustfmt::skip]
     /// Print a list of all benchmarks. With this argument no benchmarks are executed.
     ///
@@ -636,6 +938,11 @@ pub struct CommandLineArgs {
     /// However, future changes of the output format by cargo might not be incorporated into
     /// iai-callgrind. As a consequence, it is not considered safe to rely on the output in
     /// scripts.
+    ///
+    /// Use a json `--output-format` (`json` or `pretty-json`) for a machine-readable list instead.
+    /// Each benchmark file prints a single json document with the benchmark groups, the benchmarks
+    /// with their ids and tags, the resolved tool configurations and the output paths each
+    /// benchmark would write to.
     #[arg(
         long = "list",
         default_missing_value = "true",
@@ -681,6 +988,26 @@ pub struct CommandLineArgs {
     )]
     pub massif_args: Option<RawArgs>,
 
+    #[rustfmt::skip]
+    /// Write a chart of the memory over time and the peak allocations for each Massif benchmark
+    ///
+    /// Similar to `ms_print`, the snapshots of the Massif output files are rendered as svg chart
+    /// (`massif.*.chart.svg`) with the detailed snapshots and the peak snapshot highlighted. The
+    /// heap tree of the peak snapshot with the top allocations is written as text file
+    /// (`massif.*.peak.txt`). Both files are written next to the Massif output files.
+    #[arg(
+        long = "massif-chart",
+        default_missing_value = "true",
+        default_value = "false",
+        num_args = 0..=1,
+        require_equals = true,
+        value_parser = BoolishValueParser::new(),
+        action = ArgAction::Set,
+        env = "IAI_CALLGRIND_MASSIF_CHART",
+        display_order = 300
+    )]
+    pub massif_chart: bool,
+
     #[rustfmt::skip]
     /// The command-line arguments to pass through to Memcheck
     ///
@@ -733,6 +1060,29 @@ pub struct CommandLineArgs {
     )]
     pub memcheck_metrics: Option<IndexSet<ErrorMetric>>,
 
+    #[rustfmt::skip]
+    /// Attach custom metadata to the benchmark run
+    ///
+    /// The metadata is a `KEY=VALUE` pair which is stored in the `metadata` of the json summaries,
+    /// so downstream dashboards can slice the results by arbitrary dimensions like feature flags or
+    /// the version of a dataset. This option can be specified multiple times. The metadata of the
+    /// command-line takes precedence over the metadata with the same key of the `main!` macro
+    /// (`main!(metadata = [("KEY", "VALUE")]; ...)`).
+    ///
+    /// Examples:
+    ///   * --metadata=job=1234
+    ///   * --metadata=dataset=v2 --metadata=features=simd
+    #[arg(
+        long = "metadata",
+        value_parser = parse_metadata,
+        num_args = 1,
+        action = ArgAction::Append,
+        verbatim_doc_comment,
+        env = "IAI_CALLGRIND_METADATA",
+        display_order = 100
+    )]
+    pub metadata: Vec<(String, String)>,
+
     #[rustfmt::skip]
     /// Don't capture terminal output of benchmarks
     ///
@@ -781,6 +1131,87 @@ pub struct CommandLineArgs {
     )]
     pub nosummary: bool,
 
+    #[rustfmt::skip]
+    /// Run a command when the benchmark run has finished
+    ///
+    /// The command is run with `sh -c` after the summary of the benchmark run was printed, even if
+    /// the run was aborted by an error. The placeholder `{summary}` in the command is replaced with
+    /// the shell-quoted path to the `summaries.jsonl` file of the benchmark run, which contains the
+    /// json summary of each finished benchmark in a separate line (see also `--save-summary`). The
+    /// output of the command goes to stderr. A failing command results in a warning but doesn't
+    /// change the exit code.
+    ///
+    /// Examples:
+    ///   * --on-finish='./notify.sh {summary}'
+    ///   * --on-finish='curl -X POST --data-binary @{summary} "$HOOK"'
+    #[arg(
+        long = "on-finish",
+        num_args = 1,
+        verbatim_doc_comment,
+        env = "IAI_CALLGRIND_ON_FINISH",
+        display_order = 300
+    )]
+    pub on_finish: Option<String>,
+
+    #[rustfmt::skip]
+    /// Run a command when any benchmark or benchmark group has regressed
+    ///
+    /// The command is run like the command of `--on-finish` and before it, but only if any
+    /// benchmark or the regression budget of any benchmark group has regressed. Expected
+    /// regressions don't count.
+    ///
+    /// Examples:
+    ///   * --on-regression='./notify-slack.sh {summary}'
+    #[arg(
+        long = "on-regression",
+        num_args = 1,
+        verbatim_doc_comment,
+        env = "IAI_CALLGRIND_ON_REGRESSION",
+        display_order = 300
+    )]
+    pub on_regression: Option<String>,
+
+    #[rustfmt::skip]
+    /// A comma separated list of the only tools which are run
+    ///
+    /// All other tools resolved from the benchmark configurations and --tools, including the
+    /// default tool, are not run. This allows for example a memory-only pass in the CI without
+    /// changing the benchmarks.
+    ///
+    /// Examples
+    ///   * --only-tools dhat
+    ///   * --only-tools callgrind,dhat
+    #[arg(
+        long = "only-tools",
+        num_args = 1..,
+        value_delimiter = ',',
+        verbatim_doc_comment,
+        env = "IAI_CALLGRIND_ONLY_TOOLS",
+        display_order = 50
+    )]
+    pub only_tools: Vec<ValgrindTool>,
+
+    #[rustfmt::skip]
+    /// Open the flamegraph or the html report after the benchmark run
+    ///
+    /// If exactly one benchmark was run, for example because of a `FILTER`, and this benchmark
+    /// created a flamegraph, the flamegraph is opened. Otherwise, if the results are stored with
+    /// `--store`, the html report (see `iai-callgrind-runner --report=site`) is generated next to
+    /// the database and opened once after `cargo bench` has finished all benchmark files. The files
+    /// are opened with the default application of your system (`xdg-open`, `open` on macOS).
+    #[arg(
+        long = "open",
+        default_missing_value = "true",
+        default_value = "false",
+        num_args = 0..=1,
+        require_equals = true,
+        value_parser = BoolishValueParser::new(),
+        action = ArgAction::Set,
+        env = "IAI_CALLGRIND_OPEN",
+        display_order = 300
+    )]
+    pub open: bool,
+
     #[rustfmt::skip]
     /// The terminal output format in default human-readable format or in machine-readable json
     /// format
@@ -807,10 +1238,71 @@ pub struct CommandLineArgs {
     )]
     pub output_format: OutputFormatKind,
 
+    #[rustfmt::skip]
+    /// The maximum number of fractional-part digits of the differences (Default: 5)
+    ///
+    /// The differences in percent and the factors are shown with at most this amount of digits
+    /// after the decimal point. The higher the integer-part of a difference, the lower the number
+    /// of fractional-part digits, so a precision greater than `5` has no effect.
+    ///
+    /// Examples:
+    ///   * --precision=2 (shows differences like `+1.23%`)
+    ///   * --precision=0 (shows differences like `+1%`)
+    #[arg(
+        long = "precision",
+        num_args = 1,
+        verbatim_doc_comment,
+        env = "IAI_CALLGRIND_PRECISION",
+        display_order = 300
+    )]
+    pub precision: Option<usize>,
+
+    #[rustfmt::skip]
+    /// The measurement profile of callgrind
+    ///
+    /// The `fast` profile runs callgrind without the cache simulation (`--cache-sim=no`), doesn't
+    /// create flamegraphs and shows only the instructions (`Ir`). This cuts the runtime of
+    /// callgrind roughly in half for quick local iterations. The `full` profile restores the
+    /// default behavior with the cache simulation and the default callgrind metrics, even if the
+    /// benchmarks are configured with `Callgrind::fast()`.
+    ///
+    /// Note that --callgrind-args and --callgrind-metrics take precedence over the profile.
+    ///
+    /// Any other name selects a named profile. Named profiles are looked up in the `profiles` of
+    /// the `iai-callgrind.json` file in the workspace root first and then in the profiles of the
+    /// `LibraryBenchmarkConfig` or `BinaryBenchmarkConfig` of the `main!` macro.
+    #[arg(
+        long = "profile",
+        required = false,
+        num_args = 1,
+        env = "IAI_CALLGRIND_PROFILE",
+        display_order = 50
+    )]
+    pub profile: Option<MeasurementProfile>,
+
+    #[rustfmt::skip]
+    /// Rebuild stale binaries of the workspace before running the binary benchmarks
+    ///
+    /// The commands which point to a binary of the workspace in the same profile directory as the
+    /// benchmark binary (like `target/release`) are checked. If the binary is older than any file
+    /// in the `src` directory or the `Cargo.toml` of its package, it is rebuilt with `cargo build
+    /// -p PACKAGE --bin NAME` in the profile of the benchmark binary. This prevents benchmarking a
+    /// stale binary, since `cargo bench` builds only the binaries of the benchmarked package.
+    #[arg(
+        long = "rebuild-binaries",
+        default_missing_value = "true",
+        num_args = 0..=1,
+        require_equals = true,
+        value_parser = BoolishValueParser::new(),
+        env = "IAI_CALLGRIND_REBUILD_BINARIES",
+        display_order = 100
+    )]
+    pub rebuild_binaries: Option<bool>,
+
     #[rustfmt::skip]
     /// If true, the first failed performance regression check fails the whole benchmark run
     ///
-    /// Note that if --regression-fail-fast is set to true, no summary is printed.
+    /// Note that if --regression-fail-fast is set to true, all remaining benchmarks are skipped.
     #[arg(
         long = "regression-fail-fast",
         default_missing_value = "true",
@@ -824,6 +1316,9 @@ pub struct CommandLineArgs {
 
     #[rustfmt::skip]
     /// Compare against this baseline if present and then overwrite it
+    ///
+    /// If the value is `auto`, the baseline name is derived from the environment variables of the
+    /// CI or the git repository with the --baseline-template.
     #[arg(
         long = "save-baseline",
         default_missing_value = "default",
@@ -836,8 +1331,8 @@ pub struct CommandLineArgs {
     pub save_baseline: Option<BaselineName>,
 
     #[rustfmt::skip]
-    /// Save a machine-readable summary of each benchmark run in json format next to the usual
-    /// benchmark output
+    /// Save a machine-readable summary of each benchmark run in json or OpenMetrics format next to
+    /// the usual benchmark output
     #[arg(
         long = "save-summary",
         value_enum,
@@ -879,6 +1374,41 @@ pub struct CommandLineArgs {
     )]
     pub separate_targets: bool,
 
+    #[rustfmt::skip]
+    /// Show the absolute difference between the new and the old metrics (Default: false)
+    ///
+    /// The absolute difference like `+12345` is shown in addition to the difference in percent
+    /// and/or the factor of the `--delta-style`. This applies to the metrics of a benchmark and
+    /// the comparisons with other benchmarks.
+    #[arg(
+        long = "show-absolute-diff",
+        default_missing_value = "true",
+        num_args = 0..=1,
+        require_equals = true,
+        value_parser = BoolishValueParser::new(),
+        env = "IAI_CALLGRIND_SHOW_ABSOLUTE_DIFF",
+        display_order = 300
+    )]
+    pub show_absolute_diff: Option<bool>,
+
+    #[rustfmt::skip]
+    /// Show the call tree of the functions with the inclusive instructions (Default: false)
+    ///
+    /// Similar to `callgrind_annotate --tree=both`, the called functions are shown indented below
+    /// the calling function with the inclusive instructions and the amount of calls. Only
+    /// functions with at least 1% of the total instructions are shown. The complete call tree is
+    /// always written next to the callgrind output files (`callgrind.*.calltree.txt`).
+    #[arg(
+        long = "show-call-tree",
+        default_missing_value = "true",
+        num_args = 0..=1,
+        require_equals = true,
+        value_parser = BoolishValueParser::new(),
+        env = "IAI_CALLGRIND_SHOW_CALL_TREE",
+        display_order = 300
+    )]
+    pub show_call_tree: Option<bool>,
+
     #[rustfmt::skip]
     /// Show an ascii grid in the benchmark terminal output
     ///
@@ -896,6 +1426,25 @@ pub struct CommandLineArgs {
     )]
     pub show_grid: Option<bool>,
 
+    #[rustfmt::skip]
+    /// Show the totals of each library benchmark group (Default: false)
+    ///
+    /// After all benchmarks of a `library_benchmark_group!` have run, the primary metrics of the
+    /// benchmarks (Instructions for callgrind and cachegrind, Total bytes for DHAT and Errors for
+    /// the error tools) are summed up and compared with the summed up metrics of the old or
+    /// baseline run. This allows tracking a budget per group without summing up the numbers
+    /// manually.
+    #[arg(
+        long = "show-group-totals",
+        default_missing_value = "true",
+        num_args = 0..=1,
+        require_equals = true,
+        value_parser = BoolishValueParser::new(),
+        env = "IAI_CALLGRIND_SHOW_GROUP_TOTALS",
+        display_order = 300
+    )]
+    pub show_group_totals: Option<bool>,
+
     #[rustfmt::skip]
     /// Show intermediate metrics from parts, subprocesses, threads, ... (Default: false)
     ///
@@ -918,7 +1467,26 @@ pub struct CommandLineArgs {
     pub show_intermediate: Option<bool>,
 
     #[rustfmt::skip]
-    /// Show only the comparison between different benchmarks when using `compare_by_id`
+    /// Show the lines of the entry point with the most instructions (Default: false)
+    ///
+    /// With `--dump-line=yes` (the default), the costs of callgrind are attributed to the source
+    /// lines. Use this option to show the lines of the entry point (the benchmark function by
+    /// default) with the most instructions including the instructions of the calls from these
+    /// lines. This gives a hint which line is responsible for a regression without having to run
+    /// `callgrind_annotate`. The lines are always part of the details in the summary json file.
+    #[arg(
+        long = "show-lines",
+        default_missing_value = "true",
+        num_args = 0..=1,
+        require_equals = true,
+        value_parser = BoolishValueParser::new(),
+        env = "IAI_CALLGRIND_SHOW_LINES",
+        display_order = 300
+    )]
+    pub show_lines: Option<bool>,
+
+    #[rustfmt::skip]
+    /// Show only the comparison between different benchmarks when using `compare_by_id`
     ///
     /// If you're only interested in the comparisons between different benchmarks but not the metric
     /// differences between the self comparisons of the new and old benchmark run, use this option.
@@ -938,6 +1506,103 @@ pub struct CommandLineArgs {
     )]
     pub show_only_comparison: Option<bool>,
 
+    #[rustfmt::skip]
+    /// Show the valgrind version and the resolved arguments of each tool (Default: false)
+    ///
+    /// The arguments are the final arguments after merging the arguments of all configuration
+    /// levels and the command-line with the defaults of Iai-Callgrind. Only the arguments for the
+    /// output and log files are not shown. This makes the effect of layered configurations
+    /// auditable.
+    #[arg(
+        long = "show-tool-args",
+        default_missing_value = "true",
+        num_args = 0..=1,
+        require_equals = true,
+        value_parser = BoolishValueParser::new(),
+        env = "IAI_CALLGRIND_SHOW_TOOL_ARGS",
+        display_order = 300
+    )]
+    pub show_tool_args: Option<bool>,
+
+    #[rustfmt::skip]
+    /// Show this amount of functions with the most instructions (Default: 0)
+    ///
+    /// The functions of the callgrind output files with the most exclusive (self) instructions
+    /// are shown below the metrics of callgrind together with their inclusive instructions. This
+    /// gives a first impression where the instructions of a benchmark are spent without having to
+    /// open the output files in `KCachegrind` or `callgrind_annotate`. A value of `0` disables
+    /// this option.
+    ///
+    /// Examples:
+    ///   * --show-top-functions=5
+    #[arg(
+        long = "show-top-functions",
+        num_args = 1,
+        verbatim_doc_comment,
+        env = "IAI_CALLGRIND_SHOW_TOP_FUNCTIONS",
+        display_order = 300
+    )]
+    pub show_top_functions: Option<usize>,
+
+    #[rustfmt::skip]
+    /// A comma separated list of tools which are not run
+    ///
+    /// The tools are skipped even if they are configured in the benchmarks, with --tools or are
+    /// the default tool.
+    ///
+    /// Examples
+    ///   * --skip-tools massif
+    ///   * --skip-tools memcheck,drd
+    #[arg(
+        long = "skip-tools",
+        num_args = 1..,
+        value_delimiter = ',',
+        verbatim_doc_comment,
+        env = "IAI_CALLGRIND_SKIP_TOOLS",
+        display_order = 50
+    )]
+    pub skip_tools: Vec<ValgrindTool>,
+
+    #[rustfmt::skip]
+    /// The order of the benchmarks in the summary of the regressions (Default: declaration)
+    ///
+    /// Possible values are:
+    ///   * declaration: The order in which the benchmarks were run
+    ///   * delta-desc: The benchmarks with the worst regression first
+    ///
+    /// The benchmarks are printed while they are running, so only the summary of the regressions
+    /// after all benchmarks is sorted. The severity of a soft limit regression is the difference
+    /// to the old metric in percent and of a hard limit regression the difference to the limit in
+    /// percent.
+    #[arg(
+        long = "sort-by",
+        num_args = 1,
+        verbatim_doc_comment,
+        value_parser = parse_sort_by,
+        env = "IAI_CALLGRIND_SORT_BY",
+        display_order = 300
+    )]
+    pub sort_by: Option<Sort>,
+
+    #[rustfmt::skip]
+    /// Append the results of each benchmark run to a result store
+    ///
+    /// Currently, the only store is a SQLite database `results.sqlite` in the iai home directory
+    /// (per default `target/iai/$PACKAGE_NAME`). Each run is stored with the timestamp and the git
+    /// metadata of the project (if any). The store needs the optional `sqlite` feature of the
+    /// iai-callgrind-runner. The stored results can be queried with
+    /// `iai-callgrind-runner --query DATABASE [FILTER]`.
+    #[arg(
+        long = "store",
+        value_enum,
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "sqlite",
+        env = "IAI_CALLGRIND_STORE",
+        display_order = 300
+    )]
+    pub store: Option<StoreKind>,
+
     #[rustfmt::skip]
     /// Show changes only when they are above the `tolerance` level
     ///
@@ -1012,6 +1677,29 @@ pub struct CommandLineArgs {
     )]
     pub truncate_description: Option<TruncateDescription>,
 
+    #[rustfmt::skip]
+    /// Browse the results of the benchmark run in an interactive terminal ui
+    ///
+    /// After all benchmarks of a benchmark file have run, a results browser is opened with a list
+    /// of all benchmarks of this file, the metrics of each tool and the comparison with the
+    /// baseline. The flamegraphs and log files of a benchmark can be opened from within the
+    /// browser. The next benchmark file is run after the browser was closed.
+    ///
+    /// The results browser is only available if the `iai-callgrind-runner` was installed with the
+    /// `tui` feature and stdout is a terminal.
+    #[arg(
+        long = "tui",
+        default_missing_value = "true",
+        default_value = "false",
+        num_args = 0..=1,
+        require_equals = true,
+        value_parser = BoolishValueParser::new(),
+        action = ArgAction::Set,
+        env = "IAI_CALLGRIND_TUI",
+        display_order = 300
+    )]
+    pub tui: bool,
+
     #[rustfmt::skip]
     /// The command-line arguments to pass through to all tools
     ///
@@ -1032,6 +1720,81 @@ pub struct CommandLineArgs {
         display_order = 500
     )]
     pub valgrind_args: Option<RawArgs>,
+
+    #[rustfmt::skip]
+    /// Run each benchmark N times and fail if the metrics of the runs differ (Default: 2)
+    ///
+    /// The benchmarks are only useful if they are deterministic, since only then a difference to
+    /// a previous run can be attributed to a change in the code. With this option, the default
+    /// tool of each (filtered) benchmark is run N times in total and the metrics of each run are
+    /// compared with the first run. If any metric differs, the benchmark fails and the event kinds
+    /// and parts which varied are reported. Typical causes of nondeterminism are branches on the
+    /// current time or the randomized hashing of a `HashMap`. The output files of the additional
+    /// runs are discarded.
+    #[arg(
+        long = "verify-determinism",
+        value_name = "N",
+        default_missing_value = "2",
+        num_args = 0..=1,
+        require_equals = true,
+        value_parser = parse_verify_determinism,
+        env = "IAI_CALLGRIND_VERIFY_DETERMINISM",
+        display_order = 100
+    )]
+    pub verify_determinism: Option<usize>,
+
+    #[rustfmt::skip]
+    /// Run the benchmarks under the gdbserver of valgrind to debug them with gdb
+    ///
+    /// Only the default tool is run. Valgrind waits for gdb to connect after the given number of
+    /// errors (`0` waits before the benchmark starts) and the command to attach gdb is printed to
+    /// the terminal. The output of the benchmarks is not parsed or compared. Use this option
+    /// together with a `FILTER` to debug a single benchmark, for example a crash which only
+    /// reproduces under valgrind.
+    ///
+    /// The MODE is one of `full` (`--vgdb=full`) or `yes` (`--vgdb=yes`), optionally followed by
+    /// `:` and the number of errors (Default: `0`).
+    ///
+    /// Examples:
+    ///   * --vgdb
+    ///   * --vgdb=yes
+    ///   * --vgdb=full:2
+    #[arg(
+        long = "vgdb",
+        value_name = "MODE",
+        default_missing_value = "full",
+        num_args = 0..=1,
+        require_equals = true,
+        value_parser = parse_vgdb,
+        verbatim_doc_comment,
+        env = "IAI_CALLGRIND_VGDB",
+        display_order = 100
+    )]
+    pub vgdb: Option<VgdbMode>,
+
+    #[rustfmt::skip]
+    /// Re-run the benchmarks whenever the benchmark executable changes
+    ///
+    /// After the benchmarks have run, iai-callgrind doesn't exit but waits for changes of the
+    /// benchmark executable. As soon as it has been rebuilt, for example with `cargo bench
+    /// --no-run` or `cargo watch -x 'bench --no-run'` in another terminal, the screen is cleared
+    /// and the benchmarks are run again with the same arguments. Use this option together with a
+    /// `FILTER` to re-run only the benchmarks you're working on. Stop watching with `Ctrl-C`.
+    ///
+    /// `cargo bench` runs the benchmark executables one after another, so this option is meant to
+    /// be used with a single benchmark target (`--bench`).
+    #[arg(
+        long = "watch",
+        default_missing_value = "true",
+        default_value = "false",
+        num_args = 0..=1,
+        require_equals = true,
+        value_parser = BoolishValueParser::new(),
+        action = ArgAction::Set,
+        env = "IAI_CALLGRIND_WATCH",
+        display_order = 100
+    )]
+    pub watch: bool,
 }
 
 impl BenchmarkFilter {
@@ -1050,6 +1813,57 @@ impl FromStr for BenchmarkFilter {
     }
 }
 
+impl CommandLineArgs {
+    /// Return true if the benchmarks should run in the CodSpeed compatible mode
+    ///
+    /// If `--codspeed` is not given, the mode is enabled if the environment variable
+    /// `CODSPEED_ENV` is present.
+    pub fn is_codspeed(&self) -> bool {
+        self.codspeed
+            .unwrap_or_else(|| std::env::var_os(envs::CODSPEED_ENV).is_some())
+    }
+
+    /// Return true if the `tool` is not filtered out by `--only-tools` or `--skip-tools`
+    pub fn is_tool_selected(&self, tool: ValgrindTool) -> bool {
+        (self.only_tools.is_empty() || self.only_tools.contains(&tool))
+            && !self.skip_tools.contains(&tool)
+    }
+}
+
+impl Default for ExitCodes {
+    fn default() -> Self {
+        Self {
+            errored: 1,
+            regressed: 3,
+            skipped: 0,
+        }
+    }
+}
+
+impl KeepSandbox {
+    /// Return true if the sandbox directory should be kept
+    pub fn keeps(self, is_failure: bool) -> bool {
+        match self {
+            Self::Never => false,
+            Self::OnFailure => is_failure,
+            Self::Always => true,
+        }
+    }
+}
+
+impl FromStr for MeasurementProfile {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "fast" => Ok(Self::Fast),
+            "full" => Ok(Self::Full),
+            "" => Err("The name of a profile must not be empty".to_owned()),
+            name => Ok(Self::Named(name.to_owned())),
+        }
+    }
+}
+
 impl NoCapture {
     /// Apply the `NoCapture` option to the [`Command`]
     pub fn apply(self, command: &mut Command) {
@@ -1104,6 +1918,11 @@ fn parse_args(value: &str) -> Result<RawArgs, String> {
         .map(RawArgs::new)
 }
 
+/// Parse --baseline-keep
+fn parse_baseline_keep(value: &str) -> Result<BaselineRetention, String> {
+    value.parse()
+}
+
 /// Same as `parse_callgrind_limits` but for cachegrind
 fn parse_cachegrind_limits(value: &str) -> Result<ToolRegressionConfig, String> {
     let (soft_limits, hard_limits) = parse_limits(value, |key, metric| {
@@ -1170,6 +1989,40 @@ fn parse_callgrind_metrics(value: &str) -> Result<IndexSet<EventKind>, String> {
     })
 }
 
+fn parse_color_thresholds(value: &str) -> Result<ColorThresholds, String> {
+    let parse = |threshold: &str| {
+        threshold
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .filter(|threshold| !threshold.is_nan())
+            .map(f64::abs)
+            .ok_or_else(|| format!("Invalid color threshold '{threshold}': Expected a number"))
+    };
+
+    let (neutral, warning) = value.split_once(',').ok_or_else(|| {
+        format!("Invalid color thresholds '{value}': Expected the format 'NEUTRAL,WARNING'")
+    })?;
+    let neutral = parse(neutral)?;
+    let warning = parse(warning)?;
+
+    Ok(ColorThresholds {
+        neutral,
+        warning: warning.max(neutral),
+    })
+}
+
+fn parse_delta_style(value: &str) -> Result<DeltaStyle, String> {
+    match value.trim().to_lowercase().as_str() {
+        "percent" => Ok(DeltaStyle::Percent),
+        "factor" => Ok(DeltaStyle::Factor),
+        "both" => Ok(DeltaStyle::Both),
+        _ => Err(format!(
+            "Invalid delta style '{value}': Valid styles are 'percent', 'factor' and 'both'"
+        )),
+    }
+}
+
 /// Same as `parse_callgrind_limits` but for dhat
 fn parse_dhat_limits(value: &str) -> Result<ToolRegressionConfig, String> {
     let (soft_limits, hard_limits) = parse_limits(value, |key, metric| {
@@ -1278,11 +2131,84 @@ fn parse_limits<T: Eq + Hash>(
     Ok((soft_limits, hard_limits))
 }
 
+/// Parse --exit-codes
+fn parse_exit_codes(value: &str) -> Result<ExitCodes, String> {
+    let mut exit_codes = ExitCodes::default();
+    for item in value.split(',') {
+        let (class, code) = item
+            .split_once('=')
+            .ok_or_else(|| format!("Invalid format of key=value pair: '{item}'"))?;
+        let code = code
+            .trim()
+            .parse::<u8>()
+            .map_err(|error| format!("Invalid exit code '{}': {error}", code.trim()))?;
+        match class.trim().to_lowercase().as_str() {
+            "errored" => exit_codes.errored = code,
+            "regressed" => exit_codes.regressed = code,
+            "skipped" => exit_codes.skipped = code,
+            class => {
+                return Err(format!(
+                    "Invalid class '{class}': Valid classes are 'errored', 'regressed' and \
+                     'skipped'"
+                ))
+            }
+        }
+    }
+
+    Ok(exit_codes)
+}
+
+/// Parse --group-regression-budget
+fn parse_group_regression_budget(value: &str) -> Result<f64, String> {
+    let trimmed = value.trim();
+    let budget = trimmed
+        .strip_suffix('%')
+        .unwrap_or(trimmed)
+        .trim()
+        .parse::<f64>()
+        .map_err(|error| format!("Invalid regression budget '{trimmed}': {error}"))?;
+    if budget.is_finite() {
+        Ok(budget)
+    } else {
+        Err(format!(
+            "Invalid regression budget '{trimmed}': The budget has to be a finite number"
+        ))
+    }
+}
+
+/// Parse --keep-sandbox
+fn parse_keep_sandbox(value: &str) -> Result<KeepSandbox, String> {
+    match value.trim().to_lowercase().as_str() {
+        "never" => Ok(KeepSandbox::Never),
+        "on-failure" => Ok(KeepSandbox::OnFailure),
+        "always" => Ok(KeepSandbox::Always),
+        _ => Err(format!(
+            "Invalid value '{value}': Valid values are 'never', 'on-failure' and 'always'"
+        )),
+    }
+}
+
 /// Parse the memcheck metrics as error metrics
 fn parse_memcheck_metrics(value: &str) -> Result<IndexSet<ErrorMetric>, String> {
     parse_tool_metrics(value, parse_error_metrics)
 }
 
+/// Parse a `KEY=VALUE` pair of --metadata
+fn parse_metadata(value: &str) -> Result<(String, String), String> {
+    let Some((key, value)) = value.split_once('=') else {
+        return Err(format!(
+            "Invalid metadata '{value}': The metadata has to be a KEY=VALUE pair"
+        ));
+    };
+
+    let key = key.trim();
+    if key.is_empty() {
+        return Err(format!("Invalid metadata '={value}': The key is empty"));
+    }
+
+    Ok((key.to_owned(), value.to_owned()))
+}
+
 /// Parse --nocapture
 fn parse_nocapture(value: &str) -> Result<NoCapture, String> {
     // Taken from clap source code
@@ -1304,6 +2230,16 @@ fn parse_nocapture(value: &str) -> Result<NoCapture, String> {
     }
 }
 
+fn parse_sort_by(value: &str) -> Result<Sort, String> {
+    match value.trim().to_lowercase().as_str() {
+        "declaration" => Ok(Sort::Declaration),
+        "delta-desc" => Ok(Sort::DeltaDesc),
+        _ => Err(format!(
+            "Invalid sort order '{value}': Valid values are 'declaration' and 'delta-desc'"
+        )),
+    }
+}
+
 /// Utility function to parse the --callgrind-metrics, ...
 fn parse_tool_metrics<T: Eq + Hash>(
     value: &str,
@@ -1344,8 +2280,39 @@ fn parse_truncate_description(value: &str) -> Result<TruncateDescription, String
     }
 }
 
+fn parse_verify_determinism(value: &str) -> Result<usize, String> {
+    let runs = value
+        .trim()
+        .parse::<usize>()
+        .map_err(|error| format!("Invalid number of runs '{}': {error}", value.trim()))?;
+    if runs < 2 {
+        return Err(format!(
+            "Invalid number of runs '{runs}': At least 2 runs are required"
+        ));
+    }
+
+    Ok(runs)
+}
+
+fn parse_vgdb(value: &str) -> Result<VgdbMode, String> {
+    let (mode, num_errors) = value.split_once(':').unwrap_or((value, "0"));
+    let num_errors = num_errors
+        .trim()
+        .parse::<u64>()
+        .map_err(|error| format!("Invalid number of errors '{}': {error}", num_errors.trim()))?;
+    match mode.trim().to_lowercase().as_str() {
+        "full" => Ok(VgdbMode::FullAt(num_errors)),
+        "yes" => Ok(VgdbMode::At(num_errors)),
+        mode => Err(format!(
+            "Invalid vgdb mode '{mode}': Valid modes are 'full' and 'yes'"
+        )),
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use std::time::Duration;
+
     use rstest::rstest;
 
     use super::*;
@@ -1504,6 +2471,7 @@ mod tests {
     #[case::default("", SummaryFormat::Json)]
     #[case::json("json", SummaryFormat::Json)]
     #[case::pretty_json("pretty-json", SummaryFormat::PrettyJson)]
+    #[case::openmetrics("openmetrics", SummaryFormat::OpenMetrics)]
     fn test_save_summary_cli(#[case] value: &str, #[case] expected: SummaryFormat) {
         let result = if value.is_empty() {
             CommandLineArgs::parse_from(["--save-summary".to_owned()])
@@ -1534,6 +2502,64 @@ mod tests {
         assert_eq!(result.allow_aslr, Some(expected));
     }
 
+    #[rstest]
+    #[case::default("", true)]
+    #[case::yes("yes", true)]
+    #[case::no("no", false)]
+    fn test_allow_unsupported_cli(#[case] value: &str, #[case] expected: bool) {
+        let result = if value.is_empty() {
+            CommandLineArgs::parse_from(["--allow-unsupported".to_owned()])
+        } else {
+            CommandLineArgs::parse_from([format!("--allow-unsupported={value}")])
+        };
+        assert_eq!(result.allow_unsupported, expected);
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_codspeed_env() {
+        std::env::set_var("IAI_CALLGRIND_CODSPEED", "yes");
+        let result = CommandLineArgs::parse_from::<[_; 0], &str>([]);
+        std::env::remove_var("IAI_CALLGRIND_CODSPEED");
+        assert_eq!(result.codspeed, Some(true));
+        assert!(result.is_codspeed());
+    }
+
+    #[rstest]
+    #[case::default("", true)]
+    #[case::yes("yes", true)]
+    #[case::no("no", false)]
+    fn test_codspeed_cli(#[case] value: &str, #[case] expected: bool) {
+        let result = if value.is_empty() {
+            CommandLineArgs::parse_from(["--codspeed".to_owned()])
+        } else {
+            CommandLineArgs::parse_from([format!("--codspeed={value}")])
+        };
+        assert_eq!(result.codspeed, Some(expected));
+        assert_eq!(result.is_codspeed(), expected);
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_store_env() {
+        std::env::set_var("IAI_CALLGRIND_STORE", "sqlite");
+        let result = CommandLineArgs::parse_from::<[_; 0], &str>([]);
+        std::env::remove_var("IAI_CALLGRIND_STORE");
+        assert_eq!(result.store, Some(StoreKind::Sqlite));
+    }
+
+    #[rstest]
+    #[case::default("")]
+    #[case::sqlite("sqlite")]
+    fn test_store_cli(#[case] value: &str) {
+        let result = if value.is_empty() {
+            CommandLineArgs::parse_from(["--store".to_owned()])
+        } else {
+            CommandLineArgs::parse_from([format!("--store={value}")])
+        };
+        assert_eq!(result.store, Some(StoreKind::Sqlite));
+    }
+
     #[test]
     #[serial_test::serial]
     fn test_separate_targets_env() {
@@ -1557,66 +2583,480 @@ mod tests {
 
     #[test]
     #[serial_test::serial]
-    fn test_home_env() {
-        std::env::set_var("IAI_CALLGRIND_HOME", "/tmp/my_iai_home");
+    fn test_open_env() {
+        std::env::set_var("IAI_CALLGRIND_OPEN", "yes");
         let result = CommandLineArgs::parse_from::<[_; 0], &str>([]);
-        assert_eq!(result.home, Some(PathBuf::from("/tmp/my_iai_home")));
+        assert!(result.open);
     }
 
-    #[test]
-    fn test_home_cli() {
-        let result = CommandLineArgs::parse_from(["--home=/test_me".to_owned()]);
-        assert_eq!(result.home, Some(PathBuf::from("/test_me")));
+    #[rstest]
+    #[case::default("", true)]
+    #[case::yes("yes", true)]
+    #[case::no("no", false)]
+    fn test_open_cli(#[case] value: &str, #[case] expected: bool) {
+        let result = if value.is_empty() {
+            CommandLineArgs::parse_from(["--open".to_owned()])
+        } else {
+            CommandLineArgs::parse_from([format!("--open={value}")])
+        };
+        assert_eq!(result.open, expected);
     }
 
     #[test]
-    fn test_home_cli_when_no_value_then_error() {
-        let result = CommandLineArgs::try_parse_from(["--home=".to_owned()]);
-        result.unwrap_err();
+    #[serial_test::serial]
+    fn test_tui_env() {
+        std::env::set_var("IAI_CALLGRIND_TUI", "yes");
+        let result = CommandLineArgs::parse_from::<[_; 0], &str>([]);
+        assert!(result.tui);
     }
 
     #[rstest]
-    #[case::default("", NoCapture::True)]
-    #[case::yes("true", NoCapture::True)]
-    #[case::no("false", NoCapture::False)]
-    #[case::stdout("stdout", NoCapture::Stdout)]
-    #[case::stderr("stderr", NoCapture::Stderr)]
-    fn test_nocapture_cli(#[case] value: &str, #[case] expected: NoCapture) {
+    #[case::default("", true)]
+    #[case::yes("yes", true)]
+    #[case::no("no", false)]
+    fn test_tui_cli(#[case] value: &str, #[case] expected: bool) {
         let result = if value.is_empty() {
-            CommandLineArgs::parse_from(["--nocapture".to_owned()])
+            CommandLineArgs::parse_from(["--tui".to_owned()])
         } else {
-            CommandLineArgs::parse_from([format!("--nocapture={value}")])
+            CommandLineArgs::parse_from([format!("--tui={value}")])
         };
-        assert_eq!(result.nocapture, expected);
+        assert_eq!(result.tui, expected);
     }
 
     #[test]
     #[serial_test::serial]
-    fn test_nocapture_env() {
-        std::env::set_var("IAI_CALLGRIND_NOCAPTURE", "true");
+    fn test_watch_env() {
+        std::env::set_var("IAI_CALLGRIND_WATCH", "yes");
         let result = CommandLineArgs::parse_from::<[_; 0], &str>([]);
-        assert_eq!(result.nocapture, NoCapture::True);
+        assert!(result.watch);
     }
 
     #[rstest]
-    #[case::single("drd", &[ValgrindTool::DRD])]
-    #[case::two("drd,callgrind", &[ValgrindTool::DRD, ValgrindTool::Callgrind])]
-    fn test_tools_cli(#[case] tools: &str, #[case] expected: &[ValgrindTool]) {
-        let actual = CommandLineArgs::parse_from([format!("--tools={tools}")]);
-        assert_eq!(actual.tools, expected);
+    #[case::default("", true)]
+    #[case::yes("yes", true)]
+    #[case::no("no", false)]
+    fn test_watch_cli(#[case] value: &str, #[case] expected: bool) {
+        let result = if value.is_empty() {
+            CommandLineArgs::parse_from(["--watch".to_owned()])
+        } else {
+            CommandLineArgs::parse_from([format!("--watch={value}")])
+        };
+        assert_eq!(result.watch, expected);
     }
 
     #[rstest]
-    #[case::y("y", true)]
-    #[case::yes("yes", true)]
-    #[case::t("t", true)]
-    #[case::true_value("true", true)]
-    #[case::on("on", true)]
-    #[case::one("1", true)]
-    #[case::n("n", false)]
-    #[case::no("no", false)]
-    #[case::f("f", false)]
-    #[case::false_value("false", false)]
+    #[case::integer("3", 3.0)]
+    #[case::float("2.5", 2.5)]
+    #[case::percent("2.5%", 2.5)]
+    #[case::negative("-1", -1.0)]
+    #[case::with_whitespace(" 4 % ", 4.0)]
+    fn test_group_regression_budget_cli(#[case] value: &str, #[case] expected: f64) {
+        let result = CommandLineArgs::parse_from([format!("--group-regression-budget={value}")]);
+        assert_eq!(result.group_regression_budget, Some(expected));
+    }
+
+    #[rstest]
+    #[case::empty(
+        "",
+        "Invalid regression budget '': cannot parse float from empty string"
+    )]
+    #[case::only_percent(
+        "%",
+        "Invalid regression budget '%': cannot parse float from empty string"
+    )]
+    #[case::not_a_number("foo", "Invalid regression budget 'foo': invalid float literal")]
+    #[case::infinite(
+        "inf",
+        "Invalid regression budget 'inf': The budget has to be a finite number"
+    )]
+    #[case::nan(
+        "NaN",
+        "Invalid regression budget 'NaN': The budget has to be a finite number"
+    )]
+    fn test_group_regression_budget_cli_when_invalid_then_error(
+        #[case] value: &str,
+        #[case] expected: &str,
+    ) {
+        let error = CommandLineArgs::try_parse_from([format!("--group-regression-budget={value}")])
+            .unwrap_err();
+        assert!(
+            error.to_string().contains(expected),
+            "Unexpected error: {error}"
+        );
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_group_regression_budget_env() {
+        std::env::set_var("IAI_CALLGRIND_GROUP_REGRESSION_BUDGET", "3%");
+        let result = CommandLineArgs::parse_from::<[_; 0], &str>([]);
+        assert_eq!(result.group_regression_budget, Some(3.0));
+    }
+
+    #[rstest]
+    #[case::default("", 2)]
+    #[case::three("3", 3)]
+    #[case::with_whitespace(" 10 ", 10)]
+    fn test_verify_determinism_cli(#[case] value: &str, #[case] expected: usize) {
+        let result = if value.is_empty() {
+            CommandLineArgs::parse_from(["--verify-determinism".to_owned()])
+        } else {
+            CommandLineArgs::parse_from([format!("--verify-determinism={value}")])
+        };
+        assert_eq!(result.verify_determinism, Some(expected));
+    }
+
+    #[rstest]
+    #[case::zero("0", "Invalid number of runs '0': At least 2 runs are required")]
+    #[case::one("1", "Invalid number of runs '1': At least 2 runs are required")]
+    #[case::negative("-1", "Invalid number of runs '-1': invalid digit found in string")]
+    #[case::not_a_number("foo", "Invalid number of runs 'foo': invalid digit found in string")]
+    fn test_verify_determinism_cli_when_invalid_then_error(
+        #[case] value: &str,
+        #[case] expected: &str,
+    ) {
+        let error =
+            CommandLineArgs::try_parse_from([format!("--verify-determinism={value}")]).unwrap_err();
+        assert!(
+            error.to_string().contains(expected),
+            "Unexpected error: {error}"
+        );
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_verify_determinism_env() {
+        std::env::set_var("IAI_CALLGRIND_VERIFY_DETERMINISM", "5");
+        let result = CommandLineArgs::parse_from::<[_; 0], &str>([]);
+        assert_eq!(result.verify_determinism, Some(5));
+    }
+
+    #[rstest]
+    #[case::count("10", BaselineRetention::Count(10))]
+    #[case::age("30d", BaselineRetention::Age(Duration::from_secs(30 * 24 * 60 * 60)))]
+    fn test_baseline_keep_cli(#[case] value: &str, #[case] expected: BaselineRetention) {
+        let result = CommandLineArgs::parse_from([format!("--baseline-keep={value}")]);
+        assert_eq!(result.baseline_keep, Some(expected));
+    }
+
+    #[test]
+    fn test_baseline_keep_cli_when_invalid_then_error() {
+        let error = CommandLineArgs::try_parse_from(["--baseline-keep=10y"]).unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("Invalid unit 'y' of the baseline retention '10y'"),
+            "Unexpected error: {error}"
+        );
+    }
+
+    #[rstest]
+    #[case::single(&["--metadata=ci_job=1234"], &[("ci_job", "1234")])]
+    #[case::multiple(
+        &["--metadata=dataset=v2", "--metadata=features=a=b"],
+        &[("dataset", "v2"), ("features", "a=b")]
+    )]
+    #[case::empty_value(&["--metadata=key="], &[("key", "")])]
+    #[case::trim_key(&["--metadata= key =value"], &[("key", "value")])]
+    fn test_metadata_cli(#[case] args: &[&str], #[case] expected: &[(&str, &str)]) {
+        let result = CommandLineArgs::parse_from(args);
+        assert_eq!(
+            result.metadata,
+            expected
+                .iter()
+                .map(|(k, v)| ((*k).to_owned(), (*v).to_owned()))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[rstest]
+    #[case::no_equals(
+        "--metadata=key",
+        "Invalid metadata 'key': The metadata has to be a KEY=VALUE pair"
+    )]
+    #[case::empty_key("--metadata==value", "Invalid metadata '=value': The key is empty")]
+    fn test_metadata_cli_when_invalid_then_error(#[case] value: &str, #[case] expected: &str) {
+        let error = CommandLineArgs::try_parse_from([value]).unwrap_err();
+        assert!(
+            error.to_string().contains(expected),
+            "Unexpected error: {error}"
+        );
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_vgdb_env() {
+        std::env::set_var("IAI_CALLGRIND_VGDB", "yes:3");
+        let result = CommandLineArgs::parse_from::<[_; 0], &str>([]);
+        assert_eq!(result.vgdb, Some(VgdbMode::At(3)));
+    }
+
+    #[rstest]
+    #[case::default("", VgdbMode::FullAt(0))]
+    #[case::full("full", VgdbMode::FullAt(0))]
+    #[case::yes("yes", VgdbMode::At(0))]
+    #[case::full_with_errors("full:2", VgdbMode::FullAt(2))]
+    #[case::yes_with_errors("YES: 10", VgdbMode::At(10))]
+    fn test_vgdb_cli(#[case] value: &str, #[case] expected: VgdbMode) {
+        let result = if value.is_empty() {
+            CommandLineArgs::parse_from(["--vgdb".to_owned()])
+        } else {
+            CommandLineArgs::parse_from([format!("--vgdb={value}")])
+        };
+        assert_eq!(result.vgdb, Some(expected));
+    }
+
+    #[rstest]
+    #[case::invalid_mode("no", "Invalid vgdb mode 'no': Valid modes are 'full' and 'yes'")]
+    #[case::invalid_errors(
+        "full:-1",
+        "Invalid number of errors '-1': invalid digit found in string"
+    )]
+    #[case::missing_errors(
+        "full:",
+        "Invalid number of errors '': cannot parse integer from empty string"
+    )]
+    fn test_vgdb_cli_when_invalid_then_error(#[case] value: &str, #[case] expected: &str) {
+        let error = CommandLineArgs::try_parse_from([format!("--vgdb={value}")]).unwrap_err();
+        assert!(
+            error.to_string().contains(expected),
+            "Unexpected error: {error}"
+        );
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_dhat_heap_summary_env() {
+        std::env::set_var("IAI_CALLGRIND_DHAT_HEAP_SUMMARY", "yes");
+        let result = CommandLineArgs::parse_from::<[_; 0], &str>([]);
+        assert!(result.dhat_heap_summary);
+    }
+
+    #[rstest]
+    #[case::default("", true)]
+    #[case::yes("yes", true)]
+    #[case::no("no", false)]
+    fn test_dhat_heap_summary_cli(#[case] value: &str, #[case] expected: bool) {
+        let result = if value.is_empty() {
+            CommandLineArgs::parse_from(["--dhat-heap-summary".to_owned()])
+        } else {
+            CommandLineArgs::parse_from([format!("--dhat-heap-summary={value}")])
+        };
+        assert_eq!(result.dhat_heap_summary, expected);
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_massif_chart_env() {
+        std::env::set_var("IAI_CALLGRIND_MASSIF_CHART", "yes");
+        let result = CommandLineArgs::parse_from::<[_; 0], &str>([]);
+        assert!(result.massif_chart);
+    }
+
+    #[rstest]
+    #[case::default("", true)]
+    #[case::yes("yes", true)]
+    #[case::no("no", false)]
+    fn test_massif_chart_cli(#[case] value: &str, #[case] expected: bool) {
+        let result = if value.is_empty() {
+            CommandLineArgs::parse_from(["--massif-chart".to_owned()])
+        } else {
+            CommandLineArgs::parse_from([format!("--massif-chart={value}")])
+        };
+        assert_eq!(result.massif_chart, expected);
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_profile_env() {
+        std::env::set_var("IAI_CALLGRIND_PROFILE", "fast");
+        let result = CommandLineArgs::parse_from::<[_; 0], &str>([]);
+        assert_eq!(result.profile, Some(MeasurementProfile::Fast));
+    }
+
+    #[rstest]
+    #[case::fast("fast", MeasurementProfile::Fast)]
+    #[case::full("full", MeasurementProfile::Full)]
+    #[case::named("memory", MeasurementProfile::Named("memory".to_owned()))]
+    fn test_profile_cli(#[case] value: &str, #[case] expected: MeasurementProfile) {
+        let result = CommandLineArgs::parse_from([format!("--profile={value}")]);
+        assert_eq!(result.profile, Some(expected));
+    }
+
+    #[test]
+    fn test_profile_cli_when_empty_then_error() {
+        let error = CommandLineArgs::try_parse_from(["--profile="]).unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("The name of a profile must not be empty"),
+            "Unexpected error: {error}"
+        );
+    }
+
+    #[rstest]
+    #[case::default("errored=1,regressed=3,skipped=0", 1, 3, 0)]
+    #[case::single("regressed=0", 1, 0, 0)]
+    #[case::all("errored=10,regressed=11,skipped=12", 10, 11, 12)]
+    #[case::whitespace(" Skipped = 4 , errored=2", 2, 3, 4)]
+    fn test_parse_exit_codes(
+        #[case] value: &str,
+        #[case] errored: u8,
+        #[case] regressed: u8,
+        #[case] skipped: u8,
+    ) {
+        let expected = ExitCodes {
+            errored,
+            regressed,
+            skipped,
+        };
+        assert_eq!(parse_exit_codes(value), Ok(expected));
+    }
+
+    #[rstest]
+    #[case::empty("")]
+    #[case::no_value("regressed")]
+    #[case::unknown_class("passed=0")]
+    #[case::negative("errored=-1")]
+    #[case::too_large("errored=256")]
+    fn test_parse_exit_codes_then_error(#[case] value: &str) {
+        parse_exit_codes(value).unwrap_err();
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_exit_codes_env() {
+        std::env::set_var("IAI_CALLGRIND_EXIT_CODES", "regressed=0");
+        let result = CommandLineArgs::parse_from::<[_; 0], &str>([]);
+        assert_eq!(
+            result.exit_codes,
+            ExitCodes {
+                regressed: 0,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_baseline_template_env() {
+        std::env::set_var("IAI_CALLGRIND_BASELINE_TEMPLATE", "pr_{pr}");
+        let result = CommandLineArgs::parse_from::<[_; 0], &str>([]);
+        assert_eq!(result.baseline_template, "pr_{pr}");
+    }
+
+    #[rstest]
+    #[case::branch("{branch}")]
+    #[case::branch_and_commit("{branch}_{commit}")]
+    fn test_baseline_template_cli(#[case] value: &str) {
+        let result = CommandLineArgs::parse_from([format!("--baseline-template={value}")]);
+        assert_eq!(result.baseline_template, value);
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_home_env() {
+        std::env::set_var("IAI_CALLGRIND_HOME", "/tmp/my_iai_home");
+        let result = CommandLineArgs::parse_from::<[_; 0], &str>([]);
+        assert_eq!(result.home, Some(PathBuf::from("/tmp/my_iai_home")));
+    }
+
+    #[test]
+    fn test_home_cli() {
+        let result = CommandLineArgs::parse_from(["--home=/test_me".to_owned()]);
+        assert_eq!(result.home, Some(PathBuf::from("/test_me")));
+    }
+
+    #[test]
+    fn test_home_cli_when_no_value_then_error() {
+        let result = CommandLineArgs::try_parse_from(["--home=".to_owned()]);
+        result.unwrap_err();
+    }
+
+    #[rstest]
+    #[case::default("", NoCapture::True)]
+    #[case::yes("true", NoCapture::True)]
+    #[case::no("false", NoCapture::False)]
+    #[case::stdout("stdout", NoCapture::Stdout)]
+    #[case::stderr("stderr", NoCapture::Stderr)]
+    fn test_nocapture_cli(#[case] value: &str, #[case] expected: NoCapture) {
+        let result = if value.is_empty() {
+            CommandLineArgs::parse_from(["--nocapture".to_owned()])
+        } else {
+            CommandLineArgs::parse_from([format!("--nocapture={value}")])
+        };
+        assert_eq!(result.nocapture, expected);
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_nocapture_env() {
+        std::env::set_var("IAI_CALLGRIND_NOCAPTURE", "true");
+        let result = CommandLineArgs::parse_from::<[_; 0], &str>([]);
+        assert_eq!(result.nocapture, NoCapture::True);
+    }
+
+    #[rstest]
+    #[case::default("", KeepSandbox::OnFailure)]
+    #[case::never("never", KeepSandbox::Never)]
+    #[case::on_failure("on-failure", KeepSandbox::OnFailure)]
+    #[case::always("always", KeepSandbox::Always)]
+    fn test_keep_sandbox_cli(#[case] value: &str, #[case] expected: KeepSandbox) {
+        let result = if value.is_empty() {
+            CommandLineArgs::parse_from(["--keep-sandbox".to_owned()])
+        } else {
+            CommandLineArgs::parse_from([format!("--keep-sandbox={value}")])
+        };
+        assert_eq!(result.keep_sandbox, expected);
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_keep_sandbox_env() {
+        std::env::set_var("IAI_CALLGRIND_KEEP_SANDBOX", "always");
+        let result = CommandLineArgs::parse_from::<[_; 0], &str>([]);
+        assert_eq!(result.keep_sandbox, KeepSandbox::Always);
+    }
+
+    #[rstest]
+    #[case::single("drd", &[ValgrindTool::DRD])]
+    #[case::two("drd,callgrind", &[ValgrindTool::DRD, ValgrindTool::Callgrind])]
+    fn test_tools_cli(#[case] tools: &str, #[case] expected: &[ValgrindTool]) {
+        let actual = CommandLineArgs::parse_from([format!("--tools={tools}")]);
+        assert_eq!(actual.tools, expected);
+    }
+
+    #[rstest]
+    #[case::none(&[], ValgrindTool::Massif, true)]
+    #[case::only(&["--only-tools=callgrind,dhat"], ValgrindTool::DHAT, true)]
+    #[case::not_only(&["--only-tools=callgrind,dhat"], ValgrindTool::Massif, false)]
+    #[case::skip(&["--skip-tools=massif"], ValgrindTool::Massif, false)]
+    #[case::not_skip(&["--skip-tools=massif"], ValgrindTool::Callgrind, true)]
+    #[case::only_and_skip(
+        &["--only-tools=callgrind,dhat", "--skip-tools=dhat"],
+        ValgrindTool::DHAT,
+        false
+    )]
+    fn test_is_tool_selected(
+        #[case] args: &[&str],
+        #[case] tool: ValgrindTool,
+        #[case] expected: bool,
+    ) {
+        let actual = CommandLineArgs::parse_from(args);
+        assert_eq!(actual.is_tool_selected(tool), expected);
+    }
+
+    #[rstest]
+    #[case::y("y", true)]
+    #[case::yes("yes", true)]
+    #[case::t("t", true)]
+    #[case::true_value("true", true)]
+    #[case::on("on", true)]
+    #[case::one("1", true)]
+    #[case::n("n", false)]
+    #[case::no("no", false)]
+    #[case::f("f", false)]
+    #[case::false_value("false", false)]
     #[case::off("off", false)]
     #[case::zero("0", false)]
     fn test_boolish(#[case] value: &str, #[case] expected: bool) {
@@ -1902,6 +3342,256 @@ mod tests {
         assert_eq!(result.show_intermediate, Some(true));
     }
 
+    #[rstest]
+    #[case::when_no_equals("--ascii", true)]
+    #[case::when_true("--ascii=true", true)]
+    #[case::when_false("--ascii=false", false)]
+    fn test_arg_ascii(#[case] input: &str, #[case] expected: bool) {
+        let result = CommandLineArgs::try_parse_from([input]).unwrap();
+        assert_eq!(result.ascii, Some(expected));
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_arg_ascii_when_env() {
+        std::env::set_var("IAI_CALLGRIND_ASCII", "yes");
+        let result = CommandLineArgs::parse_from::<[_; 0], &str>([]);
+        assert_eq!(result.ascii, Some(true));
+    }
+
+    #[rstest]
+    #[case::simple("--color-thresholds=1,5", 1.0, 5.0)]
+    #[case::with_whitespace("--color-thresholds= 1.5 , 10 ", 1.5, 10.0)]
+    #[case::negative("--color-thresholds=-1,-5", 1.0, 5.0)]
+    #[case::warning_below_neutral("--color-thresholds=5,1", 5.0, 5.0)]
+    #[case::zero("--color-thresholds=0,0", 0.0, 0.0)]
+    fn test_arg_color_thresholds(#[case] input: &str, #[case] neutral: f64, #[case] warning: f64) {
+        let result = CommandLineArgs::try_parse_from([input]).unwrap();
+        assert_eq!(
+            result.color_thresholds,
+            Some(ColorThresholds { neutral, warning })
+        );
+    }
+
+    #[rstest]
+    #[case::missing_warning("--color-thresholds=1")]
+    #[case::not_a_number("--color-thresholds=1,abc")]
+    #[case::nan("--color-thresholds=NaN,5")]
+    #[case::empty("--color-thresholds=")]
+    fn test_arg_color_thresholds_then_error(#[case] input: &str) {
+        CommandLineArgs::try_parse_from([input]).unwrap_err();
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_arg_color_thresholds_when_env() {
+        std::env::set_var("IAI_CALLGRIND_COLOR_THRESHOLDS", "2,10");
+        let result = CommandLineArgs::parse_from::<[_; 0], &str>([]);
+        assert_eq!(
+            result.color_thresholds,
+            Some(ColorThresholds {
+                neutral: 2.0,
+                warning: 10.0
+            })
+        );
+    }
+
+    #[rstest]
+    #[case::percent("--delta-style=percent", DeltaStyle::Percent)]
+    #[case::factor("--delta-style=factor", DeltaStyle::Factor)]
+    #[case::both("--delta-style=both", DeltaStyle::Both)]
+    #[case::case_insensitive("--delta-style=Factor", DeltaStyle::Factor)]
+    fn test_arg_delta_style(#[case] input: &str, #[case] expected: DeltaStyle) {
+        let result = CommandLineArgs::try_parse_from([input]).unwrap();
+        assert_eq!(result.delta_style, Some(expected));
+    }
+
+    #[test]
+    fn test_arg_delta_style_then_error() {
+        CommandLineArgs::try_parse_from(["--delta-style=pct"]).unwrap_err();
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_arg_delta_style_when_env() {
+        std::env::set_var("IAI_CALLGRIND_DELTA_STYLE", "percent");
+        let result = CommandLineArgs::parse_from::<[_; 0], &str>([]);
+        assert_eq!(result.delta_style, Some(DeltaStyle::Percent));
+    }
+
+    #[rstest]
+    #[case::when_no_equals("--show-lines", true)]
+    #[case::when_true("--show-lines=true", true)]
+    #[case::when_false("--show-lines=false", false)]
+    fn test_arg_show_lines(#[case] input: &str, #[case] expected: bool) {
+        let result = CommandLineArgs::try_parse_from([input]).unwrap();
+        assert_eq!(result.show_lines, Some(expected));
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_arg_show_lines_when_env() {
+        std::env::set_var("IAI_CALLGRIND_SHOW_LINES", "yes");
+        let result = CommandLineArgs::parse_from::<[_; 0], &str>([]);
+        assert_eq!(result.show_lines, Some(true));
+    }
+
+    #[rstest]
+    #[case::zero("--precision=0", 0)]
+    #[case::two("--precision=2", 2)]
+    #[case::greater_than_max("--precision=10", 10)]
+    fn test_arg_precision(#[case] input: &str, #[case] expected: usize) {
+        let result = CommandLineArgs::try_parse_from([input]).unwrap();
+        assert_eq!(result.precision, Some(expected));
+    }
+
+    #[rstest]
+    #[case::negative("--precision=-1")]
+    #[case::float("--precision=1.5")]
+    fn test_arg_precision_then_error(#[case] input: &str) {
+        CommandLineArgs::try_parse_from([input]).unwrap_err();
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_arg_precision_when_env() {
+        std::env::set_var("IAI_CALLGRIND_PRECISION", "3");
+        let result = CommandLineArgs::parse_from::<[_; 0], &str>([]);
+        assert_eq!(result.precision, Some(3));
+    }
+
+    #[test]
+    fn test_arg_on_finish() {
+        let result =
+            CommandLineArgs::try_parse_from(["--on-finish=./notify.sh {summary}"]).unwrap();
+        assert_eq!(result.on_finish.as_deref(), Some("./notify.sh {summary}"));
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_arg_on_regression_when_env() {
+        std::env::set_var("IAI_CALLGRIND_ON_REGRESSION", "./notify.sh {summary}");
+        let result = CommandLineArgs::parse_from::<[_; 0], &str>([]);
+        std::env::remove_var("IAI_CALLGRIND_ON_REGRESSION");
+        assert_eq!(
+            result.on_regression.as_deref(),
+            Some("./notify.sh {summary}")
+        );
+    }
+
+    #[rstest]
+    #[case::when_no_equals("--rebuild-binaries", true)]
+    #[case::when_true("--rebuild-binaries=true", true)]
+    #[case::when_false("--rebuild-binaries=false", false)]
+    fn test_arg_rebuild_binaries(#[case] input: &str, #[case] expected: bool) {
+        let result = CommandLineArgs::try_parse_from([input]).unwrap();
+        assert_eq!(result.rebuild_binaries, Some(expected));
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_arg_rebuild_binaries_when_env() {
+        std::env::set_var("IAI_CALLGRIND_REBUILD_BINARIES", "yes");
+        let result = CommandLineArgs::parse_from::<[_; 0], &str>([]);
+        assert_eq!(result.rebuild_binaries, Some(true));
+    }
+
+    #[rstest]
+    #[case::when_no_equals("--show-tool-args", true)]
+    #[case::when_true("--show-tool-args=true", true)]
+    #[case::when_false("--show-tool-args=false", false)]
+    fn test_arg_show_tool_args(#[case] input: &str, #[case] expected: bool) {
+        let result = CommandLineArgs::try_parse_from([input]).unwrap();
+        assert_eq!(result.show_tool_args, Some(expected));
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_arg_show_tool_args_when_env() {
+        std::env::set_var("IAI_CALLGRIND_SHOW_TOOL_ARGS", "yes");
+        let result = CommandLineArgs::parse_from::<[_; 0], &str>([]);
+        assert_eq!(result.show_tool_args, Some(true));
+    }
+
+    #[rstest]
+    #[case::declaration("--sort-by=declaration", Sort::Declaration)]
+    #[case::delta_desc("--sort-by=delta-desc", Sort::DeltaDesc)]
+    #[case::case_insensitive("--sort-by=Delta-Desc", Sort::DeltaDesc)]
+    fn test_arg_sort_by(#[case] input: &str, #[case] expected: Sort) {
+        let result = CommandLineArgs::try_parse_from([input]).unwrap();
+        assert_eq!(result.sort_by, Some(expected));
+    }
+
+    #[test]
+    fn test_arg_sort_by_then_error() {
+        CommandLineArgs::try_parse_from(["--sort-by=delta"]).unwrap_err();
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_arg_sort_by_when_env() {
+        std::env::set_var("IAI_CALLGRIND_SORT_BY", "delta-desc");
+        let result = CommandLineArgs::parse_from::<[_; 0], &str>([]);
+        assert_eq!(result.sort_by, Some(Sort::DeltaDesc));
+    }
+
+    #[rstest]
+    #[case::when_no_equals("--changed-only", true)]
+    #[case::when_true("--changed-only=true", true)]
+    #[case::when_false("--changed-only=false", false)]
+    fn test_arg_changed_only(#[case] input: &str, #[case] expected: bool) {
+        let result = CommandLineArgs::try_parse_from([input]).unwrap();
+        assert_eq!(result.changed_only, Some(expected));
+    }
+
+    #[rstest]
+    #[case::when_no_equals("--show-group-totals", true)]
+    #[case::when_true("--show-group-totals=true", true)]
+    #[case::when_false("--show-group-totals=false", false)]
+    fn test_arg_show_group_totals(#[case] input: &str, #[case] expected: bool) {
+        let result = CommandLineArgs::try_parse_from([input]).unwrap();
+        assert_eq!(result.show_group_totals, Some(expected));
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_arg_show_group_totals_when_env() {
+        std::env::set_var("IAI_CALLGRIND_SHOW_GROUP_TOTALS", "yes");
+        let result = CommandLineArgs::parse_from::<[_; 0], &str>([]);
+        assert_eq!(result.show_group_totals, Some(true));
+    }
+
+    #[rstest]
+    #[case::when_no_equals("--show-absolute-diff", true)]
+    #[case::when_true("--show-absolute-diff=true", true)]
+    #[case::when_false("--show-absolute-diff=false", false)]
+    fn test_arg_show_absolute_diff(#[case] input: &str, #[case] expected: bool) {
+        let result = CommandLineArgs::try_parse_from([input]).unwrap();
+        assert_eq!(result.show_absolute_diff, Some(expected));
+    }
+
+    #[rstest]
+    #[case::when_no_equals("--show-call-tree", true)]
+    #[case::when_true("--show-call-tree=true", true)]
+    #[case::when_false("--show-call-tree=false", false)]
+    fn test_arg_show_call_tree(#[case] input: &str, #[case] expected: bool) {
+        let result = CommandLineArgs::try_parse_from([input]).unwrap();
+        assert_eq!(result.show_call_tree, Some(expected));
+    }
+
+    #[rstest]
+    #[case::zero("--show-top-functions=0", 0)]
+    #[case::five("--show-top-functions=5", 5)]
+    fn test_arg_show_top_functions(#[case] input: &str, #[case] expected: usize) {
+        let result = CommandLineArgs::try_parse_from([input]).unwrap();
+        assert_eq!(result.show_top_functions, Some(expected));
+    }
+
+    #[test]
+    fn test_arg_show_top_functions_then_error() {
+        CommandLineArgs::try_parse_from(["--show-top-functions=-1"]).unwrap_err();
+    }
+
     #[rstest]
     #[case::when_no_equals("--show-grid", true)]
     #[case::when_true("--show-grid=true", true)]
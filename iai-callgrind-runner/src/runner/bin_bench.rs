@@ -1,38 +1,70 @@
 //! The module responsible for running a binary benchmark
 
 mod defaults {
+    use std::time::Duration;
+
     use crate::api::Stdin;
 
     pub const COMPARE_BY_ID: bool = false;
     pub const ENV_CLEAR: bool = true;
+    pub const INPUT_SCRIPT_TIMEOUT: Duration = Duration::from_secs(10);
     pub const STDIN: Stdin = Stdin::Pipe;
-    pub const WORKSPACE_ROOT_ENV: &str = "_WORKSPACE_ROOT";
 }
 
 use std::collections::HashMap;
-use std::ffi::OsString;
+use std::ffi::{OsStr, OsString};
 use std::io::ErrorKind::WouldBlock;
 use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr, TcpStream, UdpSocket};
 use std::path::{Path, PathBuf};
 use std::sync::mpsc::{self, RecvTimeoutError};
-use std::time::{Duration, Instant};
-use std::{panic, thread};
+use std::time::{Duration, Instant, SystemTime};
+use std::{fs, io, panic, thread};
 
 use anyhow::{anyhow, Context, Result};
-use log::{debug, warn};
+use cargo_metadata::TargetKind;
+use log::{debug, info, warn};
+use which::{which, which_in};
 
 use super::common::{Assistant, AssistantKind, Baselines, BenchmarkSummaries, Config, ModulePath};
-use super::format::{BinaryBenchmarkHeader, OutputFormat};
+use super::envs;
+use super::format::{BinaryBenchmarkHeader, OutputFormat, OutputFormatKind};
+use super::list::{BenchmarkList, ListBenchmark, ListGroup, ListTool};
 use super::meta::Metadata;
-use super::summary::{BaselineKind, BaselineName, BenchmarkKind, BenchmarkSummary, SummaryOutput};
+use super::pipeline::{self, Job, Pending, Pipeline};
+use super::profiles;
+use super::run_log::{self, RunLog};
+use super::summary::{
+    ArtifactKind, ArtifactRegistry, BaselineKind, BaselineName, BenchmarkKind, BenchmarkSummary,
+    SummaryOutput,
+};
 use super::tool::config::ToolConfigs;
 use super::tool::path::{ToolOutputPath, ToolOutputPathKind};
-use super::tool::run::RunOptions;
+use super::tool::run::{RunOptions, Step};
 use crate::api::{
-    self, BinaryBenchmarkConfig, BinaryBenchmarkGroups, DelayKind, EntryPoint, Stdin, ValgrindTool,
+    self, BinaryBenchmarkConfig, BinaryBenchmarkGroups, DelayKind, EntryPoint, ExitWith,
+    PathResolution, Stdin, ValgrindTool,
 };
 use crate::error::Error;
 use crate::runner::format;
+use crate::util::make_absolute;
+
+/// The placeholders in the arguments of a binary benchmark [`Command`]
+///
+/// The placeholders are expanded at execution time:
+///
+/// * `{id}`: The id of the benchmark (`#[bench::id(...)]`)
+/// * `{sandbox}`: The path of the sandbox directory
+/// * `{fixture:NAME}`: The path of the fixture with the file name `NAME`. If the sandbox is
+///   enabled, this is the path of the copied fixture in the sandbox directory.
+///
+/// Everything else including other text in curly braces is passed to the command as is.
+#[derive(Debug, Clone, Default)]
+pub struct ArgsTemplate {
+    /// The absolute paths of the fixtures of the [`api::Sandbox`]
+    fixtures: Vec<PathBuf>,
+    /// The id of the benchmark
+    id: Option<String>,
+}
 
 #[derive(Debug)]
 struct BaselineBenchmark {
@@ -58,6 +90,8 @@ pub struct BinBench {
     pub output_format: OutputFormat,
     /// The [`RunOptions`]
     pub run_options: RunOptions,
+    /// The tags of all configuration levels
+    pub tags: Vec<String>,
     /// The tool configurations for this benchmark run
     pub tools: ToolConfigs,
 }
@@ -126,9 +160,126 @@ struct SaveBaselineBenchmark {
 
 trait Benchmark: std::fmt::Debug {
     fn baselines(&self) -> Baselines;
+    fn is_serial(&self, bin_bench: &BinBench, config: &Config) -> bool;
     fn output_path(&self, bin_bench: &BinBench, config: &Config, group: &Group) -> ToolOutputPath;
-    fn run(&self, bin_bench: &BinBench, config: &Config, group: &Group)
-        -> Result<BenchmarkSummary>;
+    fn run<'a>(
+        &self,
+        bin_bench: &'a BinBench,
+        config: &'a Config,
+        group: &Group,
+        is_serial: bool,
+        registry: &ArtifactRegistry,
+    ) -> Job<'a, BenchmarkSummary>;
+}
+
+/// The pending [`Job`] of a [`BinBench`] in the [`Pipeline`], the `run.log` and the
+/// [`ArtifactRegistry`] of the benchmark
+type PendingBench<'scope, 'a> = (
+    Pending<'scope, BenchmarkSummary>,
+    &'a BinBench,
+    Option<RunLog>,
+    ArtifactRegistry,
+);
+
+impl ArgsTemplate {
+    /// Create a new `ArgsTemplate`
+    pub fn new(id: Option<String>, sandbox: Option<&api::Sandbox>, project_root: &Path) -> Self {
+        Self {
+            fixtures: sandbox.map_or_else(Vec::new, |sandbox| {
+                sandbox
+                    .fixtures
+                    .iter()
+                    .map(|fixture| make_absolute(project_root, fixture))
+                    .collect()
+            }),
+            id,
+        }
+    }
+
+    /// Expand the placeholders in the `args`
+    ///
+    /// The `sandbox_dir` is the path of the sandbox directory if the sandbox is enabled. Arguments
+    /// which are not valid UTF-8 are not expanded.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value of a placeholder is not available
+    pub fn expand(
+        &self,
+        args: &[OsString],
+        sandbox_dir: Option<&Path>,
+    ) -> Result<Vec<OsString>, String> {
+        args.iter()
+            .map(|arg| match arg.to_str() {
+                Some(arg) => self.expand_arg(arg, sandbox_dir).map(OsString::from),
+                None => Ok(arg.clone()),
+            })
+            .collect()
+    }
+
+    fn expand_arg(&self, arg: &str, sandbox_dir: Option<&Path>) -> Result<String, String> {
+        let mut expanded = String::with_capacity(arg.len());
+        let mut rest = arg;
+        while let Some(start) = rest.find('{') {
+            expanded.push_str(&rest[..start]);
+            rest = &rest[start..];
+
+            let Some(end) = rest.find('}') else {
+                break;
+            };
+            let placeholder = &rest[1..end];
+            if placeholder.contains('{') {
+                expanded.push('{');
+                rest = &rest[1..];
+                continue;
+            }
+
+            match self.expand_placeholder(placeholder, sandbox_dir)? {
+                Some(value) => expanded.push_str(&value),
+                None => expanded.push_str(&rest[..=end]),
+            }
+            rest = &rest[end + 1..];
+        }
+        expanded.push_str(rest);
+
+        Ok(expanded)
+    }
+
+    fn expand_placeholder(
+        &self,
+        placeholder: &str,
+        sandbox_dir: Option<&Path>,
+    ) -> Result<Option<String>, String> {
+        let path = match placeholder {
+            "id" => {
+                return self.id.clone().map(Some).ok_or_else(|| {
+                    "The placeholder '{id}' requires a benchmark with an id".to_owned()
+                });
+            }
+            "sandbox" => sandbox_dir.map(Path::to_path_buf).ok_or_else(|| {
+                "The placeholder '{sandbox}' requires an enabled sandbox".to_owned()
+            })?,
+            _ => {
+                let Some(name) = placeholder.strip_prefix("fixture:") else {
+                    return Ok(None);
+                };
+                let fixture = self
+                    .fixtures
+                    .iter()
+                    .find(|fixture| {
+                        fixture
+                            .file_name()
+                            .is_some_and(|file_name| file_name == name)
+                    })
+                    .ok_or_else(|| {
+                        format!("The placeholder '{{{placeholder}}}': No fixture with this name")
+                    })?;
+                sandbox_dir.map_or_else(|| fixture.clone(), |dir| dir.join(name))
+            }
+        };
+
+        Ok(Some(path.display().to_string()))
+    }
 }
 
 impl Benchmark for BaselineBenchmark {
@@ -155,53 +306,73 @@ impl Benchmark for BaselineBenchmark {
         }
     }
 
-    fn run(
+    fn is_serial(&self, bin_bench: &BinBench, config: &Config) -> bool {
+        bin_bench.run_options.is_serial(config.meta.args.nocapture)
+    }
+
+    fn run<'a>(
         &self,
-        bin_bench: &BinBench,
-        config: &Config,
+        bin_bench: &'a BinBench,
+        config: &'a Config,
         group: &Group,
-    ) -> Result<BenchmarkSummary> {
+        is_serial: bool,
+        registry: &ArtifactRegistry,
+    ) -> Job<'a, BenchmarkSummary> {
         let header = BinaryBenchmarkHeader::new(&config.meta, bin_bench);
-        header.print();
+        if is_serial {
+            header.print();
+        }
 
-        let out_path = self.output_path(bin_bench, config, group);
-        out_path.init()?;
+        let job = (|| -> Result<Job<'a, BenchmarkSummary>> {
+            let out_path = self.output_path(bin_bench, config, group);
+            out_path.init()?;
 
-        for path in bin_bench.tools.output_paths(&out_path) {
-            path.shift()?;
-            if path.kind == ToolOutputPathKind::Out {
-                path.to_log_output().shift()?;
-            }
-            if let Some(path) = path.to_xtree_output() {
-                path.shift()?;
-            }
-            if let Some(path) = path.to_xleak_output() {
+            for path in bin_bench.tools.output_paths(&out_path) {
                 path.shift()?;
+                if path.kind == ToolOutputPathKind::Out {
+                    path.to_log_output().shift()?;
+                }
+                if let Some(path) = path.to_xtree_output() {
+                    path.shift()?;
+                }
+                if let Some(path) = path.to_xleak_output() {
+                    path.shift()?;
+                }
             }
-        }
 
-        let benchmark_summary = bin_bench.create_benchmark_summary(
-            config,
-            &out_path,
-            &bin_bench.function_name,
-            header.description(),
-            self.baselines(),
-        )?;
-
-        bin_bench.tools.run(
-            &header.to_title(),
-            benchmark_summary,
-            &self.baselines(),
-            &self.baseline_kind,
-            config,
-            &bin_bench.command.path,
-            &bin_bench.command.args,
-            &bin_bench.run_options,
-            &out_path,
-            false,
-            &bin_bench.module_path,
-            &bin_bench.output_format,
-        )
+            let benchmark_summary = bin_bench.create_benchmark_summary(
+                config,
+                &out_path,
+                &bin_bench.function_name,
+                header.description(),
+                self.baselines(),
+            )?;
+
+            Ok(bin_bench.tools.run(
+                &header.to_title(),
+                benchmark_summary,
+                &self.baselines(),
+                &self.baseline_kind,
+                config,
+                &bin_bench.command.path,
+                &bin_bench.command.args,
+                &bin_bench.run_options,
+                &out_path,
+                false,
+                &bin_bench.module_path,
+                &bin_bench.output_format,
+                is_serial,
+                registry,
+            ))
+        })()
+        .unwrap_or_else(pipeline::failed);
+
+        Box::new(move || {
+            if !is_serial {
+                header.print();
+            }
+            job()
+        })
     }
 }
 
@@ -241,18 +412,29 @@ impl BinBench {
             stdout,
             stderr,
             delay,
+            terminate,
+            mut input_script,
+            steps,
             ..
         } = command;
 
-        let command = Command::new(&module_path, path, args).map_err(|error| {
-            Error::ConfigurationError(module_path.clone(), id.clone(), error.to_string())
-        })?;
+        if let Some(input_script) = &mut input_script {
+            check_input_script(input_script, stdin.as_ref(), stdout.as_ref()).map_err(|error| {
+                Error::ConfigurationError(module_path.clone(), id.clone(), error)
+            })?;
+            input_script
+                .timeout
+                .get_or_insert(defaults::INPUT_SCRIPT_TIMEOUT);
+        }
+
+        let path_resolution = config.path_resolution.clone().unwrap_or_default();
+        let command =
+            Command::new(&module_path, path, args, &path_resolution).map_err(|error| {
+                Error::ConfigurationError(module_path.clone(), id.clone(), error.to_string())
+            })?;
 
         let mut assistant_envs = config.collect_envs();
-        assistant_envs.push((
-            OsString::from(defaults::WORKSPACE_ROOT_ENV),
-            meta.project_root.clone().into(),
-        ));
+        assistant_envs.extend(meta.path_envs(&module_path));
 
         let command_envs = config.resolve_envs();
 
@@ -299,24 +481,57 @@ impl BinBench {
             false,
         ));
 
+        let mut run_options = RunOptions {
+            args_template: Some(ArgsTemplate::new(
+                id.clone(),
+                config.sandbox.as_ref(),
+                &meta.project_root,
+            )),
+            env_clear: config.env_clear.unwrap_or(defaults::ENV_CLEAR),
+            envs: command_envs,
+            stdin: stdin.or(Some(defaults::STDIN)),
+            stdout,
+            stderr,
+            exit_with: config
+                .exit_with
+                .or_else(|| terminate.map(|terminate| ExitWith::Signal(terminate.signal))),
+            input_script,
+            iter_batch: None,
+            current_dir: config.current_dir,
+            setup,
+            teardown,
+            terminate,
+            sandbox: config.sandbox,
+            delay: delay.map(Into::into),
+            vgdb: meta.args.vgdb.or(config.vgdb),
+            steps: vec![],
+        };
+
+        for (index, step) in steps.into_iter().enumerate() {
+            let step = new_step(
+                step,
+                &path_resolution,
+                &module_path,
+                &run_options,
+                config.exit_with,
+            )
+            .map_err(|error| {
+                Error::ConfigurationError(
+                    module_path.clone(),
+                    id.clone(),
+                    format!("Step {}: {error}", index + 2),
+                )
+            })?;
+            run_options.steps.push(step);
+        }
+
         Ok(Self {
             id,
             display,
             function_name,
             tools: tool_configs,
-            run_options: RunOptions {
-                env_clear: config.env_clear.unwrap_or(defaults::ENV_CLEAR),
-                envs: command_envs,
-                stdin: stdin.or(Some(defaults::STDIN)),
-                stdout,
-                stderr,
-                exit_with: config.exit_with,
-                current_dir: config.current_dir,
-                setup,
-                teardown,
-                sandbox: config.sandbox,
-                delay: delay.map(Into::into),
-            },
+            run_options,
+            tags: config.tags,
             module_path,
             command,
             output_format,
@@ -324,6 +539,14 @@ impl BinBench {
         })
     }
 
+    /// Return true if a regression of this benchmark aborts the benchmark run
+    fn is_fail_fast(&self) -> bool {
+        self.tools
+            .0
+            .iter()
+            .any(|c| c.regression_config.is_fail_fast())
+    }
+
     fn name(&self) -> String {
         if let Some(bench_id) = &self.id {
             format!("{}.{}", self.function_name, bench_id)
@@ -348,6 +571,7 @@ impl BinBench {
             None
         };
 
+        let git = config.summary_git(summary_output.as_ref());
         Ok(BenchmarkSummary::new(
             BenchmarkKind::BinaryBenchmark,
             config.meta.project_root.clone(),
@@ -360,16 +584,59 @@ impl BinBench {
             description,
             summary_output,
             baselines,
+            git,
+            Some(
+                config
+                    .meta
+                    .environment
+                    .with_tools(self.tools.enabled_tools()),
+            ),
+            config.metadata.clone(),
         ))
     }
 }
 
 impl Command {
-    fn new(module_path: &ModulePath, path: PathBuf, args: Vec<OsString>) -> Result<Self> {
+    /// Create a new `Command` resolving a bare program name in the `path` as configured
+    ///
+    /// With [`PathResolution::Inherit`], a program which can't be found in the `PATH` is kept as
+    /// is and the error occurs when the benchmark is run.
+    fn new(
+        module_path: &ModulePath,
+        path: PathBuf,
+        args: Vec<OsString>,
+        path_resolution: &PathResolution,
+    ) -> Result<Self> {
         if path.as_os_str().is_empty() {
             return Err(anyhow!("{module_path}: Empty path in command",));
         }
 
+        let is_bare = !path.is_absolute() && path.components().count() == 1;
+        let path = match path_resolution {
+            PathResolution::Absolute if !path.is_absolute() => {
+                return Err(anyhow!(
+                    "The path '{}' of the command is not absolute",
+                    path.display()
+                ));
+            }
+            PathResolution::Inherit if is_bare => which(&path).unwrap_or(path),
+            PathResolution::Dirs(dirs) if is_bare => {
+                let search_path = std::env::join_paths(dirs)?;
+                which_in(&path, Some(search_path), std::env::current_dir()?).map_err(|error| {
+                    anyhow!(
+                        "{error}: '{}' could not be found in any of the directories {}",
+                        path.display(),
+                        dirs.iter()
+                            .map(|dir| format!("'{}'", dir.display()))
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    )
+                })?
+            }
+            PathResolution::Absolute | PathResolution::Inherit | PathResolution::Dirs(_) => path,
+        };
+        debug!("{module_path}: Resolved command path: '{}'", path.display());
+
         Ok(Self { args, path })
     }
 }
@@ -389,7 +656,7 @@ impl Delay {
         if let DelayKind::DurationElapse(_) = self.kind {
             self.exec_delay_fn()
         } else {
-            let (tx, rx) = mpsc::channel::<std::result::Result<(), anyhow::Error>>();
+            let (tx, rx) = mpsc::channel::<Result<(), anyhow::Error>>();
 
             let delay = self.clone();
             let handle = thread::spawn(move || {
@@ -516,39 +783,160 @@ impl From<api::Delay> for Delay {
 }
 
 impl Group {
+    /// Run all [`BinBench`] benchmarks of this group
+    ///
+    /// The benchmarks run in a [`Pipeline`]: While valgrind runs a benchmark, the output files of
+    /// the previous benchmark are parsed and its terminal output is printed. The summary of a
+    /// benchmark is saved after its [`Job`] has finished.
+    ///
+    /// If a benchmark fails with an error or a regression with `fail_fast`, the group is aborted
+    /// (see [`BenchmarkSummaries::abort`]).
     fn run(&self, benchmark: &dyn Benchmark, config: &Config) -> Result<BenchmarkSummaries> {
         let mut benchmark_summaries = BenchmarkSummaries::default();
 
         let mut summaries: HashMap<String, Vec<BenchmarkSummary>> =
             HashMap::with_capacity(self.benches.len());
-        for bench in &self.benches {
-            let fail_fast = bench
-                .tools
-                .0
-                .iter()
-                .any(|c| c.regression_config.is_fail_fast());
-
-            let summary = benchmark.run(bench, config, self)?;
-            summary.print_and_save(&config.meta.args.output_format)?;
-            summary.check_regression(fail_fast)?;
-
-            benchmark_summaries.add_summary(summary.clone());
-            if self.compare_by_id && bench.output_format.is_default() {
-                if let Some(id) = &summary.id {
-                    if let Some(sums) = summaries.get_mut(id) {
-                        for sum in sums.iter() {
-                            sum.compare_and_print(id, &summary, &bench.output_format)?;
+        std::thread::scope(|scope| -> Result<()> {
+            let pipeline = Pipeline::new(scope);
+
+            // Wait for the pending benchmark and handle its summary. Returns true if the group is
+            // aborted.
+            let mut finish = |pending: PendingBench<'_, '_>| -> Result<bool> {
+                let bench = pending.1;
+                let result = Self::finish_bench(pending, config).and_then(|mut summary| {
+                    if bench.output_format.is_hidden(&summary) {
+                        benchmark_summaries.num_unchanged += 1;
+                    }
+                    // Only the totals are needed after the complete summary was streamed
+                    if config.summary_stream.is_some() {
+                        summary.clear_parts();
+                    }
+                    benchmark_summaries.add_summary(summary.clone());
+                    summary
+                        .check_regression(bench.is_fail_fast())
+                        .map(|()| summary)
+                });
+                let summary = match result {
+                    Ok(summary) => summary,
+                    Err(error) => {
+                        benchmark_summaries.abort(error);
+                        return Ok(true);
+                    }
+                };
+
+                if self.compare_by_id && bench.output_format.is_default() {
+                    if let Some(id) = &summary.id {
+                        if let Some(sums) = summaries.get_mut(id) {
+                            // The comparisons of a hidden benchmark are hidden, too
+                            if !bench.output_format.is_hidden(&summary) {
+                                for sum in sums.iter() {
+                                    sum.compare_and_print(id, &summary, &bench.output_format)?;
+                                }
+                            }
+                            sums.push(summary);
+                        } else {
+                            summaries.insert(id.clone(), vec![summary]);
                         }
-                        sums.push(summary);
-                    } else {
-                        summaries.insert(id.clone(), vec![summary]);
                     }
                 }
+
+                Ok(false)
+            };
+
+            let mut pending: Option<PendingBench<'_, '_>> = None;
+            for bench in &self.benches {
+                let is_serial = benchmark.is_serial(bench, config);
+
+                // The terminal output of the pending benchmark has to be printed first. A
+                // regression with `fail_fast` aborts the group before the next benchmark runs.
+                if is_serial
+                    || pending
+                        .as_ref()
+                        .is_some_and(|(_, bench, ..)| bench.is_fail_fast())
+                {
+                    if let Some(pending) = pending.take() {
+                        if finish(pending)? {
+                            return Ok(());
+                        }
+                    }
+                }
+
+                let (job, run_log, registry) = self.run_bench(benchmark, bench, config, is_serial);
+                if let Some(pending) = pending.take() {
+                    if finish(pending)? {
+                        return Ok(());
+                    }
+                }
+                pending = Some((pipeline.submit(job, is_serial), bench, run_log, registry));
             }
-        }
+
+            if let Some(pending) = pending {
+                finish(pending)?;
+            }
+            Ok(())
+        })?;
 
         Ok(benchmark_summaries)
     }
+
+    /// Run valgrind for a single [`BinBench`] benchmark with its `run.log`
+    ///
+    /// The returned [`Job`] continues writing into the `run.log` of this benchmark even if it runs
+    /// on a worker thread. With [`OutputFormat::hide_unchanged`], the job prints the terminal
+    /// output of the benchmark after the benchmark has run if the metrics have changed.
+    fn run_bench<'a>(
+        &self,
+        benchmark: &dyn Benchmark,
+        bench: &'a BinBench,
+        config: &'a Config,
+        is_serial: bool,
+    ) -> (Job<'a, BenchmarkSummary>, Option<RunLog>, ArtifactRegistry) {
+        let run_log = match RunLog::start(&benchmark.output_path(bench, config, self).dir) {
+            Ok(run_log) => run_log,
+            Err(error) => return (pipeline::failed(error), None, ArtifactRegistry::default()),
+        };
+        let registry = ArtifactRegistry::default();
+        registry.register(ArtifactKind::RunLog, [run_log.path()]);
+
+        let job = benchmark.run(bench, config, self, is_serial, &registry);
+        let context = run_log.context();
+        let baselines = benchmark.baselines();
+        let job: Job<'a, BenchmarkSummary> = Box::new(move || {
+            let _context = context.enter();
+            let summary = job()?;
+            if bench.output_format.hide_unchanged && !bench.output_format.is_hidden(&summary) {
+                BinaryBenchmarkHeader::new(&config.meta, bench).print_deferred();
+                bench
+                    .tools
+                    .print_deferred(&summary, &baselines, config, &bench.output_format)?;
+            }
+            Ok(summary)
+        });
+
+        (job, Some(run_log), registry)
+    }
+
+    /// Wait for the `pending` [`Job`] of a [`BinBench`] benchmark and print and save the summary
+    ///
+    /// The summary is saved only after the job has finished, so the summary contains everything
+    /// the job has created and the [`ArtifactRegistry`] is complete.
+    fn finish_bench(
+        (pending, _, run_log, registry): PendingBench<'_, '_>,
+        config: &Config,
+    ) -> Result<BenchmarkSummary> {
+        let mut summary = pending.join()?;
+
+        // The `RunLog` of the next benchmark may already be active and the run.log of this
+        // benchmark has to be complete before its checksum is calculated
+        let _context = run_log::Context::default().enter();
+        summary.run_log = run_log.map(|run_log| run_log.path().to_owned());
+        summary.collect_artifacts(&registry)?;
+        summary.print_and_save(&config.meta.args.output_format)?;
+        if let Some(path) = &config.summary_stream {
+            summary.append_to_stream(path)?;
+        }
+        Ok(summary)
+    }
 }
 
 impl Groups {
@@ -559,6 +947,8 @@ impl Groups {
         meta: &Metadata,
     ) -> Result<Self> {
         let global_config = benchmark_groups.config;
+        let profile_config = profiles::find(&global_config.profiles, meta.args.profile.as_ref())?;
+        let global_config = global_config.clone().update_from_all([profile_config]);
         let default_tool = benchmark_groups.default_tool;
 
         let mut groups = vec![];
@@ -683,13 +1073,19 @@ impl Groups {
         Ok(Self(groups))
     }
 
+    /// Return the total number of [`BinBench`] benchmarks in all groups
+    fn num_benchmarks(&self) -> usize {
+        self.0.iter().map(|group| group.benches.len()).sum()
+    }
+
     /// Run all [`Group`] benchmarks
     ///
-    /// # Errors
+    /// If a benchmark fails with an error or a regression with `fail_fast`, the benchmark run is
+    /// aborted and all remaining benchmarks are skipped.
     ///
-    /// Return an [`anyhow::Error`] with sources:
+    /// # Errors
     ///
-    /// * [`Error::RegressionError`] if a regression occurred.
+    /// Return an [`anyhow::Error`] if the setup or teardown of a group failed
     fn run(&self, benchmark: &dyn Benchmark, config: &Config) -> Result<BenchmarkSummaries> {
         let mut benchmark_summaries = BenchmarkSummaries::default();
         for group in &self.0 {
@@ -698,9 +1094,14 @@ impl Groups {
             }
 
             let summaries = group.run(benchmark, config)?;
+            if summaries.is_aborted() {
+                benchmark_summaries.add_other(summaries);
+                benchmark_summaries.skip_remaining(self.num_benchmarks());
+                return Ok(benchmark_summaries);
+            }
 
             if let Some(teardown) = &group.teardown {
-                teardown.run(config, &group.module_path)?;
+                teardown.run_with_outcomes(config, &group.module_path, &summaries.summaries)?;
             }
 
             benchmark_summaries.add_other(summaries);
@@ -734,34 +1135,45 @@ impl Benchmark for LoadBaselineBenchmark {
         )
     }
 
-    fn run(
+    fn is_serial(&self, _: &BinBench, _: &Config) -> bool {
+        true
+    }
+
+    fn run<'a>(
         &self,
-        bin_bench: &BinBench,
-        config: &Config,
+        bin_bench: &'a BinBench,
+        config: &'a Config,
         group: &Group,
-    ) -> Result<BenchmarkSummary> {
-        let header = BinaryBenchmarkHeader::new(&config.meta, bin_bench);
-        header.print();
-
-        let out_path = self.output_path(bin_bench, config, group);
-        let benchmark_summary = bin_bench.create_benchmark_summary(
-            config,
-            &out_path,
-            &bin_bench.function_name,
-            header.description(),
-            self.baselines(),
-        )?;
-
-        bin_bench.tools.run_loaded_vs_base(
-            &header.to_title(),
-            &self.baseline,
-            &self.loaded_baseline,
-            benchmark_summary,
-            &self.baselines(),
-            config,
-            &out_path,
-            &bin_bench.output_format,
-        )
+        _: bool,
+        registry: &ArtifactRegistry,
+    ) -> Job<'a, BenchmarkSummary> {
+        let result = (|| -> Result<BenchmarkSummary> {
+            let header = BinaryBenchmarkHeader::new(&config.meta, bin_bench);
+            header.print();
+
+            let out_path = self.output_path(bin_bench, config, group);
+            let benchmark_summary = bin_bench.create_benchmark_summary(
+                config,
+                &out_path,
+                &bin_bench.function_name,
+                header.description(),
+                self.baselines(),
+            )?;
+
+            bin_bench.tools.run_loaded_vs_base(
+                &header.to_title(),
+                &self.baseline,
+                &self.loaded_baseline,
+                benchmark_summary,
+                &self.baselines(),
+                config,
+                &out_path,
+                &bin_bench.output_format,
+                registry,
+            )
+        })();
+
+        Box::new(move || result)
     }
 }
 
@@ -821,6 +1233,109 @@ impl Runner {
         })
     }
 
+    /// Create the [`BenchmarkList`] of all benchmarks in all groups
+    fn list(&self) -> BenchmarkList {
+        let mut list = BenchmarkList::new(BenchmarkKind::BinaryBenchmark, &self.config);
+        for group in &self.groups.0 {
+            let mut list_group = ListGroup::new(&group.name, &group.module_path);
+            for bench in &group.benches {
+                let output_path = self.benchmark.output_path(bench, &self.config, group);
+                list_group.benchmarks.push(ListBenchmark {
+                    description: None,
+                    details: bench.display.clone(),
+                    function: bench.function_name.clone(),
+                    id: bench.id.clone(),
+                    module_path: bench.module_path.to_string(),
+                    output_dir: output_path.dir.clone(),
+                    tags: bench.tags.clone(),
+                    tools: ListTool::from_tool_configs(&bench.tools, &output_path),
+                });
+            }
+            list.groups.push(list_group);
+        }
+
+        list
+    }
+
+    /// Rebuild the binaries of the workspace used in the commands if they are stale
+    ///
+    /// Only the commands pointing to a binary in the same profile directory as the benchmark binary
+    /// (like `target/release`) are considered. A binary is stale if it doesn't exist or if it is
+    /// older than any file in the `src` directory or the `Cargo.toml` of its package.
+    fn rebuild_binaries(&self) -> Result<()> {
+        let Some(profile_dir) = self.config.bench_bin.parent().and_then(Path::parent) else {
+            return Ok(());
+        };
+
+        let mut binaries = self
+            .groups
+            .0
+            .iter()
+            .flat_map(|group| &group.benches)
+            .map(|bench| &bench.command.path)
+            .filter(|path| path.parent() == Some(profile_dir))
+            .collect::<Vec<_>>();
+        binaries.sort();
+        binaries.dedup();
+
+        if binaries.is_empty() {
+            return Ok(());
+        }
+
+        let metadata = cargo_metadata::MetadataCommand::new()
+            .current_dir(&self.config.meta.project_root)
+            .no_deps()
+            .exec()?;
+
+        for binary in binaries {
+            let Some(name) = binary
+                .file_name()
+                .and_then(OsStr::to_str)
+                .and_then(|name| name.strip_suffix(std::env::consts::EXE_SUFFIX))
+            else {
+                continue;
+            };
+
+            let Some(package) = metadata.packages.iter().find(|package| {
+                package
+                    .targets
+                    .iter()
+                    .any(|target| target.kind.contains(&TargetKind::Bin) && target.name == name)
+            }) else {
+                debug!(
+                    "Skipping '{}': Not a binary of the workspace",
+                    binary.display()
+                );
+                continue;
+            };
+
+            let manifest_path = package.manifest_path.as_std_path();
+            let sources_modified = [
+                latest_modification(manifest_path)?,
+                latest_modification(&manifest_path.with_file_name("src"))?,
+            ]
+            .into_iter()
+            .max()
+            .flatten();
+
+            let binary_modified = binary.metadata().and_then(|meta| meta.modified()).ok();
+            if binary_modified.is_some() && binary_modified >= sources_modified {
+                debug!("Binary '{}' is up to date", binary.display());
+                continue;
+            }
+
+            info!("Rebuilding stale binary '{}'", binary.display());
+            cargo_build(
+                &self.config.meta.project_root,
+                profile_dir,
+                &package.name,
+                name,
+            )?;
+        }
+
+        Ok(())
+    }
+
     fn run(&self) -> Result<BenchmarkSummaries> {
         if let Some(setup) = &self.setup {
             setup.run(&self.config, &self.config.module_path)?;
@@ -828,8 +1343,12 @@ impl Runner {
 
         let summaries = self.groups.run(self.benchmark.as_ref(), &self.config)?;
 
-        if let Some(teardown) = &self.teardown {
-            teardown.run(&self.config, &self.config.module_path)?;
+        if let Some(teardown) = self.teardown.as_ref().filter(|_| !summaries.is_aborted()) {
+            teardown.run_with_outcomes(
+                &self.config,
+                &self.config.module_path,
+                &summaries.summaries,
+            )?;
         }
 
         Ok(summaries)
@@ -860,45 +1379,151 @@ impl Benchmark for SaveBaselineBenchmark {
         )
     }
 
-    fn run(
+    fn is_serial(&self, bin_bench: &BinBench, config: &Config) -> bool {
+        bin_bench.run_options.is_serial(config.meta.args.nocapture)
+    }
+
+    fn run<'a>(
         &self,
-        bin_bench: &BinBench,
-        config: &Config,
+        bin_bench: &'a BinBench,
+        config: &'a Config,
         group: &Group,
-    ) -> Result<BenchmarkSummary> {
+        is_serial: bool,
+        registry: &ArtifactRegistry,
+    ) -> Job<'a, BenchmarkSummary> {
         let header = BinaryBenchmarkHeader::new(&config.meta, bin_bench);
-        header.print();
+        if is_serial {
+            header.print();
+        }
+
+        let job = (|| -> Result<Job<'a, BenchmarkSummary>> {
+            let out_path = self.output_path(bin_bench, config, group);
+            out_path.init()?;
+
+            let benchmark_summary = bin_bench.create_benchmark_summary(
+                config,
+                &out_path,
+                &bin_bench.function_name,
+                header.description(),
+                self.baselines(),
+            )?;
+
+            Ok(bin_bench.tools.run(
+                &header.to_title(),
+                benchmark_summary,
+                &self.baselines(),
+                &BaselineKind::Name(self.baseline.clone()),
+                config,
+                &bin_bench.command.path,
+                &bin_bench.command.args,
+                &bin_bench.run_options,
+                &out_path,
+                true,
+                &bin_bench.module_path,
+                &bin_bench.output_format,
+                is_serial,
+                registry,
+            ))
+        })()
+        .unwrap_or_else(pipeline::failed);
+
+        Box::new(move || {
+            if !is_serial {
+                header.print();
+            }
+            job()
+        })
+    }
+}
 
-        let out_path = self.output_path(bin_bench, config, group);
-        out_path.init()?;
+/// Build the binary `name` of the `package` with `cargo` in the profile of the `profile_dir`
+fn cargo_build(project_root: &Path, profile_dir: &Path, package: &str, name: &str) -> Result<()> {
+    let profile = match profile_dir.file_name().and_then(OsStr::to_str) {
+        Some("debug") => "dev",
+        Some(profile) => profile,
+        None => {
+            return Err(anyhow!(
+                "Unable to detect the cargo profile from '{}'",
+                profile_dir.display()
+            ))
+        }
+    };
+
+    let cargo = std::env::var_os(envs::CARGO).map_or_else(|| PathBuf::from("cargo"), PathBuf::from);
+    let status = std::process::Command::new(&cargo)
+        .args([
+            "build",
+            "--profile",
+            profile,
+            "--package",
+            package,
+            "--bin",
+            name,
+        ])
+        .current_dir(project_root)
+        .status()
+        .map_err(|error| Error::LaunchError(cargo, error.to_string()))?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(anyhow!("Failed to rebuild the binary '{name}': {status}"))
+    }
+}
 
-        let benchmark_summary = bin_bench.create_benchmark_summary(
-            config,
-            &out_path,
-            &bin_bench.function_name,
-            header.description(),
-            self.baselines(),
-        )?;
-
-        bin_bench.tools.run(
-            &header.to_title(),
-            benchmark_summary,
-            &self.baselines(),
-            &BaselineKind::Name(self.baseline.clone()),
-            config,
-            &bin_bench.command.path,
-            &bin_bench.command.args,
-            &bin_bench.run_options,
-            &out_path,
-            true,
-            &bin_bench.module_path,
-            &bin_bench.output_format,
-        )
+/// Check that the [`api::InputScript`] can drive the configured `stdin` and read the `stdout`
+fn check_input_script(
+    input_script: &api::InputScript,
+    stdin: Option<&Stdin>,
+    stdout: Option<&api::Stdio>,
+) -> Result<(), String> {
+    if stdin.is_some_and(|stdin| *stdin != Stdin::Pipe) {
+        return Err("An input script requires the stdin to be 'Stdin::Pipe'".to_owned());
     }
+
+    if input_script.has_wait_steps()
+        && matches!(stdout, Some(api::Stdio::Null | api::Stdio::File(_)))
+    {
+        return Err(
+            "An input script waiting for output requires the stdout not to be redirected"
+                .to_owned(),
+        );
+    }
+
+    Ok(())
+}
+
+/// Return the latest modification time of the `path` or of any file in the directory `path`
+///
+/// Returns `None` if the `path` does not exist.
+fn latest_modification(path: &Path) -> io::Result<Option<SystemTime>> {
+    let metadata = match fs::metadata(path) {
+        Ok(metadata) => metadata,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(error) => return Err(error),
+    };
+
+    let mut latest = Some(metadata.modified()?);
+    if metadata.is_dir() {
+        for entry in fs::read_dir(path)? {
+            latest = latest.max(latest_modification(&entry?.path())?);
+        }
+    }
+
+    Ok(latest)
 }
 
 /// Print a list of all benchmarks with a short summary
-pub fn list(benchmark_groups: BinaryBenchmarkGroups, config: &Config) -> Result<()> {
+///
+/// With a json `--output-format`, the [`BenchmarkList`] is printed instead of the terminal output.
+pub fn list(benchmark_groups: BinaryBenchmarkGroups, config: Config) -> Result<()> {
+    let output_format = config.meta.args.output_format;
+    if output_format != OutputFormatKind::Default {
+        return Runner::new(benchmark_groups, config)?
+            .list()
+            .print(output_format);
+    }
+
     let groups =
         Groups::from_binary_benchmark(&config.module_path, benchmark_groups, &config.meta)?;
 
@@ -915,9 +1540,70 @@ pub fn list(benchmark_groups: BinaryBenchmarkGroups, config: &Config) -> Result<
     Ok(())
 }
 
+/// Create a [`Step`] of a multi-step benchmark from the `command`
+///
+/// Only the path, the arguments and the stdio of the `command` can be configured. The environment
+/// variables and the current directory are the same as in the `run_options` of the first command
+/// of the benchmark.
+fn new_step(
+    command: api::Command,
+    path_resolution: &PathResolution,
+    module_path: &ModulePath,
+    run_options: &RunOptions,
+    exit_with: Option<ExitWith>,
+) -> Result<Step> {
+    let unsupported = [
+        (
+            command.config != BinaryBenchmarkConfig::default(),
+            "a configuration",
+        ),
+        (command.delay.is_some(), "a delay"),
+        (command.input_script.is_some(), "an input script"),
+        (command.terminate.is_some(), "a termination signal"),
+        (
+            matches!(command.stdin, Some(Stdin::Setup(_))),
+            "the stdin 'Stdin::Setup'",
+        ),
+    ];
+    if let Some((_, what)) = unsupported.iter().find(|(is_set, _)| *is_set) {
+        return Err(anyhow!(
+            "Only the first command of a benchmark can have {what}"
+        ));
+    }
+
+    let executable = Command::new(module_path, command.path, vec![], path_resolution)?.path;
+    Ok(Step {
+        args: command.args,
+        executable,
+        run_options: RunOptions {
+            current_dir: run_options.current_dir.clone(),
+            env_clear: run_options.env_clear,
+            envs: run_options.envs.clone(),
+            exit_with,
+            stderr: command.stderr,
+            stdin: command.stdin.or(Some(defaults::STDIN)),
+            stdout: command.stdout,
+            ..Default::default()
+        },
+    })
+}
+
 /// The top-level method which should be used to initiate running all benchmarks
 pub fn run(benchmark_groups: BinaryBenchmarkGroups, config: Config) -> Result<BenchmarkSummaries> {
+    if config.meta.args.is_codspeed() {
+        warn!(
+            "Binary benchmarks are not supported in the CodSpeed mode. Skipping '{}'...",
+            config.bench_file.display()
+        );
+        let mut summaries = BenchmarkSummaries::default();
+        summaries.elapsed(Instant::now());
+        return Ok(summaries);
+    }
+
     let runner = Runner::new(benchmark_groups, config)?;
+    if runner.config.meta.args.rebuild_binaries.unwrap_or(false) {
+        runner.rebuild_binaries()?;
+    }
 
     let start = Instant::now();
     let mut summaries = runner.run()?;
@@ -1172,4 +1858,266 @@ mod tests {
             "Timeout of '100ms' reached"
         );
     }
+
+    #[test]
+    fn test_latest_modification() {
+        let dir = tempdir().unwrap();
+        let sub_dir = dir.path().join("sub");
+        fs::create_dir_all(&sub_dir).unwrap();
+        let file_path = sub_dir.join("file.rs");
+        File::create(&file_path).unwrap();
+
+        let expected = [dir.path(), &sub_dir, &file_path]
+            .iter()
+            .map(|path| fs::metadata(path).unwrap().modified().unwrap())
+            .max();
+
+        assert_eq!(latest_modification(dir.path()).unwrap(), expected);
+        assert_eq!(
+            latest_modification(&dir.path().join("does_not_exist")).unwrap(),
+            None
+        );
+    }
+
+    #[rstest]
+    #[case::no_placeholder("--some", "--some")]
+    #[case::id("{id}", "bench_id")]
+    #[case::id_embedded("out_{id}.txt", "out_bench_id.txt")]
+    #[case::sandbox("{sandbox}/file", "/sandbox/file")]
+    #[case::fixture("{fixture:fix_1.txt}", "/sandbox/fix_1.txt")]
+    #[case::multiple("{id}={fixture:fixtures}", "bench_id=/sandbox/fixtures")]
+    #[case::unknown("{unknown}", "{unknown}")]
+    #[case::json(r#"{"id": {id}}"#, r#"{"id": bench_id}"#)]
+    #[case::unclosed("{id", "{id")]
+    fn test_args_template_expand(#[case] arg: &str, #[case] expected: &str) {
+        let sandbox = api::Sandbox {
+            enabled: Some(true),
+            fixtures: vec![
+                PathBuf::from("benches/fix_1.txt"),
+                PathBuf::from("/abs/fixtures"),
+            ],
+            follow_symlinks: None,
+        };
+        let template = ArgsTemplate::new(
+            Some("bench_id".to_owned()),
+            Some(&sandbox),
+            Path::new("/root"),
+        );
+
+        assert_eq!(
+            template
+                .expand(&[OsString::from(arg)], Some(Path::new("/sandbox")))
+                .unwrap(),
+            vec![OsString::from(expected)]
+        );
+    }
+
+    #[test]
+    fn test_args_template_expand_fixture_without_sandbox_dir() {
+        let sandbox = api::Sandbox {
+            enabled: Some(false),
+            fixtures: vec![PathBuf::from("benches/fix_1.txt")],
+            follow_symlinks: None,
+        };
+        let template = ArgsTemplate::new(None, Some(&sandbox), Path::new("/root"));
+
+        assert_eq!(
+            template
+                .expand(&[OsString::from("{fixture:fix_1.txt}")], None)
+                .unwrap(),
+            vec![OsString::from("/root/benches/fix_1.txt")]
+        );
+    }
+
+    #[rstest]
+    #[case::id("{id}", "The placeholder '{id}' requires a benchmark with an id")]
+    #[case::sandbox("{sandbox}", "The placeholder '{sandbox}' requires an enabled sandbox")]
+    #[case::fixture(
+        "{fixture:other}",
+        "The placeholder '{fixture:other}': No fixture with this name"
+    )]
+    fn test_args_template_expand_then_error(#[case] arg: &str, #[case] expected: &str) {
+        let template = ArgsTemplate::default();
+        assert_eq!(
+            template.expand(&[OsString::from(arg)], None).unwrap_err(),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case::inherit_not_found(
+        PathResolution::Inherit,
+        "does-not-exist-1234",
+        "does-not-exist-1234"
+    )]
+    #[case::inherit_relative(PathResolution::Inherit, "target/my-foo", "target/my-foo")]
+    #[case::inherit_absolute(PathResolution::Inherit, "/usr/bin/foo", "/usr/bin/foo")]
+    #[case::dirs_relative(PathResolution::Dirs(vec![]), "./my-foo", "./my-foo")]
+    #[case::absolute(PathResolution::Absolute, "/usr/bin/foo", "/usr/bin/foo")]
+    fn test_command_new_path_resolution(
+        #[case] path_resolution: PathResolution,
+        #[case] path: &str,
+        #[case] expected: &str,
+    ) {
+        let command = Command::new(
+            &ModulePath::new("some::path"),
+            PathBuf::from(path),
+            vec![],
+            &path_resolution,
+        )
+        .unwrap();
+        assert_eq!(command.path, PathBuf::from(expected));
+    }
+
+    #[test]
+    fn test_command_new_path_resolution_dirs() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempdir().unwrap();
+        let program = dir.path().join("my-tool");
+        File::create(&program).unwrap();
+        fs::set_permissions(&program, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let command = Command::new(
+            &ModulePath::new("some::path"),
+            PathBuf::from("my-tool"),
+            vec![],
+            &PathResolution::Dirs(vec![dir.path().to_path_buf()]),
+        )
+        .unwrap();
+        assert_eq!(command.path, program);
+
+        Command::new(
+            &ModulePath::new("some::path"),
+            PathBuf::from("other-tool"),
+            vec![],
+            &PathResolution::Dirs(vec![dir.path().to_path_buf()]),
+        )
+        .unwrap_err();
+    }
+
+    #[rstest]
+    #[case::bare("echo")]
+    #[case::relative("target/my-foo")]
+    fn test_command_new_path_resolution_absolute_then_error(#[case] path: &str) {
+        let error = Command::new(
+            &ModulePath::new("some::path"),
+            PathBuf::from(path),
+            vec![],
+            &PathResolution::Absolute,
+        )
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            format!("The path '{path}' of the command is not absolute")
+        );
+    }
+
+    #[rstest]
+    #[case::default(None, None, true)]
+    #[case::pipe(Some(Stdin::Pipe), Some(api::Stdio::Pipe), true)]
+    #[case::stdout_inherit(None, Some(api::Stdio::Inherit), true)]
+    #[case::stdin_null(Some(Stdin::Null), None, false)]
+    #[case::stdin_setup(Some(Stdin::Setup(api::Pipe::Stdout)), None, false)]
+    #[case::stdout_null(None, Some(api::Stdio::Null), false)]
+    #[case::stdout_file(None, Some(api::Stdio::File("some".into())), false)]
+    fn test_check_input_script(
+        #[case] stdin: Option<Stdin>,
+        #[case] stdout: Option<api::Stdio>,
+        #[case] expected: bool,
+    ) {
+        let input_script = api::InputScript {
+            steps: vec![
+                api::InputStep::Write(b"foo\n".to_vec()),
+                api::InputStep::WaitFor("> ".to_owned()),
+            ],
+            timeout: None,
+        };
+        assert_eq!(
+            check_input_script(&input_script, stdin.as_ref(), stdout.as_ref()).is_ok(),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_check_input_script_when_no_wait_steps() {
+        let input_script = api::InputScript {
+            steps: vec![api::InputStep::Write(b"foo\n".to_vec())],
+            timeout: None,
+        };
+        check_input_script(&input_script, None, Some(&api::Stdio::Null)).unwrap();
+    }
+
+    #[test]
+    fn test_new_step() {
+        let run_options = RunOptions {
+            current_dir: Some(PathBuf::from("/some")),
+            env_clear: true,
+            envs: vec![("FOO".into(), "BAR".into())],
+            ..Default::default()
+        };
+        let command = api::Command {
+            path: PathBuf::from("/usr/bin/echo"),
+            args: vec!["foo".into()],
+            stdout: Some(api::Stdio::Null),
+            ..Default::default()
+        };
+
+        let step = new_step(
+            command,
+            &PathResolution::Inherit,
+            &ModulePath::new("some::path"),
+            &run_options,
+            Some(ExitWith::Failure),
+        )
+        .unwrap();
+
+        assert_eq!(step.executable, PathBuf::from("/usr/bin/echo"));
+        assert_eq!(step.args, vec![OsString::from("foo")]);
+        assert_eq!(step.run_options.current_dir, run_options.current_dir);
+        assert!(step.run_options.env_clear);
+        assert_eq!(step.run_options.envs, run_options.envs);
+        assert_eq!(step.run_options.exit_with, Some(ExitWith::Failure));
+        assert_eq!(step.run_options.stdin, Some(Stdin::Pipe));
+        assert_eq!(step.run_options.stdout, Some(api::Stdio::Null));
+    }
+
+    #[rstest]
+    #[case::config(
+        api::Command {
+            config: BinaryBenchmarkConfig {
+                env_clear: Some(false),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        "a configuration"
+    )]
+    #[case::delay(
+        api::Command { delay: Some(api::Delay::default()), ..Default::default() },
+        "a delay"
+    )]
+    #[case::input_script(
+        api::Command { input_script: Some(api::InputScript::default()), ..Default::default() },
+        "an input script"
+    )]
+    #[case::stdin_setup(
+        api::Command { stdin: Some(Stdin::Setup(api::Pipe::Stdout)), ..Default::default() },
+        "the stdin 'Stdin::Setup'"
+    )]
+    fn test_new_step_when_unsupported(#[case] mut command: api::Command, #[case] what: &str) {
+        command.path = PathBuf::from("/usr/bin/echo");
+        let error = new_step(
+            command,
+            &PathResolution::Inherit,
+            &ModulePath::new("some::path"),
+            &RunOptions::default(),
+            None,
+        )
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            format!("Only the first command of a benchmark can have {what}")
+        );
+    }
 }
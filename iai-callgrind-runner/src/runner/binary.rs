@@ -0,0 +1,423 @@
+//! The module containing the [`BinaryInfo`] used to detect misconfigured benchmark builds
+//!
+//! Benchmarks which are run with a build without optimizations or without debug info produce
+//! results which are either meaningless or useless for the attribution of the costs to functions.
+//! Nothing fails in such a case, so we inspect the benchmark executable and print warnings.
+
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+use log::{debug, warn};
+
+/// The magic bytes at the start of every ELF file
+const ELF_MAGIC: [u8; 4] = [0x7f, b'E', b'L', b'F'];
+
+/// The value of `e_shstrndx` if the actual index is stored in `sh_link` of the first section
+const SHN_XINDEX: u16 = 0xffff;
+
+/// The information about the compiled benchmark executable
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BinaryInfo {
+    /// True if the executable contains a `.debug_info` section or references split debug info
+    ///
+    /// This value is `None` if the executable is not an ELF file or could not be inspected.
+    pub has_debug_info: Option<bool>,
+    /// True if the executable was built with the `dev` profile (in the `debug` directory)
+    pub is_debug_profile: bool,
+    /// The path to the executable
+    pub path: PathBuf,
+}
+
+/// The endianness and word size of an ELF file needed to read the section headers
+#[derive(Debug, Clone, Copy)]
+struct ElfClass {
+    is_64: bool,
+    is_big_endian: bool,
+}
+
+impl BinaryInfo {
+    /// Inspect the benchmark executable at `path`
+    ///
+    /// Cargo places the executables built with the `dev` profile in `target/debug/deps` and with
+    /// the `bench` or `release` profile in `target/release/deps`. The debug info is detected by
+    /// reading the section headers of the ELF executable.
+    pub fn new(path: &Path) -> Self {
+        let is_debug_profile = path
+            .parent()
+            .filter(|deps| deps.file_name().is_some_and(|name| name == "deps"))
+            .and_then(Path::parent)
+            .and_then(Path::file_name)
+            .is_some_and(|profile| profile == "debug");
+
+        let has_debug_info = match elf_section_names(path) {
+            Ok(Some(names)) => Some(has_debug_info(path, &names)),
+            Ok(None) => None,
+            Err(error) => {
+                debug!(
+                    "Failed to read the section headers of '{}': {error}",
+                    path.display()
+                );
+                None
+            }
+        };
+
+        Self {
+            has_debug_info,
+            is_debug_profile,
+            path: path.to_owned(),
+        }
+    }
+
+    /// Print warnings if the benchmark executable is not suitable for benchmarking
+    pub fn print_warnings(&self) {
+        if self.is_debug_profile {
+            warn!(
+                "The benchmark '{}' was built without optimizations (debug profile). The results \
+                 don't reflect the performance of an optimized build. Run the benchmarks with \
+                 'cargo bench'.",
+                self.path.display()
+            );
+        }
+
+        if self.has_debug_info == Some(false) {
+            warn!(
+                "The benchmark '{}' was built without debug info. The costs cannot be attributed \
+                 to functions and the entry point and toggles won't match. Add 'debug = true' to \
+                 the [profile.bench] section of your Cargo.toml and make sure the debug symbols \
+                 are not stripped.",
+                self.path.display()
+            );
+        }
+    }
+}
+
+impl ElfClass {
+    fn read_u16(self, bytes: &[u8]) -> u16 {
+        let bytes = [bytes[0], bytes[1]];
+        if self.is_big_endian {
+            u16::from_be_bytes(bytes)
+        } else {
+            u16::from_le_bytes(bytes)
+        }
+    }
+
+    fn read_u32(self, bytes: &[u8]) -> u32 {
+        let bytes = [bytes[0], bytes[1], bytes[2], bytes[3]];
+        if self.is_big_endian {
+            u32::from_be_bytes(bytes)
+        } else {
+            u32::from_le_bytes(bytes)
+        }
+    }
+
+    /// Read an address or offset which is 8 bytes wide in 64-bit and 4 bytes in 32-bit files
+    fn read_word(self, bytes: &[u8]) -> u64 {
+        if self.is_64 {
+            let mut buffer = [0; 8];
+            buffer.copy_from_slice(&bytes[..8]);
+            if self.is_big_endian {
+                u64::from_be_bytes(buffer)
+            } else {
+                u64::from_le_bytes(buffer)
+            }
+        } else {
+            u64::from(self.read_u32(bytes))
+        }
+    }
+}
+
+/// Return the names of all sections of an ELF file or `None` if the file is not an ELF file
+pub fn elf_section_names(path: &Path) -> std::io::Result<Option<Vec<String>>> {
+    let mut file = File::open(path)?;
+    let file_len = file.metadata()?.len();
+
+    let mut header = [0; 64];
+    let read = file.read(&mut header)?;
+    if read < 52 || header[..4] != ELF_MAGIC {
+        return Ok(None);
+    }
+
+    let class = ElfClass {
+        is_64: header[4] == 2,
+        is_big_endian: header[5] == 2,
+    };
+
+    // The offsets of e_shoff, e_shentsize, e_shnum and e_shstrndx in the ELF header
+    let (shoff, shentsize, shnum, shstrndx) = if class.is_64 {
+        (
+            class.read_word(&header[0x28..]),
+            class.read_u16(&header[0x3a..]),
+            class.read_u16(&header[0x3c..]),
+            class.read_u16(&header[0x3e..]),
+        )
+    } else {
+        (
+            class.read_word(&header[0x20..]),
+            class.read_u16(&header[0x2e..]),
+            class.read_u16(&header[0x30..]),
+            class.read_u16(&header[0x32..]),
+        )
+    };
+
+    let min_shentsize = if class.is_64 { 64 } else { 40 };
+    if shoff == 0 || shentsize < min_shentsize {
+        return Ok(Some(vec![]));
+    }
+    let shentsize = usize::from(shentsize);
+
+    // The offsets of sh_offset, sh_size and sh_link in a section header
+    let (offset_index, size_index, link_index) = if class.is_64 {
+        (0x18, 0x20, 0x28)
+    } else {
+        (0x10, 0x14, 0x18)
+    };
+
+    // With extended section numbering, the number of sections is stored in `sh_size` and the index
+    // of the section name string table in `sh_link` of the first section header.
+    let (shnum, shstrndx) = if shnum == 0 || shstrndx == SHN_XINDEX {
+        let mut first = vec![0; shentsize];
+        file.seek(SeekFrom::Start(shoff))?;
+        file.read_exact(&mut first)?;
+
+        let shnum = if shnum == 0 {
+            to_usize(class.read_word(&first[size_index..]))?
+        } else {
+            usize::from(shnum)
+        };
+        let shstrndx = if shstrndx == SHN_XINDEX {
+            to_usize(u64::from(class.read_u32(&first[link_index..])))?
+        } else {
+            usize::from(shstrndx)
+        };
+        (shnum, shstrndx)
+    } else {
+        (usize::from(shnum), usize::from(shstrndx))
+    };
+
+    if shnum == 0 || shstrndx >= shnum {
+        return Ok(Some(vec![]));
+    }
+
+    // Don't trust the number of sections of a corrupt file for the size of the buffer
+    let size = shentsize
+        .checked_mul(shnum)
+        .filter(|size| {
+            u64::try_from(*size)
+                .ok()
+                .and_then(|size| shoff.checked_add(size))
+                .is_some_and(|end| end <= file_len)
+        })
+        .ok_or_else(|| invalid_data("The section headers exceed the end of the file"))?;
+
+    let mut section_headers = vec![0; size];
+    file.seek(SeekFrom::Start(shoff))?;
+    file.read_exact(&mut section_headers)?;
+
+    let sections = section_headers
+        .chunks_exact(shentsize)
+        .map(|section| {
+            (
+                class.read_u32(section),
+                class.read_word(&section[offset_index..]),
+                class.read_word(&section[size_index..]),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let (_, strtab_offset, strtab_size) = sections[shstrndx];
+    if strtab_offset.saturating_add(strtab_size) > file_len {
+        return Err(invalid_data(
+            "The section name string table exceeds the end of the file",
+        ));
+    }
+    let mut strtab = vec![0; to_usize(strtab_size)?];
+    file.seek(SeekFrom::Start(strtab_offset))?;
+    file.read_exact(&mut strtab)?;
+
+    let names = sections
+        .iter()
+        .filter_map(|(name_index, _, _)| {
+            let start = usize::try_from(*name_index).ok()?;
+            let rest = strtab.get(start..)?;
+            let end = rest.iter().position(|byte| *byte == 0)?;
+            Some(String::from_utf8_lossy(&rest[..end]).into_owned())
+        })
+        .collect();
+
+    Ok(Some(names))
+}
+
+/// Return true if the executable at `path` with the section `names` has debug info
+///
+/// The debug info is either embedded in the `.debug_info` section or split into a separate file.
+/// A separate debug info file is referenced by a `.gnu_debuglink` section (for example created
+/// with `objcopy --only-keep-debug`) or is a `.dwp` package next to the executable (`-C
+/// split-debuginfo=packed`). Valgrind reads the debug info from these files, too.
+fn has_debug_info(path: &Path, names: &[String]) -> bool {
+    names
+        .iter()
+        .any(|name| name == ".debug_info" || name == ".gnu_debuglink")
+        || {
+            let mut dwp = path.as_os_str().to_owned();
+            dwp.push(".dwp");
+            Path::new(&dwp).exists()
+        }
+}
+
+fn invalid_data(message: &str) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, message.to_owned())
+}
+
+fn to_usize(value: u64) -> std::io::Result<usize> {
+    usize::try_from(value)
+        .map_err(|error| std::io::Error::new(std::io::ErrorKind::InvalidData, error))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use rstest::rstest;
+    use tempfile::tempdir;
+
+    use super::*;
+
+    /// Write a minimal 64-bit little-endian ELF file with the sections `names`
+    ///
+    /// If `is_extended` is true, the number of sections and the index of the section name string
+    /// table are stored in the first section header like with extended section numbering.
+    fn write_elf(path: &Path, names: &[&str], is_extended: bool) {
+        let mut strtab = vec![0];
+        let mut name_indices = vec![0_u32];
+        for name in names.iter().chain(&[".shstrtab"]) {
+            name_indices.push(u32::try_from(strtab.len()).unwrap());
+            strtab.extend_from_slice(name.as_bytes());
+            strtab.push(0);
+        }
+        let shnum = name_indices.len();
+        let shstrndx = shnum - 1;
+        let shoff = 64 + strtab.len();
+
+        let mut bytes = vec![0; 64];
+        bytes[..4].copy_from_slice(&ELF_MAGIC);
+        bytes[4] = 2;
+        bytes[5] = 1;
+        bytes[0x28..0x30].copy_from_slice(&(shoff as u64).to_le_bytes());
+        bytes[0x3a..0x3c].copy_from_slice(&64_u16.to_le_bytes());
+        if is_extended {
+            bytes[0x3e..0x40].copy_from_slice(&SHN_XINDEX.to_le_bytes());
+        } else {
+            bytes[0x3c..0x3e].copy_from_slice(&u16::try_from(shnum).unwrap().to_le_bytes());
+            bytes[0x3e..0x40].copy_from_slice(&u16::try_from(shstrndx).unwrap().to_le_bytes());
+        }
+
+        let strtab_size = strtab.len() as u64;
+        bytes.extend(strtab);
+        for (index, name_index) in name_indices.into_iter().enumerate() {
+            let mut header = [0; 64];
+            header[..4].copy_from_slice(&name_index.to_le_bytes());
+            if index == shstrndx {
+                header[0x18..0x20].copy_from_slice(&64_u64.to_le_bytes());
+                header[0x20..0x28].copy_from_slice(&strtab_size.to_le_bytes());
+            } else if index == 0 && is_extended {
+                header[0x20..0x28].copy_from_slice(&(shnum as u64).to_le_bytes());
+                header[0x28..0x2c].copy_from_slice(&u32::try_from(shstrndx).unwrap().to_le_bytes());
+            } else {
+                // The other sections have no content
+            }
+            bytes.extend(header);
+        }
+
+        fs::write(path, bytes).unwrap();
+    }
+
+    #[rstest]
+    #[case::debug("target/debug/deps/bench-1234", true)]
+    #[case::release("target/release/deps/bench-1234", false)]
+    #[case::custom("target/x86_64-unknown-linux-gnu/debug/deps/bench-1234", true)]
+    #[case::no_deps("target/debug/bench-1234", false)]
+    fn test_binary_info_is_debug_profile(#[case] path: &str, #[case] expected: bool) {
+        assert_eq!(BinaryInfo::new(Path::new(path)).is_debug_profile, expected);
+    }
+
+    #[rstest]
+    #[case::debug_info(&[".text", ".debug_info"], false, Some(true))]
+    #[case::no_debug_info(&[".text"], false, Some(false))]
+    #[case::extended_debug_info(&[".text", ".debug_info"], true, Some(true))]
+    #[case::extended_no_debug_info(&[".text"], true, Some(false))]
+    #[case::gnu_debuglink(&[".text", ".gnu_debuglink"], false, Some(true))]
+    fn test_binary_info_has_debug_info(
+        #[case] names: &[&str],
+        #[case] is_extended: bool,
+        #[case] expected: Option<bool>,
+    ) {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("bench-1234");
+        write_elf(&path, names, is_extended);
+
+        assert_eq!(BinaryInfo::new(&path).has_debug_info, expected);
+    }
+
+    #[test]
+    fn test_binary_info_has_debug_info_when_split_into_dwp() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("bench-1234");
+        write_elf(&path, &[".text"], false);
+        fs::write(dir.path().join("bench-1234.dwp"), "").unwrap();
+
+        assert_eq!(BinaryInfo::new(&path).has_debug_info, Some(true));
+    }
+
+    #[rstest]
+    #[case::normal(false)]
+    #[case::extended(true)]
+    fn test_elf_section_names(#[case] is_extended: bool) {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("bench-1234");
+        write_elf(&path, &[".text", ".debug_info"], is_extended);
+
+        assert_eq!(
+            elf_section_names(&path).unwrap(),
+            Some(vec![
+                String::new(),
+                ".text".to_owned(),
+                ".debug_info".to_owned(),
+                ".shstrtab".to_owned()
+            ])
+        );
+    }
+
+    #[test]
+    fn test_elf_section_names_when_section_headers_exceed_file() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("bench-1234");
+        write_elf(&path, &[".text"], true);
+
+        let mut bytes = fs::read(&path).unwrap();
+        let shoff = bytes.len() - 3 * 64;
+        bytes[shoff + 0x20..shoff + 0x28].copy_from_slice(&u64::MAX.to_le_bytes());
+        fs::write(&path, bytes).unwrap();
+
+        assert_eq!(
+            elf_section_names(&path).unwrap_err().kind(),
+            std::io::ErrorKind::InvalidData
+        );
+        assert_eq!(BinaryInfo::new(&path).has_debug_info, None);
+    }
+
+    #[test]
+    fn test_elf_section_names_when_not_elf() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml");
+        assert_eq!(elf_section_names(&path).unwrap(), None);
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_elf_section_names_of_current_exe() {
+        let names = elf_section_names(&std::env::current_exe().unwrap())
+            .unwrap()
+            .unwrap();
+        assert!(names.iter().any(|name| name == ".text"));
+    }
+}
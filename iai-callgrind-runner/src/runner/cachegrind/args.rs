@@ -105,6 +105,8 @@ impl From<Args> for ToolArgs {
             verbose: value.verbose,
             trace_children: value.trace_children,
             fair_sched: value.fair_sched,
+            gen_suppressions: false,
+            collect_jumps: false,
             other,
         }
     }
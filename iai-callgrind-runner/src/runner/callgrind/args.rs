@@ -17,6 +17,7 @@ use crate::util::{bool_to_yesno, yesno_to_bool};
 #[derive(Debug, Clone)]
 pub struct Args {
     cache_sim: bool,
+    collect_jumps: bool,
     /// --combine-dumps is currently not supported by the callgrind parsers, so we print a warning
     combine_dumps: bool,
     compress_pos: bool,
@@ -55,6 +56,11 @@ impl Args {
                         Error::InvalidBoolArgument(key.to_owned(), value.to_owned())
                     })?;
                 }
+                Some((key @ "--collect-jumps", value)) => {
+                    self.collect_jumps = yesno_to_bool(value).ok_or_else(|| {
+                        Error::InvalidBoolArgument(key.to_owned(), value.to_owned())
+                    })?;
+                }
                 Some(("--toggle-collect", value)) => {
                     self.toggle_collect.push_back(value.to_owned());
                 }
@@ -119,6 +125,7 @@ impl Default for Args {
             d1: defaults::D1.into(),
             ll: defaults::LL.into(),
             cache_sim: defaults::CACHE_SIM,
+            collect_jumps: false,
             compress_pos: defaults::COMPRESS_POS,
             compress_strings: defaults::COMPRESS_STRINGS,
             combine_dumps: defaults::COMBINE_DUMPS,
@@ -154,6 +161,9 @@ impl From<Args> for ToolArgs {
                 bool_to_yesno(value.separate_threads)
             ),
         ];
+        if value.collect_jumps {
+            other.push("--collect-jumps=yes".to_owned());
+        }
         other.append(
             &mut value
                 .toggle_collect
@@ -165,6 +175,7 @@ impl From<Args> for ToolArgs {
 
         Self {
             tool: ValgrindTool::Callgrind,
+            collect_jumps: value.collect_jumps,
             output_paths: Vec::default(),
             log_path: Option::default(),
             xtree_path: Option::default(),
@@ -173,6 +184,7 @@ impl From<Args> for ToolArgs {
             verbose: value.verbose,
             trace_children: value.trace_children,
             fair_sched: value.fair_sched,
+            gen_suppressions: false,
             other,
         }
     }
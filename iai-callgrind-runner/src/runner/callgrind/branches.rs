@@ -0,0 +1,451 @@
+//! The module containing the branch report of the callgrind `--collect-jumps=yes` argument
+//!
+//! With `--collect-jumps=yes`, callgrind records how often each conditional jump was executed and
+//! how often the jump was taken (the `jcnd` lines of the output file). The conditional jumps are
+//! aggregated per function and source line into a report next to the callgrind output files,
+//! listing the branches per function and the most unpredictable branches. A branch is the more
+//! unpredictable the more often it went into the less frequent direction.
+
+use std::cmp::Reverse;
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use indexmap::IndexMap;
+
+use super::hashmap_parser::{make_path, Id};
+use super::parser::{parse_header, CallgrindParser, CallgrindProperties};
+use crate::error::Error;
+use crate::runner::tool::path::ToolOutputPath;
+
+/// The maximum number of branches in the list of the most unpredictable branches
+const MAX_UNPREDICTABLE: usize = 20;
+
+/// A conditional branch aggregated from all `jcnd` lines of a function and source line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Branch {
+    /// The amount of executions of the conditional jump
+    pub executed: u64,
+    /// The function of the conditional jump
+    pub id: Id,
+    /// The source line of the conditional jump if present
+    pub line: Option<u64>,
+    /// The amount of times the jump was taken
+    pub taken: u64,
+}
+
+/// The conditional branches of a callgrind output file
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Branches(pub Vec<Branch>);
+
+/// Parse the conditional jumps (`jcnd` lines) of a callgrind output file into [`Branches`]
+#[derive(Debug, Default)]
+pub struct BranchParser {
+    /// The project root directory required to make paths relative
+    pub project_root: PathBuf,
+}
+
+/// The conditional branches of a function summed up
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionBranches {
+    /// The amount of executions of all conditional jumps of the function
+    pub executed: u64,
+    /// The function
+    pub id: Id,
+    /// The amount of conditional jumps of the function
+    pub num_branches: usize,
+    /// The amount of times any conditional jump of the function was taken
+    pub taken: u64,
+}
+
+impl Branch {
+    /// Return the amount of executions going into the less frequent direction
+    pub fn unpredictable(&self) -> u64 {
+        self.taken.min(self.executed.saturating_sub(self.taken))
+    }
+}
+
+impl Branches {
+    /// Return the branches summed up per function sorted by the executions (highest first)
+    pub fn functions(&self) -> Vec<FunctionBranches> {
+        let mut functions: IndexMap<&Id, FunctionBranches> = IndexMap::new();
+        for branch in &self.0 {
+            let function = functions
+                .entry(&branch.id)
+                .or_insert_with(|| FunctionBranches {
+                    executed: 0,
+                    id: branch.id.clone(),
+                    num_branches: 0,
+                    taken: 0,
+                });
+            function.executed += branch.executed;
+            function.num_branches += 1;
+            function.taken += branch.taken;
+        }
+
+        let mut functions = functions.into_values().collect::<Vec<_>>();
+        functions.sort_by_key(|function| Reverse(function.executed));
+        functions
+    }
+
+    /// Return true if there are no branches
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Return at most `limit` branches sorted by [`Branch::unpredictable`] (highest first)
+    ///
+    /// Branches which always went into the same direction are not unpredictable at all and
+    /// therefore not included.
+    pub fn most_unpredictable(&self, limit: usize) -> Vec<&Branch> {
+        let mut branches = self
+            .0
+            .iter()
+            .filter(|branch| branch.unpredictable() > 0)
+            .collect::<Vec<_>>();
+        branches.sort_by_key(|branch| (Reverse(branch.unpredictable()), Reverse(branch.executed)));
+        branches.truncate(limit);
+        branches
+    }
+}
+
+impl CallgrindParser for BranchParser {
+    type Output = Branches;
+
+    fn parse_single(&self, path: &Path) -> Result<(CallgrindProperties, Self::Output)> {
+        let mut iter = BufReader::new(File::open(path)?)
+            .lines()
+            .map(Result::unwrap);
+        let config = parse_header(&mut iter)
+            .map_err(|error| Error::ParseError(path.to_owned(), error.to_string()))?;
+
+        let mut file = None;
+        let mut func = None;
+        let mut obj = None;
+
+        let mut branches: IndexMap<(Id, Option<u64>), (u64, u64)> = IndexMap::new();
+        // The `jcnd` line is followed by a line with the position of the conditional jump
+        let mut pending = None;
+
+        for line in iter {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            match line.split_once('=') {
+                Some(("ob", value)) => obj = Some(make_path(&self.project_root, value)),
+                Some(("fl" | "fi" | "fe", value)) => {
+                    file = Some(make_path(&self.project_root, value));
+                }
+                Some(("fn", value)) => func = Some(value.to_owned()),
+                Some(("jcnd", value)) => {
+                    pending = Some(parse_jcnd(value).ok_or_else(|| {
+                        Error::ParseError(path.to_owned(), format!("Invalid jcnd line: '{line}'"))
+                    })?);
+                }
+                None if line.starts_with(|c: char| c.is_ascii_digit()) => {
+                    let Some((executed, taken)) = pending.take() else {
+                        continue;
+                    };
+                    let Some(func) = func.clone() else {
+                        return Err(Error::ParseError(
+                            path.to_owned(),
+                            format!("Missing function of jcnd line at '{line}'"),
+                        )
+                        .into());
+                    };
+
+                    let mut positions = config.positions_prototype.clone();
+                    positions.set_iter_str(line.split_whitespace());
+                    let id = Id {
+                        file: file.clone(),
+                        func,
+                        obj: obj.clone(),
+                    };
+
+                    let value = branches.entry((id, positions.line())).or_insert((0, 0));
+                    value.0 += executed;
+                    value.1 += taken;
+                }
+                // Everything else is not of interest for the branches
+                Some(_) | None => {}
+            }
+        }
+
+        Ok((
+            config,
+            Branches(
+                branches
+                    .into_iter()
+                    .map(|((id, line), (executed, taken))| Branch {
+                        executed,
+                        id,
+                        line,
+                        taken,
+                    })
+                    .collect(),
+            ),
+        ))
+    }
+}
+
+/// Create the branch report of all callgrind output files of the `output_path`
+///
+/// The report of an output file `callgrind.<name>.out` is written to
+/// `callgrind.<name>.branches.txt` (see [`ToolOutputPath::to_artifact_path`]). Output files without
+/// any conditional jumps are skipped.
+///
+/// # Errors
+///
+/// Returns an error if an output file could not be parsed or the report could not be written
+pub fn create(
+    output_path: &ToolOutputPath,
+    title: &str,
+    project_root: &Path,
+) -> Result<Vec<PathBuf>> {
+    let parser = BranchParser {
+        project_root: project_root.to_owned(),
+    };
+
+    let mut paths = vec![];
+    for (path, _, branches) in parser.parse(output_path)? {
+        if branches.is_empty() {
+            continue;
+        }
+
+        let report_path = output_path.to_artifact_path(&path, "branches", "txt");
+        std::fs::write(&report_path, render(&branches, title)).with_context(|| {
+            format!("Failed to write branch report '{}'", report_path.display())
+        })?;
+        paths.push(report_path);
+    }
+
+    Ok(paths)
+}
+
+/// Render the report of the [`Branches`] with the branches per function and the most
+/// unpredictable branches
+pub fn render(branches: &Branches, title: &str) -> String {
+    let mut output = format!(
+        "{title}\n\nConditional branches per function:\n\n{:>14} {:>14} {:>8} {:>8}  Function\n",
+        "Executed", "Taken", "Taken%", "Branches"
+    );
+    for function in branches.functions() {
+        writeln!(
+            output,
+            "{:>14} {:>14} {:>7.2}% {:>8}  {}",
+            function.executed,
+            function.taken,
+            percentage(function.taken, function.executed),
+            function.num_branches,
+            format_id(&function.id, None)
+        )
+        .unwrap();
+    }
+
+    write!(
+        output,
+        "\nMost unpredictable branches:\n\n{:>14} {:>14} {:>8} {:>14}  Position\n",
+        "Executed", "Taken", "Taken%", "Unpredictable"
+    )
+    .unwrap();
+    for branch in branches.most_unpredictable(MAX_UNPREDICTABLE) {
+        writeln!(
+            output,
+            "{:>14} {:>14} {:>7.2}% {:>14}  {}",
+            branch.executed,
+            branch.taken,
+            percentage(branch.taken, branch.executed),
+            branch.unpredictable(),
+            format_id(&branch.id, branch.line)
+        )
+        .unwrap();
+    }
+
+    output
+}
+
+/// Format the function of the [`Id`] with its file and optionally the `line`
+fn format_id(id: &Id, line: Option<u64>) -> String {
+    let file = id
+        .file
+        .as_ref()
+        .map_or_else(|| "???".to_owned(), ToString::to_string);
+    match line {
+        Some(line) => format!("{} ({file}:{line})", id.func),
+        None => format!("{} ({file})", id.func),
+    }
+}
+
+/// Parse the value of a `jcnd=<executed>/<taken> <target position>` line
+fn parse_jcnd(value: &str) -> Option<(u64, u64)> {
+    let counts = value.split_whitespace().next()?;
+    let (executed, taken) = counts.split_once('/')?;
+    Some((executed.parse().ok()?, taken.parse().ok()?))
+}
+
+/// Return the percentage of the `part` of the `total`
+#[allow(clippy::cast_precision_loss)]
+fn percentage(part: u64, total: u64) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        part as f64 / total as f64 * 100.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::*;
+    use crate::runner::callgrind::hashmap_parser::SourcePath;
+
+    fn branch(func: &str, line: u64, executed: u64, taken: u64) -> Branch {
+        Branch {
+            executed,
+            id: Id {
+                file: Some(SourcePath::Relative(PathBuf::from("src/lib.rs"))),
+                func: func.to_owned(),
+                obj: None,
+            },
+            line: Some(line),
+            taken,
+        }
+    }
+
+    #[rstest]
+    #[case::valid("10/4 +3", Some((10, 4)))]
+    #[case::without_target("10/4", Some((10, 4)))]
+    #[case::missing_taken("10 +3", None)]
+    #[case::invalid("a/4 +3", None)]
+    fn test_parse_jcnd(#[case] value: &str, #[case] expected: Option<(u64, u64)>) {
+        assert_eq!(parse_jcnd(value), expected);
+    }
+
+    #[rstest]
+    #[case::never_taken(10, 0, 0)]
+    #[case::always_taken(10, 10, 0)]
+    #[case::half(10, 5, 5)]
+    #[case::mostly_taken(10, 7, 3)]
+    fn test_branch_unpredictable(#[case] executed: u64, #[case] taken: u64, #[case] expected: u64) {
+        assert_eq!(branch("main", 1, executed, taken).unpredictable(), expected);
+    }
+
+    #[test]
+    fn test_branches_functions() {
+        let branches = Branches(vec![
+            branch("foo", 1, 10, 5),
+            branch("main", 2, 100, 1),
+            branch("foo", 3, 20, 0),
+        ]);
+        let actual = branches
+            .functions()
+            .into_iter()
+            .map(|f| (f.id.func, f.executed, f.taken, f.num_branches))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            actual,
+            vec![("main".to_owned(), 100, 1, 1), ("foo".to_owned(), 30, 5, 2)]
+        );
+    }
+
+    #[test]
+    fn test_branches_most_unpredictable() {
+        let branches = Branches(vec![
+            branch("foo", 1, 10, 5),
+            branch("main", 2, 100, 1),
+            branch("foo", 3, 20, 0),
+            branch("bar", 4, 20, 15),
+        ]);
+        let actual = branches
+            .most_unpredictable(2)
+            .into_iter()
+            .map(|b| (b.id.func.as_str(), b.line))
+            .collect::<Vec<_>>();
+        assert_eq!(actual, vec![("bar", Some(4)), ("foo", Some(1))]);
+    }
+
+    #[test]
+    fn test_branch_parser_parse_single() {
+        let content = "# callgrind format
+version: 1
+creator: callgrind-3.22.0
+pid: 1234
+cmd: ./some
+part: 1
+
+positions: line
+events: Ir
+
+ob=/some/obj
+fl=/root/src/lib.rs
+fn=main
+1 5
+jcnd=10/4 3
+2
+jump=6 4
+2
+jcnd=6/6 4
+2
+3 10
+fn=foo
+jcnd=2/1 7
+6
+";
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(content.as_bytes()).unwrap();
+
+        let parser = BranchParser {
+            project_root: PathBuf::from("/root"),
+        };
+        let (_, actual) = parser.parse_single(file.path()).unwrap();
+
+        let id = |func: &str| Id {
+            file: Some(SourcePath::Relative(PathBuf::from("src/lib.rs"))),
+            func: func.to_owned(),
+            obj: Some(SourcePath::Absolute(PathBuf::from("/some/obj"))),
+        };
+        assert_eq!(
+            actual,
+            Branches(vec![
+                Branch {
+                    executed: 16,
+                    id: id("main"),
+                    line: Some(2),
+                    taken: 10
+                },
+                Branch {
+                    executed: 2,
+                    id: id("foo"),
+                    line: Some(6),
+                    taken: 1
+                }
+            ])
+        );
+    }
+
+    #[test]
+    fn test_render() {
+        let branches = Branches(vec![branch("main", 2, 10, 4)]);
+        let expected = "bench
+
+Conditional branches per function:
+
+      Executed          Taken   Taken% Branches  Function
+            10              4   40.00%        1  main (src/lib.rs)
+
+Most unpredictable branches:
+
+      Executed          Taken   Taken%  Unpredictable  Position
+            10              4   40.00%              4  main (src/lib.rs:2)
+";
+        assert_eq!(render(&branches, "bench"), expected);
+    }
+}
@@ -0,0 +1,630 @@
+//! The module containing the call tree report of a benchmark
+//!
+//! The call tree is built from the calls (the `cfn` and `calls` lines) of the callgrind output
+//! files and shows the functions with their inclusive instructions and the amount of calls indented
+//! below the calling function, similar to `callgrind_annotate --tree=both`. Callgrind records the
+//! calls per function and not per call stack, so the callees of a function always show the calls
+//! and costs summed up over all callers of this function.
+
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use indexmap::IndexMap;
+
+use super::hashmap_parser::{make_path, Id, SourcePath};
+use super::parser::{parse_header, CallgrindParser, CallgrindProperties};
+use crate::api::EventKind;
+use crate::error::Error;
+use crate::runner::metrics::Metric;
+use crate::runner::tool::path::ToolOutputPath;
+
+/// The minimum percentage of the total instructions of a function to be shown in the terminal
+pub const MIN_PERCENTAGE: f64 = 1.0;
+
+/// The calls from a caller to a callee summed up
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Call {
+    /// The amount of calls
+    pub calls: u64,
+    /// The inclusive instructions of all calls
+    pub inclusive: Metric,
+}
+
+/// The call graph of a callgrind output file from which the call tree is rendered
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallTree {
+    /// The calls of each caller to its callees
+    pub calls: IndexMap<Id, IndexMap<Id, Call>>,
+    /// The inclusive instructions of each function
+    pub functions: IndexMap<Id, Metric>,
+    /// The total instructions
+    pub total: Metric,
+}
+
+/// Parse the calls of a callgrind output file into a [`CallTree`]
+#[derive(Debug, Default)]
+pub struct CallTreeParser {
+    /// The project root directory required to make paths relative
+    pub project_root: PathBuf,
+}
+
+#[derive(Debug, Default)]
+struct CallRecord {
+    calls: u64,
+    file: Option<SourcePath>,
+    id: Option<Id>,
+    obj: Option<SourcePath>,
+}
+
+impl CallTree {
+    /// Sum this call tree up with another call tree
+    pub fn add_mut(&mut self, other: &Self) {
+        for (id, inclusive) in &other.functions {
+            *self.functions.entry(id.clone()).or_insert(Metric::Int(0)) += *inclusive;
+        }
+        for (caller, callees) in &other.calls {
+            let calls = self.calls.entry(caller.clone()).or_default();
+            for (callee, call) in callees {
+                calls
+                    .entry(callee.clone())
+                    .and_modify(|value| {
+                        value.calls += call.calls;
+                        value.inclusive += call.inclusive;
+                    })
+                    .or_insert_with(|| call.clone());
+            }
+        }
+        self.total += other.total;
+    }
+
+    /// Add the `cost` of a cost line of the function `id`
+    ///
+    /// If the cost line follows a `calls` line, the `cost` is the inclusive cost of the call
+    /// `record`, which is also added to the `callee_costs`. Otherwise, the `cost` is added to the
+    /// total.
+    fn add_cost(
+        &mut self,
+        id: Id,
+        record: Option<CallRecord>,
+        cost: Metric,
+        callee_costs: &mut IndexMap<Id, Metric>,
+    ) {
+        *self.functions.entry(id.clone()).or_insert(Metric::Int(0)) += cost;
+
+        match record.and_then(|record| Some((record.id?, record.calls))) {
+            Some((callee, calls)) => {
+                *callee_costs.entry(callee.clone()).or_insert(Metric::Int(0)) += cost;
+                self.calls
+                    .entry(id)
+                    .or_default()
+                    .entry(callee)
+                    .and_modify(|call| {
+                        call.calls += calls;
+                        call.inclusive += cost;
+                    })
+                    .or_insert(Call {
+                        calls,
+                        inclusive: cost,
+                    });
+            }
+            None => self.total += cost,
+        }
+    }
+
+    /// Return true if there are no functions with instructions
+    pub fn is_empty(&self) -> bool {
+        self.total == Metric::Int(0)
+    }
+
+    /// Return the functions which aren't called by any other function (highest instructions first)
+    pub fn roots(&self) -> Vec<(&Id, Metric)> {
+        let mut roots = self
+            .functions
+            .iter()
+            .filter(|(id, inclusive)| {
+                **inclusive > Metric::Int(0)
+                    && !self
+                        .calls
+                        .iter()
+                        .any(|(caller, callees)| caller != *id && callees.contains_key(*id))
+            })
+            .map(|(id, inclusive)| (id, *inclusive))
+            .collect::<Vec<_>>();
+        sort(&mut roots);
+        roots
+    }
+
+    /// Return the callees of the `caller` with their [`Call`] (highest instructions first)
+    pub fn callees(&self, caller: &Id) -> Vec<(&Id, &Call)> {
+        let mut callees = self
+            .calls
+            .get(caller)
+            .map(|callees| callees.iter().collect::<Vec<_>>())
+            .unwrap_or_default();
+        callees.sort_by(|(a_id, a), (b_id, b)| {
+            b.inclusive
+                .cmp(&a.inclusive)
+                .then_with(|| a_id.func.cmp(&b_id.func))
+        });
+        callees
+    }
+}
+
+impl Default for CallTree {
+    fn default() -> Self {
+        Self {
+            calls: IndexMap::default(),
+            functions: IndexMap::default(),
+            total: Metric::Int(0),
+        }
+    }
+}
+
+impl CallgrindParser for CallTreeParser {
+    type Output = CallTree;
+
+    fn parse_single(&self, path: &Path) -> Result<(CallgrindProperties, Self::Output)> {
+        let mut iter = BufReader::new(File::open(path)?)
+            .lines()
+            .map(Result::unwrap);
+        let config = parse_header(&mut iter)
+            .map_err(|error| Error::ParseError(path.to_owned(), error.to_string()))?;
+
+        let mut file = None;
+        let mut func = None;
+        let mut obj = None;
+
+        let mut tree = CallTree::default();
+        let mut callee_costs = IndexMap::<Id, Metric>::new();
+        // The `calls` line is followed by a line with the inclusive costs of the call
+        let mut record: Option<CallRecord> = None;
+
+        for line in iter {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            match line.split_once('=') {
+                Some(("ob", value)) => obj = Some(make_path(&self.project_root, value)),
+                // The inlined files (`fi` and `fe`) don't change the function
+                Some(("fl", value)) => file = Some(make_path(&self.project_root, value)),
+                Some(("fn", value)) => func = Some(value.to_owned()),
+                Some(("cob", value)) => {
+                    record.get_or_insert_with(CallRecord::default).obj =
+                        Some(make_path(&self.project_root, value));
+                }
+                Some(("cfi" | "cfl", value)) => {
+                    record.get_or_insert_with(CallRecord::default).file =
+                        Some(make_path(&self.project_root, value));
+                }
+                Some(("cfn", value)) => {
+                    let record = record.get_or_insert_with(CallRecord::default);
+                    record.id = Some(Id {
+                        file: record.file.take().or_else(|| file.clone()),
+                        func: value.to_owned(),
+                        obj: record.obj.take().or_else(|| obj.clone()),
+                    });
+                }
+                Some(("calls", value)) => {
+                    let Some(record) = record.as_mut() else {
+                        return Err(Error::ParseError(
+                            path.to_owned(),
+                            format!("Missing called function of calls line: '{line}'"),
+                        )
+                        .into());
+                    };
+                    record.calls = value
+                        .split_whitespace()
+                        .next()
+                        .and_then(|calls| calls.parse().ok())
+                        .ok_or_else(|| {
+                            Error::ParseError(
+                                path.to_owned(),
+                                format!("Invalid calls line: '{line}'"),
+                            )
+                        })?;
+                }
+                None if line.starts_with(|c: char| c.is_ascii_digit()) => {
+                    let Some(func) = func.clone() else {
+                        return Err(Error::ParseError(
+                            path.to_owned(),
+                            format!("Missing function of cost line at '{line}'"),
+                        )
+                        .into());
+                    };
+
+                    let mut metrics = config.metrics_prototype.clone();
+                    metrics.add_iter_str(
+                        line.split_whitespace()
+                            .skip(config.positions_prototype.len()),
+                    )?;
+                    let cost = metrics
+                        .metric_by_kind(&EventKind::Ir)
+                        .unwrap_or(Metric::Int(0));
+
+                    let id = Id {
+                        file: file.clone(),
+                        func,
+                        obj: obj.clone(),
+                    };
+                    tree.add_cost(id, record.take(), cost, &mut callee_costs);
+                }
+                // Everything else is not of interest for the call tree
+                Some(_) | None => {}
+            }
+        }
+
+        // Like `callgrind_annotate`, the inclusive costs of a called function are the costs of all
+        // calls of this function
+        for (id, inclusive) in callee_costs {
+            tree.functions.insert(id, inclusive);
+        }
+
+        Ok((config, tree))
+    }
+}
+
+/// Parse the output files at `paths` into a single [`CallTree`]
+///
+/// The calls of all files (threads, subprocesses, ...) are summed up.
+///
+/// # Errors
+///
+/// Returns an error if an output file could not be parsed
+pub fn parse(paths: &[PathBuf], project_root: &Path) -> Result<CallTree> {
+    let parser = CallTreeParser {
+        project_root: project_root.to_owned(),
+    };
+
+    let mut tree = CallTree::default();
+    for path in paths {
+        let (_, other) = parser.parse_single(path)?;
+        tree.add_mut(&other);
+    }
+
+    Ok(tree)
+}
+
+/// Create the call tree report of all callgrind output files of the `output_path`
+///
+/// The report of an output file `callgrind.<name>.out` is written to
+/// `callgrind.<name>.calltree.txt` (see [`ToolOutputPath::to_artifact_path`]). Output files without
+/// any instructions are skipped.
+///
+/// # Errors
+///
+/// Returns an error if an output file could not be parsed or the report could not be written
+pub fn create(
+    output_path: &ToolOutputPath,
+    title: &str,
+    project_root: &Path,
+) -> Result<Vec<PathBuf>> {
+    let parser = CallTreeParser {
+        project_root: project_root.to_owned(),
+    };
+
+    let mut paths = vec![];
+    for (path, _, tree) in parser.parse(output_path)? {
+        if tree.is_empty() {
+            continue;
+        }
+
+        let report_path = output_path.to_artifact_path(&path, "calltree", "txt");
+        std::fs::write(&report_path, render(&tree, title)).with_context(|| {
+            format!(
+                "Failed to write call tree report '{}'",
+                report_path.display()
+            )
+        })?;
+        paths.push(report_path);
+    }
+
+    Ok(paths)
+}
+
+/// Render the report of the [`CallTree`] with all functions
+pub fn render(tree: &CallTree, title: &str) -> String {
+    let mut output = format!("{title}\n\n");
+    for line in to_lines(tree, 0.0) {
+        writeln!(output, "{line}").unwrap();
+    }
+    output
+}
+
+/// Return the call tree formatted as details
+///
+/// Only functions with at least `min_percentage` of the total instructions are shown. Returns an
+/// empty vector if there are no functions with instructions.
+pub fn to_details(tree: &CallTree, min_percentage: f64) -> Vec<String> {
+    if tree.is_empty() {
+        return vec![];
+    }
+    to_lines(tree, min_percentage)
+}
+
+/// Return the header and the lines of the call tree with at least `min_percentage`
+fn to_lines(tree: &CallTree, min_percentage: f64) -> Vec<String> {
+    let mut lines = vec![
+        format!("Call tree ({}, inclusive):", EventKind::Ir),
+        format!(
+            "{:>8} {:>12} {:>10}  Function",
+            "Incl.%", "Inclusive", "Calls"
+        ),
+    ];
+    let mut stack = vec![];
+    for (id, inclusive) in tree.roots() {
+        if percentage(inclusive, tree.total) >= min_percentage {
+            write_node(
+                tree,
+                id,
+                inclusive,
+                None,
+                min_percentage,
+                &mut stack,
+                &mut lines,
+            );
+        }
+    }
+    lines
+}
+
+/// Write the function `id` and recursively its callees
+///
+/// The functions of the current call stack are in `stack`. A recursive call is marked but not
+/// expanded again.
+fn write_node<'a>(
+    tree: &'a CallTree,
+    id: &'a Id,
+    inclusive: Metric,
+    calls: Option<u64>,
+    min_percentage: f64,
+    stack: &mut Vec<&'a Id>,
+    lines: &mut Vec<String>,
+) {
+    let is_recursive = stack.contains(&id);
+    lines.push(format!(
+        "{:>7.2}% {inclusive:>12} {:>10}  {:indent$}{}{}",
+        percentage(inclusive, tree.total),
+        calls.map_or_else(String::new, |calls| calls.to_string()),
+        "",
+        format_id(id),
+        if is_recursive { " (recursive)" } else { "" },
+        indent = stack.len() * 2
+    ));
+    if is_recursive {
+        return;
+    }
+
+    stack.push(id);
+    for (callee, call) in tree.callees(id) {
+        if percentage(call.inclusive, tree.total) >= min_percentage
+            && call.inclusive > Metric::Int(0)
+        {
+            write_node(
+                tree,
+                callee,
+                call.inclusive,
+                Some(call.calls),
+                min_percentage,
+                stack,
+                lines,
+            );
+        }
+    }
+    stack.pop();
+}
+
+/// Format the function of the [`Id`] with its file if known
+fn format_id(id: &Id) -> String {
+    match &id.file {
+        None | Some(SourcePath::Unknown) => id.func.clone(),
+        Some(file) => format!("{} ({file})", id.func),
+    }
+}
+
+/// Return the percentage of the `part` of the `total`
+fn percentage(part: Metric, total: Metric) -> f64 {
+    f64::from(part.div0(total)) * 100.0
+}
+
+/// Sort the functions by their inclusive instructions (highest first) and then by name
+fn sort(functions: &mut [(&Id, Metric)]) {
+    functions.sort_by(|(a_id, a), (b_id, b)| b.cmp(a).then_with(|| a_id.func.cmp(&b_id.func)));
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    const CONTENT: &str = "# callgrind format
+version: 1
+creator: callgrind-3.22.0
+pid: 1234
+cmd: ./some
+part: 1
+
+positions: line
+events: Ir
+
+ob=/some/obj
+fl=/root/src/lib.rs
+fn=main
+1 5
+cfn=foo
+calls=2 10
+2 60
+cfn=bar
+calls=1 20
+3 30
+fn=foo
+10 50
+cfn=bar
+calls=1 20
+11 10
+fn=bar
+20 40
+";
+
+    fn parse_content() -> CallTree {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(CONTENT.as_bytes()).unwrap();
+
+        let parser = CallTreeParser {
+            project_root: PathBuf::from("/root"),
+        };
+        parser.parse_single(file.path()).unwrap().1
+    }
+
+    fn id(func: &str) -> Id {
+        Id {
+            file: Some(SourcePath::Relative(PathBuf::from("src/lib.rs"))),
+            func: func.to_owned(),
+            obj: Some(SourcePath::Absolute(PathBuf::from("/some/obj"))),
+        }
+    }
+
+    #[test]
+    fn test_call_tree_parser_parse_single() {
+        let actual = parse_content();
+
+        assert_eq!(actual.total, Metric::Int(95));
+        assert_eq!(
+            actual.functions,
+            IndexMap::from([
+                (id("main"), Metric::Int(95)),
+                (id("foo"), Metric::Int(60)),
+                (id("bar"), Metric::Int(40)),
+            ])
+        );
+        assert_eq!(
+            actual.calls,
+            IndexMap::from([
+                (
+                    id("main"),
+                    IndexMap::from([
+                        (
+                            id("foo"),
+                            Call {
+                                calls: 2,
+                                inclusive: Metric::Int(60)
+                            }
+                        ),
+                        (
+                            id("bar"),
+                            Call {
+                                calls: 1,
+                                inclusive: Metric::Int(30)
+                            }
+                        )
+                    ])
+                ),
+                (
+                    id("foo"),
+                    IndexMap::from([(
+                        id("bar"),
+                        Call {
+                            calls: 1,
+                            inclusive: Metric::Int(10)
+                        }
+                    )])
+                )
+            ])
+        );
+    }
+
+    #[test]
+    fn test_call_tree_roots() {
+        let tree = parse_content();
+        let actual = tree
+            .roots()
+            .into_iter()
+            .map(|(id, inclusive)| (id.func.as_str(), inclusive))
+            .collect::<Vec<_>>();
+        assert_eq!(actual, vec![("main", Metric::Int(95))]);
+    }
+
+    #[test]
+    fn test_call_tree_add_mut() {
+        let mut tree = parse_content();
+        tree.add_mut(&parse_content());
+
+        assert_eq!(tree.total, Metric::Int(190));
+        assert_eq!(tree.functions[&id("main")], Metric::Int(190));
+        assert_eq!(
+            tree.calls[&id("main")][&id("foo")],
+            Call {
+                calls: 4,
+                inclusive: Metric::Int(120)
+            }
+        );
+    }
+
+    #[test]
+    fn test_render() {
+        let expected = "bench
+
+Call tree (Instructions, inclusive):
+  Incl.%    Inclusive      Calls  Function
+ 100.00%           95             main (src/lib.rs)
+  63.16%           60          2    foo (src/lib.rs)
+  10.53%           10          1      bar (src/lib.rs)
+  31.58%           30          1    bar (src/lib.rs)
+";
+        assert_eq!(render(&parse_content(), "bench"), expected);
+    }
+
+    #[test]
+    fn test_render_when_recursive() {
+        let call = |calls, inclusive| Call {
+            calls,
+            inclusive: Metric::Int(inclusive),
+        };
+        let tree = CallTree {
+            calls: IndexMap::from([
+                (id("main"), IndexMap::from([(id("foo"), call(1, 90))])),
+                (id("foo"), IndexMap::from([(id("foo"), call(3, 60))])),
+            ]),
+            functions: IndexMap::from([
+                (id("main"), Metric::Int(100)),
+                (id("foo"), Metric::Int(90)),
+            ]),
+            total: Metric::Int(100),
+        };
+        let expected = "bench
+
+Call tree (Instructions, inclusive):
+  Incl.%    Inclusive      Calls  Function
+ 100.00%          100             main (src/lib.rs)
+  90.00%           90          1    foo (src/lib.rs)
+  60.00%           60          3      foo (src/lib.rs) (recursive)
+";
+        assert_eq!(render(&tree, "bench"), expected);
+    }
+
+    #[test]
+    fn test_to_details_with_min_percentage() {
+        assert_eq!(
+            to_details(&parse_content(), 20.0),
+            vec![
+                "Call tree (Instructions, inclusive):".to_owned(),
+                "  Incl.%    Inclusive      Calls  Function".to_owned(),
+                " 100.00%           95             main (src/lib.rs)".to_owned(),
+                "  63.16%           60          2    foo (src/lib.rs)".to_owned(),
+                "  31.58%           30          1    bar (src/lib.rs)".to_owned(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_to_details_when_empty() {
+        assert!(to_details(&CallTree::default(), 1.0).is_empty());
+    }
+}
@@ -1,5 +1,4 @@
 //! Module containing the parser for callgrind flamegraphs
-use std::collections::BinaryHeap;
 use std::fmt::Write;
 use std::path::{Path, PathBuf};
 
@@ -59,6 +58,7 @@ impl FlamegraphMap {
             // a heavy id which needs to be cloned, although it is already present in the map.
             if let Some(value) = self.0.map.get_mut(other_id) {
                 value.metrics.add(&other_value.metrics);
+                value.exclusive.add(&other_value.exclusive);
             } else {
                 self.0.map.insert(other_id.clone(), other_value.clone());
             }
@@ -75,7 +75,7 @@ impl FlamegraphMap {
             return Ok(vec![]);
         }
 
-        let mut heap = BinaryHeap::new();
+        let mut elems = Vec::new();
         let sentinel_value = self
             .0
             .sentinel_key
@@ -131,41 +131,30 @@ impl FlamegraphMap {
                 }
             }
 
-            heap.push(HeapElem { cost, source });
+            elems.push(HeapElem { cost, source });
         }
 
-        let mut stacks: Vec<String> = vec![];
-        let len = heap.len();
-        if len > 1 {
-            for window in heap.into_sorted_vec().windows(2) {
-                // There is only the slice size of 2 possible due to the window size of 2
-                if let [h1, h2] = window {
-                    let stack = if let Some(last) = stacks.last() {
-                        // This unwrap is safe since the space must be present due to stack format
-                        let (split, _) = last.rsplit_once(' ').unwrap();
-                        format!("{split};{} {}", h1.source, h1.cost - h2.cost)
-                    } else {
-                        format!("{} {}", h1.source, h1.cost - h2.cost)
-                    };
-
-                    stacks.push(stack);
-
-                    // The last window needs to push the last element too
-                    if stacks.len() == len - 1 {
-                        // This unwrap is safe since we have a last element the moment we enter the
-                        // `if` statement
-                        let last = stacks.last().unwrap();
-                        let (split, _) = last.rsplit_once(' ').unwrap();
-
-                        let stack = format!("{split};{} {}", h2.source, h2.cost);
-                        stacks.push(stack);
-                    }
-                }
+        // The sorted elements form a single stack with the most expensive function at the bottom.
+        // Each line of the stack format repeats the full stack up to its function, so the prefix
+        // is built up incrementally instead of being split off and copied from the previous line,
+        // and each line is allocated only once.
+        elems.sort_unstable();
+        let mut stacks: Vec<String> = Vec::with_capacity(elems.len());
+        let mut prefix = String::new();
+        for (index, elem) in elems.iter().enumerate() {
+            if !prefix.is_empty() {
+                prefix.push(';');
             }
-        } else {
-            // This unwrap is safe since heap.len() == 1 here
-            let elem = heap.pop().unwrap();
-            stacks.push(format!("{} {}", elem.source, elem.cost));
+            prefix.push_str(&elem.source);
+
+            let cost = elems
+                .get(index + 1)
+                .map_or(elem.cost, |next| elem.cost - next.cost);
+            // An integer cost has at most 20 digits and is preceded by a space
+            let mut line = String::with_capacity(prefix.len() + 21);
+            line.push_str(&prefix);
+            write!(line, " {cost}").unwrap();
+            stacks.push(line);
         }
         Ok(stacks)
     }
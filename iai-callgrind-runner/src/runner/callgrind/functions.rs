@@ -0,0 +1,163 @@
+//! The module containing the functions with the highest costs of a benchmark
+//!
+//! The inclusive and exclusive (self) costs of the functions are taken from the [`CallgrindMap`]
+//! of the callgrind output files. The functions with the most instructions are shown below the
+//! metrics of a benchmark which gives a first impression where the instructions are spent without
+//! having to open the output files in `KCachegrind` or `callgrind_annotate`.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+
+use super::hashmap_parser::{CallgrindMap, HashMapParser, Id, SourcePath, Value};
+use super::parser::CallgrindParser;
+use crate::api::EventKind;
+use crate::runner::metrics::Metric;
+
+/// Parse the output files at `paths` into a single [`CallgrindMap`]
+///
+/// The costs of the functions of all files (threads, subprocesses, ...) are summed up.
+///
+/// # Errors
+///
+/// Returns an error if an output file could not be parsed
+pub fn parse(paths: &[PathBuf], project_root: &Path) -> Result<CallgrindMap> {
+    let parser = HashMapParser {
+        project_root: project_root.to_owned(),
+        sentinel: None,
+    };
+
+    let mut map = CallgrindMap::default();
+    for path in paths {
+        let (_, other) = parser.parse_single(path)?;
+        map.add_mut(&other);
+    }
+
+    Ok(map)
+}
+
+/// Return at most `limit` functions with the highest exclusive instructions (highest first)
+///
+/// Functions without any exclusive instructions are not included.
+pub fn top(map: &CallgrindMap, limit: usize) -> Vec<(&Id, &Value)> {
+    let mut functions = map
+        .iter()
+        .filter(|(_, value)| exclusive_instructions(value) > Metric::Int(0))
+        .collect::<Vec<_>>();
+    functions.sort_by(|(a_id, a), (b_id, b)| {
+        exclusive_instructions(b)
+            .cmp(&exclusive_instructions(a))
+            .then_with(|| a_id.func.cmp(&b_id.func))
+    });
+    functions.truncate(limit);
+    functions
+}
+
+/// Return the `limit` functions with the highest exclusive instructions formatted as details
+///
+/// Each line shows the percentage of the exclusive instructions of the total instructions, the
+/// exclusive and the inclusive instructions and the function. Returns an empty vector if there are
+/// no functions with instructions.
+pub fn to_details(map: &CallgrindMap, limit: usize) -> Vec<String> {
+    let top = top(map, limit);
+    if top.is_empty() {
+        return vec![];
+    }
+
+    let total = map
+        .iter()
+        .map(|(_, value)| exclusive_instructions(value))
+        .fold(Metric::Int(0), |acc, metric| acc + metric);
+    let mut details = vec![format!(
+        "Functions with the most {} (self, inclusive):",
+        EventKind::Ir
+    )];
+    for (id, value) in top {
+        let exclusive = exclusive_instructions(value);
+        let inclusive = value
+            .metrics
+            .metric_by_kind(&EventKind::Ir)
+            .unwrap_or(Metric::Int(0));
+        let function = match &id.file {
+            None | Some(SourcePath::Unknown) => id.func.clone(),
+            Some(file) => format!("{} ({file})", id.func),
+        };
+        details.push(format!(
+            "{:>7.2}% {exclusive:>12} {inclusive:>12}  {function}",
+            f64::from(exclusive.div0(total)) * 100.0,
+        ));
+    }
+
+    details
+}
+
+fn exclusive_instructions(value: &Value) -> Metric {
+    value
+        .exclusive
+        .metric_by_kind(&EventKind::Ir)
+        .unwrap_or(Metric::Int(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::runner::callgrind::model::Metrics;
+
+    fn function(func: &str, exclusive: u64, inclusive: u64) -> (Id, Value) {
+        (
+            Id {
+                file: Some(SourcePath::Relative(PathBuf::from("src/lib.rs"))),
+                func: func.to_owned(),
+                obj: None,
+            },
+            Value {
+                exclusive: Metrics::with_metric_kinds([(EventKind::Ir, Metric::Int(exclusive))]),
+                metrics: Metrics::with_metric_kinds([(EventKind::Ir, Metric::Int(inclusive))]),
+            },
+        )
+    }
+
+    fn map() -> CallgrindMap {
+        CallgrindMap {
+            map: HashMap::from([
+                function("bench", 10, 100),
+                function("first", 60, 60),
+                function("second", 30, 30),
+                function("empty", 0, 0),
+            ]),
+            sentinel: None,
+            sentinel_key: None,
+        }
+    }
+
+    #[test]
+    fn test_top() {
+        let map = map();
+        let actual = top(&map, 5)
+            .into_iter()
+            .map(|(id, _)| id.func.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(actual, vec!["first", "second", "bench"]);
+    }
+
+    #[test]
+    fn test_to_details() {
+        assert_eq!(
+            to_details(&map(), 2),
+            vec![
+                "Functions with the most Instructions (self, inclusive):".to_owned(),
+                "  60.00%           60           60  first (src/lib.rs)".to_owned(),
+                "  30.00%           30           30  second (src/lib.rs)".to_owned(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_to_details_when_empty() {
+        assert!(to_details(&CallgrindMap::default(), 5).is_empty());
+    }
+}
@@ -4,6 +4,7 @@ use std::cmp::Ordering;
 use std::collections::hash_map::Iter;
 use std::collections::HashMap;
 use std::ffi::OsStr;
+use std::fmt::Display;
 use std::fs::File;
 use std::io::{BufRead, BufReader};
 use std::path::{Component, Path, PathBuf};
@@ -80,7 +81,9 @@ pub struct Id {
 /// The `Value` to be associated with an [`Id`]
 #[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Value {
-    /// The callgrind `Metrics` of this `Value`
+    /// The exclusive (self) costs of the function without the costs of its calls
+    pub exclusive: Metrics,
+    /// The callgrind `Metrics` of this `Value` (the inclusive costs)
     pub metrics: Metrics,
 }
 
@@ -105,6 +108,7 @@ impl CallgrindMap {
         for (other_key, other_value) in &other.map {
             if let Some(value) = self.map.get_mut(other_key) {
                 value.metrics.add(&other_value.metrics);
+                value.exclusive.add(&other_value.exclusive);
             } else {
                 self.map.insert(other_key.clone(), other_value.clone());
             }
@@ -228,20 +232,28 @@ impl CallgrindParser for HashMapParser {
                             .skip(config.positions_prototype.len()),
                     )?;
 
-                    if let Some(cfn_record) = cfn_record.take() {
+                    // The costs of a call are inclusive costs of the caller but not its self costs
+                    let exclusive = if let Some(cfn_record) = cfn_record.take() {
                         cfn_totals
                             .entry(cfn_record.id.expect("cfn record id must be present"))
                             .and_modify(|value| value.metrics.add(&metrics))
                             .or_insert(Value {
+                                exclusive: config.metrics_prototype.clone(),
                                 metrics: metrics.clone(),
                             });
-                    }
+                        config.metrics_prototype.clone()
+                    } else {
+                        metrics.clone()
+                    };
 
                     let id = current_id.try_into().expect("A valid id");
                     match fn_totals.get_mut(&id) {
-                        Some(value) => value.metrics.add(&metrics),
+                        Some(value) => {
+                            value.metrics.add(&metrics);
+                            value.exclusive.add(&exclusive);
+                        }
                         None => {
-                            fn_totals.insert(id.clone(), Value { metrics });
+                            fn_totals.insert(id.clone(), Value { exclusive, metrics });
                         }
                     }
                     current_id = id.into();
@@ -256,9 +268,13 @@ impl CallgrindParser for HashMapParser {
             }
         }
 
-        // Correct inclusive totals
+        // Correct inclusive totals but keep the exclusive costs
         for (key, value) in cfn_totals {
-            fn_totals.insert(key, value);
+            if let Some(total) = fn_totals.get_mut(&key) {
+                total.metrics = value.metrics;
+            } else {
+                fn_totals.insert(key, value);
+            }
         }
 
         Ok((
@@ -286,6 +302,17 @@ impl TryFrom<CurrentId> for Id {
         }
     }
 }
+impl Display for SourcePath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unknown => f.write_str("???"),
+            Self::Rust(path) | Self::Relative(path) | Self::Absolute(path) => {
+                write!(f, "{}", path.display())
+            }
+        }
+    }
+}
+
 impl Ord for SourcePath {
     fn cmp(&self, other: &Self) -> Ordering {
         match (self, other) {
@@ -306,7 +333,8 @@ impl PartialOrd for SourcePath {
     }
 }
 
-fn make_path(root: &Path, source: &str) -> SourcePath {
+/// Make a [`SourcePath`] from the `source` of the output file relative to the `root` if possible
+pub fn make_path(root: &Path, source: &str) -> SourcePath {
     if source == "???" {
         SourcePath::Unknown
     } else {
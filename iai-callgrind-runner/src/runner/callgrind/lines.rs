@@ -0,0 +1,288 @@
+//! The module containing the line-level costs of the entry point
+//!
+//! With `--dump-line=yes` (the default), callgrind attributes the costs to the source lines. The
+//! costs of the lines of the entry point function including the inclusive costs of the calls from
+//! these lines are collected, so the source lines with the highest costs can be shown in the
+//! summary and the terminal output. This gives a hint which line of a benchmark regressed without
+//! having to run `callgrind_annotate`.
+
+use std::cmp::Reverse;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use indexmap::IndexMap;
+
+use super::hashmap_parser::{make_path, SourcePath};
+use super::model::Metrics;
+use super::parser::{parse_header, CallgrindParser, CallgrindProperties, Sentinel};
+use crate::api::EventKind;
+use crate::error::Error;
+use crate::runner::metrics::Metric;
+
+/// The maximum number of lines in the details of the summary
+pub const MAX_LINES: usize = 5;
+
+/// The costs of a single source line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineCost {
+    /// The file of the line
+    pub file: Option<SourcePath>,
+    /// The line number
+    pub line: u64,
+    /// The summed up costs of the line including the inclusive costs of calls
+    pub metrics: Metrics,
+}
+
+/// The costs of the source lines of the entry point in the order of their first occurrence
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LineCosts(pub Vec<LineCost>);
+
+/// Parse the costs of the source lines of the functions matching the [`Sentinel`]
+#[derive(Debug)]
+pub struct LineParser {
+    /// The project root directory required to make paths relative
+    pub project_root: PathBuf,
+    /// The entry point
+    pub sentinel: Sentinel,
+}
+
+impl LineCost {
+    fn instructions(&self) -> Metric {
+        self.metrics
+            .metric_by_kind(&EventKind::Ir)
+            .unwrap_or(Metric::Int(0))
+    }
+}
+
+impl LineCosts {
+    /// Return true if there are no line costs
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Return at most `limit` lines with the highest instructions (highest first)
+    ///
+    /// Lines without any instructions are not included.
+    pub fn top(&self, limit: usize) -> Vec<&LineCost> {
+        let mut lines = self
+            .0
+            .iter()
+            .filter(|line| line.instructions() > Metric::Int(0))
+            .collect::<Vec<_>>();
+        lines.sort_by_key(|line| Reverse(line.instructions()));
+        lines.truncate(limit);
+        lines
+    }
+
+    /// Return the `limit` lines with the highest instructions as details of the summary
+    ///
+    /// The percentage of each line is relative to the instructions of the `total`. Returns an
+    /// empty vector if there are no lines with instructions.
+    pub fn to_details(&self, total: &Metrics, limit: usize) -> Vec<String> {
+        let top = self.top(limit);
+        if top.is_empty() {
+            return vec![];
+        }
+
+        let total = total
+            .metric_by_kind(&EventKind::Ir)
+            .unwrap_or(Metric::Int(0));
+        let mut details = vec![format!(
+            "Lines of the entry point with the most {}:",
+            EventKind::Ir
+        )];
+        for line in top {
+            let instructions = line.instructions();
+            let file = line
+                .file
+                .as_ref()
+                .map_or_else(|| "???".to_owned(), ToString::to_string);
+            details.push(format!(
+                "{:>7.2}% {instructions:>12}  {file}:{}",
+                f64::from(instructions.div0(total)) * 100.0,
+                line.line
+            ));
+        }
+
+        details
+    }
+}
+
+impl CallgrindParser for LineParser {
+    type Output = LineCosts;
+
+    fn parse_single(&self, path: &Path) -> Result<(CallgrindProperties, Self::Output)> {
+        let mut iter = BufReader::new(File::open(path)?)
+            .lines()
+            .map(Result::unwrap);
+        let config = parse_header(&mut iter)
+            .map_err(|error| Error::ParseError(path.to_owned(), error.to_string()))?;
+
+        // The file of the function (`fl`) and the current file which may be an inlined file
+        // (`fi` or `fe`)
+        let mut fn_file = None;
+        let mut file = None;
+        let mut is_entry_point = false;
+
+        let mut lines: IndexMap<(Option<SourcePath>, u64), Metrics> = IndexMap::new();
+        for line in iter {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            match line.split_once('=') {
+                Some(("fl", value)) => {
+                    fn_file = Some(make_path(&self.project_root, value));
+                    file.clone_from(&fn_file);
+                }
+                Some(("fi" | "fe", value)) => file = Some(make_path(&self.project_root, value)),
+                Some(("fn", value)) => {
+                    is_entry_point = self.sentinel.matches(value);
+                    file.clone_from(&fn_file);
+                }
+                // Both, the self costs and the inclusive costs of the calls (the line after the
+                // `calls` line) are attributed to the line of the entry point
+                None if is_entry_point && line.starts_with(|c: char| c.is_ascii_digit()) => {
+                    let mut positions = config.positions_prototype.clone();
+                    positions.set_iter_str(line.split_whitespace());
+                    let Some(line_number) = positions.line() else {
+                        continue;
+                    };
+
+                    // The lines after the `jump` and `jcnd` lines contain only the positions
+                    let mut costs = line.split_whitespace().skip(positions.len()).peekable();
+                    if costs.peek().is_none() {
+                        continue;
+                    }
+
+                    let mut metrics = config.metrics_prototype.clone();
+                    metrics.add_iter_str(costs)?;
+                    lines
+                        .entry((file.clone(), line_number))
+                        .and_modify(|value| value.add(&metrics))
+                        .or_insert(metrics);
+                }
+                // Everything else is not of interest for the line costs
+                Some(_) | None => {}
+            }
+        }
+
+        Ok((
+            config,
+            LineCosts(
+                lines
+                    .into_iter()
+                    .map(|((file, line), metrics)| LineCost {
+                        file,
+                        line,
+                        metrics,
+                    })
+                    .collect(),
+            ),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn line_cost(line: u64, instructions: u64) -> LineCost {
+        LineCost {
+            file: Some(SourcePath::Relative(PathBuf::from("benches/bench.rs"))),
+            line,
+            metrics: Metrics::with_metric_kinds([(EventKind::Ir, Metric::Int(instructions))]),
+        }
+    }
+
+    #[test]
+    fn test_line_costs_top() {
+        let lines = LineCosts(vec![line_cost(1, 10), line_cost(2, 0), line_cost(3, 30)]);
+        let actual = lines
+            .top(5)
+            .into_iter()
+            .map(|line| line.line)
+            .collect::<Vec<_>>();
+        assert_eq!(actual, vec![3, 1]);
+    }
+
+    #[test]
+    fn test_line_costs_to_details() {
+        let lines = LineCosts(vec![line_cost(1, 10), line_cost(3, 30)]);
+        let total = Metrics::with_metric_kinds([(EventKind::Ir, Metric::Int(80))]);
+        assert_eq!(
+            lines.to_details(&total, 1),
+            vec![
+                "Lines of the entry point with the most Instructions:".to_owned(),
+                "  37.50%           30  benches/bench.rs:3".to_owned()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_line_costs_to_details_when_empty() {
+        let total = Metrics::with_metric_kinds([(EventKind::Ir, Metric::Int(80))]);
+        assert!(LineCosts::default().to_details(&total, 5).is_empty());
+    }
+
+    #[test]
+    fn test_line_parser_parse_single() {
+        let content = "# callgrind format
+version: 1
+creator: callgrind-3.22.0
+pid: 1234
+cmd: ./some
+part: 1
+
+positions: line
+events: Ir
+
+ob=/some/obj
+fl=/root/benches/bench.rs
+fn=other
+1 100
+fn=bench::__iai_callgrind_wrapper_mod::bench
+10 5
+jcnd=10/4 12
+11
+11 3
+cfl=/root/src/lib.rs
+cfn=some::func
+calls=1 20
+11 50
+fi=/root/src/inlined.rs
+20 7
+fn=after
+10 1000
+";
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(content.as_bytes()).unwrap();
+
+        let parser = LineParser {
+            project_root: PathBuf::from("/root"),
+            sentinel: Sentinel::default(),
+        };
+        let (_, actual) = parser.parse_single(file.path()).unwrap();
+
+        let expected = |file: &str, line: u64, instructions: u64| LineCost {
+            file: Some(SourcePath::Relative(PathBuf::from(file))),
+            line,
+            metrics: Metrics::with_metric_kinds([(EventKind::Ir, Metric::Int(instructions))]),
+        };
+        assert_eq!(
+            actual,
+            LineCosts(vec![
+                expected("benches/bench.rs", 10, 5),
+                expected("benches/bench.rs", 11, 53),
+                expected("src/inlined.rs", 20, 7),
+            ])
+        );
+    }
+}
@@ -0,0 +1,244 @@
+//! The module containing the merged callgrind output file of a benchmark
+//!
+//! A benchmark with multiple threads, subprocesses or parts produces one callgrind output file for
+//! each of them. The merged output file contains the bodies of all these output files with a
+//! single header and the summed up totals, so it can be opened in tools like `KCachegrind` or
+//! `callgrind_annotate` as a single profile. This works because iai-callgrind runs callgrind with
+//! `--compress-strings=no` and `--compress-pos=no`, so each body is self-contained.
+
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+use log::warn;
+
+use super::parser::parse_header;
+use crate::runner::tool::path::ToolOutputPath;
+
+/// Create the merged callgrind output file of all callgrind output files of the `output_path`
+///
+/// The output files `callgrind.<name>.*.out` are merged into `callgrind.<name>.total.callgrind`
+/// (see [`ToolOutputPath::to_artifact_path`]). All threads, subprocesses and parts are merged
+/// regardless of the thread selection, so the merged totals equal the terminal output only if all
+/// threads are included in the total. If there is only a single output file, no merged file is
+/// created and a stale merged file from a previous run is removed.
+///
+/// # Errors
+///
+/// Returns an error if an output file could not be read or the merged file could not be written
+pub fn create(output_path: &ToolOutputPath) -> Result<Option<PathBuf>> {
+    let base = output_path.with_modifiers(Vec::<String>::new());
+    let merged_path = base.to_artifact_path(&base.to_path(), "total", "callgrind");
+
+    let paths = output_path.real_paths()?;
+    if paths.len() < 2 {
+        if merged_path.exists() {
+            std::fs::remove_file(&merged_path).with_context(|| {
+                format!(
+                    "Failed to remove merged callgrind output file '{}'",
+                    merged_path.display()
+                )
+            })?;
+        }
+        return Ok(None);
+    }
+
+    let mut headers = Vec::with_capacity(paths.len());
+    for path in &paths {
+        let mut iter = BufReader::new(File::open(path)?)
+            .lines()
+            .map(Result::unwrap);
+        headers.push(parse_header(&mut iter)?);
+    }
+    let mut sorted = paths.into_iter().zip(headers).collect::<Vec<_>>();
+    sorted.sort_by(|(_, a), (_, b)| a.compare_target_ids(b));
+
+    if sorted
+        .windows(2)
+        .any(|w| w[0].1.metrics_prototype != w[1].1.metrics_prototype)
+    {
+        warn!(
+            "Skipping the merged callgrind output file '{}': The events of the output files differ",
+            merged_path.display()
+        );
+        return Ok(None);
+    }
+
+    let mut writer = BufWriter::new(File::create(&merged_path).with_context(|| {
+        format!(
+            "Failed to create merged callgrind output file '{}'",
+            merged_path.display()
+        )
+    })?);
+    merge(sorted.iter().map(|(path, _)| path.as_path()), &mut writer)?;
+    writer.flush()?;
+
+    Ok(Some(merged_path))
+}
+
+/// Merge the callgrind output files at `paths` into the `writer`
+///
+/// The header of the first file is used without the `pid`, `thread` and `part` lines. The bodies
+/// are appended in the given order and the `totals` line is the sum of the totals of all files.
+///
+/// # Errors
+///
+/// Returns an error if a file could not be read, contains malformed totals or the writer fails
+pub fn merge<'a, I, W>(paths: I, writer: &mut W) -> Result<()>
+where
+    I: IntoIterator<Item = &'a Path>,
+    W: Write,
+{
+    let mut totals: Vec<u64> = vec![];
+    for (index, path) in paths.into_iter().enumerate() {
+        let mut is_header = true;
+        let mut file_totals = None;
+        let mut file_summary = None;
+        for line in BufReader::new(File::open(path)?).lines() {
+            let line = line?;
+            if is_header {
+                if line.starts_with("events:") {
+                    is_header = false;
+                }
+                if index == 0
+                    && !["pid:", "thread:", "part:"]
+                        .iter()
+                        .any(|k| line.starts_with(k))
+                {
+                    writeln!(writer, "{line}")?;
+                    if line.contains("callgrind format") {
+                        writeln!(writer, "# Merged by iai-callgrind")?;
+                    }
+                }
+            } else if let Some(values) = line.strip_prefix("totals:") {
+                file_totals = Some(parse_costs(path, values)?);
+            } else if let Some(values) = line.strip_prefix("summary:") {
+                file_summary = Some(parse_costs(path, values)?);
+            } else {
+                writeln!(writer, "{line}")?;
+            }
+        }
+
+        for (position, cost) in file_totals
+            .or(file_summary)
+            .into_iter()
+            .flatten()
+            .enumerate()
+        {
+            if let Some(total) = totals.get_mut(position) {
+                *total += cost;
+            } else {
+                totals.push(cost);
+            }
+        }
+    }
+
+    if !totals.is_empty() {
+        writeln!(
+            writer,
+            "totals: {}",
+            totals
+                .iter()
+                .map(u64::to_string)
+                .collect::<Vec<_>>()
+                .join(" ")
+        )?;
+    }
+
+    Ok(())
+}
+
+fn parse_costs(path: &Path, values: &str) -> Result<Vec<u64>> {
+    values
+        .split_ascii_whitespace()
+        .map(|value| {
+            value.parse::<u64>().map_err(|error| {
+                anyhow!(
+                    "Invalid totals in callgrind output file '{}': {error}",
+                    path.display()
+                )
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_merge() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = dir.path().join("callgrind.bench.t1.out");
+        let second = dir.path().join("callgrind.bench.t2.out");
+        std::fs::write(
+            &first,
+            "# callgrind format
+version: 1
+creator: callgrind-3.22.0
+pid: 10
+cmd: bench
+part: 1
+thread: 1
+positions: line
+events: Ir Dr
+
+ob=bench
+fl=src/main.rs
+fn=main
+1 5 2
+totals: 5 2
+",
+        )
+        .unwrap();
+        std::fs::write(
+            &second,
+            "# callgrind format
+version: 1
+creator: callgrind-3.22.0
+pid: 10
+cmd: bench
+part: 1
+thread: 2
+positions: line
+events: Ir Dr
+
+ob=bench
+fl=src/main.rs
+fn=worker
+2 10 3
+totals: 10 3
+",
+        )
+        .unwrap();
+
+        let mut output = vec![];
+        merge([first.as_path(), second.as_path()], &mut output).unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "# callgrind format
+# Merged by iai-callgrind
+version: 1
+creator: callgrind-3.22.0
+cmd: bench
+positions: line
+events: Ir Dr
+
+ob=bench
+fl=src/main.rs
+fn=main
+1 5 2
+
+ob=bench
+fl=src/main.rs
+fn=worker
+2 10 3
+totals: 15 5
+"
+        );
+    }
+}
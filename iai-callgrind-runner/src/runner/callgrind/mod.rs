@@ -1,9 +1,14 @@
 //! The main module for callgrind related elements
 
 pub mod args;
+pub mod branches;
+pub mod call_tree;
 pub mod flamegraph;
 pub mod flamegraph_parser;
+pub mod functions;
 pub mod hashmap_parser;
+pub mod lines;
+pub mod merge;
 pub mod model;
 pub mod parser;
 pub mod regression;
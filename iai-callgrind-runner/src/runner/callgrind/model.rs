@@ -163,6 +163,14 @@ impl Positions {
         }
     }
 
+    /// Return the line number if present and not `0` (an unknown line)
+    pub fn line(&self) -> Option<u64> {
+        self.0
+            .get(&PositionType::Line)
+            .copied()
+            .filter(|line| *line != 0)
+    }
+
     /// Return the length of the positions
     pub fn len(&self) -> usize {
         self.0.len()
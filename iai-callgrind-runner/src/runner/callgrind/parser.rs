@@ -1,6 +1,8 @@
 //! Module containing the basic callgrind parser elements
 use std::cmp::Ordering;
 use std::fmt::Display;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
@@ -201,6 +203,22 @@ impl From<Sentinel> for String {
     }
 }
 
+/// Return true if a function matching the `sentinel` was executed in this callgrind output file
+///
+/// The function can either be the function of a cost line (`fn=`) or a called function (`cfn=`).
+pub fn contains_function(path: &Path, sentinel: &Sentinel) -> Result<bool> {
+    for line in BufReader::new(File::open(path)?).lines() {
+        let line = line?;
+        if let Some(("fn" | "cfn", value)) = line.split_once('=') {
+            if sentinel.matches(value) {
+                return Ok(true);
+            }
+        }
+    }
+
+    Ok(false)
+}
+
 /// Parse the callgrind output files header
 pub fn parse_header<I>(iter: &mut I) -> Result<CallgrindProperties>
 where
@@ -298,10 +316,35 @@ where
 
 #[cfg(test)]
 mod tests {
+    use std::io::Write;
+
     use rstest::rstest;
 
     use super::*;
 
+    #[rstest]
+    #[case::function("worker::main", true)]
+    #[case::called_function("*::compute", true)]
+    #[case::missing("other", false)]
+    fn test_contains_function(#[case] sentinel: &str, #[case] expected: bool) {
+        let content = "# callgrind format
+events: Ir
+
+fn=worker::main
+0 10
+cfn=worker::compute
+calls=1 0
+0 20
+";
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(content.as_bytes()).unwrap();
+
+        assert_eq!(
+            contains_function(file.path(), &Sentinel::new(sentinel)).unwrap(),
+            expected
+        );
+    }
+
     /// These are some non-exhaustive real world examples which a sentinel should be able to match
     #[rstest]
     #[case::main_binary("*::main", "by_binary::main")]
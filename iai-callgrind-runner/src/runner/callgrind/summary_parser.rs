@@ -6,6 +6,7 @@ use std::path::{Path, PathBuf};
 use anyhow::Result;
 use log::{debug, trace};
 
+use super::lines::{LineParser, MAX_LINES};
 use super::model::Metrics;
 use super::parser::{parse_header, CallgrindParser, CallgrindProperties};
 use crate::error::Error;
@@ -32,6 +33,8 @@ use crate::runner::tool::path::ToolOutputPath;
 /// self costs. It may be larger as the cost lines may not represent all cost of the program run.
 #[derive(Debug)]
 pub struct SummaryParser {
+    /// If present, add the lines of the entry point with the highest costs to the details
+    pub lines: Option<LineParser>,
     /// The [`ToolOutputPath`]
     pub output_path: ToolOutputPath,
 }
@@ -40,6 +43,7 @@ impl SummaryParser {
     /// Create a new `SummaryParser`
     pub fn new(output_path: &ToolOutputPath) -> Self {
         Self {
+            lines: None,
             output_path: output_path.clone(),
         }
     }
@@ -99,7 +103,15 @@ impl CallgrindParser for SummaryParser {
 
 impl Parser for SummaryParser {
     fn parse_single(&self, path: PathBuf) -> Result<ParserOutput> {
-        CallgrindParser::parse_single(self, &path).map(|(props, metrics)| ParserOutput {
+        let (props, metrics) = CallgrindParser::parse_single(self, &path)?;
+        let details = if let Some(lines) = &self.lines {
+            let (_, line_costs) = lines.parse_single(&path)?;
+            line_costs.to_details(&metrics, MAX_LINES)
+        } else {
+            vec![]
+        };
+
+        Ok(ParserOutput {
             path: path.clone(),
             header: Header {
                 command: props.cmd.expect("A command should be present"),
@@ -109,7 +121,7 @@ impl Parser for SummaryParser {
                 part: props.part,
                 desc: props.desc,
             },
-            details: vec![],
+            details,
             metrics: Callgrind(metrics),
         })
     }
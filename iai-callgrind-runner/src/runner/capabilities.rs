@@ -0,0 +1,261 @@
+//! The module containing the [`Capabilities`] of the installed valgrind
+//!
+//! The valgrind version and the availability of the tools are probed only once per run. Features
+//! which need a minimum valgrind version are checked before any benchmark is run, so the user gets
+//! an actionable error instead of a cryptic tool failure in the middle of the run.
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+
+use anyhow::{anyhow, Result};
+use log::debug;
+
+use super::tool::config::ToolConfig;
+use crate::api::ValgrindTool;
+use crate::error::Error;
+
+/// The detected capabilities of the installed valgrind
+#[derive(Debug, Clone)]
+pub struct Capabilities {
+    /// The path to the valgrind executable
+    bin: PathBuf,
+    /// The cache for the availability of the valgrind tools
+    tools: Arc<Mutex<HashMap<ValgrindTool, bool>>>,
+    /// The valgrind version if it could be detected
+    version: Option<ValgrindVersion>,
+}
+
+/// The version of valgrind as in `valgrind --version`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ValgrindVersion {
+    /// The major version
+    pub major: u32,
+    /// The minor version
+    pub minor: u32,
+    /// The patch version
+    pub patch: u32,
+}
+
+impl Capabilities {
+    /// Probe the valgrind version of the valgrind executable at `bin`
+    pub fn new(bin: &Path) -> Self {
+        let version = Command::new(bin)
+            .arg("--version")
+            .stderr(Stdio::null())
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .and_then(|output| String::from_utf8(output.stdout).ok())
+            .and_then(|output| output.parse::<ValgrindVersion>().ok());
+        debug!("Detected valgrind version: {version:?}");
+
+        Self::with_version(bin, version)
+    }
+
+    /// Create new `Capabilities` with an already known valgrind `version`
+    pub fn with_version(bin: &Path, version: Option<ValgrindVersion>) -> Self {
+        Self {
+            bin: bin.to_owned(),
+            tools: Arc::default(),
+            version,
+        }
+    }
+
+    /// Check that the installed valgrind supports the [`ToolConfig`]
+    ///
+    /// If the version of valgrind could not be detected, only the availability of the tool is
+    /// checked.
+    pub fn check(&self, tool_config: &ToolConfig) -> Result<()> {
+        let tool = tool_config.tool;
+        if !tool_config.is_enabled {
+            return Ok(());
+        }
+
+        if !self.has_tool(tool) {
+            return Err(Error::CapabilityError(format!(
+                "The tool '{}' is not available in your valgrind installation{}. Please install \
+                 a valgrind version which includes this tool.",
+                tool.id(),
+                self.version
+                    .map_or_else(String::new, |version| format!(" ({version})"))
+            ))
+            .into());
+        }
+
+        if tool == ValgrindTool::Cachegrind {
+            self.require(ValgrindVersion::new(3, 22, 0), "cachegrind client requests")?;
+        }
+
+        for arg in &tool_config.args.other {
+            if let Some((feature, minimum)) = requirement(tool, arg) {
+                self.require(minimum, feature)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Return true if the `tool` is available
+    ///
+    /// The result is cached, so valgrind is run at most once per tool.
+    pub fn has_tool(&self, tool: ValgrindTool) -> bool {
+        let mut tools = self
+            .tools
+            .lock()
+            .expect("The lock for the valgrind tools should not be poisoned");
+        *tools.entry(tool).or_insert_with(|| {
+            // Valgrind fails to start a tool which is not installed before printing the version
+            let is_available = Command::new(&self.bin)
+                .arg(format!("--tool={}", tool.id()))
+                .arg("--version")
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .status()
+                .is_ok_and(|status| status.success());
+            debug!("Valgrind tool '{}' is available: {is_available}", tool.id());
+            is_available
+        })
+    }
+
+    /// Return an error if the valgrind version is older than the `minimum` version
+    pub fn require(&self, minimum: ValgrindVersion, feature: &str) -> Result<()> {
+        match self.version {
+            Some(version) if version < minimum => Err(Error::CapabilityError(format!(
+                "Your valgrind {} lacks {feature}, need >= {}.{}",
+                version.short(),
+                minimum.major,
+                minimum.minor
+            ))
+            .into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Return the valgrind version if it could be detected
+    pub fn version(&self) -> Option<ValgrindVersion> {
+        self.version
+    }
+}
+
+impl ValgrindVersion {
+    /// Create a new `ValgrindVersion`
+    pub fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    /// Return the version in the short `MAJOR.MINOR` format
+    pub fn short(&self) -> String {
+        format!("{}.{}", self.major, self.minor)
+    }
+}
+
+impl Display for ValgrindVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+impl FromStr for ValgrindVersion {
+    type Err = anyhow::Error;
+
+    /// Parse the output of `valgrind --version` like `valgrind-3.22.0` or `valgrind-3.23.0.GIT`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let trimmed = s.trim();
+        let version = trimmed.strip_prefix("valgrind-").unwrap_or(trimmed);
+
+        let mut split = version.split(['.', '-']);
+        let mut next = |name: &str| {
+            split
+                .next()
+                .and_then(|part| part.parse::<u32>().ok())
+                .ok_or_else(|| anyhow!("Invalid valgrind version '{trimmed}': Missing {name}"))
+        };
+
+        let major = next("major")?;
+        let minor = next("minor")?;
+        let patch = next("patch").unwrap_or(0);
+
+        Ok(Self::new(major, minor, patch))
+    }
+}
+
+/// Return the feature and the minimum valgrind version required by a tool argument if any
+fn requirement(tool: ValgrindTool, arg: &str) -> Option<(&'static str, ValgrindVersion)> {
+    let (key, value) = arg.split_once('=').unwrap_or((arg, ""));
+    match (tool, key, value) {
+        (ValgrindTool::DHAT, "--mode", "ad-hoc" | "copy") => Some((
+            "the DHAT ad-hoc and copy modes",
+            ValgrindVersion::new(3, 17, 0),
+        )),
+        (ValgrindTool::Memcheck, "--xtree-leak", _) | (_, "--xtree-memory", _) => {
+            Some(("xtree memory profiling", ValgrindVersion::new(3, 13, 0)))
+        }
+        (_, "--collect-systime", "usec" | "nsec") => Some((
+            "the --collect-systime=usec|nsec option",
+            ValgrindVersion::new(3, 15, 0),
+        )),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case::release("valgrind-3.22.0\n", ValgrindVersion::new(3, 22, 0))]
+    #[case::git("valgrind-3.23.0.GIT", ValgrindVersion::new(3, 23, 0))]
+    #[case::no_patch("valgrind-3.21", ValgrindVersion::new(3, 21, 0))]
+    #[case::no_prefix("3.20.1", ValgrindVersion::new(3, 20, 1))]
+    fn test_valgrind_version_from_str(#[case] input: &str, #[case] expected: ValgrindVersion) {
+        assert_eq!(input.parse::<ValgrindVersion>().unwrap(), expected);
+    }
+
+    #[rstest]
+    #[case::empty("")]
+    #[case::no_minor("valgrind-3")]
+    #[case::garbage("valgrind-foo")]
+    fn test_valgrind_version_from_str_when_invalid(#[case] input: &str) {
+        input.parse::<ValgrindVersion>().unwrap_err();
+    }
+
+    #[rstest]
+    #[case::older(ValgrindVersion::new(3, 16, 1), false)]
+    #[case::equal(ValgrindVersion::new(3, 17, 0), true)]
+    #[case::newer(ValgrindVersion::new(3, 22, 0), true)]
+    fn test_capabilities_require(#[case] version: ValgrindVersion, #[case] expected: bool) {
+        let capabilities = Capabilities::with_version(Path::new("valgrind"), Some(version));
+        assert_eq!(
+            capabilities
+                .require(ValgrindVersion::new(3, 17, 0), "the feature")
+                .is_ok(),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_capabilities_require_error_message() {
+        let capabilities =
+            Capabilities::with_version(Path::new("valgrind"), Some(ValgrindVersion::new(3, 16, 1)));
+        let error = capabilities
+            .require(
+                ValgrindVersion::new(3, 17, 0),
+                "the DHAT ad-hoc and copy modes",
+            )
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Your valgrind 3.16 lacks the DHAT ad-hoc and copy modes, need >= 3.17"
+        );
+    }
+}
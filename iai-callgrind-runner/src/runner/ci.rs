@@ -0,0 +1,287 @@
+//! The module containing the [`CiEnv`] to derive the baseline name of `--save-baseline=auto`
+//!
+//! Keeping a baseline per branch or pull request in a CI workflow usually requires some shell
+//! plumbing to extract the branch name from the environment variables of the CI provider and to
+//! convert it into a valid baseline name. With `--save-baseline=auto` the baseline name is derived
+//! from the `--baseline-template` with the values detected by the [`CiEnv`].
+
+use std::str::FromStr;
+
+use anyhow::{anyhow, Result};
+
+use super::summary::{BaselineName, GitInfo};
+
+/// The value of `--save-baseline` to derive the baseline name from the [`CiEnv`]
+pub const AUTO_BASELINE: &str = "auto";
+
+/// The environment variables of the supported CI providers which contain the branch name
+///
+/// The order matters. For example, in a pull request in GitHub Actions, `GITHUB_HEAD_REF` contains
+/// the name of the source branch but `GITHUB_REF_NAME` something like `123/merge`.
+const BRANCH_VARS: [&str; 10] = [
+    "GITHUB_HEAD_REF",
+    "GITHUB_REF_NAME",
+    "CI_MERGE_REQUEST_SOURCE_BRANCH_NAME",
+    "CI_COMMIT_REF_NAME",
+    "BITBUCKET_BRANCH",
+    "BUILDKITE_BRANCH",
+    "CIRCLE_BRANCH",
+    "TRAVIS_PULL_REQUEST_BRANCH",
+    "TRAVIS_BRANCH",
+    "GIT_BRANCH",
+];
+
+/// The environment variables of the supported CI providers which contain the commit hash
+const COMMIT_VARS: [&str; 7] = [
+    "GITHUB_SHA",
+    "CI_COMMIT_SHA",
+    "BITBUCKET_COMMIT",
+    "BUILDKITE_COMMIT",
+    "CIRCLE_SHA1",
+    "TRAVIS_COMMIT",
+    "GIT_COMMIT",
+];
+
+/// The environment variables of the supported CI providers which contain the pull request number
+///
+/// GitHub Actions doesn't have such a variable. Its pull request number is extracted from
+/// `GITHUB_REF`.
+const PR_VARS: [&str; 6] = [
+    "CI_MERGE_REQUEST_IID",
+    "BITBUCKET_PR_ID",
+    "BUILDKITE_PULL_REQUEST",
+    "CIRCLE_PR_NUMBER",
+    "TRAVIS_PULL_REQUEST",
+    "CHANGE_ID",
+];
+
+/// The length of the short commit hash
+const SHORT_COMMIT_LENGTH: usize = 7;
+
+/// The branch, pull request number and commit of the current (CI) run
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CiEnv {
+    /// The branch name
+    pub branch: Option<String>,
+    /// The full commit hash
+    pub commit: Option<String>,
+    /// The number of the pull request or merge request
+    pub pr: Option<String>,
+}
+
+impl CiEnv {
+    /// Detect the `CiEnv` from the environment variables of the CI provider
+    ///
+    /// Outside of a CI the branch and commit of the [`GitInfo`] are used if present.
+    pub fn from_env(git: Option<&GitInfo>) -> Self {
+        Self::from_vars(|name| std::env::var(name).ok(), git)
+    }
+
+    /// Detect the `CiEnv` with the `var` function returning the value of an environment variable
+    fn from_vars<F>(var: F, git: Option<&GitInfo>) -> Self
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        // Some providers set the pull request variables to `false` if it is not a pull request
+        let var = |name: &str| var(name).filter(|value| !value.is_empty() && value != "false");
+
+        let branch = BRANCH_VARS
+            .iter()
+            .find_map(|name| var(name))
+            .map(|branch| {
+                branch
+                    .strip_prefix("origin/")
+                    .map_or_else(|| branch.clone(), ToOwned::to_owned)
+            })
+            .or_else(|| git.and_then(|git| git.branch.clone()));
+        let commit = COMMIT_VARS
+            .iter()
+            .find_map(|name| var(name))
+            .or_else(|| git.map(|git| git.commit.clone()));
+        let pr = var("GITHUB_REF")
+            .and_then(|github_ref| {
+                github_ref
+                    .strip_prefix("refs/pull/")
+                    .and_then(|rest| rest.split('/').next())
+                    .map(ToOwned::to_owned)
+            })
+            .or_else(|| PR_VARS.iter().find_map(|name| var(name)));
+
+        Self { branch, commit, pr }
+    }
+
+    /// Create the [`BaselineName`] from the `template`
+    ///
+    /// The placeholders `{branch}`, `{pr}` and `{commit}` (the short commit hash) are replaced
+    /// with the values of this `CiEnv`. All characters which are not allowed in a baseline name are
+    /// replaced with `_`.
+    pub fn baseline_name(&self, template: &str) -> Result<BaselineName> {
+        let mut name = String::new();
+        let mut rest = template;
+        while let Some(start) = rest.find('{') {
+            name.push_str(&rest[..start]);
+            let Some(end) = rest[start..].find('}').map(|end| start + end) else {
+                return Err(anyhow!(
+                    "Invalid baseline template '{template}': Missing closing '}}'"
+                ));
+            };
+
+            let placeholder = &rest[start + 1..end];
+            let value = match placeholder {
+                "branch" => self.branch.as_deref(),
+                "commit" => self
+                    .commit
+                    .as_deref()
+                    .map(|commit| commit.get(..SHORT_COMMIT_LENGTH).unwrap_or(commit)),
+                "pr" => self.pr.as_deref(),
+                _ => {
+                    return Err(anyhow!(
+                        "Invalid baseline template '{template}': Unknown placeholder \
+                         '{{{placeholder}}}'. Valid placeholders are '{{branch}}', '{{pr}}' and \
+                         '{{commit}}'"
+                    ));
+                }
+            };
+            let Some(value) = value else {
+                return Err(anyhow!(
+                    "Unable to derive the baseline name from the template '{template}': The value \
+                     of '{{{placeholder}}}' could not be detected"
+                ));
+            };
+
+            name.push_str(value);
+            rest = &rest[end + 1..];
+        }
+        name.push_str(rest);
+
+        let name = name
+            .chars()
+            .map(|char| {
+                if char.is_ascii_alphanumeric() || char == '_' {
+                    char
+                } else {
+                    '_'
+                }
+            })
+            .collect::<String>();
+        if name.is_empty() {
+            return Err(anyhow!(
+                "The baseline name derived from the template '{template}' is empty"
+            ));
+        }
+
+        BaselineName::from_str(&name).map_err(|error| anyhow!(error))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use rstest::rstest;
+
+    use super::*;
+
+    fn ci_env(branch: Option<&str>, commit: Option<&str>, pr: Option<&str>) -> CiEnv {
+        CiEnv {
+            branch: branch.map(ToOwned::to_owned),
+            commit: commit.map(ToOwned::to_owned),
+            pr: pr.map(ToOwned::to_owned),
+        }
+    }
+
+    #[rstest]
+    #[case::none(&[], None, CiEnv::default())]
+    #[case::git(
+        &[],
+        Some(GitInfo { branch: Some("main".to_owned()), commit: "abc".to_owned(), dirty: false }),
+        ci_env(Some("main"), Some("abc"), None)
+    )]
+    #[case::github_push(
+        &[("GITHUB_REF_NAME", "main"), ("GITHUB_REF", "refs/heads/main"), ("GITHUB_SHA", "abc")],
+        None,
+        ci_env(Some("main"), Some("abc"), None)
+    )]
+    #[case::github_pull_request(
+        &[
+            ("GITHUB_HEAD_REF", "feature/some"),
+            ("GITHUB_REF_NAME", "12/merge"),
+            ("GITHUB_REF", "refs/pull/12/merge"),
+            ("GITHUB_SHA", "abc")
+        ],
+        None,
+        ci_env(Some("feature/some"), Some("abc"), Some("12"))
+    )]
+    #[case::github_empty_head_ref(
+        &[("GITHUB_HEAD_REF", ""), ("GITHUB_REF_NAME", "main")],
+        None,
+        ci_env(Some("main"), None, None)
+    )]
+    #[case::gitlab_merge_request(
+        &[
+            ("CI_MERGE_REQUEST_SOURCE_BRANCH_NAME", "some"),
+            ("CI_COMMIT_REF_NAME", "other"),
+            ("CI_MERGE_REQUEST_IID", "7"),
+            ("CI_COMMIT_SHA", "abc")
+        ],
+        None,
+        ci_env(Some("some"), Some("abc"), Some("7"))
+    )]
+    #[case::travis_no_pull_request(
+        &[("TRAVIS_BRANCH", "main"), ("TRAVIS_PULL_REQUEST", "false")],
+        None,
+        ci_env(Some("main"), None, None)
+    )]
+    #[case::jenkins(
+        &[("GIT_BRANCH", "origin/main"), ("GIT_COMMIT", "abc"), ("CHANGE_ID", "3")],
+        None,
+        ci_env(Some("main"), Some("abc"), Some("3"))
+    )]
+    #[case::ci_before_git(
+        &[("CIRCLE_BRANCH", "ci"), ("CIRCLE_SHA1", "def")],
+        Some(GitInfo { branch: Some("main".to_owned()), commit: "abc".to_owned(), dirty: false }),
+        ci_env(Some("ci"), Some("def"), None)
+    )]
+    fn test_ci_env_from_vars(
+        #[case] vars: &[(&str, &str)],
+        #[case] git: Option<GitInfo>,
+        #[case] expected: CiEnv,
+    ) {
+        let vars = vars.iter().copied().collect::<HashMap<_, _>>();
+        let actual = CiEnv::from_vars(|name| vars.get(name).map(|v| (*v).to_owned()), git.as_ref());
+        assert_eq!(actual, expected);
+    }
+
+    #[rstest]
+    #[case::branch("main", "{branch}", "main")]
+    #[case::branch_sanitized("feature/some.1", "{branch}", "feature_some_1")]
+    #[case::commit("main", "{commit}", "0123456")]
+    #[case::pr("main", "pr_{pr}", "pr_12")]
+    #[case::all(
+        "feature/some.1",
+        "{branch}-{pr}-{commit}",
+        "feature_some_1_12_0123456"
+    )]
+    #[case::no_placeholders("main", "fixed", "fixed")]
+    fn test_baseline_name(#[case] branch: &str, #[case] template: &str, #[case] expected: &str) {
+        let ci_env = ci_env(
+            Some(branch),
+            Some("0123456789abcdef0123456789abcdef01234567"),
+            Some("12"),
+        );
+        assert_eq!(
+            ci_env.baseline_name(template).unwrap(),
+            BaselineName::from_str(expected).unwrap()
+        );
+    }
+
+    #[rstest]
+    #[case::missing_value("{pr}")]
+    #[case::unknown_placeholder("{tag}")]
+    #[case::missing_closing_brace("{branch")]
+    #[case::empty("")]
+    fn test_baseline_name_when_invalid(#[case] template: &str) {
+        let ci_env = ci_env(Some("main"), Some("abc"), None);
+        ci_env.baseline_name(template).unwrap_err();
+    }
+}
@@ -7,19 +7,23 @@ mod defaults {
 
 use std::ffi::OsString;
 use std::fmt::Display;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::{Child, Command, Stdio as StdStdio};
 use std::time::{Duration, Instant};
 
-use anyhow::Result;
+use anyhow::{Context, Result};
+use indexmap::IndexMap;
 use log::{debug, info, log_enabled, trace, Level};
 use tempfile::TempDir;
 
-use super::args::NoCapture;
-use super::format::{OutputFormatKind, SummaryFormatter};
+use super::args::{ExitCodes, NoCapture};
+use super::envs;
+use super::format::{print_unchanged, OutputFormatKind, SummaryFormatter};
 use super::meta::Metadata;
-use super::summary::BenchmarkSummary;
-use crate::api::{self, Pipe};
+use super::metrics::Metrics;
+use super::run_log;
+use super::summary::{BenchmarkSummary, GitInfo, SummaryOutput, ToolRegression};
+use crate::api::{self, DeltaStyle, EventKind, Pipe, Sort};
 use crate::error::Error;
 use crate::util::{copy_directory, make_absolute, write_all_to_stderr};
 
@@ -50,6 +54,16 @@ pub struct Assistant {
 /// Used to print a final summary after all benchmarks.
 #[derive(Debug, Default)]
 pub struct BenchmarkSummaries {
+    /// The error which aborted the benchmark run if any
+    pub error: Option<anyhow::Error>,
+    /// The regressions of the groups which exceeded their regression budget
+    pub group_regressions: Vec<(ModulePath, Vec<ToolRegression>)>,
+    /// The number of benchmarks which failed with an error
+    pub num_errored: usize,
+    /// The number of benchmarks which were skipped because the benchmark run was aborted
+    pub num_skipped: usize,
+    /// The number of benchmarks whose terminal output was hidden because it was unchanged
+    pub num_unchanged: usize,
     /// The benchmark summaries
     pub summaries: Vec<BenchmarkSummary>,
     /// The execution time of all benchmarks.
@@ -65,10 +79,18 @@ pub struct Config {
     pub bench_file: PathBuf,
     /// The [`Metadata`]
     pub meta: Metadata,
+    /// The user metadata of the `main!` macro merged with the metadata of the command-line
+    pub metadata: IndexMap<String, String>,
     /// The module path of the benchmark file
     pub module_path: ModulePath,
+    /// The overhead of the library benchmark harness measured by the calibration if any
+    pub overhead: Option<Metrics<EventKind>>,
     /// The package directory of the package in which `iai-callgrind` (not the runner) is used
     pub package_dir: PathBuf,
+    /// The file to which the complete summary of each benchmark is appended as a line of json
+    ///
+    /// If present, only the totals of the summaries are kept in the [`BenchmarkSummaries`].
+    pub summary_stream: Option<PathBuf>,
 }
 
 /// A helper struct similar to a file path but for module paths with the `::` delimiter
@@ -141,6 +163,44 @@ impl Assistant {
         }
     }
 
+    /// Run the `Assistant` with the outcomes of the finished benchmarks of the `summaries`
+    ///
+    /// The [`api::BenchmarkOutcome`]s are written to a temporary file whose path is passed to the
+    /// benchmark binary in the `IAI_CALLGRIND_OUTCOMES` environment variable. This is meant for the
+    /// `teardown` functions of groups and the `main!` macro, which run after the benchmarks.
+    pub fn run_with_outcomes(
+        &self,
+        config: &Config,
+        module_path: &ModulePath,
+        summaries: &[BenchmarkSummary],
+    ) -> Result<Option<Child>> {
+        if config.meta.args.load_baseline.is_some() {
+            return Ok(None);
+        }
+
+        let outcomes = summaries
+            .iter()
+            .map(BenchmarkSummary::to_outcome)
+            .collect::<Vec<_>>();
+        let encoded =
+            bincode::serialize(&outcomes).with_context(|| "Failed to encode benchmark outcomes")?;
+
+        let temp_dir = tempfile::tempdir()
+            .with_context(|| "Failed to create temporary directory for benchmark outcomes")?;
+        let path = temp_dir.path().join("outcomes");
+        std::fs::write(&path, encoded).with_context(|| {
+            format!("Failed to write benchmark outcomes to '{}'", path.display())
+        })?;
+
+        let mut assistant = self.clone();
+        assistant
+            .envs
+            .push((envs::IAI_CALLGRIND_OUTCOMES.into(), path.into_os_string()));
+
+        // Teardowns don't run in parallel, so the temporary directory outlives the teardown
+        assistant.run(config, module_path)
+    }
+
     /// Run the `Assistant` by calling the benchmark binary with the needed arguments
     ///
     /// We don't run the assistant if `--load-baseline` was given on the command-line!
@@ -209,6 +269,13 @@ impl Assistant {
                         }
                     })?;
 
+                for (stream, bytes) in [("stdout", &output.stdout), ("stderr", &output.stderr)] {
+                    run_log::write(
+                        &format!("{id} function in group '{module_path}': {stream}:"),
+                        bytes,
+                    );
+                }
+
                 if log_enabled!(Level::Info) && !output.stdout.is_empty() {
                     info!("{id} function in group '{module_path}': stdout:");
                     write_all_to_stderr(&output.stdout);
@@ -261,6 +328,15 @@ impl BenchmarkSummaries {
         self.summaries.push(summary);
     }
 
+    /// Add the `regressions` of the group with the `module_path` which exceeded its budget
+    pub fn add_group_regressions(
+        &mut self,
+        module_path: ModulePath,
+        regressions: Vec<ToolRegression>,
+    ) {
+        self.group_regressions.push((module_path, regressions));
+    }
+
     /// Add another `BenchmarkSummary`
     ///
     /// Ignores the execution time.
@@ -268,11 +344,90 @@ impl BenchmarkSummaries {
         other.summaries.into_iter().for_each(|s| {
             self.add_summary(s);
         });
+        self.group_regressions.extend(other.group_regressions);
+        self.num_errored += other.num_errored;
+        self.num_skipped += other.num_skipped;
+        self.num_unchanged += other.num_unchanged;
+        if other.error.is_some() {
+            self.error = other.error;
+        }
+    }
+
+    /// Abort the benchmark run with the `error` of the current benchmark
+    ///
+    /// A fail-fast [`Error::RegressionError`] is the result of the regression check of an already
+    /// added [`BenchmarkSummary`], so only other errors count as errored benchmark.
+    pub fn abort(&mut self, error: anyhow::Error) {
+        if !matches!(
+            error.downcast_ref::<Error>(),
+            Some(Error::RegressionError(true))
+        ) {
+            self.num_errored += 1;
+        }
+        self.error = Some(error);
+    }
+
+    /// Return the exit code of the benchmark run as configured by the [`ExitCodes`]
+    ///
+    /// The first outcome class in the order errored, regressed and skipped with benchmarks and a
+    /// non-zero exit code determines the exit code. Benchmarks which failed because of the errors
+    /// detected by an error checking tool with `fail_on_errors` count as errored and groups which
+    /// exceeded their regression budget as regressed. If all benchmarks passed, the exit code is
+    /// `0`.
+    pub fn exit_code(&self, exit_codes: ExitCodes) -> u8 {
+        [
+            (self.num_errored + self.num_failed(), exit_codes.errored),
+            (
+                self.num_regressed() + self.group_regressions.len(),
+                exit_codes.regressed,
+            ),
+            (self.num_skipped, exit_codes.skipped),
+        ]
+        .into_iter()
+        .find_map(|(num, code)| (num > 0 && code != 0).then_some(code))
+        .unwrap_or(0)
+    }
+
+    /// Return true if any benchmark has detected errors of an error checking tool
+    pub fn has_errors(&self) -> bool {
+        self.summaries.iter().any(BenchmarkSummary::has_errors)
     }
 
-    /// Return true if any regressions were encountered
+    /// Return true if the benchmark run was aborted
+    pub fn is_aborted(&self) -> bool {
+        self.error.is_some()
+    }
+
+    /// Return true if any regressions were encountered including the regressions of groups
     pub fn is_regressed(&self) -> bool {
         self.summaries.iter().any(BenchmarkSummary::is_regressed)
+            || !self.group_regressions.is_empty()
+    }
+
+    /// Return the number of benchmarks which failed because of the errors of an error checking
+    /// tool with `fail_on_errors`
+    pub fn num_failed(&self) -> usize {
+        self.summaries.iter().filter(|s| s.is_failed()).count()
+    }
+
+    /// Return the number of benchmarks which passed without regressions and failing errors
+    pub fn num_passed(&self) -> usize {
+        self.summaries.len() - self.num_regressed() - self.num_failed()
+    }
+
+    /// Return the number of regressed benchmarks
+    ///
+    /// Regressed benchmarks which failed because of errors are counted as failed.
+    pub fn num_regressed(&self) -> usize {
+        self.summaries
+            .iter()
+            .filter(|s| s.is_regressed() && !s.is_failed())
+            .count()
+    }
+
+    /// Count all benchmarks of the total `num_benchmarks` which weren't run as skipped
+    pub fn skip_remaining(&mut self, num_benchmarks: usize) {
+        self.num_skipped = num_benchmarks.saturating_sub(self.num_benchmarks());
     }
 
     /// Set the total execution from `start` to `now`
@@ -280,22 +435,46 @@ impl BenchmarkSummaries {
         self.total_time = Some(start.elapsed());
     }
 
-    /// Return the number of total benchmarks
+    /// Return the number of total benchmarks which were run including the errored benchmarks
     pub fn num_benchmarks(&self) -> usize {
-        self.summaries.len()
+        self.summaries.len() + self.num_errored
     }
 
     /// Print the summary if not prevented by command-line arguments
     ///
     /// If `nosummary` is true or [`OutputFormatKind`] is any kind of `JSON` format the summary is
-    /// not printed.
-    pub fn print(&self, nosummary: bool, output_format_kind: OutputFormatKind) {
+    /// not printed. The [`DeltaStyle`] is the style of the differences of soft regressions and the
+    /// regressed benchmarks are printed in the order of [`Sort`]. The number of benchmarks whose
+    /// terminal output was hidden because it was unchanged is printed regardless of `nosummary`.
+    pub fn print(
+        &self,
+        nosummary: bool,
+        output_format_kind: OutputFormatKind,
+        delta_style: Option<DeltaStyle>,
+        sort_by: Sort,
+    ) {
+        if self.num_unchanged > 0 && output_format_kind == OutputFormatKind::Default {
+            print_unchanged(self.num_unchanged);
+        }
         if !nosummary {
-            SummaryFormatter::new(output_format_kind).print(self);
+            SummaryFormatter::new(output_format_kind, delta_style, sort_by).print(self);
         }
     }
 }
 
+impl Config {
+    /// Return the [`GitInfo`] for a [`BenchmarkSummary`] with the `summary_output`
+    ///
+    /// The git metadata is only detected if the summary is serialized, so if it is printed as json,
+    /// saved to the `summary_output` or appended to the summary stream.
+    pub fn summary_git(&self, summary_output: Option<&SummaryOutput>) -> Option<GitInfo> {
+        let is_serialized = summary_output.is_some()
+            || self.summary_stream.is_some()
+            || self.meta.args.output_format != OutputFormatKind::Default;
+        is_serialized.then(|| self.meta.git().cloned()).flatten()
+    }
+}
+
 impl ModulePath {
     /// Create a new `ModulePath`
     ///
@@ -406,6 +585,26 @@ impl Sandbox {
         })
     }
 
+    /// Return the path of the sandbox directory if the sandbox is enabled
+    pub fn dir(&self) -> Option<&Path> {
+        self.temp_dir.as_ref().map(TempDir::path)
+    }
+
+    /// Reset the current directory but keep the temporary directory if present
+    ///
+    /// Returns the path of the kept temporary directory.
+    pub fn keep(self) -> Result<Option<PathBuf>> {
+        let Some(temp_dir) = self.temp_dir else {
+            return Ok(None);
+        };
+
+        std::env::set_current_dir(&self.current_dir).map_err(|error| {
+            Error::SandboxError(format!("Failed to reset current directory: {error}"))
+        })?;
+
+        Ok(Some(temp_dir.keep()))
+    }
+
     /// Reset the current directory and delete the temporary directory if present
     pub fn reset(self) -> Result<()> {
         if let Some(temp_dir) = self.temp_dir {
@@ -438,6 +637,7 @@ mod tests {
     use rstest::rstest;
 
     use super::*;
+    use crate::runner::metrics::{Metric, MetricKind};
 
     #[rstest]
     #[case::empty("", None)]
@@ -450,4 +650,42 @@ mod tests {
 
         assert_eq!(actual, expected);
     }
+
+    #[rstest]
+    #[case::passed(0, 0, ExitCodes::default(), 0)]
+    #[case::errored(1, 2, ExitCodes::default(), 1)]
+    #[case::skipped(0, 2, ExitCodes { skipped: 4, ..Default::default() }, 4)]
+    #[case::errored_before_skipped(1, 2, ExitCodes { skipped: 4, ..Default::default() }, 1)]
+    #[case::errored_zero(1, 2, ExitCodes { errored: 0, skipped: 4, ..Default::default() }, 4)]
+    fn test_benchmark_summaries_exit_code(
+        #[case] num_errored: usize,
+        #[case] num_skipped: usize,
+        #[case] exit_codes: ExitCodes,
+        #[case] expected: u8,
+    ) {
+        let summaries = BenchmarkSummaries {
+            num_errored,
+            num_skipped,
+            ..Default::default()
+        };
+        assert_eq!(summaries.exit_code(exit_codes), expected);
+    }
+
+    #[test]
+    fn test_benchmark_summaries_exit_code_when_group_regressed() {
+        let mut summaries = BenchmarkSummaries::default();
+        summaries.add_group_regressions(
+            ModulePath::new("bench::group"),
+            vec![ToolRegression::Soft {
+                metric: MetricKind::Callgrind(EventKind::Ir),
+                new: Metric::Int(110),
+                old: Metric::Int(100),
+                diff_pct: 10.0,
+                limit: 3.0,
+            }],
+        );
+
+        assert!(summaries.is_regressed());
+        assert_eq!(summaries.exit_code(ExitCodes::default()), 3);
+    }
 }
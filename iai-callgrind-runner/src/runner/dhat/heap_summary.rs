@@ -0,0 +1,277 @@
+//! The module containing the [`HeapSummary`] of the `--dhat-heap-summary` argument
+//!
+//! DHAT doesn't record the size of the heap over the whole run of the benchmark but only at the
+//! time of the global maximum (t-gmax) and at the end of the program (t-end). So, instead of a
+//! timeline, the [`HeapSummary`] draws these values as bars and marks the time of t-gmax on the
+//! time axis. The svg file is written next to the DHAT output files, so the memory behaviour of a
+//! benchmark is visible without loading the output files into `dh_view.html`.
+
+use std::fmt::Write as _;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+
+use super::json_parser::parse;
+use super::model::{DhatData, Mode};
+use crate::runner::report::escape_html;
+use crate::runner::tool::path::ToolOutputPath;
+
+/// The height of a bar
+const BAR_HEIGHT: f64 = 20.0;
+/// The x coordinate of the start of the bars and the time axis
+const BAR_LEFT: f64 = 90.0;
+/// The distance between the tops of two bars
+const BAR_STEP: f64 = 30.0;
+/// The maximum width of a bar and the width of the time axis
+const BAR_WIDTH: f64 = 500.0;
+/// The y coordinate of the top of the first bar
+const BARS_TOP: f64 = 40.0;
+/// The height of the chart
+const CHART_HEIGHT: f64 = 200.0;
+/// The width of the chart
+const CHART_WIDTH: f64 = 720.0;
+/// The stylesheet of the chart
+const STYLE: &str = "\
+.bar { fill: #1f77b4; fill-opacity: 0.6; }
+.gmax { stroke: #d62728; stroke-width: 2; }
+.axis { stroke: #999; stroke-width: 1; }
+text { font-family: sans-serif; font-size: 11px; fill: #555; }
+.title { font-size: 14px; fill: #222; }
+";
+
+/// The summary of the heap of a single DHAT output file
+///
+/// This is not a timeline of the heap usage. DHAT only records the heap size at t-gmax and t-end.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeapSummary {
+    /// The bytes on the heap at t-end
+    pub bytes_at_end: u64,
+    /// The bytes on the heap at t-gmax
+    pub bytes_at_max: u64,
+    /// The time at the end of the program (t-end)
+    pub time_end: u128,
+    /// The time of the global maximum (t-gmax)
+    pub time_global_max: u128,
+    /// The unit of the time, usually `instrs`
+    pub time_unit: String,
+    /// The total bytes allocated during the whole run
+    pub total_bytes: u64,
+}
+
+impl HeapSummary {
+    /// Create a new `HeapSummary` from the [`DhatData`]
+    ///
+    /// Returns `None` if DHAT didn't run in heap mode, since the heap size at t-gmax is only
+    /// available in this mode.
+    pub fn new(data: &DhatData) -> Option<Self> {
+        if data.mode != Mode::Heap {
+            return None;
+        }
+
+        let time_global_max = data.time_global_max?;
+        let (total_bytes, bytes_at_max, bytes_at_end) = data.program_points.iter().fold(
+            (0, 0, 0),
+            |(total_bytes, bytes_at_max, bytes_at_end), program_point| {
+                (
+                    total_bytes + program_point.total_bytes,
+                    bytes_at_max + program_point.bytes_at_max.unwrap_or(0),
+                    bytes_at_end + program_point.bytes_at_end.unwrap_or(0),
+                )
+            },
+        );
+
+        Some(Self {
+            bytes_at_end,
+            bytes_at_max,
+            time_end: data.time_end,
+            time_global_max,
+            time_unit: data.time_unit.clone(),
+            total_bytes,
+        })
+    }
+
+    /// Render this `HeapSummary` with the `title` as standalone svg document
+    ///
+    /// The total bytes and the bytes at t-gmax and t-end are drawn as bars relative to the largest
+    /// value. Below the bars, the time of t-gmax is marked on the time axis from the start of the
+    /// program to t-end.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn render(&self, title: &str) -> String {
+        let bars = [
+            ("Total", self.total_bytes),
+            ("At t-gmax", self.bytes_at_max),
+            ("At t-end", self.bytes_at_end),
+        ];
+
+        // Avoid a division by zero if there is no time or no heap usage at all
+        let max_bytes = bars
+            .iter()
+            .map(|(_, bytes)| *bytes)
+            .max()
+            .unwrap_or(0)
+            .max(1) as f64;
+        let time_end = self.time_end.max(1) as f64;
+
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{CHART_WIDTH}\" \
+             height=\"{CHART_HEIGHT}\" viewBox=\"0 0 {CHART_WIDTH} {CHART_HEIGHT}\">\n<style>\n\
+             {STYLE}</style>\n"
+        );
+        writeln!(
+            svg,
+            "<text class=\"title\" x=\"10\" y=\"20\">{}</text>",
+            escape_html(title)
+        )
+        .unwrap();
+
+        let mut top = BARS_TOP;
+        for (label, bytes) in bars {
+            let width = bytes as f64 / max_bytes * BAR_WIDTH;
+            let text_y = top + 14.0;
+            writeln!(
+                svg,
+                "<text x=\"10\" y=\"{text_y}\">{label}</text>\n<rect class=\"bar\" \
+                 x=\"{BAR_LEFT}\" y=\"{top}\" width=\"{width:.1}\" height=\"{BAR_HEIGHT}\">\
+                 <title>{label}: {bytes} bytes</title></rect>\n<text x=\"{:.1}\" \
+                 y=\"{text_y}\">{bytes} bytes</text>",
+                BAR_LEFT + width + 4.0
+            )
+            .unwrap();
+            top += BAR_STEP;
+        }
+
+        let axis_y = top + 20.0;
+        let axis_right = BAR_LEFT + BAR_WIDTH;
+        let gmax_x = (self.time_global_max as f64 / time_end).mul_add(BAR_WIDTH, BAR_LEFT);
+        writeln!(
+            svg,
+            "<text x=\"10\" y=\"{:.1}\">Time</text>\n<line class=\"axis\" x1=\"{BAR_LEFT}\" \
+             y1=\"{axis_y}\" x2=\"{axis_right}\" y2=\"{axis_y}\"/>\n<line class=\"gmax\" \
+             x1=\"{gmax_x:.1}\" y1=\"{:.1}\" x2=\"{gmax_x:.1}\" y2=\"{:.1}\"><title>t-gmax</title>\
+             </line>\n<text x=\"{BAR_LEFT}\" y=\"{:.1}\">0</text>\n<text x=\"{axis_right}\" \
+             y=\"{:.1}\" text-anchor=\"end\">t-end: {} {unit}</text>\n<text x=\"{gmax_x:.1}\" \
+             y=\"{:.1}\" text-anchor=\"middle\">t-gmax: {} {unit}</text>",
+            axis_y + 4.0,
+            axis_y - 6.0,
+            axis_y + 6.0,
+            axis_y + 20.0,
+            axis_y + 20.0,
+            self.time_end,
+            axis_y - 10.0,
+            self.time_global_max,
+            unit = escape_html(&self.time_unit)
+        )
+        .unwrap();
+        svg.push_str("</svg>\n");
+
+        svg
+    }
+}
+
+/// Create the [`HeapSummary`]s of all DHAT output files of the `output_path`
+///
+/// The heap summary of an output file `dhat.<name>.out` is written to
+/// `dhat.<name>.heap-summary.svg` and for baselines (`dhat.<name>.out.base@<baseline>`) to
+/// `dhat.<name>.heap-summary.base@<baseline>.svg`. The modifiers of the output files (for example
+/// the pid) are preserved. Output files without heap data are skipped.
+///
+/// # Errors
+///
+/// Returns an error if an output file could not be parsed or the heap summary could not be written
+pub fn create(output_path: &ToolOutputPath, title: &str) -> Result<Vec<PathBuf>> {
+    let mut paths = vec![];
+    for path in output_path.real_paths()? {
+        let data = parse(&path)?;
+        let Some(heap_summary) = HeapSummary::new(&data) else {
+            continue;
+        };
+
+        let svg_path = output_path.to_artifact_path(&path, "heap-summary", "svg");
+        std::fs::write(&svg_path, heap_summary.render(title)).with_context(|| {
+            format!("Failed to write dhat heap summary '{}'", svg_path.display())
+        })?;
+        paths.push(svg_path);
+    }
+
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::*;
+
+    fn heap_summary() -> HeapSummary {
+        HeapSummary {
+            bytes_at_end: 0,
+            bytes_at_max: 1000,
+            time_end: 200,
+            time_global_max: 100,
+            time_unit: "instrs".to_owned(),
+            total_bytes: 2000,
+        }
+    }
+
+    #[rstest]
+    #[case::heap("dhat.minimal.out", Some((1024, 1024, 0, 160_000, 500_000)))]
+    #[case::ad_hoc("dhat.ad_hoc_mode.out", None)]
+    #[case::copy("dhat.copy_mode.out", None)]
+    fn test_heap_summary_new(
+        #[case] fixture: &str,
+        #[case] expected: Option<(u64, u64, u64, u128, u128)>,
+    ) {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/dhat")
+            .join(fixture);
+        let expected = expected.map(
+            |(total_bytes, bytes_at_max, bytes_at_end, time_global_max, time_end)| HeapSummary {
+                bytes_at_end,
+                bytes_at_max,
+                time_end,
+                time_global_max,
+                time_unit: "instrs".to_owned(),
+                total_bytes,
+            },
+        );
+
+        assert_eq!(HeapSummary::new(&parse(&path).unwrap()), expected);
+    }
+
+    #[test]
+    fn test_heap_summary_render() {
+        let svg = heap_summary().render("some<bench>");
+
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.ends_with("</svg>\n"));
+        assert!(svg.contains("some&lt;bench&gt;"));
+        assert!(svg.contains("x=\"90\" y=\"40\" width=\"500.0\" height=\"20\"><title>Total: 2000"));
+        assert!(svg.contains("x=\"90\" y=\"70\" width=\"250.0\" height=\"20\"><title>At t-gmax"));
+        assert!(svg.contains("x=\"90\" y=\"100\" width=\"0.0\" height=\"20\"><title>At t-end"));
+        assert!(svg.contains("x1=\"340.0\" y1=\"144.0\" x2=\"340.0\" y2=\"156.0\""));
+        assert!(svg.contains("t-gmax: 100 instrs"));
+        assert!(svg.contains("t-end: 200 instrs"));
+        // The heap size is not interpolated between t-gmax and t-end
+        assert!(!svg.contains("<polygon"));
+        assert!(!svg.contains("<polyline"));
+    }
+
+    #[test]
+    fn test_heap_summary_render_when_empty() {
+        let heap_summary = HeapSummary {
+            bytes_at_end: 0,
+            bytes_at_max: 0,
+            time_end: 0,
+            time_global_max: 0,
+            time_unit: "instrs".to_owned(),
+            total_bytes: 0,
+        };
+        let svg = heap_summary.render("empty");
+
+        assert!(!svg.contains("NaN"));
+        assert!(!svg.contains("inf"));
+    }
+}
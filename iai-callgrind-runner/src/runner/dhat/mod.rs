@@ -1,5 +1,6 @@
 //! The dhat module
 
+pub mod heap_summary;
 pub mod json_parser;
 pub mod logfile_parser;
 pub mod model;
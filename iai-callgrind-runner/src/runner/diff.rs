@@ -0,0 +1,246 @@
+//! The module containing the `--diff` mode of the runner
+//!
+//! In this mode, two previously saved `summary.json` files (or directories containing them) are
+//! compared with each other without running any benchmark. The comparison is printed in the usual
+//! terminal output format and regressions are checked with the limits from the command-line.
+
+use std::ffi::OsString;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use anyhow::{anyhow, Context, Result};
+use clap::Parser;
+use glob::glob;
+use log::warn;
+
+use super::args::CommandLineArgs;
+use super::common::{BenchmarkSummaries, ModulePath};
+use super::format::{Formatter, Header, OutputFormat, VerticalFormatter};
+use super::summary::{BenchmarkSummary, ProfileTotal, ToolMetricSummary};
+use super::tool::config::ToolConfigs;
+use super::tool::regression::ToolRegressionConfig;
+use crate::api::ValgrindTool;
+use crate::error::Error;
+
+/// The arguments of the diff mode: `iai-callgrind-runner --diff OLD NEW [OPTIONS]`
+#[derive(Debug, Clone)]
+pub struct DiffArgs {
+    /// The [`CommandLineArgs`] like `--callgrind-limits`, `--output-format`, ...
+    pub args: CommandLineArgs,
+    /// The path to the new `summary.json` file or a directory containing `summary.json` files
+    pub new: PathBuf,
+    /// The path to the old `summary.json` file or a directory containing `summary.json` files
+    pub old: PathBuf,
+}
+
+impl DiffArgs {
+    /// Create new `DiffArgs` from the arguments following `--diff`
+    ///
+    /// The first two arguments are the paths to the old and new summaries. All other arguments are
+    /// parsed as [`CommandLineArgs`] exiting the process on parse errors.
+    pub fn from_args<I, T>(args: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<OsString>,
+    {
+        let mut args = args.into_iter().map(Into::into);
+        let old = args
+            .next()
+            .map(PathBuf::from)
+            .ok_or_else(|| anyhow!("--diff: Missing path to the old summary"))?;
+        let new = args
+            .next()
+            .map(PathBuf::from)
+            .ok_or_else(|| anyhow!("--diff: Missing path to the new summary"))?;
+
+        Ok(Self {
+            args: CommandLineArgs::parse_from(args),
+            new,
+            old,
+        })
+    }
+
+    /// Return the [`ToolRegressionConfig`] for the `tool` from the command-line limits
+    fn regression_config(&self, tool: ValgrindTool) -> ToolRegressionConfig {
+        let mut regression_config = match tool {
+            ValgrindTool::Callgrind => self.args.callgrind_limits.clone(),
+            ValgrindTool::Cachegrind => self.args.cachegrind_limits.clone(),
+            ValgrindTool::DHAT => self.args.dhat_limits.clone(),
+            _ => None,
+        }
+        .unwrap_or(ToolRegressionConfig::None);
+
+        if let Some(fail_fast) = self.args.regression_fail_fast {
+            match &mut regression_config {
+                ToolRegressionConfig::Callgrind(config) => config.fail_fast = fail_fast,
+                ToolRegressionConfig::Cachegrind(config) => config.fail_fast = fail_fast,
+                ToolRegressionConfig::Dhat(config) => config.fail_fast = fail_fast,
+                ToolRegressionConfig::None => {}
+            }
+        }
+
+        regression_config
+    }
+}
+
+/// Compare the old and new summaries of the [`DiffArgs`], print the comparison and return the
+/// resulting [`BenchmarkSummaries`]
+///
+/// Summaries are paired by their module path and id. The profiles of the returned summaries contain
+/// only the total comparing the new with the old metrics and the detected regressions if any. New
+/// summaries without an old counterpart are skipped with a warning.
+///
+/// # Errors
+///
+/// Returns an error if the summaries can't be read or if a regression occurred which is configured
+/// to be `fail_fast`
+pub fn diff(diff_args: &DiffArgs) -> Result<BenchmarkSummaries> {
+    let start = Instant::now();
+
+    let old_summaries = load_summaries(&diff_args.old)?;
+    let new_summaries = load_summaries(&diff_args.new)?;
+
+    let mut output_format = OutputFormat {
+        kind: diff_args.args.output_format,
+        ..Default::default()
+    };
+    output_format.update_from_args(&diff_args.args);
+
+    let mut benchmark_summaries = BenchmarkSummaries::default();
+    for mut new in new_summaries {
+        let Some(old) = old_summaries
+            .iter()
+            .find(|old| old.module_path == new.module_path && old.id == new.id)
+        else {
+            warn!(
+                "No old summary found for '{}{}'. Skipping...",
+                new.module_path,
+                new.id
+                    .as_ref()
+                    .map_or_else(String::new, |id| format!(" {id}"))
+            );
+            continue;
+        };
+
+        if output_format.is_default() {
+            Header::new(
+                &ModulePath::new(&new.module_path),
+                new.id.clone(),
+                new.details.clone(),
+                &output_format,
+            )
+            .print();
+        }
+
+        let baselines = (new.baselines.0.clone(), old.baselines.0.clone());
+        let has_multiple = new.profiles.0.len() > 1;
+        let mut fail_fast = false;
+        for profile in &mut new.profiles.0 {
+            let Some(summary) = old
+                .profiles
+                .iter()
+                .find(|p| p.tool == profile.tool)
+                .and_then(|old_profile| {
+                    ToolMetricSummary::from_self_and_other(
+                        &profile.summaries.total.summary,
+                        &old_profile.summaries.total.summary,
+                    )
+                })
+            else {
+                continue;
+            };
+
+            let mut formatter = VerticalFormatter::new(output_format.clone());
+            if output_format.is_default() {
+                if has_multiple || profile.tool != ValgrindTool::Callgrind {
+                    formatter.format_tool_headline(profile.tool);
+                }
+                formatter.format_single(
+                    profile.tool,
+                    &baselines,
+                    None,
+                    &summary,
+                    profile.tool == ValgrindTool::Callgrind,
+                )?;
+                formatter.print_buffer();
+            }
+
+            let regression_config = diff_args.regression_config(profile.tool);
+            fail_fast |= regression_config.is_fail_fast();
+
+            let mut total = ProfileTotal {
+                regressions: vec![],
+                summary,
+            };
+            total.regressions =
+                ToolConfigs::check_and_print_regressions(&regression_config, &total);
+
+            profile.summaries.parts.clear();
+            profile.summaries.total = total;
+        }
+
+        new.baselines = baselines;
+        new.summary_output = None;
+        new.print_and_save(&output_format.kind)?;
+        new.check_regression(fail_fast)?;
+
+        benchmark_summaries.add_summary(new);
+    }
+
+    benchmark_summaries.elapsed(start);
+    Ok(benchmark_summaries)
+}
+
+/// Load all `summary.json` files from the `path`
+///
+/// If the `path` is a directory, it is searched recursively for `summary.json` files.
+///
+/// # Errors
+///
+/// Returns an error if the directory can't be searched or a summary file can't be read
+pub fn load_summaries(path: &Path) -> Result<Vec<BenchmarkSummary>> {
+    if path.is_dir() {
+        let pattern = path.join("**").join("summary.json");
+        let mut summaries = vec![];
+        for entry in glob(&pattern.to_string_lossy())
+            .with_context(|| format!("Invalid summary directory: '{}'", path.display()))?
+        {
+            let entry = entry.context("Failed to read summary directory")?;
+            summaries.push(load_summary(&entry)?);
+        }
+        Ok(summaries)
+    } else {
+        load_summary(path).map(|summary| vec![summary])
+    }
+}
+
+/// Load a single `summary.json` file
+fn load_summary(path: &Path) -> Result<BenchmarkSummary> {
+    let file = File::open(path)
+        .with_context(|| format!("Failed to open summary file: '{}'", path.display()))?;
+    serde_json::from_reader(BufReader::new(file))
+        .with_context(|| format!("Failed to deserialize summary file: '{}'", path.display()))
+}
+
+/// Run the diff mode
+///
+/// This is the entry point of `iai-callgrind-runner --diff OLD NEW [OPTIONS]`. After the
+/// comparison, the summary line is printed and an [`Error::ExitCodeError`] is returned if there
+/// were any regressions and `--exit-codes` maps them to a non-zero exit code.
+pub fn run() -> Result<()> {
+    let diff_args = DiffArgs::from_args(std::env::args_os().skip(2))?;
+    let summaries = diff(&diff_args)?;
+    summaries.print(
+        diff_args.args.nosummary,
+        diff_args.args.output_format,
+        diff_args.args.delta_style,
+        diff_args.args.sort_by.unwrap_or_default(),
+    );
+
+    match summaries.exit_code(diff_args.args.exit_codes) {
+        0 => Ok(()),
+        exit_code => Err(Error::ExitCodeError(exit_code).into()),
+    }
+}
@@ -3,6 +3,7 @@
 //! All direct print statements should be part of this module and there should be no `println!` or
 //! similar statement in any other module of the runner.
 use std::borrow::Cow;
+use std::ffi::OsString;
 use std::fmt::{Display, Write};
 use std::path::PathBuf;
 
@@ -11,19 +12,24 @@ use colored::{Color, ColoredString, Colorize};
 use either_or_both::EitherOrBoth;
 use indexmap::{indexset, IndexSet};
 
-use super::args::NoCapture;
+use super::args::{CommandLineArgs, MeasurementProfile, NoCapture};
 use super::bin_bench::BinBench;
 use super::common::{Baselines, BenchmarkSummaries, Config, ModulePath};
 use super::lib_bench::LibBench;
 use super::meta::Metadata;
 use super::metrics::{Metric, MetricKind, MetricsDiff};
-use super::summary::{Diffs, ProfileData, ProfileInfo, ToolMetricSummary, ToolRegression};
+use super::summary::{
+    BenchmarkSummary, Diffs, EnvironmentInfo, ProfileData, ProfileInfo, ResourceUsage,
+    ToolMetricSummary, ToolRegression,
+};
 use crate::api::{
-    self, CachegrindMetric, CachegrindMetrics, CallgrindMetrics, DhatMetric, DhatMetrics,
-    ErrorMetric, EventKind, Tool, ToolOutputFormat, ValgrindTool,
+    self, CachegrindMetric, CachegrindMetrics, CallgrindMetrics, ColorThresholds, DeltaStyle,
+    DhatMetric, DhatMetrics, EntryPoint, ErrorMetric, EventKind, Sort, Tool, ToolOutputFormat,
+    TruncatePosition, ValgrindTool,
 };
 use crate::util::{
-    make_relative, to_string_signed_short, to_string_unsigned_short, truncate_str_utf8,
+    format_timestamp, make_relative, to_ascii, to_string_signed_precision, to_string_signed_short,
+    to_string_unsigned_short, truncate_str_utf8, truncate_str_utf8_start,
 };
 
 /// The width in bytes of the difference (and factor)
@@ -34,6 +40,8 @@ pub const FIELD_WIDTH: usize = 21;
 pub const FLOAT_WIDTH: usize = DIFF_WIDTH - 1;
 /// The width in bytes of the "left" side of the separator `|`
 pub const LEFT_WIDTH: usize = METRIC_WIDTH + FIELD_WIDTH;
+/// The default and maximum number of fractional-part digits of the differences
+pub const MAX_PRECISION: usize = 5;
 #[allow(clippy::doc_link_with_quotes)]
 /// The maximum line width
 ///
@@ -100,42 +108,78 @@ pub struct Header {
 pub struct LibraryBenchmarkHeader {
     inner: Header,
     output_format: OutputFormat,
+    user_description: Option<String>,
 }
 
 /// The `OutputFormat` of the Iai-Callgrind terminal output
+#[allow(clippy::struct_excessive_bools)]
 #[derive(Debug, Clone, PartialEq)]
 pub struct OutputFormat {
+    /// Replace all non-ASCII characters in the terminal output
+    pub ascii: bool,
     /// The Cachegrind metrics to show
     pub cachegrind: IndexSet<CachegrindMetric>,
     /// The Callgrind metrics to show
     pub callgrind: IndexSet<EventKind>,
+    /// The thresholds at which the differences change the color
+    pub color_thresholds: ColorThresholds,
+    /// The style of the differences between the new and the old metrics
+    pub delta_style: DeltaStyle,
     /// The DHAT metrics to show
     pub dhat: IndexSet<DhatMetric>,
     /// The DRD error metrics to show
     pub drd: IndexSet<ErrorMetric>,
     /// The Helgrind error metrics to show
     pub helgrind: IndexSet<ErrorMetric>,
+    /// Hide the output of benchmarks with unchanged metrics
+    pub hide_unchanged: bool,
     /// The [`OutputFormatKind`]
     pub kind: OutputFormatKind,
     /// The Memcheck error metrics to show
     pub memcheck: IndexSet<ErrorMetric>,
+    /// The maximum number of fractional-part digits of the differences
+    pub precision: usize,
+    /// Show the absolute difference between the new and the old metrics
+    pub show_absolute_diff: bool,
+    /// Show the call tree of the functions with the inclusive instructions (callgrind)
+    pub show_call_tree: bool,
     /// Show a grid instead of blank spaces
     pub show_grid: bool,
+    /// Show the summed up primary metrics of the benchmarks of a library benchmark group
+    pub show_group_totals: bool,
     /// Show intermediate metrics output or just the total
     pub show_intermediate: bool,
+    /// Show the lines of the entry point with the most instructions (callgrind)
+    pub show_lines: bool,
     /// Show only the comparison between different benchmarks when `compare_by_id` is given
     pub show_only_comparison: bool,
+    /// Show the page faults and context switches of the valgrind processes
+    pub show_resource_usage: bool,
+    /// Show the valgrind version and the resolved arguments of each tool
+    pub show_tool_args: bool,
+    /// Show this amount of functions with the most instructions (callgrind)
+    pub show_top_functions: usize,
+    /// Subtract the overhead of the benchmark harness from the callgrind metrics
+    pub subtract_overhead: bool,
     /// Don't show differences within the tolerance margin
     pub tolerance: Option<f64>,
     /// If present truncate the description to this amount of bytes
     pub truncate_description: Option<usize>,
+    /// The ellipsis which replaces the truncated part of the description
+    pub truncate_ellipsis: String,
+    /// The position at which the description is truncated
+    pub truncate_position: TruncatePosition,
 }
 
 /// The formatter of the benchmark summary printed after all benchmarks
 #[derive(Debug, Clone)]
 pub struct SummaryFormatter {
+    /// The [`DeltaStyle`] if given on the command-line
+    pub delta_style: Option<DeltaStyle>,
     /// The [`OutputFormatKind`]
     pub output_format_kind: OutputFormatKind,
+    /// The order of the regressed benchmarks
+    pub sort_by: Sort,
 }
 
 /// The main implementation of the [`Formatter`] trait
@@ -207,6 +251,14 @@ pub trait Formatter {
         details: Option<&str>,
         summaries: Vec<(ValgrindTool, ToolMetricSummary)>,
     ) -> Result<()>;
+
+    /// Print the summed up primary metrics of all benchmarks of a group
+    fn print_group_totals(
+        &mut self,
+        module_path: &ModulePath,
+        num_benchmarks: usize,
+        summaries: Vec<(ValgrindTool, ToolMetricSummary)>,
+    ) -> Result<()>;
 }
 
 impl BinaryBenchmarkHeader {
@@ -249,8 +301,18 @@ impl BinaryBenchmarkHeader {
     }
 
     /// Print the header
+    ///
+    /// With [`OutputFormat::hide_unchanged`], the header is printed with
+    /// [`BinaryBenchmarkHeader::print_deferred`] instead.
     pub fn print(&self) {
-        if self.output_format.kind == OutputFormatKind::Default {
+        if self.output_format.is_default() && !self.output_format.hide_unchanged {
+            self.inner.print();
+        }
+    }
+
+    /// Print the header held back by [`OutputFormat::hide_unchanged`]
+    pub fn print_deferred(&self) {
+        if self.output_format.is_default() && self.output_format.hide_unchanged {
             self.inner.print();
         }
     }
@@ -279,10 +341,11 @@ impl ComparisonHeader {
         U: Into<String>,
         V: Into<String>,
     {
+        let sanitize = |string: String| output_format.sanitize(&string).into_owned();
         Self {
-            function_name: function_name.into(),
-            id: id.into(),
-            details: details.map(Into::into),
+            function_name: sanitize(function_name.into()),
+            id: sanitize(id.into()),
+            details: details.map(|details| sanitize(details.into())),
             indent: if output_format.show_grid {
                 "|-".bright_black().to_string()
             } else {
@@ -327,12 +390,21 @@ impl Header {
     where
         T: Into<Option<String>>,
     {
-        let truncated = description
-            .map(|d| truncate_description(&d, output_format.truncate_description).to_string());
+        let truncated = description.map(|d| {
+            let truncated = truncate_description(
+                &d,
+                output_format.truncate_description,
+                output_format.truncate_position,
+                &output_format.truncate_ellipsis,
+            );
+            output_format.sanitize(&truncated).into_owned()
+        });
 
         Self {
-            module_path: module_path.to_string(),
-            id: id.into(),
+            module_path: output_format
+                .sanitize(&module_path.to_string())
+                .into_owned(),
+            id: id.into().map(|id| output_format.sanitize(&id).into_owned()),
             description: truncated,
         }
     }
@@ -416,13 +488,43 @@ impl LibraryBenchmarkHeader {
         Self {
             inner: header,
             output_format: lib_bench.output_format.clone(),
+            user_description: lib_bench
+                .description
+                .as_ref()
+                .map(|description| lib_bench.output_format.sanitize(description).into_owned()),
         }
     }
 
     /// Print the header
+    ///
+    /// The user provided description of the benchmark is printed on a separate line below the
+    /// header if present. With [`OutputFormat::hide_unchanged`], the header is printed with
+    /// [`LibraryBenchmarkHeader::print_deferred`] instead.
     pub fn print(&self) {
+        if self.output_format.is_default() && !self.output_format.hide_unchanged {
+            self.print_header();
+        }
+    }
+
+    /// Print the header held back by [`OutputFormat::hide_unchanged`]
+    pub fn print_deferred(&self) {
+        if self.output_format.is_default() && self.output_format.hide_unchanged {
+            self.print_header();
+        }
+    }
+
+    /// Print the header followed by the `skipped ({reason})` status
+    pub fn print_skipped(&self, reason: &str) {
         if self.output_format.is_default() {
-            self.inner.print();
+            self.print_header();
+            println!("  {}", format!("skipped ({reason})").bright_black());
+        }
+    }
+
+    fn print_header(&self) {
+        self.inner.print();
+        if let Some(description) = &self.user_description {
+            println!("  {}", description.italic());
         }
     }
 
@@ -443,11 +545,28 @@ impl OutputFormat {
         self.kind == OutputFormatKind::Default
     }
 
+    /// Return true if the terminal output of the benchmark with the `summary` is hidden
+    ///
+    /// Only the output of benchmarks with unchanged metrics is hidden and only if
+    /// [`OutputFormat::hide_unchanged`] is true.
+    pub fn is_hidden(&self, summary: &BenchmarkSummary) -> bool {
+        self.is_default() && self.hide_unchanged && summary.is_unchanged()
+    }
+
     /// Return true if the `OutputFormat` is json
     pub fn is_json(&self) -> bool {
         self.kind == OutputFormatKind::Json || self.kind == OutputFormatKind::PrettyJson
     }
 
+    /// Return the `string` with all non-ASCII characters replaced if `ascii` is true
+    pub fn sanitize<'a>(&self, string: &'a str) -> Cow<'a, str> {
+        if self.ascii {
+            to_ascii(string)
+        } else {
+            Cow::Borrowed(string)
+        }
+    }
+
     /// Update the output format from the [`Tool`] if present
     pub fn update(&mut self, tool: Option<&Tool>) {
         if let Some(tool) = tool {
@@ -483,46 +602,102 @@ impl OutputFormat {
         }
     }
 
-    /// Update the output format with data from command-line arguments in [`Metadata`]
-    pub fn update_from_meta(&mut self, meta: &Metadata) {
-        if let Some(metrics) = &meta.args.cachegrind_metrics {
+    /// Update the output format with data from the [`CommandLineArgs`]
+    pub fn update_from_args(&mut self, args: &CommandLineArgs) {
+        if let Some(metrics) = &args.cachegrind_metrics {
             self.cachegrind.clone_from(metrics);
         }
-        if let Some(metrics) = &meta.args.callgrind_metrics {
+        match args.profile {
+            Some(MeasurementProfile::Fast) => self.callgrind = indexset![EventKind::Ir],
+            Some(MeasurementProfile::Full) => {
+                self.callgrind = IndexSet::from(CallgrindMetrics::Default);
+            }
+            Some(MeasurementProfile::Named(_)) | None => {}
+        }
+        if let Some(metrics) = &args.callgrind_metrics {
             self.callgrind.clone_from(metrics);
         }
-        if let Some(metrics) = &meta.args.dhat_metrics {
+        if let Some(metrics) = &args.dhat_metrics {
             self.dhat.clone_from(metrics);
         }
-        if let Some(metrics) = &meta.args.drd_metrics {
+        if let Some(metrics) = &args.drd_metrics {
             self.drd.clone_from(metrics);
         }
-        if let Some(metrics) = &meta.args.helgrind_metrics {
+        if let Some(metrics) = &args.helgrind_metrics {
             self.helgrind.clone_from(metrics);
         }
-        if let Some(metrics) = &meta.args.memcheck_metrics {
+        if let Some(metrics) = &args.memcheck_metrics {
             self.memcheck.clone_from(metrics);
         }
 
-        if meta.args.tolerance.is_some() {
-            self.tolerance = meta.args.tolerance;
+        if args.tolerance.is_some() {
+            self.tolerance = args.tolerance;
         }
 
-        if let Some(show_only_comparison) = meta.args.show_only_comparison {
+        if let Some(show_only_comparison) = args.show_only_comparison {
             self.show_only_comparison = show_only_comparison;
         }
 
-        if let Some(show_grid) = meta.args.show_grid {
+        if let Some(show_grid) = args.show_grid {
             self.show_grid = show_grid;
         }
 
-        if let Some(truncate_description) = meta.args.truncate_description {
+        if let Some(show_absolute_diff) = args.show_absolute_diff {
+            self.show_absolute_diff = show_absolute_diff;
+        }
+
+        if let Some(show_call_tree) = args.show_call_tree {
+            self.show_call_tree = show_call_tree;
+        }
+
+        if let Some(changed_only) = args.changed_only {
+            self.hide_unchanged = changed_only;
+        }
+
+        if let Some(truncate_description) = args.truncate_description {
             self.truncate_description = truncate_description.into();
         }
 
-        if let Some(show_intermediate) = meta.args.show_intermediate {
+        if let Some(show_intermediate) = args.show_intermediate {
             self.show_intermediate = show_intermediate;
         }
+
+        if let Some(show_lines) = args.show_lines {
+            self.show_lines = show_lines;
+        }
+
+        if let Some(show_top_functions) = args.show_top_functions {
+            self.show_top_functions = show_top_functions;
+        }
+
+        if let Some(delta_style) = args.delta_style {
+            self.delta_style = delta_style;
+        }
+
+        if let Some(show_group_totals) = args.show_group_totals {
+            self.show_group_totals = show_group_totals;
+        }
+
+        if let Some(color_thresholds) = args.color_thresholds {
+            self.color_thresholds = color_thresholds;
+        }
+
+        if let Some(ascii) = args.ascii {
+            self.ascii = ascii;
+        }
+
+        if let Some(show_tool_args) = args.show_tool_args {
+            self.show_tool_args = show_tool_args;
+        }
+
+        if let Some(precision) = args.precision {
+            self.precision = precision;
+        }
+    }
+
+    /// Update the output format with data from command-line arguments in [`Metadata`]
+    pub fn update_from_meta(&mut self, meta: &Metadata) {
+        self.update_from_args(&meta.args);
     }
 }
 
@@ -532,9 +707,24 @@ impl Default for OutputFormat {
             show_only_comparison: false,
             kind: OutputFormatKind::default(),
             truncate_description: Some(50),
+            truncate_ellipsis: "...".to_owned(),
+            truncate_position: TruncatePosition::default(),
             show_intermediate: false,
+            show_lines: false,
+            show_top_functions: 0,
+            show_group_totals: false,
+            show_resource_usage: false,
+            show_tool_args: false,
             show_grid: false,
+            show_absolute_diff: false,
+            show_call_tree: false,
+            hide_unchanged: false,
+            subtract_overhead: false,
+            precision: MAX_PRECISION,
             tolerance: None,
+            delta_style: DeltaStyle::default(),
+            color_thresholds: ColorThresholds::default(),
+            ascii: false,
             callgrind: IndexSet::from(CallgrindMetrics::Default),
             cachegrind: IndexSet::from(CachegrindMetrics::Default),
             dhat: IndexSet::from(DhatMetrics::Default),
@@ -565,9 +755,24 @@ impl From<api::OutputFormat> for OutputFormat {
         Self {
             kind: OutputFormatKind::Default,
             truncate_description: value.truncate_description.unwrap_or(Some(50)),
+            truncate_ellipsis: value.truncate_ellipsis.unwrap_or_else(|| "...".to_owned()),
+            truncate_position: value.truncate_position.unwrap_or_default(),
             show_intermediate: value.show_intermediate.unwrap_or(false),
+            show_lines: value.show_lines.unwrap_or(false),
+            show_top_functions: value.show_top_functions.unwrap_or(0),
+            show_group_totals: value.show_group_totals.unwrap_or(false),
+            show_resource_usage: value.show_resource_usage.unwrap_or(false),
+            show_tool_args: value.show_tool_args.unwrap_or(false),
+            precision: value.precision.unwrap_or(MAX_PRECISION),
             show_grid: value.show_grid.unwrap_or(false),
+            show_absolute_diff: value.show_absolute_diff.unwrap_or(false),
+            show_call_tree: value.show_call_tree.unwrap_or(false),
+            hide_unchanged: value.hide_unchanged.unwrap_or(false),
+            subtract_overhead: value.subtract_overhead.unwrap_or(false),
             tolerance: value.tolerance,
+            delta_style: value.delta_style.unwrap_or_default(),
+            color_thresholds: value.color_thresholds.unwrap_or_default(),
+            ascii: value.ascii.unwrap_or(false),
             ..Default::default()
         }
     }
@@ -575,88 +780,287 @@ impl From<api::OutputFormat> for OutputFormat {
 
 impl SummaryFormatter {
     /// Create a new `SummaryFormatter`
-    pub fn new(output_format_kind: OutputFormatKind) -> Self {
-        Self { output_format_kind }
+    ///
+    /// Without a [`DeltaStyle`] the soft regressions show only the difference in percent.
+    pub fn new(
+        output_format_kind: OutputFormatKind,
+        delta_style: Option<DeltaStyle>,
+        sort_by: Sort,
+    ) -> Self {
+        Self {
+            delta_style,
+            output_format_kind,
+            sort_by,
+        }
     }
 
     /// Print the summary
     pub fn print(&self, summaries: &BenchmarkSummaries) {
-        if self.output_format_kind == OutputFormatKind::Default {
-            let total_benchmarks = summaries.num_benchmarks();
-            let total_time = to_string_unsigned_short(
-                summaries
-                    .total_time
-                    .expect("The total execution time should be present")
-                    .as_secs_f64(),
+        if self.output_format_kind != OutputFormatKind::Default {
+            return;
+        }
+
+        let total_benchmarks = summaries.num_benchmarks();
+        let total_time = to_string_unsigned_short(
+            summaries
+                .total_time
+                .expect("The total execution time should be present")
+                .as_secs_f64(),
+        );
+
+        self.print_regressions(summaries);
+        self.print_expected_regressions(summaries);
+        Self::print_errors(summaries);
+        Self::print_suspicious(summaries);
+
+        let result = if summaries.num_errored > 0 || summaries.num_failed() > 0 {
+            "Errored".bright_red().bold()
+        } else if summaries.is_regressed() {
+            "Regressed".bright_red().bold()
+        } else {
+            "Ok".green().bold()
+        };
+        println!(
+            "\nIai-Callgrind result: {result}. {} passed, {} regressed, {} errored, {} skipped; \
+             {total_benchmarks} benchmarks finished in {total_time:>6}s",
+            summaries.num_passed(),
+            summaries.num_regressed(),
+            summaries.num_errored + summaries.num_failed(),
+            summaries.num_skipped,
+        );
+
+        let tools = summaries
+            .summaries
+            .iter()
+            .flat_map(|summary| summary.profiles.iter().map(|profile| profile.tool.id()))
+            .collect::<IndexSet<_>>();
+        if !tools.is_empty() {
+            println!(
+                "{} {}",
+                "Iai-Callgrind tools:".bold(),
+                tools.into_iter().collect::<Vec<_>>().join(", ")
             );
+        }
+    }
 
-            if summaries.is_regressed() {
-                println!("\nRegressions:\n");
-                let mut num_regressed = 0;
-                for summary in summaries.summaries.iter().filter(|p| p.is_regressed()) {
-                    if let Some(id) = &summary.id {
-                        println!("  {} {}:", summary.module_path.green(), id.cyan());
-                    } else {
-                        println!("  {}:", summary.module_path.green());
-                    }
-                    for regression in summary
-                        .profiles
-                        .iter()
-                        .flat_map(|t| &t.summaries.total.regressions)
-                    {
-                        match regression {
-                            ToolRegression::Soft {
-                                metric,
-                                new,
-                                old,
-                                diff_pct,
-                                limit,
-                            } => {
-                                println!(
-                                    "    {metric} ({} -> {}): {:>6}{} exceeds limit of {:>6}{}",
-                                    old,
-                                    new.to_string().bold(),
-                                    to_string_signed_short(*diff_pct).bright_red().bold(),
-                                    "%".bright_red().bold(),
-                                    to_string_signed_short(*limit).bright_black(),
-                                    "%".bright_black()
-                                );
-                            }
-                            ToolRegression::Hard {
-                                metric,
-                                new,
-                                diff,
-                                limit,
-                            } => {
-                                println!(
-                                    "    {metric} ({0}): {0} exceeds limit of {1} by {2}",
-                                    new.to_string().bold(),
-                                    limit.to_string().bright_black(),
-                                    diff.to_string().bright_red().bold()
-                                );
-                            }
-                        }
-                    }
+    /// Print the regressions of the benchmarks and groups if any
+    fn print_regressions(&self, summaries: &BenchmarkSummaries) {
+        if !summaries.is_regressed() {
+            return;
+        }
 
-                    num_regressed += 1;
-                }
+        println!("\nRegressions:\n");
+        for (summary, regressions) in self.regressions(summaries) {
+            if let Some(id) = &summary.id {
+                println!("  {} {}:", summary.module_path.green(), id.cyan());
+            } else {
+                println!("  {}:", summary.module_path.green());
+            }
+            for regression in regressions {
+                self.print_regression(regression);
+            }
+        }
+        for (module_path, regressions) in &summaries.group_regressions {
+            println!(
+                "  {} {}:",
+                module_path.to_string().green(),
+                "(group)".cyan()
+            );
+            for regression in regressions {
+                self.print_regression(regression);
+            }
+        }
+    }
+
+    /// Print the expected regressions together with their reason if any
+    fn print_expected_regressions(&self, summaries: &BenchmarkSummaries) {
+        if !summaries
+            .summaries
+            .iter()
+            .any(BenchmarkSummary::is_expected_regression)
+        {
+            return;
+        }
 
-                let num_not_regressed = total_benchmarks - num_regressed;
+        println!("\nExpected regressions:\n");
+        for summary in summaries
+            .summaries
+            .iter()
+            .filter(|summary| summary.is_expected_regression())
+        {
+            let reason = summary
+                .expected_regression
+                .as_deref()
+                .expect("An expected regression should have a reason");
+            if let Some(id) = &summary.id {
                 println!(
-                    "\nIai-Callgrind result: {}. {num_not_regressed} without regressions; \
-                     {num_regressed} regressed; {total_benchmarks} benchmarks finished in \
-                     {total_time:>6}s",
-                    "Regressed".bright_red().bold(),
+                    "  {} {}: {}",
+                    summary.module_path.green(),
+                    id.cyan(),
+                    reason.yellow()
                 );
             } else {
+                println!("  {}: {}", summary.module_path.green(), reason.yellow());
+            }
+            for regression in summary
+                .profiles
+                .iter()
+                .flat_map(|profile| &profile.summaries.total.regressions)
+            {
+                self.print_regression(regression);
+            }
+        }
+    }
+
+    /// Print the number of errors of the tools with error metrics if any
+    fn print_errors(summaries: &BenchmarkSummaries) {
+        if !summaries.has_errors() {
+            return;
+        }
+
+        println!("\nErrors:\n");
+        for summary in summaries.summaries.iter().filter(|p| p.has_errors()) {
+            if let Some(id) = &summary.id {
+                println!("  {} {}:", summary.module_path.green(), id.cyan());
+            } else {
+                println!("  {}:", summary.module_path.green());
+            }
+            for profile in summary.profiles.iter().filter(|p| p.has_errors()) {
+                let (errors, contexts) = profile.errors().expect("The errors should be present");
+                let failed = if profile.fail_on_errors {
+                    " (fail on errors)".bright_red().to_string()
+                } else {
+                    String::new()
+                };
+                println!(
+                    "    {}: {} errors from {} contexts{failed}",
+                    profile.tool,
+                    errors.to_string().bold(),
+                    contexts
+                );
+            }
+        }
+    }
+
+    /// Print the benchmarks with suspicious metrics together with the tools if any
+    fn print_suspicious(summaries: &BenchmarkSummaries) {
+        if !summaries
+            .summaries
+            .iter()
+            .any(BenchmarkSummary::is_suspicious)
+        {
+            return;
+        }
+
+        println!("\nSuspicious (all metrics are zero):\n");
+        for summary in summaries
+            .summaries
+            .iter()
+            .filter(|summary| summary.is_suspicious())
+        {
+            let tools = summary
+                .profiles
+                .iter()
+                .filter(|profile| profile.suspicious)
+                .map(|profile| profile.tool.id())
+                .collect::<Vec<_>>()
+                .join(", ");
+            if let Some(id) = &summary.id {
+                println!(
+                    "  {} {}: {}",
+                    summary.module_path.green(),
+                    id.cyan(),
+                    tools.yellow()
+                );
+            } else {
+                println!("  {}: {}", summary.module_path.green(), tools.yellow());
+            }
+        }
+    }
+
+    /// Print a single regression of the summary of the regressions
+    fn print_regression(&self, regression: &ToolRegression) {
+        match regression {
+            ToolRegression::Soft {
+                metric,
+                new,
+                old,
+                diff_pct,
+                limit,
+            } => {
+                let factor = Diffs::new(*new, *old).factor;
+                let (diff, unit) = if self.delta_style == Some(DeltaStyle::Factor) {
+                    (to_string_signed_short(factor), "x")
+                } else {
+                    (to_string_signed_short(*diff_pct), "%")
+                };
+                let factor = if self.delta_style == Some(DeltaStyle::Both) {
+                    format!(" [{}{}]", to_string_signed_short(factor), "x")
+                } else {
+                    String::new()
+                };
+                println!(
+                    "    {metric} ({} -> {}): {:>6}{}{} exceeds limit of {:>6}{}",
+                    old,
+                    new.to_string().bold(),
+                    diff.bright_red().bold(),
+                    unit.bright_red().bold(),
+                    factor.bright_red(),
+                    to_string_signed_short(*limit).bright_black(),
+                    "%".bright_black()
+                );
+            }
+            ToolRegression::Hard {
+                metric,
+                new,
+                diff,
+                limit,
+            } => {
                 println!(
-                    "\nIai-Callgrind result: {}. {total_benchmarks} without regressions; 0 \
-                     regressed; {total_benchmarks} benchmarks finished in {total_time:>6}s",
-                    "Ok".green().bold(),
+                    "    {metric} ({0}): {0} exceeds limit of {1} by {2}",
+                    new.to_string().bold(),
+                    limit.to_string().bright_black(),
+                    diff.to_string().bright_red().bold()
                 );
             }
         }
     }
+
+    /// Return the regressed benchmarks with their regressions in the order of [`Sort`]
+    ///
+    /// With [`Sort::DeltaDesc`], the regressions of a benchmark are sorted by their severity and
+    /// the benchmarks by their worst regression (highest first).
+    fn regressions<'a>(
+        &self,
+        summaries: &'a BenchmarkSummaries,
+    ) -> Vec<(&'a BenchmarkSummary, Vec<&'a ToolRegression>)> {
+        let mut regressions = summaries
+            .summaries
+            .iter()
+            .filter(|summary| summary.is_regressed())
+            .map(|summary| {
+                let regressions = summary
+                    .profiles
+                    .iter()
+                    .flat_map(|profile| &profile.summaries.total.regressions)
+                    .collect::<Vec<_>>();
+                (summary, regressions)
+            })
+            .collect::<Vec<_>>();
+
+        if self.sort_by == Sort::DeltaDesc {
+            for (_, regressions) in &mut regressions {
+                regressions.sort_by(|a, b| b.severity().total_cmp(&a.severity()));
+            }
+            // The regressions are already sorted, so the first regression is the worst
+            regressions.sort_by(|(_, a), (_, b)| {
+                let severity = |r: &[&ToolRegression]| r.first().map_or(f64::MIN, |r| r.severity());
+                severity(b).total_cmp(&severity(a))
+            });
+        }
+
+        regressions
+    }
 }
 
 impl VerticalFormatter {
@@ -681,9 +1085,9 @@ impl VerticalFormatter {
         }
     }
 
-    /// Print the internal buffer as is and clear it afterwards
+    /// Print the internal buffer and clear it afterwards
     pub fn print_buffer(&mut self) {
-        print!("{}", self.buffer);
+        print!("{}", self.output_format.sanitize(&self.buffer));
         self.clear();
     }
 
@@ -836,13 +1240,27 @@ impl VerticalFormatter {
                 let diffs = diffs.expect(
                     "If there are new metrics and old metrics there should be a difference present",
                 );
-                let pct_string = format_float(diffs.diff_pct, '%');
-                let factor_string = format_float(diffs.factor, 'x');
-
-                let right = format!(
-                    "{old:<METRIC_WIDTH$} ({pct_string:^DIFF_WIDTH$}) \
-                     [{factor_string:^DIFF_WIDTH$}]"
-                );
+                let color = diff_color(diffs.diff_pct, self.output_format.color_thresholds);
+                let precision = self.output_format.precision;
+                let pct_string = format_float(diffs.diff_pct, '%', precision, color);
+                let factor_string = format_float(diffs.factor, 'x', precision, color);
+
+                let mut right = match self.output_format.delta_style {
+                    DeltaStyle::Percent => {
+                        format!("{old:<METRIC_WIDTH$} ({pct_string:^DIFF_WIDTH$})")
+                    }
+                    DeltaStyle::Factor => {
+                        format!("{old:<METRIC_WIDTH$} [{factor_string:^DIFF_WIDTH$}]")
+                    }
+                    DeltaStyle::Both => format!(
+                        "{old:<METRIC_WIDTH$} ({pct_string:^DIFF_WIDTH$}) \
+                         [{factor_string:^DIFF_WIDTH$}]"
+                    ),
+                };
+                if self.output_format.show_absolute_diff {
+                    let absolute_string = format_absolute_diff(**new, **old, precision, color);
+                    write!(right, " {absolute_string}").unwrap();
+                }
                 self.write_field(
                     field,
                     &EitherOrBoth::Both(&new.to_string(), &right),
@@ -887,7 +1305,8 @@ impl VerticalFormatter {
         }
     }
 
-    fn format_details(&mut self, details: &str) {
+    /// Format the `details` with a `Details:` label, each line of the `details` on its own line
+    pub fn format_details(&mut self, details: &str) {
         let mut details = details.lines();
         if let Some(head_line) = details.next() {
             self.write_indent(&IndentKind::Normal);
@@ -902,6 +1321,12 @@ impl VerticalFormatter {
         }
     }
 
+    fn format_lines(&mut self, info: &EitherOrBoth<ProfileInfo>) {
+        if let Some(details) = info.as_ref().left().and_then(|new| new.details.as_ref()) {
+            self.format_details(details);
+        }
+    }
+
     fn format_metrics<'a, K: Display>(
         &mut self,
         metrics: impl Iterator<Item = (K, &'a MetricsDiff)>,
@@ -998,6 +1423,49 @@ impl VerticalFormatter {
         self.write_field("Command:", &paths, Some(Color::Blue), true);
     }
 
+    /// Format the page faults and context switches of the [`ResourceUsage`]
+    pub fn format_resource_usage(&mut self, resource_usage: &ResourceUsage) {
+        self.write_field(
+            "Page faults:",
+            &EitherOrBoth::Left(format!(
+                "{} major, {} minor",
+                resource_usage.major_page_faults, resource_usage.minor_page_faults
+            )),
+            None,
+            true,
+        );
+        self.write_field(
+            "Context switches:",
+            &EitherOrBoth::Left(format!(
+                "{} voluntary, {} involuntary",
+                resource_usage.voluntary_context_switches,
+                resource_usage.involuntary_context_switches
+            )),
+            None,
+            true,
+        );
+    }
+
+    /// Format the valgrind `version` and the resolved arguments of a tool
+    ///
+    /// Each argument is shown on a separate line.
+    pub fn format_tool_args(&mut self, version: Option<&str>, args: &[OsString]) {
+        self.write_field(
+            "Version:",
+            &EitherOrBoth::Left(version.unwrap_or(NOT_AVAILABLE)),
+            None,
+            true,
+        );
+
+        let mut args = args.iter().map(|arg| arg.to_string_lossy());
+        if let Some(first) = args.next() {
+            self.write_field("Arguments:", &EitherOrBoth::Left(first), None, true);
+            for arg in args {
+                self.write_left_indented(&arg);
+            }
+        }
+    }
+
     /// Format the tool headline shown for all tools
     pub fn format_tool_headline(&mut self, tool: ValgrindTool) {
         self.write_indent(&IndentKind::ToolHeadline);
@@ -1017,7 +1485,7 @@ impl VerticalFormatter {
 
 impl Display for VerticalFormatter {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str(&self.buffer)
+        f.write_str(&self.output_format.sanitize(&self.buffer))
     }
 }
 
@@ -1092,6 +1560,12 @@ impl Formatter for VerticalFormatter {
                         .iter()
                         .filter_map(|e| summary.diff_by_kind(e).map(|d| (e, d))),
                 );
+
+                if self.output_format.show_lines {
+                    if let Some(info) = info {
+                        self.format_lines(info);
+                    }
+                }
             }
             ToolMetricSummary::Cachegrind(summary) => {
                 self.format_metrics(
@@ -1154,6 +1628,13 @@ impl Formatter for VerticalFormatter {
             }
         } else if data.total.is_some() {
             self.format_single(tool, baselines, None, &data.total.summary, is_default_tool)?;
+
+            // The lines are only present in the details of the parts
+            if tool == ValgrindTool::Callgrind && self.output_format.show_lines {
+                for part in &data.parts {
+                    self.format_lines(&part.details);
+                }
+            }
         } else if data.total.is_none() && !data.parts.is_empty() {
             // Since there is no total, show_all is partly ignored, and we show all data in a little
             // bit more aggregated form without the multiple files headlines. This affects currently
@@ -1205,6 +1686,42 @@ impl Formatter for VerticalFormatter {
         Ok(())
     }
 
+    fn print_group_totals(
+        &mut self,
+        module_path: &ModulePath,
+        num_benchmarks: usize,
+        summaries: Vec<(ValgrindTool, ToolMetricSummary)>,
+    ) -> Result<()> {
+        if self.output_format.is_default() {
+            println!(
+                "{} {} ({num_benchmarks} benchmarks)",
+                "Total of group".yellow().bold(),
+                self.output_format
+                    .sanitize(&module_path.to_string())
+                    .green()
+            );
+
+            let is_multiple = summaries.len() > 1;
+            for (tool, summary) in summaries
+                .iter()
+                .filter(|(_, s)| *s != ToolMetricSummary::None)
+            {
+                if is_multiple || *tool != ValgrindTool::Callgrind {
+                    self.format_line(&format!(
+                        "{}{} {}\n",
+                        self.indent_sub_header,
+                        "-------".bright_black(),
+                        tool.to_string().to_uppercase()
+                    ))?;
+                }
+                self.format_single(*tool, &(None, None), None, summary, false)?;
+            }
+            self.print_buffer();
+        }
+
+        Ok(())
+    }
+
     fn clear(&mut self) {
         self.buffer.clear();
     }
@@ -1226,25 +1743,69 @@ impl Write for VerticalFormatter {
     }
 }
 
-/// Format a floating point number with `unit`
-pub fn format_float(float: f64, unit: char) -> ColoredString {
-    let signed_short = to_string_signed_short(float);
-    if float.is_infinite() {
+/// Return the color of a difference in percent according to the [`ColorThresholds`]
+///
+/// Returns `None` if the difference is within the neutral threshold and should not be colored.
+pub fn diff_color(diff_pct: f64, thresholds: ColorThresholds) -> Option<Color> {
+    if diff_pct.abs() <= thresholds.neutral {
+        None
+    } else if diff_pct.is_sign_negative() {
+        Some(Color::BrightGreen)
+    } else if diff_pct <= thresholds.warning {
+        Some(Color::BrightYellow)
+    } else {
+        Some(Color::BrightRed)
+    }
+}
+
+/// Format a floating point number with `unit` and at most `precision` digits in the given `color`
+///
+/// Without a `color` the number is printed without any color and not in bold.
+pub fn format_float(
+    float: f64,
+    unit: char,
+    precision: usize,
+    color: Option<Color>,
+) -> ColoredString {
+    let signed_short = to_string_signed_precision(float, precision);
+    let string = if float.is_infinite() {
         if float.is_sign_positive() {
-            format!("{signed_short:+^DIFF_WIDTH$}").bright_red().bold()
+            format!("{signed_short:+^DIFF_WIDTH$}")
         } else {
             format!("{signed_short:-^DIFF_WIDTH$}")
-                .bright_green()
-                .bold()
         }
-    } else if float.is_sign_positive() {
-        format!("{signed_short:>+FLOAT_WIDTH$}{unit}")
-            .bright_red()
-            .bold()
     } else {
         format!("{signed_short:>+FLOAT_WIDTH$}{unit}")
-            .bright_green()
-            .bold()
+    };
+
+    match color {
+        Some(color) => string.color(color).bold(),
+        None => string.normal(),
+    }
+}
+
+/// Format the absolute difference between the `new` and the `old` [`Metric`] with a sign
+///
+/// The difference of [`Metric::Int`]s is exact, the difference of [`Metric::Float`]s is formatted
+/// like the differences in percent with at most `precision` fractional-part digits.
+pub fn format_absolute_diff(
+    new: Metric,
+    old: Metric,
+    precision: usize,
+    color: Option<Color>,
+) -> ColoredString {
+    let string = match (new, old) {
+        (Metric::Int(new), Metric::Int(old)) if new >= old => format!("+{}", new - old),
+        (Metric::Int(new), Metric::Int(old)) => format!("-{}", old - new),
+        (new, old) => match new - old {
+            Metric::Float(diff) => to_string_signed_precision(diff, precision),
+            Metric::Int(_) => unreachable!("The difference with a float should be a float"),
+        },
+    };
+
+    match color {
+        Some(color) => string.color(color).bold(),
+        None => string.normal(),
     }
 }
 
@@ -1270,6 +1831,23 @@ pub fn print_benchmark_list_summary(sum: u64) {
     println!("0 tests, {sum} benchmarks");
 }
 
+/// Print the [`EnvironmentInfo`] of the benchmark run as header before the benchmarks
+pub fn print_environment(environment: &EnvironmentInfo) {
+    let unknown = || "unknown".to_owned();
+    println!(
+        "{} valgrind {} | {} | {} | CPU: {} | {}",
+        "Iai-Callgrind environment:".bold(),
+        environment.valgrind.clone().unwrap_or_else(unknown),
+        environment
+            .rustc
+            .clone()
+            .unwrap_or_else(|| format!("rustc {}", unknown())),
+        environment.target,
+        environment.cpu.clone().unwrap_or_else(unknown),
+        format_timestamp(environment.timestamp)
+    );
+}
+
 /// Print a single benchmark for the --list argument
 pub fn print_list_benchmark(module_path: &ModulePath, id: Option<&String>) {
     match id {
@@ -1314,6 +1892,58 @@ pub fn print_no_capture_footer(
     }
 }
 
+/// Print that all `num_benchmarks` of the benchmark file `module` were skipped because the
+/// platform is unsupported (`--allow-unsupported`)
+pub fn print_unsupported_platform(module: &str, num_benchmarks: usize, reason: &dyn Display) {
+    println!(
+        "{} {}. {num_benchmarks} benchmarks of '{module}' skipped: {reason}",
+        "Iai-Callgrind result:".bold(),
+        "Skipped".yellow().bold(),
+    );
+}
+
+/// Print the number of benchmarks whose output was hidden because their metrics are unchanged
+pub fn print_unchanged(num_unchanged: usize) {
+    let benchmarks = if num_unchanged == 1 {
+        "benchmark"
+    } else {
+        "benchmarks"
+    };
+    println!(
+        "{}",
+        format!("{num_unchanged} {benchmarks} with unchanged metrics not shown").bright_black()
+    );
+}
+
+/// Print the regressions of the totals of a group which exceeded the regression budget to `stderr`
+pub fn print_group_regressions(module_path: &ModulePath, regressions: &[ToolRegression]) {
+    eprintln!(
+        "{} {}",
+        "Regression budget exceeded by group".yellow().bold(),
+        module_path.to_string().green()
+    );
+    print_regressions(regressions);
+}
+
+/// Print the outcome of a regression expected with `expect_regression` to `stderr`
+///
+/// If the expected regression didn't occur, the annotation is probably obsolete.
+pub fn print_expected_regression(reason: &str, is_regressed: bool) {
+    if is_regressed {
+        eprintln!(
+            "{} {reason} {}",
+            "Expected regression:".yellow().bold(),
+            "(not counted as regression)".bright_black()
+        );
+    } else {
+        eprintln!(
+            "{} {reason} {}",
+            "Expected regression did not occur:".yellow().bold(),
+            "(consider removing `expect_regression`)".bright_black()
+        );
+    }
+}
+
 /// Print detected regressions to `stderr`
 pub fn print_regressions(regressions: &[ToolRegression]) {
     for regression in regressions {
@@ -1388,16 +2018,74 @@ pub fn print_regressions(regressions: &[ToolRegression]) {
     }
 }
 
-fn truncate_description(description: &str, truncate_description: Option<usize>) -> Cow<'_, str> {
-    if let Some(num) = truncate_description {
-        let new_description = truncate_str_utf8(description, num);
-        if new_description.len() < description.len() {
-            Cow::Owned(format!("{new_description}..."))
-        } else {
-            Cow::Borrowed(description)
+/// Print a warning with remediation hints to `stderr` if the total metrics of a tool are zero
+pub fn print_suspicious(tool: ValgrindTool, entry_point: &EntryPoint) {
+    eprintln!(
+        "{}: The total metrics of {} are zero. This usually indicates a misconfiguration:",
+        "Suspicious".bold().yellow(),
+        tool.id()
+    );
+
+    let hints = match (tool, entry_point) {
+        (ValgrindTool::Cachegrind, _) => vec![
+            "Cachegrind measures only the code between the start and stop instrumentation client \
+             requests. Make sure these client requests surround the benchmarked code."
+                .to_owned(),
+        ],
+        (_, EntryPoint::None) => vec![
+            "Without an entry point, the collection might have been switched off, for example with \
+             `--collect-atstart=no`, but never switched on again with `--toggle-collect`."
+                .to_owned(),
+        ],
+        (_, EntryPoint::Default) => vec![
+            "A custom `--toggle-collect` might not match any executed function, for example \
+             because the function was inlined. Use `#[inline(never)]` on the function."
+                .to_owned(),
+            "The threads and subprocesses are separate units which are not measured with the \
+             default entry point. See the guide for threads and subprocesses."
+                .to_owned(),
+        ],
+        (_, EntryPoint::Custom(custom)) => vec![
+            format!(
+                "The custom entry point '{custom}' might not match any executed function, for \
+                 example because the function was inlined. Use `#[inline(never)]` on the function."
+            ),
+            "The threads and subprocesses are separate units which are only measured if the \
+             entry point matches a function executed in them."
+                .to_owned(),
+        ],
+    };
+    for hint in hints {
+        eprintln!("  {} {hint}", "*".yellow());
+    }
+}
+
+fn truncate_description<'a>(
+    description: &'a str,
+    truncate_description: Option<usize>,
+    position: TruncatePosition,
+    ellipsis: &str,
+) -> Cow<'a, str> {
+    let Some(num) = truncate_description else {
+        return Cow::Borrowed(description);
+    };
+    if description.len() <= num {
+        return Cow::Borrowed(description);
+    }
+
+    match position {
+        TruncatePosition::Start => Cow::Owned(format!(
+            "{ellipsis}{}",
+            truncate_str_utf8_start(description, num)
+        )),
+        TruncatePosition::Middle => {
+            let start = truncate_str_utf8(description, num.div_ceil(2));
+            let end = truncate_str_utf8_start(description, num - start.len());
+            Cow::Owned(format!("{start}{ellipsis}{end}"))
+        }
+        TruncatePosition::End => {
+            Cow::Owned(format!("{}{ellipsis}", truncate_str_utf8(description, num)))
         }
-    } else {
-        Cow::Borrowed(description)
     }
 }
 
@@ -1589,6 +2277,56 @@ mod tests {
         assert_eq!(header.to_string(), expected);
     }
 
+    #[rstest]
+    #[case::not_ascii(false, "some::module id:µs, 1,…")]
+    #[case::ascii(true, "some::module id:?s, 1,...")]
+    fn test_header_display_when_ascii(#[case] ascii: bool, #[case] expected: &str) {
+        colored::control::set_override(false);
+
+        let output_format = OutputFormat {
+            ascii,
+            truncate_description: Some(7),
+            truncate_ellipsis: "…".to_owned(),
+            ..Default::default()
+        };
+
+        let header = Header::new(
+            &ModulePath::new("some::module"),
+            Some("id".to_owned()),
+            Some("µs, 1, 2, 3".to_owned()),
+            &output_format,
+        );
+
+        assert_eq!(header.to_string(), expected);
+    }
+
+    #[rstest]
+    #[case::end(TruncatePosition::End, "...", "1, 2, 3...")]
+    #[case::start(TruncatePosition::Start, "...", "...4, 5, 6")]
+    #[case::middle(TruncatePosition::Middle, "...", "1, 2..., 6")]
+    #[case::custom_ellipsis(TruncatePosition::Middle, "~", "1, 2~, 6")]
+    fn test_truncate_description_with_position(
+        #[case] position: TruncatePosition,
+        #[case] ellipsis: &str,
+        #[case] expected: &str,
+    ) {
+        assert_eq!(
+            truncate_description("1, 2, 3, 4, 5, 6", Some(7), position, ellipsis),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case::end(TruncatePosition::End)]
+    #[case::start(TruncatePosition::Start)]
+    #[case::middle(TruncatePosition::Middle)]
+    fn test_truncate_description_when_short_then_unchanged(#[case] position: TruncatePosition) {
+        assert_eq!(
+            truncate_description("1, 2", Some(4), position, "..."),
+            "1, 2"
+        );
+    }
+
     #[rstest]
     #[case::new_costs_0(EventKind::Ir, 0, None, "*********", None)]
     #[case::old_costs_0(EventKind::Ir, 1, Some(0), "+++inf+++", Some("+++inf+++"))]
@@ -1691,6 +2429,137 @@ mod tests {
         assert_eq!(formatter.buffer, expected);
     }
 
+    #[rstest]
+    #[case::max(5, "-33.3333%", "-1.50000x")]
+    #[case::two(2, "  -33.33%", "   -1.50x")]
+    #[case::zero(0, "     -33%", "      -2x")]
+    fn test_format_vertical_when_precision_is_set(
+        #[case] precision: usize,
+        #[case] diff_pct: &str,
+        #[case] diff_fact: &str,
+    ) {
+        colored::control::set_override(false);
+
+        let expected = format!(
+            "  {:<FIELD_WIDTH$}{:>METRIC_WIDTH$}|{:<METRIC_WIDTH$} ({diff_pct}) [{diff_fact}]\n",
+            format!("{}:", EventKind::Ir),
+            2000,
+            3000
+        );
+
+        let output_format = OutputFormat {
+            precision,
+            ..Default::default()
+        };
+
+        let metrics_summary = MetricsSummary::new(EitherOrBoth::Both(
+            Metrics(indexmap! {EventKind::Ir => Metric::Int(2000)}),
+            Metrics(indexmap! {EventKind::Ir => Metric::Int(3000)}),
+        ));
+        let mut formatter = VerticalFormatter::new(output_format);
+        formatter.format_metrics(metrics_summary.all_diffs());
+
+        assert_eq!(formatter.buffer, expected);
+    }
+
+    #[rstest]
+    #[case::decrease(2000, 3000, "(-33.3333%) [-1.50000x] -1000")]
+    #[case::increase(3000, 2000, "(+50.0000%) [+1.50000x] +1000")]
+    fn test_format_vertical_when_show_absolute_diff(
+        #[case] new: u64,
+        #[case] old: u64,
+        #[case] diffs: &str,
+    ) {
+        colored::control::set_override(false);
+
+        let expected = format!(
+            "  {:<FIELD_WIDTH$}{new:>METRIC_WIDTH$}|{old:<METRIC_WIDTH$} {diffs}\n",
+            format!("{}:", EventKind::Ir),
+        );
+
+        let output_format = OutputFormat {
+            show_absolute_diff: true,
+            ..Default::default()
+        };
+
+        let metrics_summary = MetricsSummary::new(EitherOrBoth::Both(
+            Metrics(indexmap! {EventKind::Ir => Metric::Int(new)}),
+            Metrics(indexmap! {EventKind::Ir => Metric::Int(old)}),
+        ));
+        let mut formatter = VerticalFormatter::new(output_format);
+        formatter.format_metrics(metrics_summary.all_diffs());
+
+        assert_eq!(formatter.buffer, expected);
+    }
+
+    #[rstest]
+    #[case::int(Metric::Int(10), Metric::Int(3), 5, "+7")]
+    #[case::negative_int(Metric::Int(3), Metric::Int(10), 5, "-7")]
+    #[case::float(Metric::Float(1.5), Metric::Float(2.0), 5, "-0.50000")]
+    #[case::float_precision(Metric::Float(1.5), Metric::Float(2.0), 1, "-0.5")]
+    #[case::mixed(Metric::Float(1.5), Metric::Int(1), 2, "+0.50")]
+    fn test_format_absolute_diff(
+        #[case] new: Metric,
+        #[case] old: Metric,
+        #[case] precision: usize,
+        #[case] expected: &str,
+    ) {
+        colored::control::set_override(false);
+        assert_eq!(
+            format_absolute_diff(new, old, precision, None).to_string(),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case::default_when_regression(0.1, 0.0, 0.0, Some(Color::BrightRed))]
+    #[case::default_when_improvement(-0.1, 0.0, 0.0, Some(Color::BrightGreen))]
+    #[case::within_neutral(1.0, 1.0, 5.0, None)]
+    #[case::within_neg_neutral(-1.0, 1.0, 5.0, None)]
+    #[case::improvement(-1.1, 1.0, 5.0, Some(Color::BrightGreen))]
+    #[case::warning(1.1, 1.0, 5.0, Some(Color::BrightYellow))]
+    #[case::warning_exact(5.0, 1.0, 5.0, Some(Color::BrightYellow))]
+    #[case::regression(5.1, 1.0, 5.0, Some(Color::BrightRed))]
+    #[case::pos_infinity(f64::INFINITY, 1.0, 5.0, Some(Color::BrightRed))]
+    #[case::neg_infinity(f64::NEG_INFINITY, 1.0, 5.0, Some(Color::BrightGreen))]
+    fn test_diff_color(
+        #[case] diff_pct: f64,
+        #[case] neutral: f64,
+        #[case] warning: f64,
+        #[case] expected: Option<Color>,
+    ) {
+        let thresholds = ColorThresholds { neutral, warning };
+        assert_eq!(diff_color(diff_pct, thresholds), expected);
+    }
+
+    #[rstest]
+    #[case::with_version(Some("3.22.0"), "3.22.0")]
+    #[case::without_version(None, "N/A")]
+    fn test_vertical_formatter_format_tool_args(
+        #[case] version: Option<&str>,
+        #[case] expected_version: &str,
+    ) {
+        colored::control::set_override(false);
+
+        let mut formatter = VerticalFormatter::new(OutputFormat::default());
+        formatter.format_tool_args(
+            version,
+            &[
+                OsString::from("--tool=callgrind"),
+                OsString::from("--dump-instr=yes"),
+            ],
+        );
+
+        let expected = format!(
+            "  {:<FIELD_WIDTH$}{expected_version}\n  {:<FIELD_WIDTH$}--tool=callgrind\n  {}\
+             --dump-instr=yes\n",
+            "Version:",
+            "Arguments:",
+            " ".repeat(FIELD_WIDTH)
+        );
+        assert_eq!(formatter.buffer, expected);
+    }
+
     #[rstest]
     #[case::normal_no_grid(IndentKind::Normal, false, "  ")]
     #[case::tool_header_no_grid(IndentKind::ToolHeadline, false, "  ")]
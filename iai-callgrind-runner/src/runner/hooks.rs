@@ -0,0 +1,130 @@
+//! The module containing the [`Hooks`] of the `--on-finish` and `--on-regression` arguments
+//!
+//! The hooks run a user command after the benchmark run, so notifications (for example to a chat or
+//! a webhook) don't need a script wrapping the whole `cargo bench` invocation. The
+//! [`SUMMARY_PLACEHOLDER`] in the command is replaced with the path to the summary stream (the
+//! `summaries.jsonl` file) of the benchmark run.
+
+use std::fs::OpenOptions;
+use std::io::stderr;
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::{anyhow, Context, Result};
+use log::{debug, warn};
+
+/// The placeholder in the command of a hook which is replaced with the path to the summary
+pub const SUMMARY_PLACEHOLDER: &str = "{summary}";
+
+/// The user commands which are run after the benchmark run
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Hooks {
+    /// The command of `--on-finish` which is run when the benchmark run has finished
+    pub on_finish: Option<String>,
+    /// The command of `--on-regression` which is run if any benchmark or group has regressed
+    pub on_regression: Option<String>,
+}
+
+impl Hooks {
+    /// Create new `Hooks`
+    pub fn new(on_finish: Option<String>, on_regression: Option<String>) -> Self {
+        Self {
+            on_finish,
+            on_regression,
+        }
+    }
+
+    /// Return true if there are no hooks
+    pub fn is_empty(&self) -> bool {
+        self.on_finish.is_none() && self.on_regression.is_none()
+    }
+
+    /// Run the hooks with the path to the `summary` stream
+    ///
+    /// The `--on-regression` hook is run before the `--on-finish` hook and only if `is_regressed`
+    /// is true. A failing hook is not an error of the benchmark run, so it only results in a
+    /// warning.
+    pub fn run(&self, is_regressed: bool, summary: &Path) {
+        let hooks = [
+            (
+                "--on-regression",
+                self.on_regression.as_ref().filter(|_| is_regressed),
+            ),
+            ("--on-finish", self.on_finish.as_ref()),
+        ];
+        for (name, command) in hooks {
+            if let Some(command) = command {
+                if let Err(error) = run_hook(command, summary) {
+                    warn!("{name}: {error}");
+                }
+            }
+        }
+    }
+}
+
+/// Replace the [`SUMMARY_PLACEHOLDER`] in the `command` with the shell-quoted `summary` path
+fn expand(command: &str, summary: &Path) -> Result<String> {
+    let path = summary
+        .to_str()
+        .ok_or_else(|| anyhow!("Invalid utf-8 in summary path '{}'", summary.display()))?;
+    let quoted = shlex::try_quote(path)
+        .map_err(|error| anyhow!("Invalid summary path '{path}': {error}"))?;
+    Ok(command.replace(SUMMARY_PLACEHOLDER, &quoted))
+}
+
+/// Run the `command` of a hook with `sh -c`
+///
+/// The summary stream doesn't exist if no benchmark has finished, so an empty file is created in
+/// this case. The output of the command goes to stderr to keep the json output on stdout intact.
+fn run_hook(command: &str, summary: &Path) -> Result<()> {
+    if !summary.exists() {
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(summary)
+            .with_context(|| format!("Failed to create summary stream '{}'", summary.display()))?;
+    }
+
+    let command = expand(command, summary)?;
+    debug!("Running hook: sh -c {command}");
+    let status = Command::new("sh")
+        .arg("-c")
+        .arg(&command)
+        .stdout(stderr())
+        .status()
+        .map_err(|error| anyhow!("Failed to run '{command}': {error}"))?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(anyhow!("'{command}' failed: {status}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case::no_placeholder("./notify.sh", "/tmp/summaries.jsonl", "./notify.sh")]
+    #[case::placeholder(
+        "./notify.sh {summary}",
+        "/tmp/summaries.jsonl",
+        "./notify.sh /tmp/summaries.jsonl"
+    )]
+    #[case::multiple(
+        "cat {summary} {summary}",
+        "/tmp/summaries.jsonl",
+        "cat /tmp/summaries.jsonl /tmp/summaries.jsonl"
+    )]
+    #[case::quoted(
+        "./notify.sh {summary}",
+        "/tmp/my dir/summaries.jsonl",
+        "./notify.sh '/tmp/my dir/summaries.jsonl'"
+    )]
+    fn test_expand(#[case] command: &str, #[case] summary: &str, #[case] expected: &str) {
+        assert_eq!(expand(command, Path::new(summary)).unwrap(), expected);
+    }
+}
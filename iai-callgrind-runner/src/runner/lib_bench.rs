@@ -8,30 +8,63 @@ mod defaults {
 
 use std::collections::HashMap;
 use std::ffi::OsString;
+use std::process::{Command, Stdio};
+use std::sync::Arc;
 use std::time::Instant;
 
 use anyhow::Result;
-use log::warn;
+use indexmap::map::Entry;
+use indexmap::IndexMap;
+use log::{debug, warn};
 
+use super::args::NoCapture;
+use super::cachegrind::regression::CachegrindRegressionConfig;
+use super::callgrind::regression::CallgrindRegressionConfig;
 use super::common::{Assistant, AssistantKind, Baselines, BenchmarkSummaries, Config, ModulePath};
-use super::format::{LibraryBenchmarkHeader, OutputFormat};
+use super::dhat::regression::DhatRegressionConfig;
+use super::envs;
+use super::format::{
+    Formatter, LibraryBenchmarkHeader, OutputFormat, OutputFormatKind, VerticalFormatter,
+};
+use super::list::{BenchmarkList, ListBenchmark, ListGroup, ListTool};
 use super::meta::Metadata;
-use super::summary::{BaselineKind, BaselineName, BenchmarkKind, BenchmarkSummary, SummaryOutput};
+use super::pipeline::{self, Job, Pending, Pipeline};
+use super::profiles;
+use super::run_log::{self, RunLog};
+use super::summary::{
+    ArtifactKind, ArtifactRegistry, BaselineKind, BaselineName, BenchmarkKind, BenchmarkSummary,
+    SummaryOutput, ToolMetricSummary, ToolMetrics, ToolRegression,
+};
+use super::tool::batch::{IterBatch, IterBatchElement};
 use super::tool::config::ToolConfigs;
+use super::tool::parser::parser_factory;
 use super::tool::path::{ToolOutputPath, ToolOutputPathKind};
-use super::tool::run::RunOptions;
+use super::tool::regression::RegressionConfig;
+use super::tool::run::{RunOptions, ToolCommand};
 use crate::api::{
-    EntryPoint, LibraryBenchmarkConfig, LibraryBenchmarkGroups, RawArgs, ValgrindTool,
+    CachegrindMetric, DhatMetric, EntryPoint, EventKind, LibraryBenchmarkConfig,
+    LibraryBenchmarkGroup, LibraryBenchmarkGroups, RawArgs, ValgrindTool,
 };
 use crate::error::Error;
 use crate::runner::format;
 
+/// The argument of the benchmark harness to run the empty benchmark function of the calibration
+const CALIBRATION: &str = "__calibration";
+
 /// Implements [`Benchmark`] to run a [`LibBench`] and compare against an earlier [`BenchmarkKind`]
 #[derive(Debug)]
 struct BaselineBenchmark {
     baseline_kind: BaselineKind,
 }
 
+/// Implements [`Benchmark`] to run a [`LibBench`] in the CodSpeed compatible mode
+///
+/// The benchmark function is executed directly without valgrind and surrounded by the
+/// instrumentation markers of the CodSpeed runner, which also collects the metrics. The resulting
+/// [`BenchmarkSummary`] doesn't contain any profiles.
+#[derive(Debug)]
+struct CodSpeedBenchmark;
+
 // A `Group` is the organizational unit and counterpart of the `library_benchmark_group!` macro
 #[derive(Debug)]
 struct Group {
@@ -39,6 +72,7 @@ struct Group {
     compare_by_id: bool,
     module_path: ModulePath,
     name: String,
+    regression_budget: Option<f64>,
     setup: Option<Assistant>,
     teardown: Option<Assistant>,
 }
@@ -56,8 +90,12 @@ pub struct LibBench {
     pub bench_index: usize,
     /// The default [`ValgrindTool`]. If not changed it is `Callgrind`.
     pub default_tool: ValgrindTool,
+    /// The user provided description as in `#[library_benchmark(description = "...")]`
+    pub description: Option<String>,
     /// The arguments of `args` attribute as a single string
     pub display: Option<String>,
+    /// The reason why a regression is expected as in `#[bench::id(expect_regression = "...")]`
+    pub expect_regression: Option<String>,
     /// The name of the annotated function
     pub function_name: String,
     /// The index of the group in the benchmark harness
@@ -66,6 +104,8 @@ pub struct LibBench {
     pub id: Option<String>,
     /// The index of the element in the iterator of `#[benches::id(iter = ITERATOR)]` if present
     pub iter_index: Option<usize>,
+    /// The stable key as in `#[library_benchmark(key = "...")]` or `#[bench::id(key = "...")]`
+    pub key: Option<String>,
     /// The [`ModulePath`].
     ///
     /// This is an artificial path for display purposes and does not reflect the real module path
@@ -75,6 +115,12 @@ pub struct LibBench {
     pub output_format: OutputFormat,
     /// The [`RunOptions`]
     pub run_options: RunOptions,
+    /// The target triple as in `#[bench::id(runs_on = "target")]` maybe prefixed with `!`
+    pub runs_on: Option<String>,
+    /// The reason if this benchmark was skipped at compile time
+    pub skip: Option<String>,
+    /// The tags of all configuration levels
+    pub tags: Vec<String>,
     /// The tool configurations for this benchmark run
     pub tools: ToolConfigs,
 }
@@ -112,11 +158,27 @@ struct SaveBaselineBenchmark {
 /// designed to run a `LibBench` only.
 trait Benchmark: std::fmt::Debug {
     fn baselines(&self) -> Baselines;
+    fn is_serial(&self, lib_bench: &LibBench, config: &Config) -> bool;
     fn output_path(&self, lib_bench: &LibBench, config: &Config, group: &Group) -> ToolOutputPath;
-    fn run(&self, lib_bench: &LibBench, config: &Config, group: &Group)
-        -> Result<BenchmarkSummary>;
+    fn run<'a>(
+        &self,
+        lib_bench: &'a LibBench,
+        config: &'a Config,
+        group: &Group,
+        is_serial: bool,
+        registry: &ArtifactRegistry,
+    ) -> Job<'a, BenchmarkSummary>;
 }
 
+/// The pending [`Job`] of a [`LibBench`] in the [`Pipeline`], the `run.log` and the
+/// [`ArtifactRegistry`] of the benchmark
+type PendingBench<'scope, 'a> = (
+    Pending<'scope, BenchmarkSummary>,
+    &'a LibBench,
+    Option<RunLog>,
+    ArtifactRegistry,
+);
+
 impl Benchmark for BaselineBenchmark {
     fn output_path(&self, lib_bench: &LibBench, config: &Config, group: &Group) -> ToolOutputPath {
         let kind = if lib_bench.default_tool.has_output_file() {
@@ -141,54 +203,281 @@ impl Benchmark for BaselineBenchmark {
         }
     }
 
-    fn run(
+    fn is_serial(&self, lib_bench: &LibBench, config: &Config) -> bool {
+        lib_bench.run_options.is_serial(config.meta.args.nocapture)
+    }
+
+    fn run<'a>(
         &self,
-        lib_bench: &LibBench,
-        config: &Config,
+        lib_bench: &'a LibBench,
+        config: &'a Config,
         group: &Group,
-    ) -> Result<BenchmarkSummary> {
+        is_serial: bool,
+        registry: &ArtifactRegistry,
+    ) -> Job<'a, BenchmarkSummary> {
         let header = LibraryBenchmarkHeader::new(lib_bench);
-        header.print();
+        if is_serial {
+            header.print();
+        }
 
-        let out_path = self.output_path(lib_bench, config, group);
-        out_path.init()?;
+        let job = (|| -> Result<Job<'a, BenchmarkSummary>> {
+            let out_path = self.output_path(lib_bench, config, group);
+            out_path.init()?;
 
-        for path in lib_bench.tools.output_paths(&out_path) {
-            path.shift()?;
-            if path.kind == ToolOutputPathKind::Out {
-                path.to_log_output().shift()?;
-            }
-            if let Some(path) = path.to_xtree_output() {
+            for path in lib_bench.tools.output_paths(&out_path) {
                 path.shift()?;
+                if path.kind == ToolOutputPathKind::Out {
+                    path.to_log_output().shift()?;
+                }
+                if let Some(path) = path.to_xtree_output() {
+                    path.shift()?;
+                }
+                if let Some(path) = path.to_xleak_output() {
+                    path.shift()?;
+                }
             }
-            if let Some(path) = path.to_xleak_output() {
-                path.shift()?;
+
+            let benchmark_summary = lib_bench.create_benchmark_summary(
+                config,
+                &out_path,
+                &lib_bench.function_name,
+                header.description(),
+                self.baselines(),
+            )?;
+
+            Ok(lib_bench.tools.run(
+                &header.to_title(),
+                benchmark_summary,
+                &self.baselines(),
+                &self.baseline_kind,
+                config,
+                &config.bench_bin,
+                &lib_bench.bench_args(group),
+                &lib_bench.run_options,
+                &out_path,
+                false,
+                &lib_bench.module_path,
+                &lib_bench.output_format,
+                is_serial,
+                registry,
+            ))
+        })()
+        .unwrap_or_else(pipeline::failed);
+
+        Box::new(move || {
+            if !is_serial {
+                header.print();
+            }
+            job()
+        })
+    }
+}
+
+impl Benchmark for CodSpeedBenchmark {
+    fn output_path(&self, lib_bench: &LibBench, config: &Config, group: &Group) -> ToolOutputPath {
+        ToolOutputPath::new(
+            ToolOutputPathKind::Out,
+            lib_bench.default_tool,
+            &BaselineKind::Old,
+            &config.meta.target_dir,
+            &group.module_path,
+            &lib_bench.name(),
+        )
+    }
+
+    fn baselines(&self) -> Baselines {
+        (None, None)
+    }
+
+    fn is_serial(&self, _: &LibBench, _: &Config) -> bool {
+        true
+    }
+
+    fn run<'a>(
+        &self,
+        lib_bench: &'a LibBench,
+        config: &'a Config,
+        group: &Group,
+        _: bool,
+        _: &ArtifactRegistry,
+    ) -> Job<'a, BenchmarkSummary> {
+        let result = (|| -> Result<BenchmarkSummary> {
+            let header = LibraryBenchmarkHeader::new(lib_bench);
+            header.print();
+
+            let out_path = self.output_path(lib_bench, config, group);
+            out_path.init()?;
+
+            let benchmark_summary = lib_bench.create_benchmark_summary(
+                config,
+                &out_path,
+                &lib_bench.function_name,
+                header.description(),
+                self.baselines(),
+            )?;
+
+            let mut command = Command::new(&config.bench_bin);
+            command.args(lib_bench.bench_args(group));
+            command.envs(lib_bench.run_options.envs.iter().cloned());
+            command.env(
+                envs::IAI_CALLGRIND_CODSPEED_URI,
+                lib_bench.codspeed_uri(config),
+            );
+
+            match config.meta.args.nocapture {
+                NoCapture::False => {
+                    command.stdout(Stdio::null()).stderr(Stdio::null());
+                }
+                nocapture => nocapture.apply(&mut command),
+            }
+
+            let status = command
+                .status()
+                .map_err(|error| Error::LaunchError(config.bench_bin.clone(), error.to_string()))?;
+            if !status.success() {
+                return Err(Error::ProcessError(
+                    lib_bench.module_path.to_string(),
+                    None,
+                    status,
+                    None,
+                )
+                .into());
+            }
+
+            Ok(benchmark_summary)
+        })();
+
+        Box::new(move || result)
+    }
+}
+
+impl Group {
+    /// Create a new `Group` without benchmarks from the [`LibraryBenchmarkGroup`]
+    fn new(
+        module_path: &ModulePath,
+        library_benchmark_group: &LibraryBenchmarkGroup,
+        group_config: &LibraryBenchmarkConfig,
+        meta: &Metadata,
+    ) -> Self {
+        let assistant = |kind| {
+            Assistant::new_group_assistant(
+                kind,
+                &library_benchmark_group.id,
+                group_config.collect_envs(),
+                false,
+            )
+        };
+
+        Self {
+            name: library_benchmark_group.id.clone(),
+            module_path: module_path.join(&library_benchmark_group.id),
+            benches: vec![],
+            setup: library_benchmark_group
+                .has_setup
+                .then(|| assistant(AssistantKind::Setup)),
+            teardown: library_benchmark_group
+                .has_teardown
+                .then(|| assistant(AssistantKind::Teardown)),
+            compare_by_id: library_benchmark_group
+                .compare_by_id
+                .unwrap_or(defaults::COMPARE_BY_ID),
+            regression_budget: meta
+                .args
+                .group_regression_budget
+                .or(library_benchmark_group.regression_budget),
+        }
+    }
+
+    /// Run the last `count` benchmarks of this group, the elements of an `iter`, in a single
+    /// [`IterBatch`]
+    fn batch_last(&mut self, count: usize) {
+        let first = self.benches.len() - count;
+        let batch = Arc::new(IterBatch::new(
+            self.benches[first].iter_batch_args(self),
+            count,
+        ));
+        for (index, lib_bench) in self.benches[first..].iter_mut().enumerate() {
+            lib_bench.run_options.iter_batch =
+                Some(IterBatchElement::new(Arc::clone(&batch), index));
+        }
+    }
+
+    /// Check the `totals` of this group against the regression budget
+    ///
+    /// The budget is a soft limit of the primary metric of callgrind, cachegrind and DHAT. The
+    /// totals of the error checking tools are not checked.
+    fn check_budget(
+        totals: &IndexMap<ValgrindTool, ToolMetricSummary>,
+        budget: f64,
+    ) -> Vec<ToolRegression> {
+        let mut regressions = vec![];
+        for summary in totals.values() {
+            match summary {
+                ToolMetricSummary::Callgrind(summary) => regressions.extend(
+                    CallgrindRegressionConfig {
+                        soft_limits: vec![(EventKind::Ir, budget)],
+                        ..Default::default()
+                    }
+                    .check(summary),
+                ),
+                ToolMetricSummary::Cachegrind(summary) => regressions.extend(
+                    CachegrindRegressionConfig {
+                        soft_limits: vec![(CachegrindMetric::Ir, budget)],
+                        ..Default::default()
+                    }
+                    .check(summary),
+                ),
+                ToolMetricSummary::Dhat(summary) => regressions.extend(
+                    DhatRegressionConfig {
+                        soft_limits: vec![(DhatMetric::TotalBytes, budget)],
+                        ..Default::default()
+                    }
+                    .check(summary),
+                ),
+                ToolMetricSummary::ErrorTool(_) | ToolMetricSummary::None => {}
             }
         }
 
-        let benchmark_summary = lib_bench.create_benchmark_summary(
-            config,
-            &out_path,
-            &lib_bench.function_name,
-            header.description(),
-            self.baselines(),
-        )?;
+        regressions
+    }
 
-        lib_bench.tools.run(
-            &header.to_title(),
-            benchmark_summary,
-            &self.baselines(),
-            &self.baseline_kind,
-            config,
-            &config.bench_bin,
-            &lib_bench.bench_args(group),
-            &lib_bench.run_options,
-            &out_path,
-            false,
-            &lib_bench.module_path,
-            &lib_bench.output_format,
+    /// Print the summed up primary metrics of this group
+    ///
+    /// Nothing is printed if there are no metrics, for example in the CodSpeed mode.
+    fn print_totals(
+        &self,
+        num_benchmarks: usize,
+        totals: &IndexMap<ValgrindTool, ToolMetricSummary>,
+        output_format: &OutputFormat,
+    ) -> Result<()> {
+        if totals.is_empty() {
+            return Ok(());
+        }
+
+        VerticalFormatter::new(output_format.clone()).print_group_totals(
+            &self.module_path,
+            num_benchmarks,
+            totals.clone().into_iter().collect(),
         )
     }
+
+    /// Sum up the primary metrics of the `summaries` of the benchmarks of this group per tool
+    ///
+    /// See also [`ToolMetricSummary::to_primary`]
+    fn totals(summaries: &[BenchmarkSummary]) -> IndexMap<ValgrindTool, ToolMetricSummary> {
+        let mut totals: IndexMap<ValgrindTool, ToolMetricSummary> = IndexMap::new();
+        for profile in summaries.iter().flat_map(|summary| summary.profiles.iter()) {
+            let primary = profile.summaries.total.summary.to_primary();
+            match totals.entry(profile.tool) {
+                Entry::Occupied(mut entry) => entry.get_mut().add_mut(&primary),
+                Entry::Vacant(entry) => {
+                    entry.insert(primary);
+                }
+            }
+        }
+
+        totals
+    }
 }
 
 impl Groups {
@@ -200,44 +489,16 @@ impl Groups {
         meta: &Metadata,
     ) -> Result<Self> {
         let global_config = benchmark_groups.config;
+        let profile_config = profiles::find(&global_config.profiles, meta.args.profile.as_ref())?;
+        let global_config = global_config.clone().update_from_all([profile_config]);
         let default_tool = benchmark_groups.default_tool;
 
         let mut groups = vec![];
         for library_benchmark_group in benchmark_groups.groups {
-            let group_module_path = module_path.join(&library_benchmark_group.id);
             let group_config = global_config
                 .clone()
                 .update_from_all([library_benchmark_group.config.as_ref()]);
-
-            let setup =
-                library_benchmark_group
-                    .has_setup
-                    .then_some(Assistant::new_group_assistant(
-                        AssistantKind::Setup,
-                        &library_benchmark_group.id,
-                        group_config.collect_envs(),
-                        false,
-                    ));
-            let teardown =
-                library_benchmark_group
-                    .has_teardown
-                    .then_some(Assistant::new_group_assistant(
-                        AssistantKind::Teardown,
-                        &library_benchmark_group.id,
-                        group_config.collect_envs(),
-                        false,
-                    ));
-
-            let mut group = Group {
-                name: library_benchmark_group.id,
-                module_path: group_module_path,
-                benches: vec![],
-                setup,
-                teardown,
-                compare_by_id: library_benchmark_group
-                    .compare_by_id
-                    .unwrap_or(defaults::COMPARE_BY_ID),
-            };
+            let mut group = Group::new(module_path, &library_benchmark_group, &group_config, meta);
 
             for (group_index, library_benchmark_benches) in library_benchmark_group
                 .library_benchmarks
@@ -266,9 +527,17 @@ impl Groups {
                             }
                             _ => {
                                 for iter_index in 0..iter_count {
+                                    let id = match &library_benchmark_bench.iter_ids {
+                                        Some(ids) => ids.get(iter_index).cloned(),
+                                        None => library_benchmark_bench
+                                            .id
+                                            .as_ref()
+                                            .map(|id| format!("{id}_{iter_index}")),
+                                    };
                                     let lib_bench = LibBench::new(
-                                        library_benchmark_bench.id.clone(),
+                                        id,
                                         library_benchmark_bench.args.clone(),
+                                        library_benchmark_bench.description.clone(),
                                         module_path.clone(),
                                         library_benchmark_bench.function_name.clone(),
                                         meta,
@@ -277,15 +546,26 @@ impl Groups {
                                         bench_index,
                                         Some(iter_index),
                                         default_tool,
+                                        library_benchmark_bench.key.clone(),
+                                        library_benchmark_bench.runs_on.clone(),
+                                        library_benchmark_bench.skip.clone(),
+                                        library_benchmark_bench.expect_regression.clone(),
                                     )?;
                                     group.benches.push(lib_bench);
                                 }
+
+                                // The benchmarks of criterion_compat can't run all elements in a
+                                // single benchmark process
+                                if library_benchmark_bench.iter_ids.is_none() {
+                                    group.batch_last(iter_count);
+                                }
                             }
                         }
                     } else {
                         let lib_bench = LibBench::new(
                             library_benchmark_bench.id,
                             library_benchmark_bench.args,
+                            library_benchmark_bench.description,
                             module_path,
                             library_benchmark_bench.function_name,
                             meta,
@@ -294,6 +574,10 @@ impl Groups {
                             bench_index,
                             None,
                             default_tool,
+                            library_benchmark_bench.key,
+                            library_benchmark_bench.runs_on,
+                            library_benchmark_bench.skip,
+                            library_benchmark_bench.expect_regression,
                         )?;
                         group.benches.push(lib_bench);
                     }
@@ -306,59 +590,238 @@ impl Groups {
         Ok(Self(groups))
     }
 
+    /// Return the total number of [`LibBench`] benchmarks in all groups which aren't skipped
+    fn num_benchmarks(&self) -> usize {
+        self.0
+            .iter()
+            .flat_map(|group| group.benches.iter())
+            .filter(|bench| bench.skip_reason().is_none())
+            .count()
+    }
+
     /// Run all [`LibBench`] benchmarks
+    ///
+    /// The benchmarks of a group run in a [`Pipeline`]: While valgrind runs a benchmark, the
+    /// output files of the previous benchmark are parsed and its terminal output is printed. The
+    /// summary of a benchmark is saved after its [`Job`] has finished.
+    ///
+    /// If a benchmark fails with an error or a regression with `fail_fast`, the benchmark run is
+    /// aborted and all remaining benchmarks are skipped (see [`BenchmarkSummaries::abort`]).
+    #[allow(clippy::too_many_lines)]
     fn run(&self, benchmark: &dyn Benchmark, config: &Config) -> Result<BenchmarkSummaries> {
+        let num_benchmarks = self.num_benchmarks();
         let mut benchmark_summaries = BenchmarkSummaries::default();
         for group in &self.0 {
             if let Some(setup) = &group.setup {
                 setup.run(config, &group.module_path)?;
             }
 
+            let first_summary = benchmark_summaries.summaries.len();
             let mut lib_bench_summaries: HashMap<String, Vec<BenchmarkSummary>> =
                 HashMap::with_capacity(group.benches.len());
-            for bench in &group.benches {
-                let fail_fast = bench
-                    .tools
-                    .0
-                    .iter()
-                    .any(|c| c.regression_config.is_fail_fast());
-
-                let lib_bench_summary = benchmark.run(bench, config, group)?;
-                lib_bench_summary.print_and_save(&config.meta.args.output_format)?;
-                lib_bench_summary.check_regression(fail_fast)?;
-
-                benchmark_summaries.add_summary(lib_bench_summary.clone());
-                if group.compare_by_id && bench.output_format.is_default() {
-                    if let Some(id) = &lib_bench_summary.id {
-                        if let Some(sums) = lib_bench_summaries.get_mut(id) {
-                            for sum in sums.iter() {
-                                sum.compare_and_print(
-                                    id,
-                                    &lib_bench_summary,
-                                    &bench.output_format,
-                                )?;
+            let totals_output_format = group
+                .benches
+                .iter()
+                .map(|bench| &bench.output_format)
+                .find(|output_format| output_format.show_group_totals);
+            let mut group_summaries = vec![];
+
+            let is_aborted = std::thread::scope(|scope| -> Result<bool> {
+                let pipeline = Pipeline::new(scope);
+
+                // Wait for the pending benchmark and handle its summary. Returns true if the
+                // benchmark run is aborted.
+                let mut finish = |pending: PendingBench<'_, '_>| -> Result<bool> {
+                    let bench = pending.1;
+                    let result = Self::finish_bench(pending, config).and_then(|mut summary| {
+                        if bench.output_format.is_hidden(&summary) {
+                            benchmark_summaries.num_unchanged += 1;
+                        }
+                        // Only the totals are needed after the complete summary was streamed
+                        if config.summary_stream.is_some() {
+                            summary.clear_parts();
+                        }
+                        benchmark_summaries.add_summary(summary.clone());
+                        summary
+                            .check_regression(bench.is_fail_fast())
+                            .map(|()| summary)
+                    });
+                    let lib_bench_summary = match result {
+                        Ok(summary) => summary,
+                        Err(error) => {
+                            benchmark_summaries.abort(error);
+                            benchmark_summaries.skip_remaining(num_benchmarks);
+                            return Ok(true);
+                        }
+                    };
+
+                    if totals_output_format.is_some() || group.regression_budget.is_some() {
+                        group_summaries.push(lib_bench_summary.clone());
+                    }
+
+                    if group.compare_by_id && bench.output_format.is_default() {
+                        if let Some(id) = &lib_bench_summary.id {
+                            if let Some(sums) = lib_bench_summaries.get_mut(id) {
+                                // The comparisons of a hidden benchmark are hidden, too
+                                if !bench.output_format.is_hidden(&lib_bench_summary) {
+                                    for sum in sums.iter() {
+                                        sum.compare_and_print(
+                                            id,
+                                            &lib_bench_summary,
+                                            &bench.output_format,
+                                        )?;
+                                    }
+                                }
+                                sums.push(lib_bench_summary);
+                            } else {
+                                lib_bench_summaries.insert(id.clone(), vec![lib_bench_summary]);
                             }
-                            sums.push(lib_bench_summary);
-                        } else {
-                            lib_bench_summaries.insert(id.clone(), vec![lib_bench_summary]);
                         }
                     }
+
+                    Ok(false)
+                };
+
+                let mut pending: Option<PendingBench<'_, '_>> = None;
+                for bench in &group.benches {
+                    let is_serial = benchmark.is_serial(bench, config);
+
+                    // The terminal output of the pending benchmark has to be printed first. A
+                    // regression with `fail_fast` aborts the benchmark run before the next
+                    // benchmark runs.
+                    if bench.skip_reason().is_some()
+                        || is_serial
+                        || pending
+                            .as_ref()
+                            .is_some_and(|(_, bench, ..)| bench.is_fail_fast())
+                    {
+                        if let Some(pending) = pending.take() {
+                            if finish(pending)? {
+                                return Ok(true);
+                            }
+                        }
+                    }
+
+                    if let Some(reason) = bench.skip_reason() {
+                        LibraryBenchmarkHeader::new(bench).print_skipped(reason);
+                        continue;
+                    }
+
+                    let (job, run_log, registry) =
+                        Self::run_bench(benchmark, bench, config, group, is_serial);
+                    if let Some(pending) = pending.take() {
+                        if finish(pending)? {
+                            return Ok(true);
+                        }
+                    }
+                    pending = Some((pipeline.submit(job, is_serial), bench, run_log, registry));
+                }
+
+                match pending {
+                    Some(pending) => finish(pending),
+                    None => Ok(false),
+                }
+            })?;
+            if is_aborted {
+                return Ok(benchmark_summaries);
+            }
+
+            let totals = Group::totals(&group_summaries);
+            if let Some(output_format) = totals_output_format {
+                group.print_totals(group_summaries.len(), &totals, output_format)?;
+            }
+
+            if let Some(budget) = group.regression_budget {
+                let regressions = Group::check_budget(&totals, budget);
+                if !regressions.is_empty() {
+                    format::print_group_regressions(&group.module_path, &regressions);
+                    benchmark_summaries
+                        .add_group_regressions(group.module_path.clone(), regressions);
                 }
             }
 
             if let Some(teardown) = &group.teardown {
-                teardown.run(config, &group.module_path)?;
+                teardown.run_with_outcomes(
+                    config,
+                    &group.module_path,
+                    &benchmark_summaries.summaries[first_summary..],
+                )?;
             }
         }
 
         Ok(benchmark_summaries)
     }
+
+    /// Run valgrind for a single [`LibBench`] benchmark with its `run.log`
+    ///
+    /// The returned [`Job`] continues writing into the `run.log` of this benchmark even if it runs
+    /// on a worker thread. With [`OutputFormat::hide_unchanged`], the job prints the terminal
+    /// output of the benchmark after the benchmark has run if the metrics have changed.
+    fn run_bench<'a>(
+        benchmark: &dyn Benchmark,
+        bench: &'a LibBench,
+        config: &'a Config,
+        group: &Group,
+        is_serial: bool,
+    ) -> (Job<'a, BenchmarkSummary>, Option<RunLog>, ArtifactRegistry) {
+        let run_log = match RunLog::start(&benchmark.output_path(bench, config, group).dir) {
+            Ok(run_log) => run_log,
+            Err(error) => return (pipeline::failed(error), None, ArtifactRegistry::default()),
+        };
+        let registry = ArtifactRegistry::default();
+        registry.register(ArtifactKind::RunLog, [run_log.path()]);
+
+        let job = benchmark.run(bench, config, group, is_serial, &registry);
+        let context = run_log.context();
+        let baselines = benchmark.baselines();
+        let job: Job<'a, BenchmarkSummary> = Box::new(move || {
+            let _context = context.enter();
+            let summary = job()?;
+            if bench.output_format.hide_unchanged && !bench.output_format.is_hidden(&summary) {
+                LibraryBenchmarkHeader::new(bench).print_deferred();
+                bench
+                    .tools
+                    .print_deferred(&summary, &baselines, config, &bench.output_format)?;
+            }
+            if let Some(reason) = &summary.expected_regression {
+                if !bench.output_format.is_hidden(&summary) {
+                    format::print_expected_regression(reason, summary.is_expected_regression());
+                }
+            }
+            Ok(summary)
+        });
+
+        (job, Some(run_log), registry)
+    }
+
+    /// Wait for the `pending` [`Job`] of a [`LibBench`] benchmark and print and save the summary
+    ///
+    /// The summary is saved only after the job has finished, so the summary contains everything
+    /// the job has created and the [`ArtifactRegistry`] is complete.
+    fn finish_bench(
+        (pending, _, run_log, registry): PendingBench<'_, '_>,
+        config: &Config,
+    ) -> Result<BenchmarkSummary> {
+        let mut summary = pending.join()?;
+
+        // The `RunLog` of the next benchmark may already be active and the run.log of this
+        // benchmark has to be complete before its checksum is calculated
+        let _context = run_log::Context::default().enter();
+        summary.run_log = run_log.map(|run_log| run_log.path().to_owned());
+        summary.collect_artifacts(&registry)?;
+        summary.print_and_save(&config.meta.args.output_format)?;
+        if let Some(path) = &config.summary_stream {
+            summary.append_to_stream(path)?;
+        }
+        Ok(summary)
+    }
 }
 
 impl LibBench {
     fn new(
         id: Option<String>,
         display: Option<String>,
+        description: Option<String>,
         module_path: ModulePath,
         function_name: String,
         meta: &Metadata,
@@ -367,13 +830,11 @@ impl LibBench {
         bench_index: usize,
         iter_index: Option<usize>,
         default_tool: ValgrindTool,
+        key: Option<String>,
+        runs_on: Option<String>,
+        skip: Option<String>,
+        expect_regression: Option<String>,
     ) -> Result<Self> {
-        let id = if let Some(iter_index) = iter_index {
-            id.as_ref().map(|s| format!("{s}_{iter_index}"))
-        } else {
-            id
-        };
-
         let envs = config.resolve_envs();
         let mut default_args = HashMap::new();
 
@@ -420,12 +881,20 @@ impl LibBench {
             iter_index,
             id,
             function_name,
+            description,
             display,
+            expect_regression,
             run_options: RunOptions {
                 env_clear: config.env_clear.unwrap_or(true),
                 envs,
+                sandbox: config.sandbox,
+                vgdb: meta.args.vgdb.or(config.vgdb),
                 ..Default::default()
             },
+            key,
+            runs_on,
+            skip,
+            tags: config.tags,
             tools: tool_configs,
             module_path,
             output_format,
@@ -436,18 +905,67 @@ impl LibBench {
     /// The name of this `LibBench` consisting of the name of the benchmark function and if present,
     /// the id of the bench attribute (`#[bench::ID(...)]`)
     ///
-    /// The name is used to identify a benchmark run within the same [`Group`] and has therefore to
-    /// be unique within the same [`Group`]
+    /// If a stable `key` is present, it replaces the function name and id, so renaming them doesn't
+    /// orphan the output files and baselines of former runs. The name is used to identify a
+    /// benchmark run within the same [`Group`] and has therefore to be unique within the same
+    /// [`Group`]
+    /// Return true if a regression of this benchmark aborts the benchmark run
+    fn is_fail_fast(&self) -> bool {
+        self.tools
+            .0
+            .iter()
+            .any(|c| c.regression_config.is_fail_fast())
+    }
+
     fn name(&self) -> String {
-        if let Some(bench_id) = &self.id {
+        if let Some(key) = &self.key {
+            if let Some(iter_index) = self.iter_index {
+                format!("{key}_{iter_index}")
+            } else {
+                key.clone()
+            }
+        } else if let Some(bench_id) = &self.id {
             format!("{}.{}", &self.function_name, bench_id)
         } else {
             self.function_name.clone()
         }
     }
 
+    /// Return true if this benchmark runs on the target of this runner
+    ///
+    /// A benchmark without `runs_on` runs on all targets. If `runs_on` is prefixed with `!`, the
+    /// benchmark runs on all targets except the one following the `!`.
+    fn runs_on_target(&self) -> bool {
+        self.runs_on.as_deref().map_or(true, |runs_on| {
+            runs_on.strip_prefix('!').map_or_else(
+                || runs_on == env!("IC_BUILD_TRIPLE"),
+                |target| target.trim_start() != env!("IC_BUILD_TRIPLE"),
+            )
+        })
+    }
+
+    /// Return the reason if this benchmark is skipped
+    ///
+    /// A benchmark is skipped if it was already skipped at compile time, for example because of
+    /// the `rust_version`, or if it doesn't run on this target.
+    fn skip_reason(&self) -> Option<&str> {
+        self.skip
+            .as_deref()
+            .or_else(|| (!self.runs_on_target()).then_some("target"))
+    }
+
     /// The arguments for the `bench_bin` to actually run the benchmark function
     fn bench_args(&self, group: &Group) -> Vec<OsString> {
+        self.harness_args(group, self.iter_index.map(|index| index.to_string()))
+    }
+
+    /// The arguments for the `bench_bin` to run all elements of an `iter` benchmark
+    fn iter_batch_args(&self, group: &Group) -> Vec<OsString> {
+        self.harness_args(group, Some("all".to_owned()))
+    }
+
+    /// The arguments for the `bench_bin` with the element of an `iter` benchmark if present
+    fn harness_args(&self, group: &Group, iter_arg: Option<String>) -> Vec<OsString> {
         let mut args = vec![
             OsString::from("--iai-run".to_owned()),
             OsString::from(&group.name),
@@ -455,14 +973,26 @@ impl LibBench {
             OsString::from(self.bench_index.to_string()),
         ];
 
-        if let Some(iter_index) = self.iter_index {
-            args.push(OsString::from(iter_index.to_string()));
+        if let Some(iter_arg) = iter_arg {
+            args.push(OsString::from(iter_arg));
         }
 
         args.push(OsString::from(self.module_path.to_string()));
         args
     }
 
+    /// The uri identifying this benchmark in the CodSpeed compatible mode
+    ///
+    /// The uri has the form `path/to/bench_file.rs::module::path` with the id appended if present
+    fn codspeed_uri(&self, config: &Config) -> String {
+        let uri = format!("{}::{}", config.bench_file.display(), self.module_path);
+        if let Some(id) = &self.id {
+            format!("{uri}::{id}")
+        } else {
+            uri
+        }
+    }
+
     /// This method creates the initial [`BenchmarkSummary`]
     fn create_benchmark_summary(
         &self,
@@ -480,7 +1010,8 @@ impl LibBench {
             None
         };
 
-        Ok(BenchmarkSummary::new(
+        let git = config.summary_git(summary_output.as_ref());
+        let mut summary = BenchmarkSummary::new(
             BenchmarkKind::LibraryBenchmark,
             config.meta.project_root.clone(),
             config.package_dir.clone(),
@@ -492,7 +1023,21 @@ impl LibBench {
             description,
             summary_output,
             baselines,
-        ))
+            git,
+            Some(
+                config
+                    .meta
+                    .environment
+                    .with_tools(self.tools.enabled_tools()),
+            ),
+            config.metadata.clone(),
+        );
+        summary.description.clone_from(&self.description);
+        summary
+            .expected_regression
+            .clone_from(&self.expect_regression);
+
+        Ok(summary)
     }
 }
 
@@ -520,35 +1065,46 @@ impl Benchmark for LoadBaselineBenchmark {
         )
     }
 
-    fn run(
+    fn is_serial(&self, _: &LibBench, _: &Config) -> bool {
+        true
+    }
+
+    fn run<'a>(
         &self,
-        lib_bench: &LibBench,
-        config: &Config,
+        lib_bench: &'a LibBench,
+        config: &'a Config,
         group: &Group,
-    ) -> Result<BenchmarkSummary> {
-        let header = LibraryBenchmarkHeader::new(lib_bench);
-        header.print();
-
-        let out_path = self.output_path(lib_bench, config, group);
-
-        let benchmark_summary = lib_bench.create_benchmark_summary(
-            config,
-            &out_path,
-            &lib_bench.function_name,
-            header.description(),
-            self.baselines(),
-        )?;
-
-        lib_bench.tools.run_loaded_vs_base(
-            &header.to_title(),
-            &self.baseline,
-            &self.loaded_baseline,
-            benchmark_summary,
-            &self.baselines(),
-            config,
-            &out_path,
-            &lib_bench.output_format,
-        )
+        _: bool,
+        registry: &ArtifactRegistry,
+    ) -> Job<'a, BenchmarkSummary> {
+        let result = (|| -> Result<BenchmarkSummary> {
+            let header = LibraryBenchmarkHeader::new(lib_bench);
+            header.print();
+
+            let out_path = self.output_path(lib_bench, config, group);
+
+            let benchmark_summary = lib_bench.create_benchmark_summary(
+                config,
+                &out_path,
+                &lib_bench.function_name,
+                header.description(),
+                self.baselines(),
+            )?;
+
+            lib_bench.tools.run_loaded_vs_base(
+                &header.to_title(),
+                &self.baseline,
+                &self.loaded_baseline,
+                benchmark_summary,
+                &self.baselines(),
+                config,
+                &out_path,
+                &lib_bench.output_format,
+                registry,
+            )
+        })();
+
+        Box::new(move || result)
     }
 }
 
@@ -573,32 +1129,33 @@ impl Runner {
         let groups =
             Groups::from_library_benchmark(&config.module_path, benchmark_groups, &config.meta)?;
 
-        let benchmark: Box<dyn Benchmark> =
-            if let Some(baseline_name) = &config.meta.args.save_baseline {
-                Box::new(SaveBaselineBenchmark {
-                    baseline: baseline_name.clone(),
-                })
-            } else if let Some(baseline_name) = &config.meta.args.load_baseline {
-                Box::new(LoadBaselineBenchmark {
-                    loaded_baseline: baseline_name.clone(),
-                    baseline: config
-                        .meta
-                        .args
-                        .baseline
-                        .as_ref()
-                        .expect("A baseline should be present")
-                        .clone(),
-                })
-            } else {
-                Box::new(BaselineBenchmark {
-                    baseline_kind: config
-                        .meta
-                        .args
-                        .baseline
-                        .as_ref()
-                        .map_or(BaselineKind::Old, |name| BaselineKind::Name(name.clone())),
-                })
-            };
+        let benchmark: Box<dyn Benchmark> = if config.meta.args.is_codspeed() {
+            Box::new(CodSpeedBenchmark)
+        } else if let Some(baseline_name) = &config.meta.args.save_baseline {
+            Box::new(SaveBaselineBenchmark {
+                baseline: baseline_name.clone(),
+            })
+        } else if let Some(baseline_name) = &config.meta.args.load_baseline {
+            Box::new(LoadBaselineBenchmark {
+                loaded_baseline: baseline_name.clone(),
+                baseline: config
+                    .meta
+                    .args
+                    .baseline
+                    .as_ref()
+                    .expect("A baseline should be present")
+                    .clone(),
+            })
+        } else {
+            Box::new(BaselineBenchmark {
+                baseline_kind: config
+                    .meta
+                    .args
+                    .baseline
+                    .as_ref()
+                    .map_or(BaselineKind::Old, |name| BaselineKind::Name(name.clone())),
+            })
+        };
 
         Ok(Self {
             benchmark,
@@ -609,6 +1166,103 @@ impl Runner {
         })
     }
 
+    /// Measure the overhead of the benchmark harness with an empty benchmark function
+    ///
+    /// The calibration is run only once and only if the overhead is subtracted in at least one
+    /// benchmark. The callgrind configuration of the first of these benchmarks is used. Nothing is
+    /// run in the CodSpeed compatible mode and with `--load-baseline`.
+    fn calibrate(&mut self) -> Result<()> {
+        let args = &self.config.meta.args;
+        if args.is_codspeed() || args.load_baseline.is_some() {
+            return Ok(());
+        }
+
+        let Some((bench, tool_config)) = self
+            .groups
+            .0
+            .iter()
+            .flat_map(|group| &group.benches)
+            .filter(|bench| bench.output_format.subtract_overhead)
+            .find_map(|bench| {
+                bench
+                    .tools
+                    .0
+                    .iter()
+                    .find(|tool_config| {
+                        tool_config.is_enabled && tool_config.tool == ValgrindTool::Callgrind
+                    })
+                    .map(|tool_config| (bench, tool_config))
+            })
+        else {
+            return Ok(());
+        };
+
+        let temp_dir = tempfile::tempdir()?;
+        let output_path = ToolOutputPath {
+            baseline_kind: BaselineKind::Old,
+            dir: temp_dir.path().to_owned(),
+            kind: ToolOutputPathKind::Out,
+            modifiers: vec![],
+            name: CALIBRATION.to_owned(),
+            tool: ValgrindTool::Callgrind,
+        };
+
+        ToolCommand::new(ValgrindTool::Callgrind, &self.config.meta, NoCapture::False).run(
+            tool_config.clone(),
+            &self.config.bench_bin,
+            &[OsString::from("--iai-run"), OsString::from(CALIBRATION)],
+            RunOptions {
+                env_clear: bench.run_options.env_clear,
+                envs: bench.run_options.envs.clone(),
+                ..Default::default()
+            },
+            &output_path,
+            &self.config.module_path,
+            None,
+        )?;
+
+        let overhead = parser_factory(
+            tool_config,
+            self.config.meta.project_root.clone(),
+            &output_path,
+        )
+        .parse()?
+        .into_iter()
+        .find_map(|output| match output.metrics {
+            ToolMetrics::Callgrind(metrics) => Some(metrics),
+            _ => None,
+        });
+
+        debug!("The overhead of the benchmark harness: {overhead:?}");
+        self.config.overhead = overhead;
+
+        Ok(())
+    }
+
+    /// Create the [`BenchmarkList`] of all benchmarks in all groups
+    fn list(&self) -> BenchmarkList {
+        let mut list = BenchmarkList::new(BenchmarkKind::LibraryBenchmark, &self.config);
+        for group in &self.groups.0 {
+            let mut list_group = ListGroup::new(&group.name, &group.module_path);
+            for bench in &group.benches {
+                let output_path = self.benchmark.output_path(bench, &self.config, group);
+                list_group.benchmarks.push(ListBenchmark {
+                    description: bench.description.clone(),
+                    details: bench.display.clone(),
+                    function: bench.function_name.clone(),
+                    id: bench.id.clone(),
+                    module_path: bench.module_path.to_string(),
+                    output_dir: output_path.dir.clone(),
+                    tags: bench.tags.clone(),
+                    tools: ListTool::from_tool_configs(&bench.tools, &output_path),
+                });
+            }
+            list.groups.push(list_group);
+        }
+
+        list
+    }
+
     /// Run all benchmarks in all groups
     fn run(&self) -> Result<BenchmarkSummaries> {
         if let Some(setup) = &self.setup {
@@ -617,8 +1271,12 @@ impl Runner {
 
         let summaries = self.groups.run(self.benchmark.as_ref(), &self.config)?;
 
-        if let Some(teardown) = &self.teardown {
-            teardown.run(&self.config, &self.config.module_path)?;
+        if let Some(teardown) = self.teardown.as_ref().filter(|_| !summaries.is_aborted()) {
+            teardown.run_with_outcomes(
+                &self.config,
+                &self.config.module_path,
+                &summaries.summaries,
+            )?;
         }
 
         Ok(summaries)
@@ -649,45 +1307,74 @@ impl Benchmark for SaveBaselineBenchmark {
         )
     }
 
-    fn run(
+    fn is_serial(&self, lib_bench: &LibBench, config: &Config) -> bool {
+        lib_bench.run_options.is_serial(config.meta.args.nocapture)
+    }
+
+    fn run<'a>(
         &self,
-        lib_bench: &LibBench,
-        config: &Config,
+        lib_bench: &'a LibBench,
+        config: &'a Config,
         group: &Group,
-    ) -> Result<BenchmarkSummary> {
+        is_serial: bool,
+        registry: &ArtifactRegistry,
+    ) -> Job<'a, BenchmarkSummary> {
         let header = LibraryBenchmarkHeader::new(lib_bench);
-        header.print();
-
-        let out_path = self.output_path(lib_bench, config, group);
-        out_path.init()?;
-
-        let benchmark_summary = lib_bench.create_benchmark_summary(
-            config,
-            &out_path,
-            &lib_bench.function_name,
-            header.description(),
-            self.baselines(),
-        )?;
+        if is_serial {
+            header.print();
+        }
 
-        lib_bench.tools.run(
-            &header.to_title(),
-            benchmark_summary,
-            &self.baselines(),
-            &BaselineKind::Name(self.baseline.clone()),
-            config,
-            &config.bench_bin,
-            &lib_bench.bench_args(group),
-            &lib_bench.run_options,
-            &out_path,
-            true,
-            &lib_bench.module_path,
-            &lib_bench.output_format,
-        )
+        let job = (|| -> Result<Job<'a, BenchmarkSummary>> {
+            let out_path = self.output_path(lib_bench, config, group);
+            out_path.init()?;
+
+            let benchmark_summary = lib_bench.create_benchmark_summary(
+                config,
+                &out_path,
+                &lib_bench.function_name,
+                header.description(),
+                self.baselines(),
+            )?;
+
+            Ok(lib_bench.tools.run(
+                &header.to_title(),
+                benchmark_summary,
+                &self.baselines(),
+                &BaselineKind::Name(self.baseline.clone()),
+                config,
+                &config.bench_bin,
+                &lib_bench.bench_args(group),
+                &lib_bench.run_options,
+                &out_path,
+                true,
+                &lib_bench.module_path,
+                &lib_bench.output_format,
+                is_serial,
+                registry,
+            ))
+        })()
+        .unwrap_or_else(pipeline::failed);
+
+        Box::new(move || {
+            if !is_serial {
+                header.print();
+            }
+            job()
+        })
     }
 }
 
 /// Print a list of all benchmarks with a short summary
-pub fn list(benchmark_groups: LibraryBenchmarkGroups, config: &Config) -> Result<()> {
+///
+/// With a json `--output-format`, the [`BenchmarkList`] is printed instead of the terminal output.
+pub fn list(benchmark_groups: LibraryBenchmarkGroups, config: Config) -> Result<()> {
+    let output_format = config.meta.args.output_format;
+    if output_format != OutputFormatKind::Default {
+        return Runner::new(benchmark_groups, config)?
+            .list()
+            .print(output_format);
+    }
+
     let groups =
         Groups::from_library_benchmark(&config.module_path, benchmark_groups, &config.meta)?;
 
@@ -706,9 +1393,10 @@ pub fn list(benchmark_groups: LibraryBenchmarkGroups, config: &Config) -> Result
 
 /// The top-level method which should be used to initiate running all benchmarks
 pub fn run(benchmark_groups: LibraryBenchmarkGroups, config: Config) -> Result<BenchmarkSummaries> {
-    let runner = Runner::new(benchmark_groups, config)?;
+    let mut runner = Runner::new(benchmark_groups, config)?;
 
     let start = Instant::now();
+    runner.calibrate()?;
     let mut summaries = runner.run()?;
     summaries.elapsed(start);
 
@@ -0,0 +1,331 @@
+//! The module containing the [`BenchmarkList`] of the `--list` argument in the json output format
+//!
+//! In contrast to the terminal output of `--list`, which mimics the output of the libtest harness,
+//! the json output contains the complete benchmark tree of a benchmark file with the resolved tool
+//! configurations and the output paths each benchmark would write to. It is meant to be consumed by
+//! tools like IDE test explorers. Each benchmark file prints a single json document.
+
+use std::io::stdout;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+use super::common::{Config, ModulePath};
+use super::format::OutputFormatKind;
+use super::summary::BenchmarkKind;
+use super::tool::config::ToolConfigs;
+use super::tool::path::ToolOutputPath;
+use crate::api::{EntryPoint, ValgrindTool};
+
+/// All benchmarks of a single benchmark file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BenchmarkList {
+    /// The path to the compiled benchmark executable
+    pub bench_bin: PathBuf,
+    /// The path to the benchmark file
+    pub bench_file: PathBuf,
+    /// The benchmark groups in the order of the `main!` macro
+    pub groups: Vec<ListGroup>,
+    /// The kind of benchmarks, either library or binary benchmarks
+    pub kind: BenchmarkKind,
+    /// The directory of the package containing the benchmark file
+    pub package_dir: PathBuf,
+}
+
+/// A single benchmark of a [`ListGroup`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ListBenchmark {
+    /// The user provided description of a library benchmark
+    pub description: Option<String>,
+    /// The arguments of the benchmark as string (the same as shown in the terminal output)
+    pub details: Option<String>,
+    /// The name of the benchmark function
+    pub function: String,
+    /// The id of the benchmark as in `#[bench::id]`
+    pub id: Option<String>,
+    /// The module path of the benchmark as shown in the terminal output
+    pub module_path: String,
+    /// The directory in which all output files of this benchmark are stored
+    pub output_dir: PathBuf,
+    /// The tags of the benchmark as in `LibraryBenchmarkConfig::tags`
+    pub tags: Vec<String>,
+    /// The enabled tools of this benchmark
+    pub tools: Vec<ListTool>,
+}
+
+/// A benchmark group as in `library_benchmark_group!` or `binary_benchmark_group!`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ListGroup {
+    /// The benchmarks of this group
+    pub benchmarks: Vec<ListBenchmark>,
+    /// The module path of this group
+    pub module_path: String,
+    /// The name of this group
+    pub name: String,
+}
+
+/// The resolved configuration of an enabled tool
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ListTool {
+    /// The valgrind arguments of this tool without the output file arguments
+    pub args: Vec<String>,
+    /// The [`EntryPoint`] of this tool
+    pub entry_point: EntryPoint,
+    /// True if this tool is the default tool of the benchmark
+    pub is_default: bool,
+    /// The path to the log file
+    pub log_path: PathBuf,
+    /// The path to the output file if the tool creates an output file besides the log file
+    pub output_path: Option<PathBuf>,
+    /// The valgrind tool
+    pub tool: ValgrindTool,
+}
+
+impl BenchmarkList {
+    /// Create a new `BenchmarkList` without any groups
+    pub fn new(kind: BenchmarkKind, config: &Config) -> Self {
+        Self {
+            bench_bin: config.bench_bin.clone(),
+            bench_file: config.bench_file.clone(),
+            groups: vec![],
+            kind,
+            package_dir: config.package_dir.clone(),
+        }
+    }
+
+    /// Return the number of benchmarks in all groups
+    pub fn num_benchmarks(&self) -> usize {
+        self.groups.iter().map(|group| group.benchmarks.len()).sum()
+    }
+
+    /// Print this list as json to stdout
+    ///
+    /// Nothing is printed if the `output_format` is the default terminal output format.
+    pub fn print(&self, output_format: OutputFormatKind) -> Result<()> {
+        let writer = stdout().lock();
+        let result = match output_format {
+            OutputFormatKind::Default => return Ok(()),
+            OutputFormatKind::Json => serde_json::to_writer(writer, self),
+            OutputFormatKind::PrettyJson => serde_json::to_writer_pretty(writer, self),
+        };
+        println!();
+
+        result.with_context(|| "Failed to print the benchmark list as json to stdout")
+    }
+}
+
+impl ListGroup {
+    /// Create a new and empty `ListGroup`
+    pub fn new(name: &str, module_path: &ModulePath) -> Self {
+        Self {
+            benchmarks: vec![],
+            module_path: module_path.to_string(),
+            name: name.to_owned(),
+        }
+    }
+}
+
+impl ListTool {
+    /// Create the `ListTool`s of all enabled tools in `tools`
+    ///
+    /// The `output_path` is the output path of the default tool as used in the benchmark run.
+    pub fn from_tool_configs(tools: &ToolConfigs, output_path: &ToolOutputPath) -> Vec<Self> {
+        tools
+            .0
+            .iter()
+            .filter(|tool_config| tool_config.is_enabled)
+            .map(|tool_config| {
+                let tool_output_path = output_path.to_tool_output(tool_config.tool);
+                Self {
+                    args: tool_config
+                        .args
+                        .to_vec()
+                        .iter()
+                        .map(|arg| arg.to_string_lossy().into_owned())
+                        .collect(),
+                    entry_point: tool_config.entry_point.clone(),
+                    is_default: tool_config.is_default,
+                    log_path: tool_output_path.to_log_output().to_path(),
+                    output_path: tool_config
+                        .tool
+                        .has_output_file()
+                        .then(|| tool_output_path.to_path()),
+                    tool: tool_config.tool,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    use super::*;
+    use crate::api::{ThreadSelection, TotalAggregation};
+    use crate::runner::summary::BaselineKind;
+    use crate::runner::tool::args::ToolArgs;
+    use crate::runner::tool::config::{ToolConfig, ToolFlamegraphConfig};
+    use crate::runner::tool::path::ToolOutputPathKind;
+    use crate::runner::tool::regression::ToolRegressionConfig;
+
+    fn tool_config(tool: ValgrindTool, is_enabled: bool, is_default: bool) -> ToolConfig {
+        ToolConfig::new(
+            tool,
+            is_enabled,
+            ToolArgs::try_from_raw_args(tool, &[]).unwrap(),
+            ToolRegressionConfig::None,
+            ToolFlamegraphConfig::None,
+            EntryPoint::None,
+            is_default,
+            vec![],
+            false,
+            ThreadSelection::default(),
+            TotalAggregation::default(),
+        )
+    }
+
+    fn list_benchmark(function: &str, tags: &[&str]) -> ListBenchmark {
+        ListBenchmark {
+            description: None,
+            details: Some("1, 2".to_owned()),
+            function: function.to_owned(),
+            id: Some("some_id".to_owned()),
+            module_path: format!("bench_file::group::{function}"),
+            output_dir: PathBuf::from("/target/iai/bench_file/group").join(function),
+            tags: tags.iter().map(|tag| (*tag).to_owned()).collect(),
+            tools: vec![ListTool {
+                args: vec!["--trace-children=yes".to_owned()],
+                entry_point: EntryPoint::None,
+                is_default: true,
+                log_path: PathBuf::from("/target/iai/bench_file/group")
+                    .join(function)
+                    .join("memcheck.some_id.log"),
+                output_path: None,
+                tool: ValgrindTool::Memcheck,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_benchmark_list_serialize() {
+        let list = BenchmarkList {
+            bench_bin: PathBuf::from("/target/release/deps/bench_file-1234"),
+            bench_file: PathBuf::from("benches/bench_file.rs"),
+            groups: vec![ListGroup {
+                benchmarks: vec![list_benchmark("bench", &["slow", "io"])],
+                module_path: "bench_file::group".to_owned(),
+                name: "group".to_owned(),
+            }],
+            kind: BenchmarkKind::BinaryBenchmark,
+            package_dir: PathBuf::from("/package"),
+        };
+
+        assert_eq!(
+            serde_json::to_value(&list).unwrap(),
+            json!({
+                "bench_bin": "/target/release/deps/bench_file-1234",
+                "bench_file": "benches/bench_file.rs",
+                "groups": [{
+                    "benchmarks": [{
+                        "description": null,
+                        "details": "1, 2",
+                        "function": "bench",
+                        "id": "some_id",
+                        "module_path": "bench_file::group::bench",
+                        "output_dir": "/target/iai/bench_file/group/bench",
+                        "tags": ["slow", "io"],
+                        "tools": [{
+                            "args": ["--trace-children=yes"],
+                            "entry_point": "None",
+                            "is_default": true,
+                            "log_path": "/target/iai/bench_file/group/bench/memcheck.some_id.log",
+                            "output_path": null,
+                            "tool": "Memcheck"
+                        }]
+                    }],
+                    "module_path": "bench_file::group",
+                    "name": "group"
+                }],
+                "kind": "BinaryBenchmark",
+                "package_dir": "/package"
+            })
+        );
+    }
+
+    #[test]
+    fn test_benchmark_list_serialize_when_no_tags() {
+        let value = serde_json::to_value(list_benchmark("bench", &[])).unwrap();
+        assert_eq!(value["tags"], json!([]));
+    }
+
+    #[test]
+    fn test_benchmark_list_num_benchmarks() {
+        let mut list = BenchmarkList {
+            bench_bin: PathBuf::from("bench_bin"),
+            bench_file: PathBuf::from("bench_file.rs"),
+            groups: vec![],
+            kind: BenchmarkKind::LibraryBenchmark,
+            package_dir: PathBuf::from("package"),
+        };
+        assert_eq!(list.num_benchmarks(), 0);
+
+        for num in [1, 2] {
+            let mut group = ListGroup::new("group", &ModulePath::new("bench_file::group"));
+            group
+                .benchmarks
+                .extend(std::iter::repeat_with(|| list_benchmark("bench", &[])).take(num));
+            list.groups.push(group);
+        }
+        assert_eq!(list.num_benchmarks(), 3);
+    }
+
+    #[test]
+    fn test_list_tool_from_tool_configs() {
+        let tools = ToolConfigs(vec![
+            tool_config(ValgrindTool::Callgrind, true, true),
+            tool_config(ValgrindTool::DHAT, false, false),
+            tool_config(ValgrindTool::Memcheck, true, false),
+        ]);
+        let output_path = ToolOutputPath::new(
+            ToolOutputPathKind::Out,
+            ValgrindTool::Callgrind,
+            &BaselineKind::Old,
+            Path::new("/target/iai"),
+            &ModulePath::new("bench_file::group"),
+            "bench.some_id",
+        );
+
+        let actual = ListTool::from_tool_configs(&tools, &output_path);
+        let dir = Path::new("/target/iai/bench_file/group/bench.some_id");
+        assert_eq!(
+            actual
+                .iter()
+                .map(|tool| (
+                    tool.tool,
+                    tool.is_default,
+                    tool.log_path.clone(),
+                    tool.output_path.clone()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    ValgrindTool::Callgrind,
+                    true,
+                    dir.join("callgrind.bench.some_id.log"),
+                    Some(dir.join("callgrind.bench.some_id.out"))
+                ),
+                (
+                    ValgrindTool::Memcheck,
+                    false,
+                    dir.join("memcheck.bench.some_id.log"),
+                    None
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,327 @@
+//! The module containing the charts of the `--massif-chart` argument
+//!
+//! Similar to `ms_print`, the snapshots of a massif output file are rendered as chart of the memory
+//! over time (as svg file) and the heap tree of the peak snapshot as text file next to the massif
+//! output files. So, the massif output doesn't have to be run through `ms_print` manually.
+
+use std::fmt::Write as _;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+
+use super::model::{HeapTree, MassifData, Snapshot};
+use crate::runner::report::escape_html;
+use crate::runner::summary::ArtifactKind;
+use crate::runner::tool::path::ToolOutputPath;
+
+/// The height of the chart
+const CHART_HEIGHT: f64 = 300.0;
+/// The padding around the plot area of the chart
+const CHART_PADDING: f64 = 60.0;
+/// The width of the chart
+const CHART_WIDTH: f64 = 720.0;
+/// The stylesheet of the chart
+const STYLE: &str = "\
+.memory { fill: none; stroke: #999; stroke-width: 1; }
+.snapshot { stroke: #1f77b4; stroke-width: 3; }
+.detailed { stroke: #ff7f0e; stroke-width: 3; }
+.peak { stroke: #d62728; stroke-width: 5; }
+.axis { stroke: #999; stroke-width: 1; }
+text { font-family: sans-serif; font-size: 11px; fill: #555; }
+.title { font-size: 14px; fill: #222; }
+";
+
+/// Create the memory chart and the peak tree of all massif output files of the `output_path`
+///
+/// The chart of an output file `massif.<name>.out` is written to `massif.<name>.chart.svg` and the
+/// peak tree to `massif.<name>.peak.txt` (see [`ToolOutputPath::to_artifact_path`] for the names
+/// of baseline output files). Output files without any snapshots are skipped.
+///
+/// # Errors
+///
+/// Returns an error if an output file could not be parsed or a file could not be written
+pub fn create(output_path: &ToolOutputPath, title: &str) -> Result<Vec<(ArtifactKind, PathBuf)>> {
+    let mut paths = vec![];
+    for path in output_path.real_paths()? {
+        let data = MassifData::from_path(&path)?;
+        if data.snapshots.is_empty() {
+            continue;
+        }
+
+        let chart_path = output_path.to_artifact_path(&path, "chart", "svg");
+        std::fs::write(&chart_path, render_chart(&data, title))
+            .with_context(|| format!("Failed to write massif chart '{}'", chart_path.display()))?;
+        paths.push((ArtifactKind::Chart, chart_path));
+
+        if let Some(peak_tree) = render_peak_tree(&data, title) {
+            let tree_path = output_path.to_artifact_path(&path, "peak", "txt");
+            std::fs::write(&tree_path, peak_tree).with_context(|| {
+                format!("Failed to write massif peak tree '{}'", tree_path.display())
+            })?;
+            paths.push((ArtifactKind::PeakTree, tree_path));
+        }
+    }
+
+    Ok(paths)
+}
+
+/// Render the memory over time of the snapshots in the [`MassifData`] as standalone svg document
+///
+/// Each snapshot is a bar with the total memory of the snapshot. Detailed snapshots and the peak
+/// snapshot are highlighted like the `@` and `#` bars of `ms_print`.
+#[allow(clippy::cast_precision_loss)]
+pub fn render_chart(data: &MassifData, title: &str) -> String {
+    let padding = CHART_PADDING;
+    let bottom = CHART_HEIGHT - padding;
+    let right = CHART_WIDTH - padding;
+    let plot_width = 2.0f64.mul_add(-padding, CHART_WIDTH);
+    let plot_height = 2.0f64.mul_add(-padding, CHART_HEIGHT);
+
+    let peak = data.peak();
+    let time_end = data.snapshots.last().map_or(0, |snapshot| snapshot.time);
+    let max_total = data
+        .snapshots
+        .iter()
+        .map(Snapshot::total)
+        .max()
+        .unwrap_or(0);
+
+    // Avoid a division by zero if there is no time or no memory usage at all
+    let x = |time: u64| (time as f64 / time_end.max(1) as f64).mul_add(plot_width, padding);
+    let y = |bytes: u64| (bytes as f64 / max_total.max(1) as f64).mul_add(-plot_height, bottom);
+    let time_unit = time_unit_name(&data.time_unit);
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{CHART_WIDTH}\" \
+         height=\"{CHART_HEIGHT}\" viewBox=\"0 0 {CHART_WIDTH} {CHART_HEIGHT}\">\n<style>\n\
+         {STYLE}</style>\n"
+    );
+    writeln!(
+        svg,
+        "<text class=\"title\" x=\"{padding}\" y=\"20\">{}</text>",
+        escape_html(title)
+    )
+    .unwrap();
+    if let Some(peak) = peak {
+        writeln!(
+            svg,
+            "<text x=\"{padding}\" y=\"38\">Peak: {} bytes at {} {time_unit} (snapshot {})</text>",
+            peak.total(),
+            peak.time,
+            peak.index
+        )
+        .unwrap();
+    }
+    writeln!(
+        svg,
+        "<line class=\"axis\" x1=\"{padding}\" y1=\"{padding}\" x2=\"{padding}\" \
+         y2=\"{bottom}\"/>\n<line class=\"axis\" x1=\"{padding}\" y1=\"{bottom}\" x2=\"{right}\" \
+         y2=\"{bottom}\"/>"
+    )
+    .unwrap();
+
+    let line = data
+        .snapshots
+        .iter()
+        .map(|snapshot| format!("{:.1},{:.1}", x(snapshot.time), y(snapshot.total())))
+        .collect::<Vec<_>>()
+        .join(" ");
+    writeln!(svg, "<polyline class=\"memory\" points=\"{line}\"/>").unwrap();
+
+    for snapshot in &data.snapshots {
+        let class = if peak.is_some_and(|peak| peak.index == snapshot.index) {
+            "peak"
+        } else if snapshot.heap_tree.is_some() {
+            "detailed"
+        } else {
+            "snapshot"
+        };
+        writeln!(
+            svg,
+            "<line class=\"{class}\" x1=\"{0:.1}\" y1=\"{bottom}\" x2=\"{0:.1}\" \
+             y2=\"{1:.1}\"><title>Snapshot {2} at {3} {time_unit}: {4} bytes (heap: {5}, extra \
+             heap: {6}, stacks: {7})</title></line>",
+            x(snapshot.time),
+            y(snapshot.total()),
+            snapshot.index,
+            snapshot.time,
+            snapshot.total(),
+            snapshot.mem_heap,
+            snapshot.mem_heap_extra,
+            snapshot.mem_stacks
+        )
+        .unwrap();
+    }
+
+    writeln!(
+        svg,
+        "<text x=\"4\" y=\"{:.1}\">{max_total}</text>\n<text x=\"4\" y=\"{bottom}\">0</text>\n<text \
+         x=\"{padding}\" y=\"{:.1}\">0</text>\n<text x=\"{right}\" y=\"{:.1}\" \
+         text-anchor=\"end\">{time_end} {time_unit}</text>",
+        padding + 4.0,
+        bottom + 16.0,
+        bottom + 16.0,
+    )
+    .unwrap();
+    svg.push_str("</svg>\n");
+
+    svg
+}
+
+/// Render the heap tree of the peak snapshot of the [`MassifData`] like `ms_print`
+///
+/// Returns `None` if there is no detailed snapshot.
+pub fn render_peak_tree(data: &MassifData, title: &str) -> Option<String> {
+    let peak = data.peak()?;
+    let tree = peak.heap_tree.as_ref()?;
+
+    let mut output = format!(
+        "{title}\n\nPeak snapshot {} at {} {}: {} bytes (heap: {}, extra heap: {}, stacks: \
+         {})\n\n",
+        peak.index,
+        peak.time,
+        time_unit_name(&data.time_unit),
+        peak.total(),
+        peak.mem_heap,
+        peak.mem_heap_extra,
+        peak.mem_stacks
+    );
+    write_tree(&mut output, tree, peak.total(), "", "");
+
+    Some(output)
+}
+
+/// Return the long name of the massif `time_unit`
+fn time_unit_name(time_unit: &str) -> &str {
+    match time_unit {
+        "i" => "instructions",
+        "B" => "bytes",
+        time_unit => time_unit,
+    }
+}
+
+/// Write the `tree` with its percentage of the `total` bytes and all its children to `output`
+///
+/// The `prefix` is written before the node itself and the `child_prefix` before the nodes of the
+/// children.
+#[allow(clippy::cast_precision_loss)]
+fn write_tree(output: &mut String, tree: &HeapTree, total: u64, prefix: &str, child_prefix: &str) {
+    let percentage = if total == 0 {
+        0.0
+    } else {
+        tree.bytes as f64 / total as f64 * 100.0
+    };
+    writeln!(
+        output,
+        "{prefix}{percentage:.2}% ({}B) {}",
+        tree.bytes, tree.description
+    )
+    .unwrap();
+
+    let num_children = tree.children.len();
+    for (index, child) in tree.children.iter().enumerate() {
+        let continuation = if index + 1 < num_children { "| " } else { "  " };
+        write_tree(
+            output,
+            child,
+            total,
+            &format!("{child_prefix}->"),
+            &format!("{child_prefix}{continuation}"),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn snapshot(index: usize, time: u64, mem_heap: u64, heap_tree: Option<HeapTree>) -> Snapshot {
+        Snapshot {
+            heap_tree,
+            index,
+            is_peak: false,
+            mem_heap,
+            mem_heap_extra: 0,
+            mem_stacks: 0,
+            time,
+        }
+    }
+
+    fn tree(bytes: u64, description: &str, children: Vec<HeapTree>) -> HeapTree {
+        HeapTree {
+            bytes,
+            children,
+            description: description.to_owned(),
+        }
+    }
+
+    fn massif_data() -> MassifData {
+        let heap_tree = tree(
+            800,
+            "(heap allocation functions)",
+            vec![
+                tree(
+                    600,
+                    "0x1: main (some.c:5)",
+                    vec![
+                        tree(400, "0x2: foo (some.c:10)", vec![]),
+                        tree(200, "0x3: bar (some.c:15)", vec![]),
+                    ],
+                ),
+                tree(200, "0x4: baz (some.c:20)", vec![]),
+            ],
+        );
+
+        MassifData {
+            command: "./some".to_owned(),
+            snapshots: vec![
+                snapshot(0, 0, 0, None),
+                snapshot(1, 100, 800, Some(heap_tree)),
+                snapshot(2, 200, 0, None),
+            ],
+            time_unit: "i".to_owned(),
+        }
+    }
+
+    #[test]
+    fn test_render_chart() {
+        let svg = render_chart(&massif_data(), "some<bench>");
+
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.ends_with("</svg>\n"));
+        assert!(svg.contains("some&lt;bench&gt;"));
+        assert!(svg.contains("Peak: 800 bytes at 100 instructions (snapshot 1)"));
+        assert!(svg.contains("points=\"60.0,240.0 360.0,60.0 660.0,240.0\""));
+        assert!(svg.contains("<line class=\"peak\" x1=\"360.0\""));
+    }
+
+    #[test]
+    fn test_render_peak_tree() {
+        let expected = "bench
+
+Peak snapshot 1 at 100 instructions: 800 bytes (heap: 800, extra heap: 0, stacks: 0)
+
+100.00% (800B) (heap allocation functions)
+->75.00% (600B) 0x1: main (some.c:5)
+| ->50.00% (400B) 0x2: foo (some.c:10)
+| ->25.00% (200B) 0x3: bar (some.c:15)
+->25.00% (200B) 0x4: baz (some.c:20)
+";
+        assert_eq!(
+            render_peak_tree(&massif_data(), "bench").as_deref(),
+            Some(expected)
+        );
+    }
+
+    #[test]
+    fn test_render_peak_tree_when_no_detailed_snapshot_then_none() {
+        let data = MassifData {
+            command: "./some".to_owned(),
+            snapshots: vec![snapshot(0, 0, 0, None)],
+            time_unit: "i".to_owned(),
+        };
+        assert_eq!(render_peak_tree(&data, "bench"), None);
+    }
+}
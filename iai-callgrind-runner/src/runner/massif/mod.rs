@@ -0,0 +1,4 @@
+//! The main module of massif
+
+pub mod chart;
+pub mod model;
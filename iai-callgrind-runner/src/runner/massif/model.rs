@@ -0,0 +1,320 @@
+//! This module contains the structs to model the massif output file content
+//!
+//! The format of a massif output file (`massif.out`) looks like this:
+//!
+//! ```text
+//! desc: (none)
+//! cmd: ./some
+//! time_unit: i
+//! #-----------
+//! snapshot=0
+//! #-----------
+//! time=0
+//! mem_heap_B=0
+//! mem_heap_extra_B=0
+//! mem_stacks_B=0
+//! heap_tree=empty
+//! #-----------
+//! snapshot=1
+//! #-----------
+//! time=1000
+//! mem_heap_B=600
+//! mem_heap_extra_B=24
+//! mem_stacks_B=0
+//! heap_tree=peak
+//! n1: 600 (heap allocation functions) malloc/new/new[], --alloc-fns, etc.
+//!  n0: 600 0x10916D: main (some.c:5)
+//! ```
+
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+
+/// A node of the heap tree of a detailed [`Snapshot`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeapTree {
+    /// The bytes allocated by this node and all its children
+    pub bytes: u64,
+    /// The child nodes
+    pub children: Vec<Self>,
+    /// The description of the node, usually the address and the function
+    pub description: String,
+}
+
+/// The top-level data extracted from a massif output file
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MassifData {
+    /// The executed command
+    pub command: String,
+    /// The [`Snapshot`]s in the order of their time
+    pub snapshots: Vec<Snapshot>,
+    /// The unit of the time (`i`, `ms` or `B`)
+    pub time_unit: String,
+}
+
+/// A single massif snapshot
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Snapshot {
+    /// The heap tree of detailed and peak snapshots
+    pub heap_tree: Option<HeapTree>,
+    /// The number of the snapshot
+    pub index: usize,
+    /// True if this snapshot is the peak snapshot
+    pub is_peak: bool,
+    /// The useful heap bytes (`mem_heap_B`)
+    pub mem_heap: u64,
+    /// The extra heap bytes for the administration and alignment (`mem_heap_extra_B`)
+    pub mem_heap_extra: u64,
+    /// The size of the stacks (`mem_stacks_B`)
+    pub mem_stacks: u64,
+    /// The time of the snapshot in the `time_unit` of the [`MassifData`]
+    pub time: u64,
+}
+
+impl HeapTree {
+    /// Parse a node and all its children from the `lines`
+    ///
+    /// A node looks like `n2: 1000 0x10916D: main (some.c:5)` where `2` is the number of children
+    /// which follow in the next lines and `1000` the number of bytes.
+    fn parse<I>(lines: &mut I) -> Result<Self>
+    where
+        I: Iterator<Item = String>,
+    {
+        let line = lines
+            .next()
+            .ok_or_else(|| anyhow!("Invalid massif output: Missing heap tree node"))?;
+        let node = line.trim_start();
+
+        let (num_children, rest) = node
+            .strip_prefix('n')
+            .and_then(|rest| rest.split_once(": "))
+            .ok_or_else(|| anyhow!("Invalid heap tree node in massif output: '{node}'"))?;
+        let (bytes, description) = rest.split_once(' ').unwrap_or((rest, ""));
+
+        let num_children: usize = parse_number("heap tree children", num_children)?;
+        let children = std::iter::repeat_with(|| Self::parse(lines))
+            .take(num_children)
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            bytes: parse_number("heap tree bytes", bytes)?,
+            children,
+            description: description.to_owned(),
+        })
+    }
+}
+
+impl MassifData {
+    /// Parse the `lines` of a massif output file
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a line could not be parsed
+    pub fn parse<I>(mut lines: I) -> Result<Self>
+    where
+        I: Iterator<Item = String>,
+    {
+        let mut data = Self::default();
+        while let Some(line) = lines.next() {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if let Some(command) = line.strip_prefix("cmd: ") {
+                command.clone_into(&mut data.command);
+                continue;
+            }
+            if let Some(time_unit) = line.strip_prefix("time_unit: ") {
+                time_unit.clone_into(&mut data.time_unit);
+                continue;
+            }
+            if line.starts_with("desc: ") {
+                continue;
+            }
+
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| anyhow!("Invalid line in massif output: '{line}'"))?;
+            if key == "snapshot" {
+                data.snapshots.push(Snapshot {
+                    index: parse_number(key, value)?,
+                    ..Default::default()
+                });
+                continue;
+            }
+
+            let snapshot = data.snapshots.last_mut().ok_or_else(|| {
+                anyhow!("Invalid massif output: '{key}' before the first snapshot")
+            })?;
+            match key {
+                "time" => snapshot.time = parse_number(key, value)?,
+                "mem_heap_B" => snapshot.mem_heap = parse_number(key, value)?,
+                "mem_heap_extra_B" => snapshot.mem_heap_extra = parse_number(key, value)?,
+                "mem_stacks_B" => snapshot.mem_stacks = parse_number(key, value)?,
+                "heap_tree" => match value {
+                    "empty" => {}
+                    "detailed" | "peak" => {
+                        snapshot.is_peak = value == "peak";
+                        snapshot.heap_tree = Some(HeapTree::parse(&mut lines)?);
+                    }
+                    _ => {
+                        return Err(anyhow!(
+                            "Invalid heap tree kind in massif output: '{value}'"
+                        ))
+                    }
+                },
+                _ => return Err(anyhow!("Unknown key in massif output: '{key}'")),
+            }
+        }
+
+        Ok(data)
+    }
+
+    /// Parse the massif output file at `path`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file could not be opened or parsed
+    pub fn from_path(path: &Path) -> Result<Self> {
+        let file = File::open(path)
+            .with_context(|| format!("Error opening massif output file '{}'", path.display()))?;
+        let lines = BufReader::new(file)
+            .lines()
+            .collect::<std::io::Result<Vec<_>>>()
+            .with_context(|| format!("Error reading massif output file '{}'", path.display()))?;
+
+        Self::parse(lines.into_iter())
+            .with_context(|| format!("Error parsing massif output file '{}'", path.display()))
+    }
+
+    /// Return the peak [`Snapshot`]
+    ///
+    /// Massif marks the peak snapshot only if the peak was detailed. Otherwise, the detailed
+    /// snapshot with the most memory is returned.
+    pub fn peak(&self) -> Option<&Snapshot> {
+        self.snapshots
+            .iter()
+            .find(|snapshot| snapshot.is_peak)
+            .or_else(|| {
+                self.snapshots
+                    .iter()
+                    .filter(|snapshot| snapshot.heap_tree.is_some())
+                    .max_by_key(|snapshot| snapshot.total())
+            })
+    }
+}
+
+impl Snapshot {
+    /// Return the total memory of this snapshot (heap, extra heap and stacks)
+    pub fn total(&self) -> u64 {
+        self.mem_heap + self.mem_heap_extra + self.mem_stacks
+    }
+}
+
+fn parse_number<T>(key: &str, value: &str) -> Result<T>
+where
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    value
+        .parse()
+        .map_err(|error| anyhow!("Invalid value of '{key}' in massif output: '{value}': {error}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    const MASSIF_OUT: &str = "desc: (none)
+cmd: ./some arg
+time_unit: i
+#-----------
+snapshot=0
+#-----------
+time=0
+mem_heap_B=0
+mem_heap_extra_B=0
+mem_stacks_B=0
+heap_tree=empty
+#-----------
+snapshot=1
+#-----------
+time=1000
+mem_heap_B=600
+mem_heap_extra_B=24
+mem_stacks_B=8
+heap_tree=peak
+n2: 600 (heap allocation functions) malloc/new/new[], --alloc-fns, etc.
+ n1: 400 0x10916D: main (some.c:5)
+  n0: 400 0x10917D: foo (some.c:10)
+ n0: 200 in 2 places, all below massif's threshold (1.00%)
+#-----------
+snapshot=2
+#-----------
+time=2000
+mem_heap_B=0
+mem_heap_extra_B=0
+mem_stacks_B=0
+heap_tree=empty
+";
+
+    fn lines(input: &str) -> impl Iterator<Item = String> + '_ {
+        input.lines().map(ToOwned::to_owned)
+    }
+
+    #[test]
+    fn test_massif_data_parse() {
+        let data = MassifData::parse(lines(MASSIF_OUT)).unwrap();
+
+        assert_eq!(data.command, "./some arg");
+        assert_eq!(data.time_unit, "i");
+        assert_eq!(data.snapshots.len(), 3);
+
+        let peak = data.peak().unwrap();
+        assert_eq!(peak.index, 1);
+        assert_eq!(peak.time, 1000);
+        assert_eq!(peak.total(), 632);
+        assert_eq!(
+            peak.heap_tree,
+            Some(HeapTree {
+                bytes: 600,
+                children: vec![
+                    HeapTree {
+                        bytes: 400,
+                        children: vec![HeapTree {
+                            bytes: 400,
+                            children: vec![],
+                            description: "0x10917D: foo (some.c:10)".to_owned()
+                        }],
+                        description: "0x10916D: main (some.c:5)".to_owned()
+                    },
+                    HeapTree {
+                        bytes: 200,
+                        children: vec![],
+                        description: "in 2 places, all below massif's threshold (1.00%)".to_owned()
+                    }
+                ],
+                description: "(heap allocation functions) malloc/new/new[], --alloc-fns, etc."
+                    .to_owned()
+            })
+        );
+    }
+
+    #[test]
+    fn test_massif_data_peak_when_no_peak_then_largest_detailed() {
+        let input = MASSIF_OUT.replace("heap_tree=peak", "heap_tree=detailed");
+        let data = MassifData::parse(lines(&input)).unwrap();
+
+        assert_eq!(data.peak().unwrap().index, 1);
+    }
+
+    #[test]
+    fn test_massif_data_parse_when_missing_children_then_error() {
+        let input = MASSIF_OUT.replace(" n0: 200 in 2 places", "#");
+        MassifData::parse(lines(&input)).unwrap_err();
+    }
+}
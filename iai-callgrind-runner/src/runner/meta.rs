@@ -3,6 +3,7 @@
 use std::ffi::OsString;
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::OnceLock;
 
 use anyhow::Result;
 use cargo_metadata::TargetKind;
@@ -10,7 +11,13 @@ use clap::Parser;
 use log::debug;
 
 use super::args::CommandLineArgs;
+use super::capabilities::Capabilities;
+use super::ci::{CiEnv, AUTO_BASELINE};
+use super::common::ModulePath;
 use super::envs;
+use super::profiles::{BenchmarkArgs, ConfigFile};
+use super::summary::{EnvironmentInfo, GitInfo};
+use crate::error::Error;
 use crate::util::resolve_binary_path;
 
 /// The basic commands (like valgrind) to be executed with default arguments
@@ -34,6 +41,18 @@ pub struct Metadata {
     pub args: CommandLineArgs,
     /// The name of the benchmark to run (might be different to the name of the file)
     pub bench_name: String,
+    /// The command-line arguments of single benchmarks from the [`CONFIG_FILE`]
+    ///
+    /// [`CONFIG_FILE`]: super::profiles::CONFIG_FILE
+    pub benchmark_args: BenchmarkArgs,
+    /// The [`Capabilities`] of the installed valgrind
+    pub capabilities: Capabilities,
+    /// The target directory of cargo (per default `$WORKSPACE_ROOT/target`)
+    pub cargo_target_dir: PathBuf,
+    /// The environment of the benchmark run like the valgrind and rustc versions
+    pub environment: EnvironmentInfo,
+    /// The git metadata of the project, detected when it is first needed (see [`Metadata::git`])
+    pub git: OnceLock<Option<GitInfo>>,
     /// The path to the project top-level directory
     pub project_root: PathBuf,
     /// The absolute path of the `HOME` (per default `$WORKSPACE_ROOT/target/iai`). Plus, if
@@ -101,15 +120,34 @@ impl Metadata {
         let project_root = meta.workspace_root.into_std_path_buf();
         debug!("Detected project root: '{}'", project_root.display());
 
-        let target_dir = {
-            let mut home = args.home.as_ref().map_or_else(
-                || {
-                    std::env::var_os(envs::CARGO_TARGET_DIR)
-                        .map_or_else(|| meta.target_directory.into_std_path_buf(), PathBuf::from)
-                        .join("iai")
-                },
-                Clone::clone,
+        let config_file = ConfigFile::load(&project_root)?;
+        let benchmark_args = config_file.benchmark_args(&args, raw_command_line_args)?;
+        let mut args = config_file.resolve(args, raw_command_line_args)?;
+        debug!("Resolved measurement profile: {:?}", args.profile);
+
+        let git = OnceLock::new();
+        if args
+            .save_baseline
+            .as_ref()
+            .is_some_and(|name| name.0 == AUTO_BASELINE)
+        {
+            let ci_env = CiEnv::from_env(detect_git(&git, &project_root));
+            debug!("Detected CI environment: {ci_env:?}");
+            let baseline_name = ci_env.baseline_name(&args.baseline_template)?;
+            debug!(
+                "Derived baseline name from '{}': {baseline_name}",
+                args.baseline_template
             );
+            args.save_baseline = Some(baseline_name);
+        }
+
+        let cargo_target_dir = std::env::var_os(envs::CARGO_TARGET_DIR)
+            .map_or_else(|| meta.target_directory.into_std_path_buf(), PathBuf::from);
+        let target_dir = {
+            let mut home = args
+                .home
+                .as_ref()
+                .map_or_else(|| cargo_target_dir.join("iai"), Clone::clone);
 
             if args.separate_targets {
                 home = home.join(env!("IC_BUILD_TRIPLE").to_ascii_lowercase());
@@ -122,50 +160,21 @@ impl Metadata {
         debug!("Detected target directory: '{}'", target_dir.display());
 
         // Invoke Valgrind, disabling ASLR if possible because ASLR could noise up the results a bit
-        let valgrind_path = resolve_binary_path("valgrind")?;
+        let valgrind_path = resolve_binary_path("valgrind").map_err(|error| {
+            if args.allow_unsupported {
+                Error::UnsupportedPlatform(error.to_string()).into()
+            } else {
+                error
+            }
+        })?;
+        let capabilities = Capabilities::new(&valgrind_path);
+        let environment = EnvironmentInfo::detect(capabilities.version());
+        debug!("Detected environment: {environment:?}");
         let valgrind_wrapper = if args.allow_aslr.unwrap_or_default() {
             debug!("Running with ASLR enabled");
             None
-        } else if cfg!(target_os = "linux") {
-            debug!("Trying to run with ASLR disabled: Using 'setarch'");
-
-            if let Ok(set_arch) = resolve_binary_path("setarch") {
-                Some(Cmd {
-                    bin: set_arch,
-                    args: vec![
-                        OsString::from(&arch),
-                        OsString::from("-R"),
-                        OsString::from(&valgrind_path),
-                    ],
-                })
-            } else {
-                debug!("Failed to switch ASLR off: 'setarch' not found. Running with ASLR enabled");
-                None
-            }
-        } else if cfg!(target_os = "freebsd") {
-            debug!("Trying to run with ASLR disabled: Using 'proccontrol'");
-
-            if let Ok(proc_control) = resolve_binary_path("proccontrol") {
-                Some(Cmd {
-                    bin: proc_control,
-                    args: vec![
-                        OsString::from("-m"),
-                        OsString::from("aslr"),
-                        OsString::from("-s"),
-                        OsString::from("disable"),
-                        OsString::from(&valgrind_path),
-                    ],
-                })
-            } else {
-                debug!(
-                    " Failed to switch ASLR off: 'proccontrol' not found. Running with ASLR \
-                     enabled"
-                );
-                None
-            }
         } else {
-            debug!("Failed to switch ASLR off. No utility available. Running with ASLR enabled");
-            None
+            aslr_disabling_wrapper(&arch, &valgrind_path)
         };
 
         Ok(Self {
@@ -179,6 +188,105 @@ impl Metadata {
             project_root,
             args,
             bench_name,
+            benchmark_args,
+            capabilities,
+            cargo_target_dir,
+            environment,
+            git,
         })
     }
+
+    /// Return the git metadata of the project if it is a git repository
+    ///
+    /// Running `git` is only necessary if the git metadata is stored somewhere, so it's detected on
+    /// the first call of this method.
+    pub fn git(&self) -> Option<&GitInfo> {
+        detect_git(&self.git, &self.project_root)
+    }
+
+    /// Return the output directory of the benchmark file of the `module_path`
+    pub fn bench_output_dir(&self, module_path: &ModulePath) -> PathBuf {
+        module_path.first().map_or_else(
+            || self.target_dir.clone(),
+            |first| self.target_dir.join(first.as_str()),
+        )
+    }
+
+    /// Return the environment variables with the paths of the workspace for the benchmarks
+    ///
+    /// These are the workspace root, the cargo target directory and the output directory of the
+    /// benchmark file of the `module_path`. The `iai_callgrind::paths` module of the benchmark
+    /// harness reads these variables.
+    pub fn path_envs(&self, module_path: &ModulePath) -> Vec<(OsString, OsString)> {
+        vec![
+            (
+                OsString::from(envs::BENCH_OUTPUT_DIR),
+                self.bench_output_dir(module_path).into_os_string(),
+            ),
+            (
+                OsString::from(envs::TARGET_DIR),
+                self.cargo_target_dir.clone().into_os_string(),
+            ),
+            (
+                OsString::from(envs::WORKSPACE_ROOT),
+                self.project_root.clone().into_os_string(),
+            ),
+        ]
+    }
+}
+
+/// Return the wrapper of valgrind to run valgrind with ASLR disabled if possible
+///
+/// ASLR could noise up the results a bit.
+fn aslr_disabling_wrapper(arch: &str, valgrind_path: &Path) -> Option<Cmd> {
+    if cfg!(target_os = "linux") {
+        debug!("Trying to run with ASLR disabled: Using 'setarch'");
+
+        if let Ok(set_arch) = resolve_binary_path("setarch") {
+            Some(Cmd {
+                bin: set_arch,
+                args: vec![
+                    OsString::from(arch),
+                    OsString::from("-R"),
+                    OsString::from(valgrind_path),
+                ],
+            })
+        } else {
+            debug!("Failed to switch ASLR off: 'setarch' not found. Running with ASLR enabled");
+            None
+        }
+    } else if cfg!(target_os = "freebsd") {
+        debug!("Trying to run with ASLR disabled: Using 'proccontrol'");
+
+        if let Ok(proc_control) = resolve_binary_path("proccontrol") {
+            Some(Cmd {
+                bin: proc_control,
+                args: vec![
+                    OsString::from("-m"),
+                    OsString::from("aslr"),
+                    OsString::from("-s"),
+                    OsString::from("disable"),
+                    OsString::from(valgrind_path),
+                ],
+            })
+        } else {
+            debug!(
+                " Failed to switch ASLR off: 'proccontrol' not found. Running with ASLR enabled"
+            );
+            None
+        }
+    } else {
+        debug!("Failed to switch ASLR off. No utility available. Running with ASLR enabled");
+        None
+    }
+}
+
+/// Detect the [`GitInfo`] of the repository in the `project_root` if not done yet
+fn detect_git<'a>(git: &'a OnceLock<Option<GitInfo>>, project_root: &Path) -> Option<&'a GitInfo> {
+    git.get_or_init(|| {
+        let git = GitInfo::from_repository(project_root);
+        debug!("Detected git metadata: {git:?}");
+        git
+    })
+    .as_ref()
 }
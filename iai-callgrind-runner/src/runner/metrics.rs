@@ -3,7 +3,6 @@
 #![allow(clippy::cast_precision_loss)]
 
 use std::borrow::Cow;
-use std::cmp::Ordering;
 use std::fmt::Display;
 use std::hash::Hash;
 use std::ops::{Add, AddAssign, Div, Mul, Sub};
@@ -12,82 +11,12 @@ use std::str::FromStr;
 use anyhow::{anyhow, Context, Result};
 use either_or_both::EitherOrBoth;
 use indexmap::IndexMap;
-#[cfg(feature = "schema")]
-use schemars::JsonSchema;
-use serde::{Deserialize, Serialize};
 
 use super::summary::Diffs;
-use crate::api::{self, CachegrindMetric, DhatMetric, ErrorMetric, EventKind};
+use crate::api;
+pub use crate::summary::{Metric, MetricKind, Metrics, MetricsDiff, MetricsSummary};
 use crate::util::{to_string_unsigned_short, Union};
 
-/// The metric measured by valgrind or derived from one or more other metrics
-///
-/// The valgrind metrics measured by any of its tools are `u64`. However, to be able to represent
-/// derived metrics like cache miss/hit rates it is inevitable to have a type which can store a
-/// `u64` or a `f64`. When doing math with metrics, the original type should be preserved as far as
-/// possible by using `u64` operations. A float metric should be a last resort.
-///
-/// Float operations with a `Metric` that stores a `u64` introduce a precision loss and are to be
-/// avoided. Especially comparison between a `u64` metric and `f64` metric are not exact because the
-/// `u64` has to be converted to a `f64`. Also, if adding/multiplying two `u64` metrics would result
-/// in an overflow the metric saturates at `u64::MAX`. This choice was made to preserve precision
-/// and the original type (instead of for example adding the two `u64` by converting both of them to
-/// `f64`).
-#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
-#[cfg_attr(feature = "schema", derive(JsonSchema))]
-pub enum Metric {
-    /// An integer `Metric`
-    Int(u64),
-    /// A float `Metric`
-    Float(f64),
-}
-
-/// The different metrics distinguished by tool and if it is an error checking tool as `ErrorMetric`
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-#[cfg_attr(feature = "schema", derive(JsonSchema))]
-pub enum MetricKind {
-    /// The `None` kind if there are no metrics for a tool
-    None,
-    /// The Callgrind metric kind
-    Callgrind(EventKind),
-    /// The Cachegrind metric kind
-    Cachegrind(CachegrindMetric),
-    /// The DHAT metric kind
-    Dhat(DhatMetric),
-    /// The Memcheck metric kind
-    Memcheck(ErrorMetric),
-    /// The Helgrind metric kind
-    Helgrind(ErrorMetric),
-    /// The DRD metric kind
-    DRD(ErrorMetric),
-}
-
-/// The `Metrics` backed by an [`indexmap::IndexMap`]
-///
-/// The insertion order is preserved.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-#[cfg_attr(feature = "schema", derive(JsonSchema))]
-pub struct Metrics<K: Hash + Eq>(pub IndexMap<K, Metric>);
-
-/// The `MetricsDiff` describes the difference between a `new` and `old` metric as percentage and
-/// factor.
-///
-/// Only if both metrics are present there is also a `Diffs` present. Otherwise, it just stores the
-/// `new` or `old` metric.
-#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
-#[cfg_attr(feature = "schema", derive(JsonSchema))]
-pub struct MetricsDiff {
-    /// If both metrics are present there is also a `Diffs` present
-    pub diffs: Option<Diffs>,
-    /// Either the `new`, `old` or both metrics
-    pub metrics: EitherOrBoth<Metric>,
-}
-
-/// The `MetricsSummary` contains all differences between two tool run segments
-#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
-#[cfg_attr(feature = "schema", derive(JsonSchema))]
-pub struct MetricsSummary<K: Hash + Eq = EventKind>(IndexMap<K, MetricsDiff>);
-
 /// Trait for tools which summarize and calculate derived metrics
 pub trait Summarize: Hash + Eq + Clone {
     /// Calculate the derived metrics if any
@@ -197,8 +126,6 @@ impl Div for Metric {
     }
 }
 
-impl Eq for Metric {}
-
 impl From<u64> for Metric {
     fn from(value: u64) -> Self {
         Self::Int(value)
@@ -245,34 +172,6 @@ impl Mul<u64> for Metric {
     }
 }
 
-impl Ord for Metric {
-    fn cmp(&self, other: &Self) -> Ordering {
-        match (self, other) {
-            (Self::Int(a), Self::Int(b)) => a.cmp(b),
-            (Self::Int(a), Self::Float(b)) => (*a as f64).total_cmp(b),
-            (Self::Float(a), Self::Int(b)) => a.total_cmp(&(*b as f64)),
-            (Self::Float(a), Self::Float(b)) => a.total_cmp(b),
-        }
-    }
-}
-
-impl PartialEq for Metric {
-    fn eq(&self, other: &Self) -> bool {
-        match (self, other) {
-            (Self::Int(a), Self::Int(b)) => a == b,
-            (Self::Int(a), Self::Float(b)) => (*a as f64).total_cmp(b) == Ordering::Equal,
-            (Self::Float(a), Self::Int(b)) => a.total_cmp(&(*b as f64)) == Ordering::Equal,
-            (Self::Float(a), Self::Float(b)) => a.total_cmp(b) == Ordering::Equal,
-        }
-    }
-}
-
-impl PartialOrd for Metric {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
-}
-
 impl Sub for Metric {
     type Output = Self;
 
@@ -354,6 +253,18 @@ where
         }
     }
 
+    /// Subtract the metrics of another `Metrics` with the same kind from this `Metrics`
+    ///
+    /// Other than [`Metrics::add`], the metrics are matched by their kind. Metric kinds which are
+    /// not present in this `Metrics` are ignored and integer metrics don't go below zero.
+    pub fn subtract(&mut self, other: &Self) {
+        for (kind, other) in other {
+            if let Some(this) = self.0.get_mut(kind) {
+                *this = *this - *other;
+            }
+        }
+    }
+
     /// Return the metric of the kind at index (of insertion order) if present
     ///
     /// This operation is O(1)
@@ -482,27 +393,43 @@ impl MetricsDiff {
     /// Sum this metrics diff with another [`MetricsDiff`]
     #[must_use]
     pub fn add(&self, other: &Self) -> Self {
+        self.combine(other, |a, b| a + b)
+    }
+
+    /// Return the maximum of this metrics diff and another [`MetricsDiff`]
+    ///
+    /// The maximum of the new and old metrics are calculated separately.
+    #[must_use]
+    pub fn max(&self, other: &Self) -> Self {
+        self.combine(other, Ord::max)
+    }
+
+    /// Combine the new and old metrics of this and another [`MetricsDiff`] with the function `f`
+    fn combine<F>(&self, other: &Self, f: F) -> Self
+    where
+        F: Fn(Metric, Metric) -> Metric,
+    {
         match (&self.metrics, &other.metrics) {
             (EitherOrBoth::Left(new), EitherOrBoth::Left(other_new)) => {
-                Self::new(EitherOrBoth::Left(*new + *other_new))
+                Self::new(EitherOrBoth::Left(f(*new, *other_new)))
             }
             (EitherOrBoth::Right(old), EitherOrBoth::Left(new))
             | (EitherOrBoth::Left(new), EitherOrBoth::Right(old)) => {
                 Self::new(EitherOrBoth::Both(*new, *old))
             }
             (EitherOrBoth::Right(old), EitherOrBoth::Right(other_old)) => {
-                Self::new(EitherOrBoth::Right(*old + *other_old))
+                Self::new(EitherOrBoth::Right(f(*old, *other_old)))
             }
             (EitherOrBoth::Both(new, old), EitherOrBoth::Left(other_new))
             | (EitherOrBoth::Left(new), EitherOrBoth::Both(other_new, old)) => {
-                Self::new(EitherOrBoth::Both(*new + *other_new, *old))
+                Self::new(EitherOrBoth::Both(f(*new, *other_new), *old))
             }
             (EitherOrBoth::Both(new, old), EitherOrBoth::Right(other_old))
             | (EitherOrBoth::Right(old), EitherOrBoth::Both(new, other_old)) => {
-                Self::new(EitherOrBoth::Both(*new, *old + *other_old))
+                Self::new(EitherOrBoth::Both(*new, f(*old, *other_old)))
             }
             (EitherOrBoth::Both(new, old), EitherOrBoth::Both(other_new, other_old)) => {
-                Self::new(EitherOrBoth::Both(*new + *other_new, *old + *other_old))
+                Self::new(EitherOrBoth::Both(f(*new, *other_new), f(*old, *other_old)))
             }
         }
     }
@@ -562,6 +489,15 @@ where
         self.0.iter()
     }
 
+    /// Return true if all new metrics are identical to the old metrics
+    ///
+    /// Metrics which are only present in the new or old [`Metrics`] count as changed.
+    pub fn is_unchanged(&self) -> bool {
+        self.0
+            .values()
+            .all(|diff| matches!(diff.metrics, EitherOrBoth::Both(new, old) if new == old))
+    }
+
     /// Extract the [`Metrics`] from this summary
     ///
     /// This is the exact reverse operation to [`MetricsSummary::new`]
@@ -586,6 +522,20 @@ where
             }
         }
     }
+
+    /// Set each metric to the maximum of this and another `MetricsSummary`
+    ///
+    /// If a [`MetricsDiff`] is not present in this summary but in the other, it is added to this
+    /// summary.
+    pub fn max(&mut self, other: &Self) {
+        for (other_key, other_value) in &other.0 {
+            if let Some(value) = self.0.get_mut(other_key) {
+                *value = value.max(other_value);
+            } else {
+                self.0.insert(other_key.clone(), other_value.clone());
+            }
+        }
+    }
 }
 
 impl<K> Default for MetricsSummary<K>
@@ -769,6 +719,22 @@ mod tests {
         assert!(metrics.add_iter_str(to_add).is_err());
     }
 
+    #[rstest]
+    #[case::same_kinds(&[(Ir, 10), (Dr, 5)], &[(Ir, 3), (Dr, 1)], &[(Ir, 7), (Dr, 4)])]
+    #[case::different_order(&[(Ir, 10), (Dr, 5)], &[(Dr, 1), (Ir, 3)], &[(Ir, 7), (Dr, 4)])]
+    #[case::missing_kind(&[(Ir, 10)], &[(Ir, 3), (Dr, 1)], &[(Ir, 7)])]
+    #[case::saturating(&[(Ir, 1)], &[(Ir, 3)], &[(Ir, 0)])]
+    fn test_metrics_subtract(
+        #[case] metrics: &[(EventKind, u64)],
+        #[case] other: &[(EventKind, u64)],
+        #[case] expected: &[(EventKind, u64)],
+    ) {
+        let mut metrics = expected_metrics(metrics.iter().copied());
+        metrics.subtract(&expected_metrics(other.iter().copied()));
+
+        assert_eq!(metrics, expected_metrics(expected.iter().copied()));
+    }
+
     #[rstest]
     #[case::all_zero_int(0, 0, 0.0f64)]
     #[case::lhs_zero_int_one(0, 1, 0.0f64)]
@@ -1067,6 +1033,42 @@ mod tests {
         assert_eq!(old_diff.add(&new_diff), expected);
     }
 
+    #[rstest]
+    #[case::new(EitherOrBoth::Left(1), EitherOrBoth::Left(2), EitherOrBoth::Left(2))]
+    #[case::old(EitherOrBoth::Right(3), EitherOrBoth::Right(2), EitherOrBoth::Right(3))]
+    #[case::new_and_old(
+        EitherOrBoth::Left(1),
+        EitherOrBoth::Right(2),
+        EitherOrBoth::Both(1, 2)
+    )]
+    #[case::both_and_new(
+        EitherOrBoth::Both(1, 5),
+        EitherOrBoth::Left(2),
+        EitherOrBoth::Both(2, 5)
+    )]
+    #[case::both_and_old(
+        EitherOrBoth::Both(1, 5),
+        EitherOrBoth::Right(2),
+        EitherOrBoth::Both(1, 5)
+    )]
+    #[case::both(
+        EitherOrBoth::Both(3, 1),
+        EitherOrBoth::Both(1, 4),
+        EitherOrBoth::Both(3, 4)
+    )]
+    fn test_metrics_diff_max(
+        #[case] metric: EitherOrBoth<u64>,
+        #[case] other_metric: EitherOrBoth<u64>,
+        #[case] expected: EitherOrBoth<u64>,
+    ) {
+        let new_diff = MetricsDiff::new(metric.map(Metric::Int));
+        let old_diff = MetricsDiff::new(other_metric.map(Metric::Int));
+        let expected = MetricsDiff::new(expected.map(Metric::Int));
+
+        assert_eq!(new_diff.max(&old_diff), expected);
+        assert_eq!(old_diff.max(&new_diff), expected);
+    }
+
     #[rstest]
     #[case::new_ir(&[0], &[], &[(EitherOrBoth::Left(Metric::Int(0)), None)])]
     #[case::new_is_summarized(&[10, 20, 30, 1, 2, 3, 4, 2, 0], &[],
@@ -1215,4 +1217,25 @@ mod tests {
 
         assert_eq!(actual, expected_metrics_summary);
     }
+
+    #[rstest]
+    #[case::equal(&[10, 20, 30, 1, 2, 3, 4, 2, 0], &[10, 20, 30, 1, 2, 3, 4, 2, 0], true)]
+    #[case::not_equal(&[10, 20, 30, 1, 2, 3, 4, 2, 0], &[10, 20, 30, 1, 2, 3, 4, 1, 0], false)]
+    #[case::only_new(&[10, 20, 30, 1, 2, 3, 4, 2, 0], &[], false)]
+    fn test_metrics_summary_is_unchanged(
+        #[case] new_metrics: &[u64],
+        #[case] old_metrics: &[u64],
+        #[case] expected: bool,
+    ) {
+        let summary = if old_metrics.is_empty() {
+            MetricsSummary::new(EitherOrBoth::Left(metrics_fixture(new_metrics)))
+        } else {
+            MetricsSummary::new(EitherOrBoth::Both(
+                metrics_fixture(new_metrics),
+                metrics_fixture(old_metrics),
+            ))
+        };
+
+        assert_eq!(summary.is_unchanged(), expected);
+    }
 }
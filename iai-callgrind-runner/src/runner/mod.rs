@@ -2,61 +2,134 @@
 
 pub mod args;
 pub mod bin_bench;
+pub mod binary;
 pub mod cachegrind;
 pub mod callgrind;
+pub mod capabilities;
+pub mod ci;
 pub mod common;
 pub mod dhat;
+pub mod diff;
 
 /// Names of environment variables which are used in different places
 ///
 /// The variables here are not part of the parsed environment variables of `clap` in
 /// [`crate::runner::args::CommandLineArgs`]
 pub mod envs {
+    /// The path to the cargo binary which is executing the benchmarks
+    pub const CARGO: &str = "CARGO";
     /// The name of the package
     pub const CARGO_PKG_NAME: &str = "CARGO_PKG_NAME";
     /// Location of where to place all generated artifacts
     pub const CARGO_TARGET_DIR: &str = "CARGO_TARGET_DIR";
     /// The default color mode
     pub const CARGO_TERM_COLOR: &str = "CARGO_TERM_COLOR";
-
+    /// Set by the CodSpeed runner
+    pub const CODSPEED_ENV: &str = "CODSPEED_ENV";
+    /// The rust compiler used by cargo
+    pub const RUSTC: &str = "RUSTC";
+
+    /// The output directory of the benchmark file passed to the benchmarks
+    pub const BENCH_OUTPUT_DIR: &str = "_BENCH_OUTPUT_DIR";
+    /// The target directory of cargo passed to the benchmarks
+    pub const TARGET_DIR: &str = "_TARGET_DIR";
+    /// The workspace root passed to the benchmarks
+    pub const WORKSPACE_ROOT: &str = "_WORKSPACE_ROOT";
+
+    /// The uri of the benchmark passed to the benchmark binary in the CodSpeed mode
+    pub const IAI_CALLGRIND_CODSPEED_URI: &str = "IAI_CALLGRIND_CODSPEED_URI";
     /// The environment variable to set the color (same syntax as `CARGO_TERM_COLOR`)
     pub const IAI_CALLGRIND_COLOR: &str = "IAI_CALLGRIND_COLOR";
     /// Set the logging output of Iai-Callgrind
     pub const IAI_CALLGRIND_LOG: &str = "IAI_CALLGRIND_LOG";
+    /// The file with the outcomes of the finished benchmarks passed to the `teardown` functions
+    pub const IAI_CALLGRIND_OUTCOMES: &str = "IAI_CALLGRIND_OUTCOMES";
+    /// Set if the runner captures the output of a library benchmark and expects the phase markers
+    pub const IAI_CALLGRIND_PHASE_MARKERS: &str = "IAI_CALLGRIND_PHASE_MARKERS";
+    /// The id of the valgrind tool running the benchmark passed to the benchmarked executable
+    pub const IAI_CALLGRIND_TOOL: &str = "IAI_CALLGRIND_TOOL";
 }
 
 pub mod format;
+pub mod hooks;
 pub mod lib_bench;
+pub mod list;
+pub mod massif;
 pub mod meta;
 pub mod metrics;
+pub mod pipeline;
+pub mod profiles;
+pub mod report;
+pub mod retention;
+pub mod run_log;
+pub mod store;
 pub mod summary;
 pub mod tool;
+#[cfg(feature = "tui")]
+pub mod tui;
+pub mod watch;
 
 use std::env::ArgsOs;
 use std::ffi::OsString;
 use std::io::{stdin, Read};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use anyhow::{Context, Result};
-use args::CommandLineArgs;
+use args::{CommandLineArgs, ExitCodes};
+use clap::Parser;
 use common::{BenchmarkSummaries, Config, ModulePath};
 use format::OutputFormatKind;
-use log::debug;
+use log::{debug, error, info, warn};
 
+use self::binary::BinaryInfo;
+use self::hooks::Hooks;
 use self::meta::Metadata;
-use self::summary::BenchmarkKind;
-use crate::api::{BinaryBenchmarkGroups, LibraryBenchmarkGroups};
+use self::retention::BaselineRetention;
+use self::store::{ResultStore, SqliteStore};
+use self::summary::{BenchmarkKind, SummaryFormat};
+use self::watch::Watcher;
+use crate::api::{BinaryBenchmarkGroups, CommandKind, DeltaStyle, LibraryBenchmarkGroups, Sort};
 use crate::error::Error;
+use crate::util::{
+    open_with_default_application, open_with_default_application_after, parent_process_id,
+};
 
 /// The default toggle/frame used by the [`crate::api::EntryPoint::Default`]
 pub const DEFAULT_TOGGLE: &str = "*::__iai_callgrind_wrapper_mod::*";
 
+/// The pruning of the saved baselines with `--baseline-keep`
+#[derive(Debug)]
+struct BaselinePruning {
+    /// The baselines saved or compared against in this benchmark run which are never pruned
+    active_baselines: Vec<String>,
+    bench_output_dir: PathBuf,
+    retention: BaselineRetention,
+}
+
+/// The benchmark groups to run, either library or binary benchmark groups
+#[derive(Debug)]
+pub enum BenchmarkGroups {
+    /// The groups of a binary benchmark (the `binary_benchmark_group!` macros)
+    Binary(BinaryBenchmarkGroups),
+    /// The groups of a library benchmark (the `library_benchmark_group!` macros)
+    Library(LibraryBenchmarkGroups),
+}
+
 /// Execute post benchmark run actions like printing the summary line with regressions
 #[derive(Debug)]
 struct PostRun {
     benchmark_summaries: BenchmarkSummaries,
+    delta_style: Option<DeltaStyle>,
+    exit_codes: ExitCodes,
+    hooks: Hooks,
     nosummary: bool,
+    open: bool,
     output_format_kind: OutputFormatKind,
+    sort_by: Sort,
+    store: Option<SqliteStore>,
+    summary_stream: Option<PathBuf>,
+    tui: bool,
 }
 
 /// The arguments sent by the iai-callgrind benchmarking harness
@@ -75,34 +148,259 @@ struct RunnerArgs {
 
 struct RunnerArgsIterator(ArgsOs);
 
+/// The configuration to run benchmarks programmatically with [`run_with`]
+///
+/// Usually, the `iai-callgrind-runner` binary receives this information from the benchmark harness
+/// created by the `main!` macro. Embedding applications like integration tests, xtask scripts or
+/// custom CI drivers have to provide it themselves, usually with [`RunConfig::new`].
+#[derive(Debug)]
+pub struct RunConfig {
+    /// The path to the compiled benchmark binary with the benchmark harness
+    pub bench_bin: PathBuf,
+    /// The path to the benchmark file which contains the benchmark harness
+    pub bench_file: PathBuf,
+    /// The [`BenchmarkGroups`] to run
+    pub groups: BenchmarkGroups,
+    /// The module path of the benchmark file, usually the file name without the extension
+    pub module: String,
+    /// The package directory of the package in which `iai-callgrind` (not the runner) is used
+    pub package_dir: PathBuf,
+    /// The name of the package in which `iai-callgrind` is used
+    pub package_name: String,
+}
+
+impl BaselinePruning {
+    /// Create a new `BaselinePruning` if `--baseline-keep` is present
+    fn new(config: &Config) -> Option<Self> {
+        let args = &config.meta.args;
+        args.baseline_keep.map(|retention| Self {
+            active_baselines: [&args.save_baseline, &args.baseline, &args.load_baseline]
+                .into_iter()
+                .flatten()
+                .map(ToString::to_string)
+                .collect(),
+            bench_output_dir: config.meta.bench_output_dir(&config.module_path),
+            retention,
+        })
+    }
+
+    /// Prune the saved baselines in the `bench_output_dir` and the runs of the `result_store`
+    fn prune(&self, result_store: Option<&ResultStore>) -> Result<()> {
+        let now = SystemTime::now();
+        let active = self
+            .active_baselines
+            .iter()
+            .map(String::as_str)
+            .collect::<Vec<_>>();
+        let removed = retention::prune(&self.bench_output_dir, &self.retention, &active, now)?;
+        if removed > 0 {
+            info!(
+                "Pruned {removed} files of old baselines in '{}'",
+                self.bench_output_dir.display()
+            );
+        }
+
+        if let (Some(result_store), Some(cutoff)) = (result_store, self.retention.cutoff(now)) {
+            result_store.store.prune(
+                cutoff
+                    .duration_since(UNIX_EPOCH)
+                    .map_or(0, |duration| duration.as_secs()),
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+impl BenchmarkGroups {
+    /// Return the command-line arguments the benchmark executable was run with
+    fn command_line_args(&self) -> &[String] {
+        match self {
+            Self::Binary(groups) => &groups.command_line_args,
+            Self::Library(groups) => &groups.command_line_args,
+        }
+    }
+
+    /// Return the total number of benchmarks in all groups
+    ///
+    /// A benchmark with `iter` counts as many benchmarks as there are elements in the iterator.
+    fn num_benchmarks(&self) -> usize {
+        match self {
+            Self::Binary(groups) => groups
+                .groups
+                .iter()
+                .flat_map(|group| &group.binary_benchmarks)
+                .flat_map(|benchmark| &benchmark.benches)
+                .map(|bench| match &bench.command {
+                    CommandKind::Default(_) => 1,
+                    CommandKind::Iter(commands) => commands.len(),
+                })
+                .sum(),
+            Self::Library(groups) => groups
+                .groups
+                .iter()
+                .flat_map(|group| &group.library_benchmarks)
+                .flat_map(|benchmark| &benchmark.benches)
+                .map(|bench| bench.iter_count.unwrap_or(1))
+                .sum(),
+        }
+    }
+
+    /// Return the user metadata of the `main!` macro
+    fn metadata(&self) -> &[(String, String)] {
+        match self {
+            Self::Binary(groups) => &groups.metadata,
+            Self::Library(groups) => &groups.metadata,
+        }
+    }
+
+    /// Return the [`Sort`] of the output format of the main configuration if present
+    fn sort_by(&self) -> Option<Sort> {
+        let output_format = match self {
+            Self::Binary(groups) => groups.config.output_format.as_ref(),
+            Self::Library(groups) => groups.config.output_format.as_ref(),
+        };
+        output_format.and_then(|output_format| output_format.sort_by)
+    }
+}
+
 impl PostRun {
     /// Create a new `PostRun`
+    #[allow(clippy::too_many_arguments)]
     fn new(
+        delta_style: Option<DeltaStyle>,
+        exit_codes: ExitCodes,
+        hooks: Hooks,
         nosummary: bool,
+        open: bool,
         output_format_kind: OutputFormatKind,
+        sort_by: Sort,
+        store: Option<SqliteStore>,
+        summary_stream: Option<PathBuf>,
+        tui: bool,
         benchmark_summaries: BenchmarkSummaries,
     ) -> Self {
         Self {
             benchmark_summaries,
+            delta_style,
+            exit_codes,
+            hooks,
             nosummary,
+            open,
             output_format_kind,
+            sort_by,
+            store,
+            summary_stream,
+            tui,
         }
     }
 
-    /// Print the summary returning [`Error::RegressionError`] if regressions were present
+    /// Print the summary returning [`Error::ExitCodeError`] if the outcome of the benchmark run
+    /// maps to a non-zero exit code or the benchmark run was aborted
     ///
     /// The summary is not printed if `nosummary` is true or the [`OutputFormatKind`] is not the
-    /// default format (i.e. JSON).
-    fn execute(self) -> Result<()> {
-        self.benchmark_summaries
-            .print(self.nosummary, self.output_format_kind);
+    /// default format (i.e. JSON). The [`Hooks`] are run after the summary. With `--open`, the
+    /// flamegraph or html report is opened and with `--tui`, the results browser is opened. The
+    /// error which aborted the benchmark run is the source of the returned
+    /// [`Error::ExitCodeError`].
+    fn execute(mut self) -> Result<()> {
+        self.benchmark_summaries.print(
+            self.nosummary,
+            self.output_format_kind,
+            self.delta_style,
+            self.sort_by,
+        );
+
+        if let Some(summary_stream) = &self.summary_stream {
+            self.hooks
+                .run(self.benchmark_summaries.is_regressed(), summary_stream);
+        }
 
-        if self.benchmark_summaries.is_regressed() {
-            Err(Error::RegressionError(false).into())
+        if self.open {
+            self.open();
+        }
+
+        if self.tui {
+            self.browse()?;
+        }
+
+        let exit_code = self.benchmark_summaries.exit_code(self.exit_codes);
+        match self.benchmark_summaries.error.take() {
+            Some(error) => Err(error.context(Error::ExitCodeError(exit_code))),
+            None if exit_code != 0 => Err(Error::ExitCodeError(exit_code).into()),
+            None => Ok(()),
+        }
+    }
+
+    /// Open the interactive results browser if possible
+    #[cfg(feature = "tui")]
+    fn browse(&self) -> Result<()> {
+        use std::io::IsTerminal;
+
+        if std::io::stdout().is_terminal() {
+            tui::browse(&self.benchmark_summaries)
         } else {
+            warn!("The results browser (--tui) needs a terminal. Skipping ...");
             Ok(())
         }
     }
+
+    /// Open the flamegraph of a single benchmark or the html report in the default application
+    ///
+    /// If exactly one benchmark was run, for example because of a `FILTER`, its flamegraph is
+    /// opened. Otherwise, the html report is generated from the result store of `--store` and
+    /// opened. Failing to generate the report or to open a file is not an error of the benchmark
+    /// run, so it only results in a warning.
+    ///
+    /// `cargo bench` runs each benchmark file with a separate runner, so the html report is opened
+    /// only once after `cargo bench` has exited. Until then, every runner updates the report with
+    /// the results of its benchmark file.
+    fn open(&self) {
+        let flamegraph = match self.benchmark_summaries.summaries.as_slice() {
+            [summary] => summary
+                .profiles
+                .0
+                .iter()
+                .flat_map(|profile| &profile.flamegraphs)
+                .find_map(|flamegraph| flamegraph.regular_path.clone()),
+            _ => None,
+        };
+
+        let result = if let Some(flamegraph) = flamegraph {
+            open_with_default_application(&flamegraph)
+        } else if let Some(store) = &self.store {
+            let dir = report::default_dir(&store.path);
+            if let Err(error) = store
+                .records()
+                .and_then(|records| report::generate_site(&records, &dir))
+            {
+                warn!("--open: Failed to generate the html report: {error}");
+                return;
+            }
+            open_report(&dir)
+        } else {
+            warn!(
+                "--open: Nothing to open. Select a single benchmark with flamegraphs with a \
+                 FILTER or store the results with --store to open the html report."
+            );
+            return;
+        };
+
+        if let Err(error) = result {
+            warn!("--open: {error}");
+        }
+    }
+
+    /// The results browser is not available without the `tui` feature
+    #[cfg(not(feature = "tui"))]
+    #[allow(clippy::unnecessary_wraps, clippy::unused_self)]
+    fn browse(&self) -> Result<()> {
+        warn!(
+            "The results browser (--tui) is not available. Please install the \
+             iai-callgrind-runner with the 'tui' feature."
+        );
+        Ok(())
+    }
 }
 
 impl RunnerArgs {
@@ -171,6 +469,40 @@ impl RunnerArgsIterator {
     }
 }
 
+impl RunConfig {
+    /// Create a new `RunConfig` for the benchmark `groups` of the `bench_file`
+    ///
+    /// The `module` is the file name of the `bench_file` without the extension, like the
+    /// `module_path!()` of a benchmark file in the `main!` macro.
+    pub fn new<P, B, F>(
+        package_name: &str,
+        package_dir: P,
+        bench_file: F,
+        bench_bin: B,
+        groups: BenchmarkGroups,
+    ) -> Self
+    where
+        P: Into<PathBuf>,
+        B: Into<PathBuf>,
+        F: Into<PathBuf>,
+    {
+        let bench_file = bench_file.into();
+        let module = bench_file
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        Self {
+            bench_bin: bench_bin.into(),
+            bench_file,
+            groups,
+            module,
+            package_dir: package_dir.into(),
+            package_name: package_name.to_owned(),
+        }
+    }
+}
+
 fn compare_versions<R, L>(runner_version: R, library_version: L) -> Result<()>
 where
     R: AsRef<str>,
@@ -229,6 +561,14 @@ where
 }
 
 /// Run this benchmark
+///
+/// This is the entry point of the `iai-callgrind-runner` binary. The [`RunConfig`] is read from the
+/// command-line arguments and the encoded benchmark groups from `stdin`. After the run, the summary
+/// line is printed and an [`Error::ExitCodeError`] is returned if the outcome of the run maps to a
+/// non-zero exit code (see `--exit-codes`).
+///
+/// With `--watch`, errors of the benchmark run are only logged and this function doesn't return
+/// but re-runs the benchmark executable whenever it changes (see [`Watcher`]).
 pub fn run() -> Result<()> {
     let RunnerArgs {
         bench_kind,
@@ -240,86 +580,333 @@ pub fn run() -> Result<()> {
         num_bytes,
     } = RunnerArgs::new()?;
 
-    let post_run = match bench_kind {
-        BenchmarkKind::LibraryBenchmark => {
-            let benchmark_groups: LibraryBenchmarkGroups = receive_benchmark(num_bytes)?;
-            let meta = Metadata::new(
-                &benchmark_groups.command_line_args,
-                &package_name,
-                &bench_file,
-            )?;
-            if meta
-                .args
-                .filter
-                .as_ref()
-                .is_some_and(|filter| !filter.apply(&meta.bench_name))
-            {
-                debug!("Benchmark '{}' is filtered out", bench_file.display());
-                return Ok(());
-            }
+    let groups = match bench_kind {
+        BenchmarkKind::LibraryBenchmark => BenchmarkGroups::Library(receive_benchmark(num_bytes)?),
+        BenchmarkKind::BinaryBenchmark => BenchmarkGroups::Binary(receive_benchmark(num_bytes)?),
+    };
 
-            let config = Config {
-                package_dir,
-                bench_file,
-                module_path: ModulePath::new(&module),
-                bench_bin,
-                meta,
-            };
-
-            let CommandLineArgs {
-                output_format,
-                list,
-                nosummary,
-                ..
-            } = config.meta.args;
-
-            if list {
-                return lib_bench::list(benchmark_groups, &config);
-            }
+    // The arguments are parsed again in `run_config` and errors are reported there
+    let watcher = CommandLineArgs::try_parse_from(groups.command_line_args())
+        .is_ok_and(|args| args.watch)
+        .then(|| Watcher::new(&bench_bin, groups.command_line_args()));
 
-            lib_bench::run(benchmark_groups, config)
-                .map(|summaries| PostRun::new(nosummary, output_format, summaries))?
+    let result = match run_config(RunConfig {
+        bench_bin,
+        bench_file,
+        groups,
+        module,
+        package_dir,
+        package_name,
+    }) {
+        Ok(Some(post_run)) => post_run.execute(),
+        // The benchmark was filtered out or `--list` was given, so there's nothing to watch
+        Ok(None) => return Ok(()),
+        Err(error) => Err(error),
+    };
+
+    if let Some(watcher) = watcher {
+        if let Err(error) = result {
+            error!("{error}");
         }
-        BenchmarkKind::BinaryBenchmark => {
-            let benchmark_groups: BinaryBenchmarkGroups = receive_benchmark(num_bytes)?;
-            let meta = Metadata::new(
-                &benchmark_groups.command_line_args,
-                &package_name,
-                &bench_file,
-            )?;
-            if meta
-                .args
-                .filter
-                .as_ref()
-                .is_some_and(|filter| !filter.apply(&meta.bench_name))
-            {
-                debug!("Benchmark '{}' is filtered out", bench_file.display());
-                return Ok(());
-            }
+        watcher.watch()
+    } else {
+        result
+    }
+}
 
-            let config = Config {
-                package_dir,
-                bench_file,
-                module_path: ModulePath::new(&module),
-                bench_bin,
-                meta,
-            };
-
-            let CommandLineArgs {
-                output_format,
-                list,
-                nosummary,
-                ..
-            } = config.meta.args;
-
-            if list {
-                return bin_bench::list(benchmark_groups, &config);
-            }
+/// Run the benchmarks described by the [`RunConfig`] in-process and return the
+/// [`BenchmarkSummaries`]
+///
+/// In contrast to [`run`], nothing is read from the command-line or `stdin`, the final summary line
+/// is not printed and regressions don't result in an error unless they are configured to be
+/// `fail_fast`. Use [`BenchmarkSummaries::is_regressed`] to inspect the results for regressions. If
+/// the benchmark is filtered out or the `--list` argument is present, the returned
+/// [`BenchmarkSummaries`] is empty. The error which aborted the benchmark run is returned as is.
+///
+/// With `--save-summary`, the complete summaries are streamed to the `summaries.jsonl` file and the
+/// returned summaries contain only the totals of the profiles (see
+/// [`BenchmarkSummary::clear_parts`](summary::BenchmarkSummary::clear_parts)).
+pub fn run_with(config: RunConfig) -> Result<BenchmarkSummaries> {
+    let mut summaries = run_config(config)?.map_or_else(BenchmarkSummaries::default, |post_run| {
+        post_run.benchmark_summaries
+    });
+    match summaries.error.take() {
+        Some(error) => Err(error),
+        None => Ok(summaries),
+    }
+}
+
+/// Open the `index.html` of the html report in the `dir` after `cargo bench` has exited
+///
+/// The `cargo` process is the parent of the benchmark executable, which is the parent of this
+/// runner. If the benchmark executable wasn't run by cargo, the report is opened immediately.
+fn open_report(dir: &Path) -> Result<()> {
+    let index = dir.join("index.html");
+    let cargo_pid = std::env::var_os("CARGO")
+        .and_then(|_| parent_process_id(std::os::unix::process::parent_id()));
+
+    if let Some(pid) = cargo_pid {
+        let lock = dir.join(format!(".open.{pid}"));
+        if !open_with_default_application_after(&index, pid, &lock)? {
+            debug!("The html report is already opened after cargo with pid {pid} has exited");
+        }
+        Ok(())
+    } else {
+        open_with_default_application(&index)
+    }
+}
 
-            bin_bench::run(benchmark_groups, config)
-                .map(|summaries| PostRun::new(nosummary, output_format, summaries))?
+/// Return the [`Metadata`] of the benchmark run or `None` if the benchmark isn't run
+///
+/// The benchmark isn't run on an unsupported platform or if it is filtered out with `--filter`.
+fn metadata(
+    groups: &BenchmarkGroups,
+    module: &str,
+    package_name: &str,
+    bench_file: &Path,
+) -> Result<Option<Metadata>> {
+    let meta = match Metadata::new(groups.command_line_args(), package_name, bench_file) {
+        Err(error)
+            if matches!(
+                error.downcast_ref::<Error>(),
+                Some(Error::UnsupportedPlatform(_))
+            ) =>
+        {
+            format::print_unsupported_platform(module, groups.num_benchmarks(), &error);
+            return Ok(None);
         }
+        result => result?,
     };
+    if meta
+        .args
+        .filter
+        .as_ref()
+        .is_some_and(|filter| !filter.apply(&meta.bench_name))
+    {
+        debug!("Benchmark '{}' is filtered out", bench_file.display());
+        return Ok(None);
+    }
+
+    Ok(Some(meta))
+}
+
+/// Return the path to the summary stream if the summaries have to be streamed
+///
+/// The summary stream is needed by `--save-summary` (except for the `openmetrics` format) and by
+/// the hooks, which receive the path to the summary stream.
+fn summary_stream(meta: &Metadata, module_path: &ModulePath, hooks: &Hooks) -> Option<PathBuf> {
+    (meta
+        .args
+        .save_summary
+        .is_some_and(|format| format != SummaryFormat::OpenMetrics)
+        || !hooks.is_empty())
+    .then(|| meta.bench_output_dir(module_path).join("summaries.jsonl"))
+}
 
-    post_run.execute()
+/// Remove the summary stream at `path` of a previous benchmark run if present
+///
+/// The summary stream contains only the summaries of the current benchmark run.
+fn remove_summary_stream(path: &Path) -> Result<()> {
+    if path.exists() {
+        std::fs::remove_file(path)
+            .with_context(|| format!("Failed to remove summary stream '{}'", path.display()))?;
+    }
+    Ok(())
+}
+
+/// Append the `summaries` to the `result_store` if present and return its [`SqliteStore`]
+fn append_to_store(
+    result_store: Option<ResultStore>,
+    summaries: &BenchmarkSummaries,
+) -> Result<Option<SqliteStore>> {
+    result_store
+        .map(|result_store| result_store.append(summaries).map(|()| result_store.store))
+        .transpose()
+}
+
+/// Run the benchmarks of the [`RunConfig`] returning the [`PostRun`] if any benchmarks were run
+fn run_config(config: RunConfig) -> Result<Option<PostRun>> {
+    let RunConfig {
+        bench_bin,
+        bench_file,
+        groups,
+        module,
+        package_dir,
+        package_name,
+    } = config;
+
+    let Some(meta) = metadata(&groups, &module, &package_name, &bench_file)? else {
+        return Ok(None);
+    };
+
+    // The metadata of the command-line takes precedence over the metadata of the `main!` macro
+    let metadata = groups
+        .metadata()
+        .iter()
+        .chain(meta.args.metadata.iter())
+        .cloned()
+        .collect();
+
+    let module_path = ModulePath::new(&module);
+    let hooks = Hooks::new(meta.args.on_finish.clone(), meta.args.on_regression.clone());
+    let summary_stream = summary_stream(&meta, &module_path, &hooks);
+
+    let config = Config {
+        package_dir,
+        bench_file,
+        module_path,
+        bench_bin,
+        meta,
+        metadata,
+        overhead: None,
+        summary_stream,
+    };
+
+    let CommandLineArgs {
+        delta_style,
+        exit_codes,
+        output_format,
+        list,
+        nosummary,
+        open,
+        sort_by,
+        store,
+        tui,
+        ..
+    } = config.meta.args;
+    let sort_by = sort_by.or_else(|| groups.sort_by()).unwrap_or_default();
+
+    if !list {
+        BinaryInfo::new(&config.bench_bin).print_warnings();
+        if output_format == OutputFormatKind::Default {
+            format::print_environment(&config.meta.environment);
+        }
+    }
+
+    let result_store = store
+        .filter(|_| !list)
+        .map(|kind| ResultStore::new(kind, &config.meta))
+        .transpose()?;
+    let pruning = BaselinePruning::new(&config);
+    let summary_stream = config.summary_stream.clone().filter(|_| !hooks.is_empty());
+    if let Some(path) = config.summary_stream.as_ref().filter(|_| !list) {
+        remove_summary_stream(path)?;
+    }
+
+    let summaries = match groups {
+        BenchmarkGroups::Binary(groups) if list => {
+            return bin_bench::list(groups, config).map(|()| None);
+        }
+        BenchmarkGroups::Binary(groups) => bin_bench::run(groups, config)?,
+        BenchmarkGroups::Library(groups) if list => {
+            return lib_bench::list(groups, config).map(|()| None);
+        }
+        BenchmarkGroups::Library(groups) => lib_bench::run(groups, config)?,
+    };
+
+    // Only a successful benchmark run prunes the baselines. The baselines of a regressed run may be
+    // needed to investigate the regression.
+    if let Some(pruning) = pruning.filter(|_| !summaries.is_regressed()) {
+        pruning.prune(result_store.as_ref())?;
+    }
+    let store = append_to_store(result_store, &summaries)?;
+
+    Ok(Some(PostRun::new(
+        delta_style,
+        exit_codes,
+        hooks,
+        nosummary,
+        open,
+        output_format,
+        sort_by,
+        store,
+        summary_stream,
+        tui,
+        summaries,
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::*;
+    use crate::api::{
+        BinaryBenchmark, BinaryBenchmarkBench, BinaryBenchmarkConfig, BinaryBenchmarkGroup,
+        BinaryBenchmarkGroups, Command, ValgrindTool,
+    };
+
+    fn binary_benchmark_groups(num_commands: usize) -> BinaryBenchmarkGroups {
+        let bench = BinaryBenchmarkBench {
+            args: None,
+            command: CommandKind::Iter(vec![Command::default(); num_commands]),
+            config: None,
+            function_name: "bench_echo".to_owned(),
+            has_setup: false,
+            has_teardown: false,
+            id: Some("iter".to_owned()),
+        };
+        let default_bench = BinaryBenchmarkBench {
+            command: CommandKind::Default(Box::default()),
+            id: Some("default".to_owned()),
+            ..bench.clone()
+        };
+
+        BinaryBenchmarkGroups {
+            command_line_args: vec!["--bench".to_owned(), "--list".to_owned()],
+            config: BinaryBenchmarkConfig::default(),
+            default_tool: ValgrindTool::Callgrind,
+            groups: vec![BinaryBenchmarkGroup {
+                binary_benchmarks: vec![BinaryBenchmark {
+                    benches: vec![bench, default_bench],
+                    config: None,
+                }],
+                compare_by_id: None,
+                config: None,
+                has_setup: false,
+                has_teardown: false,
+                id: "my_group".to_owned(),
+            }],
+            has_setup: false,
+            has_teardown: false,
+            metadata: vec![("key".to_owned(), "value".to_owned())],
+        }
+    }
+
+    #[test]
+    fn test_run_config_new() {
+        let config = RunConfig::new(
+            "my-package",
+            "/home/my/my-package",
+            "benches/my_bench.rs",
+            "/home/my/my-package/target/release/deps/my_bench-1234",
+            BenchmarkGroups::Binary(binary_benchmark_groups(2)),
+        );
+
+        assert_eq!(config.module, "my_bench");
+        assert_eq!(config.package_name, "my-package");
+        assert_eq!(config.package_dir, PathBuf::from("/home/my/my-package"));
+        assert_eq!(config.bench_file, PathBuf::from("benches/my_bench.rs"));
+        assert_eq!(
+            config.bench_bin,
+            PathBuf::from("/home/my/my-package/target/release/deps/my_bench-1234")
+        );
+        assert_eq!(
+            config.groups.command_line_args(),
+            ["--bench".to_owned(), "--list".to_owned()]
+        );
+        assert_eq!(
+            config.groups.metadata(),
+            [("key".to_owned(), "value".to_owned())]
+        );
+    }
+
+    #[rstest]
+    #[case::empty_iter(0, 1)]
+    #[case::iter(3, 4)]
+    fn test_benchmark_groups_num_benchmarks(#[case] num_commands: usize, #[case] expected: usize) {
+        let groups = BenchmarkGroups::Binary(binary_benchmark_groups(num_commands));
+        assert_eq!(groups.num_benchmarks(), expected);
+    }
 }
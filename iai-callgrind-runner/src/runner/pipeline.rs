@@ -0,0 +1,163 @@
+//! The module containing the [`Pipeline`] of the benchmarks of a group
+//!
+//! A benchmark consists of two parts: The valgrind runs and everything which only needs the output
+//! files of valgrind, like parsing the output files, the terminal output, the flamegraphs and the
+//! other artifacts. The second part of a benchmark is a [`Job`] which runs on a worker thread while
+//! valgrind already runs the next benchmark. There is at most one [`Pending`] job at a time, so the
+//! terminal output of the benchmarks is printed in the same order as without the pipeline.
+
+use std::thread::{Scope, ScopedJoinHandle};
+
+use anyhow::Result;
+use log::debug;
+
+/// The second part of a benchmark which runs in the [`Pipeline`]
+pub type Job<'a, T> = Box<dyn FnOnce() -> Result<T> + Send + 'a>;
+
+/// A [`Job`] which was submitted to the [`Pipeline`]
+#[derive(Debug)]
+pub enum Pending<'scope, T> {
+    /// The result of a job which was run immediately
+    Done(Result<T>),
+    /// A job which runs on a worker thread
+    Running(ScopedJoinHandle<'scope, Result<T>>),
+}
+
+/// The pipeline of the benchmarks of a group
+///
+/// The worker threads are scoped threads, so the [`Job`]s can borrow the configuration of the
+/// benchmarks and all jobs have finished when the [`std::thread::scope`] ends.
+#[derive(Debug, Clone, Copy)]
+pub struct Pipeline<'scope, 'env> {
+    scope: &'scope Scope<'scope, 'env>,
+}
+
+impl<'scope, 'env> Pipeline<'scope, 'env> {
+    /// Create a new `Pipeline` which starts the worker threads in the `scope`
+    pub fn new(scope: &'scope Scope<'scope, 'env>) -> Self {
+        Self { scope }
+    }
+
+    /// Submit a `job` to a worker thread
+    ///
+    /// If `is_serial` is true or the worker thread can't be started, the `job` is run immediately.
+    /// A serial job is required if the output of the benchmark isn't captured, since the output
+    /// would be interleaved with the terminal output of the pending job otherwise.
+    pub fn submit<T>(&self, job: Job<'scope, T>, is_serial: bool) -> Pending<'scope, T>
+    where
+        T: Send + 'scope,
+    {
+        if is_serial {
+            return Pending::Done(job());
+        }
+
+        // The job is moved into the closure only if the thread could be started, so the job can
+        // still be run immediately if starting the thread fails
+        let (sender, receiver) = std::sync::mpsc::sync_channel::<Job<'scope, T>>(1);
+        let handle = std::thread::Builder::new()
+            .name("pipeline".to_owned())
+            .spawn_scoped(self.scope, move || match receiver.recv() {
+                Ok(job) => job(),
+                Err(_) => unreachable!("The job should be sent after the thread has started"),
+            });
+
+        match handle {
+            Ok(handle) => {
+                sender
+                    .send(job)
+                    .expect("The worker thread should wait for the job");
+                Pending::Running(handle)
+            }
+            Err(error) => {
+                debug!("Failed to start a pipeline worker thread: {error}");
+                Pending::Done(job())
+            }
+        }
+    }
+}
+
+impl<T> Pending<'_, T> {
+    /// Wait for the [`Job`] to finish and return its result
+    ///
+    /// A panic of the job is propagated to the current thread.
+    pub fn join(self) -> Result<T> {
+        match self {
+            Pending::Done(result) => result,
+            Pending::Running(handle) => handle
+                .join()
+                .unwrap_or_else(|payload| std::panic::resume_unwind(payload)),
+        }
+    }
+}
+
+/// Return a [`Job`] which only returns the `error`
+pub fn failed<'a, T>(error: anyhow::Error) -> Job<'a, T> {
+    Box::new(move || Err(error))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread::ThreadId;
+
+    use anyhow::anyhow;
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case::worker(false)]
+    #[case::serial(true)]
+    fn test_pipeline_submit_then_join(#[case] is_serial: bool) {
+        let counter = AtomicUsize::new(0);
+        let main_thread = std::thread::current().id();
+
+        let thread_ids: Vec<ThreadId> = std::thread::scope(|scope| {
+            let pipeline = Pipeline::new(scope);
+            std::iter::repeat_with(|| {
+                let counter = &counter;
+                pipeline
+                    .submit(
+                        Box::new(move || {
+                            counter.fetch_add(1, Ordering::SeqCst);
+                            Ok(std::thread::current().id())
+                        }),
+                        is_serial,
+                    )
+                    .join()
+                    .unwrap()
+            })
+            .take(10)
+            .collect()
+        });
+
+        assert_eq!(counter.load(Ordering::SeqCst), 10);
+        assert_eq!(
+            thread_ids.iter().all(|id| *id == main_thread),
+            is_serial,
+            "{thread_ids:?}"
+        );
+    }
+
+    #[test]
+    fn test_pipeline_join_returns_error_of_job() {
+        let result = std::thread::scope(|scope| {
+            Pipeline::new(scope)
+                .submit::<()>(Box::new(|| Err(anyhow!("failed"))), false)
+                .join()
+        });
+
+        assert_eq!(result.unwrap_err().to_string(), "failed");
+    }
+
+    #[test]
+    #[should_panic = "job panicked"]
+    fn test_pipeline_join_propagates_panic_of_job() {
+        std::thread::scope(|scope| {
+            let _ = Pipeline::new(scope)
+                .submit::<()>(Box::new(|| panic!("job panicked")), false)
+                .join();
+        });
+    }
+}
@@ -0,0 +1,556 @@
+//! The module containing the [`CONFIG_FILE`] and the named profiles of the `--profile` argument
+//!
+//! A named profile bundles a set of tools, arguments, flamegraph and regression settings under a
+//! name, so the same benchmark suite can be run in different configurations (for example `quick`,
+//! `memory` and `full` in CI) without changing the benchmark code. Named profiles are defined in
+//! the [`CONFIG_FILE`] in the workspace root as command-line arguments or in the benchmark files
+//! with `LibraryBenchmarkConfig::profile` and `BinaryBenchmarkConfig::profile`. The profiles of
+//! the [`CONFIG_FILE`] take precedence.
+//!
+//! Besides the profiles, the [`CONFIG_FILE`] contains the default command-line arguments of the
+//! project, so they don't have to be repeated in every CI job or developer documentation, and the
+//! command-line arguments of single benchmarks, so their tools, regression limits and tool
+//! arguments can be configured outside of the benchmark code.
+
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+use clap::{CommandFactory, Parser};
+use indexmap::IndexMap;
+use serde::Deserialize;
+
+use super::args::{CommandLineArgs, MeasurementProfile};
+use super::common::ModulePath;
+use crate::util::Glob;
+
+/// The name of the configuration file with the default arguments and named profiles in the
+/// workspace root
+pub const CONFIG_FILE: &str = "iai-callgrind.json";
+
+/// The configuration file [`CONFIG_FILE`]
+///
+/// ```json
+/// {
+///   "args": ["--callgrind-limits=ir=5%", "--save-summary"],
+///   "profiles": {
+///     "quick": ["--profile=fast"],
+///     "memory": ["--tools=dhat,massif", "--dhat-limits=totalbytes=5%"]
+///   },
+///   "benchmarks": {
+///     "my_group::bench_parser::*": ["--callgrind-limits=ir=10%"]
+///   }
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConfigFile {
+    /// The default command-line arguments
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// The command-line arguments of the benchmarks matching the `group::function[::id]` patterns
+    #[serde(default)]
+    pub benchmarks: IndexMap<String, Vec<String>>,
+    /// The named profiles with the command-line arguments of each profile
+    #[serde(default)]
+    pub profiles: IndexMap<String, Vec<String>>,
+}
+
+/// The command-line arguments of the benchmarks of the [`ConfigFile`]
+///
+/// The arguments of the sections which match a benchmark are placed behind the default arguments
+/// and the arguments of the named profile but in front of the arguments of the user.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BenchmarkArgs {
+    prefix: Vec<String>,
+    profile: Option<MeasurementProfile>,
+    raw: Vec<String>,
+    sections: Vec<(Glob, Vec<String>)>,
+}
+
+impl BenchmarkArgs {
+    /// Return true if the `pattern` matches the benchmark at `module_path` with the `id`
+    ///
+    /// The first segment of the `module_path`, the name of the benchmark file, is not part of the
+    /// pattern. A pattern without the `id` matches all benchmarks of the function.
+    fn is_match(pattern: &Glob, module_path: &ModulePath, id: Option<&String>) -> bool {
+        let path = module_path
+            .as_str()
+            .split_once("::")
+            .map_or(module_path.as_str(), |(_, rest)| rest);
+        pattern.is_match(path) || id.is_some_and(|id| pattern.is_match(&format!("{path}::{id}")))
+    }
+
+    /// Resolve the command-line arguments of the benchmark at `module_path` with the `id`
+    ///
+    /// Returns `None` if no section matches the benchmark. Otherwise, the arguments of all
+    /// matching sections are applied in the order of the [`CONFIG_FILE`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the arguments of the matching sections are invalid
+    pub fn resolve(
+        &self,
+        module_path: &ModulePath,
+        id: Option<&String>,
+    ) -> Result<Option<CommandLineArgs>> {
+        let mut matching = self
+            .sections
+            .iter()
+            .filter(|(pattern, _)| Self::is_match(pattern, module_path, id))
+            .flat_map(|(_, args)| args)
+            .peekable();
+        if matching.peek().is_none() {
+            return Ok(None);
+        }
+
+        let mut resolved =
+            CommandLineArgs::try_parse_from(self.prefix.iter().chain(matching).chain(&self.raw))
+                .map_err(|error| {
+                    anyhow!("Invalid benchmark arguments in '{CONFIG_FILE}': {error}")
+                })?;
+        resolved.profile.clone_from(&self.profile);
+
+        Ok(Some(resolved))
+    }
+}
+
+impl ConfigFile {
+    /// Load the [`CONFIG_FILE`] from the `project_root`
+    ///
+    /// Returns the default (without any profiles) if there is no such file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file exists but could not be read or parsed
+    pub fn load(project_root: &Path) -> Result<Self> {
+        let path = project_root.join(CONFIG_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let file = File::open(&path)
+            .with_context(|| format!("Error opening configuration file '{}'", path.display()))?;
+        serde_json::from_reader(BufReader::new(file))
+            .with_context(|| format!("Error parsing configuration file '{}'", path.display()))
+    }
+
+    /// Return the default arguments of this configuration file which aren't overridden by their
+    /// environment variable
+    ///
+    /// The default arguments are placed in front of the command-line arguments, so they would take
+    /// precedence over the environment variables. Instead, the environment variables take
+    /// precedence over the default arguments just like over the default values of the arguments.
+    pub fn default_args(&self) -> Vec<String> {
+        let command = CommandLineArgs::command();
+        let is_env_set = |name: &str| {
+            command
+                .get_arguments()
+                .find(|arg| arg.get_long() == Some(name))
+                .and_then(|arg| arg.get_env())
+                .is_some_and(|env| std::env::var_os(env).is_some())
+        };
+
+        let mut default_args = vec![];
+        let mut iter = self.args.iter().peekable();
+        while let Some(arg) = iter.next() {
+            let Some(long) = arg.strip_prefix("--") else {
+                default_args.push(arg.clone());
+                continue;
+            };
+            let (name, value) = match long.split_once('=') {
+                Some((name, _)) => (name, None),
+                None => (long, iter.next_if(|next| !next.starts_with('-')).cloned()),
+            };
+
+            if !is_env_set(name) {
+                default_args.push(arg.clone());
+                default_args.extend(value);
+            }
+        }
+
+        default_args
+    }
+
+    /// Return the arguments in front of the `raw_command_line_args` and the resolved profile
+    ///
+    /// These are the default arguments and the arguments of the named profile of the `args` if
+    /// the profile is defined in this file.
+    fn prefix_args(
+        &self,
+        args: &CommandLineArgs,
+        raw_command_line_args: &[String],
+    ) -> Result<(Vec<String>, Option<MeasurementProfile>)> {
+        let mut prefix = self.default_args();
+        let profile = if prefix.is_empty() {
+            args.profile.clone()
+        } else {
+            CommandLineArgs::try_parse_from(prefix.iter().chain(raw_command_line_args))
+                .map_err(|error| anyhow!("Invalid default arguments in '{CONFIG_FILE}': {error}"))?
+                .profile
+        };
+
+        let Some(MeasurementProfile::Named(name)) = &profile else {
+            return Ok((prefix, profile));
+        };
+        let Some(profile_args) = self.profiles.get(name) else {
+            return Ok((prefix, profile));
+        };
+
+        let resolved = profile_args
+            .iter()
+            .zip(profile_args.iter().skip(1).map(Some).chain([None]))
+            .filter_map(|(arg, next)| {
+                arg.strip_prefix("--profile=")
+                    .or_else(|| (arg == "--profile").then_some(next?.as_str()))
+            })
+            .last()
+            .map(str::parse::<MeasurementProfile>)
+            .transpose()
+            .map_err(|error| anyhow!("Invalid arguments of profile '{name}': {error}"))?;
+        if let Some(MeasurementProfile::Named(other)) = &resolved {
+            return Err(anyhow!(
+                "Invalid arguments of profile '{name}': A profile can't select another named \
+                 profile ('{other}')"
+            ));
+        }
+
+        CommandLineArgs::try_parse_from(profile_args.iter().chain(raw_command_line_args))
+            .map_err(|error| anyhow!("Invalid arguments of profile '{name}': {error}"))?;
+        prefix.extend(profile_args.iter().cloned());
+
+        Ok((prefix, resolved))
+    }
+
+    /// Resolve the default arguments and the named profile of the `args` with this configuration
+    /// file
+    ///
+    /// The `raw_command_line_args` are parsed again with the default arguments and the arguments
+    /// of the named profile (if defined in this file) in front of them, so the arguments of the
+    /// user take precedence. Arguments which accumulate, like `--tools`, are combined. The
+    /// `--profile` of the result is the `fast` or `full` profile of the profile arguments if
+    /// present. If the named profile is not defined in this file, it is looked up in the benchmark
+    /// configuration (see [`find`]).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the default arguments or the arguments of the profile are invalid or
+    /// the profile selects another named profile
+    pub fn resolve(
+        &self,
+        args: CommandLineArgs,
+        raw_command_line_args: &[String],
+    ) -> Result<CommandLineArgs> {
+        let (prefix, profile) = self.prefix_args(&args, raw_command_line_args)?;
+        if prefix.is_empty() {
+            return Ok(args);
+        }
+
+        let mut resolved =
+            CommandLineArgs::try_parse_from(prefix.iter().chain(raw_command_line_args))
+                .map_err(|error| anyhow!("Invalid arguments in '{CONFIG_FILE}': {error}"))?;
+        resolved.profile = profile;
+
+        Ok(resolved)
+    }
+
+    /// Return the [`BenchmarkArgs`] of this configuration file
+    ///
+    /// The `args` are the arguments parsed from the `raw_command_line_args` before they were
+    /// resolved with this file (see [`ConfigFile::resolve`]).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the arguments of a benchmark section are invalid or select a profile
+    pub fn benchmark_args(
+        &self,
+        args: &CommandLineArgs,
+        raw_command_line_args: &[String],
+    ) -> Result<BenchmarkArgs> {
+        if self.benchmarks.is_empty() {
+            return Ok(BenchmarkArgs::default());
+        }
+
+        let (prefix, profile) = self.prefix_args(args, raw_command_line_args)?;
+        let mut sections = Vec::with_capacity(self.benchmarks.len());
+        for (pattern, benchmark_args) in &self.benchmarks {
+            if benchmark_args
+                .iter()
+                .any(|arg| arg.starts_with("--profile"))
+            {
+                return Err(anyhow!(
+                    "Invalid arguments of benchmark '{pattern}': The profile can't be changed per \
+                     benchmark"
+                ));
+            }
+            CommandLineArgs::try_parse_from(benchmark_args.iter().chain(raw_command_line_args))
+                .map_err(|error| anyhow!("Invalid arguments of benchmark '{pattern}': {error}"))?;
+            sections.push((Glob::new(pattern.as_str()), benchmark_args.clone()));
+        }
+
+        Ok(BenchmarkArgs {
+            prefix,
+            profile,
+            raw: raw_command_line_args.to_vec(),
+            sections,
+        })
+    }
+}
+
+/// Find the configuration of the named profile of the `--profile` argument in the `profiles`
+///
+/// Returns `None` if no named profile is selected or if it was already resolved with the
+/// [`ConfigFile`].
+///
+/// # Errors
+///
+/// Returns an error if the named profile is not defined
+pub fn find<'a, T>(
+    profiles: &'a [(String, T)],
+    profile: Option<&MeasurementProfile>,
+) -> Result<Option<&'a T>> {
+    let Some(MeasurementProfile::Named(name)) = profile else {
+        return Ok(None);
+    };
+
+    profiles
+        .iter()
+        .find_map(|(other, config)| (other == name).then_some(config))
+        .map(Some)
+        .ok_or_else(|| {
+            anyhow!(
+                "Unknown profile '{name}': The profile is neither defined in '{CONFIG_FILE}' nor \
+                 in the benchmark configuration"
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::*;
+    use crate::api::ValgrindTool;
+    use crate::runner::args::NoCapture;
+
+    fn config_file() -> ConfigFile {
+        ConfigFile {
+            args: vec![],
+            benchmarks: IndexMap::new(),
+            profiles: IndexMap::from([
+                ("quick".to_owned(), vec!["--profile=fast".to_owned()]),
+                (
+                    "memory".to_owned(),
+                    vec!["--tools=dhat".to_owned(), "--nocapture".to_owned()],
+                ),
+                (
+                    "nested".to_owned(),
+                    vec!["--profile".to_owned(), "quick".to_owned()],
+                ),
+            ]),
+        }
+    }
+
+    fn resolve(raw_args: &[&str]) -> Result<CommandLineArgs> {
+        let raw_args = raw_args.iter().map(|s| (*s).to_owned()).collect::<Vec<_>>();
+        let args = CommandLineArgs::parse_from(&raw_args);
+        config_file().resolve(args, &raw_args)
+    }
+
+    #[test]
+    fn test_config_file_deserialize() {
+        let actual: ConfigFile = serde_json::from_str(
+            r#"{"profiles": {"quick": ["--profile=fast"], "memory": ["--tools=dhat"]}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            actual.profiles.get("memory"),
+            Some(&vec!["--tools=dhat".to_owned()])
+        );
+    }
+
+    #[test]
+    fn test_config_file_resolve_when_fast() {
+        let actual = resolve(&["--profile=quick"]).unwrap();
+        assert_eq!(actual.profile, Some(MeasurementProfile::Fast));
+    }
+
+    #[test]
+    fn test_config_file_resolve_when_user_args_then_precedence() {
+        let actual =
+            resolve(&["--profile=memory", "--nocapture=stderr", "--tools=massif"]).unwrap();
+        assert_eq!(actual.profile, None);
+        assert_eq!(actual.nocapture, NoCapture::Stderr);
+        assert_eq!(actual.tools, vec![ValgrindTool::DHAT, ValgrindTool::Massif]);
+    }
+
+    #[test]
+    fn test_config_file_resolve_when_not_defined_then_unchanged() {
+        let actual = resolve(&["--profile=other"]).unwrap();
+        assert_eq!(
+            actual.profile,
+            Some(MeasurementProfile::Named("other".to_owned()))
+        );
+    }
+
+    #[test]
+    fn test_config_file_resolve_when_nested_then_error() {
+        resolve(&["--profile=nested"]).unwrap_err();
+    }
+
+    #[rstest]
+    #[case::defaults(&[], NoCapture::Stdout, vec![ValgrindTool::DHAT])]
+    #[case::user_args_then_precedence(
+        &["--nocapture=stderr", "--tools=massif"],
+        NoCapture::Stderr,
+        vec![ValgrindTool::DHAT, ValgrindTool::Massif]
+    )]
+    #[case::profile(
+        &["--profile=memory"],
+        NoCapture::True,
+        vec![ValgrindTool::DHAT, ValgrindTool::DHAT]
+    )]
+    #[serial_test::serial]
+    fn test_config_file_resolve_with_default_args(
+        #[case] raw_args: &[&str],
+        #[case] expected_nocapture: NoCapture,
+        #[case] expected_tools: Vec<ValgrindTool>,
+    ) {
+        std::env::remove_var("IAI_CALLGRIND_NOCAPTURE");
+        std::env::remove_var("IAI_CALLGRIND_TOOLS");
+
+        let mut config_file = config_file();
+        config_file.args = vec![
+            "--nocapture=stdout".to_owned(),
+            "--tools".to_owned(),
+            "dhat".to_owned(),
+        ];
+        let raw_args = raw_args.iter().map(|s| (*s).to_owned()).collect::<Vec<_>>();
+        let args = CommandLineArgs::parse_from(&raw_args);
+
+        let actual = config_file.resolve(args, &raw_args).unwrap();
+        assert_eq!(actual.nocapture, expected_nocapture);
+        assert_eq!(actual.tools, expected_tools);
+    }
+
+    #[rstest]
+    #[case::function("group::function", "file::group::function", None, true)]
+    #[case::function_with_id("group::function", "file::group::function", Some("id"), true)]
+    #[case::id("group::function::id", "file::group::function", Some("id"), true)]
+    #[case::other_id("group::function::id", "file::group::function", Some("other"), false)]
+    #[case::wildcard("group::*", "file::group::function", Some("id"), true)]
+    #[case::other_group("other::*", "file::group::function", None, false)]
+    #[case::with_file("file::group::function", "file::group::function", None, false)]
+    fn test_benchmark_args_is_match(
+        #[case] pattern: &str,
+        #[case] module_path: &str,
+        #[case] id: Option<&str>,
+        #[case] expected: bool,
+    ) {
+        let id = id.map(ToOwned::to_owned);
+        assert_eq!(
+            BenchmarkArgs::is_match(
+                &Glob::new(pattern),
+                &ModulePath::new(module_path),
+                id.as_ref()
+            ),
+            expected
+        );
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_config_file_benchmark_args() {
+        let mut config_file = config_file();
+        config_file.args = vec!["--tools=dhat".to_owned()];
+        config_file.benchmarks = IndexMap::from([
+            (
+                "group::*".to_owned(),
+                vec!["--callgrind-limits=ir=10%".to_owned()],
+            ),
+            (
+                "group::function::id".to_owned(),
+                vec!["--tools=massif".to_owned()],
+            ),
+        ]);
+        let raw_args = vec!["--profile=quick".to_owned(), "--tools=memcheck".to_owned()];
+        let args = CommandLineArgs::parse_from(&raw_args);
+        let benchmark_args = config_file.benchmark_args(&args, &raw_args).unwrap();
+
+        let module_path = ModulePath::new("file::group::function");
+        let actual = benchmark_args
+            .resolve(&module_path, Some(&"id".to_owned()))
+            .unwrap()
+            .unwrap();
+        assert_eq!(actual.profile, Some(MeasurementProfile::Fast));
+        assert_eq!(
+            actual.tools,
+            vec![
+                ValgrindTool::DHAT,
+                ValgrindTool::Massif,
+                ValgrindTool::Memcheck
+            ]
+        );
+        assert_eq!(
+            actual.callgrind_limits,
+            CommandLineArgs::parse_from(["--callgrind-limits=ir=10%"]).callgrind_limits
+        );
+
+        assert!(benchmark_args
+            .resolve(&ModulePath::new("file::other::function"), None)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_config_file_benchmark_args_when_profile_then_error() {
+        let mut config_file = config_file();
+        config_file.benchmarks =
+            IndexMap::from([("group::*".to_owned(), vec!["--profile=quick".to_owned()])]);
+        let args = CommandLineArgs::parse_from(Vec::<String>::new());
+
+        config_file.benchmark_args(&args, &[]).unwrap_err();
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_config_file_default_args_when_env_then_skipped() {
+        let config_file = ConfigFile {
+            args: vec![
+                "--nocapture=stdout".to_owned(),
+                "--tools".to_owned(),
+                "dhat".to_owned(),
+                "--nosummary".to_owned(),
+            ],
+            benchmarks: IndexMap::new(),
+            profiles: IndexMap::new(),
+        };
+
+        std::env::remove_var("IAI_CALLGRIND_NOCAPTURE");
+        std::env::remove_var("IAI_CALLGRIND_NOSUMMARY");
+        std::env::set_var("IAI_CALLGRIND_TOOLS", "massif");
+        let actual = config_file.default_args();
+        std::env::remove_var("IAI_CALLGRIND_TOOLS");
+
+        assert_eq!(
+            actual,
+            vec!["--nocapture=stdout".to_owned(), "--nosummary".to_owned()]
+        );
+    }
+
+    #[rstest]
+    #[case::none(None, Ok(None))]
+    #[case::fast(Some(MeasurementProfile::Fast), Ok(None))]
+    #[case::named(Some(MeasurementProfile::Named("memory".to_owned())), Ok(Some(&2)))]
+    #[case::unknown(Some(MeasurementProfile::Named("unknown".to_owned())), Err(()))]
+    fn test_find(
+        #[case] profile: Option<MeasurementProfile>,
+        #[case] expected: Result<Option<&usize>, ()>,
+    ) {
+        let profiles = [("quick".to_owned(), 1), ("memory".to_owned(), 2)];
+        let actual = find(&profiles, profile.as_ref()).map_err(|_| ());
+        assert_eq!(actual, expected);
+    }
+}
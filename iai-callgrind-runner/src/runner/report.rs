@@ -0,0 +1,663 @@
+//! The module containing the `--report=site` mode of the runner
+//!
+//! In this mode, the history stored with `--store=sqlite` is rendered into a static html site with
+//! an overview page (`index.html`) and a page with trend charts per benchmark. The site doesn't
+//! need any javascript or external resources, so it can be published as is, for example with
+//! GitHub Pages.
+//!
+//! In the `--report=targets` mode, the `summary.json` files of the benchmark runs on multiple
+//! targets with `--separate-targets` are compared side by side on a single html page
+//! (`targets.html`). The first target (in alphabetical order) is the reference for the changes.
+
+use std::collections::BTreeMap;
+use std::fmt::{Display, Write as _};
+use std::hash::Hash;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+use either_or_both::EitherOrBoth;
+use indexmap::IndexMap;
+
+use super::diff::load_summaries;
+use super::metrics::{Metric, MetricsSummary};
+use super::store::{SqliteStore, StoredRecord};
+use super::summary::{BenchmarkSummary, ToolMetricSummary};
+
+/// The height of a trend chart on a benchmark page
+const CHART_HEIGHT: f64 = 240.0;
+/// The padding around the plot area of a chart
+const CHART_PADDING: f64 = 40.0;
+/// The width of a trend chart on a benchmark page
+const CHART_WIDTH: f64 = 720.0;
+/// The height of a sparkline on the overview page
+const SPARKLINE_HEIGHT: f64 = 24.0;
+/// The width of a sparkline on the overview page
+const SPARKLINE_WIDTH: f64 = 120.0;
+/// The stylesheet shared by all pages
+const STYLE: &str = "\
+body { font-family: sans-serif; margin: 2em auto; max-width: 60em; color: #222; }
+h1 { font-size: 1.6em; }
+h2 { font-size: 1.2em; margin-top: 2em; }
+table { border-collapse: collapse; margin: 1em 0; }
+th, td { padding: 0.3em 0.8em; text-align: left; border-bottom: 1px solid #ddd; }
+td.num { text-align: right; font-family: monospace; }
+.increase { color: #b00; }
+.decrease { color: #070; }
+svg .line { fill: none; stroke: #1f77b4; stroke-width: 2; }
+svg .point { fill: #1f77b4; }
+svg .axis { stroke: #999; stroke-width: 1; }
+svg text { font-size: 11px; fill: #555; }
+";
+
+/// The kind of the report
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportKind {
+    /// A static html site with trend charts
+    Site,
+    /// A single html page comparing the benchmarks across the targets of `--separate-targets`
+    Targets,
+}
+
+/// The series of the values of a single metric of a benchmark ordered by the run
+type Series<'a> = Vec<&'a StoredRecord>;
+
+/// The metric of each target (in the order of the targets) by tool and metric kind
+type TargetMetrics = IndexMap<(String, String), Vec<Option<Metric>>>;
+
+/// A benchmark of the report with all its [`Series`] by tool and metric kind
+#[derive(Debug)]
+struct ReportBenchmark<'a> {
+    /// The id of the benchmark if any
+    bench_id: Option<&'a str>,
+    /// The description of the benchmark if any
+    description: Option<&'a str>,
+    /// The file name of the benchmark page
+    file_name: String,
+    /// The module path of the benchmark
+    module_path: &'a str,
+    /// The series of the metrics by tool and metric kind
+    series: IndexMap<(&'a str, &'a str), Series<'a>>,
+}
+
+/// The benchmarks of all targets of the cross-target report
+#[derive(Debug, Default)]
+struct TargetsReport {
+    /// The [`TargetMetrics`] of the benchmarks by module path and id
+    benchmarks: BTreeMap<(String, Option<String>), TargetMetrics>,
+    /// The names of the targets
+    targets: Vec<String>,
+}
+
+impl ReportKind {
+    /// Parse the `ReportKind` from the value of `--report[=KIND]`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the kind is unknown
+    pub fn from_arg(arg: &str) -> Result<Self> {
+        match arg.strip_prefix("--report") {
+            Some("" | "=site") => Ok(Self::Site),
+            Some("=targets") => Ok(Self::Targets),
+            Some(value) => Err(anyhow!(
+                "--report: Invalid report kind '{}'. Possible values are: site, targets",
+                value.trim_start_matches('=')
+            )),
+            None => Err(anyhow!("Invalid report argument: '{arg}'")),
+        }
+    }
+}
+
+impl<'a> ReportBenchmark<'a> {
+    /// Create a new `ReportBenchmark` without any [`Series`]
+    fn new(module_path: &'a str, bench_id: Option<&'a str>, description: Option<&'a str>) -> Self {
+        let name = bench_id.map_or_else(
+            || module_path.replace("::", "."),
+            |id| format!("{}.{id}", module_path.replace("::", ".")),
+        );
+        Self {
+            bench_id,
+            description,
+            file_name: format!("{}.html", sanitize_filename::sanitize(name)),
+            module_path,
+            series: IndexMap::new(),
+        }
+    }
+
+    /// Return the full name of this benchmark as shown in the report
+    fn name(&self) -> String {
+        self.bench_id.map_or_else(
+            || self.module_path.to_owned(),
+            |id| format!("{} {id}", self.module_path),
+        )
+    }
+
+    /// Render the html page of this benchmark
+    fn render(&self) -> String {
+        let name = escape_html(&self.name());
+        let mut body =
+            format!("<p><a href=\"index.html\">&larr; All benchmarks</a></p>\n<h1>{name}</h1>\n");
+        if let Some(description) = self.description {
+            writeln!(body, "<p>{}</p>", escape_html(description)).unwrap();
+        }
+
+        for ((tool, kind), series) in &self.series {
+            writeln!(
+                body,
+                "<h2>{}: {}</h2>\n{}",
+                escape_html(tool),
+                escape_html(kind),
+                svg_chart(series, CHART_WIDTH, CHART_HEIGHT, true)
+            )
+            .unwrap();
+
+            body.push_str(
+                "<table>\n<thead><tr><th>Time \
+                 (UTC)</th><th>Commit</th><th>Value</th><th>Change</th></tr></thead>\n<tbody>\n",
+            );
+            for (index, record) in series.iter().enumerate().rev() {
+                let change = index.checked_sub(1).map_or_else(
+                    || "<td></td>".to_owned(),
+                    |previous| change_cell(series[previous].value, record.value),
+                );
+                writeln!(
+                    body,
+                    "<tr><td>{}</td><td><code>{}</code></td><td class=\"num\">{}</td>{change}</tr>",
+                    escape_html(&record.time),
+                    escape_html(short_commit(record.git_commit.as_deref())),
+                    record.value,
+                )
+                .unwrap();
+            }
+            body.push_str("</tbody>\n</table>\n");
+        }
+
+        render_page(&name, &body)
+    }
+}
+
+impl TargetsReport {
+    /// Add the new metrics of the `summaries` of the `target`
+    ///
+    /// Benchmarks or metrics which are missing in a target are shown as not available.
+    fn add_target(&mut self, target: &str, summaries: &[BenchmarkSummary]) {
+        let index = self.targets.len();
+        self.targets.push(target.to_owned());
+
+        for summary in summaries {
+            let benchmark = self
+                .benchmarks
+                .entry((summary.module_path.clone(), summary.id.clone()))
+                .or_default();
+            for profile in summary.profiles.iter() {
+                let tool = profile.tool.to_string();
+                let new_metrics = match &profile.summaries.total.summary {
+                    ToolMetricSummary::None => vec![],
+                    ToolMetricSummary::ErrorTool(metrics) => new_metrics(metrics),
+                    ToolMetricSummary::Dhat(metrics) => new_metrics(metrics),
+                    ToolMetricSummary::Callgrind(metrics) => new_metrics(metrics),
+                    ToolMetricSummary::Cachegrind(metrics) => new_metrics(metrics),
+                };
+                for (kind, metric) in new_metrics {
+                    let values = benchmark.entry((tool.clone(), kind)).or_default();
+                    values.resize(index + 1, None);
+                    values[index] = Some(metric);
+                }
+            }
+        }
+    }
+
+    /// Render the html page of the cross-target report
+    fn render(&self) -> String {
+        let reference = self
+            .targets
+            .first()
+            .map_or_else(String::new, |target| escape_html(target));
+        let mut body = format!(
+            "<h1>Iai-Callgrind Cross-Target Report</h1>\n<p>{} benchmarks on {} targets. The \
+             changes are relative to <code>{}</code>.</p>\n<table>\n<thead><tr><th>Benchmark</\
+             th><th>Tool</th><th>Metric</th>",
+            self.benchmarks.len(),
+            self.targets.len(),
+            reference
+        );
+        for (index, target) in self.targets.iter().enumerate() {
+            write!(body, "<th>{}</th>", escape_html(target)).unwrap();
+            if index > 0 {
+                body.push_str("<th>Change</th>");
+            }
+        }
+        body.push_str("</tr></thead>\n<tbody>\n");
+
+        for ((module_path, bench_id), metrics) in &self.benchmarks {
+            let name = bench_id
+                .as_ref()
+                .map_or_else(|| module_path.clone(), |id| format!("{module_path} {id}"));
+            for ((tool, kind), values) in metrics {
+                write!(
+                    body,
+                    "<tr><td>{}</td><td>{}</td><td>{}</td>",
+                    escape_html(&name),
+                    escape_html(tool),
+                    escape_html(kind)
+                )
+                .unwrap();
+                let reference = values.first().copied().flatten();
+                for index in 0..self.targets.len() {
+                    let value = values.get(index).copied().flatten();
+                    match value {
+                        Some(value) => write!(body, "<td class=\"num\">{value}</td>").unwrap(),
+                        None => body.push_str("<td class=\"num\">N/A</td>"),
+                    }
+                    if index > 0 {
+                        match (reference, value) {
+                            (Some(reference), Some(value)) => {
+                                body.push_str(&change_cell(reference.into(), value.into()));
+                            }
+                            _ => body.push_str("<td></td>"),
+                        }
+                    }
+                }
+                body.push_str("</tr>\n");
+            }
+        }
+        body.push_str("</tbody>\n</table>\n");
+
+        render_page("Iai-Callgrind Cross-Target Report", &body)
+    }
+}
+
+/// Return the html table cell with the percentage change from `old` to `new`
+fn change_cell(old: f64, new: f64) -> String {
+    if (new - old).abs() < f64::EPSILON {
+        "<td class=\"num\">No change</td>".to_owned()
+    } else if old.abs() < f64::EPSILON {
+        "<td class=\"num increase\">+inf%</td>".to_owned()
+    } else {
+        let pct = (new - old) / old * 100.0;
+        let class = if pct > 0.0 { "increase" } else { "decrease" };
+        format!("<td class=\"num {class}\">{pct:+.2}%</td>")
+    }
+}
+
+/// Return the default directory of the report which is the `report` directory next to the
+/// `database`
+pub fn default_dir(database: &Path) -> PathBuf {
+    database
+        .parent()
+        .unwrap_or_else(|| Path::new("."))
+        .join("report")
+}
+
+/// Escape the special html characters in `value`
+pub fn escape_html(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for char in value.chars() {
+        match char {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            char => escaped.push(char),
+        }
+    }
+    escaped
+}
+
+/// Generate the static html site from the `records` in the directory `dir`
+///
+/// The `index.html` page lists all benchmarks with the latest value of their first metric and a
+/// sparkline. Each benchmark has its own page with a trend chart and a table of all runs for each
+/// metric. The `dir` is created if it doesn't exist and existing pages are overwritten.
+///
+/// # Errors
+///
+/// Returns an error if the directory or one of the pages can't be written
+pub fn generate_site(records: &[StoredRecord], dir: &Path) -> Result<()> {
+    let mut benchmarks: BTreeMap<(&str, Option<&str>), ReportBenchmark> = BTreeMap::new();
+    for record in records {
+        let module_path = record.module_path.as_str();
+        let bench_id = record.bench_id.as_deref();
+        let description = record.description.as_deref();
+        benchmarks
+            .entry((module_path, bench_id))
+            .or_insert_with(|| ReportBenchmark::new(module_path, bench_id, description))
+            .series
+            .entry((record.tool.as_str(), record.kind.as_str()))
+            .or_default()
+            .push(record);
+    }
+
+    std::fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create the report directory '{}'", dir.display()))?;
+
+    let mut runs = records.iter().map(|r| r.run_id).collect::<Vec<_>>();
+    runs.sort_unstable();
+    runs.dedup();
+
+    let mut body = format!(
+        "<h1>Iai-Callgrind Report</h1>\n<p>{} benchmarks in {} \
+         runs</p>\n<table>\n<thead><tr><th>Benchmark</th><th>Tool</th><th>Metric</th><th>Latest</\
+         th><th>Change</th><th>Trend</th></tr></thead>\n<tbody>\n",
+        benchmarks.len(),
+        runs.len()
+    );
+    for benchmark in benchmarks.values() {
+        write_page(&dir.join(&benchmark.file_name), &benchmark.render())?;
+
+        if let Some(((tool, kind), series)) = benchmark.series.first() {
+            let latest = series.last().expect("A series should not be empty");
+            let change = series.len().checked_sub(2).map_or_else(
+                || "<td></td>".to_owned(),
+                |previous| change_cell(series[previous].value, latest.value),
+            );
+            let description = benchmark
+                .description
+                .map_or_else(String::new, |description| {
+                    format!("<br><small>{}</small>", escape_html(description))
+                });
+            writeln!(
+                body,
+                "<tr><td><a href=\"{}\">{}</a>{description}</td><td>{}</td><td>{}</td><td \
+                 class=\"num\">{}</td>{change}<td>{}</td></tr>",
+                escape_html(&benchmark.file_name),
+                escape_html(&benchmark.name()),
+                escape_html(tool),
+                escape_html(kind),
+                latest.value,
+                svg_chart(series, SPARKLINE_WIDTH, SPARKLINE_HEIGHT, false)
+            )
+            .unwrap();
+        }
+    }
+    body.push_str("</tbody>\n</table>\n");
+
+    write_page(
+        &dir.join("index.html"),
+        &render_page("Iai-Callgrind Report", &body),
+    )
+}
+
+/// Generate the cross-target report from the `summary.json` files of each target in `targets_dir`
+/// into the file `targets.html` in the directory `dir`
+///
+/// The `targets_dir` is the iai home directory of the `--separate-targets` layout (per default
+/// `target/iai`). Each subdirectory containing `summary.json` files is a target. The `dir` is
+/// created if it doesn't exist and returned is the path to the report.
+///
+/// # Errors
+///
+/// Returns an error if less than two targets with summaries are found, a summary can't be read or
+/// the report can't be written
+pub fn generate_targets(targets_dir: &Path, dir: &Path) -> Result<PathBuf> {
+    let mut target_dirs = std::fs::read_dir(targets_dir)
+        .with_context(|| {
+            format!(
+                "Failed to read the targets directory '{}'",
+                targets_dir.display()
+            )
+        })?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_dir())
+        .collect::<Vec<_>>();
+    target_dirs.sort();
+
+    let mut report = TargetsReport::default();
+    for target_dir in target_dirs {
+        let summaries = load_summaries(&target_dir)?;
+        if summaries.is_empty() {
+            continue;
+        }
+        let target = target_dir
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .expect("A directory entry should have a file name");
+        report.add_target(&target, &summaries);
+    }
+
+    if report.targets.len() < 2 {
+        return Err(anyhow!(
+            "--report=targets: Found {} target(s) with summary.json files in '{}' but need at \
+             least two. Run the benchmarks with --separate-targets and --save-summary on each \
+             target",
+            report.targets.len(),
+            targets_dir.display()
+        ));
+    }
+
+    std::fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create the report directory '{}'", dir.display()))?;
+    let path = dir.join("targets.html");
+    write_page(&path, &report.render())?;
+    Ok(path)
+}
+
+/// Return the new metrics of the `metrics` summary by the name of their metric kind
+fn new_metrics<K>(metrics: &MetricsSummary<K>) -> Vec<(String, Metric)>
+where
+    K: Hash + Eq + Display,
+{
+    metrics
+        .0
+        .iter()
+        .filter_map(|(kind, diff)| match diff.metrics {
+            EitherOrBoth::Left(metric) | EitherOrBoth::Both(metric, _) => {
+                Some((kind.to_string(), metric))
+            }
+            EitherOrBoth::Right(_) => None,
+        })
+        .collect()
+}
+
+/// Render a complete html page with the (already escaped) `title` and `body`
+fn render_page(title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<meta \
+         name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n<title>{title}</\
+         title>\n<style>\n{STYLE}</style>\n</head>\n<body>\n{body}</body>\n</html>\n"
+    )
+}
+
+/// Run the report mode
+///
+/// This is the entry point of `iai-callgrind-runner --report=site DATABASE [OUTPUT_DIR]` and
+/// `iai-callgrind-runner --report=targets TARGETS_DIR [OUTPUT_DIR]`. If not given, the
+/// `OUTPUT_DIR` is the `report` directory next to the `DATABASE` or in the `TARGETS_DIR`.
+///
+/// # Errors
+///
+/// Returns an error if the arguments are invalid, the database or summaries can't be read or the
+/// report can't be written
+pub fn run() -> Result<()> {
+    let mut args = std::env::args_os().skip(1);
+    let kind = args
+        .next()
+        .and_then(|arg| arg.into_string().ok())
+        .ok_or_else(|| anyhow!("Missing report argument"))
+        .and_then(|arg| ReportKind::from_arg(&arg))?;
+    let path = args.next().map(PathBuf::from).ok_or_else(|| match kind {
+        ReportKind::Site => anyhow!("--report: Missing path to the SQLite database"),
+        ReportKind::Targets => anyhow!("--report: Missing path to the directory of the targets"),
+    })?;
+
+    match kind {
+        ReportKind::Site => {
+            let dir = args
+                .next()
+                .map_or_else(|| default_dir(&path), PathBuf::from);
+            let records = SqliteStore::new(&path)?.records()?;
+            generate_site(&records, &dir)?;
+            println!("Report written to '{}'", dir.join("index.html").display());
+        }
+        ReportKind::Targets => {
+            let dir = args
+                .next()
+                .map_or_else(|| path.join("report"), PathBuf::from);
+            let report = generate_targets(&path, &dir)?;
+            println!("Report written to '{}'", report.display());
+        }
+    }
+
+    Ok(())
+}
+
+/// Return the abbreviated git commit hash or an empty string
+fn short_commit(git_commit: Option<&str>) -> &str {
+    git_commit.map_or("", |commit| commit.get(..12).unwrap_or(commit))
+}
+
+/// Render the `series` as svg line chart of the size `width` x `height`
+///
+/// If `with_axis` is true, the chart has a y-axis with the minimum and maximum values and each
+/// point has a tooltip with the time, commit and value of the run. Otherwise, the chart is rendered
+/// as a sparkline.
+fn svg_chart(series: &[&StoredRecord], width: f64, height: f64, with_axis: bool) -> String {
+    let padding = if with_axis { CHART_PADDING } else { 2.0 };
+    let min = series.iter().map(|r| r.value).fold(f64::INFINITY, f64::min);
+    let max = series
+        .iter()
+        .map(|r| r.value)
+        .fold(f64::NEG_INFINITY, f64::max);
+    // Avoid a division by zero if all values are equal by centering the line
+    let (low, range) = if max > min {
+        (min, max - min)
+    } else {
+        (min - 1.0, 2.0)
+    };
+
+    let plot_width = 2.0f64.mul_add(-padding, width);
+    let plot_height = 2.0f64.mul_add(-padding, height);
+    #[allow(clippy::cast_precision_loss)]
+    let step = if series.len() > 1 {
+        plot_width / (series.len() - 1) as f64
+    } else {
+        0.0
+    };
+    #[allow(clippy::cast_precision_loss)]
+    let points = series
+        .iter()
+        .enumerate()
+        .map(|(index, record)| {
+            let x = if series.len() > 1 {
+                (index as f64).mul_add(step, padding)
+            } else {
+                width / 2.0
+            };
+            let y = ((record.value - low) / range).mul_add(-plot_height, height - padding);
+            (x, y, record)
+        })
+        .collect::<Vec<_>>();
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" \
+         viewBox=\"0 0 {width} {height}\">"
+    );
+    if with_axis {
+        write!(
+            svg,
+            "<line class=\"axis\" x1=\"{padding}\" y1=\"{padding}\" x2=\"{padding}\" \
+             y2=\"{0}\"/><line class=\"axis\" x1=\"{padding}\" y1=\"{0}\" x2=\"{1}\" \
+             y2=\"{0}\"/><text x=\"2\" y=\"{padding}\">{max}</text><text x=\"2\" \
+             y=\"{0}\">{min}</text>",
+            height - padding,
+            width - padding,
+        )
+        .unwrap();
+    }
+    let line = points
+        .iter()
+        .map(|(x, y, _)| format!("{x:.1},{y:.1}"))
+        .collect::<Vec<_>>()
+        .join(" ");
+    write!(svg, "<polyline class=\"line\" points=\"{line}\"/>").unwrap();
+    if with_axis {
+        for (x, y, record) in &points {
+            write!(
+                svg,
+                "<circle class=\"point\" cx=\"{x:.1}\" cy=\"{y:.1}\" r=\"3\"><title>{} {}: \
+                 {}</title></circle>",
+                escape_html(&record.time),
+                escape_html(short_commit(record.git_commit.as_deref())),
+                record.value
+            )
+            .unwrap();
+        }
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+/// Write the html `page` to `path`
+fn write_page(path: &Path, page: &str) -> Result<()> {
+    std::fs::write(path, page)
+        .with_context(|| format!("Failed to write report page '{}'", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case::default("--report", ReportKind::Site)]
+    #[case::site("--report=site", ReportKind::Site)]
+    #[case::targets("--report=targets", ReportKind::Targets)]
+    fn test_report_kind_from_arg(#[case] arg: &str, #[case] expected: ReportKind) {
+        assert_eq!(ReportKind::from_arg(arg).unwrap(), expected);
+    }
+
+    #[rstest]
+    #[case::unknown_kind("--report=html")]
+    #[case::not_report("--query")]
+    fn test_report_kind_from_arg_when_invalid_then_error(#[case] arg: &str) {
+        ReportKind::from_arg(arg).unwrap_err();
+    }
+
+    #[rstest]
+    #[case::no_change(100.0, 100.0, "<td class=\"num\">No change</td>")]
+    #[case::increase(100.0, 110.0, "<td class=\"num increase\">+10.00%</td>")]
+    #[case::decrease(100.0, 95.0, "<td class=\"num decrease\">-5.00%</td>")]
+    #[case::from_zero(0.0, 1.0, "<td class=\"num increase\">+inf%</td>")]
+    fn test_change_cell(#[case] old: f64, #[case] new: f64, #[case] expected: &str) {
+        assert_eq!(change_cell(old, new), expected);
+    }
+
+    #[test]
+    fn test_render_page() {
+        assert_eq!(
+            render_page("Title", "<h1>Title</h1>\n"),
+            format!(
+                "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<meta \
+                 name=\"viewport\" content=\"width=device-width, \
+                 initial-scale=1\">\n<title>Title</title>\n<style>\n{STYLE}</style>\n</\
+                 head>\n<body>\n<h1>Title</h1>\n</body>\n</html>\n"
+            )
+        );
+    }
+
+    #[test]
+    fn test_targets_report_render_page() {
+        let report = TargetsReport {
+            benchmarks: BTreeMap::new(),
+            targets: vec!["x86_64".to_owned(), "aarch64".to_owned()],
+        };
+
+        let page = report.render();
+        assert!(page.starts_with(
+            "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n"
+        ));
+        assert!(page
+            .contains("</style>\n</head>\n<body>\n<h1>Iai-Callgrind Cross-Target Report</h1>\n"));
+        assert!(page.ends_with("</tbody>\n</table>\n</body>\n</html>\n"));
+        assert!(!page.contains('\\'));
+    }
+
+    #[test]
+    fn test_escape_html() {
+        assert_eq!(
+            escape_html("<a href=\"x\">'&'</a>"),
+            "&lt;a href=&quot;x&quot;&gt;&#39;&amp;&#39;&lt;/a&gt;"
+        );
+    }
+}
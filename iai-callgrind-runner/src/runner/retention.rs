@@ -0,0 +1,285 @@
+//! The module containing the retention policy of the saved baselines
+//!
+//! With `--baseline-keep`, the saved baselines (`*.base@<name>` files) of each benchmark are pruned
+//! after a successful benchmark run. This keeps the iai home directory from growing unbounded when
+//! baselines are saved for example per pull request. The baselines of the current benchmark run
+//! (`--save-baseline`, `--baseline` and `--load-baseline`) are never pruned.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime};
+
+use anyhow::{Context, Result};
+use log::debug;
+
+/// The marker of a baseline in the file name of an output file
+const BASELINE_MARKER: &str = "base@";
+
+/// The marker of the cache file of a parsed baseline (see [`super::tool::cache`])
+const CACHE_MARKER: &str = ".parsed.base@";
+
+/// The retention policy of the saved baselines
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BaselineRetention {
+    /// Keep the given amount of the most recently saved baselines of each benchmark
+    Count(usize),
+    /// Keep the baselines which were saved within the given duration
+    Age(Duration),
+}
+
+impl BaselineRetention {
+    /// Select the names of the `baselines` which have to be removed according to this retention
+    ///
+    /// The `baselines` are the names of the baselines of a single benchmark together with the
+    /// time they were last saved. The `active` baselines are excluded before applying the
+    /// retention, so they are never selected and don't count towards the number of kept baselines.
+    pub fn select(
+        &self,
+        baselines: Vec<(String, SystemTime)>,
+        active: &[&str],
+        now: SystemTime,
+    ) -> Vec<String> {
+        let mut baselines = baselines
+            .into_iter()
+            .filter(|(name, _)| !active.contains(&name.as_str()))
+            .collect::<Vec<_>>();
+        match self {
+            Self::Count(count) => {
+                baselines.sort_by(|(a_name, a), (b_name, b)| b.cmp(a).then(a_name.cmp(b_name)));
+                baselines
+                    .into_iter()
+                    .skip(*count)
+                    .map(|(name, _)| name)
+                    .collect()
+            }
+            Self::Age(age) => {
+                let mut names = baselines
+                    .into_iter()
+                    .filter(|(_, modified)| {
+                        now.duration_since(*modified)
+                            .is_ok_and(|elapsed| elapsed > *age)
+                    })
+                    .map(|(name, _)| name)
+                    .collect::<Vec<_>>();
+                names.sort();
+                names
+            }
+        }
+    }
+
+    /// Return the time before which the runs of the result store are pruned if any
+    ///
+    /// Only a time-based retention prunes the result store, since the runs of the store aren't
+    /// saved per benchmark.
+    pub fn cutoff(&self, now: SystemTime) -> Option<SystemTime> {
+        match self {
+            Self::Count(_) => None,
+            Self::Age(age) => now.checked_sub(*age),
+        }
+    }
+}
+
+impl FromStr for BaselineRetention {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim();
+        let (number, unit) = value
+            .find(|c: char| !c.is_ascii_digit())
+            .map_or((value, ""), |index| value.split_at(index));
+        let number = number.parse::<u64>().map_err(|_| {
+            format!(
+                "Invalid baseline retention '{value}': Expected a number of baselines like '10' or \
+                 an age like '30d'"
+            )
+        })?;
+
+        let seconds = match unit.trim() {
+            "" => {
+                return usize::try_from(number)
+                    .map(Self::Count)
+                    .map_err(|error| format!("Invalid baseline retention '{value}': {error}"));
+            }
+            "s" => 1,
+            "m" => 60,
+            "h" => 60 * 60,
+            "d" => 24 * 60 * 60,
+            "w" => 7 * 24 * 60 * 60,
+            unit => {
+                return Err(format!(
+                    "Invalid unit '{unit}' of the baseline retention '{value}': Valid units are \
+                     's', 'm', 'h', 'd' and 'w'"
+                ))
+            }
+        };
+
+        number
+            .checked_mul(seconds)
+            .map(|seconds| Self::Age(Duration::from_secs(seconds)))
+            .ok_or_else(|| format!("Invalid baseline retention '{value}': The age is too large"))
+    }
+}
+
+/// Return the name of the baseline in the `file_name` of an output file if present
+///
+/// For example, the baseline name of `callgrind.bench.out.base@main.1` is `main`.
+pub fn baseline_name(file_name: &str) -> Option<&str> {
+    let (_, rest) = file_name.split_once(BASELINE_MARKER)?;
+    let end = rest
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .unwrap_or(rest.len());
+    (end > 0).then_some(&rest[..end])
+}
+
+/// Prune the saved baselines of all benchmarks in `dir` according to the [`BaselineRetention`]
+///
+/// Each directory is treated as the output directory of a single benchmark. The time a baseline
+/// was saved is the latest modification time of its files excluding the cache of the parsed
+/// baseline. The `active` baselines are never pruned (see [`BaselineRetention::select`]). Returns
+/// the number of removed files.
+///
+/// # Errors
+///
+/// Returns an error if a directory can't be read or a file can't be removed
+pub fn prune(
+    dir: &Path,
+    retention: &BaselineRetention,
+    active: &[&str],
+    now: SystemTime,
+) -> Result<usize> {
+    if !dir.is_dir() {
+        return Ok(0);
+    }
+
+    let mut removed = 0;
+    let mut files: HashMap<String, Vec<_>> = HashMap::new();
+    let mut baselines: HashMap<String, SystemTime> = HashMap::new();
+    for entry in std::fs::read_dir(dir)
+        .with_context(|| format!("Failed to read the directory '{}'", dir.display()))?
+    {
+        let entry = entry?;
+        let path = entry.path();
+        let metadata = entry.metadata()?;
+        if metadata.is_dir() {
+            removed += prune(&path, retention, active, now)?;
+            continue;
+        }
+
+        let Some(file_name) = path.file_name().and_then(|f| f.to_str()) else {
+            continue;
+        };
+        let Some(name) = baseline_name(file_name) else {
+            continue;
+        };
+        // The cache of a parsed baseline is written when comparing against the baseline and
+        // doesn't count as saving the baseline
+        let modified = if file_name.contains(CACHE_MARKER) {
+            SystemTime::UNIX_EPOCH
+        } else {
+            metadata.modified()?
+        };
+        baselines
+            .entry(name.to_owned())
+            .and_modify(|latest| *latest = (*latest).max(modified))
+            .or_insert(modified);
+        files.entry(name.to_owned()).or_default().push(path);
+    }
+
+    for name in retention.select(baselines.into_iter().collect(), active, now) {
+        debug!("Pruning baseline '{name}' in '{}'", dir.display());
+        for path in files.remove(&name).unwrap_or_default() {
+            std::fs::remove_file(&path)
+                .with_context(|| format!("Failed to remove '{}'", path.display()))?;
+            removed += 1;
+        }
+    }
+
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case::count("10", BaselineRetention::Count(10))]
+    #[case::zero("0", BaselineRetention::Count(0))]
+    #[case::seconds("30s", BaselineRetention::Age(Duration::from_secs(30)))]
+    #[case::minutes("2m", BaselineRetention::Age(Duration::from_secs(120)))]
+    #[case::hours("12h", BaselineRetention::Age(Duration::from_secs(12 * 3600)))]
+    #[case::days("30d", BaselineRetention::Age(Duration::from_secs(30 * 86400)))]
+    #[case::weeks("1w", BaselineRetention::Age(Duration::from_secs(7 * 86400)))]
+    #[case::whitespace(" 7 d ", BaselineRetention::Age(Duration::from_secs(7 * 86400)))]
+    fn test_baseline_retention_from_str(#[case] value: &str, #[case] expected: BaselineRetention) {
+        assert_eq!(value.parse::<BaselineRetention>(), Ok(expected));
+    }
+
+    #[rstest]
+    #[case::empty("", "Invalid baseline retention '': Expected a number")]
+    #[case::negative("-1", "Invalid baseline retention '-1': Expected a number")]
+    #[case::only_unit("d", "Invalid baseline retention 'd': Expected a number")]
+    #[case::invalid_unit("10y", "Invalid unit 'y' of the baseline retention '10y'")]
+    #[case::too_large(
+        "18446744073709551615w",
+        "Invalid baseline retention '18446744073709551615w': The age is too large"
+    )]
+    fn test_baseline_retention_from_str_when_invalid(#[case] value: &str, #[case] expected: &str) {
+        let error = value.parse::<BaselineRetention>().unwrap_err();
+        assert!(error.starts_with(expected), "Unexpected error: {error}");
+    }
+
+    #[rstest]
+    #[case::out("callgrind.bench.out.base@main", Some("main"))]
+    #[case::with_modifiers("callgrind.bench.out.base@pr_12.#1234.t1", Some("pr_12"))]
+    #[case::summary("summary.base@default.json", Some("default"))]
+    #[case::cache("callgrind.bench.parsed.base@main.json", Some("main"))]
+    #[case::old("callgrind.bench.out.old", None)]
+    #[case::new("callgrind.bench.out", None)]
+    #[case::empty_name("callgrind.bench.out.base@", None)]
+    fn test_baseline_name(#[case] file_name: &str, #[case] expected: Option<&str>) {
+        assert_eq!(baseline_name(file_name), expected);
+    }
+
+    #[rstest]
+    #[case::keep_all(BaselineRetention::Count(3), vec![])]
+    #[case::keep_two(BaselineRetention::Count(2), vec!["oldest"])]
+    #[case::keep_none(BaselineRetention::Count(0), vec!["new", "old", "oldest"])]
+    #[case::age(BaselineRetention::Age(Duration::from_secs(150)), vec!["oldest"])]
+    #[case::age_all(BaselineRetention::Age(Duration::from_secs(500)), vec![])]
+    fn test_baseline_retention_select(
+        #[case] retention: BaselineRetention,
+        #[case] expected: Vec<&str>,
+    ) {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1000);
+        let baselines = vec![
+            ("old".to_owned(), now - Duration::from_secs(100)),
+            ("oldest".to_owned(), now - Duration::from_secs(200)),
+            ("new".to_owned(), now),
+        ];
+        assert_eq!(retention.select(baselines, &[], now), expected);
+    }
+
+    #[rstest]
+    #[case::count_zero(BaselineRetention::Count(0), vec!["old"])]
+    #[case::count_one(BaselineRetention::Count(1), vec![])]
+    #[case::age_zero(BaselineRetention::Age(Duration::ZERO), vec!["old"])]
+    fn test_baseline_retention_select_when_active(
+        #[case] retention: BaselineRetention,
+        #[case] expected: Vec<&str>,
+    ) {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1000);
+        let baselines = vec![
+            ("old".to_owned(), now - Duration::from_secs(100)),
+            ("loaded".to_owned(), now - Duration::from_secs(200)),
+            ("saved".to_owned(), now - Duration::from_secs(1)),
+        ];
+        assert_eq!(
+            retention.select(baselines, &["saved", "loaded"], now),
+            expected
+        );
+    }
+}
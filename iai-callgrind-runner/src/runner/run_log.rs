@@ -0,0 +1,337 @@
+//! The module containing the [`RunLog`], the `run.log` file of a single benchmark
+//!
+//! The log messages of the runner and the output of all benchmarks are interleaved in the terminal
+//! output, which makes it hard to debug a single failing benchmark. While a [`RunLog`] is active,
+//! all log messages of the runner up to the [`log::Level::Debug`], regardless of the log level
+//! configured with `IAI_CALLGRIND_LOG`, and the captured output of the benchmark are additionally
+//! written into the `run.log` file in the output directory of the benchmark. The maximum log level
+//! is only raised to [`log::Level::Debug`] while a `run.log` file is active, so the debug messages
+//! don't cost anything outside of a benchmark run.
+//!
+//! The second part of a benchmark, the parsing and terminal output, may run on a worker thread
+//! while the next benchmark already writes into its own [`RunLog`]. The worker thread enters the
+//! [`Context`] of its benchmark, so its log messages still end up in the right `run.log` file.
+
+use std::cell::RefCell;
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock, PoisonError};
+
+use anyhow::{Context as _, Result};
+use log::{Level, LevelFilter, Log, Metadata, Record, SetLoggerError};
+
+/// The file of a [`RunLog`] which is shared with the [`Context`]s of the same benchmark
+type SharedFile = Arc<Mutex<File>>;
+
+/// The file of the currently active [`RunLog`]
+static RUN_LOG: Mutex<Option<SharedFile>> = Mutex::new(None);
+
+/// The number of active [`RunLog`]s and entered [`Context`]s which write into a `run.log` file
+static NUM_ACTIVE: Mutex<usize> = Mutex::new(0);
+
+/// The maximum log level of the `inner` logger of the [`Logger`] if it was initialized
+static INNER_MAX_LEVEL: OnceLock<LevelFilter> = OnceLock::new();
+
+thread_local! {
+    /// The entered [`Context`] of this thread which takes precedence over the active [`RunLog`]
+    static CONTEXT: RefCell<Option<Context>> = const { RefCell::new(None) };
+}
+
+/// The file name of the run log in the output directory of a benchmark
+pub const FILE_NAME: &str = "run.log";
+
+/// The `run.log` file of a benchmark which can be entered on any thread
+///
+/// A detached `Context` (the [`Default`]) doesn't write into any `run.log` file.
+#[derive(Debug, Clone, Default)]
+pub struct Context {
+    file: Option<SharedFile>,
+}
+
+/// The guard of an entered [`Context`]
+///
+/// The previous [`Context`] of the thread is restored when the guard is dropped.
+#[derive(Debug)]
+pub struct ContextGuard {
+    has_file: bool,
+    previous: Option<Context>,
+}
+
+/// The [`Log`] implementation of the runner
+///
+/// This logger writes to the active [`RunLog`] and forwards the log messages to the `inner` logger
+/// which prints the log messages to the terminal as configured with `IAI_CALLGRIND_LOG`.
+pub struct Logger {
+    inner: env_logger::Logger,
+}
+
+/// The guard of the `run.log` file of a single benchmark
+///
+/// The log messages are written to the `run.log` file until the `RunLog` is dropped.
+#[derive(Debug)]
+pub struct RunLog {
+    file: SharedFile,
+    path: PathBuf,
+}
+
+impl Context {
+    /// Enter this `Context` on the current thread until the returned [`ContextGuard`] is dropped
+    pub fn enter(&self) -> ContextGuard {
+        let has_file = self.file.is_some();
+        if has_file {
+            activate();
+        }
+
+        let previous = CONTEXT.with(|context| context.replace(Some(self.clone())));
+        ContextGuard { has_file, previous }
+    }
+}
+
+impl Drop for ContextGuard {
+    fn drop(&mut self) {
+        // Accessing the thread local fails only if the thread is already exiting
+        let _ = CONTEXT.try_with(|context| *context.borrow_mut() = self.previous.take());
+        if self.has_file {
+            deactivate();
+        }
+    }
+}
+
+impl Logger {
+    /// Initialize the `Logger` with the `inner` logger as global logger
+    ///
+    /// The maximum log level is the level of the `inner` logger. Only while a [`RunLog`] or a
+    /// [`Context`] with a `run.log` file is active, it is raised to at least [`LevelFilter::Debug`]
+    /// so that the debug messages reach the `run.log` file. The `inner` logger still filters the
+    /// messages as usual.
+    pub fn init(inner: env_logger::Logger) -> Result<(), SetLoggerError> {
+        let inner_max_level = inner.filter();
+        log::set_logger(Box::leak(Box::new(Self { inner })))?;
+
+        let num_active = lock(&NUM_ACTIVE);
+        let _ = INNER_MAX_LEVEL.set(inner_max_level);
+        update_max_level(*num_active > 0);
+        Ok(())
+    }
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        self.inner.enabled(metadata)
+    }
+
+    fn log(&self, record: &Record<'_>) {
+        if record.level() <= Level::Debug && record.target().starts_with(env!("CARGO_CRATE_NAME")) {
+            with_file(|file| {
+                let _ = writeln!(
+                    file,
+                    "{}: {:<5}: {}",
+                    record
+                        .module_path()
+                        .unwrap_or_else(|| record.module_path_static().unwrap_or("???")),
+                    match record.level() {
+                        Level::Error => "Error",
+                        Level::Warn => "Warn",
+                        Level::Info => "Info",
+                        Level::Debug => "Debug",
+                        Level::Trace => "Trace",
+                    },
+                    record.args()
+                );
+            });
+        }
+
+        self.inner.log(record);
+    }
+
+    fn flush(&self) {
+        self.inner.flush();
+    }
+}
+
+impl RunLog {
+    /// Start writing to a new `run.log` file in the directory `dir`
+    ///
+    /// The directory is created if it doesn't exist and an existing `run.log` is truncated.
+    pub fn start(dir: &Path) -> Result<Self> {
+        std::fs::create_dir_all(dir).with_context(|| {
+            format!("Failed to create benchmark directory: '{}'", dir.display())
+        })?;
+
+        let path = dir.join(FILE_NAME);
+        let file = File::create(&path)
+            .with_context(|| format!("Failed to create run log: '{}'", path.display()))?;
+        let file = Arc::new(Mutex::new(file));
+        *lock(&RUN_LOG) = Some(Arc::clone(&file));
+        activate();
+
+        Ok(Self { file, path })
+    }
+
+    /// Return the [`Context`] of this `RunLog` to continue writing into it on another thread
+    pub fn context(&self) -> Context {
+        Context {
+            file: Some(Arc::clone(&self.file)),
+        }
+    }
+
+    /// Return the path to the `run.log` file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for RunLog {
+    fn drop(&mut self) {
+        // The `RunLog` of the next benchmark may already be active
+        let mut active = lock(&RUN_LOG);
+        if active
+            .as_ref()
+            .is_some_and(|file| Arc::ptr_eq(file, &self.file))
+        {
+            *active = None;
+        }
+        drop(active);
+
+        deactivate();
+    }
+}
+
+/// Count an active `run.log` file and raise the maximum log level if it is the first one
+fn activate() {
+    let mut num_active = lock(&NUM_ACTIVE);
+    *num_active += 1;
+    if *num_active == 1 {
+        update_max_level(true);
+    }
+}
+
+/// Stop counting an active `run.log` file and restore the maximum log level if it was the last one
+fn deactivate() {
+    let mut num_active = lock(&NUM_ACTIVE);
+    *num_active = num_active.saturating_sub(1);
+    if *num_active == 0 {
+        update_max_level(false);
+    }
+}
+
+/// Lock the `mutex` of a `run.log` file
+///
+/// A poisoned lock is not an issue here, since the file is only written to.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Return the maximum log level with the `inner` maximum log level of the [`Logger`]
+///
+/// If `is_active` is true, the debug messages are needed for a `run.log` file.
+fn max_level(inner: LevelFilter, is_active: bool) -> LevelFilter {
+    if is_active {
+        inner.max(LevelFilter::Debug)
+    } else {
+        inner
+    }
+}
+
+/// Set the maximum log level if the [`Logger`] is initialized
+///
+/// The maximum log level of a logger of an application which embeds the runner is not changed.
+fn update_max_level(is_active: bool) {
+    if let Some(inner) = INNER_MAX_LEVEL.get() {
+        log::set_max_level(max_level(*inner, is_active));
+    }
+}
+
+/// Call `f` with the file of the entered [`Context`] or else of the active [`RunLog`]
+fn with_file<F>(f: F)
+where
+    F: FnOnce(&mut File),
+{
+    let file = CONTEXT
+        .try_with(|context| {
+            context
+                .borrow()
+                .as_ref()
+                .map(|context| context.file.clone())
+        })
+        .ok()
+        .flatten()
+        .unwrap_or_else(|| lock(&RUN_LOG).clone());
+    if let Some(file) = file {
+        f(&mut lock(&file));
+    }
+}
+
+/// Write the `label` followed by the captured output `bytes` to the `run.log` file of the entered
+/// [`Context`] or else of the active [`RunLog`]
+///
+/// Nothing is written if the `bytes` are empty.
+pub fn write(label: &str, bytes: &[u8]) {
+    if bytes.is_empty() {
+        return;
+    }
+
+    with_file(|file| {
+        let _ = writeln!(file, "{label}").and_then(|()| file.write_all(bytes));
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case::off(LevelFilter::Off, false, LevelFilter::Off)]
+    #[case::off_when_active(LevelFilter::Off, true, LevelFilter::Debug)]
+    #[case::warn(LevelFilter::Warn, false, LevelFilter::Warn)]
+    #[case::warn_when_active(LevelFilter::Warn, true, LevelFilter::Debug)]
+    #[case::trace(LevelFilter::Trace, false, LevelFilter::Trace)]
+    #[case::trace_when_active(LevelFilter::Trace, true, LevelFilter::Trace)]
+    fn test_max_level(
+        #[case] inner: LevelFilter,
+        #[case] is_active: bool,
+        #[case] expected: LevelFilter,
+    ) {
+        assert_eq!(max_level(inner, is_active), expected);
+    }
+
+    #[test]
+    fn test_run_log_context_when_next_run_log_is_active() {
+        let first_dir = tempfile::tempdir().unwrap();
+        let second_dir = tempfile::tempdir().unwrap();
+
+        let first = RunLog::start(first_dir.path()).unwrap();
+        let context = first.context();
+        let second = RunLog::start(second_dir.path()).unwrap();
+
+        std::thread::spawn(move || {
+            let _guard = context.enter();
+            write("first:", b"job\n");
+        })
+        .join()
+        .unwrap();
+        write("second:", b"run\n");
+        {
+            let _guard = Context::default().enter();
+            write("detached:", b"nothing\n");
+        }
+
+        // Dropping the first run log must not deactivate the second one
+        drop(first);
+        write("second:", b"again\n");
+        drop(second);
+
+        assert_eq!(
+            std::fs::read_to_string(first_dir.path().join(FILE_NAME)).unwrap(),
+            "first:\njob\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(second_dir.path().join(FILE_NAME)).unwrap(),
+            "second:\nrun\nsecond:\nagain\n"
+        );
+    }
+}
@@ -0,0 +1,520 @@
+//! The module containing the result store of the runner
+//!
+//! With `--store=sqlite`, the metrics of each benchmark run are appended to a SQLite database
+//! together with the timestamp and the git metadata (commit hash, branch and dirty state) of the
+//! run. The history can be inspected with `iai-callgrind-runner --query DATABASE [FILTER]`.
+//!
+//! The database is accessed with [`rusqlite`] and all values are bound as parameters of the
+//! statements. The store is only available with the optional `sqlite` feature.
+
+#[cfg(feature = "sqlite")]
+use std::fmt::Debug;
+#[cfg(feature = "sqlite")]
+use std::hash::Hash;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[cfg(feature = "sqlite")]
+use anyhow::Context;
+use anyhow::{anyhow, Result};
+#[cfg(feature = "sqlite")]
+use either_or_both::EitherOrBoth;
+#[cfg(feature = "sqlite")]
+use log::debug;
+#[cfg(feature = "sqlite")]
+use rusqlite::types::{Value, ValueRef};
+#[cfg(feature = "sqlite")]
+use rusqlite::{params, Connection, OpenFlags, Transaction};
+
+use super::common::BenchmarkSummaries;
+use super::meta::Metadata;
+#[cfg(feature = "sqlite")]
+use super::metrics::{Metric, MetricsSummary};
+use super::summary::GitInfo;
+#[cfg(feature = "sqlite")]
+use super::summary::ToolMetricSummary;
+
+/// The name of the SQLite database file in the iai home directory
+pub const SQLITE_DATABASE: &str = "results.sqlite";
+
+/// The statements to create the tables of the database if they don't exist
+#[cfg(feature = "sqlite")]
+const SQLITE_SCHEMA: &str = "CREATE TABLE IF NOT EXISTS runs (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    timestamp INTEGER NOT NULL,
+    git_commit TEXT,
+    git_branch TEXT,
+    git_dirty INTEGER
+);
+CREATE TABLE IF NOT EXISTS metrics (
+    run_id INTEGER NOT NULL REFERENCES runs(id),
+    module_path TEXT NOT NULL,
+    bench_id TEXT,
+    tool TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    value NUMERIC NOT NULL
+);
+CREATE TABLE IF NOT EXISTS descriptions (
+    module_path TEXT NOT NULL,
+    bench_id TEXT,
+    description TEXT NOT NULL
+);
+";
+
+/// The kind of the result store
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum StoreKind {
+    /// Append the results to a SQLite database
+    Sqlite,
+}
+
+/// The result store of a benchmark run
+///
+/// The store is created before the benchmarks are run, so a store which is not available is
+/// detected early.
+#[derive(Debug, Clone)]
+pub struct ResultStore {
+    /// The git metadata of the project, if it is a git repository
+    pub git: Option<GitInfo>,
+    /// The [`SqliteStore`]
+    pub store: SqliteStore,
+}
+
+/// The SQLite result store
+#[derive(Debug, Clone)]
+pub struct SqliteStore {
+    /// The path to the database file
+    pub path: PathBuf,
+}
+
+/// A single stored metric of a benchmark run
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredRecord {
+    /// The id of the benchmark if any
+    pub bench_id: Option<String>,
+    /// The latest description of the benchmark if any
+    pub description: Option<String>,
+    /// The git commit hash of the run if any
+    pub git_commit: Option<String>,
+    /// The metric kind like `Ir`
+    pub kind: String,
+    /// The module path of the benchmark
+    pub module_path: String,
+    /// The id of the run
+    pub run_id: u64,
+    /// The time of the run formatted as `YYYY-MM-DD HH:MM:SS` (UTC)
+    pub time: String,
+    /// The valgrind tool
+    pub tool: String,
+    /// The value of the metric
+    pub value: f64,
+}
+
+/// The parameters of the metrics of a profile which are inserted into the `metrics` table
+#[cfg(feature = "sqlite")]
+struct MetricInsert<'a> {
+    bench_id: Option<&'a str>,
+    module_path: &'a str,
+    run_id: i64,
+    tool: String,
+    transaction: &'a Transaction<'a>,
+}
+
+impl ResultStore {
+    /// Create a new `ResultStore` of the [`StoreKind`] in the iai home directory of the
+    /// [`Metadata`]
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the store can't be used
+    pub fn new(kind: StoreKind, meta: &Metadata) -> Result<Self> {
+        let store = match kind {
+            StoreKind::Sqlite => SqliteStore::new(meta.target_dir.join(SQLITE_DATABASE))?,
+        };
+
+        Ok(Self {
+            git: meta.git().cloned(),
+            store,
+        })
+    }
+
+    /// Append the results of the [`BenchmarkSummaries`] with the current time as timestamp
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the results can't be stored
+    pub fn append(&self, summaries: &BenchmarkSummaries) -> Result<()> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_secs());
+        self.store.append(summaries, timestamp, self.git.as_ref())
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl SqliteStore {
+    /// Create a new `SqliteStore` with the database at `path`
+    ///
+    /// # Errors
+    ///
+    /// Only without the `sqlite` feature, this function returns an error
+    pub fn new<T>(path: T) -> Result<Self>
+    where
+        T: Into<PathBuf>,
+    {
+        Ok(Self { path: path.into() })
+    }
+
+    /// Append the metrics of all [`BenchmarkSummaries`] as a new run to the database
+    ///
+    /// Only the new metrics of the total of each profile are stored. The database and its tables
+    /// are created if they don't exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database can't be created or written to
+    pub fn append(
+        &self,
+        summaries: &BenchmarkSummaries,
+        timestamp: u64,
+        git: Option<&GitInfo>,
+    ) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create the directory '{}'", dir.display()))?;
+        }
+
+        debug!(
+            "Appending results to SQLite database: '{}'",
+            self.path.display()
+        );
+        let mut connection = self.open(OpenFlags::default())?;
+        connection.execute_batch(SQLITE_SCHEMA)?;
+
+        let transaction = connection.transaction()?;
+        transaction.execute(
+            "INSERT INTO runs (timestamp, git_commit, git_branch, git_dirty) VALUES (?1, ?2, ?3, \
+             ?4)",
+            params![
+                i64::try_from(timestamp)?,
+                git.map(|git| git.commit.as_str()),
+                git.and_then(|git| git.branch.as_deref()),
+                git.map(|git| git.dirty)
+            ],
+        )?;
+        let run_id = transaction.last_insert_rowid();
+
+        for summary in &summaries.summaries {
+            if let Some(description) = &summary.description {
+                transaction.execute(
+                    "DELETE FROM descriptions WHERE module_path = ?1 AND bench_id IS ?2",
+                    params![summary.module_path, summary.id],
+                )?;
+                transaction.execute(
+                    "INSERT INTO descriptions VALUES (?1, ?2, ?3)",
+                    params![summary.module_path, summary.id, description],
+                )?;
+            }
+            for profile in summary.profiles.iter() {
+                let insert = MetricInsert {
+                    bench_id: summary.id.as_deref(),
+                    module_path: &summary.module_path,
+                    run_id,
+                    tool: profile.tool.to_string(),
+                    transaction: &transaction,
+                };
+                match &profile.summaries.total.summary {
+                    ToolMetricSummary::None => {}
+                    ToolMetricSummary::ErrorTool(metrics) => insert.execute(metrics)?,
+                    ToolMetricSummary::Dhat(metrics) => insert.execute(metrics)?,
+                    ToolMetricSummary::Callgrind(metrics) => insert.execute(metrics)?,
+                    ToolMetricSummary::Cachegrind(metrics) => insert.execute(metrics)?,
+                }
+            }
+        }
+
+        transaction.commit().map_err(Into::into)
+    }
+
+    /// Remove all runs and their metrics which are older than the `cutoff` timestamp
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database can't be written to
+    pub fn prune(&self, cutoff: u64) -> Result<()> {
+        if !self.path.exists() {
+            return Ok(());
+        }
+
+        debug!(
+            "Pruning runs older than {cutoff} from SQLite database: '{}'",
+            self.path.display()
+        );
+        let cutoff = i64::try_from(cutoff)?;
+        let mut connection = self.open(OpenFlags::default())?;
+        let transaction = connection.transaction()?;
+        transaction.execute(
+            "DELETE FROM metrics WHERE run_id IN (SELECT id FROM runs WHERE timestamp < ?1)",
+            [cutoff],
+        )?;
+        transaction.execute("DELETE FROM runs WHERE timestamp < ?1", [cutoff])?;
+        transaction.commit().map_err(Into::into)
+    }
+
+    /// Query the stored metrics and return the report as table
+    ///
+    /// If present, only the metrics of benchmarks are shown which contain the `filter` in the
+    /// module path or id. The rows are ordered by the module path, id, tool, metric kind and the
+    /// time of the run. The table is empty if there are no matching metrics.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database doesn't exist or can't be read
+    pub fn query(&self, filter: Option<&str>) -> Result<String> {
+        let connection = self.open_read_only()?;
+        let mut statement = connection.prepare(
+            "SELECT datetime(runs.timestamp, 'unixepoch') AS time, substr(runs.git_commit, 1, 12) \
+             || CASE WHEN runs.git_dirty THEN '-dirty' ELSE '' END AS git_commit, \
+             metrics.module_path, metrics.bench_id AS id, metrics.tool, metrics.kind, \
+             metrics.value FROM metrics JOIN runs ON runs.id = metrics.run_id WHERE ?1 IS NULL OR \
+             metrics.module_path LIKE ?1 OR metrics.bench_id LIKE ?1 ORDER BY \
+             metrics.module_path, metrics.bench_id, metrics.tool, metrics.kind, runs.timestamp, \
+             runs.id",
+        )?;
+
+        let header = statement
+            .column_names()
+            .into_iter()
+            .map(ToOwned::to_owned)
+            .collect::<Vec<_>>();
+        let pattern = filter.map(|filter| format!("%{filter}%"));
+        let rows = statement
+            .query_map([pattern], |row| {
+                (0..header.len())
+                    .map(|index| row.get_ref(index).map(format_value))
+                    .collect::<rusqlite::Result<Vec<_>>>()
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(format_table(&header, &rows))
+    }
+
+    /// Return all [`StoredRecord`]s ordered by the run
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database doesn't exist or can't be read
+    pub fn records(&self) -> Result<Vec<StoredRecord>> {
+        let connection = self.open_read_only()?;
+        let mut statement = connection.prepare(
+            "SELECT runs.id, datetime(runs.timestamp, 'unixepoch'), runs.git_commit, \
+             metrics.module_path, metrics.bench_id, descriptions.description, metrics.tool, \
+             metrics.kind, metrics.value FROM metrics JOIN runs ON runs.id = metrics.run_id LEFT \
+             JOIN descriptions ON descriptions.module_path = metrics.module_path AND \
+             descriptions.bench_id IS metrics.bench_id ORDER BY runs.timestamp, runs.id",
+        )?;
+
+        let records = statement
+            .query_map([], |row| {
+                Ok(StoredRecord {
+                    run_id: row.get(0)?,
+                    time: row.get(1)?,
+                    git_commit: row.get(2)?,
+                    module_path: row.get(3)?,
+                    bench_id: row.get(4)?,
+                    description: row.get(5)?,
+                    tool: row.get(6)?,
+                    kind: row.get(7)?,
+                    value: row.get(8)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .with_context(|| "Failed to read the records of the SQLite database")?;
+
+        Ok(records)
+    }
+
+    /// Open the database with the `flags`
+    fn open(&self, flags: OpenFlags) -> Result<Connection> {
+        Connection::open_with_flags(&self.path, flags).with_context(|| {
+            format!(
+                "Failed to open the SQLite database: '{}'",
+                self.path.display()
+            )
+        })
+    }
+
+    /// Open the existing database read-only
+    fn open_read_only(&self) -> Result<Connection> {
+        if !self.path.exists() {
+            return Err(anyhow!(
+                "SQLite database not found: '{}'",
+                self.path.display()
+            ));
+        }
+
+        self.open(OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX)
+    }
+}
+
+/// Without the `sqlite` feature, a `SqliteStore` can't be created
+#[cfg(not(feature = "sqlite"))]
+#[allow(clippy::unused_self)]
+impl SqliteStore {
+    /// Return an error because the `sqlite` feature is disabled
+    pub fn new<T>(_: T) -> Result<Self>
+    where
+        T: Into<PathBuf>,
+    {
+        Err(unavailable())
+    }
+
+    /// Return an error because the `sqlite` feature is disabled
+    pub fn append(&self, _: &BenchmarkSummaries, _: u64, _: Option<&GitInfo>) -> Result<()> {
+        Err(unavailable())
+    }
+
+    /// Return an error because the `sqlite` feature is disabled
+    pub fn prune(&self, _: u64) -> Result<()> {
+        Err(unavailable())
+    }
+
+    /// Return an error because the `sqlite` feature is disabled
+    pub fn query(&self, _: Option<&str>) -> Result<String> {
+        Err(unavailable())
+    }
+
+    /// Return an error because the `sqlite` feature is disabled
+    pub fn records(&self) -> Result<Vec<StoredRecord>> {
+        Err(unavailable())
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl MetricInsert<'_> {
+    /// Insert the new metrics of the `metrics` summary
+    fn execute<K>(&self, metrics: &MetricsSummary<K>) -> Result<()>
+    where
+        K: Hash + Eq + Debug,
+    {
+        for (kind, diff) in &metrics.0 {
+            if let EitherOrBoth::Left(metric) | EitherOrBoth::Both(metric, _) = diff.metrics {
+                if let Some(value) = sql_value(metric) {
+                    self.transaction.execute(
+                        "INSERT INTO metrics VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                        params![
+                            self.run_id,
+                            self.module_path,
+                            self.bench_id,
+                            self.tool,
+                            format!("{kind:?}"),
+                            value
+                        ],
+                    )?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Run the query mode
+///
+/// This is the entry point of `iai-callgrind-runner --query DATABASE [FILTER]`. The report of the
+/// stored metrics is printed to `stdout`.
+///
+/// # Errors
+///
+/// Returns an error if the arguments are invalid or the database can't be queried
+pub fn run() -> Result<()> {
+    let mut args = std::env::args_os().skip(2);
+    let path = args
+        .next()
+        .map(PathBuf::from)
+        .ok_or_else(|| anyhow!("--query: Missing path to the SQLite database"))?;
+    let filter = args
+        .next()
+        .map(|filter| {
+            filter
+                .into_string()
+                .map_err(|_| anyhow!("--query: The filter is not valid utf-8"))
+        })
+        .transpose()?;
+
+    print!("{}", SqliteStore::new(path)?.query(filter.as_deref())?);
+    Ok(())
+}
+
+/// Format the `value` of a column like the `sqlite3` command-line tool
+#[cfg(feature = "sqlite")]
+fn format_value(value: ValueRef<'_>) -> String {
+    match value {
+        ValueRef::Null => String::new(),
+        ValueRef::Integer(value) => value.to_string(),
+        ValueRef::Real(value) => format!("{value:?}"),
+        ValueRef::Text(value) | ValueRef::Blob(value) => {
+            String::from_utf8_lossy(value).into_owned()
+        }
+    }
+}
+
+/// Format the `rows` as table with the `header` in the column mode of the `sqlite3` tool
+///
+/// The columns are left aligned and separated by two spaces. Without any rows, the table is empty.
+#[cfg(feature = "sqlite")]
+fn format_table(header: &[String], rows: &[Vec<String>]) -> String {
+    if rows.is_empty() {
+        return String::new();
+    }
+
+    let widths = header
+        .iter()
+        .enumerate()
+        .map(|(index, name)| {
+            rows.iter()
+                .map(|row| row[index].chars().count())
+                .fold(name.chars().count(), usize::max)
+        })
+        .collect::<Vec<_>>();
+
+    let mut table = String::new();
+    let mut write_row = |cells: &mut dyn Iterator<Item = String>| {
+        let line = cells
+            .zip(&widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect::<Vec<_>>()
+            .join("  ");
+        table.push_str(&line);
+        table.push('\n');
+    };
+
+    write_row(&mut header.iter().cloned());
+    write_row(&mut widths.iter().map(|width| "-".repeat(*width)));
+    for row in rows {
+        write_row(&mut row.iter().cloned());
+    }
+
+    table
+}
+
+/// Convert the `metric` into a SQL value if it is representable in SQL
+#[cfg(feature = "sqlite")]
+fn sql_value(metric: Metric) -> Option<Value> {
+    match metric {
+        Metric::Int(value) => {
+            Some(i64::try_from(value).map_or_else(|_| Value::Real(metric.into()), Value::Integer))
+        }
+        Metric::Float(value) if value.is_finite() => Some(Value::Real(value)),
+        Metric::Float(_) => None,
+    }
+}
+
+/// The error if the `sqlite` feature is disabled
+#[cfg(not(feature = "sqlite"))]
+fn unavailable() -> anyhow::Error {
+    anyhow!(
+        "The SQLite result store is not available. Please install the iai-callgrind-runner with \
+         the 'sqlite' feature."
+    )
+}
@@ -1,325 +1,85 @@
 //! The summary of a benchmark run
 
-use std::fmt::{Debug, Display};
-use std::fs::File;
-use std::io::stdout;
+use std::fmt::{Debug, Write as _};
+use std::fs::{File, OpenOptions};
+use std::hash::Hash;
+use std::io::{stdout, Write};
 use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
 use std::str::FromStr;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use anyhow::{anyhow, Context, Result};
-use derive_more::AsRef;
 use either_or_both::EitherOrBoth;
 use glob::glob;
+use indexmap::IndexMap;
 use itertools::Itertools;
-#[cfg(feature = "schema")]
-use schemars::JsonSchema;
-use serde::{Deserialize, Serialize};
+use log::debug;
+use sha2::{Digest, Sha256};
 
+use super::capabilities::ValgrindVersion;
 use super::common::{Baselines, ModulePath};
+use super::envs;
 use super::format::{Formatter, OutputFormat, OutputFormatKind, VerticalFormatter};
-use super::metrics::{Metric, MetricKind, Metrics, MetricsSummary};
+use super::metrics::{Metric, MetricKind, MetricsDiff, MetricsSummary};
 use super::tool::parser::ParserOutput;
 use super::tool::regression::RegressionMetrics;
-use crate::api::{CachegrindMetric, DhatMetric, ErrorMetric, EventKind, ValgrindTool};
+use crate::api::{
+    BenchmarkOutcome, CachegrindMetric, DhatMetric, ErrorMetric, EventKind, OutcomeMetric,
+    TotalAggregation, ValgrindTool,
+};
 use crate::error::Error;
+pub use crate::summary::{
+    Artifact, ArtifactKind, Baseline, BaselineKind, BaselineName, BenchmarkKind, BenchmarkSummary,
+    Diffs, EnvironmentInfo, FlamegraphSummaries, FlamegraphSummary, GitInfo, Profile, ProfileData,
+    ProfileInfo, ProfilePart, ProfileTotal, Profiles, ResourceUsage, SummaryFormat, SummaryOutput,
+    ToolMetricSummary, ToolMetrics, ToolRegression, SCHEMA_VERSION,
+};
 use crate::util::{factor_diff, make_absolute, percentage_diff};
 
-/// The version of the summary json schema
-pub const SCHEMA_VERSION: &str = "6";
-
-/// The `BaselineKind` describing the baseline
-#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
-#[cfg_attr(feature = "schema", derive(JsonSchema))]
-pub enum BaselineKind {
-    /// Compare new against `*.old` output files
-    Old,
-    /// Compare new against a named baseline
-    Name(BaselineName),
-}
-
-/// The `BenchmarkKind`, differentiating between library and binary benchmarks
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-#[cfg_attr(feature = "schema", derive(JsonSchema))]
-pub enum BenchmarkKind {
-    /// A library benchmark
-    LibraryBenchmark,
-    /// A binary benchmark
-    BinaryBenchmark,
-}
-
-/// The format (json, ...) in which the summary file should be saved or printed
-#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum, Serialize, Deserialize)]
-#[cfg_attr(feature = "schema", derive(JsonSchema))]
-pub enum SummaryFormat {
-    /// The format in a space optimal json representation without newlines
-    Json,
-    /// The format in pretty printed json
-    PrettyJson,
-}
-
-/// The `ToolMetricSummary` contains the `MetricsSummary` distinguished by tool and metric kinds
-#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
-#[cfg_attr(feature = "schema", derive(JsonSchema))]
-pub enum ToolMetricSummary {
-    /// If there are no metrics extracted (currently massif, bbv)
-    #[default]
-    None,
-    /// The error summary of tools which reports errors (memcheck, helgrind, drd)
-    ErrorTool(MetricsSummary<ErrorMetric>),
-    /// The dhat summary
-    Dhat(MetricsSummary<DhatMetric>),
-    /// The callgrind summary
-    Callgrind(MetricsSummary<EventKind>),
-    /// The cachegrind summary
-    Cachegrind(MetricsSummary<CachegrindMetric>),
-}
-
-/// The metrics distinguished per tool class
-///
-/// The tool classes are: dhat, error metrics from memcheck, drd, helgrind and callgrind
-#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
-#[cfg_attr(feature = "schema", derive(JsonSchema))]
-pub enum ToolMetrics {
-    /// If there were no metrics extracted from a tool (currently massif, bbv)
-    #[default]
-    None,
-    /// The metrics of a dhat benchmark
-    Dhat(Metrics<DhatMetric>),
-    /// The metrics of a tool run which reports errors (memcheck, helgrind, drd)
-    ErrorTool(Metrics<ErrorMetric>),
-    /// The metrics of a callgrind benchmark
-    Callgrind(Metrics<EventKind>),
-    /// The metrics of a cachegrind benchmark
-    Cachegrind(Metrics<CachegrindMetric>),
-}
-
-/// A detected performance regression depending on the limit either `Soft` or `Hard`
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[cfg_attr(feature = "schema", derive(JsonSchema))]
-pub enum ToolRegression {
-    /// A performance regression triggered by a soft limit
-    Soft {
-        /// The metric kind per tool
-        metric: MetricKind,
-        /// The value of the new benchmark run
-        new: Metric,
-        /// The value of the old benchmark run
-        old: Metric,
-        /// The difference between new and old in percent. Serialized as string to preserve
-        /// infinity values and avoid null in json.
-        #[serde(with = "crate::serde::float_64")]
-        #[cfg_attr(feature = "schema", schemars(with = "String"))]
-        diff_pct: f64,
-        /// The value of the limit which was exceeded to cause a performance regression. Serialized
-        /// as string to preserve infinity values and avoid null in json.
-        #[serde(with = "crate::serde::float_64")]
-        #[cfg_attr(feature = "schema", schemars(with = "String"))]
-        limit: f64,
-    },
-    /// A performance regression triggered by a hard limit
-    Hard {
-        /// The metric kind per tool
-        metric: MetricKind,
-        /// The value of the benchmark run
-        new: Metric,
-        /// The difference between new and the limit
-        diff: Metric,
-        /// The limit
-        limit: Metric,
-    },
-}
-
-/// A `Baseline` depending on the [`BaselineKind`] which points to the corresponding path
-///
-/// This baseline is used for comparisons with the new output of valgrind tools.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-#[cfg_attr(feature = "schema", derive(JsonSchema))]
-pub struct Baseline {
-    /// The kind of the `Baseline`
-    pub kind: BaselineKind,
-    /// The path to the file which is used to compare against the new output
-    pub path: PathBuf,
-}
-
-/// The name of the baseline
-#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
-#[cfg_attr(feature = "schema", derive(JsonSchema))]
-pub struct BaselineName(String);
-
-/// The `BenchmarkSummary` containing all the information of a single benchmark run
-///
-/// This includes produced files, recorded callgrind events, performance regressions ...
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[cfg_attr(feature = "schema", derive(JsonSchema))]
-pub struct BenchmarkSummary {
-    /// The baselines if any. An absent first baseline indicates that new output was produced. An
-    /// absent second baseline indicates the usage of the usual "*.old" output.
-    pub baselines: (Option<String>, Option<String>),
-    /// The path to the binary which is executed by valgrind. In case of a library benchmark this
-    /// is the compiled benchmark file. In case of a binary benchmark this is the path to the
-    /// command.
-    pub benchmark_exe: PathBuf,
-    /// The path to the benchmark file
-    pub benchmark_file: PathBuf,
-    /// More details describing this benchmark run
-    pub details: Option<String>,
-    /// The name of the function under test
-    pub function_name: String,
-    /// The user provided id of this benchmark
-    pub id: Option<String>,
-    /// Whether this summary describes a library or binary benchmark
-    pub kind: BenchmarkKind,
-    /// The rust path in the form `bench_file::group::bench`
-    pub module_path: String,
-    /// The directory of the package
-    pub package_dir: PathBuf,
-    /// The summary of other valgrind tool runs
-    pub profiles: Profiles,
-    /// The project's root directory
-    pub project_root: PathBuf,
-    /// The destination and kind of the summary file
-    pub summary_output: Option<SummaryOutput>,
-    /// The version of this format. Only backwards incompatible changes cause an increase of the
-    /// version
-    pub version: String,
-}
-
-/// The differences between two `Metrics` as percentage and factor
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
-#[cfg_attr(feature = "schema", derive(JsonSchema))]
-pub struct Diffs {
-    /// The percentage of the difference between two `Metrics` serialized as string to preserve
-    /// infinity values and avoid `null` in json
-    #[serde(with = "crate::serde::float_64")]
-    #[cfg_attr(feature = "schema", schemars(with = "String"))]
-    pub diff_pct: f64,
-    /// The factor of the difference between two `Metrics` serialized as string to preserve
-    /// infinity values and void `null` in json
-    #[serde(with = "crate::serde::float_64")]
-    #[cfg_attr(feature = "schema", schemars(with = "String"))]
-    pub factor: f64,
-}
-
-/// All callgrind flamegraph summaries and their totals
-#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
-#[cfg_attr(feature = "schema", derive(JsonSchema))]
-pub struct FlamegraphSummaries {
-    /// The `FlamegraphSummary`s
-    pub summaries: Vec<FlamegraphSummary>,
-    /// The totals over the `FlamegraphSummary`s
-    pub totals: Vec<FlamegraphSummary>,
-}
-
-/// The callgrind `FlamegraphSummary` records all created paths for an [`EventKind`] specific
-/// flamegraph
-///
-/// Either the `regular_path`, `old_path` or the `diff_path` are present. Never can all of them be
-/// absent.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-#[cfg_attr(feature = "schema", derive(JsonSchema))]
-pub struct FlamegraphSummary {
-    /// If present, the path to the file of the old regular (non-differential) flamegraph
-    pub base_path: Option<PathBuf>,
-    /// If present, the path to the file of the differential flamegraph
-    pub diff_path: Option<PathBuf>,
-    /// The `EventKind` of the flamegraph
-    pub event_kind: EventKind,
-    /// If present, the path to the file of the regular (non-differential) flamegraph
-    pub regular_path: Option<PathBuf>,
-}
-
-/// The `ToolSummary` containing all information about a valgrind tool run
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[cfg_attr(feature = "schema", derive(JsonSchema))]
-pub struct Profile {
-    /// Details and information about the created flamegraphs if any
-    pub flamegraphs: Vec<FlamegraphSummary>,
-    /// The paths to the `*.log` files. All tools produce at least one log file
-    pub log_paths: Vec<PathBuf>,
-    /// The paths to the `*.out` files. Not all tools produce an output in addition to the log
-    /// files
-    pub out_paths: Vec<PathBuf>,
-    /// The metrics and details about the tool run
-    pub summaries: ProfileData,
-    /// The Valgrind tool like `DHAT`, `Memcheck` etc.
-    pub tool: ValgrindTool,
-}
-
-/// The `ToolRun` contains all information about a single tool run with possibly multiple segments
+/// The registry of the files produced by a benchmark run
 ///
-/// The total is always present and summarizes all tool run segments. In the special case of a
-/// single tool run segment, the total equals the metrics of this segment.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[cfg_attr(feature = "schema", derive(JsonSchema))]
-pub struct ProfileData {
-    /// All [`ProfilePart`]s
-    pub parts: Vec<ProfilePart>,
-    /// The total over the [`ProfilePart`]s
-    pub total: ProfileTotal,
-}
-
-/// Some additional and necessary information about the tool run segment
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, AsRef)]
-#[cfg_attr(feature = "schema", derive(JsonSchema))]
-pub struct ProfileInfo {
-    /// The executed command extracted from Valgrind output
-    pub command: String,
-    /// More details for example from the logging output of the tool run
-    pub details: Option<String>,
-    /// The parent pid of this process
-    pub parent_pid: Option<i32>,
-    /// The part of this tool run (only callgrind)
-    pub part: Option<u64>,
-    /// The path to the file from the tool run
-    pub path: PathBuf,
-    /// The pid of this process
-    pub pid: i32,
-    /// The thread of this tool run (only callgrind)
-    pub thread: Option<usize>,
-}
-
-/// A single segment of a tool run and if present the comparison with the "old" segment
-///
-/// A tool run can produce multiple segments, for example for each process and subprocess with
-/// (--trace-children).
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[cfg_attr(feature = "schema", derive(JsonSchema))]
-pub struct ProfilePart {
-    /// Details like command, pid, ppid, thread number etc. (see [`ProfileInfo`])
-    pub details: EitherOrBoth<ProfileInfo>,
-    /// The [`ToolMetricSummary`]
-    pub metrics_summary: ToolMetricSummary,
-}
-
-/// The total metrics over all [`ProfilePart`]s and if detected any [`ToolRegression`]
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[cfg_attr(feature = "schema", derive(JsonSchema))]
-pub struct ProfileTotal {
-    /// The detected regressions if any
-    pub regressions: Vec<ToolRegression>,
-    /// The summary of metrics of the tool
-    pub summary: ToolMetricSummary,
-}
+/// Everything which creates a file of a benchmark registers the file in the registry of the
+/// benchmark, also if it runs on a worker thread of the [`Pipeline`](super::pipeline::Pipeline).
+/// The [`Artifact`]s are collected from the registry with [`BenchmarkSummary::collect_artifacts`]
+/// after the benchmark has finished.
+#[derive(Debug, Clone, Default)]
+pub struct ArtifactRegistry(Arc<Mutex<Vec<(ArtifactKind, PathBuf)>>>);
+
+impl ArtifactRegistry {
+    /// Register the files at `paths` as [`Artifact`]s of the `kind`
+    pub fn register<I, P>(&self, kind: ArtifactKind, paths: I)
+    where
+        I: IntoIterator<Item = P>,
+        P: Into<PathBuf>,
+    {
+        let mut artifacts = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        artifacts.extend(paths.into_iter().map(|path| (kind, path.into())));
+    }
 
-/// The collection of all generated [`Profile`]s
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[cfg_attr(feature = "schema", derive(JsonSchema))]
-#[derive(Default)]
-pub struct Profiles(Vec<Profile>);
-
-/// Manage the summary output file with this `SummaryOutput`
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-#[cfg_attr(feature = "schema", derive(JsonSchema))]
-pub struct SummaryOutput {
-    /// The [`SummaryFormat`]
-    format: SummaryFormat,
-    /// The path to the destination file of this summary
-    path: PathBuf,
-}
+    /// Register the `*.out` and `*.log` files and the flamegraphs of the [`Profile`]
+    pub fn register_profile(&self, profile: &Profile) {
+        self.register(ArtifactKind::Out, &profile.out_paths);
+        self.register(ArtifactKind::Log, &profile.log_paths);
+        self.register(
+            ArtifactKind::Flamegraph,
+            profile
+                .flamegraphs
+                .iter()
+                .flat_map(|flamegraph| [&flamegraph.regular_path, &flamegraph.diff_path])
+                .flatten(),
+        );
+    }
 
-impl Display for BaselineName {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str(&self.0)
+    /// Return the registered paths in the order of their registration without duplicates
+    fn paths(&self) -> Vec<(ArtifactKind, PathBuf)> {
+        let artifacts = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        artifacts
+            .iter()
+            .unique_by(|(_, path)| path)
+            .cloned()
+            .collect()
     }
 }
 
@@ -355,17 +115,27 @@ impl BenchmarkSummary {
         details: Option<String>,
         output: Option<SummaryOutput>,
         baselines: Baselines,
+        git: Option<GitInfo>,
+        environment: Option<EnvironmentInfo>,
+        metadata: IndexMap<String, String>,
     ) -> Self {
         Self {
             version: SCHEMA_VERSION.to_owned(),
+            artifacts: vec![],
             kind,
             benchmark_file: make_absolute(&project_root, benchmark_file),
             benchmark_exe: make_absolute(&project_root, benchmark_exe),
+            description: None,
             module_path: module_path.to_string(),
             function_name: function_name.to_owned(),
+            git,
             id,
             details,
+            environment,
+            expected_regression: None,
+            metadata,
             profiles: Profiles::default(),
+            run_log: None,
             summary_output: output,
             project_root,
             package_dir,
@@ -373,6 +143,71 @@ impl BenchmarkSummary {
         }
     }
 
+    /// Collect the [`Artifact`]s of this benchmark run with their checksums
+    ///
+    /// These are all files in the [`ArtifactRegistry`] of this benchmark run. Files which don't
+    /// exist are skipped. The paths are stored relative to the `project_root`.
+    ///
+    /// # Errors
+    ///
+    /// If an existing file could not be read
+    pub fn collect_artifacts(&mut self, registry: &ArtifactRegistry) -> Result<()> {
+        let mut artifacts = vec![];
+        for (kind, path) in registry.paths() {
+            if !path.exists() {
+                continue;
+            }
+
+            let (sha256, size) = checksum(&path)?;
+            artifacts.push(Artifact {
+                kind,
+                path: path
+                    .strip_prefix(&self.project_root)
+                    .unwrap_or(&path)
+                    .to_owned(),
+                sha256,
+                size,
+            });
+        }
+
+        self.artifacts = artifacts;
+        Ok(())
+    }
+
+    /// Append this summary as a single line of json to the summary stream file at `path`
+    ///
+    /// The stream contains the summaries of all benchmarks of a benchmark file in the order the
+    /// benchmarks finished, so the results of the finished benchmarks are available even if the
+    /// benchmark run is aborted.
+    pub fn append_to_stream(&self, path: &Path) -> Result<()> {
+        let mut line =
+            serde_json::to_vec(self).with_context(|| "Failed to serialize summary to json")?;
+        line.push(b'\n');
+
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .and_then(|mut file| file.write_all(&line))
+            .with_context(|| {
+                format!(
+                    "Failed to append summary to the summary stream: {}",
+                    path.display()
+                )
+            })
+    }
+
+    /// Remove the metrics of the [`ProfilePart`]s of all profiles keeping only the totals
+    ///
+    /// After a summary was appended to the summary stream, the final summary, the result store and
+    /// the results browser need only the totals of the summary. So, the parts of the summaries of
+    /// all finished benchmarks don't have to be kept in memory until the end of the benchmark run.
+    pub fn clear_parts(&mut self) {
+        for profile in &mut self.profiles.0 {
+            profile.summaries.parts.clear();
+        }
+    }
+
     /// If the summary is json output, print it and eventually safe it, if configured to do so
     pub fn print_and_save(&self, output_format: &OutputFormatKind) -> Result<()> {
         let value = match (output_format, &self.summary_output) {
@@ -402,12 +237,14 @@ impl BenchmarkSummary {
         result.with_context(|| "Failed to print json to stdout")?;
 
         if let Some(output) = &self.summary_output {
-            let file = output.create()?;
+            let mut file = output.create()?;
 
-            let result = if matches!(output.format, SummaryFormat::PrettyJson) {
-                serde_json::to_writer_pretty(file, &value)
-            } else {
-                serde_json::to_writer(file, &value)
+            let result = match output.format {
+                SummaryFormat::Json => serde_json::to_writer(file, &value).map_err(Into::into),
+                SummaryFormat::PrettyJson => {
+                    serde_json::to_writer_pretty(file, &value).map_err(Into::into)
+                }
+                SummaryFormat::OpenMetrics => file.write_all(self.to_openmetrics().as_bytes()),
             };
 
             result.with_context(|| {
@@ -418,22 +255,145 @@ impl BenchmarkSummary {
         Ok(())
     }
 
+    /// Return the metrics of this summary in the OpenMetrics text exposition format
+    ///
+    /// Each metric of the total of each [`Profile`] is a gauge labeled with the `group` (the module
+    /// path without the function name), the `benchmark` (the function name), the `id` (if
+    /// present), the `tool`, the metric `kind` and the `baseline` (`new` or `old`). If new and old
+    /// metrics are present, the percentage difference is exported in a separate gauge without the
+    /// `baseline` label.
+    pub fn to_openmetrics(&self) -> String {
+        let group = self
+            .module_path
+            .strip_suffix(&self.function_name)
+            .and_then(|group| group.strip_suffix("::"))
+            .unwrap_or(&self.module_path);
+
+        let mut labels = format!(
+            "group=\"{}\",benchmark=\"{}\"",
+            escape_label_value(group),
+            escape_label_value(&self.function_name)
+        );
+        if let Some(id) = &self.id {
+            write!(labels, ",id=\"{}\"", escape_label_value(id)).unwrap();
+        }
+
+        let mut metrics = String::new();
+        let mut diffs = String::new();
+        for profile in self.profiles.iter() {
+            let labels = format!("{labels},tool=\"{}\"", profile.tool);
+            match &profile.summaries.total.summary {
+                ToolMetricSummary::None => {}
+                ToolMetricSummary::ErrorTool(summary) => {
+                    write_openmetrics_samples(&labels, summary, &mut metrics, &mut diffs);
+                }
+                ToolMetricSummary::Dhat(summary) => {
+                    write_openmetrics_samples(&labels, summary, &mut metrics, &mut diffs);
+                }
+                ToolMetricSummary::Callgrind(summary) => {
+                    write_openmetrics_samples(&labels, summary, &mut metrics, &mut diffs);
+                }
+                ToolMetricSummary::Cachegrind(summary) => {
+                    write_openmetrics_samples(&labels, summary, &mut metrics, &mut diffs);
+                }
+            }
+        }
+
+        let mut output = String::new();
+        output.push_str("# TYPE iai_callgrind_metric gauge\n");
+        output.push_str("# HELP iai_callgrind_metric The metrics of the benchmark\n");
+        output.push_str(&metrics);
+        output.push_str("# TYPE iai_callgrind_metric_diff_pct gauge\n");
+        output.push_str(
+            "# HELP iai_callgrind_metric_diff_pct The difference between the new and old metrics \
+             in percent\n",
+        );
+        output.push_str(&diffs);
+        output.push_str("# EOF\n");
+        output
+    }
+
+    /// Return the [`BenchmarkOutcome`] of this summary which is passed to the `teardown` functions
+    pub fn to_outcome(&self) -> BenchmarkOutcome {
+        let mut metrics = vec![];
+        for profile in self.profiles.iter() {
+            match &profile.summaries.total.summary {
+                ToolMetricSummary::None => {}
+                ToolMetricSummary::ErrorTool(summary) => {
+                    push_outcome_metrics(profile.tool, summary, &mut metrics);
+                }
+                ToolMetricSummary::Dhat(summary) => {
+                    push_outcome_metrics(profile.tool, summary, &mut metrics);
+                }
+                ToolMetricSummary::Callgrind(summary) => {
+                    push_outcome_metrics(profile.tool, summary, &mut metrics);
+                }
+                ToolMetricSummary::Cachegrind(summary) => {
+                    push_outcome_metrics(profile.tool, summary, &mut metrics);
+                }
+            }
+        }
+
+        BenchmarkOutcome {
+            function_name: self.function_name.clone(),
+            id: self.id.clone(),
+            metrics,
+            module_path: self.module_path.clone(),
+            regressed: self.is_regressed(),
+        }
+    }
+
     /// Check if this `BenchmarkSummary` has recorded any performance regressions
     ///
     /// # Errors
     ///
     /// If a regressions is present and are configured to be `fail_fast` an error is returned
     pub fn check_regression(&self, fail_fast: bool) -> Result<()> {
-        if self.profiles.is_regressed() && fail_fast {
+        if self.is_regressed() && fail_fast {
             return Err(Error::RegressionError(true).into());
         }
 
         Ok(())
     }
 
-    /// Return true if any [`Profile`] has regressed
+    /// Return true if any [`Profile`] has detected errors
+    pub fn has_errors(&self) -> bool {
+        self.profiles.iter().any(Profile::has_errors)
+    }
+
+    /// Return true if any [`Profile`] has detected errors which fail the benchmark run
+    pub fn is_failed(&self) -> bool {
+        self.profiles.iter().any(Profile::is_failed)
+    }
+
+    /// Return true if any [`Profile`] has regressed and the regression wasn't expected
     pub fn is_regressed(&self) -> bool {
-        self.profiles.is_regressed()
+        self.expected_regression.is_none() && self.profiles.is_regressed()
+    }
+
+    /// Return true if any [`Profile`] has regressed but the regression was expected
+    ///
+    /// Expected regressions are reported as warning and don't fail the benchmark run.
+    pub fn is_expected_regression(&self) -> bool {
+        self.expected_regression.is_some() && self.profiles.is_regressed()
+    }
+
+    /// Return true if any [`Profile`] is suspicious of a misconfiguration
+    pub fn is_suspicious(&self) -> bool {
+        self.profiles.iter().any(|profile| profile.suspicious)
+    }
+
+    /// Return true if the metrics of all [`Profile`]s are identical to the metrics of the old run
+    ///
+    /// Benchmarks without any metrics or with regressions are never unchanged.
+    pub fn is_unchanged(&self) -> bool {
+        self.profiles
+            .iter()
+            .any(|profile| profile.summaries.total.is_some())
+            && self
+                .profiles
+                .iter()
+                .all(|profile| !profile.is_regressed() && profile.summaries.is_unchanged())
     }
 
     /// Compare this summary with another and print the result of the comparison
@@ -481,6 +441,39 @@ impl Diffs {
     }
 }
 
+impl EnvironmentInfo {
+    /// Detect the environment of the benchmark run with the already detected valgrind `version`
+    ///
+    /// The version of `rustc` is detected with the `rustc` in the `RUSTC` environment variable if
+    /// present, otherwise in the `PATH`. The tools are empty and can be set per benchmark with
+    /// [`EnvironmentInfo::with_tools`].
+    pub fn detect(valgrind: Option<ValgrindVersion>) -> Self {
+        let rustc = std::env::var_os(envs::RUSTC).unwrap_or_else(|| "rustc".into());
+        let rustc = run_command(Command::new(rustc).arg("--version"));
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_secs());
+
+        Self {
+            cpu: detect_cpu(),
+            rustc,
+            target: env!("IC_BUILD_TRIPLE").to_owned(),
+            timestamp,
+            tools: vec![],
+            valgrind: valgrind.map(|version| version.to_string()),
+        }
+    }
+
+    /// Return a copy of this `EnvironmentInfo` with the enabled `tools` of a benchmark
+    #[must_use]
+    pub fn with_tools(&self, tools: Vec<ValgrindTool>) -> Self {
+        Self {
+            tools,
+            ..self.clone()
+        }
+    }
+}
+
 impl FlamegraphSummary {
     /// Create a new `FlamegraphSummary`
     pub fn new(event_kind: EventKind) -> Self {
@@ -493,7 +486,85 @@ impl FlamegraphSummary {
     }
 }
 
+impl GitInfo {
+    /// Detect the git metadata of the repository in `dir`
+    ///
+    /// Returns `None` if `dir` is not within a git repository, there are no commits yet or `git`
+    /// is not installed. The commit, branch and dirty state are extracted from a single `git
+    /// status` call.
+    pub fn from_repository(dir: &Path) -> Option<Self> {
+        let status = run_git(
+            dir,
+            &[
+                "status",
+                "--porcelain=v2",
+                "--branch",
+                "--untracked-files=no",
+            ],
+        )?;
+        Self::from_porcelain_status(&status)
+    }
+
+    /// Parse the output of `git status --porcelain=v2 --branch`
+    ///
+    /// Returns `None` if there is no commit (`# branch.oid (initial)`). The branch is `None` if the
+    /// `HEAD` is detached. The repository is dirty if there are any changed entries.
+    pub fn from_porcelain_status(status: &str) -> Option<Self> {
+        let mut commit = None;
+        let mut branch = None;
+        let mut dirty = false;
+
+        for line in status.lines() {
+            if let Some(header) = line.strip_prefix("# ") {
+                if let Some(oid) = header.strip_prefix("branch.oid ") {
+                    commit = Some(oid).filter(|oid| *oid != "(initial)");
+                } else if let Some(head) = header.strip_prefix("branch.head ") {
+                    branch = Some(head).filter(|head| *head != "(detached)");
+                } else {
+                    // Other headers like `branch.upstream` are ignored
+                }
+            } else if !line.is_empty() {
+                dirty = true;
+            } else {
+                // do nothing
+            }
+        }
+
+        commit.map(|commit| Self {
+            branch: branch.map(ToOwned::to_owned),
+            commit: commit.to_owned(),
+            dirty,
+        })
+    }
+}
+
 impl Profile {
+    /// Return the new number of errors and error contexts of an error checking tool
+    ///
+    /// Returns `None` if this is not the profile of an error checking tool like `Memcheck`.
+    pub fn errors(&self) -> Option<(Metric, Metric)> {
+        let ToolMetricSummary::ErrorTool(summary) = &self.summaries.total.summary else {
+            return None;
+        };
+
+        let new = |metric_kind| match summary.diff_by_kind(&metric_kind).map(|d| &d.metrics) {
+            Some(EitherOrBoth::Left(new) | EitherOrBoth::Both(new, _)) => *new,
+            _ => Metric::Int(0),
+        };
+        Some((new(ErrorMetric::Errors), new(ErrorMetric::Contexts)))
+    }
+
+    /// Return true if the error checking tool of this profile has detected any errors
+    pub fn has_errors(&self) -> bool {
+        self.errors()
+            .is_some_and(|(errors, _)| errors > Metric::Int(0))
+    }
+
+    /// Return true if the detected errors fail the benchmark run
+    pub fn is_failed(&self) -> bool {
+        self.fail_on_errors && self.has_errors()
+    }
+
     /// Return true if one of the summaries has regressed
     pub fn is_regressed(&self) -> bool {
         self.summaries.is_regressed()
@@ -516,6 +587,15 @@ impl ProfileData {
         self.parts.len() > 1
     }
 
+    /// Return true if the metrics of the total and all parts are identical to the old metrics
+    pub fn is_unchanged(&self) -> bool {
+        self.total.summary.is_unchanged()
+            && self
+                .parts
+                .iter()
+                .all(|part| part.metrics_summary.is_unchanged())
+    }
+
     /// Used internally to group the output by pid, then by parts and then by threads
     ///
     /// The grouping simplifies the zipping of the new and old parser output later.
@@ -588,6 +668,32 @@ impl ProfileData {
     ///
     /// Secondly and finally, the groups are processed and summarized in a total.
     pub fn new(parsed_new: Vec<ParserOutput>, parsed_old: Option<Vec<ParserOutput>>) -> Self {
+        Self::with_selection(parsed_new, parsed_old, |_| true, TotalAggregation::Sum)
+    }
+
+    /// Create a new `ProfileData` like [`ProfileData::new`] but aggregate only the selected outputs
+    ///
+    /// All parser outputs are present in the parts, but only the outputs for which `is_selected`
+    /// returns true are included in the total. If there is a new and an old output, the new output
+    /// decides. The selected outputs are summed up or with [`TotalAggregation::Max`] the maximum of
+    /// each metric is used.
+    pub fn with_selection<F>(
+        parsed_new: Vec<ParserOutput>,
+        parsed_old: Option<Vec<ParserOutput>>,
+        is_selected: F,
+        aggregation: TotalAggregation,
+    ) -> Self
+    where
+        F: Fn(&ParserOutput) -> bool,
+    {
+        let aggregate =
+            |total: &mut ToolMetricSummary, summary: &ToolMetricSummary| match aggregation {
+                TotalAggregation::Sum | TotalAggregation::ExcludeMainThread => {
+                    total.add_mut(summary);
+                }
+                TotalAggregation::Max => total.max_mut(summary),
+            };
+
         let mut total = match parsed_new
             .first()
             .expect("At least 1 parsed result should be present")
@@ -612,32 +718,41 @@ impl ProfileData {
                         match e_parts {
                             itertools::EitherOrBoth::Both(new_threads, old_threads) => {
                                 for e_threads in new_threads.into_iter().zip_longest(old_threads) {
-                                    let summary = match e_threads {
-                                        itertools::EitherOrBoth::Both(new, old) => {
-                                            ProfilePart::from_new_and_old(new, old)
-                                        }
+                                    let (selected, summary) = match e_threads {
+                                        itertools::EitherOrBoth::Both(new, old) => (
+                                            is_selected(&new),
+                                            ProfilePart::from_new_and_old(new, old),
+                                        ),
                                         itertools::EitherOrBoth::Left(new) => {
-                                            ProfilePart::from_new(new)
+                                            (is_selected(&new), ProfilePart::from_new(new))
                                         }
                                         itertools::EitherOrBoth::Right(old) => {
-                                            ProfilePart::from_old(old)
+                                            (is_selected(&old), ProfilePart::from_old(old))
                                         }
                                     };
-                                    total.add_mut(&summary.metrics_summary);
+                                    if selected {
+                                        aggregate(&mut total, &summary.metrics_summary);
+                                    }
                                     summaries.push(summary);
                                 }
                             }
                             itertools::EitherOrBoth::Left(left) => {
                                 for new in left {
+                                    let selected = is_selected(&new);
                                     let summary = ProfilePart::from_new(new);
-                                    total.add_mut(&summary.metrics_summary);
+                                    if selected {
+                                        aggregate(&mut total, &summary.metrics_summary);
+                                    }
                                     summaries.push(summary);
                                 }
                             }
                             itertools::EitherOrBoth::Right(right) => {
                                 for old in right {
+                                    let selected = is_selected(&old);
                                     let summary = ProfilePart::from_old(old);
-                                    total.add_mut(&summary.metrics_summary);
+                                    if selected {
+                                        aggregate(&mut total, &summary.metrics_summary);
+                                    }
                                     summaries.push(summary);
                                 }
                             }
@@ -646,15 +761,21 @@ impl ProfileData {
                 }
                 itertools::EitherOrBoth::Left(left) => {
                     for new in left.into_iter().flatten() {
+                        let selected = is_selected(&new);
                         let summary = ProfilePart::from_new(new);
-                        total.add_mut(&summary.metrics_summary);
+                        if selected {
+                            aggregate(&mut total, &summary.metrics_summary);
+                        }
                         summaries.push(summary);
                     }
                 }
                 itertools::EitherOrBoth::Right(right) => {
                     for old in right.into_iter().flatten() {
+                        let selected = is_selected(&old);
                         let summary = ProfilePart::from_old(old);
-                        total.add_mut(&summary.metrics_summary);
+                        if selected {
+                            aggregate(&mut total, &summary.metrics_summary);
+                        }
                         summaries.push(summary);
                     }
                 }
@@ -773,12 +894,69 @@ impl Profiles {
     }
 }
 
-impl IntoIterator for Profiles {
-    type Item = Profile;
-    type IntoIter = std::vec::IntoIter<Self::Item>;
+impl ResourceUsage {
+    /// Return the accumulated resource usage of all terminated and waited for child processes
+    ///
+    /// Returns `None` if `getrusage` failed.
+    pub fn of_children() -> Option<Self> {
+        let mut usage = std::mem::MaybeUninit::<libc::rusage>::zeroed();
+        // SAFETY: The pointer is valid and `getrusage` writes at most a `rusage` struct into it
+        let result = unsafe { libc::getrusage(libc::RUSAGE_CHILDREN, usage.as_mut_ptr()) };
+        if result != 0 {
+            debug!(
+                "Failed to get the resource usage: {}",
+                std::io::Error::last_os_error()
+            );
+            return None;
+        }
+
+        // SAFETY: The struct was zero initialized and successfully filled by `getrusage`
+        let usage = unsafe { usage.assume_init() };
+        let to_u64 = |value: libc::c_long| u64::try_from(value).unwrap_or_default();
+        Some(Self {
+            involuntary_context_switches: to_u64(usage.ru_nivcsw),
+            major_page_faults: to_u64(usage.ru_majflt),
+            minor_page_faults: to_u64(usage.ru_minflt),
+            voluntary_context_switches: to_u64(usage.ru_nvcsw),
+        })
+    }
+
+    /// Add the `other` resource usage to this resource usage (saturating)
+    #[must_use]
+    pub fn add(&self, other: &Self) -> Self {
+        Self {
+            involuntary_context_switches: self
+                .involuntary_context_switches
+                .saturating_add(other.involuntary_context_switches),
+            major_page_faults: self
+                .major_page_faults
+                .saturating_add(other.major_page_faults),
+            minor_page_faults: self
+                .minor_page_faults
+                .saturating_add(other.minor_page_faults),
+            voluntary_context_switches: self
+                .voluntary_context_switches
+                .saturating_add(other.voluntary_context_switches),
+        }
+    }
 
-    fn into_iter(self) -> Self::IntoIter {
-        self.0.into_iter()
+    /// Subtract the `other` resource usage from this resource usage (saturating)
+    #[must_use]
+    pub fn saturating_sub(&self, other: &Self) -> Self {
+        Self {
+            involuntary_context_switches: self
+                .involuntary_context_switches
+                .saturating_sub(other.involuntary_context_switches),
+            major_page_faults: self
+                .major_page_faults
+                .saturating_sub(other.major_page_faults),
+            minor_page_faults: self
+                .minor_page_faults
+                .saturating_sub(other.minor_page_faults),
+            voluntary_context_switches: self
+                .voluntary_context_switches
+                .saturating_sub(other.voluntary_context_switches),
+        }
     }
 }
 
@@ -786,9 +964,14 @@ impl SummaryOutput {
     /// Create a new `SummaryOutput` with `dir` as base dir and an extension fitting the
     /// [`SummaryFormat`]
     pub fn new(format: SummaryFormat, dir: &Path) -> Self {
+        let file_name = match format {
+            SummaryFormat::Json | SummaryFormat::PrettyJson => "summary.json",
+            SummaryFormat::OpenMetrics => "summary.prom",
+        };
+
         Self {
             format,
-            path: dir.join("summary.json"),
+            path: dir.join(file_name),
         }
     }
 
@@ -811,11 +994,64 @@ impl SummaryOutput {
 
     /// Try to create an empty summary file returning the [`File`] object
     pub fn create(&self) -> Result<File> {
-        File::create(&self.path).with_context(|| "Failed to create json summary file")
+        File::create(&self.path).with_context(|| "Failed to create summary file")
     }
 }
 
 impl ToolMetricSummary {
+    /// Return a new summary with only the primary metric of the tool
+    ///
+    /// The primary metric is `Instructions` for callgrind and cachegrind, `Total bytes` for DHAT
+    /// and `Errors` for the error tools. Other than the derived metrics like the cache hit rates,
+    /// the primary metrics can be summed up over multiple benchmarks.
+    #[must_use]
+    pub fn to_primary(&self) -> Self {
+        match self {
+            Self::None => Self::None,
+            Self::ErrorTool(summary) => {
+                Self::ErrorTool(retain_metric(summary, &ErrorMetric::Errors))
+            }
+            Self::Dhat(summary) => Self::Dhat(retain_metric(summary, &DhatMetric::TotalBytes)),
+            Self::Callgrind(summary) => Self::Callgrind(retain_metric(summary, &EventKind::Ir)),
+            Self::Cachegrind(summary) => {
+                Self::Cachegrind(retain_metric(summary, &CachegrindMetric::Ir))
+            }
+        }
+    }
+
+    /// Return true if all new metrics are identical to the old metrics
+    ///
+    /// Always true if there are no metrics, like for massif and bbv.
+    pub fn is_unchanged(&self) -> bool {
+        match self {
+            Self::None => true,
+            Self::ErrorTool(summary) => summary.is_unchanged(),
+            Self::Dhat(summary) => summary.is_unchanged(),
+            Self::Callgrind(summary) => summary.is_unchanged(),
+            Self::Cachegrind(summary) => summary.is_unchanged(),
+        }
+    }
+
+    /// Return true if the new instructions of callgrind or cachegrind are zero
+    ///
+    /// Benchmarks without any instructions usually indicate a misconfiguration, for example an
+    /// entry point or toggle which doesn't match any executed function. Always false for the other
+    /// tools, since zero metrics are perfectly valid for them.
+    pub fn is_zero(&self) -> bool {
+        let is_zero = |diff: Option<&MetricsDiff>| {
+            matches!(
+                diff.map(|diff| &diff.metrics),
+                Some(EitherOrBoth::Left(new) | EitherOrBoth::Both(new, _)) if *new == Metric::Int(0)
+            )
+        };
+
+        match self {
+            Self::Callgrind(summary) => is_zero(summary.diff_by_kind(&EventKind::Ir)),
+            Self::Cachegrind(summary) => is_zero(summary.diff_by_kind(&CachegrindMetric::Ir)),
+            Self::None | Self::ErrorTool(_) | Self::Dhat(_) => false,
+        }
+    }
+
     /// Sum up another summary metrics to these metrics
     pub fn add_mut(&mut self, other: &Self) {
         match (self, other) {
@@ -835,6 +1071,25 @@ impl ToolMetricSummary {
         }
     }
 
+    /// Set each metric to the maximum of these and another summary's metrics
+    pub fn max_mut(&mut self, other: &Self) {
+        match (self, other) {
+            (Self::ErrorTool(this), Self::ErrorTool(other)) => {
+                this.max(other);
+            }
+            (Self::Dhat(this), Self::Dhat(other)) => {
+                this.max(other);
+            }
+            (Self::Callgrind(this), Self::Callgrind(other)) => {
+                this.max(other);
+            }
+            (Self::Cachegrind(this), Self::Cachegrind(other)) => {
+                this.max(other);
+            }
+            _ => {}
+        }
+    }
+
     /// Create a new summary from `new` [`ToolMetrics`]
     pub fn from_new_metrics(metrics: &ToolMetrics) -> Self {
         match metrics {
@@ -992,6 +1247,17 @@ impl ToolMetricSummary {
 }
 
 impl ToolRegression {
+    /// Return the severity of the regression as difference in percent
+    ///
+    /// For soft limits this is the difference between the new and old metric. For hard limits
+    /// it's the difference between the new metric and the limit.
+    pub fn severity(&self) -> f64 {
+        match self {
+            Self::Soft { diff_pct, .. } => *diff_pct,
+            Self::Hard { new, limit, .. } => percentage_diff(*new, *limit),
+        }
+    }
+
     /// Create a new `ToolRegression`
     pub fn with<T>(apply: fn(T) -> MetricKind, regressions: RegressionMetrics<T>) -> Self {
         match regressions {
@@ -1011,3 +1277,175 @@ impl ToolRegression {
         }
     }
 }
+
+/// Return the sha256 checksum as lowercase hexadecimal string and the size of the file at `path`
+fn checksum(path: &Path) -> Result<(String, u64)> {
+    let mut file = File::open(path)
+        .with_context(|| format!("Failed to open artifact '{}'", path.display()))?;
+    let mut hasher = Sha256::new();
+    let size = std::io::copy(&mut file, &mut hasher)
+        .with_context(|| format!("Failed to read artifact '{}'", path.display()))?;
+
+    let sha256 = hasher
+        .finalize()
+        .iter()
+        .fold(String::with_capacity(64), |mut hex, byte| {
+            write!(hex, "{byte:02x}").unwrap();
+            hex
+        });
+    Ok((sha256, size))
+}
+
+/// Escape the `value` of an OpenMetrics label
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Format the `metric` as OpenMetrics sample value
+fn openmetrics_value(metric: Metric) -> String {
+    match metric {
+        Metric::Int(value) => value.to_string(),
+        Metric::Float(value) if value.is_nan() => "NaN".to_owned(),
+        Metric::Float(value) if value.is_infinite() => {
+            if value.is_sign_positive() {
+                "+Inf".to_owned()
+            } else {
+                "-Inf".to_owned()
+            }
+        }
+        Metric::Float(value) => value.to_string(),
+    }
+}
+
+/// Write the OpenMetrics samples of the `summary` into `metrics` and `diffs`
+fn write_openmetrics_samples<K>(
+    labels: &str,
+    summary: &MetricsSummary<K>,
+    metrics: &mut String,
+    diffs: &mut String,
+) where
+    K: Hash + Eq + Debug,
+{
+    for (kind, diff) in &summary.0 {
+        let labels = format!("{labels},kind=\"{kind:?}\"");
+        let (new, old) = match diff.metrics {
+            EitherOrBoth::Both(new, old) => (Some(new), Some(old)),
+            EitherOrBoth::Left(new) => (Some(new), None),
+            EitherOrBoth::Right(old) => (None, Some(old)),
+        };
+        for (baseline, metric) in [("new", new), ("old", old)] {
+            if let Some(metric) = metric {
+                writeln!(
+                    metrics,
+                    "iai_callgrind_metric{{{labels},baseline=\"{baseline}\"}} {}",
+                    openmetrics_value(metric)
+                )
+                .unwrap();
+            }
+        }
+        if let Some(Diffs { diff_pct, .. }) = diff.diffs {
+            writeln!(
+                diffs,
+                "iai_callgrind_metric_diff_pct{{{labels}}} {}",
+                openmetrics_value(Metric::Float(diff_pct))
+            )
+            .unwrap();
+        }
+    }
+}
+
+/// Append the metrics of the `summary` of the `tool` to the `metrics` of a [`BenchmarkOutcome`]
+fn push_outcome_metrics<K>(
+    tool: ValgrindTool,
+    summary: &MetricsSummary<K>,
+    metrics: &mut Vec<OutcomeMetric>,
+) where
+    K: Hash + Eq + Debug,
+{
+    for (kind, diff) in &summary.0 {
+        let (new, old) = match diff.metrics {
+            EitherOrBoth::Both(new, old) => (Some(new), Some(old)),
+            EitherOrBoth::Left(new) => (Some(new), None),
+            EitherOrBoth::Right(old) => (None, Some(old)),
+        };
+        metrics.push(OutcomeMetric {
+            kind: format!("{kind:?}"),
+            new: new.map(f64::from),
+            old: old.map(f64::from),
+            tool,
+        });
+    }
+}
+
+/// Return a new [`MetricsSummary`] with only the `metric_kind` if present
+fn retain_metric<K>(summary: &MetricsSummary<K>, metric_kind: &K) -> MetricsSummary<K>
+where
+    K: Hash + Eq + Clone,
+{
+    MetricsSummary(
+        summary
+            .0
+            .get_key_value(metric_kind)
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .into_iter()
+            .collect(),
+    )
+}
+
+/// Detect the model name of the CPU
+///
+/// On linux, the model name is read from `/proc/cpuinfo` and on other platforms with `sysctl`.
+fn detect_cpu() -> Option<String> {
+    if cfg!(target_os = "linux") {
+        std::fs::read_to_string("/proc/cpuinfo")
+            .ok()?
+            .lines()
+            .find_map(|line| {
+                line.split_once(':')
+                    .filter(|(key, _)| key.trim() == "model name")
+                    .map(|(_, value)| value.trim().to_owned())
+            })
+    } else {
+        run_command(Command::new("sysctl").args(["-n", "hw.model"]))
+    }
+}
+
+/// Run the `command` returning the trimmed output on success
+fn run_command(command: &mut Command) -> Option<String> {
+    let output = command.stderr(Stdio::null()).output().ok()?;
+    if output.status.success() {
+        String::from_utf8(output.stdout)
+            .ok()
+            .map(|output| output.trim().to_owned())
+            .filter(|output| !output.is_empty())
+    } else {
+        debug!("Running '{command:?}' failed");
+        None
+    }
+}
+
+/// Run `git` with the `args` in `dir` returning the trimmed output on success
+fn run_git(dir: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+
+    if output.status.success() {
+        String::from_utf8(output.stdout)
+            .ok()
+            .map(|output| output.trim().to_owned())
+    } else {
+        debug!(
+            "Running 'git {}' in '{}' failed",
+            args.join(" "),
+            dir.display()
+        );
+        None
+    }
+}
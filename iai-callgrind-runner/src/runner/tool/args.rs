@@ -65,12 +65,17 @@ pub enum FairSched {
 }
 
 /// The arguments to pass to the valgrind tool
+#[allow(clippy::struct_excessive_bools)]
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ToolArgs {
+    /// If `--collect-jumps=yes` was given to callgrind
+    pub collect_jumps: bool,
     /// The error exit code for error checking tools like `Memcheck`
     pub error_exitcode: String,
     /// The --fair-sched argument
     pub fair_sched: FairSched,
+    /// If true, pass `--gen-suppressions=all` to collect the suppressions from the log files
+    pub gen_suppressions: bool,
     /// The logfile paths argument --log-file
     pub log_path: Option<OsString>,
     /// All other arguments
@@ -138,6 +143,8 @@ impl ToolArgs {
             other: Vec::default(),
             trace_children: defaults::TRACE_CHILDREN,
             fair_sched: defaults::FAIR_SCHED,
+            gen_suppressions: false,
+            collect_jumps: false,
         };
 
         for args in raw_args {
@@ -155,6 +162,26 @@ impl ToolArgs {
                     Some(("--fair-sched", value)) => {
                         tool_args.fair_sched = FairSched::from_str(value)?;
                     }
+                    Some(("--gen-suppressions", value)) => {
+                        tool_args.gen_suppressions = match value {
+                            "all" => true,
+                            "no" => false,
+                            // The interactive mode would wait for input which never arrives
+                            "yes" => {
+                                warn!(
+                                    "Using --gen-suppressions=all instead of the interactive \
+                                     --gen-suppressions=yes"
+                                );
+                                true
+                            }
+                            _ => {
+                                return Err(anyhow!(
+                                    "Invalid argument for --gen-suppressions. Valid arguments \
+                                     are: 'yes', 'no', 'all'"
+                                ))
+                            }
+                        };
+                    }
                     Some((arg, _)) if is_ignored_outfile_argument(arg) => warn!(
                         "Ignoring {} argument '{arg}': Output/Log files of tools are managed by \
                          Iai-Callgrind",
@@ -323,6 +350,9 @@ impl ToolArgs {
         if self.verbose {
             vec.push("--verbose".into());
         }
+        if self.gen_suppressions {
+            vec.push("--gen-suppressions=all".into());
+        }
 
         vec.extend(self.other.iter().map(OsString::from));
         vec.extend_from_slice(&self.output_paths);
@@ -0,0 +1,431 @@
+//! The module containing the [`IterBatch`]
+
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::PathBuf;
+use std::process::Output;
+use std::sync::{Arc, Mutex};
+
+use anyhow::{anyhow, Result};
+use log::debug;
+use tempfile::TempDir;
+
+use super::config::ToolConfig;
+use super::path::ToolOutputPath;
+use super::run::{RunOptions, ToolOutput};
+use crate::api::{EntryPoint, ValgrindTool};
+use crate::runner::callgrind::parser::parse_header;
+use crate::runner::meta::Metadata;
+
+/// The callgrind argument to dump the metrics before each separator between two elements
+///
+/// The separator is the `__iai_callgrind_iter_separator` function of the `iai-callgrind` library.
+const DUMP_BEFORE_SEPARATOR: &str = "--dump-before=*::__iai_callgrind_iter_separator";
+
+/// Run all elements of an `iter` benchmark in a single callgrind process
+///
+/// The `iter` expression of a library benchmark is evaluated in the benchmark process. If each
+/// element runs in its own process, the process advances the iterator to its element, so all
+/// elements before it are produced again. Instead, the benchmark process of the `IterBatch` runs
+/// all elements one after another. Callgrind dumps the metrics before each separator between two
+/// elements and the resulting parts are handed out to the elements as if each element had run in
+/// its own process.
+///
+/// All elements share the process, so the caches of the cache simulation and the heap are warm
+/// after the first element. The process setup and teardown of the benchmark harness run only
+/// once per batch (see `process_setup` of the `main!` macro).
+#[derive(Debug)]
+pub struct IterBatch {
+    args: Vec<OsString>,
+    len: usize,
+    state: Mutex<Option<BatchState>>,
+}
+
+/// An element of an [`IterBatch`]
+#[derive(Debug, Clone)]
+pub struct IterBatchElement {
+    /// The batch shared by all elements of the `iter` benchmark
+    pub batch: Arc<IterBatch>,
+    /// The index of the element in the iterator
+    pub index: usize,
+}
+
+/// The state of an [`IterBatch`] after the benchmark process has run
+#[derive(Debug)]
+enum BatchState {
+    /// The output files in the temporary directory are ready to be handed out to the elements
+    Done {
+        /// Keeps the temporary directory alive until all elements have their output files
+        _dir: TempDir,
+        /// The log files with the suffix after the `<tool>.<name>` prefix
+        logs: Vec<(PathBuf, String)>,
+        /// The output of the benchmark process, handed out to the first element only
+        output: Option<Output>,
+        /// The output files of each element
+        parts: Vec<Vec<PartFile>>,
+    },
+    /// Running the benchmark process failed
+    Failed,
+    /// The output files couldn't be assigned to the elements
+    Unusable,
+}
+
+/// An output file of a callgrind part of an [`IterBatch`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PartFile {
+    path: PathBuf,
+    pid: Option<i32>,
+    thread: Option<usize>,
+}
+
+impl IterBatch {
+    /// Create a new `IterBatch` running the benchmark harness with `args` for `len` elements
+    pub fn new(args: Vec<OsString>, len: usize) -> Self {
+        Self {
+            args,
+            len,
+            state: Mutex::new(None),
+        }
+    }
+
+    /// Return true if the elements can run in a batch with the `tool_config` and `run_options`
+    ///
+    /// Only the callgrind output can be split into the elements. Without an entry point, the
+    /// metrics of the first element would include everything before the first element.
+    pub fn is_supported(
+        tool_config: &ToolConfig,
+        run_options: &RunOptions,
+        meta: &Metadata,
+    ) -> bool {
+        tool_config.tool == ValgrindTool::Callgrind
+            && tool_config.entry_point != EntryPoint::None
+            && run_options.args_template.is_none()
+            && run_options.setup.is_none()
+            && run_options.steps.is_empty()
+            && run_options.vgdb.is_none()
+            && meta.args.verify_determinism.is_none()
+    }
+
+    /// Write the output files of the element at `index` to the `output_path`
+    ///
+    /// The benchmark process of the batch is run with `run` on the first call, which receives the
+    /// [`ToolConfig`] with the additional callgrind arguments, the arguments for the benchmark
+    /// harness and the temporary [`ToolOutputPath`] of the batch. The returned [`ToolOutput`]
+    /// carries the output of the benchmark process only for the element which has run the
+    /// process, which is signalled with `true`. The resource usage of the process can't be
+    /// attributed to a single element and is not returned.
+    ///
+    /// Returns `None` if the output files of the batch can't be assigned to the elements, for
+    /// example if the benchmark spawns subprocesses. The element has to run in its own process
+    /// then.
+    pub fn output<F>(
+        &self,
+        index: usize,
+        tool_config: &ToolConfig,
+        output_path: &ToolOutputPath,
+        run: F,
+    ) -> Result<Option<(ToolOutput, bool)>>
+    where
+        F: FnOnce(ToolConfig, &[OsString], &ToolOutputPath) -> Result<ToolOutput>,
+    {
+        let mut state = self.state.lock().unwrap();
+        let has_run = state.is_none();
+        if has_run {
+            let dir = tempfile::tempdir()?;
+            let batch_path = ToolOutputPath {
+                dir: dir.path().to_owned(),
+                ..output_path.clone()
+            };
+
+            let mut config = tool_config.clone();
+            config.args.other.push(DUMP_BEFORE_SEPARATOR.to_owned());
+            let tool_output = match run(config, &self.args, &batch_path) {
+                Ok(tool_output) => tool_output,
+                Err(error) => {
+                    *state = Some(BatchState::Failed);
+                    return Err(error);
+                }
+            };
+
+            *state = Some(
+                if let Some((parts, logs)) = Self::split(&batch_path, self.len)? {
+                    BatchState::Done {
+                        _dir: dir,
+                        logs,
+                        output: tool_output.output,
+                        parts,
+                    }
+                } else {
+                    debug!(
+                        "{}: The output of the batch can't be split into {} elements. Running \
+                         each element in its own process.",
+                        tool_config.tool.id(),
+                        self.len
+                    );
+                    BatchState::Unusable
+                },
+            );
+        }
+
+        match state.as_mut().expect("The batch state should be present") {
+            BatchState::Done {
+                logs,
+                output,
+                parts,
+                ..
+            } => {
+                for file in std::mem::take(&mut parts[index]) {
+                    write_part(&file, output_path)?;
+                }
+                for (path, suffix) in logs.iter() {
+                    std::fs::copy(
+                        path,
+                        output_path
+                            .dir
+                            .join(format!("{}{suffix}", output_path.prefix())),
+                    )?;
+                }
+
+                Ok(Some((
+                    ToolOutput {
+                        output: output.take(),
+                        resource_usage: None,
+                        tool: tool_config.tool,
+                    },
+                    has_run,
+                )))
+            }
+            BatchState::Failed => Err(anyhow!(
+                "{}: The benchmark process of all elements has failed",
+                tool_config.tool.id()
+            )),
+            BatchState::Unusable => Ok(None),
+        }
+    }
+
+    /// Group the callgrind output files of the batch by part
+    ///
+    /// Each part is the output of an element. Returns `None` if the parts don't match the elements
+    /// or if there is more than a single process.
+    #[allow(clippy::type_complexity)]
+    fn split(
+        batch_path: &ToolOutputPath,
+        len: usize,
+    ) -> Result<Option<(Vec<Vec<PartFile>>, Vec<(PathBuf, String)>)>> {
+        let mut logs = vec![];
+        let mut parts: BTreeMap<u64, Vec<PartFile>> = BTreeMap::new();
+        let mut pids = vec![];
+        for entry in batch_path.walk_dir()? {
+            let file_name = entry.file_name();
+            let file_name = file_name.to_string_lossy();
+            let Some(suffix) = batch_path.strip_prefix(&file_name) else {
+                continue;
+            };
+
+            if suffix.starts_with(".log") {
+                logs.push((entry.path(), suffix.to_owned()));
+            } else if suffix.starts_with(".out") {
+                // Callgrind sometimes creates empty files for no reason
+                if entry.metadata()?.len() == 0 {
+                    continue;
+                }
+
+                let properties = parse_header(
+                    &mut BufReader::new(File::open(entry.path())?)
+                        .lines()
+                        .map(Result::unwrap),
+                )?;
+                let Some(part) = properties.part else {
+                    return Ok(None);
+                };
+                if !pids.contains(&properties.pid) {
+                    pids.push(properties.pid);
+                }
+
+                parts.entry(part).or_default().push(PartFile {
+                    path: entry.path(),
+                    pid: properties.pid,
+                    thread: properties.thread,
+                });
+            } else {
+                // do nothing
+            }
+        }
+
+        if pids.len() != 1 || parts.len() != len {
+            return Ok(None);
+        }
+
+        Ok(Some((parts.into_values().collect(), logs)))
+    }
+}
+
+impl IterBatchElement {
+    /// Create a new `IterBatchElement` for the element at `index` of the `batch`
+    pub fn new(batch: Arc<IterBatch>, index: usize) -> Self {
+        Self { batch, index }
+    }
+}
+
+/// Write the part `file` as single part output file to the `output_path`
+///
+/// The file is named like an original callgrind output file, so it is renamed by
+/// [`ToolOutputPath::sanitize`] like the output files of a benchmark running in its own process.
+fn write_part(file: &PartFile, output_path: &ToolOutputPath) -> Result<()> {
+    let mut dest = output_path
+        .with_modifiers(file.pid.map(|pid| format!("#{pid}")))
+        .to_path()
+        .into_os_string();
+    if let Some(thread) = file.thread {
+        dest.push(format!("-{thread:02}"));
+    }
+
+    let mut reader = BufReader::new(File::open(&file.path)?);
+    let mut writer = BufWriter::new(File::create(dest)?);
+
+    // Only the header has to be rewritten, so the rest of the file is copied as is
+    let mut line = String::new();
+    while reader.read_line(&mut line)? > 0 {
+        if line.starts_with("part:") {
+            writer.write_all(b"part: 1\n")?;
+            break;
+        }
+        writer.write_all(line.as_bytes())?;
+        line.clear();
+    }
+    std::io::copy(&mut reader, &mut writer)?;
+    writer.flush()?;
+
+    std::fs::remove_file(&file.path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::runner::summary::BaselineKind;
+    use crate::runner::tool::path::ToolOutputPathKind;
+
+    fn output_path(dir: &Path, name: &str) -> ToolOutputPath {
+        ToolOutputPath {
+            baseline_kind: BaselineKind::Old,
+            dir: dir.to_owned(),
+            kind: ToolOutputPathKind::Out,
+            modifiers: vec![],
+            name: name.to_owned(),
+            tool: ValgrindTool::Callgrind,
+        }
+    }
+
+    fn write_out(path: &ToolOutputPath, suffix: &str, pid: i32, part: u64, thread: usize) {
+        std::fs::write(
+            path.dir.join(format!("{}{suffix}", path.prefix())),
+            format!(
+                "# callgrind format\nversion: 1\ncreator: callgrind-3.25.1\npid: {pid}\ncmd: \
+                 bench\npart: {part}\nthread: {thread}\n\ndesc: I1 cache:\npositions: \
+                 line\nevents: Ir\nsummary: {part}\n\nfl=file.rs\n"
+            ),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_split_by_part() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = output_path(dir.path(), "batch");
+        write_out(&path, ".out.#100.1", 100, 1, 1);
+        write_out(&path, ".out.#100.2", 100, 2, 1);
+        write_out(&path, ".out.#100", 100, 3, 1);
+        std::fs::write(dir.path().join("callgrind.batch.log.#100"), "log").unwrap();
+
+        let (parts, logs) = IterBatch::split(&path, 3).unwrap().unwrap();
+
+        assert_eq!(
+            parts,
+            vec![
+                vec![PartFile {
+                    path: dir.path().join("callgrind.batch.out.#100.1"),
+                    pid: Some(100),
+                    thread: Some(1),
+                }],
+                vec![PartFile {
+                    path: dir.path().join("callgrind.batch.out.#100.2"),
+                    pid: Some(100),
+                    thread: Some(1),
+                }],
+                vec![PartFile {
+                    path: dir.path().join("callgrind.batch.out.#100"),
+                    pid: Some(100),
+                    thread: Some(1),
+                }],
+            ]
+        );
+        assert_eq!(
+            logs,
+            vec![(
+                dir.path().join("callgrind.batch.log.#100"),
+                ".log.#100".to_owned()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_split_when_parts_do_not_match_elements() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = output_path(dir.path(), "batch");
+        write_out(&path, ".out.#100.1", 100, 1, 1);
+        write_out(&path, ".out.#100", 100, 2, 1);
+
+        assert_eq!(IterBatch::split(&path, 3).unwrap(), None);
+    }
+
+    #[test]
+    fn test_split_when_multiple_processes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = output_path(dir.path(), "batch");
+        write_out(&path, ".out.#100.1", 100, 1, 1);
+        write_out(&path, ".out.#100", 100, 2, 1);
+        write_out(&path, ".out.#101", 101, 1, 1);
+
+        assert_eq!(IterBatch::split(&path, 2).unwrap(), None);
+    }
+
+    #[test]
+    fn test_write_part_then_sanitize() {
+        let batch_dir = tempfile::tempdir().unwrap();
+        let batch_path = output_path(batch_dir.path(), "batch");
+        write_out(&batch_path, ".out.#100.2-02", 100, 2, 2);
+        write_out(&batch_path, ".out.#100.2-01", 100, 2, 1);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = output_path(dir.path(), "bench");
+        let (parts, _) = IterBatch::split(&batch_path, 1).unwrap().unwrap();
+        for file in &parts[0] {
+            write_part(file, &path).unwrap();
+        }
+        path.sanitize().unwrap();
+
+        let mut real_paths = path.real_paths().unwrap();
+        real_paths.sort();
+        assert_eq!(
+            real_paths,
+            vec![
+                dir.path().join("callgrind.bench.t1.p1.out"),
+                dir.path().join("callgrind.bench.t2.p1.out")
+            ]
+        );
+        let content = std::fs::read_to_string(&real_paths[1]).unwrap();
+        assert!(content.contains("\npart: 1\n"));
+        assert!(content.contains("\nthread: 2\n"));
+        assert!(!batch_dir
+            .path()
+            .join("callgrind.batch.out.#100.2-02")
+            .exists());
+    }
+}
@@ -0,0 +1,223 @@
+//! The module containing the cache of the parsed baselines
+//!
+//! Parsing the output files of a baseline can be expensive, especially if the callgrind output
+//! files are big or the details of the entry point are collected. Named baselines usually don't
+//! change between benchmark runs, so the parsed [`ParserOutput`]s are stored next to the baseline
+//! files in `<tool>.<name>.parsed.base@<baseline>.json` and reused as long as the baseline files
+//! and the parser configuration stay the same. The `.old` files are overwritten by each run and
+//! therefore not cached.
+
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use anyhow::Result;
+use log::{debug, trace};
+use serde::{Deserialize, Serialize};
+
+use super::parser::{Parser, ParserOutput};
+use super::path::{ToolOutputPath, ToolOutputPathKind};
+
+/// The content of the cache file of a baseline
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct BaselineCache {
+    /// The baseline files which were parsed
+    files: Vec<CachedFile>,
+    /// The fingerprint of the parser configuration and the runner version
+    key: String,
+    /// The outputs of the parser
+    outputs: Vec<ParserOutput>,
+}
+
+/// The identity of a parsed baseline file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct CachedFile {
+    modified: Option<(u64, u32)>,
+    path: PathBuf,
+    size: u64,
+}
+
+impl CachedFile {
+    fn new(path: &Path) -> Result<Self> {
+        let metadata = std::fs::metadata(path)?;
+        let modified = metadata
+            .modified()
+            .ok()
+            .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+            .map(|duration| (duration.as_secs(), duration.subsec_nanos()));
+
+        Ok(Self {
+            path: path.to_owned(),
+            size: metadata.len(),
+            modified,
+        })
+    }
+}
+
+/// Return the path of the cache file of the baseline `output_path` if it can be cached
+pub fn cache_path(output_path: &ToolOutputPath) -> Option<PathBuf> {
+    match output_path.kind {
+        ToolOutputPathKind::BaseOut(_) | ToolOutputPathKind::BaseLog(_) => {
+            let base = output_path.with_modifiers(Vec::<String>::new());
+            Some(base.to_artifact_path(&base.to_path(), "parsed", "json"))
+        }
+        _ => None,
+    }
+}
+
+/// Parse the baseline `output_path` with the `parser` reusing the cached outputs if possible
+///
+/// The `key` identifies the configuration of the `parser`. The cached outputs are only used if the
+/// `key` and all baseline files are unchanged. Otherwise, the baseline is parsed and the cache is
+/// updated. Failing to read or write the cache is not an error, the baseline is parsed instead.
+///
+/// # Errors
+///
+/// Returns an error if the baseline files can't be parsed
+pub fn parse<P>(parser: &P, output_path: &ToolOutputPath, key: &str) -> Result<Vec<ParserOutput>>
+where
+    P: Parser + ?Sized,
+{
+    let Some(cache_path) = cache_path(output_path) else {
+        return parser.parse_with(output_path);
+    };
+
+    let files = output_path.real_paths().and_then(|paths| {
+        paths
+            .iter()
+            .map(|path| CachedFile::new(path))
+            .collect::<Result<Vec<_>>>()
+    });
+    let Ok(files) = files else {
+        return parser.parse_with(output_path);
+    };
+
+    if let Some(outputs) = load(&cache_path, key, &files) {
+        debug!(
+            "{}: Using the cached baseline '{}'",
+            output_path.tool.id(),
+            cache_path.display()
+        );
+        return Ok(outputs);
+    }
+
+    let outputs = parser.parse_with(output_path)?;
+    if files.is_empty() {
+        return Ok(outputs);
+    }
+
+    let cache = BaselineCache {
+        key: key.to_owned(),
+        files,
+        outputs,
+    };
+    if let Err(error) = store(&cache_path, &cache) {
+        trace!(
+            "Failed to write the baseline cache '{}': {error}",
+            cache_path.display()
+        );
+    }
+
+    Ok(cache.outputs)
+}
+
+fn load(path: &Path, key: &str, files: &[CachedFile]) -> Option<Vec<ParserOutput>> {
+    let file = File::open(path).ok()?;
+    let cache: BaselineCache = serde_json::from_reader(BufReader::new(file)).ok()?;
+    (cache.key == key && cache.files == files).then_some(cache.outputs)
+}
+
+fn store(path: &Path, cache: &BaselineCache) -> Result<()> {
+    let file = File::create(path)?;
+    serde_json::to_writer(BufWriter::new(file), cache)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::api::ValgrindTool;
+    use crate::runner::common::ModulePath;
+    use crate::runner::summary::{BaselineKind, ToolMetrics};
+    use crate::runner::tool::parser::Header;
+
+    #[derive(Debug)]
+    struct TestParser(ToolOutputPath);
+
+    impl Parser for TestParser {
+        fn get_output_path(&self) -> &ToolOutputPath {
+            &self.0
+        }
+
+        fn parse_single(&self, path: PathBuf) -> Result<ParserOutput> {
+            let content = std::fs::read_to_string(&path)?;
+            Ok(ParserOutput {
+                details: vec![content],
+                header: Header {
+                    command: "bench".to_owned(),
+                    desc: vec![],
+                    parent_pid: None,
+                    part: None,
+                    pid: 1,
+                    thread: None,
+                },
+                metrics: ToolMetrics::None,
+                path,
+            })
+        }
+    }
+
+    #[test]
+    fn test_parse_uses_and_invalidates_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let output_path = ToolOutputPath::new(
+            ToolOutputPathKind::Out,
+            ValgrindTool::Callgrind,
+            &BaselineKind::Name("main".parse().unwrap()),
+            dir.path(),
+            &ModulePath::new("bench"),
+            "id",
+        )
+        .to_base_path();
+        std::fs::create_dir_all(&output_path.dir).unwrap();
+        let base_file = output_path.dir.join("callgrind.id.out.base@main");
+        std::fs::write(&base_file, "first").unwrap();
+
+        let parser = TestParser(output_path.clone());
+        let outputs = parse(&parser, &output_path, "key").unwrap();
+        assert_eq!(outputs[0].details, vec!["first".to_owned()]);
+
+        let cache_path = cache_path(&output_path).unwrap();
+        assert_eq!(
+            cache_path,
+            output_path.dir.join("callgrind.id.parsed.base@main.json")
+        );
+        assert!(cache_path.exists());
+
+        // The cached outputs are returned without parsing the files
+        let mut cache: BaselineCache =
+            serde_json::from_reader(File::open(&cache_path).unwrap()).unwrap();
+        cache.outputs[0].details = vec!["cached".to_owned()];
+        store(&cache_path, &cache).unwrap();
+        assert_eq!(
+            parse(&parser, &output_path, "key").unwrap()[0].details,
+            vec!["cached".to_owned()]
+        );
+
+        // A different parser configuration invalidates the cache
+        assert_eq!(
+            parse(&parser, &output_path, "other").unwrap()[0].details,
+            vec!["first".to_owned()]
+        );
+
+        // A changed baseline file invalidates the cache
+        std::fs::write(&base_file, "changed").unwrap();
+        assert_eq!(
+            parse(&parser, &output_path, "other").unwrap()[0].details,
+            vec!["changed".to_owned()]
+        );
+    }
+}
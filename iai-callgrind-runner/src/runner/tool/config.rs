@@ -1,33 +1,50 @@
 //! The module containing the [`ToolConfig`] and other related elements
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::ffi::OsString;
+use std::fmt::Display;
+use std::hash::Hash;
 use std::io::stderr;
 use std::path::Path;
+use std::time::{Duration, Instant};
 
 use anyhow::{anyhow, Result};
+use itertools::Itertools;
+use log::debug;
 
-use super::args::ToolArgs;
-use super::parser::{parser_factory, ParserOutput};
+use super::args::{defaults, ToolArgs};
+use super::batch::IterBatch;
+use super::cache;
+use super::parser::{parser_factory, Parser, ParserOutput};
 use super::path::ToolOutputPath;
 use super::regression::{RegressionConfig, ToolRegressionConfig};
-use super::run::{RunOptions, ToolCommand};
-use crate::api::{self, EntryPoint, RawArgs, Tool, Tools, ValgrindTool};
-use crate::runner::args::NoCapture;
+use super::run::{RunOptions, ToolCommand, ToolOutput};
+use super::suppressions;
+use crate::api::{
+    self, EntryPoint, EventKind, RawArgs, ThreadSelection, Tool, Tools, TotalAggregation,
+    ValgrindTool,
+};
+use crate::error::Error;
+use crate::runner::args::{MeasurementProfile, NoCapture};
 use crate::runner::callgrind::flamegraph::{
     BaselineFlamegraphGenerator, Config as FlamegraphConfig, Flamegraph, FlamegraphGenerator,
     LoadBaselineFlamegraphGenerator, SaveBaselineFlamegraphGenerator,
 };
-use crate::runner::callgrind::parser::Sentinel;
+use crate::runner::callgrind::parser::{contains_function, Sentinel};
 use crate::runner::common::{Baselines, Config, ModulePath, Sandbox};
-use crate::runner::format::{print_no_capture_footer, Formatter, OutputFormat, VerticalFormatter};
+use crate::runner::format::{
+    print_no_capture_footer, print_regressions, print_suspicious, Formatter, OutputFormat,
+    VerticalFormatter,
+};
 use crate::runner::meta::Metadata;
+use crate::runner::metrics::Metrics;
+use crate::runner::pipeline::Job;
 use crate::runner::summary::{
-    BaselineKind, BaselineName, BenchmarkSummary, Profile, ProfileData, ProfileTotal,
-    ToolMetricSummary, ToolRegression,
+    ArtifactKind, ArtifactRegistry, BaselineKind, BaselineName, BenchmarkSummary, Profile,
+    ProfileData, ProfileTotal, ToolMetricSummary, ToolMetrics, ToolRegression,
 };
-use crate::runner::{cachegrind, callgrind, DEFAULT_TOGGLE};
-use crate::util::Glob;
+use crate::runner::{cachegrind, callgrind, dhat, massif, DEFAULT_TOGGLE};
+use crate::util::{self, bool_to_yesno, make_absolute, Glob};
 
 /// The tool specific flamegraph configuration
 #[derive(Debug, Clone, PartialEq)]
@@ -45,6 +62,8 @@ pub struct ToolConfig {
     pub args: ToolArgs,
     /// The [`EntryPoint`] of this tool
     pub entry_point: EntryPoint,
+    /// If true, the errors of this error checking tool fail the benchmark run
+    pub fail_on_errors: bool,
     /// The tool specific flamegraph configuration
     pub flamegraph_config: ToolFlamegraphConfig,
     /// The [`Glob`] patterns used to matched a function in the call stack of a program point
@@ -55,13 +74,18 @@ pub struct ToolConfig {
     pub is_enabled: bool,
     /// The tool specific regression check configuration
     pub regression_config: ToolRegressionConfig,
+    /// The threads which are included in the total
+    pub thread_selection: ThreadSelection,
     /// The [`ValgrindTool`]
     pub tool: ValgrindTool,
+    /// How the total is computed
+    pub total_aggregation: TotalAggregation,
 }
 
 #[derive(Debug)]
 struct ToolConfigBuilder {
     entry_point: Option<EntryPoint>,
+    fail_on_errors: bool,
     flamegraph_config: ToolFlamegraphConfig,
     frames: Vec<String>,
     is_default: bool,
@@ -69,13 +93,26 @@ struct ToolConfigBuilder {
     kind: ValgrindTool,
     raw_args: RawArgs,
     regression_config: ToolRegressionConfig,
+    thread_selection: ThreadSelection,
     tool: Option<Tool>,
+    total_aggregation: TotalAggregation,
 }
 
 /// Multiple [`ToolConfig`]s
 #[derive(Debug, Clone)]
 pub struct ToolConfigs(pub Vec<ToolConfig>);
 
+/// The valgrind run of a tool whose output files still need to be parsed
+struct ToolRun<'a> {
+    log_path: ToolOutputPath,
+    output_path: ToolOutputPath,
+    outputs: Vec<ToolOutput>,
+    parse_time: Duration,
+    parsed_old: Option<Vec<ParserOutput>>,
+    run_time: Duration,
+    tool_config: &'a ToolConfig,
+}
+
 impl ToolConfig {
     /// Create a new `ToolConfig`
     pub fn new(
@@ -87,50 +124,188 @@ impl ToolConfig {
         entry_point: EntryPoint,
         is_default: bool,
         frames: Vec<Glob>,
+        fail_on_errors: bool,
+        thread_selection: ThreadSelection,
+        total_aggregation: TotalAggregation,
     ) -> Self {
         Self {
             args,
             entry_point,
+            fail_on_errors,
             flamegraph_config,
             frames,
             is_default,
             is_enabled,
             regression_config,
+            thread_selection,
             tool,
+            total_aggregation,
+        }
+    }
+
+    /// Create the artifacts of a tool run which are configured to be created
+    ///
+    /// The artifacts aren't part of the [`BenchmarkSummary`] but are created from the `output_path`
+    /// and `log_path` of the tool run. All created files are registered in the `registry`.
+    fn create_artifacts(
+        &self,
+        config: &Config,
+        output_path: &ToolOutputPath,
+        log_path: &ToolOutputPath,
+        title: &str,
+        registry: &ArtifactRegistry,
+    ) -> Result<()> {
+        let project_root = &config.meta.project_root;
+        if self.tool == ValgrindTool::Callgrind {
+            if let Some(path) = callgrind::merge::create(output_path)? {
+                debug!("Created merged callgrind output file: '{}'", path.display());
+                registry.register(ArtifactKind::Merged, [path]);
+            }
+            for path in callgrind::call_tree::create(output_path, title, project_root)? {
+                debug!("Created callgrind call tree report: '{}'", path.display());
+                registry.register(ArtifactKind::CallTree, [path]);
+            }
+        }
+
+        if self.tool == ValgrindTool::Callgrind && self.args.collect_jumps {
+            for path in callgrind::branches::create(output_path, title, project_root)? {
+                debug!("Created callgrind branch report: '{}'", path.display());
+                registry.register(ArtifactKind::Branches, [path]);
+            }
         }
+
+        if self.tool == ValgrindTool::DHAT && config.meta.args.dhat_heap_summary {
+            for path in dhat::heap_summary::create(output_path, title)? {
+                debug!("Created dhat heap summary: '{}'", path.display());
+                registry.register(ArtifactKind::Chart, [path]);
+            }
+        }
+
+        if self.tool == ValgrindTool::Massif && config.meta.args.massif_chart {
+            for (kind, path) in massif::chart::create(output_path, title)? {
+                debug!("Created massif chart: '{}'", path.display());
+                registry.register(kind, [path]);
+            }
+        }
+
+        if self.args.gen_suppressions {
+            for path in suppressions::create(log_path)? {
+                debug!("Created suppression file: '{}'", path.display());
+                registry.register(ArtifactKind::Suppressions, [path]);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Parse the "old" or "base" files of the `parser` reusing the cached outputs of a baseline
+    ///
+    /// See [`cache::parse`]. The cache key includes the runner version and everything of this
+    /// configuration which changes the outputs of the parser.
+    pub fn parse_base(&self, parser: &dyn Parser, meta: &Metadata) -> Result<Vec<ParserOutput>> {
+        let key = format!(
+            "{}:{}:{:?}:{:?}:{}",
+            env!("CARGO_PKG_VERSION"),
+            self.tool.id(),
+            self.entry_point,
+            self.frames,
+            meta.project_root.display()
+        );
+        cache::parse(
+            parser,
+            &parser.get_output_path().to_base_path(),
+            key.as_str(),
+        )
     }
 
     /// Parse the [`Profile`] from profile data or log files
+    ///
+    /// If present, the `overhead` of the benchmark harness is subtracted from the callgrind metrics
+    /// of the main process of the new and old data.
     pub fn parse(
         &self,
         meta: &Metadata,
         output_path: &ToolOutputPath,
         parsed_old: Option<Vec<ParserOutput>>,
+        overhead: Option<&Metrics<EventKind>>,
     ) -> Result<Profile> {
         let parser = parser_factory(self, meta.project_root.clone(), output_path);
 
-        let parsed_new = parser.parse()?;
-        let parsed_old = if let Some(parsed_old) = parsed_old {
+        let mut parsed_new = parser.parse()?;
+        let mut parsed_old = if let Some(parsed_old) = parsed_old {
             parsed_old
         } else {
-            parser.parse_base()?
+            self.parse_base(parser.as_ref(), meta)?
         };
 
+        if let Some(overhead) = overhead {
+            for output in parsed_new
+                .first_mut()
+                .into_iter()
+                .chain(parsed_old.first_mut())
+            {
+                if let ToolMetrics::Callgrind(metrics) = &mut output.metrics {
+                    metrics.subtract(overhead);
+                }
+            }
+        }
+
+        let mut excluded = HashSet::new();
+        for output in parsed_new.iter_mut().chain(parsed_old.iter_mut()) {
+            if !self.is_selected(output)? {
+                output
+                    .details
+                    .push("This thread is not included in the total".to_owned());
+                excluded.insert(output.path.clone());
+            }
+        }
+        let is_selected = |output: &ParserOutput| !excluded.contains(&output.path);
+
         let data = match (parsed_new.is_empty(), parsed_old.is_empty()) {
             (true, false | true) => return Err(anyhow!("A new dataset should always be present")),
-            (false, true) => ProfileData::new(parsed_new, None),
-            (false, false) => ProfileData::new(parsed_new, Some(parsed_old)),
+            (false, true) => {
+                ProfileData::with_selection(parsed_new, None, is_selected, self.total_aggregation)
+            }
+            (false, false) => ProfileData::with_selection(
+                parsed_new,
+                Some(parsed_old),
+                is_selected,
+                self.total_aggregation,
+            ),
         };
 
         Ok(Profile {
+            fail_on_errors: self.fail_on_errors,
             tool: self.tool,
             log_paths: output_path.to_log_output().real_paths()?,
             out_paths: output_path.real_paths()?,
+            resource_usage: None,
+            suspicious: data.total.summary.is_zero(),
             summaries: data,
             flamegraphs: vec![],
         })
     }
 
+    /// Return true if the [`ThreadSelection`] and [`TotalAggregation`] include the parsed output
+    /// in the total
+    fn is_selected(&self, output: &ParserOutput) -> Result<bool> {
+        if self.total_aggregation == TotalAggregation::ExcludeMainThread
+            && output.header.thread == Some(1)
+        {
+            return Ok(false);
+        }
+
+        match &self.thread_selection {
+            ThreadSelection::All => Ok(true),
+            ThreadSelection::Only(thread) => {
+                Ok(output.header.thread.map_or(true, |t| t == *thread))
+            }
+            ThreadSelection::Function(function) => {
+                contains_function(&output.path, &Sentinel::new(function))
+            }
+        }
+    }
+
     fn print(
         &self,
         config: &Config,
@@ -169,6 +344,9 @@ impl ToolConfigBuilder {
             self.entry_point.unwrap_or(EntryPoint::None),
             self.is_default,
             self.frames.iter().map(Into::into).collect(),
+            self.fail_on_errors,
+            self.thread_selection,
+            self.total_aggregation,
         ))
     }
 
@@ -254,6 +432,38 @@ impl ToolConfigBuilder {
         }
     }
 
+    /// Expand the environment variables like `${HOME}` in the raw arguments
+    fn expand_env_vars(&mut self, module_path: &ModulePath, id: Option<&String>) -> Result<()> {
+        for arg in &mut self.raw_args.0 {
+            *arg =
+                util::expand_env_vars(arg, |name| std::env::var(name).ok()).map_err(|error| {
+                    Error::ConfigurationError(module_path.clone(), id.cloned(), error)
+                })?;
+        }
+
+        Ok(())
+    }
+
+    /// Configure the error checking tools to collect the errors of all benchmarks
+    ///
+    /// If `fail_on_errors` is configured, the benchmark doesn't fail immediately with the
+    /// `--error-exitcode` of valgrind. Instead, the errors are reported in the summary after all
+    /// benchmarks and fail the benchmark run if `fail_on_errors` is true. An explicitly given
+    /// `--error-exitcode` still takes precedence.
+    fn fail_on_errors(&mut self) {
+        if !matches!(
+            self.kind,
+            ValgrindTool::Memcheck | ValgrindTool::Helgrind | ValgrindTool::DRD
+        ) {
+            return;
+        }
+
+        if let Some(fail_on_errors) = self.tool.as_ref().and_then(|t| t.fail_on_errors) {
+            self.raw_args.extend_ignore_flag(["error-exitcode=0"]);
+            self.fail_on_errors = fail_on_errors;
+        }
+    }
+
     fn flamegraph_config(&mut self) {
         if let Some(tool) = &self.tool {
             if let Some(flamegraph_config) = &tool.flamegraph_config {
@@ -294,6 +504,7 @@ impl ToolConfigBuilder {
             is_enabled: is_default || tool.as_ref().map_or(true, |t| t.enable.unwrap_or(true)),
             tool,
             entry_point: Option::default(),
+            fail_on_errors: false,
             flamegraph_config: ToolFlamegraphConfig::None,
             frames: Vec::default(),
             is_default,
@@ -302,21 +513,53 @@ impl ToolConfigBuilder {
                 .cloned()
                 .unwrap_or_default(),
             regression_config: ToolRegressionConfig::None,
+            thread_selection: ThreadSelection::All,
+            total_aggregation: TotalAggregation::Sum,
             kind: valgrind_tool,
         };
 
         // Since the construction sequence is currently always the same, the construction of the
         // `ToolConfig` can happen here in one go instead of having a separate director for it.
+        builder.fail_on_errors();
         builder.valgrind_args(valgrind_args);
         builder.entry_point(default_entry_point, module_path, id);
         builder.tool_args();
-        builder.meta_args(meta);
+        builder.thread_selection();
+        builder.total_aggregation();
+        builder.suppressions(meta)?;
         builder.flamegraph_config();
+        builder.profile(meta);
+        builder.meta_args(meta);
+        builder.expand_env_vars(module_path, id)?;
         builder.regression_config(meta)?;
 
         Ok(builder)
     }
 
+    /// Apply the measurement profile of the `--profile` argument to callgrind
+    ///
+    /// The profile overrides the configuration in the benchmark files but not the `--*-args`
+    /// arguments from the command-line.
+    fn profile(&mut self, meta: &Metadata) {
+        if self.kind != ValgrindTool::Callgrind {
+            return;
+        }
+
+        match meta.args.profile {
+            Some(MeasurementProfile::Fast) => {
+                self.raw_args.extend_ignore_flag(["cache-sim=no"]);
+                self.flamegraph_config = ToolFlamegraphConfig::None;
+            }
+            Some(MeasurementProfile::Full) => {
+                self.raw_args.extend_ignore_flag(&[format!(
+                    "cache-sim={}",
+                    bool_to_yesno(defaults::CACHE_SIM)
+                )]);
+            }
+            Some(MeasurementProfile::Named(_)) | None => {}
+        }
+    }
+
     fn regression_config(&mut self, meta: &Metadata) -> Result<()> {
         let meta_limits = match self.kind {
             ValgrindTool::Callgrind => meta.args.callgrind_limits.clone(),
@@ -356,12 +599,68 @@ impl ToolConfigBuilder {
         Ok(())
     }
 
+    /// Add the suppression files and the `--gen-suppressions` argument of the [`Tool`]
+    ///
+    /// Relative paths of suppression files are resolved against the project root, so they don't
+    /// depend on the current directory of the benchmark run.
+    fn suppressions(&mut self, meta: &Metadata) -> Result<()> {
+        let Some(tool) = self.tool.as_ref() else {
+            return Ok(());
+        };
+
+        for path in tool.suppressions.iter().flatten() {
+            let path = make_absolute(&meta.project_root, path);
+            if !path.is_file() {
+                return Err(anyhow!(
+                    "The suppression file '{}' of {} does not exist",
+                    path.display(),
+                    self.kind
+                ));
+            }
+
+            self.raw_args
+                .extend_ignore_flag(&[format!("suppressions={}", path.display())]);
+        }
+
+        match tool.gen_suppressions {
+            Some(true) => self.raw_args.extend_ignore_flag(["gen-suppressions=all"]),
+            Some(false) => self.raw_args.extend_ignore_flag(["gen-suppressions=no"]),
+            None => {}
+        }
+
+        Ok(())
+    }
+
+    /// Configure the threads of callgrind which are included in the total
+    ///
+    /// Selecting threads requires the output of each thread in a separate file, so
+    /// `--separate-threads=yes` overrides the arguments of the benchmark.
+    fn thread_selection(&mut self) {
+        if self.kind != ValgrindTool::Callgrind {
+            return;
+        }
+
+        if let Some(thread_selection) = self.tool.as_ref().and_then(|t| t.thread_selection.clone())
+        {
+            if thread_selection != ThreadSelection::All {
+                self.raw_args.extend_ignore_flag(["separate-threads=yes"]);
+            }
+            self.thread_selection = thread_selection;
+        }
+    }
+
     fn tool_args(&mut self) {
         if let Some(tool) = self.tool.as_ref() {
             self.raw_args.update(&tool.raw_args);
         }
     }
 
+    fn total_aggregation(&mut self) {
+        if let Some(total_aggregation) = self.tool.as_ref().and_then(|t| t.total_aggregation) {
+            self.total_aggregation = total_aggregation;
+        }
+    }
+
     fn valgrind_args(&mut self, valgrind_args: &RawArgs) {
         self.raw_args.update(valgrind_args);
     }
@@ -395,6 +694,19 @@ impl ToolConfigs {
         valgrind_args: &RawArgs,
         default_args: &HashMap<ValgrindTool, RawArgs>,
     ) -> Result<Self> {
+        // The arguments of the benchmark from the configuration file only apply to the tools of
+        // this benchmark
+        let benchmark_meta;
+        let meta = if let Some(args) = meta.benchmark_args.resolve(module_path, id)? {
+            benchmark_meta = Metadata {
+                args,
+                ..meta.clone()
+            };
+            &benchmark_meta
+        } else {
+            meta
+        };
+
         let extracted_tool = tools.consume(default_tool);
 
         output_format.update(extracted_tool.as_ref());
@@ -446,6 +758,15 @@ impl ToolConfigs {
             .build()
         }))?;
 
+        // The tool filter of the command-line applies to all tools including the default tool
+        for tool_config in &mut tool_configs.0 {
+            tool_config.is_enabled &= meta.args.is_tool_selected(tool_config.tool);
+        }
+
+        for tool_config in &tool_configs.0 {
+            meta.capabilities.check(tool_config)?;
+        }
+
         output_format.update_from_meta(meta);
         Ok(tool_configs)
     }
@@ -455,6 +776,15 @@ impl ToolConfigs {
         self.0.iter().any(|t| t.is_enabled)
     }
 
+    /// Return the enabled [`ValgrindTool`]s
+    pub fn enabled_tools(&self) -> Vec<ValgrindTool> {
+        self.0
+            .iter()
+            .filter(|t| t.is_enabled)
+            .map(|t| t.tool)
+            .collect()
+    }
+
     /// Return true if there are multiple tools configured and are enabled
     pub fn has_multiple(&self) -> bool {
         self.0.len() > 1 && self.0.iter().filter(|f| f.is_enabled).count() > 1
@@ -481,15 +811,76 @@ impl ToolConfigs {
         Ok(())
     }
 
-    fn print_headline(&self, tool_config: &ToolConfig, output_format: &OutputFormat) {
+    fn print_headline(
+        &self,
+        tool_config: &ToolConfig,
+        meta: &Metadata,
+        output_format: &OutputFormat,
+    ) {
+        if output_format.is_default() && !output_format.show_only_comparison {
+            let mut formatter = VerticalFormatter::new(output_format.clone());
+            if self.has_multiple() || tool_config.tool != ValgrindTool::Callgrind {
+                formatter.format_tool_headline(tool_config.tool);
+            }
+            if output_format.show_tool_args {
+                formatter.format_tool_args(
+                    meta.environment.valgrind.as_deref(),
+                    &tool_config.args.to_vec(),
+                );
+            }
+            formatter.print_buffer();
+        }
+    }
+
+    /// Print the metrics, top functions, call tree, resource usage and suspicious warning of the
+    /// `profile`
+    fn print_profile(
+        tool_config: &ToolConfig,
+        profile: &Profile,
+        baselines: &Baselines,
+        config: &Config,
+        output_format: &OutputFormat,
+    ) -> Result<()> {
+        tool_config.print(config, output_format, &profile.summaries, baselines)?;
         if output_format.is_default()
             && !output_format.show_only_comparison
-            && (self.has_multiple() || tool_config.tool != ValgrindTool::Callgrind)
+            && output_format.show_top_functions > 0
+            && tool_config.tool == ValgrindTool::Callgrind
         {
-            let mut formatter = VerticalFormatter::new(output_format.clone());
-            formatter.format_tool_headline(tool_config.tool);
-            formatter.print_buffer();
+            let map = callgrind::functions::parse(&profile.out_paths, &config.meta.project_root)?;
+            let details = callgrind::functions::to_details(&map, output_format.show_top_functions);
+            if !details.is_empty() {
+                let mut formatter = VerticalFormatter::new(output_format.clone());
+                formatter.format_details(&details.join("\n"));
+                formatter.print_buffer();
+            }
+        }
+        if output_format.is_default()
+            && !output_format.show_only_comparison
+            && output_format.show_call_tree
+            && tool_config.tool == ValgrindTool::Callgrind
+        {
+            let tree = callgrind::call_tree::parse(&profile.out_paths, &config.meta.project_root)?;
+            let details =
+                callgrind::call_tree::to_details(&tree, callgrind::call_tree::MIN_PERCENTAGE);
+            if !details.is_empty() {
+                let mut formatter = VerticalFormatter::new(output_format.clone());
+                formatter.format_details(&details.join("\n"));
+                formatter.print_buffer();
+            }
+        }
+        if output_format.is_default() && output_format.show_resource_usage {
+            if let Some(resource_usage) = &profile.resource_usage {
+                let mut formatter = VerticalFormatter::new(output_format.clone());
+                formatter.format_resource_usage(resource_usage);
+                formatter.print_buffer();
+            }
+        }
+        if profile.suspicious {
+            print_suspicious(tool_config.tool, &tool_config.entry_point);
         }
+
+        Ok(())
     }
 
     /// Check for regressions as defined in [`RegressionConfig`] and print an error if a regression
@@ -499,7 +890,22 @@ impl ToolConfigs {
     ///
     /// Checking performance regressions for other tools than callgrind and cachegrind is not
     /// implemented and panics
-    fn check_and_print_regressions(
+    pub fn check_and_print_regressions(
+        tool_regression_config: &ToolRegressionConfig,
+        tool_total: &ProfileTotal,
+    ) -> Vec<ToolRegression> {
+        let regressions = Self::check_regressions(tool_regression_config, tool_total);
+        print_regressions(&regressions);
+        regressions
+    }
+
+    /// Check for regressions as defined in [`RegressionConfig`] without printing them
+    ///
+    /// # Panics
+    ///
+    /// Checking performance regressions for other tools than callgrind and cachegrind is not
+    /// implemented and panics
+    pub fn check_regressions(
         tool_regression_config: &ToolRegressionConfig,
         tool_total: &ProfileTotal,
     ) -> Vec<ToolRegression> {
@@ -507,15 +913,15 @@ impl ToolConfigs {
             (
                 ToolRegressionConfig::Callgrind(callgrind_regression_config),
                 ToolMetricSummary::Callgrind(metrics_summary),
-            ) => callgrind_regression_config.check_and_print(metrics_summary),
+            ) => callgrind_regression_config.check(metrics_summary),
             (
                 ToolRegressionConfig::Cachegrind(cachegrind_regression_config),
                 ToolMetricSummary::Cachegrind(metrics_summary),
-            ) => cachegrind_regression_config.check_and_print(metrics_summary),
+            ) => cachegrind_regression_config.check(metrics_summary),
             (
                 ToolRegressionConfig::Dhat(dhat_regression_config),
                 ToolMetricSummary::Dhat(metrics_summary),
-            ) => dhat_regression_config.check_and_print(metrics_summary),
+            ) => dhat_regression_config.check(metrics_summary),
             (ToolRegressionConfig::None, _) => vec![],
             _ => {
                 panic!("The summary type should match the regression config")
@@ -523,6 +929,35 @@ impl ToolConfigs {
         }
     }
 
+    /// Print the terminal output of the `benchmark_summary` which was held back while running
+    ///
+    /// With [`OutputFormat::hide_unchanged`], the headlines, metrics and regressions of the tools
+    /// are not printed while the benchmark runs but only after the benchmark has run and only if
+    /// its metrics have changed.
+    pub fn print_deferred(
+        &self,
+        benchmark_summary: &BenchmarkSummary,
+        baselines: &Baselines,
+        config: &Config,
+        output_format: &OutputFormat,
+    ) -> Result<()> {
+        for tool_config in self.0.iter().filter(|t| t.is_enabled) {
+            let Some(profile) = benchmark_summary
+                .profiles
+                .iter()
+                .find(|profile| profile.tool == tool_config.tool)
+            else {
+                continue;
+            };
+
+            self.print_headline(tool_config, &config.meta, output_format);
+            Self::print_profile(tool_config, profile, baselines, config, output_format)?;
+            print_regressions(&profile.summaries.total.regressions);
+        }
+
+        Ok(())
+    }
+
     /// Run a benchmark when --load-baseline was given
     pub fn run_loaded_vs_base(
         &self,
@@ -534,20 +969,27 @@ impl ToolConfigs {
         config: &Config,
         output_path: &ToolOutputPath,
         output_format: &OutputFormat,
+        registry: &ArtifactRegistry,
     ) -> Result<BenchmarkSummary> {
         for tool_config in self.0.iter().filter(|t| t.is_enabled) {
-            self.print_headline(tool_config, output_format);
+            if !output_format.hide_unchanged {
+                self.print_headline(tool_config, &config.meta, output_format);
+            }
 
             let tool = tool_config.tool;
             let output_path = output_path.to_tool_output(tool);
 
-            let mut profile = tool_config.parse(&config.meta, &output_path, None)?;
+            let mut profile = tool_config.parse(&config.meta, &output_path, None, None)?;
 
-            tool_config.print(config, output_format, &profile.summaries, baselines)?;
-            profile.summaries.total.regressions = Self::check_and_print_regressions(
-                &tool_config.regression_config,
-                &profile.summaries.total,
-            );
+            profile.summaries.total.regressions = if output_format.hide_unchanged {
+                Self::check_regressions(&tool_config.regression_config, &profile.summaries.total)
+            } else {
+                Self::print_profile(tool_config, &profile, baselines, config, output_format)?;
+                Self::check_and_print_regressions(
+                    &tool_config.regression_config,
+                    &profile.summaries.total,
+                )
+            };
 
             if ValgrindTool::Callgrind == tool {
                 if let ToolFlamegraphConfig::Callgrind(flamegraph_config) =
@@ -568,6 +1010,7 @@ impl ToolConfigs {
                 }
             }
 
+            registry.register_profile(&profile);
             benchmark_summary.profiles.push(profile);
 
             let log_path = output_path.to_log_output();
@@ -578,27 +1021,48 @@ impl ToolConfigs {
     }
 
     /// Run a benchmark with this configuration if not --load-baseline was given
+    ///
+    /// Only the valgrind runs of the enabled tools happen immediately. Parsing the output files,
+    /// the terminal output, the flamegraphs and the other artifacts are part of the returned
+    /// [`Job`] which may run while valgrind runs the next benchmark. If `is_serial` is true, each
+    /// tool is run and finished before the next tool and the returned [`Job`] only returns the
+    /// result.
+    ///
+    /// An error of a valgrind run is returned by the [`Job`] after the terminal output of the tools
+    /// which have run before, so the terminal output is the same as without the pipeline. The files
+    /// created by the [`Job`] are registered in the `registry`.
     #[allow(clippy::too_many_lines)]
-    pub fn run(
-        &self,
+    pub fn run<'a>(
+        &'a self,
         title: &str,
         mut benchmark_summary: BenchmarkSummary,
         baselines: &Baselines,
         baseline_kind: &BaselineKind,
-        config: &Config,
+        config: &'a Config,
         executable: &Path,
         executable_args: &[OsString],
         run_options: &RunOptions,
         output_path: &ToolOutputPath,
         save_baseline: bool,
         module_path: &ModulePath,
-        output_format: &OutputFormat,
-    ) -> Result<BenchmarkSummary> {
-        for tool_config in self.0.iter().filter(|t| t.is_enabled) {
-            // Print the headline as soon as possible, so if there are any errors, the errors shown
-            // in the terminal output can be associated with the tool
-            self.print_headline(tool_config, output_format);
+        output_format: &'a OutputFormat,
+        is_serial: bool,
+        registry: &ArtifactRegistry,
+    ) -> Job<'a, BenchmarkSummary> {
+        // The output files are parsed after the sandbox of the next benchmark has changed the
+        // current directory, so the paths of a `ToolRun` have to be absolute
+        let current_dir = match std::env::current_dir() {
+            Ok(current_dir) => current_dir,
+            Err(error) => return Box::new(move || Err(error.into())),
+        };
+        let absolute = |path: &ToolOutputPath| {
+            let mut path = path.clone();
+            path.dir = make_absolute(&current_dir, &path.dir);
+            path
+        };
 
+        // Runs valgrind with this tool. Parsing the output files is left to `finish`.
+        let run_tool = |tool_config: &'a ToolConfig| -> Result<Option<ToolRun<'a>>> {
             let tool = tool_config.tool;
 
             let nocapture = if tool_config.is_default {
@@ -606,17 +1070,26 @@ impl ToolConfigs {
             } else {
                 NoCapture::False
             };
-            let command = ToolCommand::new(tool, &config.meta, nocapture);
+            if !run_options.steps.is_empty() && !tool_config.args.trace_children {
+                return Err(Error::ConfigurationError(
+                    module_path.clone(),
+                    None,
+                    "A benchmark with multiple steps requires '--trace-children=yes'".to_owned(),
+                )
+                .into());
+            }
 
             let output_path = output_path.to_tool_output(tool);
-
-            let parser =
-                parser_factory(tool_config, config.meta.project_root.clone(), &output_path);
-            let parsed_old = parser.parse_base()?;
-
             let log_path = output_path.to_log_output();
 
-            if save_baseline {
+            // The output files of the baseline are overwritten, so they have to be parsed before
+            // the benchmark runs
+            let start = Instant::now();
+            let parsed_old = if save_baseline {
+                let parser =
+                    parser_factory(tool_config, config.meta.project_root.clone(), &output_path);
+                let parsed_old = tool_config.parse_base(parser.as_ref(), &config.meta)?;
+
                 output_path.clear()?;
                 log_path.clear()?;
                 if let Some(path) = output_path.to_xtree_output() {
@@ -625,67 +1098,244 @@ impl ToolConfigs {
                 if let Some(path) = output_path.to_xleak_output() {
                     path.clear()?;
                 }
-            }
+                Some(parsed_old)
+            } else {
+                None
+            };
+            let parse_time = start.elapsed();
+
+            // Runs the commands of the benchmark including the `setup` and `teardown` within the
+            // sandbox if present
+            let run_benchmark = |output_path: &ToolOutputPath,
+                                 nocapture: NoCapture|
+             -> Result<Vec<ToolOutput>> {
+                // We're implicitly applying the default here: In the absence of a user provided
+                // sandbox we don't run the benchmarks in a sandbox. Everything from here on runs
+                // with the current directory set to the sandbox directory until the sandbox
+                // is reset.
+                let sandbox = run_options
+                    .sandbox
+                    .as_ref()
+                    .map(|sandbox| Sandbox::setup(sandbox, &config.meta))
+                    .transpose()?;
+
+                // The sandbox has to be reset or kept even if running the commands fails
+                let outputs = (|| -> Result<Vec<ToolOutput>> {
+                    let executable_args = run_options
+                        .args_template
+                        .as_ref()
+                        .map_or_else(
+                            || Ok(executable_args.to_vec()),
+                            |template| {
+                                template.expand(
+                                    executable_args,
+                                    sandbox.as_ref().and_then(Sandbox::dir),
+                                )
+                            },
+                        )
+                        .map_err(|error| {
+                            Error::ConfigurationError(module_path.clone(), None, error)
+                        })?;
+
+                    let mut child = run_options
+                        .setup
+                        .as_ref()
+                        .map_or(Ok(None), |setup| setup.run(config, module_path))?;
+
+                    if let Some(delay) = run_options.delay.as_ref() {
+                        if let Err(error) = delay.run() {
+                            if let Some(mut child) = child.take() {
+                                // To avoid zombies
+                                child.kill()?;
+                                return Err(error);
+                            }
+                        }
+                    }
 
-            // We're implicitly applying the default here: In the absence of a user provided sandbox
-            // we don't run the benchmarks in a sandbox. Everything from here on runs
-            // with the current directory set to the sandbox directory until the sandbox
-            // is reset.
-            let sandbox = run_options
-                .sandbox
-                .as_ref()
-                .map(|sandbox| Sandbox::setup(sandbox, &config.meta))
-                .transpose()?;
+                    // The elements of an `iter` benchmark share a single benchmark process if
+                    // possible. Only the element which has run the process prints the footer.
+                    let batched =
+                        match run_options.iter_batch.as_ref().filter(|_| {
+                            IterBatch::is_supported(tool_config, run_options, &config.meta)
+                        }) {
+                            Some(element) => element.batch.output(
+                                element.index,
+                                tool_config,
+                                output_path,
+                                |tool_config, args, batch_path| {
+                                    ToolCommand::new(tool, &config.meta, nocapture).run(
+                                        tool_config,
+                                        executable,
+                                        args,
+                                        run_options.clone(),
+                                        batch_path,
+                                        module_path,
+                                        None,
+                                    )
+                                },
+                            )?,
+                            None => None,
+                        };
+                    let (output, has_run) = match batched {
+                        Some(batched) => batched,
+                        None => (
+                            ToolCommand::new(tool, &config.meta, nocapture).run(
+                                tool_config.clone(),
+                                executable,
+                                &executable_args,
+                                run_options.clone(),
+                                output_path,
+                                module_path,
+                                child,
+                            )?,
+                            true,
+                        ),
+                    };
+                    let mut outputs = vec![output];
+
+                    // Each step writes its own output files with the pid in the file name, so the
+                    // steps are shown like the processes of a benchmark with `--trace-children=yes`
+                    for (index, step) in run_options.steps.iter().enumerate() {
+                        let step_args = run_options
+                            .args_template
+                            .as_ref()
+                            .map_or_else(
+                                || Ok(step.args.clone()),
+                                |template| {
+                                    template
+                                        .expand(&step.args, sandbox.as_ref().and_then(Sandbox::dir))
+                                },
+                            )
+                            .map_err(|error| {
+                                Error::ConfigurationError(module_path.clone(), None, error)
+                            })?;
+
+                        debug!("{}: Running step {}", tool.id(), index + 1);
+                        outputs.push(ToolCommand::new(tool, &config.meta, nocapture).run(
+                            tool_config.clone(),
+                            &step.executable,
+                            &step_args,
+                            step.run_options.clone(),
+                            output_path,
+                            module_path,
+                            None,
+                        )?);
+                    }
+                    output_path.sanitize()?;
 
-            let mut child = run_options
-                .setup
-                .as_ref()
-                .map_or(Ok(None), |setup| setup.run(config, module_path))?;
-
-            if let Some(delay) = run_options.delay.as_ref() {
-                if let Err(error) = delay.run() {
-                    if let Some(mut child) = child.take() {
-                        // To avoid zombies
-                        child.kill()?;
-                        return Err(error);
+                    if let Some(teardown) = run_options.teardown.as_ref() {
+                        teardown.run(config, module_path)?;
+                    }
+
+                    // We print the no capture footer after the teardown to keep the output
+                    // consistent with library benchmarks.
+                    if has_run {
+                        print_no_capture_footer(
+                            nocapture,
+                            run_options.stdout.as_ref(),
+                            run_options.stderr.as_ref(),
+                        );
+                    }
+
+                    Ok(outputs)
+                })();
+
+                if let Some(sandbox) = sandbox {
+                    if config.meta.args.keep_sandbox.keeps(outputs.is_err()) {
+                        if let Some(path) = sandbox.keep()? {
+                            eprintln!(
+                                "{module_path}: Kept the sandbox directory: '{}'",
+                                path.display()
+                            );
+                        }
+                    } else {
+                        sandbox.reset()?;
                     }
                 }
+                outputs
+            };
+            let start = Instant::now();
+            let outputs = run_benchmark(&output_path, nocapture)?;
+            let run_time = start.elapsed();
+
+            // The output of a debugging session isn't meaningful for a comparison
+            if run_options.vgdb.is_some() {
+                log_path.dump_log(log::Level::Info, &mut stderr())?;
+                return Ok(None);
             }
 
-            let output = command.run(
-                tool_config.clone(),
-                executable,
-                executable_args,
-                run_options.clone(),
-                &output_path,
-                module_path,
-                child,
-            )?;
-
-            if let Some(teardown) = run_options.teardown.as_ref() {
-                teardown.run(config, module_path)?;
+            if tool_config.is_default {
+                if let Some(runs) = config.meta.args.verify_determinism {
+                    Self::verify_determinism(
+                        tool_config,
+                        &config.meta,
+                        &output_path,
+                        runs,
+                        module_path,
+                        |output_path| run_benchmark(output_path, NoCapture::False),
+                    )?;
+                }
             }
 
-            // We print the no capture footer after the teardown to keep the output consistent with
-            // library benchmarks.
-            print_no_capture_footer(
-                nocapture,
-                run_options.stdout.as_ref(),
-                run_options.stderr.as_ref(),
-            );
+            Ok(Some(ToolRun {
+                log_path: absolute(&log_path),
+                output_path: absolute(&output_path),
+                outputs,
+                parse_time,
+                parsed_old,
+                run_time,
+                tool_config,
+            }))
+        };
 
-            if let Some(sandbox) = sandbox {
-                sandbox.reset()?;
-            }
+        // Parses the output files of a `ToolRun`, prints the terminal output and creates the
+        // flamegraphs and other artifacts
+        let title = title.to_owned();
+        let baselines = baselines.clone();
+        let baseline_kind = baseline_kind.clone();
+        let module_path = module_path.clone();
+        let registry = registry.clone();
+        let finish = move |tool_run: ToolRun<'a>| -> Result<Profile> {
+            let ToolRun {
+                log_path,
+                output_path,
+                outputs,
+                mut parse_time,
+                parsed_old,
+                run_time,
+                tool_config,
+            } = tool_run;
+            let tool = tool_config.tool;
 
-            let mut profile = tool_config.parse(&config.meta, &output_path, Some(parsed_old))?;
+            let overhead = config
+                .overhead
+                .as_ref()
+                .filter(|_| output_format.subtract_overhead);
+            let start = Instant::now();
+            let mut profile =
+                tool_config.parse(&config.meta, &output_path, parsed_old, overhead)?;
+            parse_time += start.elapsed();
+            profile.resource_usage = outputs
+                .iter()
+                .filter_map(|output| output.resource_usage)
+                .reduce(|acc, usage| acc.add(&usage));
+
+            let start = Instant::now();
+            if !output_format.hide_unchanged {
+                Self::print_profile(tool_config, &profile, &baselines, config, output_format)?;
+            }
+            let output_time = start.elapsed();
 
-            tool_config.print(config, output_format, &profile.summaries, baselines)?;
-            profile.summaries.total.regressions = Self::check_and_print_regressions(
-                &tool_config.regression_config,
-                &profile.summaries.total,
-            );
+            profile.summaries.total.regressions = if output_format.hide_unchanged {
+                Self::check_regressions(&tool_config.regression_config, &profile.summaries.total)
+            } else {
+                Self::check_and_print_regressions(
+                    &tool_config.regression_config,
+                    &profile.summaries.total,
+                )
+            };
 
+            let start = Instant::now();
             if tool_config.tool == ValgrindTool::Callgrind {
                 if save_baseline {
                     let BaselineKind::Name(baseline) = baseline_kind.clone() else {
@@ -695,7 +1345,7 @@ impl ToolConfigs {
                         &tool_config.flamegraph_config
                     {
                         profile.flamegraphs = SaveBaselineFlamegraphGenerator { baseline }.create(
-                            &Flamegraph::new(title.to_owned(), flamegraph_config.to_owned()),
+                            &Flamegraph::new(title.clone(), flamegraph_config.to_owned()),
                             &output_path,
                             (tool_config.entry_point == EntryPoint::Default)
                                 .then(Sentinel::default)
@@ -710,7 +1360,7 @@ impl ToolConfigs {
                         baseline_kind: baseline_kind.clone(),
                     }
                     .create(
-                        &Flamegraph::new(title.to_owned(), flamegraph_config.to_owned()),
+                        &Flamegraph::new(title.clone(), flamegraph_config.to_owned()),
                         &output_path,
                         (tool_config.entry_point == EntryPoint::Default)
                             .then(Sentinel::default)
@@ -721,15 +1371,192 @@ impl ToolConfigs {
                     // do nothing
                 }
             }
+            let flamegraph_time = start.elapsed();
 
-            benchmark_summary.profiles.push(profile);
+            tool_config.create_artifacts(config, &output_path, &log_path, &title, &registry)?;
+            registry.register_profile(&profile);
+
+            debug!(
+                "{}: {module_path}: Timings: run {run_time:?}, parse {parse_time:?}, output \
+                 {output_time:?}, flamegraph {flamegraph_time:?}",
+                tool.id()
+            );
 
-            output.dump_log(log::Level::Info);
+            for output in &outputs {
+                output.dump_log(log::Level::Info);
+            }
             log_path.dump_log(log::Level::Info, &mut stderr())?;
+
+            Ok(profile)
+        };
+
+        let mut tool_runs = vec![];
+        let mut failure = None;
+        for tool_config in self.0.iter().filter(|t| t.is_enabled) {
+            // Debugging with gdb is only possible for a single valgrind run, so only the default
+            // tool is run under the gdbserver
+            if run_options.vgdb.is_some() && !tool_config.is_default {
+                continue;
+            }
+
+            // Print the headline as soon as possible, so if there are any errors, the errors shown
+            // in the terminal output can be associated with the tool
+            if is_serial && !output_format.hide_unchanged {
+                self.print_headline(tool_config, &config.meta, output_format);
+            }
+
+            let result = run_tool(tool_config).and_then(|tool_run| match tool_run {
+                Some(tool_run) if is_serial => {
+                    benchmark_summary.profiles.push(finish(tool_run)?);
+                    Ok(())
+                }
+                Some(tool_run) => {
+                    tool_runs.push(tool_run);
+                    Ok(())
+                }
+                None => Ok(()),
+            });
+            if let Err(error) = result {
+                failure = Some((tool_config, error));
+                break;
+            }
         }
 
-        Ok(benchmark_summary)
+        Box::new(move || {
+            for tool_run in tool_runs {
+                if !output_format.hide_unchanged {
+                    self.print_headline(tool_run.tool_config, &config.meta, output_format);
+                }
+                benchmark_summary.profiles.push(finish(tool_run)?);
+            }
+
+            if let Some((tool_config, error)) = failure {
+                if !is_serial && !output_format.hide_unchanged {
+                    self.print_headline(tool_config, &config.meta, output_format);
+                }
+                return Err(error);
+            }
+
+            Ok(benchmark_summary)
+        })
     }
+
+    /// Run the benchmark `runs - 1` more times and compare the metrics with the first run
+    ///
+    /// The repetitions are run in temporary directories, so the output files of the first run stay
+    /// untouched. The metrics of each part (process, part, thread) have to be exactly the same in
+    /// all runs. Otherwise, the benchmark is considered nondeterministic and an error listing the
+    /// varying metrics is returned.
+    fn verify_determinism<F>(
+        tool_config: &ToolConfig,
+        meta: &Metadata,
+        output_path: &ToolOutputPath,
+        runs: usize,
+        module_path: &ModulePath,
+        run: F,
+    ) -> Result<()>
+    where
+        F: Fn(&ToolOutputPath) -> Result<Vec<ToolOutput>>,
+    {
+        let expected =
+            parser_factory(tool_config, meta.project_root.clone(), output_path).parse()?;
+
+        for repetition in 2..=runs {
+            debug!(
+                "{}: Verifying determinism: Run {repetition} of {runs}",
+                tool_config.tool.id()
+            );
+
+            let temp_dir = tempfile::tempdir()?;
+            let verify_path = ToolOutputPath {
+                dir: temp_dir.path().to_owned(),
+                ..output_path.clone()
+            };
+            run(&verify_path)?;
+            let actual =
+                parser_factory(tool_config, meta.project_root.clone(), &verify_path).parse()?;
+
+            let mut differences = vec![];
+            for (index, outputs) in expected.iter().zip_longest(actual.iter()).enumerate() {
+                let (expected, actual) = match outputs {
+                    itertools::EitherOrBoth::Both(expected, actual) => (expected, actual),
+                    itertools::EitherOrBoth::Left(output)
+                    | itertools::EitherOrBoth::Right(output) => {
+                        differences.push(format!(
+                            "  #{}: {}: The part is missing in one of the runs",
+                            index + 1,
+                            output.header.command
+                        ));
+                        continue;
+                    }
+                };
+
+                let kinds = match (&expected.metrics, &actual.metrics) {
+                    (ToolMetrics::None, ToolMetrics::None) => vec![],
+                    (ToolMetrics::Dhat(expected), ToolMetrics::Dhat(actual)) => {
+                        differing_metrics(expected, actual)
+                    }
+                    (ToolMetrics::ErrorTool(expected), ToolMetrics::ErrorTool(actual)) => {
+                        differing_metrics(expected, actual)
+                    }
+                    (ToolMetrics::Callgrind(expected), ToolMetrics::Callgrind(actual)) => {
+                        differing_metrics(expected, actual)
+                    }
+                    (ToolMetrics::Cachegrind(expected), ToolMetrics::Cachegrind(actual)) => {
+                        differing_metrics(expected, actual)
+                    }
+                    _ => panic!("The metrics of the same tool should be of the same type"),
+                };
+
+                if !kinds.is_empty() {
+                    let thread = expected
+                        .header
+                        .thread
+                        .map_or_else(String::new, |thread| format!(" (thread {thread})"));
+                    differences.push(format!(
+                        "  #{}: {}{thread}: {}",
+                        index + 1,
+                        expected.header.command,
+                        kinds.join(", ")
+                    ));
+                }
+            }
+
+            if !differences.is_empty() {
+                return Err(Error::BenchmarkError(
+                    tool_config.tool,
+                    module_path.clone(),
+                    format!(
+                        "The benchmark is not deterministic: The metrics of run {repetition} \
+                         differ from the first run:\n{}",
+                        differences.join("\n")
+                    ),
+                )
+                .into());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Return the names of the metrics which are not exactly the same in `expected` and `actual`
+fn differing_metrics<K>(expected: &Metrics<K>, actual: &Metrics<K>) -> Vec<String>
+where
+    K: Hash + Eq + Display + Clone,
+{
+    let mut kinds = expected
+        .iter()
+        .filter(|(kind, metric)| actual.metric_by_kind(kind) != Some(**metric))
+        .map(|(kind, _)| kind.to_string())
+        .collect::<Vec<_>>();
+    kinds.extend(
+        actual
+            .iter()
+            .filter(|(kind, _)| expected.metric_by_kind(kind).is_none())
+            .map(|(kind, _)| kind.to_string()),
+    );
+    kinds
 }
 
 impl From<Option<FlamegraphConfig>> for ToolFlamegraphConfig {
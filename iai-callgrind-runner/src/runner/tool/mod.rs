@@ -2,6 +2,8 @@
 //! specific `callgrind`, `cachegrind`, ... modules
 
 pub mod args;
+pub mod batch;
+pub mod cache;
 pub mod config;
 pub mod error_metric_parser;
 pub mod generic_parser;
@@ -10,3 +12,4 @@ pub mod parser;
 pub mod path;
 pub mod regression;
 pub mod run;
+pub mod suppressions;
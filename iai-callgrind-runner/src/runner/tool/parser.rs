@@ -5,19 +5,22 @@ use std::path::PathBuf;
 
 use anyhow::Result;
 use log::debug;
+use serde::{Deserialize, Serialize};
 
 use super::config::ToolConfig;
 use super::error_metric_parser::ErrorMetricLogfileParser;
 use super::generic_parser::GenericLogfileParser;
 use super::path::ToolOutputPath;
 use crate::api::{EntryPoint, ValgrindTool};
+use crate::runner::callgrind::lines::LineParser;
+use crate::runner::callgrind::parser::Sentinel;
 use crate::runner::dhat::json_parser::JsonParser;
 use crate::runner::dhat::logfile_parser::DhatLogfileParser;
 use crate::runner::summary::ToolMetrics;
 use crate::runner::{cachegrind, callgrind};
 
 /// The combined header of output and log files
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Header {
     /// The path to the executed command with command-line arguments
     pub command: String,
@@ -34,7 +37,7 @@ pub struct Header {
 }
 
 /// The output of a [`Parser`]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ParserOutput {
     /// Details about the profile run if present. A vector separated by lines
     pub details: Vec<String>,
@@ -110,6 +113,15 @@ pub fn parser_factory(
 ) -> Box<dyn Parser> {
     match tool_config.tool {
         ValgrindTool::Callgrind => Box::new(callgrind::summary_parser::SummaryParser {
+            lines: match &tool_config.entry_point {
+                EntryPoint::None => None,
+                EntryPoint::Default => Some(Sentinel::default()),
+                EntryPoint::Custom(custom) => Some(Sentinel::new(custom)),
+            }
+            .map(|sentinel| LineParser {
+                project_root: root_dir,
+                sentinel,
+            }),
             output_path: output_path.clone(),
         }),
         ValgrindTool::Cachegrind => Box::new(cachegrind::summary_parser::SummaryParser {
@@ -533,6 +533,33 @@ impl ToolOutputPath {
         format!("{}.{}", self.tool.id(), self.name)
     }
 
+    /// Return the path of an artifact created from the output file at `path`
+    ///
+    /// The artifact of an output file `<tool>.<name>[.<modifiers>].out` is named
+    /// `<tool>.<name>[.<modifiers>].<artifact>.<extension>` and of a baseline output file
+    /// `<tool>.<name>[.<modifiers>].<artifact>.base@<baseline>.<extension>`. The modifiers are
+    /// the real modifiers of the output file, for example the pid.
+    pub fn to_artifact_path(&self, path: &Path, artifact: &str, extension: &str) -> PathBuf {
+        let suffix = format!(".{}", self.extension());
+        let file_name = path
+            .file_name()
+            .map(|file_name| file_name.to_string_lossy())
+            .unwrap_or_default();
+        let modifiers = self
+            .strip_prefix(&file_name)
+            .and_then(|rest| rest.strip_suffix(suffix.as_str()))
+            .unwrap_or_default();
+
+        let file_name = match &self.kind {
+            ToolOutputPathKind::BaseOut(name) | ToolOutputPathKind::BaseLog(name) => format!(
+                "{}{modifiers}.{artifact}.base@{name}.{extension}",
+                self.prefix()
+            ),
+            _ => format!("{}{modifiers}.{artifact}.{extension}", self.prefix()),
+        };
+        self.dir.join(file_name)
+    }
+
     /// Return the `real` paths of a tool's output files
     ///
     /// A tool can have many output files so [`Self::to_path`] is not enough
@@ -1266,6 +1293,42 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[rstest]
+    #[case::out(ToolOutputPathKind::Out, "massif.some.out", "massif.some.chart.svg")]
+    #[case::out_pid(
+        ToolOutputPathKind::Out,
+        "massif.some.123.out",
+        "massif.some.123.chart.svg"
+    )]
+    #[case::base_out(
+        ToolOutputPathKind::BaseOut("foo".to_owned()),
+        "massif.some.out.base@foo",
+        "massif.some.chart.base@foo.svg"
+    )]
+    #[case::base_out_pid(
+        ToolOutputPathKind::BaseOut("foo".to_owned()),
+        "massif.some.123.out.base@foo",
+        "massif.some.123.chart.base@foo.svg"
+    )]
+    fn test_tool_output_path_to_artifact_path(
+        #[case] kind: ToolOutputPathKind,
+        #[case] input: PathBuf,
+        #[case] expected: PathBuf,
+    ) {
+        let output_path = ToolOutputPath::new(
+            kind,
+            ValgrindTool::Massif,
+            &BaselineKind::Old,
+            &PathBuf::from("/root"),
+            &ModulePath::new("hello::world"),
+            "some",
+        );
+        let expected = output_path.dir.join(expected);
+        let actual = output_path.to_artifact_path(&output_path.dir.join(input), "chart", "svg");
+
+        assert_eq!(actual, expected);
+    }
+
     #[test]
     fn test_tool_output_path_log_path_of_when_log_then_same() {
         let output_path = ToolOutputPath::new(
@@ -1,25 +1,37 @@
 //! The module responsible for the actual run of the benchmark
 
 use std::ffi::OsString;
+use std::io::{Read, Write};
+use std::os::unix::process::ExitStatusExt;
 use std::path::{Path, PathBuf};
-use std::process::{Child, Command, ExitStatus, Output};
+use std::process::{Child, ChildStdin, ChildStdout, Command, ExitStatus, Output};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use std::{io, thread};
 
 use anyhow::Result;
 use log::{debug, error, log_enabled};
 
+use super::batch::IterBatchElement;
 use super::config::ToolConfig;
 use super::path::ToolOutputPath;
-use crate::api::{self, ExitWith, Stream, ValgrindTool};
+use crate::api::{self, ExitWith, InputStep, Phase, Stream, ValgrindTool, VgdbMode};
 use crate::error::Error;
 use crate::runner::args::NoCapture;
-use crate::runner::bin_bench::Delay;
+use crate::runner::bin_bench::{ArgsTemplate, Delay};
 use crate::runner::common::{Assistant, ModulePath};
 use crate::runner::meta::Metadata;
+use crate::runner::summary::ResourceUsage;
+use crate::runner::{self, run_log};
 use crate::util::{self, resolve_binary_path};
 
 /// The run options for the [`ToolCommand`]
 #[derive(Debug, Default, Clone)]
 pub struct RunOptions {
+    /// The [`ArgsTemplate`] to expand the placeholders in the arguments of the executable
+    pub args_template: Option<ArgsTemplate>,
     /// Set the current directory of the [`ToolCommand`]
     pub current_dir: Option<PathBuf>,
     /// The optional [`Delay`] to apply to the command
@@ -30,6 +42,10 @@ pub struct RunOptions {
     pub envs: Vec<(OsString, OsString)>,
     /// Configuration of the expected exit code/signal
     pub exit_with: Option<ExitWith>,
+    /// If present, drive the stdin of the executable with the [`api::InputScript`]
+    pub input_script: Option<api::InputScript>,
+    /// If present, run the element of an `iter` benchmark together with the other elements
+    pub iter_batch: Option<IterBatchElement>,
     /// If present, execute the [`ToolCommand`] in a [`api::Sandbox`]
     pub sandbox: Option<api::Sandbox>,
     /// The `setup` assistant to run if present
@@ -40,8 +56,25 @@ pub struct RunOptions {
     pub stdin: Option<api::Stdin>,
     /// The `stdout`
     pub stdout: Option<api::Stdio>,
+    /// The [`Step`]s which are run sequentially after the executable
+    pub steps: Vec<Step>,
     /// The `teardown` assistant to run if present
     pub teardown: Option<Assistant>,
+    /// If present, terminate the [`ToolCommand`] with a signal
+    pub terminate: Option<api::Terminate>,
+    /// If present, run the [`ToolCommand`] under the gdbserver of valgrind
+    pub vgdb: Option<VgdbMode>,
+}
+
+/// A step of a multi-step benchmark which is run after the executable of the benchmark
+#[derive(Debug, Clone)]
+pub struct Step {
+    /// The arguments of the executable of this step
+    pub args: Vec<OsString>,
+    /// The path to the executable of this step
+    pub executable: PathBuf,
+    /// The run options of this step
+    pub run_options: RunOptions,
 }
 
 /// The final command to execute
@@ -51,14 +84,48 @@ pub struct ToolCommand {
     tool: ValgrindTool,
 }
 
+/// The captured stdout of the executable while it is driven by an [`api::InputScript`]
+#[derive(Debug, Default)]
+struct OutputBuffer {
+    changed: Condvar,
+    state: Mutex<OutputBufferState>,
+}
+
+#[derive(Debug, Default)]
+struct OutputBufferState {
+    bytes: Vec<u8>,
+    is_closed: bool,
+}
+
 /// The tool specific [`Output`] of the [`ToolCommand`]
 pub struct ToolOutput {
     /// The output if present
     pub output: Option<Output>,
+    /// The resource usage of the valgrind process if available
+    pub resource_usage: Option<ResourceUsage>,
     /// The valgrind tool
     pub tool: ValgrindTool,
 }
 
+impl RunOptions {
+    /// Return true if the benchmark with these `RunOptions` can't run in the pipeline
+    ///
+    /// The benchmarks run in a [`Pipeline`](crate::runner::pipeline::Pipeline) print their
+    /// terminal output after valgrind has run, so the commands of a benchmark in the pipeline must
+    /// neither write to nor read from the terminal.
+    pub fn is_serial(&self, nocapture: NoCapture) -> bool {
+        nocapture != NoCapture::False
+            || std::iter::once(self)
+                .chain(self.steps.iter().map(|step| &step.run_options))
+                .any(|options| {
+                    options.vgdb.is_some()
+                        || options.input_script.is_some()
+                        || options.stdout == Some(api::Stdio::Inherit)
+                        || options.stderr == Some(api::Stdio::Inherit)
+                })
+    }
+}
+
 impl ToolCommand {
     /// Create new `ToolCommand`
     pub fn new(tool: ValgrindTool, meta: &Metadata, nocapture: NoCapture) -> Self {
@@ -96,6 +163,9 @@ impl ToolCommand {
     }
 
     /// Run the `ToolCommand`
+    ///
+    /// The output files in the `output_path` are not sanitized, so multiple `ToolCommand`s can
+    /// write into the same `output_path`. Call [`ToolOutputPath::sanitize`] after the last run.
     #[allow(clippy::too_many_lines)]
     pub fn run(
         mut self,
@@ -121,6 +191,9 @@ impl ToolCommand {
             stdin,
             stdout,
             stderr,
+            terminate,
+            input_script,
+            vgdb,
             ..
         } = run_options;
 
@@ -155,23 +228,55 @@ impl ToolCommand {
                 .join(" ")
         );
 
+        self.command.args(tool_args.to_vec());
+        if let Some(vgdb) = vgdb {
+            self.command.args(vgdb.to_args());
+        }
         self.command
-            .args(tool_args.to_vec())
             .arg(&executable)
             .args(executable_args)
-            .envs(envs);
+            .envs(envs)
+            .env(runner::envs::IAI_CALLGRIND_TOOL, self.tool.id());
 
         if config.is_default {
             debug!("Applying --nocapture options");
             self.nocapture.apply(&mut self.command);
         }
 
+        // The terminated command is spawned and not run with `Command::output`, so the streams
+        // which are not configured need the same defaults as in `Command::output`
+        if terminate.is_some() && !(config.is_default && self.nocapture != NoCapture::False) {
+            self.command
+                .stdin(std::process::Stdio::null())
+                .stdout(std::process::Stdio::piped())
+                .stderr(std::process::Stdio::piped());
+        }
+
         if let Some(stdin) = stdin {
             stdin
                 .apply(&mut self.command, Stream::Stdin, child.as_mut())
                 .map_err(|error| Error::BenchmarkError(self.tool, module_path.clone(), error))?;
         }
 
+        // The phase markers separate the output of the `setup` and `teardown` functions in the
+        // captured output and would only be noise if the output is shown in the terminal
+        let is_captured = vgdb.is_none()
+            && input_script.is_none()
+            && stdout.is_none()
+            && stderr.is_none()
+            && !(config.is_default && self.nocapture != NoCapture::False);
+        if is_captured {
+            self.command
+                .env(runner::envs::IAI_CALLGRIND_PHASE_MARKERS, "yes");
+        }
+
+        // The stdout of the executable is shown in the terminal if it is not captured. If the
+        // input script waits for output, the stdout is read by the runner and forwarded instead.
+        let tee = matches!(stdout, Some(api::Stdio::Inherit))
+            || (stdout.is_none()
+                && config.is_default
+                && matches!(self.nocapture, NoCapture::True | NoCapture::Stdout));
+
         if let Some(stdout) = stdout {
             stdout
                 .apply(&mut self.command, Stream::Stdout)
@@ -184,44 +289,64 @@ impl ToolCommand {
                 .map_err(|error| Error::BenchmarkError(self.tool, module_path.clone(), error))?;
         }
 
-        let output = match self.nocapture {
-            NoCapture::True | NoCapture::Stderr | NoCapture::Stdout if config.is_default => {
-                self.command
-                    .status()
-                    .map_err(|error| {
-                        Error::LaunchError(PathBuf::from("valgrind"), error.to_string()).into()
-                    })
-                    .and_then(|status| {
+        if let Some(input_script) = &input_script {
+            self.command.stdin(std::process::Stdio::piped());
+            if input_script.has_wait_steps() {
+                self.command.stdout(std::process::Stdio::piped());
+            }
+        }
+
+        // The resource usage of the children includes only terminated and waited for processes, so
+        // the difference is the resource usage of the valgrind process
+        let usage_before = ResourceUsage::of_children();
+        let output = if let Some(vgdb) = vgdb {
+            self.run_vgdb(
+                vgdb,
+                &executable,
+                &output_path.to_log_output(),
+                exit_with.as_ref(),
+            )?;
+            None
+        } else {
+            match self.nocapture {
+                NoCapture::True | NoCapture::Stderr | NoCapture::Stdout if config.is_default => {
+                    self.status(terminate, input_script, tee, module_path)
+                        .and_then(|status| {
+                            check_exit(
+                                self.tool,
+                                &executable,
+                                None,
+                                status,
+                                &output_path.to_log_output(),
+                                exit_with.as_ref(),
+                            )
+                        })?;
+                    None
+                }
+                _ => self
+                    .output(terminate, input_script, tee, module_path)
+                    .and_then(|output| {
+                        for (label, bytes) in labeled_output(self.tool, &output) {
+                            run_log::write(&label, bytes);
+                        }
+                        let status = output.status;
                         check_exit(
                             self.tool,
                             &executable,
-                            None,
+                            Some(output),
                             status,
                             &output_path.to_log_output(),
                             exit_with.as_ref(),
                         )
-                    })?;
-                None
+                    })?,
             }
-            _ => self
-                .command
-                .output()
-                .map_err(|error| {
-                    Error::LaunchError(PathBuf::from("valgrind"), error.to_string()).into()
-                })
-                .and_then(|output| {
-                    let status = output.status;
-                    check_exit(
-                        self.tool,
-                        &executable,
-                        Some(output),
-                        status,
-                        &output_path.to_log_output(),
-                        exit_with.as_ref(),
-                    )
-                })?,
         };
 
+        let resource_usage = usage_before
+            .zip(ResourceUsage::of_children())
+            .filter(|_| vgdb.is_none())
+            .map(|(before, after)| after.saturating_sub(&before));
+
         if let Some(mut child) = child {
             debug!("Waiting for setup child process");
             let status = child.wait().expect("Setup child process should have run");
@@ -236,13 +361,172 @@ impl ToolCommand {
             }
         }
 
-        output_path.sanitize()?;
-
         Ok(ToolOutput {
             tool: self.tool,
             output,
+            resource_usage,
+        })
+    }
+
+    /// Run the command like [`Command::output`]
+    ///
+    /// If present, the signal of `terminate` is sent and the stdin is driven by the
+    /// `input_script`. The stdout read by the `input_script` is forwarded to the stdout of the
+    /// runner if `tee` is true.
+    fn output(
+        &mut self,
+        terminate: Option<api::Terminate>,
+        input_script: Option<api::InputScript>,
+        tee: bool,
+        module_path: &ModulePath,
+    ) -> Result<Output> {
+        let launch_error =
+            |error: io::Error| Error::LaunchError(PathBuf::from("valgrind"), error.to_string());
+        match (terminate, input_script) {
+            (terminate, Some(input_script)) => {
+                let child = self.command.spawn().map_err(launch_error)?;
+                wait_with_script(child, &input_script, terminate, tee).map_err(|error| {
+                    Error::BenchmarkError(self.tool, module_path.clone(), error).into()
+                })
+            }
+            (Some(terminate), None) => self
+                .command
+                .spawn()
+                .and_then(|child| wait_and_terminate(child, terminate))
+                .map_err(|error| launch_error(error).into()),
+            (None, None) => self
+                .command
+                .output()
+                .map_err(|error| launch_error(error).into()),
+        }
+    }
+
+    /// Run the command like [`Command::status`]
+    ///
+    /// See [`ToolCommand::output`] for the meaning of the arguments.
+    fn status(
+        &mut self,
+        terminate: Option<api::Terminate>,
+        input_script: Option<api::InputScript>,
+        tee: bool,
+        module_path: &ModulePath,
+    ) -> Result<ExitStatus> {
+        if terminate.is_none() && input_script.is_none() {
+            return self.command.status().map_err(|error| {
+                Error::LaunchError(PathBuf::from("valgrind"), error.to_string()).into()
+            });
+        }
+
+        self.output(terminate, input_script, tee, module_path)
+            .map(|output| output.status)
+    }
+
+    /// Spawn the command under the gdbserver of valgrind and wait until it has finished
+    ///
+    /// The command to attach gdb to the gdbserver is printed to `stderr` as soon as the process is
+    /// running.
+    fn run_vgdb(
+        &mut self,
+        vgdb: VgdbMode,
+        executable: &Path,
+        log_path: &ToolOutputPath,
+        exit_with: Option<&ExitWith>,
+    ) -> Result<()> {
+        let mut child = self
+            .command
+            .spawn()
+            .map_err(|error| Error::LaunchError(PathBuf::from("valgrind"), error.to_string()))?;
+
+        let (VgdbMode::At(num_errors) | VgdbMode::FullAt(num_errors)) = vgdb;
+        let pid = child.id();
+        eprintln!(
+            "{}: Valgrind (pid {pid}) waits for gdb after {num_errors} error(s). Attach gdb in \
+             another terminal with:\n\n  gdb '{}'\n  (gdb) target remote | vgdb --pid={pid}\n",
+            self.tool.id(),
+            executable.display()
+        );
+
+        let status = child
+            .wait()
+            .map_err(|error| Error::LaunchError(PathBuf::from("valgrind"), error.to_string()))?;
+        check_exit(self.tool, executable, None, status, log_path, exit_with)?;
+
+        Ok(())
+    }
+}
+
+impl OutputBuffer {
+    /// Read the `stdout` into this buffer in a new thread until it is closed
+    ///
+    /// If `tee` is true, the bytes are also written to the stdout of the runner.
+    fn read_from(self: &Arc<Self>, mut stdout: ChildStdout, tee: bool) -> JoinHandle<()> {
+        let buffer = Arc::clone(self);
+        thread::spawn(move || {
+            let mut chunk = [0u8; 8192];
+            loop {
+                match stdout.read(&mut chunk) {
+                    Ok(0) => break,
+                    Ok(len) => {
+                        if tee {
+                            let mut runner_stdout = io::stdout().lock();
+                            _ = runner_stdout
+                                .write_all(&chunk[..len])
+                                .and_then(|()| runner_stdout.flush());
+                        }
+                        let mut state = buffer.state.lock().unwrap();
+                        state.bytes.extend_from_slice(&chunk[..len]);
+                        buffer.changed.notify_all();
+                    }
+                    Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+                    Err(error) => {
+                        debug!("Failed to read the stdout of the executable: {error}");
+                        break;
+                    }
+                }
+            }
+
+            buffer.state.lock().unwrap().is_closed = true;
+            buffer.changed.notify_all();
         })
     }
+
+    /// Wait until the `expected` bytes occur in the buffer after the position `start`
+    ///
+    /// Returns the position after the match.
+    fn wait_for(&self, expected: &str, start: usize, timeout: Duration) -> Result<usize, String> {
+        let deadline = Instant::now() + timeout;
+        let mut state = self.state.lock().unwrap();
+        loop {
+            if let Some(position) = find_bytes(&state.bytes[start..], expected.as_bytes()) {
+                return Ok(start + position + expected.len());
+            }
+            if state.is_closed {
+                return Err(format!(
+                    "The stdout was closed before '{}' was found",
+                    expected.escape_debug()
+                ));
+            }
+
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(format!(
+                    "Timeout of {timeout:?} exceeded waiting for '{}' on stdout",
+                    expected.escape_debug()
+                ));
+            }
+            state = self.changed.wait_timeout(state, deadline - now).unwrap().0;
+        }
+    }
+
+    /// Return true if the stdout was closed
+    fn is_closed(&self) -> bool {
+        self.state.lock().unwrap().is_closed
+    }
+
+    /// Take the bytes out of the buffer
+    fn take(&self) -> Vec<u8> {
+        std::mem::take(&mut self.state.lock().unwrap().bytes)
+    }
 }
 
 impl ToolOutput {
@@ -250,18 +534,88 @@ impl ToolOutput {
     pub fn dump_log(&self, log_level: log::Level) {
         if let Some(output) = &self.output {
             if log_enabled!(log_level) {
-                let (stdout, stderr) = (&output.stdout, &output.stderr);
-                if !stdout.is_empty() {
-                    log::log!(log_level, "{} output on stdout:", self.tool.id());
-                    util::write_all_to_stderr(stdout);
+                for (label, bytes) in labeled_output(self.tool, output) {
+                    log::log!(log_level, "{label}");
+                    util::write_all_to_stderr(bytes);
                 }
-                if !stderr.is_empty() {
-                    log::log!(log_level, "{} output on stderr:", self.tool.id());
-                    util::write_all_to_stderr(stderr);
+            }
+        }
+    }
+}
+
+/// Return the parts of the captured stdout and stderr of the valgrind `tool` with a label
+///
+/// The output of the `setup` and `teardown` functions of a library benchmark is labeled separately
+/// from the output of the benchmark function.
+fn labeled_output(tool: ValgrindTool, output: &Output) -> Vec<(String, &[u8])> {
+    [("stdout", &output.stdout), ("stderr", &output.stderr)]
+        .into_iter()
+        .flat_map(|(stream, bytes)| {
+            split_at_phase_markers(bytes)
+                .into_iter()
+                .map(move |(phase, part)| {
+                    let label = match phase {
+                        Some(phase) => {
+                            format!("{} output of {} on {stream}:", tool.id(), phase.id())
+                        }
+                        None => format!("{} output on {stream}:", tool.id()),
+                    };
+                    (label, part)
+                })
+        })
+        .collect()
+}
+
+/// Return the captured `bytes` without the [`Phase`] markers printed by the benchmark process
+pub fn strip_phase_markers(bytes: &[u8]) -> Vec<u8> {
+    split_at_phase_markers(bytes)
+        .into_iter()
+        .flat_map(|(_, part)| part.iter().copied())
+        .collect()
+}
+
+/// Split the captured `bytes` at the [`Phase`] markers printed by the benchmark process
+///
+/// Each part is returned with the [`Phase`] it was produced in or `None` if it was produced
+/// outside of a `setup` or `teardown` phase, usually by the benchmark function. The markers
+/// themselves and empty parts are not returned.
+pub fn split_at_phase_markers(bytes: &[u8]) -> Vec<(Option<Phase>, &[u8])> {
+    let mut parts = vec![];
+    let mut phase = None;
+    let mut start = 0;
+    let mut offset = 0;
+    for line in bytes.split_inclusive(|byte| *byte == b'\n') {
+        let line_start = offset;
+        offset += line.len();
+
+        let line = line.strip_suffix(b"\n").unwrap_or(line);
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        let next = [Phase::Setup, Phase::Teardown]
+            .into_iter()
+            .find_map(|phase| {
+                if line == phase.start_marker().as_bytes() {
+                    Some(Some(phase))
+                } else if line == phase.end_marker().as_bytes() {
+                    Some(None)
+                } else {
+                    None
                 }
+            });
+
+        if let Some(next) = next {
+            if line_start > start {
+                parts.push((phase, &bytes[start..line_start]));
             }
+            phase = next;
+            start = offset;
         }
     }
+
+    if bytes.len() > start {
+        parts.push((phase, &bytes[start..]));
+    }
+
+    parts
 }
 
 /// Check the exit code of the [`ToolCommand`] and verify it matches the expected [`ExitWith`]
@@ -274,9 +628,26 @@ pub fn check_exit(
     exit_with: Option<&ExitWith>,
 ) -> Result<Option<Output>> {
     let Some(status_code) = status.code() else {
-        return Err(
-            Error::ProcessError(tool.id(), output, status, Some(output_path.clone())).into(),
-        );
+        return match (status.signal(), exit_with) {
+            (Some(actual), Some(ExitWith::Signal(expected))) if actual == expected.number() => {
+                Ok(output)
+            }
+            (Some(actual), Some(ExitWith::Signal(expected))) => {
+                error!(
+                    "{}: Expected '{}' to be terminated by '{expected}' but it was terminated by \
+                     signal '{actual}'",
+                    tool.id(),
+                    executable.display(),
+                );
+                Err(
+                    Error::ProcessError(tool.id(), output, status, Some(output_path.clone()))
+                        .into(),
+                )
+            }
+            _ => Err(
+                Error::ProcessError(tool.id(), output, status, Some(output_path.clone())).into(),
+            ),
+        };
     };
 
     match (status_code, exit_with) {
@@ -321,6 +692,441 @@ pub fn check_exit(
             );
             Err(Error::ProcessError(tool.id(), output, status, Some(output_path.clone())).into())
         }
+        (code, Some(ExitWith::Signal(signal))) => {
+            error!(
+                "{}: Expected '{}' to be terminated by '{signal}' but it exited with '{code}'",
+                tool.id(),
+                executable.display(),
+            );
+            Err(Error::ProcessError(tool.id(), output, status, Some(output_path.clone())).into())
+        }
         _ => Err(Error::ProcessError(tool.id(), output, status, Some(output_path.clone())).into()),
     }
 }
+
+/// Return the position of the first occurrence of `needle` in the `haystack`
+fn find_bytes(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    if needle.is_empty() {
+        return Some(0);
+    }
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+/// Execute the steps of the `input_script` writing to the `stdin` and waiting for the `stdout`
+///
+/// The `stdin` is closed after the last step, so the executable receives an end-of-file. If a
+/// [`InputStep::WaitFor`] step times out, the process with the `pid` is killed.
+fn run_input_script(
+    input_script: &api::InputScript,
+    mut stdin: ChildStdin,
+    stdout: &OutputBuffer,
+    pid: u32,
+) -> Result<(), String> {
+    let timeout = input_script.timeout.unwrap_or_default();
+    let mut position = 0;
+    for step in &input_script.steps {
+        match step {
+            InputStep::Write(bytes) => {
+                stdin
+                    .write_all(bytes)
+                    .and_then(|()| stdin.flush())
+                    .map_err(|error| format!("Failed to write to the stdin: {error}"))?;
+            }
+            InputStep::WaitFor(expected) => {
+                position = stdout
+                    .wait_for(expected, position, timeout)
+                    .map_err(|error| {
+                        // If the stdout was closed, the process has most likely exited already and
+                        // its pid might have been reused
+                        if !stdout.is_closed() {
+                            send_signal(pid, api::Signal::Kill);
+                        }
+                        error
+                    })?;
+            }
+            InputStep::Sleep(duration) => thread::sleep(*duration),
+        }
+    }
+
+    Ok(())
+}
+
+/// Send the `signal` to the process with the `pid`
+fn send_signal(pid: u32, signal: api::Signal) {
+    debug!("Sending {signal} to process with pid '{pid}'");
+    let Ok(pid) = libc::pid_t::try_from(pid) else {
+        debug!("Failed to send {signal}: Invalid pid '{pid}'");
+        return;
+    };
+
+    // SAFETY: `kill` takes only integer arguments and doesn't access the memory of this process
+    if unsafe { libc::kill(pid, signal.number()) } != 0 {
+        debug!("Failed to send {signal}: {}", io::Error::last_os_error());
+    }
+}
+
+/// Wait for the `child` to exit and send the signal of `terminate` to it after the configured time
+///
+/// The signal is sent with `kill(2)`. If the `child` has already exited, no signal is sent. The
+/// output of the `child` is collected like in [`Child::wait_with_output`].
+fn wait_and_terminate(child: Child, terminate: api::Terminate) -> io::Result<Output> {
+    let pid = child.id();
+    let (sender, receiver) = mpsc::channel::<()>();
+    let handle = thread::spawn(move || {
+        if receiver.recv_timeout(terminate.after) == Err(RecvTimeoutError::Timeout) {
+            send_signal(pid, terminate.signal);
+        }
+    });
+
+    let output = child.wait_with_output();
+    // The receiver is dropped if the signal was already sent
+    _ = sender.send(());
+    handle
+        .join()
+        .expect("The thread sending the signal should not panic");
+
+    output
+}
+
+/// Wait for the `child` to exit while its stdin is driven by the `input_script`
+///
+/// The stdin and stdout of the `child` have to be piped. The stdout is read in a separate thread,
+/// so the [`InputStep::WaitFor`] steps can wait for the expected output. If `tee` is true, the
+/// stdout is also forwarded to the stdout of the runner instead of being captured. The signal of
+/// `terminate` is sent as in [`wait_and_terminate`].
+fn wait_with_script(
+    mut child: Child,
+    input_script: &api::InputScript,
+    terminate: Option<api::Terminate>,
+    tee: bool,
+) -> Result<Output, String> {
+    let pid = child.id();
+    let stdin = child
+        .stdin
+        .take()
+        .ok_or_else(|| "The stdin of the executable should be piped".to_owned())?;
+    let buffer = Arc::new(OutputBuffer::default());
+    let reader = child
+        .stdout
+        .take()
+        .map(|stdout| buffer.read_from(stdout, tee));
+
+    let script_result = thread::scope(|scope| {
+        let handle = scope.spawn(|| run_input_script(input_script, stdin, &buffer, pid));
+        let output = match terminate {
+            Some(terminate) => wait_and_terminate(child, terminate),
+            None => child.wait_with_output(),
+        };
+        let result = handle
+            .join()
+            .expect("The thread running the input script should not panic");
+        result.and_then(|()| output.map_err(|error| error.to_string()))
+    });
+
+    if let Some(reader) = reader {
+        reader
+            .join()
+            .expect("The thread reading the stdout should not panic");
+    }
+
+    let mut output = script_result?;
+    if !tee {
+        output.stdout = buffer.take();
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case::empty("", &[])]
+    #[case::no_markers("some\noutput\n", &[(None, "some\noutput\n")])]
+    #[case::setup(
+        "[iai-callgrind] setup\nsetup\n[iai-callgrind] end of setup\nbench\n",
+        &[(Some(Phase::Setup), "setup\n"), (None, "bench\n")]
+    )]
+    #[case::teardown(
+        "bench\n[iai-callgrind] teardown\nteardown\n[iai-callgrind] end of teardown\n",
+        &[(None, "bench\n"), (Some(Phase::Teardown), "teardown\n")]
+    )]
+    #[case::silent_phases(
+        "[iai-callgrind] setup\n[iai-callgrind] end of setup\nbench\n[iai-callgrind] \
+         teardown\n[iai-callgrind] end of teardown\n",
+        &[(None, "bench\n")]
+    )]
+    #[case::missing_end_marker(
+        "[iai-callgrind] setup\nsetup",
+        &[(Some(Phase::Setup), "setup")]
+    )]
+    #[case::carriage_return(
+        "[iai-callgrind] setup\r\nsetup\r\n[iai-callgrind] end of setup\r\n",
+        &[(Some(Phase::Setup), "setup\r\n")]
+    )]
+    #[case::marker_not_on_own_line(
+        "bench [iai-callgrind] setup\n",
+        &[(None, "bench [iai-callgrind] setup\n")]
+    )]
+    fn test_split_at_phase_markers(
+        #[case] output: &str,
+        #[case] expected: &[(Option<Phase>, &str)],
+    ) {
+        let expected = expected
+            .iter()
+            .map(|(phase, part)| (*phase, part.as_bytes()))
+            .collect::<Vec<_>>();
+        assert_eq!(split_at_phase_markers(output.as_bytes()), expected);
+    }
+
+    #[test]
+    fn test_strip_phase_markers() {
+        assert_eq!(
+            strip_phase_markers(
+                b"[iai-callgrind] setup\nsetup\n[iai-callgrind] end of \
+                  setup\nbench\n[iai-callgrind] teardown\n[iai-callgrind] end of teardown\n"
+            ),
+            b"setup\nbench\n"
+        );
+    }
+
+    #[rstest]
+    #[case::empty_needle("abc", "", Some(0))]
+    #[case::empty_haystack("", "a", None)]
+    #[case::start("abc", "ab", Some(0))]
+    #[case::end("abc", "bc", Some(1))]
+    #[case::first_of_many("abcabc", "c", Some(2))]
+    #[case::not_found("abc", "cb", None)]
+    #[case::needle_longer("ab", "abc", None)]
+    fn test_find_bytes(
+        #[case] haystack: &str,
+        #[case] needle: &str,
+        #[case] expected: Option<usize>,
+    ) {
+        assert_eq!(find_bytes(haystack.as_bytes(), needle.as_bytes()), expected);
+    }
+
+    #[test]
+    fn test_output_buffer_wait_for() {
+        let buffer = OutputBuffer::default();
+        buffer.state.lock().unwrap().bytes = b"> foo\n> bar\n> ".to_vec();
+
+        let position = buffer.wait_for("> ", 0, Duration::ZERO).unwrap();
+        assert_eq!(position, 2);
+        let position = buffer.wait_for("> ", position, Duration::ZERO).unwrap();
+        assert_eq!(position, 8);
+        let position = buffer.wait_for("> ", position, Duration::ZERO).unwrap();
+        assert_eq!(position, 14);
+
+        assert_eq!(
+            buffer.wait_for("> ", position, Duration::ZERO).unwrap_err(),
+            "Timeout of 0ns exceeded waiting for '> ' on stdout"
+        );
+
+        buffer.state.lock().unwrap().is_closed = true;
+        assert_eq!(
+            buffer.wait_for("baz\n", 0, Duration::ZERO).unwrap_err(),
+            "The stdout was closed before 'baz\\n' was found"
+        );
+    }
+
+    #[test]
+    fn test_output_buffer_wait_for_when_output_arrives_later() {
+        let buffer = OutputBuffer::default();
+
+        let position = thread::scope(|scope| {
+            scope.spawn(|| {
+                thread::sleep(Duration::from_millis(50));
+                buffer.state.lock().unwrap().bytes.extend_from_slice(b"> ");
+                buffer.changed.notify_all();
+            });
+            buffer.wait_for("> ", 0, Duration::from_secs(10))
+        });
+
+        assert_eq!(position, Ok(2));
+    }
+
+    #[test]
+    fn test_output_buffer_wait_for_when_closed_while_waiting() {
+        let buffer = OutputBuffer::default();
+        let start = Instant::now();
+
+        let result = thread::scope(|scope| {
+            scope.spawn(|| {
+                thread::sleep(Duration::from_millis(50));
+                let mut state = buffer.state.lock().unwrap();
+                state.bytes.extend_from_slice(b"> ");
+                state.is_closed = true;
+                buffer.changed.notify_all();
+            });
+            buffer.wait_for("bye", 0, Duration::from_secs(60))
+        });
+
+        assert_eq!(
+            result.unwrap_err(),
+            "The stdout was closed before 'bye' was found"
+        );
+        assert!(start.elapsed() < Duration::from_secs(60));
+    }
+
+    #[test]
+    fn test_run_input_script_when_timeout_then_kill() {
+        let mut child = Command::new(resolve_binary_path("cat").unwrap())
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::null())
+            .spawn()
+            .unwrap();
+        let stdin = child.stdin.take().unwrap();
+        let input_script = api::InputScript {
+            steps: vec![InputStep::WaitFor("never".to_owned())],
+            timeout: Some(Duration::from_millis(100)),
+        };
+
+        assert_eq!(
+            run_input_script(&input_script, stdin, &OutputBuffer::default(), child.id())
+                .unwrap_err(),
+            "Timeout of 100ms exceeded waiting for 'never' on stdout"
+        );
+        assert_eq!(child.wait().unwrap().signal(), Some(libc::SIGKILL));
+    }
+
+    #[test]
+    fn test_wait_with_script() {
+        let child = Command::new(resolve_binary_path("cat").unwrap())
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .spawn()
+            .unwrap();
+        let input_script = api::InputScript {
+            steps: vec![
+                InputStep::Write(b"hello\n".to_vec()),
+                InputStep::WaitFor("hello".to_owned()),
+                InputStep::Sleep(Duration::from_millis(1)),
+                InputStep::Write(b"bye\n".to_vec()),
+            ],
+            timeout: Some(Duration::from_secs(10)),
+        };
+
+        let output = wait_with_script(child, &input_script, None, false).unwrap();
+        assert!(output.status.success());
+        assert_eq!(output.stdout, b"hello\nbye\n");
+    }
+
+    #[test]
+    fn test_wait_with_script_when_timeout_then_killed() {
+        let child = Command::new(resolve_binary_path("cat").unwrap())
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .spawn()
+            .unwrap();
+        let input_script = api::InputScript {
+            steps: vec![
+                InputStep::Write(b"hello\n".to_vec()),
+                InputStep::WaitFor("bye".to_owned()),
+            ],
+            timeout: Some(Duration::from_millis(100)),
+        };
+
+        assert_eq!(
+            wait_with_script(child, &input_script, None, false).unwrap_err(),
+            "Timeout of 100ms exceeded waiting for 'bye' on stdout"
+        );
+    }
+
+    #[test]
+    fn test_wait_with_script_when_stdout_closed_early_then_error() {
+        let child = Command::new(resolve_binary_path("sh").unwrap())
+            .args(["-c", "echo hello"])
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .spawn()
+            .unwrap();
+        let input_script = api::InputScript {
+            steps: vec![InputStep::WaitFor("bye".to_owned())],
+            timeout: Some(Duration::from_secs(60)),
+        };
+        let start = Instant::now();
+
+        assert_eq!(
+            wait_with_script(child, &input_script, None, false).unwrap_err(),
+            "The stdout was closed before 'bye' was found"
+        );
+        assert!(start.elapsed() < Duration::from_secs(60));
+    }
+
+    #[test]
+    fn test_wait_with_script_when_stdin_closed_then_error() {
+        let child = Command::new(resolve_binary_path("true").unwrap())
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .spawn()
+            .unwrap();
+        let input_script = api::InputScript {
+            steps: vec![
+                InputStep::Sleep(Duration::from_millis(200)),
+                InputStep::Write(b"hello\n".to_vec()),
+            ],
+            timeout: None,
+        };
+
+        assert!(wait_with_script(child, &input_script, None, false)
+            .unwrap_err()
+            .starts_with("Failed to write to the stdin: "));
+    }
+
+    #[rstest]
+    #[case::default(NoCapture::False, RunOptions::default(), false)]
+    #[case::nocapture(NoCapture::True, RunOptions::default(), true)]
+    #[case::nocapture_stdout(NoCapture::Stdout, RunOptions::default(), true)]
+    #[case::stdout_null(
+        NoCapture::False,
+        RunOptions { stdout: Some(api::Stdio::Null), ..Default::default() },
+        false
+    )]
+    #[case::stdout_inherit(
+        NoCapture::False,
+        RunOptions { stdout: Some(api::Stdio::Inherit), ..Default::default() },
+        true
+    )]
+    #[case::stderr_inherit(
+        NoCapture::False,
+        RunOptions { stderr: Some(api::Stdio::Inherit), ..Default::default() },
+        true
+    )]
+    #[case::input_script(
+        NoCapture::False,
+        RunOptions { input_script: Some(api::InputScript::default()), ..Default::default() },
+        true
+    )]
+    #[case::vgdb(
+        NoCapture::False,
+        RunOptions { vgdb: Some(VgdbMode::At(0)), ..Default::default() },
+        true
+    )]
+    #[case::step_stdout_inherit(
+        NoCapture::False,
+        RunOptions {
+            steps: vec![Step {
+                args: vec![],
+                executable: PathBuf::from("step"),
+                run_options: RunOptions {
+                    stdout: Some(api::Stdio::Inherit),
+                    ..Default::default()
+                },
+            }],
+            ..Default::default()
+        },
+        true
+    )]
+    fn test_run_options_is_serial(
+        #[case] nocapture: NoCapture,
+        #[case] run_options: RunOptions,
+        #[case] expected: bool,
+    ) {
+        assert_eq!(run_options.is_serial(nocapture), expected);
+    }
+}
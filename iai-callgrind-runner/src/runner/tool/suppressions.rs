@@ -0,0 +1,147 @@
+//! The module containing the collection of the suppressions of `--gen-suppressions=all`
+//!
+//! With `--gen-suppressions=all`, the error checking tools like `Memcheck` print a suppression for
+//! each error into the log file. These suppressions are collected from the log files of a benchmark
+//! run and written to suppression files next to the log files in the benchmark output directory,
+//! ready to be used with `--suppressions` or `Tool::suppressions`.
+
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+
+use super::logfile_parser::STRIP_PREFIX_RE;
+use super::path::ToolOutputPath;
+
+/// Collect the suppressions from all log files of the `log_path` into a suppression file
+///
+/// The suppressions of a log file `<tool>.<name>.log` are written to
+/// `<tool>.<name>.suppressions.supp` (see [`ToolOutputPath::to_artifact_path`]). Duplicate
+/// suppressions are only written once. No file is created if the log file doesn't contain any
+/// suppressions.
+///
+/// # Errors
+///
+/// Returns an error if a log file could not be read or the suppression file could not be written
+pub fn create(log_path: &ToolOutputPath) -> Result<Vec<PathBuf>> {
+    let mut paths = vec![];
+    for path in log_path.real_paths()? {
+        let file = File::open(&path)
+            .with_context(|| format!("Error opening log file '{}'", path.display()))?;
+        let lines = BufReader::new(file)
+            .lines()
+            .collect::<std::io::Result<Vec<_>>>()
+            .with_context(|| format!("Error reading log file '{}'", path.display()))?;
+
+        let suppressions = extract(lines.into_iter());
+        if suppressions.is_empty() {
+            continue;
+        }
+
+        let supp_path = log_path.to_artifact_path(&path, "suppressions", "supp");
+        std::fs::write(&supp_path, suppressions.join("\n")).with_context(|| {
+            format!("Failed to write suppression file '{}'", supp_path.display())
+        })?;
+        paths.push(supp_path);
+    }
+
+    Ok(paths)
+}
+
+/// Extract the suppressions from the `lines` of a log file
+///
+/// A suppression starts with a line containing just `{` and ends with a line containing just `}`.
+/// Valgrind doesn't prefix these lines with the `==<pid>==` prefix, however if present, the prefix
+/// is stripped. The suppressions are returned with a trailing newline in the order of their first
+/// occurrence without duplicates.
+pub fn extract<I>(lines: I) -> Vec<String>
+where
+    I: Iterator<Item = String>,
+{
+    let mut suppressions: Vec<String> = vec![];
+    let mut current: Option<String> = None;
+    for line in lines {
+        let line = STRIP_PREFIX_RE
+            .captures(&line)
+            .and_then(|caps| caps.name("rest"))
+            .map_or(line.as_str(), |rest| rest.as_str())
+            .trim_end();
+
+        if let Some(suppression) = current.as_mut() {
+            suppression.push_str(line);
+            suppression.push('\n');
+            if line == "}" {
+                let suppression = current.take().expect("A suppression should be present");
+                if !suppressions.contains(&suppression) {
+                    suppressions.push(suppression);
+                }
+            }
+        } else if line == "{" {
+            current = Some("{\n".to_owned());
+        } else {
+            // do nothing
+        }
+    }
+
+    suppressions
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    const SUPPRESSION: &str = "{
+   <insert_a_suppression_name_here>
+   Memcheck:Leak
+   match-leak-kinds: definite
+   fun:malloc
+   fun:main
+}
+";
+
+    fn lines(input: &str) -> impl Iterator<Item = String> + '_ {
+        input.lines().map(ToOwned::to_owned)
+    }
+
+    #[test]
+    fn test_extract() {
+        let input = format!(
+            "==1234== 8 bytes in 1 blocks are definitely lost in loss record 1 of 1
+==1234==    at 0x4846828: malloc (vg_replace_malloc.c:442)
+==1234==    by 0x10916D: main (some.c:5)
+==1234==
+{SUPPRESSION}==1234==
+==1234== LEAK SUMMARY:"
+        );
+
+        assert_eq!(extract(lines(&input)), vec![SUPPRESSION.to_owned()]);
+    }
+
+    #[test]
+    fn test_extract_when_prefixed() {
+        let input = SUPPRESSION
+            .lines()
+            .map(|line| format!("==1234== {line}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        assert_eq!(extract(lines(&input)), vec![SUPPRESSION.to_owned()]);
+    }
+
+    #[test]
+    fn test_extract_when_duplicates_then_once() {
+        let other = SUPPRESSION.replace("fun:main", "fun:other");
+        let input = format!("{SUPPRESSION}{other}{SUPPRESSION}");
+
+        assert_eq!(extract(lines(&input)), vec![SUPPRESSION.to_owned(), other]);
+    }
+
+    #[test]
+    fn test_extract_when_no_suppressions() {
+        let input = "==1234== ERROR SUMMARY: 0 errors from 0 contexts (suppressed: 0 from 0)";
+        assert!(extract(lines(input)).is_empty());
+    }
+}
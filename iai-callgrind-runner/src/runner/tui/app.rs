@@ -0,0 +1,278 @@
+//! The module containing the state and the event handling of the results browser
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use ratatui::crossterm::event::KeyCode;
+use ratatui::widgets::ListState;
+
+use crate::runner::summary::{BenchmarkSummary, Profile};
+
+/// The key bindings shown in the footer
+pub const HELP: &str =
+    "↑/↓ j/k: benchmark  ←/→ h/l: tool  f: open flamegraph  o: open log  q: quit";
+
+/// The action the event loop has to take after a key press
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    /// Keep the browser open and draw the next frame
+    Continue,
+    /// Open the file with the default application
+    Open(PathBuf),
+    /// Close the browser
+    Quit,
+}
+
+/// The state of the results browser
+#[derive(Debug)]
+pub struct App<'a> {
+    /// The state of the benchmark list including the selected benchmark
+    pub benchmarks: ListState,
+    /// A message shown in the footer instead of the key bindings, like errors
+    pub message: Option<String>,
+    /// The index of the selected tool (profile) of the selected benchmark
+    pub profile: usize,
+    /// The benchmark summaries to browse
+    pub summaries: &'a [BenchmarkSummary],
+}
+
+impl<'a> App<'a> {
+    /// Create a new `App` with the first benchmark and tool selected
+    pub fn new(summaries: &'a [BenchmarkSummary]) -> Self {
+        Self {
+            benchmarks: ListState::default().with_selected(Some(0)),
+            message: None,
+            profile: 0,
+            summaries,
+        }
+    }
+
+    /// Handle a key press and return the [`Action`] the event loop has to take
+    ///
+    /// The files are not opened here but by the event loop (see [`App::opened`]).
+    pub fn handle_key(&mut self, code: KeyCode) -> Action {
+        self.message = None;
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => return Action::Quit,
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.select(|index| index.saturating_add(1));
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.select(|index| index.saturating_sub(1));
+            }
+            KeyCode::Right | KeyCode::Char('l') => {
+                let num_profiles = self.summary().map_or(0, |summary| summary.profiles.0.len());
+                if self.profile + 1 < num_profiles {
+                    self.profile += 1;
+                }
+            }
+            KeyCode::Left | KeyCode::Char('h') => {
+                self.profile = self.profile.saturating_sub(1);
+            }
+            KeyCode::Char('f') => {
+                return self.open(|profile| {
+                    profile
+                        .flamegraphs
+                        .iter()
+                        .find_map(|flamegraph| flamegraph.regular_path.clone())
+                });
+            }
+            KeyCode::Char('o') => return self.open(|profile| profile.log_paths.first().cloned()),
+            _ => {}
+        }
+
+        Action::Continue
+    }
+
+    /// Show the `result` of opening the file at `path` in the footer
+    pub fn opened(&mut self, path: &Path, result: Result<()>) {
+        self.message = Some(match result {
+            Ok(()) => format!("Opened '{}'", path.display()),
+            Err(error) => error.to_string(),
+        });
+    }
+
+    /// Return the [`Profile`] of the selected tool of the selected benchmark
+    pub fn profile(&self) -> Option<&'a Profile> {
+        self.summary()
+            .and_then(|summary| summary.profiles.0.get(self.profile))
+    }
+
+    /// Return the selected [`BenchmarkSummary`]
+    pub fn summary(&self) -> Option<&'a BenchmarkSummary> {
+        self.benchmarks
+            .selected()
+            .and_then(|index| self.summaries.get(index))
+    }
+
+    /// Return the [`Action::Open`] for the file selected by `select` from the selected profile
+    fn open<F>(&mut self, select: F) -> Action
+    where
+        F: FnOnce(&Profile) -> Option<PathBuf>,
+    {
+        if let Some(path) = self.profile().and_then(select) {
+            Action::Open(path)
+        } else {
+            self.message = Some("No such file for this tool".to_owned());
+            Action::Continue
+        }
+    }
+
+    /// Select the benchmark at the index returned by `next` and reset the selected tool
+    ///
+    /// The index is clamped to the last benchmark.
+    fn select<F>(&mut self, next: F)
+    where
+        F: FnOnce(usize) -> usize,
+    {
+        let Some(last) = self.summaries.len().checked_sub(1) else {
+            return;
+        };
+        let index = next(self.benchmarks.selected().unwrap_or_default()).min(last);
+        self.benchmarks.select(Some(index));
+        self.profile = 0;
+    }
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use std::fs::File;
+
+    use anyhow::anyhow;
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::*;
+    use crate::api::ValgrindTool;
+    use crate::runner::summary::FlamegraphSummary;
+
+    /// Load the [`BenchmarkSummary`] of the callgrind summary fixture of the integration tests
+    ///
+    /// The unit tests can't use the `Fixtures` of the integration tests, so this is the counterpart
+    /// of `Fixtures::load_summary` for the unit tests of the tui.
+    pub(in crate::runner::tui) fn load_summary() -> BenchmarkSummary {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/summary/summary.callgrind.json");
+        serde_json::from_reader(File::open(path).unwrap()).unwrap()
+    }
+
+    /// Return the summaries of the `summary.callgrind.json` fixture with a DHAT profile and `ids`
+    fn summaries(ids: &[&str]) -> Vec<BenchmarkSummary> {
+        let summary = load_summary();
+
+        ids.iter()
+            .map(|id| {
+                let mut summary = summary.clone();
+                summary.id = Some((*id).to_owned());
+
+                let mut dhat = summary.profiles.0[0].clone();
+                dhat.tool = ValgrindTool::DHAT;
+                dhat.log_paths = vec![];
+                summary.profiles.0.push(dhat);
+                summary
+            })
+            .collect()
+    }
+
+    #[rstest]
+    #[case::down(&[KeyCode::Down], 1, 0)]
+    #[case::j(&[KeyCode::Char('j')], 1, 0)]
+    #[case::down_after_last(&[KeyCode::Down, KeyCode::Down, KeyCode::Down], 2, 0)]
+    #[case::up_at_first(&[KeyCode::Up], 0, 0)]
+    #[case::k(&[KeyCode::Down, KeyCode::Char('k')], 0, 0)]
+    #[case::right(&[KeyCode::Right], 0, 1)]
+    #[case::l_after_last(&[KeyCode::Char('l'), KeyCode::Char('l')], 0, 1)]
+    #[case::left_at_first(&[KeyCode::Left], 0, 0)]
+    #[case::h(&[KeyCode::Right, KeyCode::Char('h')], 0, 0)]
+    #[case::down_resets_tool(&[KeyCode::Right, KeyCode::Down], 1, 0)]
+    #[case::unknown_key(&[KeyCode::Char('x')], 0, 0)]
+    fn test_app_handle_key_navigation(
+        #[case] codes: &[KeyCode],
+        #[case] expected_benchmark: usize,
+        #[case] expected_profile: usize,
+    ) {
+        let summaries = summaries(&["one", "two", "three"]);
+        let mut app = App::new(&summaries);
+
+        for code in codes {
+            assert_eq!(app.handle_key(*code), Action::Continue);
+        }
+
+        assert_eq!(app.benchmarks.selected(), Some(expected_benchmark));
+        assert_eq!(app.summary(), Some(&summaries[expected_benchmark]));
+        assert_eq!(app.profile, expected_profile);
+        assert_eq!(
+            app.profile(),
+            Some(&summaries[expected_benchmark].profiles.0[expected_profile])
+        );
+    }
+
+    #[rstest]
+    #[case::q(KeyCode::Char('q'))]
+    #[case::esc(KeyCode::Esc)]
+    fn test_app_handle_key_quit(#[case] code: KeyCode) {
+        let summaries = summaries(&["one"]);
+        assert_eq!(App::new(&summaries).handle_key(code), Action::Quit);
+    }
+
+    #[test]
+    fn test_app_handle_key_when_no_benchmarks() {
+        let mut app = App::new(&[]);
+
+        assert_eq!(app.handle_key(KeyCode::Down), Action::Continue);
+        assert_eq!(app.handle_key(KeyCode::Right), Action::Continue);
+        assert_eq!(app.handle_key(KeyCode::Char('o')), Action::Continue);
+        assert_eq!(app.summary(), None);
+        assert_eq!(app.profile(), None);
+        assert_eq!(app.message.as_deref(), Some("No such file for this tool"));
+    }
+
+    #[test]
+    fn test_app_handle_key_open_log() {
+        let summaries = summaries(&["one"]);
+        let mut app = App::new(&summaries);
+
+        assert_eq!(
+            app.handle_key(KeyCode::Char('o')),
+            Action::Open(summaries[0].profiles.0[0].log_paths[0].clone())
+        );
+        assert_eq!(app.message, None);
+
+        // The DHAT profile has no log files
+        app.handle_key(KeyCode::Right);
+        assert_eq!(app.handle_key(KeyCode::Char('o')), Action::Continue);
+        assert_eq!(app.message.as_deref(), Some("No such file for this tool"));
+    }
+
+    #[test]
+    fn test_app_handle_key_open_flamegraph() {
+        let mut summaries = summaries(&["one"]);
+        let mut app = App::new(&summaries);
+        assert_eq!(app.handle_key(KeyCode::Char('f')), Action::Continue);
+        assert_eq!(app.message.as_deref(), Some("No such file for this tool"));
+
+        let mut flamegraph = FlamegraphSummary::new(crate::api::EventKind::Ir);
+        flamegraph.regular_path = Some(PathBuf::from("/tmp/flamegraph.svg"));
+        summaries[0].profiles.0[0].flamegraphs.push(flamegraph);
+
+        let mut app = App::new(&summaries);
+        assert_eq!(
+            app.handle_key(KeyCode::Char('f')),
+            Action::Open(PathBuf::from("/tmp/flamegraph.svg"))
+        );
+        // The message of the previous key press is cleared
+        assert_eq!(app.message, None);
+    }
+
+    #[test]
+    fn test_app_opened() {
+        let mut app = App::new(&[]);
+        let path = Path::new("/tmp/file.log");
+
+        app.opened(path, Ok(()));
+        assert_eq!(app.message.as_deref(), Some("Opened '/tmp/file.log'"));
+
+        app.opened(path, Err(anyhow!("No default application")));
+        assert_eq!(app.message.as_deref(), Some("No default application"));
+    }
+}
@@ -0,0 +1,59 @@
+//! The module containing the interactive results browser of the `--tui` argument
+//!
+//! The terminal output of a benchmark run with hundreds of benchmarks is hard to navigate in the
+//! scrollback of a terminal. The results browser presents the [`BenchmarkSummaries`] of a benchmark
+//! file after the run in a navigable terminal ui with a list of all benchmarks, the metrics of each
+//! tool including the comparison with the baseline and the possibility to open the flamegraphs and
+//! log files of a benchmark.
+//!
+//! Every benchmark file is run by its own runner process, so the browser shows the benchmarks of a
+//! single benchmark file and the next benchmark file is run after the browser was closed.
+
+pub mod app;
+pub mod ui;
+
+use anyhow::{Context, Result};
+use ratatui::crossterm::event::{self, Event, KeyEventKind};
+use ratatui::DefaultTerminal;
+
+use self::app::{Action, App};
+use super::common::BenchmarkSummaries;
+use crate::util::open_with_default_application;
+
+/// Show the [`BenchmarkSummaries`] in the interactive results browser
+///
+/// The terminal is restored when the browser is closed, even if an error occurred.
+pub fn browse(benchmark_summaries: &BenchmarkSummaries) -> Result<()> {
+    if benchmark_summaries.summaries.is_empty() {
+        return Ok(());
+    }
+
+    let mut terminal = ratatui::init();
+    let result = run_app(&mut terminal, App::new(&benchmark_summaries.summaries));
+    ratatui::restore();
+
+    result
+}
+
+/// Run the event loop of the results browser until it is closed
+fn run_app(terminal: &mut DefaultTerminal, mut app: App<'_>) -> Result<()> {
+    loop {
+        terminal
+            .draw(|frame| ui::render(frame, &mut app))
+            .with_context(|| "Failed to draw the results browser")?;
+
+        if let Event::Key(key) = event::read().with_context(|| "Failed to read terminal events")? {
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match app.handle_key(key.code) {
+                Action::Continue => {}
+                Action::Open(path) => {
+                    let result = open_with_default_application(&path);
+                    app.opened(&path, result);
+                }
+                Action::Quit => return Ok(()),
+            }
+        }
+    }
+}
@@ -0,0 +1,297 @@
+//! The module containing the rendering of the results browser
+
+use std::fmt::Display;
+use std::hash::Hash;
+
+use either_or_both::EitherOrBoth;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, List, ListItem, Paragraph, Row, Table, Tabs, Wrap};
+use ratatui::Frame;
+
+use super::app::{App, HELP};
+use crate::runner::format::NOT_AVAILABLE;
+use crate::runner::metrics::{MetricsDiff, MetricsSummary};
+use crate::runner::summary::{ToolMetricSummary, ToolRegression};
+use crate::util::to_string_signed_short;
+
+/// Return the table rows of the [`ToolMetricSummary`]
+fn metric_rows(summary: &ToolMetricSummary) -> Vec<Row<'static>> {
+    match summary {
+        ToolMetricSummary::None => vec![],
+        ToolMetricSummary::ErrorTool(summary) => metrics_summary_rows(summary),
+        ToolMetricSummary::Dhat(summary) => metrics_summary_rows(summary),
+        ToolMetricSummary::Callgrind(summary) => metrics_summary_rows(summary),
+        ToolMetricSummary::Cachegrind(summary) => metrics_summary_rows(summary),
+    }
+}
+
+fn metrics_diff_row<K: Display>(metric_kind: &K, diff: &MetricsDiff) -> Row<'static> {
+    let (new, old) = match &diff.metrics {
+        EitherOrBoth::Left(new) => (new.to_string(), NOT_AVAILABLE.to_owned()),
+        EitherOrBoth::Right(old) => (NOT_AVAILABLE.to_owned(), old.to_string()),
+        EitherOrBoth::Both(new, old) => (new.to_string(), old.to_string()),
+    };
+    let (diff_pct, factor, color) = match diff.diffs {
+        Some(diffs) if diffs.diff_pct > 0.0 => (diffs.diff_pct, diffs.factor, Color::Red),
+        Some(diffs) if diffs.diff_pct < 0.0 => (diffs.diff_pct, diffs.factor, Color::Green),
+        Some(diffs) => (diffs.diff_pct, diffs.factor, Color::Reset),
+        None => {
+            return Row::new([metric_kind.to_string(), new, old]);
+        }
+    };
+
+    Row::new([
+        metric_kind.to_string(),
+        new,
+        old,
+        format!("{}%", to_string_signed_short(diff_pct)),
+        format!("{}x", to_string_signed_short(factor)),
+    ])
+    .fg(color)
+}
+
+fn metrics_summary_rows<K>(summary: &MetricsSummary<K>) -> Vec<Row<'static>>
+where
+    K: Hash + Eq + Display,
+{
+    summary
+        .0
+        .iter()
+        .map(|(metric_kind, diff)| metrics_diff_row(metric_kind, diff))
+        .collect()
+}
+
+fn regression_to_string(regression: &ToolRegression) -> String {
+    match regression {
+        ToolRegression::Soft {
+            metric,
+            new,
+            old,
+            diff_pct,
+            limit,
+        } => format!(
+            "{metric}: {new} > {old} ({}% > limit {}%)",
+            to_string_signed_short(*diff_pct),
+            to_string_signed_short(*limit)
+        ),
+        ToolRegression::Hard {
+            metric,
+            new,
+            diff,
+            limit,
+        } => format!("{metric}: {new} > {limit} (+{diff})"),
+    }
+}
+
+/// Render the results browser with the state of the [`App`]
+pub fn render(frame: &mut Frame<'_>, app: &mut App<'_>) {
+    let [main, footer] =
+        Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).areas(frame.area());
+    let [list, details] =
+        Layout::horizontal([Constraint::Percentage(35), Constraint::Percentage(65)]).areas(main);
+
+    render_list(frame, app, list);
+    render_details(frame, app, details);
+
+    let footer_text = app.message.as_deref().unwrap_or(HELP);
+    frame.render_widget(Paragraph::new(footer_text).dim(), footer);
+}
+
+fn render_details(frame: &mut Frame<'_>, app: &App<'_>, area: Rect) {
+    let Some(summary) = app.summary() else {
+        frame.render_widget(Block::bordered().title("No benchmarks"), area);
+        return;
+    };
+
+    let baselines = match &summary.baselines {
+        (None, None) => "Baselines: default".to_owned(),
+        (new, old) => format!(
+            "Baselines: {}|{}",
+            new.as_deref().unwrap_or("default"),
+            old.as_deref().unwrap_or("default")
+        ),
+    };
+    let mut lines = vec![
+        Line::from(summary.module_path.as_str().bold()),
+        Line::from(baselines),
+    ];
+    if let Some(description) = &summary.description {
+        lines.push(Line::from(format!("Description: {description}")));
+    }
+    if let Some(details) = &summary.details {
+        lines.push(Line::from(format!("Details: {details}")));
+    }
+
+    // The header is as high as its lines plus the borders, so the details are not cut off
+    let header_height = u16::try_from(lines.len()).map_or(u16::MAX, |len| len.saturating_add(2));
+    let [header, tabs, metrics, regressions] = Layout::vertical([
+        Constraint::Length(header_height),
+        Constraint::Length(1),
+        Constraint::Min(3),
+        Constraint::Length(6),
+    ])
+    .areas(area);
+
+    frame.render_widget(
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .block(Block::bordered()),
+        header,
+    );
+
+    let titles = summary
+        .profiles
+        .0
+        .iter()
+        .map(|profile| profile.tool.to_string());
+    frame.render_widget(
+        Tabs::new(titles)
+            .select(app.profile)
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED)),
+        tabs,
+    );
+
+    let Some(profile) = app.profile() else {
+        return;
+    };
+
+    let widths = [
+        Constraint::Fill(2),
+        Constraint::Fill(1),
+        Constraint::Fill(1),
+        Constraint::Fill(1),
+        Constraint::Fill(1),
+    ];
+    let table = Table::new(metric_rows(&profile.summaries.total.summary), widths)
+        .header(Row::new(["Metric", "New", "Old", "Diff", "Factor"]).bold())
+        .block(Block::bordered().title("Total"));
+    frame.render_widget(table, metrics);
+
+    let regressions_lines = profile
+        .summaries
+        .total
+        .regressions
+        .iter()
+        .map(|regression| Line::from(regression_to_string(regression)).red())
+        .collect::<Vec<_>>();
+    let title = if regressions_lines.is_empty() {
+        "No regressions"
+    } else {
+        "Regressions"
+    };
+    frame.render_widget(
+        Paragraph::new(regressions_lines).block(Block::bordered().title(title)),
+        regressions,
+    );
+}
+
+fn render_list(frame: &mut Frame<'_>, app: &mut App<'_>, area: Rect) {
+    let items = app.summaries.iter().map(|summary| {
+        let name = summary.id.as_ref().map_or_else(
+            || summary.function_name.clone(),
+            |id| format!("{}::{id}", summary.function_name),
+        );
+        if summary.is_regressed() {
+            ListItem::new(Line::from(vec![Span::raw(name), " (regressed)".red()]))
+        } else {
+            ListItem::new(name)
+        }
+    });
+
+    let list = List::new(items)
+        .block(Block::bordered().title(format!("Benchmarks ({})", app.summaries.len())))
+        .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+    frame.render_stateful_widget(list, area, &mut app.benchmarks);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use pretty_assertions::assert_eq;
+    use ratatui::backend::TestBackend;
+    use ratatui::buffer::Cell;
+    use ratatui::crossterm::event::KeyCode;
+    use ratatui::Terminal;
+
+    use super::*;
+    use crate::runner::tui::app::tests::load_summary;
+
+    /// Render the `app` into a terminal of 120x24 cells and return the lines of the screen
+    fn render_to_lines(app: &mut App<'_>) -> Vec<String> {
+        let mut terminal = Terminal::new(TestBackend::new(120, 24)).unwrap();
+        terminal.draw(|frame| render(frame, app)).unwrap();
+
+        let buffer = terminal.backend().buffer();
+        buffer
+            .content
+            .chunks(usize::from(buffer.area.width))
+            .map(|line| line.iter().map(Cell::symbol).collect())
+            .collect()
+    }
+
+    fn contains(lines: &[String], text: &str) -> bool {
+        lines.iter().any(|line| line.contains(text))
+    }
+
+    #[test]
+    fn test_render_summary() {
+        let mut regressed = load_summary();
+        let mut not_regressed = regressed.clone();
+        not_regressed.id = Some("long".to_owned());
+        not_regressed.profiles.0[0].summaries.total.regressions = vec![];
+        regressed.id = Some("short".to_owned());
+
+        let summaries = [regressed, not_regressed];
+        let mut app = App::new(&summaries);
+        let lines = render_to_lines(&mut app);
+
+        for expected in [
+            "Benchmarks (2)",
+            "bench_fibonacci::short (regressed)",
+            "my_bench::my_group::bench_fibonacci",
+            "Baselines: default",
+            "Description: The recursive fibonacci implementation",
+            "Details: fibonacci(10)",
+            " callgrind ",
+            "Regressions",
+            "Callgrind: Instructions: 1734 > 1500 (+15.6000% > limit +10.0000%)",
+            HELP,
+        ] {
+            assert!(
+                contains(&lines, expected),
+                "'{expected}' in:\n{}",
+                lines.join("\n")
+            );
+        }
+        assert!(!contains(&lines, "bench_fibonacci::long (regressed)"));
+
+        let instructions = lines
+            .iter()
+            .find_map(|line| line.split_once("││Instructions"))
+            .expect("The row of the instructions should be present")
+            .1;
+        assert_eq!(
+            instructions.split_whitespace().collect::<Vec<_>>(),
+            ["1734", "1500", "+15.6000%", "+1.15600x", "│"]
+        );
+
+        app.handle_key(KeyCode::Down);
+        app.opened(Path::new("/tmp/file.log"), Ok(()));
+        let lines = render_to_lines(&mut app);
+        assert!(contains(&lines, "No regressions"));
+        assert!(contains(&lines, "Opened '/tmp/file.log'"));
+        assert!(!contains(&lines, HELP));
+    }
+
+    #[test]
+    fn test_render_when_no_benchmarks() {
+        let mut app = App::new(&[]);
+        let lines = render_to_lines(&mut app);
+
+        assert!(contains(&lines, "Benchmarks (0)"));
+        assert!(contains(&lines, "No benchmarks"));
+    }
+}
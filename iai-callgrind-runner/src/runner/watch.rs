@@ -0,0 +1,137 @@
+//! The module containing the [`Watcher`] of the `--watch` mode
+//!
+//! The encoded benchmarks are compiled into the benchmark executable, so a change to the benchmark
+//! code becomes visible to us only by running the rebuilt executable again. After the first run
+//! the runner stays alive, polls the modification time of the benchmark executable and re-runs it
+//! with the same command-line arguments (and therefore the same filter) as soon as it was rebuilt,
+//! for example by `cargo bench --no-run` or `cargo watch -x 'bench --no-run'`.
+
+use std::io::{stdout, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use anyhow::{Context, Result};
+use log::debug;
+
+/// The ansi escape sequence to clear the screen and move the cursor to the top left corner
+const CLEAR_SCREEN: &str = "\x1b[2J\x1b[H";
+
+/// The interval in which the modification time of the benchmark executable is checked
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Re-run the benchmark executable whenever it changes
+#[derive(Debug, Clone)]
+pub struct Watcher {
+    /// The arguments of the benchmark executable without the `--watch` argument
+    args: Vec<String>,
+    /// The path to the benchmark executable
+    bench_bin: PathBuf,
+    /// The last seen modification time of the benchmark executable
+    modified: Option<SystemTime>,
+}
+
+impl Watcher {
+    /// Create a new `Watcher` for the benchmark executable at `bench_bin`
+    ///
+    /// The `command_line_args` are the arguments the benchmark executable was originally run with.
+    pub fn new(bench_bin: &Path, command_line_args: &[String]) -> Self {
+        Self {
+            args: without_watch_args(command_line_args),
+            bench_bin: bench_bin.to_owned(),
+            modified: modified(bench_bin),
+        }
+    }
+
+    /// Wait for changes of the benchmark executable and re-run it
+    ///
+    /// This method only returns if the benchmark executable could not be run. The re-runs don't
+    /// watch themselves, so a regression or a failing benchmark in a re-run is reported by the
+    /// re-run but doesn't stop the `Watcher`. Stop watching with `Ctrl-C`.
+    pub fn watch(mut self) -> Result<()> {
+        loop {
+            eprintln!(
+                "Watching '{}' for changes. Press Ctrl-C to stop ...",
+                self.bench_bin.display()
+            );
+            self.wait_for_change();
+
+            let mut stdout = stdout();
+            if stdout.is_terminal() {
+                write!(stdout, "{CLEAR_SCREEN}")
+                    .and_then(|()| stdout.flush())
+                    .with_context(|| "Failed to clear the terminal")?;
+            }
+
+            let status = Command::new(&self.bench_bin)
+                .args(&self.args)
+                .env("IAI_CALLGRIND_WATCH", "false")
+                .status()
+                .with_context(|| {
+                    format!(
+                        "Failed to run the benchmark executable '{}'",
+                        self.bench_bin.display()
+                    )
+                })?;
+            debug!("Benchmark executable exited with: {status}");
+        }
+    }
+
+    /// Block until the benchmark executable was changed and the change is complete
+    ///
+    /// The executable is rewritten by the linker, so it is only considered to be changed if the
+    /// modification time didn't change anymore during the last poll interval.
+    fn wait_for_change(&mut self) {
+        loop {
+            thread::sleep(POLL_INTERVAL);
+            let current = modified(&self.bench_bin);
+            if current.is_none() || current == self.modified {
+                continue;
+            }
+
+            thread::sleep(POLL_INTERVAL);
+            if modified(&self.bench_bin) == current {
+                debug!(
+                    "Benchmark executable '{}' changed",
+                    self.bench_bin.display()
+                );
+                self.modified = current;
+                return;
+            }
+        }
+    }
+}
+
+/// Return the modification time of the file at `path` or `None` if it is not accessible
+fn modified(path: &Path) -> Option<SystemTime> {
+    path.metadata()
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+/// Remove the `--watch` argument from the `args`
+fn without_watch_args(args: &[String]) -> Vec<String> {
+    args.iter()
+        .filter(|arg| *arg != "--watch" && !arg.starts_with("--watch="))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case::empty(&[], &[])]
+    #[case::no_watch(&["--bench", "some"], &["--bench", "some"])]
+    #[case::watch(&["--bench", "--watch", "some"], &["--bench", "some"])]
+    #[case::watch_with_value(&["--watch=yes", "--bench"], &["--bench"])]
+    #[case::similar(&["--watcher"], &["--watcher"])]
+    fn test_without_watch_args(#[case] args: &[&str], #[case] expected: &[&str]) {
+        let args = args.iter().map(|arg| (*arg).to_owned()).collect::<Vec<_>>();
+        assert_eq!(without_watch_args(&args), expected);
+    }
+}
@@ -0,0 +1,569 @@
+//! The data model of the `summary.json` file
+//!
+//! All types in this module are (de-)serializable with `serde` and describe the `summary.json` file
+//! which is created with the `--save-summary` command-line argument or printed with
+//! `--output-format=json`. Backwards incompatible changes of these types result in an increase of
+//! the [`SCHEMA_VERSION`], so report generators and CI bots can deserialize the summaries without
+//! copying the struct definitions. To avoid pulling in all dependencies of the runner, activate the
+//! `summary` feature only:
+//!
+//! ```toml
+//! [dependencies]
+//! iai-callgrind-runner = { version = "0.16.1", default-features = false, features = ["summary"] }
+//! ```
+//!
+//! With the `runner` feature, these types are also re-exported in
+//! [`crate::runner::summary`](../runner/summary/index.html) and
+//! [`crate::runner::metrics`](../runner/metrics/index.html).
+
+#![allow(clippy::cast_precision_loss)]
+
+use std::cmp::Ordering;
+use std::fmt::Display;
+use std::hash::Hash;
+use std::path::PathBuf;
+
+use either_or_both::EitherOrBoth;
+use indexmap::IndexMap;
+#[cfg(feature = "schema")]
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::api::{CachegrindMetric, DhatMetric, ErrorMetric, EventKind, ValgrindTool};
+
+/// The version of the summary json schema
+pub const SCHEMA_VERSION: &str = "6";
+
+/// The `ArtifactKind` describing the kind of a file produced by a benchmark
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+pub enum ArtifactKind {
+    /// A `*.branches.txt` report of the most unpredictable branches of callgrind
+    Branches,
+    /// A `*.calltree.txt` report of the call tree of callgrind
+    CallTree,
+    /// A heap summary svg file of DHAT (`*.heap-summary.svg`) or a chart of Massif (`*.chart.svg`)
+    Chart,
+    /// A flamegraph svg file
+    Flamegraph,
+    /// A `*.log` file of a valgrind tool
+    Log,
+    /// The `*.total.callgrind` file with the merged callgrind output files
+    Merged,
+    /// A `*.out` file of a valgrind tool like the callgrind output or the DHAT json file
+    Out,
+    /// A `*.peak.txt` file with the allocation tree of the peak snapshot of Massif
+    PeakTree,
+    /// The `run.log` file with the log messages of the runner and the captured output of the
+    /// benchmark
+    RunLog,
+    /// A `*.suppressions.supp` file with the suppressions generated by valgrind
+    Suppressions,
+}
+
+/// The `BaselineKind` describing the baseline
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+pub enum BaselineKind {
+    /// Compare new against `*.old` output files
+    Old,
+    /// Compare new against a named baseline
+    Name(BaselineName),
+}
+
+/// The `BenchmarkKind`, differentiating between library and binary benchmarks
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+pub enum BenchmarkKind {
+    /// A library benchmark
+    LibraryBenchmark,
+    /// A binary benchmark
+    BinaryBenchmark,
+}
+
+/// The metric measured by valgrind or derived from one or more other metrics
+///
+/// The valgrind metrics measured by any of its tools are `u64`. However, to be able to represent
+/// derived metrics like cache miss/hit rates it is inevitable to have a type which can store a
+/// `u64` or a `f64`. When doing math with metrics, the original type should be preserved as far as
+/// possible by using `u64` operations. A float metric should be a last resort.
+///
+/// Float operations with a `Metric` that stores a `u64` introduce a precision loss and are to be
+/// avoided. Especially comparison between a `u64` metric and `f64` metric are not exact because the
+/// `u64` has to be converted to a `f64`. Also, if adding/multiplying two `u64` metrics would result
+/// in an overflow the metric saturates at `u64::MAX`. This choice was made to preserve precision
+/// and the original type (instead of for example adding the two `u64` by converting both of them to
+/// `f64`).
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+pub enum Metric {
+    /// An integer `Metric`
+    Int(u64),
+    /// A float `Metric`
+    Float(f64),
+}
+
+/// The different metrics distinguished by tool and if it is an error checking tool as `ErrorMetric`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+pub enum MetricKind {
+    /// The `None` kind if there are no metrics for a tool
+    None,
+    /// The Callgrind metric kind
+    Callgrind(EventKind),
+    /// The Cachegrind metric kind
+    Cachegrind(CachegrindMetric),
+    /// The DHAT metric kind
+    Dhat(DhatMetric),
+    /// The Memcheck metric kind
+    Memcheck(ErrorMetric),
+    /// The Helgrind metric kind
+    Helgrind(ErrorMetric),
+    /// The DRD metric kind
+    DRD(ErrorMetric),
+}
+
+/// The format (json, ...) in which the summary file should be saved or printed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "runner", derive(clap::ValueEnum))]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+pub enum SummaryFormat {
+    /// The format in a space optimal json representation without newlines
+    Json,
+    /// The format in pretty printed json
+    PrettyJson,
+    /// The OpenMetrics text format with the metrics as labeled gauges
+    #[cfg_attr(feature = "runner", value(name = "openmetrics"))]
+    OpenMetrics,
+}
+
+/// The `ToolMetricSummary` contains the `MetricsSummary` distinguished by tool and metric kinds
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+pub enum ToolMetricSummary {
+    /// If there are no metrics extracted (currently massif, bbv)
+    #[default]
+    None,
+    /// The error summary of tools which reports errors (memcheck, helgrind, drd)
+    ErrorTool(MetricsSummary<ErrorMetric>),
+    /// The dhat summary
+    Dhat(MetricsSummary<DhatMetric>),
+    /// The callgrind summary
+    Callgrind(MetricsSummary<EventKind>),
+    /// The cachegrind summary
+    Cachegrind(MetricsSummary<CachegrindMetric>),
+}
+
+/// The metrics distinguished per tool class
+///
+/// The tool classes are: dhat, error metrics from memcheck, drd, helgrind and callgrind
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+pub enum ToolMetrics {
+    /// If there were no metrics extracted from a tool (currently massif, bbv)
+    #[default]
+    None,
+    /// The metrics of a dhat benchmark
+    Dhat(Metrics<DhatMetric>),
+    /// The metrics of a tool run which reports errors (memcheck, helgrind, drd)
+    ErrorTool(Metrics<ErrorMetric>),
+    /// The metrics of a callgrind benchmark
+    Callgrind(Metrics<EventKind>),
+    /// The metrics of a cachegrind benchmark
+    Cachegrind(Metrics<CachegrindMetric>),
+}
+
+/// A detected performance regression depending on the limit either `Soft` or `Hard`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+pub enum ToolRegression {
+    /// A performance regression triggered by a soft limit
+    Soft {
+        /// The metric kind per tool
+        metric: MetricKind,
+        /// The value of the new benchmark run
+        new: Metric,
+        /// The value of the old benchmark run
+        old: Metric,
+        /// The difference between new and old in percent. Serialized as string to preserve
+        /// infinity values and avoid null in json.
+        #[serde(with = "crate::serde::float_64")]
+        #[cfg_attr(feature = "schema", schemars(with = "String"))]
+        diff_pct: f64,
+        /// The value of the limit which was exceeded to cause a performance regression. Serialized
+        /// as string to preserve infinity values and avoid null in json.
+        #[serde(with = "crate::serde::float_64")]
+        #[cfg_attr(feature = "schema", schemars(with = "String"))]
+        limit: f64,
+    },
+    /// A performance regression triggered by a hard limit
+    Hard {
+        /// The metric kind per tool
+        metric: MetricKind,
+        /// The value of the benchmark run
+        new: Metric,
+        /// The difference between new and the limit
+        diff: Metric,
+        /// The limit
+        limit: Metric,
+    },
+}
+
+/// A file produced by a benchmark with its checksum
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+pub struct Artifact {
+    /// The kind of the file
+    pub kind: ArtifactKind,
+    /// The path to the file relative to the `project_root` or the absolute path if the file is
+    /// outside of the `project_root`
+    pub path: PathBuf,
+    /// The sha256 checksum of the content of the file as lowercase hexadecimal string
+    pub sha256: String,
+    /// The size of the file in bytes
+    pub size: u64,
+}
+
+/// A `Baseline` depending on the [`BaselineKind`] which points to the corresponding path
+///
+/// This baseline is used for comparisons with the new output of valgrind tools.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+pub struct Baseline {
+    /// The kind of the `Baseline`
+    pub kind: BaselineKind,
+    /// The path to the file which is used to compare against the new output
+    pub path: PathBuf,
+}
+
+/// The name of the baseline
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+pub struct BaselineName(pub(crate) String);
+
+/// The `BenchmarkSummary` containing all the information of a single benchmark run
+///
+/// This includes produced files, recorded callgrind events, performance regressions ...
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+pub struct BenchmarkSummary {
+    /// All files produced by this benchmark run like the `*.out` and `*.log` files of the valgrind
+    /// tools, the flamegraphs, the reports, the charts and the `run.log`
+    #[serde(default)]
+    pub artifacts: Vec<Artifact>,
+    /// The baselines if any. An absent first baseline indicates that new output was produced. An
+    /// absent second baseline indicates the usage of the usual "*.old" output.
+    pub baselines: (Option<String>, Option<String>),
+    /// The path to the binary which is executed by valgrind. In case of a library benchmark this
+    /// is the compiled benchmark file. In case of a binary benchmark this is the path to the
+    /// command.
+    pub benchmark_exe: PathBuf,
+    /// The path to the benchmark file
+    pub benchmark_file: PathBuf,
+    /// The user provided description of the benchmark
+    pub description: Option<String>,
+    /// More details describing this benchmark run
+    pub details: Option<String>,
+    /// The environment of the benchmark run like the valgrind and rustc versions
+    pub environment: Option<EnvironmentInfo>,
+    /// The user provided reason why a performance regression of this benchmark is expected as in
+    /// `#[bench::id(expect_regression = "...")]`. An expected regression doesn't fail the benchmark
+    /// run.
+    #[serde(default)]
+    pub expected_regression: Option<String>,
+    /// The name of the function under test
+    pub function_name: String,
+    /// The git metadata of the project at the time of the benchmark run if it is a git repository
+    pub git: Option<GitInfo>,
+    /// The user provided id of this benchmark
+    pub id: Option<String>,
+    /// Whether this summary describes a library or binary benchmark
+    pub kind: BenchmarkKind,
+    /// The user provided metadata of this benchmark run as in `main!(metadata = [...])` and
+    /// `--metadata`
+    #[serde(default)]
+    pub metadata: IndexMap<String, String>,
+    /// The rust path in the form `bench_file::group::bench`
+    pub module_path: String,
+    /// The directory of the package
+    pub package_dir: PathBuf,
+    /// The summary of other valgrind tool runs
+    pub profiles: Profiles,
+    /// The project's root directory
+    pub project_root: PathBuf,
+    /// The path to the `run.log` file with the log messages of the runner and the captured output
+    /// of this benchmark
+    pub run_log: Option<PathBuf>,
+    /// The destination and kind of the summary file
+    pub summary_output: Option<SummaryOutput>,
+    /// The version of this format. Only backwards incompatible changes cause an increase of the
+    /// version
+    pub version: String,
+}
+
+/// The differences between two `Metrics` as percentage and factor
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+pub struct Diffs {
+    /// The percentage of the difference between two `Metrics` serialized as string to preserve
+    /// infinity values and avoid `null` in json
+    #[serde(with = "crate::serde::float_64")]
+    #[cfg_attr(feature = "schema", schemars(with = "String"))]
+    pub diff_pct: f64,
+    /// The factor of the difference between two `Metrics` serialized as string to preserve
+    /// infinity values and void `null` in json
+    #[serde(with = "crate::serde::float_64")]
+    #[cfg_attr(feature = "schema", schemars(with = "String"))]
+    pub factor: f64,
+}
+
+/// The environment of a benchmark run
+///
+/// This information is required to interpret archived results later, since the metrics depend on
+/// the valgrind version, the toolchain and (with the cache simulation) the CPU.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+pub struct EnvironmentInfo {
+    /// The model name of the CPU if it could be detected
+    pub cpu: Option<String>,
+    /// The output of `rustc --version` if it could be detected
+    pub rustc: Option<String>,
+    /// The target triple of the `iai-callgrind-runner`
+    pub target: String,
+    /// The start of the benchmark run in seconds since the unix epoch
+    pub timestamp: u64,
+    /// The valgrind tools enabled for the benchmark
+    pub tools: Vec<ValgrindTool>,
+    /// The valgrind version if it could be detected
+    pub valgrind: Option<String>,
+}
+
+/// All callgrind flamegraph summaries and their totals
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+pub struct FlamegraphSummaries {
+    /// The `FlamegraphSummary`s
+    pub summaries: Vec<FlamegraphSummary>,
+    /// The totals over the `FlamegraphSummary`s
+    pub totals: Vec<FlamegraphSummary>,
+}
+
+/// The callgrind `FlamegraphSummary` records all created paths for an [`EventKind`] specific
+/// flamegraph
+///
+/// Either the `regular_path`, `old_path` or the `diff_path` are present. Never can all of them be
+/// absent.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+pub struct FlamegraphSummary {
+    /// If present, the path to the file of the old regular (non-differential) flamegraph
+    pub base_path: Option<PathBuf>,
+    /// If present, the path to the file of the differential flamegraph
+    pub diff_path: Option<PathBuf>,
+    /// The `EventKind` of the flamegraph
+    pub event_kind: EventKind,
+    /// If present, the path to the file of the regular (non-differential) flamegraph
+    pub regular_path: Option<PathBuf>,
+}
+
+/// The git metadata of the project at the time of the benchmark run
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+pub struct GitInfo {
+    /// The name of the checked out branch or `None` if the `HEAD` is detached
+    pub branch: Option<String>,
+    /// The full commit hash of the `HEAD`
+    pub commit: String,
+    /// True if there were uncommitted changes to tracked files
+    pub dirty: bool,
+}
+
+/// The `Metrics` backed by an [`indexmap::IndexMap`]
+///
+/// The insertion order is preserved.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+pub struct Metrics<K: Hash + Eq>(pub IndexMap<K, Metric>);
+
+/// The `MetricsDiff` describes the difference between a `new` and `old` metric as percentage and
+/// factor.
+///
+/// Only if both metrics are present there is also a `Diffs` present. Otherwise, it just stores the
+/// `new` or `old` metric.
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+pub struct MetricsDiff {
+    /// If both metrics are present there is also a `Diffs` present
+    pub diffs: Option<Diffs>,
+    /// Either the `new`, `old` or both metrics
+    pub metrics: EitherOrBoth<Metric>,
+}
+
+/// The `MetricsSummary` contains all differences between two tool run segments
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+pub struct MetricsSummary<K: Hash + Eq = EventKind>(pub IndexMap<K, MetricsDiff>);
+
+/// The `ToolSummary` containing all information about a valgrind tool run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+pub struct Profile {
+    /// If true, the errors of this error checking tool fail the benchmark run
+    #[serde(default)]
+    pub fail_on_errors: bool,
+    /// Details and information about the created flamegraphs if any
+    pub flamegraphs: Vec<FlamegraphSummary>,
+    /// The paths to the `*.log` files. All tools produce at least one log file
+    pub log_paths: Vec<PathBuf>,
+    /// The paths to the `*.out` files. Not all tools produce an output in addition to the log
+    /// files
+    pub out_paths: Vec<PathBuf>,
+    /// The resource usage of the valgrind processes running the benchmark if available
+    #[serde(default)]
+    pub resource_usage: Option<ResourceUsage>,
+    /// The metrics and details about the tool run
+    pub summaries: ProfileData,
+    /// True if the total metrics are zero which usually indicates a misconfiguration like a wrong
+    /// entry point or toggle
+    #[serde(default)]
+    pub suspicious: bool,
+    /// The Valgrind tool like `DHAT`, `Memcheck` etc.
+    pub tool: ValgrindTool,
+}
+
+/// The resource usage of the benchmark processes as reported by `getrusage`
+///
+/// These are auxiliary metrics of the valgrind processes (including the benchmark) which are not
+/// compared against the old run. They can help to explain changes in the simulated cache metrics
+/// like the `RAM Hits` between otherwise identical runs.
+#[allow(clippy::unsafe_derive_deserialize)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+pub struct ResourceUsage {
+    /// The number of involuntary context switches (`ru_nivcsw`)
+    pub involuntary_context_switches: u64,
+    /// The number of page faults which required I/O (`ru_majflt`)
+    pub major_page_faults: u64,
+    /// The number of page faults which were serviced without I/O (`ru_minflt`)
+    pub minor_page_faults: u64,
+    /// The number of voluntary context switches (`ru_nvcsw`)
+    pub voluntary_context_switches: u64,
+}
+
+/// The `ToolRun` contains all information about a single tool run with possibly multiple segments
+///
+/// The total is always present and summarizes all tool run segments. In the special case of a
+/// single tool run segment, the total equals the metrics of this segment.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+pub struct ProfileData {
+    /// All [`ProfilePart`]s
+    pub parts: Vec<ProfilePart>,
+    /// The total over the [`ProfilePart`]s
+    pub total: ProfileTotal,
+}
+
+/// Some additional and necessary information about the tool run segment
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "runner", derive(derive_more::AsRef))]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+pub struct ProfileInfo {
+    /// The executed command extracted from Valgrind output
+    pub command: String,
+    /// More details for example from the logging output of the tool run
+    pub details: Option<String>,
+    /// The parent pid of this process
+    pub parent_pid: Option<i32>,
+    /// The part of this tool run (only callgrind)
+    pub part: Option<u64>,
+    /// The path to the file from the tool run
+    pub path: PathBuf,
+    /// The pid of this process
+    pub pid: i32,
+    /// The thread of this tool run (only callgrind)
+    pub thread: Option<usize>,
+}
+
+/// A single segment of a tool run and if present the comparison with the "old" segment
+///
+/// A tool run can produce multiple segments, for example for each process and subprocess with
+/// (--trace-children).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+pub struct ProfilePart {
+    /// Details like command, pid, ppid, thread number etc. (see [`ProfileInfo`])
+    pub details: EitherOrBoth<ProfileInfo>,
+    /// The [`ToolMetricSummary`]
+    pub metrics_summary: ToolMetricSummary,
+}
+
+/// The total metrics over all [`ProfilePart`]s and if detected any [`ToolRegression`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+pub struct ProfileTotal {
+    /// The detected regressions if any
+    pub regressions: Vec<ToolRegression>,
+    /// The summary of metrics of the tool
+    pub summary: ToolMetricSummary,
+}
+
+/// The collection of all generated [`Profile`]s
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[derive(Default)]
+pub struct Profiles(pub Vec<Profile>);
+
+/// Manage the summary output file with this `SummaryOutput`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+pub struct SummaryOutput {
+    /// The [`SummaryFormat`]
+    pub format: SummaryFormat,
+    /// The path to the destination file of this summary
+    pub path: PathBuf,
+}
+
+impl Display for BaselineName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Eq for Metric {}
+
+impl Ord for Metric {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Self::Int(a), Self::Int(b)) => a.cmp(b),
+            (Self::Int(a), Self::Float(b)) => (*a as f64).total_cmp(b),
+            (Self::Float(a), Self::Int(b)) => a.total_cmp(&(*b as f64)),
+            (Self::Float(a), Self::Float(b)) => a.total_cmp(b),
+        }
+    }
+}
+
+impl PartialEq for Metric {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Int(a), Self::Int(b)) => a == b,
+            (Self::Int(a), Self::Float(b)) => (*a as f64).total_cmp(b) == Ordering::Equal,
+            (Self::Float(a), Self::Int(b)) => a.total_cmp(&(*b as f64)) == Ordering::Equal,
+            (Self::Float(a), Self::Float(b)) => a.total_cmp(b) == Ordering::Equal,
+        }
+    }
+}
+
+impl PartialOrd for Metric {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl IntoIterator for Profiles {
+    type Item = Profile;
+    type IntoIter = std::vec::IntoIter<Self::Item>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
@@ -1,11 +1,13 @@
 //! This module provides common utility functions
 
 // spell-checker: ignore axxxxxbcd
+use std::borrow::Cow;
 use std::ffi::OsStr;
+use std::fs::File;
 use std::io::{self, BufWriter, Write};
 use std::ops::Neg;
 use std::path::{Path, PathBuf};
-use std::process::Command;
+use std::process::{Command, Stdio};
 
 use anyhow::{anyhow, Result};
 use either_or_both::EitherOrBoth;
@@ -248,6 +250,84 @@ pub fn copy_directory(source: &Path, dest: &Path, follow_symlinks: bool) -> Resu
     Ok(())
 }
 
+/// Return the program and its arguments which open a file with the default application
+fn default_application() -> (&'static str, &'static [&'static str]) {
+    if cfg!(target_os = "macos") {
+        ("open", &[])
+    } else if cfg!(target_os = "windows") {
+        ("cmd", &["/C", "start", ""])
+    } else {
+        ("xdg-open", &[])
+    }
+}
+
+/// Expand the environment variables in the `value` with the `lookup` function
+///
+/// A variable is referenced with `${NAME}`. The escape sequence `$${` expands to a literal `${`, so
+/// `$${NAME}` expands to `${NAME}`. All other occurrences of `$`, for example in mangled symbol
+/// names like `$LT$`, are kept as they are.
+///
+/// # Errors
+///
+/// Returns an error if a variable is not terminated with `}`, has an empty name or `lookup` returns
+/// `None` for it
+pub fn expand_env_vars<F>(value: &str, lookup: F) -> Result<String, String>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let mut expanded = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find('$') {
+        expanded.push_str(&rest[..start]);
+        rest = &rest[start + 1..];
+
+        if let Some(remainder) = rest.strip_prefix("${") {
+            expanded.push_str("${");
+            rest = remainder;
+        } else if let Some(remainder) = rest.strip_prefix('{') {
+            let end = remainder
+                .find('}')
+                .ok_or_else(|| format!("Missing '}}' of the environment variable in '{value}'"))?;
+            let name = &remainder[..end];
+            if name.is_empty() {
+                return Err(format!(
+                    "Missing the name of the environment variable in '{value}'"
+                ));
+            }
+            let variable = lookup(name).ok_or_else(|| {
+                format!("The environment variable '{name}' in '{value}' is not set")
+            })?;
+            expanded.push_str(&variable);
+            rest = &remainder[end + 1..];
+        } else {
+            expanded.push('$');
+        }
+    }
+    expanded.push_str(rest);
+
+    Ok(expanded)
+}
+
+/// Calculate the levenshtein distance between the strings `a` and `b`
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut row = (0..=b.len()).collect::<Vec<_>>();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, b_char) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = if a_char == *b_char {
+                diagonal
+            } else {
+                1 + diagonal.min(above).min(row[j])
+            };
+            diagonal = above;
+        }
+    }
+    row[b.len()]
+}
+
 /// Calculate the difference between `new` and `old` as factor
 pub fn factor_diff(new: Metric, old: Metric) -> f64 {
     if new == old {
@@ -270,6 +350,55 @@ pub fn factor_diff(new: Metric, old: Metric) -> f64 {
     }
 }
 
+/// Return the candidate which is most similar to `value` if `value` is likely a misspelling of it
+///
+/// The comparison is case-insensitive and uses the edit (levenshtein) distance. A candidate is
+/// considered similar if at most a third of the characters of `value` (but at least one) have to
+/// be changed.
+pub fn find_similar<I, T>(value: &str, candidates: I) -> Option<T>
+where
+    I: IntoIterator<Item = T>,
+    T: AsRef<str>,
+{
+    let value = value.to_lowercase();
+    let max_distance = (value.chars().count() / 3).max(1);
+    candidates
+        .into_iter()
+        .map(|candidate| {
+            let distance = edit_distance(&value, &candidate.as_ref().to_lowercase());
+            (distance, candidate)
+        })
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// Format the `timestamp` (seconds since the unix epoch) as UTC date `YYYY-MM-DD HH:MM:SS UTC`
+pub fn format_timestamp(timestamp: u64) -> String {
+    let days = timestamp / 86_400;
+    let seconds = timestamp % 86_400;
+
+    // Convert the days since the unix epoch to the civil date (proleptic gregorian calendar). See
+    // <https://howardhinnant.github.io/date_algorithms.html#civil_from_days>
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let day_of_era = z - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}:{:02} UTC",
+        seconds / 3600,
+        (seconds % 3600) / 60,
+        seconds % 60
+    )
+}
+
 /// Convert a valgrind glob pattern into a [`Regex`]
 ///
 /// A valgrind glob pattern is a simpler glob pattern usually used to match function calls for
@@ -317,6 +446,84 @@ where
     path.strip_prefix(base_dir).unwrap_or(path).to_owned()
 }
 
+/// Open the file at `path` with the default application of the desktop environment
+///
+/// The application is started in the background with all standard streams closed. This function
+/// doesn't wait for the application to exit.
+pub fn open_with_default_application(path: &Path) -> Result<()> {
+    let (program, args) = default_application();
+
+    debug!("Opening '{}' with '{program}'", path.display());
+    spawn_detached(Command::new(program).args(args).arg(path)).map_err(|error| {
+        anyhow!(
+            "Failed to open '{}' with '{program}': {error}",
+            path.display()
+        )
+    })
+}
+
+/// Open the file at `path` with the default application after the process `pid` has exited
+///
+/// The `lock` file is created to make sure the file is opened only once, even if this function is
+/// called multiple times (and from multiple processes) with the same `lock`. Returns false if the
+/// `lock` already exists. A background `sh` process polls for the exit of the process `pid` once
+/// per second, then removes the `lock` and opens the file. This function doesn't wait for it.
+pub fn open_with_default_application_after(path: &Path, pid: u32, lock: &Path) -> Result<bool> {
+    match File::options().write(true).create_new(true).open(lock) {
+        Ok(_) => {}
+        Err(error) if error.kind() == io::ErrorKind::AlreadyExists => return Ok(false),
+        Err(error) => {
+            return Err(anyhow!(
+                "Failed to create the lock file '{}': {error}",
+                lock.display()
+            ))
+        }
+    }
+
+    let (program, args) = default_application();
+
+    debug!(
+        "Opening '{}' with '{program}' after the process with pid {pid} has exited",
+        path.display()
+    );
+    spawn_detached(
+        Command::new("sh")
+            .arg("-c")
+            .arg(
+                r#"while kill -0 "$0" 2>/dev/null; do sleep 1; done; rm -f "$1"; shift; exec "$@""#,
+            )
+            .arg(pid.to_string())
+            .arg(lock)
+            .arg(program)
+            .args(args)
+            .arg(path),
+    )
+    .map(|()| true)
+    .map_err(|error| {
+        let _ = std::fs::remove_file(lock);
+        anyhow!(
+            "Failed to open '{}' with '{program}': {error}",
+            path.display()
+        )
+    })
+}
+
+/// Return the id of the parent process of the process `pid` as reported by `ps`
+pub fn parent_process_id(pid: u32) -> Option<u32> {
+    let output = Command::new("ps")
+        .args(["-o", "ppid=", "-p"])
+        .arg(pid.to_string())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+
+    if output.status.success() {
+        String::from_utf8_lossy(&output.stdout).trim().parse().ok()
+    } else {
+        None
+    }
+}
+
 /// Calculate the difference between `new` and `old` as percentage
 pub fn percentage_diff(new: Metric, old: Metric) -> f64 {
     if new == old {
@@ -352,27 +559,66 @@ where
     }
 }
 
-/// Format a float as string depending on the number of digits of the integer-part
+/// Spawn the `command` with all standard streams closed without waiting for it
+fn spawn_detached(command: &mut Command) -> io::Result<()> {
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map(drop)
+}
+
+/// Replace all non-ASCII characters in the `string`
 ///
-/// The higher the number of integer-part digits the lower the number of fractional-part digits.
-/// This procedure accounts for the fractional-part being less significant the higher the value of
-/// the floating point number gets.
-pub fn to_string_signed_short(n: f64) -> String {
+/// The unicode ellipsis `…` is replaced with `...` and all other non-ASCII characters with `?`.
+/// The `string` is returned as is if it consists of ASCII characters only.
+pub fn to_ascii(string: &str) -> Cow<'_, str> {
+    if string.is_ascii() {
+        return Cow::Borrowed(string);
+    }
+
+    let mut ascii = String::with_capacity(string.len());
+    for c in string.chars() {
+        match c {
+            c if c.is_ascii() => ascii.push(c),
+            '…' => ascii.push_str("..."),
+            _ => ascii.push('?'),
+        }
+    }
+    Cow::Owned(ascii)
+}
+
+/// Same as [`to_string_signed_short`] but with at most `precision` fractional-part digits
+///
+/// A `precision` greater than `5` has no effect.
+pub fn to_string_signed_precision(n: f64, precision: usize) -> String {
     let n_abs = n.abs();
 
-    if n_abs < 10.0f64 {
-        format!("{n:+.5}")
+    let digits = if n_abs < 10.0f64 {
+        5
     } else if n_abs < 100.0f64 {
-        format!("{n:+.4}")
+        4
     } else if n_abs < 1000.0f64 {
-        format!("{n:+.3}")
+        3
     } else if n_abs < 10000.0f64 {
-        format!("{n:+.2}")
+        2
     } else if n_abs < 100_000.0_f64 {
-        format!("{n:+.1}")
+        1
     } else {
-        format!("{n:+.0}")
-    }
+        0
+    };
+
+    format!("{n:+.0$}", digits.min(precision))
+}
+
+/// Format a float as string depending on the number of digits of the integer-part
+///
+/// The higher the number of integer-part digits the lower the number of fractional-part digits.
+/// This procedure accounts for the fractional-part being less significant the higher the value of
+/// the floating point number gets.
+pub fn to_string_signed_short(n: f64) -> String {
+    to_string_signed_precision(n, 5)
 }
 
 /// Format a float as string depending on the number of digits of the integer-part without sign
@@ -407,6 +653,23 @@ pub fn truncate_str_utf8(string: &str, len: usize) -> &str {
     }
 }
 
+/// Truncate a utf-8 [`std::str`] from the start to a given `len`
+///
+/// The counterpart of [`truncate_str_utf8`] which keeps the end of the `string` instead of the
+/// start.
+pub fn truncate_str_utf8_start(string: &str, len: usize) -> &str {
+    if let Some((pos, _)) = string
+        .char_indices()
+        .rev()
+        .take_while(|(i, _)| string.len() - i <= len)
+        .last()
+    {
+        &string[pos..]
+    } else {
+        &string[string.len()..]
+    }
+}
+
 /// Dump all data to `stderr`
 pub fn write_all_to_stderr(bytes: &[u8]) {
     if !bytes.is_empty() {
@@ -458,6 +721,129 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn test_open_with_default_application_after_when_lock_exists() {
+        let dir = tempfile::tempdir().unwrap();
+        let lock = dir.path().join(".open.1");
+        File::create(&lock).unwrap();
+
+        assert!(
+            !open_with_default_application_after(&dir.path().join("index.html"), 1, &lock).unwrap()
+        );
+        assert!(lock.exists());
+    }
+
+    #[test]
+    fn test_open_with_default_application_after_when_lock_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let lock = dir.path().join("missing").join(".open.1");
+
+        let error = open_with_default_application_after(&dir.path().join("index.html"), 1, &lock)
+            .unwrap_err();
+        assert!(error
+            .to_string()
+            .starts_with("Failed to create the lock file"));
+    }
+
+    #[test]
+    fn test_parent_process_id() {
+        assert_eq!(
+            parent_process_id(std::process::id()),
+            Some(std::os::unix::process::parent_id())
+        );
+    }
+
+    #[rstest]
+    #[case::equal("ir", "ir", 0)]
+    #[case::empty("", "ir", 2)]
+    #[case::deletion("cycles", "cycle", 1)]
+    #[case::transposition("cylces", "cycles", 2)]
+    #[case::different("ir", "dw", 2)]
+    fn test_edit_distance(#[case] a: &str, #[case] b: &str, #[case] expected: usize) {
+        assert_eq!(edit_distance(a, b), expected);
+        assert_eq!(edit_distance(b, a), expected);
+    }
+
+    #[rstest]
+    #[case::typo("estimatedcylces", Some("EstimatedCycles"))]
+    #[case::ignore_case("L1HIT", Some("L1hits"))]
+    #[case::closest("ir", Some("Ir"))]
+    #[case::too_different("WRONG", None)]
+    fn test_find_similar(#[case] value: &str, #[case] expected: Option<&str>) {
+        let candidates = ["Ir", "Dr", "L1hits", "LLhits", "EstimatedCycles"];
+        assert_eq!(find_similar(value, candidates), expected);
+    }
+
+    #[rstest]
+    #[case::epoch(0, "1970-01-01 00:00:00 UTC")]
+    #[case::leap_day(951_782_400, "2000-02-29 00:00:00 UTC")]
+    #[case::end_of_year(1_704_067_199, "2023-12-31 23:59:59 UTC")]
+    #[case::some_time(1_709_296_200, "2024-03-01 12:30:00 UTC")]
+    fn test_format_timestamp(#[case] timestamp: u64, #[case] expected: &str) {
+        assert_eq!(format_timestamp(timestamp), expected);
+    }
+
+    #[rstest]
+    #[case::zero(0.0, 5, "+0.00000")]
+    #[case::small(1.234_567, 5, "+1.23457")]
+    #[case::small_precision_2(1.234_567, 2, "+1.23")]
+    #[case::small_precision_0(-1.234_567, 0, "-1")]
+    #[case::big_precision_2(12_345.678, 2, "+12345.7")]
+    #[case::precision_greater_than_max(1.234_567, 8, "+1.23457")]
+    #[case::infinity(f64::INFINITY, 2, "+inf")]
+    fn test_to_string_signed_precision(
+        #[case] n: f64,
+        #[case] precision: usize,
+        #[case] expected: &str,
+    ) {
+        assert_eq!(to_string_signed_precision(n, precision), expected);
+    }
+
+    #[rstest]
+    #[case::empty("", "")]
+    #[case::ascii("some::module id:1, 2", "some::module id:1, 2")]
+    #[case::ellipsis("some…", "some...")]
+    #[case::multi_byte("aµb", "a?b")]
+    #[case::multi_byte_and_ellipsis("µ…", "?...")]
+    fn test_to_ascii(#[case] input: &str, #[case] expected: &str) {
+        assert_eq!(to_ascii(input), expected);
+    }
+
+    #[rstest]
+    #[case::empty("", "")]
+    #[case::no_variable("--toggle-collect=main", "--toggle-collect=main")]
+    #[case::variable("--suppressions=${DIR}/file.supp", "--suppressions=/tmp/file.supp")]
+    #[case::multiple("${DIR}${NAME}", "/tmpname")]
+    #[case::escape("--arg=$${DIR}", "--arg=${DIR}")]
+    #[case::dollars("$$", "$$")]
+    #[case::mangled("*$u20$$LT$impl$GT$*", "*$u20$$LT$impl$GT$*")]
+    #[case::single_dollar("--arg=$DIR$", "--arg=$DIR$")]
+    fn test_expand_env_vars(#[case] value: &str, #[case] expected: &str) {
+        let lookup = |name: &str| match name {
+            "DIR" => Some("/tmp".to_owned()),
+            "NAME" => Some("name".to_owned()),
+            _ => None,
+        };
+        assert_eq!(expand_env_vars(value, lookup), Ok(expected.to_owned()));
+    }
+
+    #[rstest]
+    #[case::unset(
+        "${UNSET}",
+        "The environment variable 'UNSET' in '${UNSET}' is not set"
+    )]
+    #[case::unterminated(
+        "--arg=${DIR",
+        "Missing '}' of the environment variable in '--arg=${DIR'"
+    )]
+    #[case::empty_name(
+        "--arg=${}",
+        "Missing the name of the environment variable in '--arg=${}'"
+    )]
+    fn test_expand_env_vars_when_error(#[case] value: &str, #[case] expected: &str) {
+        assert_eq!(expand_env_vars(value, |_| None).unwrap_err(), expected);
+    }
+
     #[rstest]
     #[case::empty_0("", 0, "")]
     #[case::empty_1("", 1, "")]
@@ -491,6 +877,24 @@ mod tests {
         assert_eq!(truncate_str_utf8(input, len), expected);
     }
 
+    #[rstest]
+    #[case::empty_0("", 0, "")]
+    #[case::empty_1("", 1, "")]
+    #[case::single_0("a", 0, "")]
+    #[case::single_1("a", 1, "a")]
+    #[case::two_1("ab", 1, "b")]
+    #[case::multi_byte_1("µ", 1, "")]
+    #[case::multi_byte_2("µ", 2, "µ")]
+    #[case::uni_then_multi_byte_1("aµ", 1, "")]
+    #[case::uni_then_multi_byte_2("aµ", 2, "µ")]
+    #[case::uni_then_multi_byte_3("aµ", 3, "aµ")]
+    #[case::multi_byte_then_uni_1("µa", 1, "a")]
+    #[case::multi_byte_then_uni_2("µa", 2, "a")]
+    #[case::multi_byte_then_uni_3("µa", 3, "µa")]
+    fn test_truncate_str_start(#[case] input: &str, #[case] len: usize, #[case] expected: &str) {
+        assert_eq!(truncate_str_utf8_start(input, len), expected);
+    }
+
     #[rstest]
     #[case::zero(0, 0, 1f64)]
     #[case::float_zero_int_zero(0, 0f64, 1f64)]
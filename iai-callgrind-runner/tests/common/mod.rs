@@ -9,6 +9,7 @@ use anyhow::Result;
 use iai_callgrind_runner::api::ValgrindTool;
 use iai_callgrind_runner::runner::summary::BaselineKind;
 use iai_callgrind_runner::runner::tool::path::{ToolOutputPath, ToolOutputPathKind};
+use iai_callgrind_runner::summary::BenchmarkSummary;
 use pretty_assertions::assert_eq;
 use serde::{Deserialize, Serialize};
 
@@ -87,6 +88,13 @@ impl Fixtures {
         reader.lines().map(std::result::Result::unwrap).collect()
     }
 
+    /// Load the [`BenchmarkSummary`] of the callgrind summary fixture
+    #[allow(unused)]
+    pub fn load_summary() -> BenchmarkSummary {
+        let file = File::open(Self::get_path_of("summary/summary.callgrind.json")).unwrap();
+        serde_json::from_reader(file).unwrap()
+    }
+
     #[allow(unused)]
     pub fn save_serialized<T, N>(name: N, value: &T) -> Result<(), serde_yaml::Error>
     where
@@ -0,0 +1,126 @@
+use std::fs;
+
+use iai_callgrind_runner::runner::report::generate_site;
+use iai_callgrind_runner::runner::store::StoredRecord;
+use tempfile::tempdir;
+
+fn record(run_id: u64, module_path: &str, bench_id: Option<&str>, value: f64) -> StoredRecord {
+    StoredRecord {
+        bench_id: bench_id.map(ToOwned::to_owned),
+        description: None,
+        git_commit: Some(format!("{run_id:040}")),
+        kind: "Ir".to_owned(),
+        module_path: module_path.to_owned(),
+        run_id,
+        time: format!("1970-01-0{run_id} 00:00:00"),
+        tool: "callgrind".to_owned(),
+        value,
+    }
+}
+
+#[test]
+fn test_generate_site() {
+    let dir = tempdir().unwrap();
+    let report_dir = dir.path().join("report");
+    let records = vec![
+        record(
+            1,
+            "my_bench::my_group::bench_fibonacci",
+            Some("short"),
+            1500.0,
+        ),
+        record(1, "my_bench::my_group::bench_other", None, 100.0),
+        record(
+            2,
+            "my_bench::my_group::bench_fibonacci",
+            Some("short"),
+            1734.0,
+        ),
+        record(2, "my_bench::my_group::bench_other", None, 100.0),
+    ];
+
+    generate_site(&records, &report_dir).unwrap();
+
+    let index = fs::read_to_string(report_dir.join("index.html")).unwrap();
+    assert!(index.contains("2 benchmarks in 2 runs"));
+    assert!(index.contains(
+        "<a href=\"my_bench.my_group.bench_fibonacci.short.html\"\
+         >my_bench::my_group::bench_fibonacci short</a>"
+    ));
+    assert!(index.contains("<td class=\"num increase\">+15.60%</td>"));
+    assert!(index.contains("<a href=\"my_bench.my_group.bench_other.html\">"));
+    assert!(index.contains("<td class=\"num\">No change</td>"));
+
+    let page = fs::read_to_string(report_dir.join("my_bench.my_group.bench_fibonacci.short.html"))
+        .unwrap();
+    assert!(page.contains("<h2>callgrind: Ir</h2>"));
+    assert!(page.contains("<polyline"));
+    assert!(page.contains("<td>1970-01-02 00:00:00</td><td><code>000000000000</code></td>"));
+    assert!(report_dir
+        .join("my_bench.my_group.bench_other.html")
+        .exists());
+}
+
+#[test]
+fn test_generate_site_escapes_html() {
+    let dir = tempdir().unwrap();
+    let records = vec![record(
+        1,
+        "my_bench::my_group::bench",
+        Some("<script>"),
+        1.0,
+    )];
+
+    generate_site(&records, dir.path()).unwrap();
+
+    let index = fs::read_to_string(dir.path().join("index.html")).unwrap();
+    assert!(index.contains("my_bench::my_group::bench &lt;script&gt;"));
+    assert!(!index.contains("<script>"));
+}
+
+#[test]
+fn test_generate_site_with_description() {
+    let dir = tempdir().unwrap();
+    let mut record = record(1, "my_bench::my_group::bench", None, 1.0);
+    record.description = Some("The hot path of the <parser>".to_owned());
+
+    generate_site(&[record], dir.path()).unwrap();
+
+    let index = fs::read_to_string(dir.path().join("index.html")).unwrap();
+    assert!(index.contains("<br><small>The hot path of the &lt;parser&gt;</small>"));
+
+    let page = fs::read_to_string(dir.path().join("my_bench.my_group.bench.html")).unwrap();
+    assert!(page.contains("<p>The hot path of the &lt;parser&gt;</p>"));
+}
+
+#[test]
+fn test_generate_site_when_no_records() {
+    let dir = tempdir().unwrap();
+
+    generate_site(&[], dir.path()).unwrap();
+
+    let index = fs::read_to_string(dir.path().join("index.html")).unwrap();
+    assert!(index.contains("0 benchmarks in 0 runs"));
+}
+
+#[test]
+fn test_generate_site_page_skeleton() {
+    let dir = tempdir().unwrap();
+
+    generate_site(&[], dir.path()).unwrap();
+
+    let index = fs::read_to_string(dir.path().join("index.html")).unwrap();
+    let (head, body) = index.split_once("</style>\n").unwrap();
+    assert!(head.starts_with(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<meta \
+         name=\"viewport\" content=\"width=device-width, \
+         initial-scale=1\">\n<title>Iai-Callgrind Report</title>\n<style>\n"
+    ));
+    assert_eq!(
+        body,
+        "</head>\n<body>\n<h1>Iai-Callgrind Report</h1>\n<p>0 benchmarks in 0 \
+         runs</p>\n<table>\n<thead><tr><th>Benchmark</th><th>Tool</th><th>Metric</th><th>Latest</\
+         th><th>Change</th><th>Trend</th></tr></thead>\n<tbody>\n</tbody>\n</table>\n</body>\n</\
+         html>\n"
+    );
+}
@@ -0,0 +1,2 @@
+#[cfg(feature = "sqlite")]
+mod test_sqlite;
@@ -0,0 +1,155 @@
+use std::path::PathBuf;
+
+use iai_callgrind_runner::runner::common::BenchmarkSummaries;
+use iai_callgrind_runner::runner::store::{SqliteStore, StoredRecord};
+use iai_callgrind_runner::summary::GitInfo;
+use pretty_assertions::assert_eq;
+use tempfile::tempdir;
+
+use crate::common::Fixtures;
+
+const EXPECTED: &str = "\
+time                 git_commit    module_path                          id     tool       kind             value
+-------------------  ------------  -----------------------------------  -----  ---------  ---------------  -----
+1970-01-01 00:00:00  0123456789ab  my_bench::my_group::bench_fibonacci  short  callgrind  EstimatedCycles  2300 
+1970-01-02 00:00:00                my_bench::my_group::bench_fibonacci  short  callgrind  EstimatedCycles  2300 
+1970-01-01 00:00:00  0123456789ab  my_bench::my_group::bench_fibonacci  short  callgrind  Ir               1734 
+1970-01-02 00:00:00                my_bench::my_group::bench_fibonacci  short  callgrind  Ir               1734 
+";
+
+fn git_info(dirty: bool) -> GitInfo {
+    GitInfo {
+        branch: Some("main".to_owned()),
+        commit: "0123456789abcdef".to_owned(),
+        dirty,
+    }
+}
+
+fn load_summaries_fixture() -> BenchmarkSummaries {
+    let mut summaries = BenchmarkSummaries::default();
+    summaries.add_summary(Fixtures::load_summary());
+    summaries
+}
+
+fn sqlite_store(path: PathBuf) -> SqliteStore {
+    SqliteStore::new(path).unwrap()
+}
+
+#[test]
+fn test_sqlite_store_append_and_query() {
+    let dir = tempdir().unwrap();
+    let store = sqlite_store(dir.path().join("sub/results.sqlite"));
+    let summaries = load_summaries_fixture();
+
+    store.append(&summaries, 0, Some(&git_info(false))).unwrap();
+    store.append(&summaries, 86400, None).unwrap();
+
+    assert_eq!(store.query(None).unwrap(), EXPECTED);
+    assert_eq!(store.query(Some("fibonacci")).unwrap(), EXPECTED);
+    assert_eq!(store.query(Some("does_not_exist")).unwrap(), "");
+}
+
+#[test]
+fn test_sqlite_store_when_quotes_in_id() {
+    let dir = tempdir().unwrap();
+    let store = sqlite_store(dir.path().join("results.sqlite"));
+    let mut summaries = load_summaries_fixture();
+    summaries.summaries[0].id = Some("it's".to_owned());
+
+    store.append(&summaries, 0, None).unwrap();
+
+    let actual = store.query(Some("it's")).unwrap();
+    assert_eq!(actual.lines().count(), 4);
+}
+
+#[test]
+fn test_sqlite_store_query_when_database_missing_then_error() {
+    let dir = tempdir().unwrap();
+    let store = sqlite_store(dir.path().join("results.sqlite"));
+
+    store.query(None).unwrap_err();
+}
+
+#[test]
+fn test_sqlite_store_records() {
+    let dir = tempdir().unwrap();
+    let store = sqlite_store(dir.path().join("results.sqlite"));
+    let summaries = load_summaries_fixture();
+
+    store.append(&summaries, 0, Some(&git_info(false))).unwrap();
+    store.append(&summaries, 86400, None).unwrap();
+
+    let records = store.records().unwrap();
+    assert_eq!(records.len(), 4);
+    assert_eq!(
+        records[0],
+        StoredRecord {
+            bench_id: Some("short".to_owned()),
+            description: Some("The recursive fibonacci implementation".to_owned()),
+            git_commit: Some("0123456789abcdef".to_owned()),
+            kind: "Ir".to_owned(),
+            module_path: "my_bench::my_group::bench_fibonacci".to_owned(),
+            run_id: 1,
+            time: "1970-01-01 00:00:00".to_owned(),
+            tool: "callgrind".to_owned(),
+            value: 1734.0,
+        }
+    );
+    assert_eq!(records[3].run_id, 2);
+    assert_eq!(records[3].git_commit, None);
+}
+
+#[test]
+fn test_sqlite_store_when_dirty() {
+    let dir = tempdir().unwrap();
+    let store = sqlite_store(dir.path().join("results.sqlite"));
+    let summaries = load_summaries_fixture();
+
+    store.append(&summaries, 0, Some(&git_info(true))).unwrap();
+
+    let actual = store.query(None).unwrap();
+    assert!(actual.contains(" 0123456789ab-dirty "));
+}
+
+#[test]
+fn test_sqlite_store_when_sql_in_values_then_stored_verbatim() {
+    let dir = tempdir().unwrap();
+    let store = sqlite_store(dir.path().join("results.sqlite"));
+    let mut summaries = load_summaries_fixture();
+    let id = "x'); DROP TABLE runs; --";
+    summaries.summaries[0].id = Some(id.to_owned());
+    summaries.summaries[0].description = Some("%_'\"".to_owned());
+
+    store.append(&summaries, 0, None).unwrap();
+
+    let records = store.records().unwrap();
+    assert_eq!(records.len(), 2);
+    assert!(records
+        .iter()
+        .all(|record| record.bench_id.as_deref() == Some(id)
+            && record.description.as_deref() == Some("%_'\"")));
+}
+
+#[test]
+fn test_sqlite_store_prune() {
+    let dir = tempdir().unwrap();
+    let store = sqlite_store(dir.path().join("results.sqlite"));
+    let summaries = load_summaries_fixture();
+
+    store.append(&summaries, 0, None).unwrap();
+    store.append(&summaries, 86400, None).unwrap();
+    store.prune(86400).unwrap();
+
+    let records = store.records().unwrap();
+    assert_eq!(records.len(), 2);
+    assert!(records.iter().all(|record| record.run_id == 2));
+}
+
+#[test]
+fn test_sqlite_store_prune_when_database_missing() {
+    let dir = tempdir().unwrap();
+    let store = sqlite_store(dir.path().join("results.sqlite"));
+
+    store.prune(86400).unwrap();
+    assert!(!store.path.exists());
+}
@@ -0,0 +1,6 @@
+mod test_artifacts;
+mod test_diff;
+mod test_git;
+mod test_openmetrics;
+mod test_serde;
+mod test_stream;
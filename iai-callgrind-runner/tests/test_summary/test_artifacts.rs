@@ -0,0 +1,88 @@
+use std::fs;
+use std::path::PathBuf;
+
+use iai_callgrind_runner::runner::pipeline::Pipeline;
+use iai_callgrind_runner::runner::summary::ArtifactRegistry;
+use iai_callgrind_runner::summary::{Artifact, ArtifactKind};
+use pretty_assertions::assert_eq;
+use tempfile::tempdir;
+
+use crate::common::Fixtures;
+
+/// The sha256 checksum of `abc`
+const SHA256_ABC: &str = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+
+fn artifact(kind: ArtifactKind, path: &str) -> Artifact {
+    Artifact {
+        kind,
+        path: PathBuf::from(path),
+        sha256: SHA256_ABC.to_owned(),
+        size: 3,
+    }
+}
+
+#[test]
+fn test_collect_artifacts_when_created_by_pipeline_job() {
+    let dir = tempdir().unwrap();
+    let mut summary = Fixtures::load_summary();
+    summary.project_root = dir.path().to_owned();
+
+    let registry = ArtifactRegistry::default();
+    let run_log = dir.path().join("run.log");
+    fs::write(&run_log, "abc").unwrap();
+    registry.register(ArtifactKind::RunLog, [&run_log]);
+
+    std::thread::scope(|scope| {
+        let job_registry = registry.clone();
+        let job_dir = dir.path().to_owned();
+        Pipeline::new(scope)
+            .submit(
+                Box::new(move || {
+                    let merged = job_dir.join("callgrind.bench.total.callgrind");
+                    fs::write(&merged, "abc")?;
+                    job_registry.register(ArtifactKind::Merged, [merged]);
+
+                    let chart = job_dir.join("dhat.bench.heap-summary.svg");
+                    fs::write(&chart, "abc")?;
+                    job_registry.register(ArtifactKind::Chart, [chart]);
+                    Ok(())
+                }),
+                false,
+            )
+            .join()
+            .unwrap();
+    });
+    summary.collect_artifacts(&registry).unwrap();
+
+    assert_eq!(
+        summary.artifacts,
+        vec![
+            artifact(ArtifactKind::RunLog, "run.log"),
+            artifact(ArtifactKind::Merged, "callgrind.bench.total.callgrind"),
+            artifact(ArtifactKind::Chart, "dhat.bench.heap-summary.svg"),
+        ]
+    );
+}
+
+#[test]
+fn test_collect_artifacts_skips_missing_and_duplicate_files() {
+    let dir = tempdir().unwrap();
+    let mut summary = Fixtures::load_summary();
+    summary.project_root = dir.path().to_owned();
+
+    let out = dir.path().join("callgrind.bench.out");
+    fs::write(&out, "abc").unwrap();
+
+    let registry = ArtifactRegistry::default();
+    registry.register(ArtifactKind::Out, [&out, &out]);
+    registry.register(
+        ArtifactKind::Suppressions,
+        [dir.path().join("callgrind.bench.suppressions.supp")],
+    );
+    summary.collect_artifacts(&registry).unwrap();
+
+    assert_eq!(
+        summary.artifacts,
+        vec![artifact(ArtifactKind::Out, "callgrind.bench.out")]
+    );
+}
@@ -0,0 +1,74 @@
+use std::fs;
+
+use iai_callgrind_runner::api::EventKind;
+use iai_callgrind_runner::runner::diff::{diff, DiffArgs};
+use iai_callgrind_runner::summary::{Metric, MetricKind, ToolRegression};
+use pretty_assertions::assert_eq;
+use tempfile::tempdir;
+
+use crate::common::Fixtures;
+
+#[test]
+fn test_diff_when_same_summary_then_no_regression() {
+    let path = Fixtures::get_path_of("summary/summary.callgrind.json");
+    let diff_args = DiffArgs::from_args([
+        path.as_os_str(),
+        path.as_os_str(),
+        "--callgrind-limits=ir=5%".as_ref(),
+    ])
+    .unwrap();
+
+    let summaries = diff(&diff_args).unwrap();
+
+    assert_eq!(summaries.summaries.len(), 1);
+    assert!(!summaries.is_regressed());
+}
+
+#[test]
+fn test_diff_when_directories_then_regression() {
+    let fixture =
+        fs::read_to_string(Fixtures::get_path_of("summary/summary.callgrind.json")).unwrap();
+    let old = tempdir().unwrap();
+    let new = tempdir().unwrap();
+
+    let old_dir = old.path().join("my_bench/my_group/bench_fibonacci.short");
+    fs::create_dir_all(&old_dir).unwrap();
+    fs::write(
+        old_dir.join("summary.json"),
+        fixture.replace("\"Int\": 1734", "\"Int\": 1500"),
+    )
+    .unwrap();
+    let new_dir = new.path().join("my_bench/my_group/bench_fibonacci.short");
+    fs::create_dir_all(&new_dir).unwrap();
+    fs::write(new_dir.join("summary.json"), &fixture).unwrap();
+
+    let diff_args = DiffArgs::from_args([
+        old.path().as_os_str(),
+        new.path().as_os_str(),
+        "--callgrind-limits=ir=5%".as_ref(),
+    ])
+    .unwrap();
+
+    let summaries = diff(&diff_args).unwrap();
+
+    assert_eq!(summaries.summaries.len(), 1);
+    assert!(summaries.is_regressed());
+    assert_eq!(
+        summaries.summaries[0].profiles.0[0]
+            .summaries
+            .total
+            .regressions,
+        vec![ToolRegression::Soft {
+            metric: MetricKind::Callgrind(EventKind::Ir),
+            new: Metric::Int(1734),
+            old: Metric::Int(1500),
+            diff_pct: 15.6,
+            limit: 5.0,
+        }]
+    );
+}
+
+#[test]
+fn test_diff_args_when_missing_new_then_error() {
+    DiffArgs::from_args(["old.json"]).unwrap_err();
+}
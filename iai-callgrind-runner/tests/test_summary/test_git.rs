@@ -0,0 +1,102 @@
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use iai_callgrind_runner::summary::GitInfo;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+use tempfile::tempdir;
+
+fn git(dir: &Path, args: &[&str]) {
+    let status = Command::new("git")
+        .args([
+            "-c",
+            "user.name=Iai-Callgrind",
+            "-c",
+            "user.email=iai@callgrind.test",
+        ])
+        .args(args)
+        .current_dir(dir)
+        .status()
+        .unwrap();
+    assert!(status.success());
+}
+
+#[test]
+fn test_git_info_from_repository() {
+    if which::which("git").is_err() {
+        eprintln!("Skipping test: git not found");
+        return;
+    }
+
+    let dir = tempdir().unwrap();
+    git(dir.path(), &["init", "--quiet"]);
+    git(dir.path(), &["checkout", "--quiet", "-b", "my-branch"]);
+    fs::write(dir.path().join("file.txt"), "content").unwrap();
+    git(dir.path(), &["add", "file.txt"]);
+    git(dir.path(), &["commit", "--quiet", "-m", "Initial commit"]);
+
+    let info = GitInfo::from_repository(dir.path()).unwrap();
+    assert_eq!(info.commit.len(), 40);
+    assert_eq!(info.branch.as_deref(), Some("my-branch"));
+    assert!(!info.dirty);
+
+    fs::write(dir.path().join("file.txt"), "changed").unwrap();
+    let info = GitInfo::from_repository(dir.path()).unwrap();
+    assert!(info.dirty);
+
+    git(dir.path(), &["checkout", "--quiet", "--detach"]);
+    let info = GitInfo::from_repository(dir.path()).unwrap();
+    assert_eq!(info.branch, None);
+}
+
+#[test]
+fn test_git_info_from_repository_when_directory_does_not_exist() {
+    let dir = tempdir().unwrap();
+
+    assert_eq!(
+        GitInfo::from_repository(&dir.path().join("does_not_exist")),
+        None
+    );
+}
+
+#[test]
+fn test_git_info_from_repository_when_no_commit() {
+    if which::which("git").is_err() {
+        eprintln!("Skipping test: git not found");
+        return;
+    }
+
+    let dir = tempdir().unwrap();
+    git(dir.path(), &["init", "--quiet"]);
+
+    assert_eq!(GitInfo::from_repository(dir.path()), None);
+}
+
+#[rstest]
+#[case::clean(
+    "# branch.oid abc\n# branch.head main\n# branch.upstream origin/main\n# branch.ab +0 -0",
+    Some(("abc", Some("main"), false))
+)]
+#[case::dirty(
+    "# branch.oid abc\n# branch.head main\n1 .M N... 100644 100644 100644 a b file.txt",
+    Some(("abc", Some("main"), true))
+)]
+#[case::detached(
+    "# branch.oid abc\n# branch.head (detached)",
+    Some(("abc", None, false))
+)]
+#[case::initial("# branch.oid (initial)\n# branch.head main", None)]
+#[case::empty("", None)]
+fn test_git_info_from_porcelain_status(
+    #[case] status: &str,
+    #[case] expected: Option<(&str, Option<&str>, bool)>,
+) {
+    let expected = expected.map(|(commit, branch, dirty)| GitInfo {
+        branch: branch.map(ToOwned::to_owned),
+        commit: commit.to_owned(),
+        dirty,
+    });
+
+    assert_eq!(GitInfo::from_porcelain_status(status), expected);
+}
@@ -0,0 +1,54 @@
+use std::fs;
+
+use iai_callgrind_runner::runner::format::OutputFormatKind;
+use iai_callgrind_runner::runner::summary::{SummaryFormat, SummaryOutput};
+use pretty_assertions::assert_eq;
+use tempfile::tempdir;
+
+use crate::common::Fixtures;
+
+const EXPECTED: &str = r#"# TYPE iai_callgrind_metric gauge
+# HELP iai_callgrind_metric The metrics of the benchmark
+iai_callgrind_metric{group="my_bench::my_group",benchmark="bench_fibonacci",id="short",tool="callgrind",kind="Ir",baseline="new"} 1734
+iai_callgrind_metric{group="my_bench::my_group",benchmark="bench_fibonacci",id="short",tool="callgrind",kind="Ir",baseline="old"} 1500
+iai_callgrind_metric{group="my_bench::my_group",benchmark="bench_fibonacci",id="short",tool="callgrind",kind="EstimatedCycles",baseline="new"} 2300
+iai_callgrind_metric{group="my_bench::my_group",benchmark="bench_fibonacci",id="short",tool="callgrind",kind="EstimatedCycles",baseline="old"} 2000
+# TYPE iai_callgrind_metric_diff_pct gauge
+# HELP iai_callgrind_metric_diff_pct The difference between the new and old metrics in percent
+iai_callgrind_metric_diff_pct{group="my_bench::my_group",benchmark="bench_fibonacci",id="short",tool="callgrind",kind="Ir"} 15.6
+iai_callgrind_metric_diff_pct{group="my_bench::my_group",benchmark="bench_fibonacci",id="short",tool="callgrind",kind="EstimatedCycles"} 15
+# EOF
+"#;
+
+#[test]
+fn test_to_openmetrics() {
+    let summary = Fixtures::load_summary();
+
+    assert_eq!(summary.to_openmetrics(), EXPECTED);
+}
+
+#[test]
+fn test_to_openmetrics_when_label_values_need_escaping() {
+    let mut summary = Fixtures::load_summary();
+    summary.id = Some("with \"quotes\"\nand \\".to_owned());
+
+    let actual = summary.to_openmetrics();
+
+    assert!(actual.contains(r#"id="with \"quotes\"\nand \\""#));
+}
+
+#[test]
+fn test_save_openmetrics_summary() {
+    let dir = tempdir().unwrap();
+    let mut summary = Fixtures::load_summary();
+    let output = SummaryOutput::new(SummaryFormat::OpenMetrics, dir.path());
+    assert_eq!(output.path, dir.path().join("summary.prom"));
+    summary.summary_output = Some(output);
+
+    summary.print_and_save(&OutputFormatKind::Default).unwrap();
+
+    assert_eq!(
+        fs::read_to_string(dir.path().join("summary.prom")).unwrap(),
+        EXPECTED
+    );
+}
@@ -0,0 +1,100 @@
+use std::path::Path;
+
+use either_or_both::EitherOrBoth;
+use iai_callgrind_runner::api::{EventKind, ValgrindTool};
+use iai_callgrind_runner::summary::{
+    BenchmarkKind, BenchmarkSummary, Diffs, EnvironmentInfo, GitInfo, Metric, MetricKind,
+    ToolMetricSummary, ToolRegression, SCHEMA_VERSION,
+};
+use pretty_assertions::assert_eq;
+
+use crate::common::Fixtures;
+
+#[test]
+fn test_summary_deserialize() {
+    let summary = Fixtures::load_summary();
+
+    assert_eq!(summary.version, SCHEMA_VERSION);
+    assert_eq!(summary.kind, BenchmarkKind::LibraryBenchmark);
+    assert_eq!(summary.function_name, "bench_fibonacci");
+    assert_eq!(summary.id.as_deref(), Some("short"));
+    assert_eq!(
+        summary.description.as_deref(),
+        Some("The recursive fibonacci implementation")
+    );
+    assert_eq!(
+        summary.git,
+        Some(GitInfo {
+            branch: Some("main".to_owned()),
+            commit: "0123456789abcdef0123456789abcdef01234567".to_owned(),
+            dirty: false,
+        })
+    );
+    assert_eq!(
+        summary.environment,
+        Some(EnvironmentInfo {
+            cpu: Some("AMD Ryzen 7 5800X 8-Core Processor".to_owned()),
+            rustc: Some("rustc 1.80.0 (051478957 2024-07-21)".to_owned()),
+            target: "x86_64-unknown-linux-gnu".to_owned(),
+            timestamp: 1_709_296_200,
+            tools: vec![ValgrindTool::Callgrind],
+            valgrind: Some("3.23.0".to_owned()),
+        })
+    );
+    assert_eq!(
+        summary.metadata.into_iter().collect::<Vec<_>>(),
+        vec![
+            ("ci_job".to_owned(), "1234".to_owned()),
+            ("dataset".to_owned(), "v2".to_owned())
+        ]
+    );
+    assert_eq!(
+        summary.run_log.as_deref(),
+        Some(Path::new(
+            "/home/user/project/target/iai/my_bench/my_group/bench_fibonacci.short/run.log"
+        ))
+    );
+
+    let profiles = summary.profiles.0;
+    assert_eq!(profiles.len(), 1);
+
+    let profile = &profiles[0];
+    assert_eq!(profile.tool, ValgrindTool::Callgrind);
+    assert_eq!(profile.summaries.parts.len(), 1);
+    assert_eq!(
+        profile.summaries.total.regressions,
+        vec![ToolRegression::Soft {
+            metric: MetricKind::Callgrind(EventKind::Ir),
+            new: Metric::Int(1734),
+            old: Metric::Int(1500),
+            diff_pct: 15.6,
+            limit: 10.0,
+        }]
+    );
+
+    let ToolMetricSummary::Callgrind(metrics_summary) = &profile.summaries.total.summary else {
+        panic!("Expected a callgrind summary");
+    };
+    let metrics_diff = metrics_summary.0.get(&EventKind::Ir).unwrap();
+    assert_eq!(
+        metrics_diff.metrics,
+        EitherOrBoth::Both(Metric::Int(1734), Metric::Int(1500))
+    );
+    assert_eq!(
+        metrics_diff.diffs,
+        Some(Diffs {
+            diff_pct: 15.6,
+            factor: 1.156
+        })
+    );
+}
+
+#[test]
+fn test_summary_serialize_roundtrip() {
+    let summary = Fixtures::load_summary();
+
+    let serialized = serde_json::to_string(&summary).unwrap();
+    let actual: BenchmarkSummary = serde_json::from_str(&serialized).unwrap();
+
+    assert_eq!(actual, summary);
+}
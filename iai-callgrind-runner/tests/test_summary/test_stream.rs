@@ -0,0 +1,90 @@
+use std::fs;
+
+use iai_callgrind_runner::summary::BenchmarkSummary;
+use pretty_assertions::assert_eq;
+use tempfile::tempdir;
+
+use crate::common::Fixtures;
+
+#[test]
+fn test_append_to_stream_writes_one_line_of_json_per_summary() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("summaries.jsonl");
+
+    let first = Fixtures::load_summary();
+    let mut second = Fixtures::load_summary();
+    second.id = Some("long".to_owned());
+
+    first.append_to_stream(&path).unwrap();
+    second.append_to_stream(&path).unwrap();
+
+    let content = fs::read_to_string(&path).unwrap();
+    assert!(content.ends_with('\n'));
+
+    let lines = content.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(
+        serde_json::from_str::<BenchmarkSummary>(lines[0]).unwrap(),
+        first
+    );
+    assert_eq!(
+        serde_json::from_str::<BenchmarkSummary>(lines[1]).unwrap(),
+        second
+    );
+}
+
+#[test]
+fn test_append_to_stream_keeps_existing_content() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("summaries.jsonl");
+    fs::write(&path, "{}\n").unwrap();
+
+    let summary = Fixtures::load_summary();
+    summary.append_to_stream(&path).unwrap();
+
+    let content = fs::read_to_string(&path).unwrap();
+    let (first, second) = content.split_once('\n').unwrap();
+    assert_eq!(first, "{}");
+    assert_eq!(
+        serde_json::from_str::<BenchmarkSummary>(second.trim_end()).unwrap(),
+        summary
+    );
+}
+
+#[test]
+fn test_append_to_stream_when_directory_missing_then_error() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("missing").join("summaries.jsonl");
+
+    let summary = Fixtures::load_summary();
+    assert!(summary.append_to_stream(&path).is_err());
+}
+
+#[test]
+fn test_clear_parts_keeps_totals() {
+    let mut summary = Fixtures::load_summary();
+    let expected = summary
+        .profiles
+        .iter()
+        .map(|profile| profile.summaries.total.clone())
+        .collect::<Vec<_>>();
+    assert!(summary
+        .profiles
+        .iter()
+        .any(|profile| !profile.summaries.parts.is_empty()));
+
+    summary.clear_parts();
+
+    assert!(summary
+        .profiles
+        .iter()
+        .all(|profile| profile.summaries.parts.is_empty()));
+    assert_eq!(
+        summary
+            .profiles
+            .iter()
+            .map(|profile| profile.summaries.total.clone())
+            .collect::<Vec<_>>(),
+        expected
+    );
+}
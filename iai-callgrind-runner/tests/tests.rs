@@ -7,6 +7,12 @@ mod test_callgrind;
 #[cfg(feature = "default")]
 mod test_dhat;
 #[cfg(feature = "default")]
+mod test_report;
+#[cfg(feature = "default")]
 mod test_runner_binary;
 #[cfg(feature = "default")]
+mod test_store;
+#[cfg(feature = "default")]
+mod test_summary;
+#[cfg(feature = "default")]
 mod test_tool;
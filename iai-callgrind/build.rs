@@ -186,7 +186,7 @@ mod imp {
                 || ((target.vendor == "sun") || target.vendor == "pc") && target.os == "solaris")
         {
             Some(Support::X86)
-        } else if target.arch == "arm" && target.os == "linux" && target.env == "gnu" {
+        } else if target.arch == "arm" && (target.os == "linux" || target.os == "android") {
             Some(Support::Arm)
         } else if target.arch == "aarch64"
             && (target.os == "freebsd" || (target.os == "linux" && target.env == "gnu"))
@@ -39,6 +39,7 @@ impl GroupsBuilder {
                 has_setup,
                 has_teardown,
                 default_tool: ValgrindTool::Cachegrind,
+                metadata: Vec::default(),
             }
         } else {
             InternalBinaryBenchmarkGroups {
@@ -48,6 +49,7 @@ impl GroupsBuilder {
                 has_setup,
                 has_teardown,
                 default_tool: ValgrindTool::Callgrind,
+                metadata: Vec::default(),
             }
         };
 
@@ -59,6 +61,12 @@ impl GroupsBuilder {
         }
     }
 
+    pub fn add_metadata(&mut self, key: &str, value: &str) {
+        self.groups
+            .metadata
+            .push((key.to_owned(), value.to_owned()));
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub fn add_group(
         &mut self,
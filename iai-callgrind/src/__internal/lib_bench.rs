@@ -23,6 +23,7 @@ impl GroupsBuilder {
             has_setup,
             has_teardown,
             default_tool: ValgrindTool::Cachegrind,
+            metadata: Vec::default(),
         })
     }
 
@@ -40,15 +41,21 @@ impl GroupsBuilder {
             has_setup,
             has_teardown,
             default_tool: ValgrindTool::Callgrind,
+            metadata: Vec::default(),
         })
     }
 
+    pub fn add_metadata(&mut self, key: &str, value: &str) {
+        self.0.metadata.push((key.to_owned(), value.to_owned()));
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub fn add_group(
         &mut self,
         id: String,
         config: Option<crate::__internal::InternalLibraryBenchmarkConfig>,
         compare_by_id: Option<bool>,
+        regression_budget: Option<f64>,
         has_setup: bool,
         has_teardown: bool,
         benches: MacroLibBenches,
@@ -59,6 +66,7 @@ impl GroupsBuilder {
             has_setup,
             has_teardown,
             compare_by_id,
+            regression_budget,
             ..Default::default()
         };
 
@@ -68,15 +76,28 @@ impl GroupsBuilder {
                 config: get_config(),
             };
             for macro_lib_bench in *macro_lib_benches {
+                let (iter_count, iter_ids) = match macro_lib_bench.func {
+                    super::InternalLibFunctionKind::Iter(func) => {
+                        (Some(func(super::InternalIterRun::Count)), None)
+                    }
+                    super::InternalLibFunctionKind::IterIds(func) => {
+                        let ids = func(None);
+                        (Some(ids.len()), Some(ids))
+                    }
+                    super::InternalLibFunctionKind::Default(_) => (None, None),
+                };
                 let bench = crate::__internal::InternalLibraryBenchmarkBench {
                     id: macro_lib_bench.id_display.map(ToString::to_string),
                     args: macro_lib_bench.args_display.map(ToString::to_string),
                     function_name: (*function_name).to_owned(),
                     config: macro_lib_bench.config.map(|f| f()),
-                    iter_count: match macro_lib_bench.func {
-                        super::InternalLibFunctionKind::Iter(func) => Some(func(None)),
-                        super::InternalLibFunctionKind::Default(_) => None,
-                    },
+                    description: macro_lib_bench.description.map(ToString::to_string),
+                    expect_regression: macro_lib_bench.expect_regression.map(ToString::to_string),
+                    iter_count,
+                    iter_ids,
+                    key: macro_lib_bench.key.map(ToString::to_string),
+                    runs_on: macro_lib_bench.runs_on.map(ToString::to_string),
+                    skip: macro_lib_bench.skip.map(ToString::to_string),
                 };
                 benches.benches.push(bench);
             }
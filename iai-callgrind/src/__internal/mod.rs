@@ -6,6 +6,11 @@ pub mod bin_bench;
 pub mod error;
 pub mod lib_bench;
 
+use std::ffi::OsString;
+use std::sync::OnceLock;
+
+use crate::BenchContext;
+
 // The runner api is not used directly in order to decouple the user interface and
 // documentation from the internal usage.
 //
@@ -18,31 +23,75 @@ pub mod lib_bench;
 // As an exception, enums from the runner api are usually used directly and re-exported in
 // `lib.rs`.
 pub use iai_callgrind_runner::api::{
-    BinaryBenchmark as InternalBinaryBenchmark,
+    BenchmarkOutcome as InternalBenchmarkOutcome, BinaryBenchmark as InternalBinaryBenchmark,
     BinaryBenchmarkBench as InternalBinaryBenchmarkBench,
     BinaryBenchmarkConfig as InternalBinaryBenchmarkConfig,
     BinaryBenchmarkGroup as InternalBinaryBenchmarkGroup,
     BinaryBenchmarkGroups as InternalBinaryBenchmarkGroups,
     CachegrindRegressionConfig as InternalCachegrindRegressionConfig,
-    CallgrindRegressionConfig as InternalCallgrindRegressionConfig, Command as InternalCommand,
+    CallgrindRegressionConfig as InternalCallgrindRegressionConfig,
+    ColorThresholds as InternalColorThresholds, Command as InternalCommand,
     CommandKind as InternalCommandKind, Delay as InternalDelay,
     DhatRegressionConfig as InternalDhatRegressionConfig, EntryPoint as InternalEntryPoint,
     ExitWith as InternalExitWith, Fixtures as InternalFixtures,
-    FlamegraphConfig as InternalFlamegraphConfig,
-    LibraryBenchmark as InternalLibraryBenchmarkBenches,
+    FlamegraphConfig as InternalFlamegraphConfig, InputScript as InternalInputScript,
+    InputStep as InternalInputStep, LibraryBenchmark as InternalLibraryBenchmarkBenches,
     LibraryBenchmarkBench as InternalLibraryBenchmarkBench,
     LibraryBenchmarkConfig as InternalLibraryBenchmarkConfig,
     LibraryBenchmarkGroup as InternalLibraryBenchmarkGroup,
     LibraryBenchmarkGroups as InternalLibraryBenchmarkGroups, OutputFormat as InternalOutputFormat,
-    RawArgs as InternalRawArgs, Sandbox as InternalSandbox, Tool as InternalTool,
+    Phase as InternalPhase, RawArgs as InternalRawArgs, Sandbox as InternalSandbox,
+    Terminate as InternalTerminate, Tool as InternalTool,
     ToolFlamegraphConfig as InternalToolFlamegraphConfig,
     ToolOutputFormat as InternalToolOutputFormat,
     ToolRegressionConfig as InternalToolRegressionConfig, Tools as InternalTools,
 };
 
+/// The module of the empty benchmark function to measure the overhead of the benchmark harness
+///
+/// The name of the module matches the default entry point of library benchmarks, so the
+/// calibration is measured exactly like the benchmark functions.
+pub mod __iai_callgrind_wrapper_mod {
+    #[inline(never)]
+    pub fn calibration() {
+        std::hint::black_box(());
+    }
+}
+
+/// Separate the elements of an `iter` benchmark which are all run in the same process
+///
+/// The runner instructs callgrind to dump the metrics before this function is entered, so the
+/// metrics of each element end up in their own part. The function is not part of the
+/// `__iai_callgrind_wrapper_mod`, so its own costs are not collected.
+#[inline(never)]
+pub fn __iai_callgrind_iter_separator(index: usize) {
+    std::hint::black_box(index);
+}
+
+/// The name of the `library_benchmark_group!` of the library benchmark run in this process
+static LIB_BENCH_GROUP: OnceLock<&'static str> = OnceLock::new();
+
+/// The uri of the benchmark passed by the runner in the CodSpeed mode
+///
+/// The name is the same as `envs::IAI_CALLGRIND_CODSPEED_URI` of the `iai-callgrind-runner`.
+const IAI_CALLGRIND_CODSPEED_URI: &str = "IAI_CALLGRIND_CODSPEED_URI";
+
+/// What the runner requested from the function of an `iter` benchmark
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InternalIterRun {
+    /// Run all elements separated by [`__iai_callgrind_iter_separator`]
+    All,
+    /// Return the number of elements without running the benchmark
+    Count,
+    /// Run only the element at this index
+    Index(usize),
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum InternalLibFunctionKind {
-    Iter(fn(Option<usize>) -> usize),
+    Iter(fn(InternalIterRun) -> usize),
+    /// Like `Iter` but returns the ids of the benchmarks instead of the count if called with `None`
+    IterIds(fn(Option<usize>) -> Vec<String>),
     Default(fn()),
 }
 
@@ -57,6 +106,16 @@ pub enum InternalBinAssistantKind {
     None,
 }
 
+impl InternalIterRun {
+    /// Return the index if only a single element is run
+    pub fn index(self) -> Option<usize> {
+        match self {
+            Self::Index(index) => Some(index),
+            Self::All | Self::Count => None,
+        }
+    }
+}
+
 impl InternalBinAssistantKind {
     pub fn is_some(&self) -> bool {
         *self != Self::None
@@ -74,8 +133,13 @@ pub enum InternalBinFunctionKind {
 pub struct InternalMacroLibBench {
     pub args_display: Option<&'static str>,
     pub config: Option<fn() -> InternalLibraryBenchmarkConfig>,
+    pub description: Option<&'static str>,
+    pub expect_regression: Option<&'static str>,
     pub func: InternalLibFunctionKind,
     pub id_display: Option<&'static str>,
+    pub key: Option<&'static str>,
+    pub runs_on: Option<&'static str>,
+    pub skip: Option<&'static str>,
 }
 
 /// Used in iai-callgrind-macros to store the essential information about a binary benchmark
@@ -157,13 +221,26 @@ impl Runner {
     }
 
     /// Execute `iai-callgrind-runner` exiting with the exit code of the runner if not `0`
+    ///
+    /// If the runner can't be started, for example because it isn't available on this platform,
+    /// and `--allow-unsupported` is given, all benchmarks are skipped.
     pub fn exec(mut self, encoded: Vec<u8>) -> Result<(), error::Errors> {
-        let mut child = self
+        let spawned = self
             .cmd
             .arg(encoded.len().to_string())
             .stdin(std::process::Stdio::piped())
-            .spawn()
-            .map_err(|e| {
+            .spawn();
+        let mut child = match spawned {
+            Ok(child) => child,
+            Err(error) if is_unsupported_allowed() => {
+                println!(
+                    "Iai-Callgrind result: Skipped. All benchmarks of '{}' skipped: Unsupported \
+                     platform: Failed to run iai-callgrind-runner: {error}",
+                    self.module_path
+                );
+                return Ok(());
+            }
+            Err(e) => {
                 let mut errors = error::Errors::default();
                 errors.add(error::Error::new(
                     &ModulePath::new(&self.module_path),
@@ -176,8 +253,9 @@ impl Runner {
                          https://iai-callgrind.github.io/iai-callgrind/latest/html/installation/iai_callgrind.html",
                     ),
                 ));
-                errors
-            })?;
+                return Err(errors);
+            }
+        };
 
         let mut stdin = child
             .stdin
@@ -202,3 +280,155 @@ impl Runner {
         Ok(())
     }
 }
+
+/// Return true if `--allow-unsupported` is given on the command-line or as environment variable
+///
+/// The boolish values are the same as in the `iai-callgrind-runner`.
+fn is_unsupported_allowed() -> bool {
+    let is_true = |value: &str| {
+        matches!(
+            value.to_ascii_lowercase().as_str(),
+            "y" | "yes" | "t" | "true" | "on" | "1"
+        )
+    };
+
+    let from_args = std::env::args()
+        .skip(1)
+        .filter_map(|arg| match arg.as_str() {
+            "--allow-unsupported" => Some(true),
+            _ => arg.strip_prefix("--allow-unsupported=").map(is_true),
+        })
+        .last();
+
+    from_args.unwrap_or_else(|| {
+        std::env::var("IAI_CALLGRIND_ALLOW_UNSUPPORTED").is_ok_and(|value| is_true(&value))
+    })
+}
+
+#[cfg(feature = "codspeed")]
+fn run_codspeed<F>(uri: &str, func: F)
+where
+    F: FnOnce(),
+{
+    use crate::client_requests::callgrind;
+
+    let metadata = std::ffi::CString::new(concat!(
+        "Metadata: iai-callgrind ",
+        env!("CARGO_PKG_VERSION")
+    ))
+    .expect("The metadata should not contain a nul byte");
+    let uri = std::ffi::CString::new(uri).expect("The benchmark uri should not contain a nul byte");
+
+    callgrind::dump_stats_at(&metadata);
+    callgrind::zero_stats();
+    callgrind::start_instrumentation();
+    func();
+    callgrind::stop_instrumentation();
+    callgrind::dump_stats_at(&uri);
+}
+
+/// Apply the `args_filter` of the `main!` macro to the command-line `args` of the benchmark binary
+///
+/// # Panics
+///
+/// If the `args_filter` returns an argument which is not valid unicode
+pub fn filter_args<F>(args_filter: F, args: Vec<String>) -> Vec<String>
+where
+    F: FnOnce(Vec<OsString>) -> Vec<OsString>,
+{
+    args_filter(args.into_iter().map(OsString::from).collect())
+        .into_iter()
+        .map(|arg| {
+            arg.into_string().unwrap_or_else(|arg| {
+                panic!("The arguments returned by `args_filter` should be valid unicode: {arg:?}")
+            })
+        })
+        .collect()
+}
+
+/// Create the [`BenchContext`] of a library benchmark in the `library_benchmark_group!` which is
+/// currently run
+pub fn bench_context(function: &'static str, id: Option<&'static str>) -> BenchContext {
+    BenchContext {
+        function,
+        group: LIB_BENCH_GROUP.get().copied().unwrap_or_default(),
+        id,
+    }
+}
+
+/// Run the library benchmark `func` of the `group` as requested by the runner
+///
+/// If the runner runs in the CodSpeed compatible mode, it sets the uri of the benchmark in the
+/// `IAI_CALLGRIND_CODSPEED_URI` environment variable and `func` is surrounded with the
+/// instrumentation markers expected by CodSpeed. Otherwise, `func` is just called.
+#[cfg(feature = "codspeed")]
+#[inline(never)]
+pub fn run_lib_bench<F>(group: &'static str, func: F)
+where
+    F: FnOnce(),
+{
+    // Only one benchmark is run per process, so this should never fail. Setting a `&'static str`
+    // doesn't allocate.
+    let _ = LIB_BENCH_GROUP.set(group);
+
+    match std::env::var(IAI_CALLGRIND_CODSPEED_URI) {
+        Ok(uri) => run_codspeed(&uri, func),
+        Err(_) => func(),
+    }
+}
+
+/// Run the library benchmark `func` of the `group` as requested by the runner
+///
+/// Without the `codspeed` feature, this function is always inlined, so it doesn't add a frame to
+/// the call graph of the benchmark.
+///
+/// # Panics
+///
+/// If the runner runs in the CodSpeed compatible mode
+#[cfg(not(feature = "codspeed"))]
+#[allow(clippy::inline_always)]
+#[inline(always)]
+pub fn run_lib_bench<F>(group: &'static str, func: F)
+where
+    F: FnOnce(),
+{
+    // See the `run_lib_bench` function with the `codspeed` feature
+    let _ = LIB_BENCH_GROUP.set(group);
+
+    assert!(
+        std::env::var_os(IAI_CALLGRIND_CODSPEED_URI).is_none(),
+        "The CodSpeed compatible mode requires the `codspeed` feature of iai-callgrind. Please \
+         enable it in your Cargo.toml file or disable the CodSpeed mode with `--codspeed=no`."
+    );
+    func();
+}
+
+/// Return true if the runner captures the output and expects the phase markers
+///
+/// If the output is shown in the terminal, for example with `--nocapture`, the markers would only
+/// be noise.
+fn is_phase_marked() -> bool {
+    std::env::var_os("IAI_CALLGRIND_PHASE_MARKERS").is_some()
+}
+
+/// Print the end marker of the [`InternalPhase`] of a library benchmark to stdout and stderr
+///
+/// The marker is only printed if the runner captures the output.
+pub fn phase_end(phase: InternalPhase) {
+    if is_phase_marked() {
+        println!("{}", phase.end_marker());
+        eprintln!("{}", phase.end_marker());
+    }
+}
+
+/// Print the start marker of the [`InternalPhase`] of a library benchmark to stdout and stderr
+///
+/// The markers allow the runner to separate the output of the `setup` and `teardown` functions
+/// from the output of the benchmark function. Like the end marker, the start marker is only
+/// printed if the runner captures the output.
+pub fn phase_start(phase: InternalPhase) {
+    if is_phase_marked() {
+        println!("{}", phase.start_marker());
+        eprintln!("{}", phase.start_marker());
+    }
+}
@@ -9,7 +9,9 @@ use std::time::Duration;
 use derive_more::AsRef;
 use iai_callgrind_macros::IntoInner;
 
-use crate::{DelayKind, ExitWith, Stdin, Stdio, ValgrindTool, __internal};
+use crate::{
+    DelayKind, ExitWith, PathResolution, Signal, Stdin, Stdio, ValgrindTool, VgdbMode, __internal,
+};
 
 /// [low level api](`crate::binary_benchmark_group`) only: Create a new benchmark id
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -274,6 +276,37 @@ pub struct Command(__internal::InternalCommand);
 #[derive(Debug, Default, Clone, PartialEq, Eq, IntoInner, AsRef)]
 pub struct Delay(__internal::InternalDelay);
 
+/// The `InputScript` to drive the stdin of an interactive [`Command`]
+///
+/// An `InputScript` is a sequence of steps which are executed by the runner while the [`Command`]
+/// is running: Writing to the stdin of the [`Command`] ([`InputScript::write`]), waiting until the
+/// stdout of the [`Command`] contains an expected string ([`InputScript::wait_for`]) and sleeping
+/// ([`InputScript::sleep`]). This allows benchmarking interactive command-line applications like
+/// REPLs or shells deterministically from end to end. After the last step, the stdin is closed,
+/// so the [`Command`] receives an end-of-file.
+///
+/// # Examples
+///
+/// Assuming `my-repl` prints a prompt `> ` before it reads the next line from the stdin and exits
+/// on `quit`:
+///
+/// ```rust
+/// # macro_rules! env { ($m:tt) => {{ "/some/path" }} }
+/// use iai_callgrind::{Command, InputScript};
+///
+/// let command = Command::new(env!("CARGO_BIN_EXE_my-repl"))
+///     .input_script(
+///         InputScript::new()
+///             .wait_for("> ")
+///             .write_line("1 + 2")
+///             .wait_for("> ")
+///             .write_line("quit"),
+///     )
+///     .build();
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq, IntoInner, AsRef)]
+pub struct InputScript(__internal::InternalInputScript);
+
 /// The `Sandbox` in which the `setup`, `teardown` and the [`Command`] are run
 ///
 /// The `Sandbox` is a temporary directory which is created before the execution of the
@@ -1071,6 +1104,9 @@ impl BinaryBenchmarkConfig {
     /// arguments](https://valgrind.org/docs/manual/manual-core.html#manual-core.options) are
     /// allowed.
     ///
+    /// Instead of plain strings, the arguments can be built with the validated setters of
+    /// [`crate::ValgrindArgs`].
+    ///
     /// These arguments can be overwritten by tool specific arguments for example with
     /// [`crate::Callgrind::args`].
     ///
@@ -1373,6 +1409,36 @@ impl BinaryBenchmarkConfig {
         self
     }
 
+    /// Configure how a bare program name (like `echo`) of the [`Command`] is resolved
+    ///
+    /// Per default ([`PathResolution::Inherit`]), bare program names are searched in the `PATH` of
+    /// `iai-callgrind-runner`. The resolved absolute path is shown in the logs and stored in the
+    /// summary. For reproducible benchmarks of system binaries, the search can be restricted to
+    /// specific directories with [`PathResolution::Dirs`] or disabled with
+    /// [`PathResolution::Absolute`], in which case all command paths have to be absolute.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use iai_callgrind::{binary_benchmark_group};
+    /// # binary_benchmark_group!(
+    /// #    name = my_group;
+    /// #    benchmarks = |_group: &mut BinaryBenchmarkGroup| {});
+    /// use iai_callgrind::{main, BinaryBenchmarkConfig, PathResolution};
+    ///
+    /// # fn main() {
+    /// main!(
+    ///     config = BinaryBenchmarkConfig::default()
+    ///         .path_resolution(PathResolution::Dirs(vec!["/usr/bin".into()]));
+    ///     binary_benchmark_groups = my_group
+    /// );
+    /// # }
+    /// ```
+    pub fn path_resolution(&mut self, value: PathResolution) -> &mut Self {
+        self.0.path_resolution = Some(value);
+        self
+    }
+
     /// Add a configuration for a valgrind tool
     ///
     /// Valid configurations are [`crate::Callgrind`], [`crate::Cachegrind`], [`crate::Dhat`],
@@ -1572,6 +1638,110 @@ impl BinaryBenchmarkConfig {
         self.0.setup_parallel = Some(setup_parallel);
         self
     }
+
+    /// Add a named profile which can be selected with `--profile=<name>`
+    ///
+    /// See also [`crate::LibraryBenchmarkConfig::profile`].
+    ///
+    /// # Examples
+    ///
+    /// Run the benchmarks with `cargo bench -- --profile=memory` to run DHAT and Massif in
+    /// addition to callgrind:
+    ///
+    /// ```rust
+    /// # use iai_callgrind::{binary_benchmark, binary_benchmark_group};
+    /// # #[binary_benchmark]
+    /// # fn some_func() -> iai_callgrind::Command { iai_callgrind::Command::new("some/path") }
+    /// # binary_benchmark_group!(
+    /// #    name = some_group;
+    /// #    benchmarks = some_func
+    /// # );
+    /// use iai_callgrind::{main, BinaryBenchmarkConfig, Dhat, Massif};
+    ///
+    /// # fn main() {
+    /// main!(
+    ///     config = BinaryBenchmarkConfig::default()
+    ///         .profile(
+    ///             "memory",
+    ///             BinaryBenchmarkConfig::default()
+    ///                 .tool(Dhat::default())
+    ///                 .tool(Massif::default())
+    ///         );
+    ///     binary_benchmark_groups = some_group
+    /// );
+    /// # }
+    /// ```
+    pub fn profile<T, U>(&mut self, name: T, config: U) -> &mut Self
+    where
+        T: Into<String>,
+        U: Into<__internal::InternalBinaryBenchmarkConfig>,
+    {
+        self.0.profiles.push((name.into(), config.into()));
+        self
+    }
+
+    /// Add tags to the benchmarks
+    ///
+    /// See also [`crate::LibraryBenchmarkConfig::tags`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use iai_callgrind::{binary_benchmark, binary_benchmark_group};
+    /// # #[binary_benchmark]
+    /// # fn some_func() -> iai_callgrind::Command { iai_callgrind::Command::new("some/path") }
+    /// # binary_benchmark_group!(
+    /// #    name = some_group;
+    /// #    benchmarks = some_func
+    /// # );
+    /// use iai_callgrind::{main, BinaryBenchmarkConfig};
+    ///
+    /// # fn main() {
+    /// main!(
+    ///     config = BinaryBenchmarkConfig::default().tags(["slow", "io"]);
+    ///     binary_benchmark_groups = some_group
+    /// );
+    /// # }
+    /// ```
+    pub fn tags<I, T>(&mut self, tags: I) -> &mut Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        self.0.tags.extend(tags.into_iter().map(Into::into));
+        self
+    }
+
+    /// Run the benchmarks under the gdbserver of valgrind to debug them with gdb
+    ///
+    /// Only the default tool is run with the [`crate::VgdbMode`] and the command to attach gdb is
+    /// printed to the terminal. The output of the benchmarks is not parsed or compared. This is the
+    /// same as the command-line argument `--vgdb`, which takes precedence. Usually, this option is
+    /// best used in the configuration of a single benchmark.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use iai_callgrind::{binary_benchmark, binary_benchmark_group};
+    /// # #[binary_benchmark]
+    /// # fn some_func() -> iai_callgrind::Command { iai_callgrind::Command::new("some/path") }
+    /// # binary_benchmark_group!(
+    /// #    name = some_group;
+    /// #    benchmarks = some_func
+    /// # );
+    /// use iai_callgrind::{main, BinaryBenchmarkConfig, VgdbMode};
+    ///
+    /// # fn main() {
+    /// main!(
+    ///     config = BinaryBenchmarkConfig::default().vgdb(VgdbMode::FullAt(0));
+    ///     binary_benchmark_groups = some_group
+    /// );
+    /// # }
+    /// ```
+    pub fn vgdb(&mut self, mode: VgdbMode) -> &mut Self {
+        self.0.vgdb = Some(mode);
+        self
+    }
 }
 
 impl BinaryBenchmarkGroup {
@@ -1702,6 +1872,133 @@ impl Command {
         })
     }
 
+    /// Create a new [`Command`] for a binary of your package by its `name`
+    ///
+    /// The path of the binary is resolved relative to the benchmark executable in the target
+    /// directory, like `target/release/<name>`. Other than hard-coded paths, this also works with
+    /// a custom target directory, `--separate-targets` and when cross compiling. Usually,
+    /// [`env!("CARGO_BIN_EXE_<name>)`](https://doc.rust-lang.org/cargo/reference/environment-variables.html#environment-variables-cargo-sets-for-crates)
+    /// is the better choice, but it isn't available for example in benchmarks of another package
+    /// of the workspace. The binary has to be built with the same profile as the benchmarks.
+    ///
+    /// # Examples
+    ///
+    /// Assume the project's binary or one of your project's binaries name is `my-echo`:
+    ///
+    /// ```
+    /// use iai_callgrind::Command;
+    ///
+    /// let command = Command::cargo_bin("my-echo").arg("foo").build();
+    /// ```
+    pub fn cargo_bin<T>(name: T) -> Self
+    where
+        T: AsRef<str>,
+    {
+        Self::new(Self::cargo_profile_dir().join(format!(
+            "{}{}",
+            name.as_ref(),
+            std::env::consts::EXE_SUFFIX
+        )))
+    }
+
+    /// Create a new [`Command`] for an example of your package by its `name`
+    ///
+    /// Same as [`Command::cargo_bin`], but the path is resolved to the `examples` directory in the
+    /// target directory, like `target/release/examples/<name>`. Cargo doesn't build the examples
+    /// when running the benchmarks, so the example has to be built beforehand with the same
+    /// profile as the benchmarks, for example with `cargo build --release --examples`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use iai_callgrind::Command;
+    ///
+    /// let command = Command::cargo_example("demo").arg("foo").build();
+    /// ```
+    pub fn cargo_example<T>(name: T) -> Self
+    where
+        T: AsRef<str>,
+    {
+        Self::new(Self::cargo_profile_dir().join("examples").join(format!(
+            "{}{}",
+            name.as_ref(),
+            std::env::consts::EXE_SUFFIX
+        )))
+    }
+
+    /// Return the directory of the profile in the target directory (like `target/release`)
+    ///
+    /// The benchmark executable itself is located in the `deps` directory of the profile
+    /// directory.
+    fn cargo_profile_dir() -> PathBuf {
+        let exe = std::env::current_exe()
+            .expect("The path to the benchmark executable should be accessible");
+        Self::profile_dir_of(&exe)
+    }
+
+    /// Return the profile directory of the benchmark executable `exe`
+    fn profile_dir_of(exe: &Path) -> PathBuf {
+        let dir = exe.parent().unwrap_or(exe);
+        if dir.ends_with("deps") {
+            dir.parent().unwrap_or(dir).to_path_buf()
+        } else {
+            dir.to_path_buf()
+        }
+    }
+
+    /// Configure this [`Command`] with a [`BinaryBenchmarkConfig`]
+    ///
+    /// The configuration of the `Command` has the highest precedence and is applied on top of the
+    /// configurations of the `#[bench]`, `#[benches]` or [`Bench`], the `#[binary_benchmark]`,
+    /// the group and `main!`. Since the `Command` is built in the benchmark function, this allows
+    /// running different commands of the same `#[binary_benchmark]` function under different
+    /// valgrind tools (see [`BinaryBenchmarkConfig::tool`] and
+    /// [`BinaryBenchmarkConfig::tool_override`]) without splitting the function. Calling this
+    /// method multiple times merges the configurations in the given order.
+    ///
+    /// # Examples
+    ///
+    /// Run DHAT in addition to callgrind only for the command with the big input file:
+    ///
+    /// ```rust
+    /// # macro_rules! env { ($m:tt) => {{ "/some/path" }} }
+    /// # use iai_callgrind::main;
+    /// use iai_callgrind::{binary_benchmark, binary_benchmark_group, BinaryBenchmarkConfig, Dhat};
+    ///
+    /// #[binary_benchmark]
+    /// #[bench::small("benches/small.txt")]
+    /// #[bench::big("benches/big.txt")]
+    /// fn bench_binary(path: &str) -> iai_callgrind::Command {
+    ///     let mut command = iai_callgrind::Command::new(env!("CARGO_BIN_EXE_my-foo"));
+    ///     command.arg(path);
+    ///     if path.ends_with("big.txt") {
+    ///         command.config(BinaryBenchmarkConfig::default().tool(Dhat::default()));
+    ///     }
+    ///     command.build()
+    /// }
+    ///
+    /// binary_benchmark_group!(
+    ///     name = my_group;
+    ///     benchmarks = bench_binary
+    /// );
+    /// # fn main() {
+    /// # main!(binary_benchmark_groups = my_group);
+    /// # }
+    /// ```
+    pub fn config<T>(&mut self, config: T) -> &mut Self
+    where
+        T: Into<__internal::InternalBinaryBenchmarkConfig>,
+    {
+        let config = config.into();
+        self.0.config = std::mem::take(&mut self.0.config).update_from_all([Some(&config)]);
+        // The runner applies the configuration of the command on top of the other configurations,
+        // so the override of the tools has to be kept
+        if config.tools_override.is_some() {
+            self.0.config.tools_override = config.tools_override;
+        }
+        self
+    }
+
     /// Delay the execution of the [`Command`]
     ///
     /// This option allows to delay the [`Command`] execution till a certain event has happened.
@@ -1794,6 +2091,16 @@ impl Command {
     /// This option works exactly the same way as [`std::process::Command::arg`]. To pass multiple
     /// arguments see [`Command::args`].
     ///
+    /// The following placeholders in the arguments are expanded when the benchmark is run:
+    ///
+    /// * `{id}`: The id of the benchmark (`#[bench::id(...)]`)
+    /// * `{sandbox}`: The path of the [`Sandbox`] directory. The sandbox has to be enabled.
+    /// * `{fixture:NAME}`: The path of the fixture with the file name `NAME` as specified with
+    ///   [`Sandbox::fixtures`]. If the sandbox is enabled, this is the path of the fixture copied
+    ///   into the sandbox.
+    ///
+    /// Other text in curly braces is passed to the [`Command`] as is.
+    ///
     /// # Examples
     ///
     /// ```rust
@@ -1826,7 +2133,8 @@ impl Command {
 
     /// Adds multiple arguments to pass to the [`Command`]
     ///
-    /// This option works exactly the same way as [`std::process::Command::args`].
+    /// This option works exactly the same way as [`std::process::Command::args`]. The placeholders
+    /// in the arguments are expanded as described in [`Command::arg`].
     ///
     /// # Examples
     ///
@@ -2274,6 +2582,140 @@ impl Command {
         self
     }
 
+    /// Terminate the [`Command`] with the [`Signal`] after the given time
+    ///
+    /// The signal is sent by the runner to the running [`Command`] after the duration `after` has
+    /// passed since its start. This allows measuring the shutdown path of long-running
+    /// applications like servers, including their signal handlers. If no [`Command::exit_with`] is
+    /// given, the [`Command`] is expected to be terminated by the `signal`
+    /// ([`ExitWith::Signal`]). If the signal is handled by the [`Command`] and it exits gracefully,
+    /// set the expected exit status with [`Command::exit_with`] for example to
+    /// [`ExitWith::Success`].
+    ///
+    /// Note that the [`Command`] runs a lot slower under valgrind than natively, so the duration
+    /// should be chosen generously.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # macro_rules! env { ($m:tt) => {{ "/some/path" }} }
+    /// # use iai_callgrind::main;
+    /// use std::time::Duration;
+    ///
+    /// use iai_callgrind::{binary_benchmark, binary_benchmark_group, ExitWith, Signal};
+    ///
+    /// #[binary_benchmark]
+    /// fn bench_binary() -> iai_callgrind::Command {
+    ///     iai_callgrind::Command::new(env!("CARGO_BIN_EXE_my-server"))
+    ///         .terminate_with(Signal::Term, Duration::from_secs(2))
+    ///         .exit_with(ExitWith::Success)
+    ///         .build()
+    /// }
+    ///
+    /// binary_benchmark_group!(
+    ///     name = my_group;
+    ///     benchmarks = bench_binary
+    /// );
+    /// # fn main() {
+    /// # main!(binary_benchmark_groups = my_group);
+    /// # }
+    /// ```
+    pub fn terminate_with(&mut self, signal: Signal, after: Duration) -> &mut Self {
+        self.0.terminate = Some(__internal::InternalTerminate { after, signal });
+        self
+    }
+
+    /// Drive the stdin of this [`Command`] with an [`InputScript`]
+    ///
+    /// The [`InputScript`] is executed by the runner while the [`Command`] is running. This is
+    /// useful to benchmark interactive command-line applications. The stdin of the [`Command`] is
+    /// connected to the runner, so the [`InputScript`] can't be combined with a [`Command::stdin`]
+    /// other than [`Stdin::Pipe`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # macro_rules! env { ($m:tt) => {{ "/some/path" }} }
+    /// # use iai_callgrind::main;
+    /// use iai_callgrind::{binary_benchmark, binary_benchmark_group, InputScript};
+    ///
+    /// #[binary_benchmark]
+    /// fn bench_repl() -> iai_callgrind::Command {
+    ///     iai_callgrind::Command::new(env!("CARGO_BIN_EXE_my-repl"))
+    ///         .input_script(
+    ///             InputScript::new()
+    ///                 .wait_for("> ")
+    ///                 .write_line("1 + 2")
+    ///                 .wait_for("> ")
+    ///                 .write_line("quit"),
+    ///         )
+    ///         .build()
+    /// }
+    ///
+    /// binary_benchmark_group!(
+    ///     name = my_group;
+    ///     benchmarks = bench_repl
+    /// );
+    /// # fn main() {
+    /// # main!(binary_benchmark_groups = my_group);
+    /// # }
+    /// ```
+    pub fn input_script<T>(&mut self, input_script: T) -> &mut Self
+    where
+        T: Into<__internal::InternalInputScript>,
+    {
+        self.0.input_script = Some(input_script.into());
+        self
+    }
+
+    /// Run the `command` after this [`Command`] as the next step of the same benchmark
+    ///
+    /// A benchmark with multiple steps runs all commands sequentially under the same valgrind tools
+    /// and reports the metrics of each step followed by the combined total of all steps. This
+    /// allows benchmarking workflows like "init + run + shutdown" as one comparable unit. The
+    /// `setup` runs before the first step and the `teardown` after the last step.
+    ///
+    /// The steps are run and shown in the terminal output like the processes of a benchmark with
+    /// `--trace-children=yes` (the default), so this option is required. Only the path, the
+    /// arguments and the [`Command::stdin`], [`Command::stdout`] and [`Command::stderr`] of the
+    /// following steps are used. Everything else, for example the environment variables, is
+    /// configured with this first [`Command`] and applies to all steps.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # macro_rules! env { ($m:tt) => {{ "/some/path" }} }
+    /// # use iai_callgrind::main;
+    /// use iai_callgrind::{binary_benchmark, binary_benchmark_group, Command};
+    ///
+    /// #[binary_benchmark]
+    /// fn bench_workflow() -> Command {
+    ///     Command::new(env!("CARGO_BIN_EXE_my-db"))
+    ///         .arg("init")
+    ///         .then(Command::new(env!("CARGO_BIN_EXE_my-db")).arg("run"))
+    ///         .then(Command::new(env!("CARGO_BIN_EXE_my-db")).arg("shutdown"))
+    ///         .build()
+    /// }
+    ///
+    /// binary_benchmark_group!(
+    ///     name = my_group;
+    ///     benchmarks = bench_workflow
+    /// );
+    /// # fn main() {
+    /// # main!(binary_benchmark_groups = my_group);
+    /// # }
+    /// ```
+    pub fn then<T>(&mut self, command: T) -> &mut Self
+    where
+        T: Into<__internal::InternalCommand>,
+    {
+        let mut command = command.into();
+        let steps = std::mem::take(&mut command.steps);
+        self.0.steps.push(command);
+        self.0.steps.extend(steps);
+        self
+    }
+
     /// Finalize and build this [`Command`]
     ///
     /// # Examples
@@ -2472,6 +2914,131 @@ where
     }
 }
 
+impl InputScript {
+    /// Create a new and empty `InputScript`
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use iai_callgrind::InputScript;
+    ///
+    /// let script = InputScript::new().write_line("quit").build();
+    /// ```
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Write the `bytes` to the stdin of the [`Command`]
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use iai_callgrind::InputScript;
+    ///
+    /// let script = InputScript::new().write("1 + 2\n").write(b"\x04").build();
+    /// ```
+    pub fn write<T>(&mut self, bytes: T) -> &mut Self
+    where
+        T: AsRef<[u8]>,
+    {
+        self.0.steps.push(__internal::InternalInputStep::Write(
+            bytes.as_ref().to_vec(),
+        ));
+        self
+    }
+
+    /// Write the `line` followed by a newline to the stdin of the [`Command`]
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use iai_callgrind::InputScript;
+    ///
+    /// let script = InputScript::new().write_line("quit").build();
+    /// ```
+    pub fn write_line<T>(&mut self, line: T) -> &mut Self
+    where
+        T: AsRef<str>,
+    {
+        self.write(format!("{}\n", line.as_ref()))
+    }
+
+    /// Wait until the stdout of the [`Command`] contains the `expected` string
+    ///
+    /// Only the output after the match of the previous `wait_for` is searched, so waiting twice
+    /// for the same prompt waits for two prompts. If the `expected` string is not found within the
+    /// [`InputScript::timeout`], the [`Command`] is killed and the benchmark fails.
+    ///
+    /// The stdout of the [`Command`] is read by the runner and can't be redirected with
+    /// [`Command::stdout`] to a file or [`Stdio::Null`] if the `InputScript` waits for output.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use iai_callgrind::InputScript;
+    ///
+    /// let script = InputScript::new().wait_for("> ").write_line("quit").build();
+    /// ```
+    pub fn wait_for<T>(&mut self, expected: T) -> &mut Self
+    where
+        T: Into<String>,
+    {
+        self.0
+            .steps
+            .push(__internal::InternalInputStep::WaitFor(expected.into()));
+        self
+    }
+
+    /// Wait for the `duration` before executing the next step
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    ///
+    /// use iai_callgrind::InputScript;
+    ///
+    /// let script = InputScript::new()
+    ///     .sleep(Duration::from_millis(100))
+    ///     .write_line("quit")
+    ///     .build();
+    /// ```
+    pub fn sleep(&mut self, duration: Duration) -> &mut Self {
+        self.0
+            .steps
+            .push(__internal::InternalInputStep::Sleep(duration));
+        self
+    }
+
+    /// Set the timeout of each [`InputScript::wait_for`] step
+    ///
+    /// The default timeout is 10 seconds. Note that the [`Command`] runs a lot slower under
+    /// valgrind than natively.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    ///
+    /// use iai_callgrind::InputScript;
+    ///
+    /// let script = InputScript::new()
+    ///     .timeout(Duration::from_secs(30))
+    ///     .wait_for("> ")
+    ///     .build();
+    /// ```
+    pub fn timeout(&mut self, duration: Duration) -> &mut Self {
+        self.0.timeout = Some(duration);
+        self
+    }
+
+    /// Finalize and build this [`InputScript`]
+    #[must_use]
+    pub fn build(&mut self) -> Self {
+        self.clone()
+    }
+}
+
 impl Sandbox {
     /// Create a new `Sandbox` builder
     ///
@@ -2601,6 +3168,69 @@ mod tests {
         id.validate().unwrap();
     }
 
+    #[rstest]
+    #[case::deps("/target/release/deps/bench-1234", "/target/release")]
+    #[case::cross_target(
+        "/target/x86_64-unknown-linux-gnu/release/deps/bench-1234",
+        "/target/x86_64-unknown-linux-gnu/release"
+    )]
+    #[case::cross_target_project(
+        "/home/user/project/target/aarch64-unknown-linux-gnu/release/deps/bench-1234",
+        "/home/user/project/target/aarch64-unknown-linux-gnu/release"
+    )]
+    #[case::no_deps("/target/release/bench", "/target/release")]
+    fn test_command_profile_dir_of(#[case] exe: &str, #[case] expected: &str) {
+        assert_eq!(
+            Command::profile_dir_of(Path::new(exe)),
+            PathBuf::from(expected)
+        );
+    }
+
+    #[test]
+    fn test_command_config() {
+        let command = Command::new("echo")
+            .env("FOO", "BAR")
+            .config(BinaryBenchmarkConfig::default().env("BAZ", "QUX"))
+            .config(BinaryBenchmarkConfig::default().tool_override(crate::Dhat::default()))
+            .build();
+
+        let config = &command.0.config;
+        assert_eq!(
+            config.envs,
+            vec![
+                ("FOO".into(), Some("BAR".into())),
+                ("BAZ".into(), Some("QUX".into()))
+            ]
+        );
+        assert_eq!(
+            config.tools_override.as_ref().map(|tools| tools.0.len()),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn test_command_then() {
+        let command = Command::new("first")
+            .then(Command::new("second").then(Command::new("third")))
+            .then(Command::new("fourth"))
+            .build();
+
+        assert_eq!(
+            command
+                .0
+                .steps
+                .iter()
+                .map(|step| step.path.clone())
+                .collect::<Vec<_>>(),
+            vec![
+                PathBuf::from("second"),
+                PathBuf::from("third"),
+                PathBuf::from("fourth")
+            ]
+        );
+        assert!(command.0.steps.iter().all(|step| step.steps.is_empty()));
+    }
+
     #[rstest]
     #[case::empty("", "Invalid id: Cannot be empty")]
     #[case::non_ascii_first(
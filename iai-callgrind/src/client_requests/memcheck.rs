@@ -239,6 +239,136 @@ pub fn check_value_is_defined<T>(value: &T) -> usize {
     )
 }
 
+/// Mark the memory of the `slice` as unaddressable
+///
+/// The typed variant of [`make_mem_noaccess`]. This is useful for example in allocators to mark
+/// the memory of freed blocks, so that memcheck reports any access to it. Note the memory stays
+/// valid for the rust compiler, it's just memcheck which reports an access as error.
+///
+/// # Examples
+///
+/// ```rust
+/// use iai_callgrind::client_requests::memcheck;
+///
+/// let buffer = vec![0u8; 16];
+/// memcheck::make_slice_noaccess(&buffer[8..]);
+/// ```
+#[inline(always)]
+pub fn make_slice_noaccess<T>(slice: &[T]) -> usize {
+    make_mem_noaccess(slice.as_ptr().cast(), core::mem::size_of_val(slice))
+}
+
+/// Mark the memory of the `slice` as addressable but undefined
+///
+/// The typed variant of [`make_mem_undefined`]. This is useful for example to mark the memory
+/// handed out by an allocator as uninitialized, even if it is reused.
+///
+/// # Examples
+///
+/// ```rust
+/// use iai_callgrind::client_requests::memcheck;
+///
+/// let buffer = vec![0u8; 16];
+/// memcheck::make_slice_undefined(&buffer);
+/// ```
+#[inline(always)]
+pub fn make_slice_undefined<T>(slice: &[T]) -> usize {
+    make_mem_undefined(slice.as_ptr().cast(), core::mem::size_of_val(slice))
+}
+
+/// Mark the memory of the `slice` as addressable and defined
+///
+/// The typed variant of [`make_mem_defined`]. This is useful for example for memory which was
+/// initialized by a foreign function that memcheck can't see, for example a syscall unknown to
+/// valgrind.
+///
+/// # Examples
+///
+/// ```rust
+/// use iai_callgrind::client_requests::memcheck;
+///
+/// let buffer = vec![0u8; 16];
+/// memcheck::make_slice_defined(&buffer);
+/// ```
+#[inline(always)]
+pub fn make_slice_defined<T>(slice: &[T]) -> usize {
+    make_mem_defined(slice.as_ptr().cast(), core::mem::size_of_val(slice))
+}
+
+/// Check that the memory of the `slice` is addressable and defined
+///
+/// The typed variant of [`check_mem_is_defined`]. If suitable addressibility and definedness are
+/// not established, Valgrind prints an error message and this function returns the offset in
+/// bytes of the first offending byte relative to the start of the `slice` as error. Not running
+/// under valgrind, this function always returns `Ok`.
+///
+/// # Examples
+///
+/// ```rust
+/// use iai_callgrind::client_requests::memcheck;
+///
+/// let buffer = vec![0u8; 16];
+/// assert_eq!(memcheck::check_slice_is_defined(&buffer), Ok(()));
+/// ```
+#[inline(always)]
+pub fn check_slice_is_defined<T>(slice: &[T]) -> Result<(), usize> {
+    let start = slice.as_ptr() as usize;
+    match check_mem_is_defined(slice.as_ptr().cast(), core::mem::size_of_val(slice)) {
+        0 => Ok(()),
+        addr => Err(addr.saturating_sub(start)),
+    }
+}
+
+/// A named memory range created with [`create_block`] which is discarded when dropped
+///
+/// When memcheck reports an invalid access to an address in the range of the `Block`, it
+/// describes the address in terms of this block and its description. When the `Block` is dropped,
+/// the range is discarded with [`discard`]. The `Block` doesn't borrow the memory, so it can be
+/// stored next to the memory it describes for example in an allocator or a FFI wrapper.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::ffi::CString;
+///
+/// use iai_callgrind::client_requests::memcheck::Block;
+///
+/// let buffer = vec![0u8; 16];
+/// let block = Block::new(&buffer, CString::new("my buffer").unwrap());
+/// // memcheck errors in `buffer` now refer to "my buffer"
+/// drop(block);
+/// ```
+#[derive(Debug, PartialEq, Eq)]
+pub struct Block {
+    handle: BlockHandle,
+}
+
+impl Block {
+    /// Create a new `Block` for the memory of the `slice` with the description `desc`
+    ///
+    /// See also [`create_block`]
+    #[inline(always)]
+    pub fn new<T, D>(slice: &[T], desc: D) -> Self
+    where
+        D: AsRef<CStr>,
+    {
+        Self {
+            handle: create_block(slice.as_ptr().cast(), core::mem::size_of_val(slice), desc),
+        }
+    }
+
+    /// Return the raw [`BlockHandle`] of this `Block`
+    pub fn handle(&self) -> BlockHandle {
+        self.handle
+    }
+}
+
+impl Drop for Block {
+    fn drop(&mut self) {
+        discard::<()>(self.handle);
+    }
+}
+
 /// Do a full memory leak check (like `--leak-check=full`) mid-execution
 ///
 /// This is useful for incrementally checking for leaks between arbitrary places in the program's
@@ -42,7 +42,8 @@
 //! [`crate::client_requests::valgrind::count_errors`].
 //!
 //! The only exception to this rule are the [`crate::valgrind_printf`] macro and its descendents
-//! like [`crate::valgrind_printf_unchecked`] which can be found in the root of `iai-callgrind`.
+//! like [`crate::valgrind_printf_unchecked`] and the shorthands [`crate::vg_println`] and
+//! [`crate::vg_print_backtrace`] which can be found in the root of `iai-callgrind`.
 //!
 //! # Features
 //!
@@ -100,6 +101,7 @@
 //! | `x86/solaris`         | yes | -
 //! | `x86/windows+msvc`    | no  | TBD
 //! | `arm/linux`           | yes | -
+//! | `arm/android`         | yes | -
 //! | `aarch64/linux`       | yes | -
 //! | `riscv64/linux`       | yes | -
 //! | `x86_64/windows+msvc` | no  | unsupported by valgrind
@@ -229,6 +231,89 @@ macro_rules! format_cstring {
     }};
 }
 
+/// Print a formatted message to the valgrind log ending with a newline
+///
+/// Shorthand for [`crate::valgrind_println`] accepting the same arguments as [`std::println`]. The
+/// formatted message is converted into a [`std::ffi::CString`] and an interior `\0` byte results in
+/// a [`ClientRequestError::ValgrindPrintError`](error::ClientRequestError::ValgrindPrintError)
+/// instead of truncating the message. Without the `client_requests` feature this macro does
+/// nothing and always returns `Ok(())`.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use iai_callgrind::vg_println;
+///
+/// let value = 42;
+/// vg_println!("The value is: {value}").unwrap();
+/// assert!(vg_println!("Interior \0 byte").is_err());
+/// ```
+#[macro_export]
+macro_rules! vg_println {
+    ($($args:tt)*) => { $crate::valgrind_println!($($args)*) };
+}
+
+/// Print a formatted message followed by a backtrace to the valgrind log
+///
+/// Shorthand for [`crate::valgrind_println_backtrace`]. Like [`crate::vg_println`], an interior
+/// `\0` byte in the formatted message results in a
+/// [`ClientRequestError::ValgrindPrintError`](error::ClientRequestError::ValgrindPrintError).
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use iai_callgrind::vg_print_backtrace;
+///
+/// let value = 42;
+/// vg_print_backtrace!("Reached with value: {value}").unwrap();
+/// ```
+#[macro_export]
+macro_rules! vg_print_backtrace {
+    ($($args:tt)*) => { $crate::valgrind_println_backtrace!($($args)*) };
+}
+
+/// Instrument only the code in the block regardless of the tool running the benchmark
+///
+/// The block is surrounded with the `start_instrumentation` and `stop_instrumentation` client
+/// requests of callgrind ([`callgrind::start_instrumentation`]) and cachegrind
+/// ([`cachegrind::start_instrumentation`]). The client requests of the tool which is not running
+/// are ignored by valgrind, so the same code works if the default tool is switched between
+/// callgrind and cachegrind for example with `--default-tool`. The value of the block is returned.
+///
+/// Like with the client requests themselves, the instrumentation needs to be switched off at the
+/// start with `--instr-at-start=no` for cachegrind and with `--instr-atstart=no` for callgrind.
+/// The default [`EntryPoint`](crate::EntryPoint) of callgrind can be kept, since the block is
+/// usually part of the benchmark function.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use iai_callgrind::instrumented;
+///
+/// fn bubble_sort(input: Vec<i32>) -> Vec<i32> {
+///     // The algorithm
+/// #   input
+/// }
+///
+/// pub fn pre_bubble_sort(input: Vec<i32>) -> Vec<i32> {
+///     println!("Doing something before the function call");
+///     instrumented! {
+///         bubble_sort(input)
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! instrumented {
+    ($($body:tt)*) => {{
+        $crate::client_requests::callgrind::start_instrumentation();
+        $crate::client_requests::cachegrind::start_instrumentation();
+        let result = { $($body)* };
+        $crate::client_requests::cachegrind::stop_instrumentation();
+        $crate::client_requests::callgrind::stop_instrumentation();
+        result
+    }};
+}
+
 cfg_if! {
     if #[cfg(feature = "client_requests")] {
         /// Allow prints to valgrind log
@@ -507,6 +592,95 @@ pub type StackId = usize;
 /// are wrapping the same type on unix systems.
 pub type RawFd = cty::c_int;
 
+/// The valgrind tool this code is running under as returned by [`detect`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DetectedTool {
+    /// Not running under valgrind
+    NotUnderValgrind,
+    /// Running under the experimental BBV tool (`exp-bbv`)
+    Bbv,
+    /// Running under Cachegrind
+    Cachegrind,
+    /// Running under Callgrind
+    Callgrind,
+    /// Running under DHAT
+    Dhat,
+    /// Running under DRD
+    Drd,
+    /// Running under Helgrind
+    Helgrind,
+    /// Running under Massif
+    Massif,
+    /// Running under Memcheck
+    Memcheck,
+    /// Running under valgrind but the tool could not be identified
+    Unknown,
+}
+
+impl DetectedTool {
+    fn from_id(id: &str) -> Option<Self> {
+        match id {
+            "exp-bbv" => Some(Self::Bbv),
+            "cachegrind" => Some(Self::Cachegrind),
+            "callgrind" => Some(Self::Callgrind),
+            "dhat" => Some(Self::Dhat),
+            "drd" => Some(Self::Drd),
+            "helgrind" => Some(Self::Helgrind),
+            "massif" => Some(Self::Massif),
+            "memcheck" => Some(Self::Memcheck),
+            _ => None,
+        }
+    }
+
+    /// Identify the tool from the preloaded `vgpreload_<tool>-<arch>-<os>.so` library of valgrind
+    fn from_preload(preload: &str) -> Option<Self> {
+        preload.split([':', ' ']).find_map(|path| {
+            let name = std::path::Path::new(path).file_stem()?.to_str()?;
+            let id = name.strip_prefix("vgpreload_")?.rsplitn(3, '-').nth(2)?;
+            Self::from_id(id)
+        })
+    }
+}
+
+/// Detect the valgrind tool this code is running under
+///
+/// If not running under valgrind ([`valgrind::running_on_valgrind`] returns `0`), this function
+/// returns [`DetectedTool::NotUnderValgrind`]. Otherwise, the tool is identified by the
+/// `IAI_CALLGRIND_TOOL` environment variable which is set by the `iai-callgrind-runner` for
+/// benchmarks, or by the preload library of the tool in `LD_PRELOAD` which is available for all
+/// tools but callgrind, cachegrind and bbv. If the tool can't be identified,
+/// [`DetectedTool::Unknown`] is returned.
+///
+/// Note that with only the `client_requests_defs` feature, the client requests do nothing and this
+/// function always returns [`DetectedTool::NotUnderValgrind`].
+///
+/// # Examples
+///
+/// ```rust
+/// use iai_callgrind::client_requests::{self, DetectedTool};
+///
+/// // Memcheck is much slower than callgrind, so use a smaller input
+/// let size = match client_requests::detect() {
+///     DetectedTool::Memcheck => 100,
+///     _ => 10_000,
+/// };
+/// ```
+pub fn detect() -> DetectedTool {
+    if valgrind::running_on_valgrind() == 0 {
+        return DetectedTool::NotUnderValgrind;
+    }
+
+    std::env::var("IAI_CALLGRIND_TOOL")
+        .ok()
+        .and_then(|id| DetectedTool::from_id(&id))
+        .or_else(|| {
+            std::env::var("LD_PRELOAD")
+                .ok()
+                .and_then(|preload| DetectedTool::from_preload(&preload))
+        })
+        .unwrap_or(DetectedTool::Unknown)
+}
+
 /// Valgrind's version number from the `valgrind.h` file
 ///
 /// Note that the version numbers were introduced at valgrind version 3.6 and so would not exist in
@@ -549,3 +723,34 @@ pub unsafe fn __valgrind_print_backtrace(ptr: *const cty::c_char) {
 #[doc(hidden)]
 #[inline(always)]
 pub unsafe fn __no_op() {}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case::empty("", None)]
+    #[case::no_valgrind("/usr/lib/libfoo.so", None)]
+    #[case::only_core("/usr/libexec/valgrind/vgpreload_core-amd64-linux.so", None)]
+    #[case::memcheck(
+        "/usr/libexec/valgrind/vgpreload_core-amd64-linux.so:\
+         /usr/libexec/valgrind/vgpreload_memcheck-amd64-linux.so",
+        Some(DetectedTool::Memcheck)
+    )]
+    #[case::dhat(
+        "/usr/lib/valgrind/vgpreload_dhat-arm64-linux.so",
+        Some(DetectedTool::Dhat)
+    )]
+    #[case::space_separated(
+        "/usr/lib/libfoo.so /usr/local/libexec/valgrind/vgpreload_drd-x86-freebsd.so",
+        Some(DetectedTool::Drd)
+    )]
+    fn test_detected_tool_from_preload(
+        #[case] preload: &str,
+        #[case] expected: Option<DetectedTool>,
+    ) {
+        assert_eq!(DetectedTool::from_preload(preload), expected);
+    }
+}
@@ -1,13 +1,15 @@
 //! Common structs for `bin_bench` and `lib_bench`
 
+use std::path::PathBuf;
 use std::vec::Vec;
 
 use derive_more::AsRef;
 use iai_callgrind_macros::IntoInner;
 
 use super::{
-    CachegrindMetric, CachegrindMetrics, CallgrindMetrics, DhatMetric, DhatMetrics, Direction,
-    ErrorMetric, EventKind, FlamegraphKind, Limit, ValgrindTool, __internal,
+    CachegrindMetric, CachegrindMetrics, CallgrindMetrics, CollectSystime, DeltaStyle, DhatMetric,
+    DhatMetrics, Direction, ErrorMetric, EventKind, FlamegraphKind, Limit, SmcCheck, Sort,
+    ThreadSelection, TotalAggregation, TruncatePosition, ValgrindTool, Vgdb, __internal,
 };
 use crate::EntryPoint;
 
@@ -36,6 +38,55 @@ use crate::EntryPoint;
 #[derive(Debug, Clone, IntoInner, AsRef)]
 pub struct Bbv(__internal::InternalTool);
 
+/// The outcome of a finished benchmark
+///
+/// The outcomes are available with [`BenchmarkOutcome::all`] in the `teardown` of the
+/// [`crate::library_benchmark_group`], [`crate::binary_benchmark_group`] and [`crate::main`]
+/// macros, which run after the benchmarks have finished. The `teardown` of a group sees the
+/// outcomes of the benchmarks of this group and the `teardown` of the `main!` macro the outcomes of
+/// all benchmarks. Use them to push the results to a custom sink or to assert invariants
+/// programmatically.
+///
+/// # Examples
+///
+/// ```rust
+/// # mod my_parser { pub fn parse(_: &str) -> u64 { 0 } }
+/// use iai_callgrind::{
+///     library_benchmark, library_benchmark_group, main, BenchmarkOutcome, ValgrindTool,
+/// };
+/// use std::hint::black_box;
+///
+/// fn check_outcomes() {
+///     for outcome in BenchmarkOutcome::all() {
+///         if let Some(instructions) = outcome.metric(ValgrindTool::Callgrind, "Ir") {
+///             assert!(
+///                 instructions < 100_000.0,
+///                 "{} needs too many instructions",
+///                 outcome.module_path()
+///             );
+///         }
+///     }
+/// }
+///
+/// #[library_benchmark]
+/// #[bench::short("a=1")]
+/// fn bench_parse(input: &str) -> u64 {
+///     black_box(my_parser::parse(input))
+/// }
+///
+/// library_benchmark_group!(
+///     name = parser;
+///     teardown = check_outcomes();
+///     benchmarks = bench_parse
+/// );
+///
+/// # fn main() {
+/// main!(library_benchmark_groups = parser);
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, AsRef)]
+pub struct BenchmarkOutcome(__internal::InternalBenchmarkOutcome);
+
 /// The configuration for cachegrind
 ///
 /// Can be specified in [`crate::LibraryBenchmarkConfig::tool`] or
@@ -280,6 +331,42 @@ pub struct Memcheck(__internal::InternalTool);
 #[derive(Debug, Clone, Default, IntoInner, AsRef)]
 pub struct OutputFormat(__internal::InternalOutputFormat);
 
+/// A builder for the valgrind core arguments of [`crate::LibraryBenchmarkConfig::valgrind_args`]
+///
+/// The setters of the `ValgrindArgs` validate their values and produce the correct [valgrind core
+/// arguments](https://valgrind.org/docs/manual/manual-core.html#manual-core.options). Setting an
+/// option a second time replaces the previous value. Arguments without a typed setter can still be
+/// passed through with [`ValgrindArgs::arg`] and [`ValgrindArgs::args`].
+///
+/// `ValgrindArgs` can be used everywhere where a list of arguments is expected, for example in
+/// [`crate::LibraryBenchmarkConfig::valgrind_args`], [`crate::BinaryBenchmarkConfig::valgrind_args`]
+/// or [`Callgrind::args`].
+///
+/// # Examples
+///
+/// ```rust
+/// # use iai_callgrind::{library_benchmark, library_benchmark_group};
+/// # #[library_benchmark]
+/// # fn some_func() {}
+/// # library_benchmark_group!(name = some_group; benchmarks = some_func);
+/// use iai_callgrind::{main, LibraryBenchmarkConfig, SmcCheck, ValgrindArgs};
+///
+/// # fn main() {
+/// main!(
+///     config = LibraryBenchmarkConfig::default()
+///         .valgrind_args(
+///             ValgrindArgs::default()
+///                 .max_stackframe(4_000_000)
+///                 .smc_check(SmcCheck::All)
+///                 .arg("--trace-children=no")
+///         );
+///     library_benchmark_groups = some_group
+/// );
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, IntoInner, AsRef)]
+pub struct ValgrindArgs(__internal::InternalRawArgs);
+
 impl Bbv {
     /// Create a new `BBV` configuration with initial command-line arguments
     ///
@@ -346,6 +433,70 @@ impl Default for Bbv {
     }
 }
 
+impl BenchmarkOutcome {
+    /// Return the outcomes of the finished benchmarks passed to the currently running `teardown`
+    ///
+    /// Outside of the `teardown` of a group or the `main!` macro there are no outcomes and the
+    /// returned vector is empty. With `--load-baseline` the `teardown` isn't run at all.
+    ///
+    /// # Panics
+    ///
+    /// If the outcomes passed on by the `iai-callgrind-runner` can't be read or decoded
+    pub fn all() -> Vec<Self> {
+        let Some(path) = std::env::var_os("IAI_CALLGRIND_OUTCOMES") else {
+            return vec![];
+        };
+        let bytes = std::fs::read(&path).unwrap_or_else(|error| {
+            panic!(
+                "The benchmark outcomes at '{}' should be readable: {error}",
+                PathBuf::from(path).display()
+            )
+        });
+        bincode::deserialize::<Vec<__internal::InternalBenchmarkOutcome>>(&bytes)
+            .expect("The benchmark outcomes should be decodable")
+            .into_iter()
+            .map(Self)
+            .collect()
+    }
+
+    /// The name of the benchmark function
+    pub fn function_name(&self) -> &str {
+        &self.0.function_name
+    }
+
+    /// The id of the benchmark, for example `my_id` in `#[bench::my_id(...)]`, if present
+    pub fn id(&self) -> Option<&str> {
+        self.0.id.as_deref()
+    }
+
+    /// Return true if the benchmark has regressed
+    ///
+    /// A regression acknowledged with `expect_regression` doesn't count as regression.
+    pub fn is_regressed(&self) -> bool {
+        self.0.regressed
+    }
+
+    /// Return the new value of the metric `kind` of the `tool` if present
+    ///
+    /// The `kind` is the name of the metric as in the json summary, for example `Ir` for
+    /// `Callgrind` or `TotalBytes` for `DHAT`.
+    pub fn metric(&self, tool: ValgrindTool, kind: &str) -> Option<f64> {
+        self.0.metric(tool, kind).and_then(|metric| metric.new)
+    }
+
+    /// The module path of the benchmark, for example `my_bench::my_group::my_function`
+    pub fn module_path(&self) -> &str {
+        &self.0.module_path
+    }
+
+    /// Return the value of the metric `kind` of the `tool` of the old run or baseline if present
+    ///
+    /// See [`BenchmarkOutcome::metric`] for the names of the metrics.
+    pub fn old_metric(&self, tool: ValgrindTool, kind: &str) -> Option<f64> {
+        self.0.metric(tool, kind).and_then(|metric| metric.old)
+    }
+}
+
 impl Cachegrind {
     /// Create a new `Cachegrind` configuration with initial command-line arguments
     ///
@@ -570,6 +721,26 @@ impl Cachegrind {
         }
         self
     }
+
+    /// Configure how the total of a benchmark is computed
+    ///
+    /// A benchmark can consist of multiple parts, for example the processes of a benchmark run with
+    /// `--trace-children=yes`. Per default, the metrics of all parts are summed up in the total
+    /// ([`TotalAggregation::Sum`]). For parallel workloads, the maximum cost of a single part is
+    /// often more meaningful than the sum, which can be configured with [`TotalAggregation::Max`].
+    /// The total is also the base for the regression checks.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use iai_callgrind::{Cachegrind, TotalAggregation};
+    ///
+    /// let config = Cachegrind::default().aggregate(TotalAggregation::Max);
+    /// ```
+    pub fn aggregate(&mut self, aggregation: TotalAggregation) -> &mut Self {
+        self.0.total_aggregation = Some(aggregation);
+        self
+    }
 }
 
 impl Default for Cachegrind {
@@ -601,6 +772,29 @@ impl Callgrind {
         ))
     }
 
+    /// Create a new `Callgrind` configuration for fast instruction-only measurements
+    ///
+    /// This preset runs callgrind without the cache simulation (`--cache-sim=no`) and shows only
+    /// the instructions ([`EventKind::Ir`]) in the terminal output. Without the cache simulation,
+    /// callgrind runs roughly twice as fast, which is useful for quick local iterations. No
+    /// flamegraphs are created unless configured with [`Callgrind::flamegraph`].
+    ///
+    /// The preset can be overridden from the command-line with `--profile=full` (or
+    /// `IAI_CALLGRIND_PROFILE=full`) which restores the default behavior.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use iai_callgrind::Callgrind;
+    ///
+    /// let config = Callgrind::fast();
+    /// ```
+    pub fn fast() -> Self {
+        let mut this = Self::with_args(["--cache-sim=no"]);
+        this.format([EventKind::Ir]);
+        this
+    }
+
     /// Add command-line arguments to the `Callgrind` configuration
     ///
     /// The command-line arguments are passed directly to the callgrind invocation. Valid arguments
@@ -1011,6 +1205,152 @@ impl Callgrind {
 
         self
     }
+
+    /// Collect the number of system calls and the time spent in them (`--collect-systime`)
+    ///
+    /// With [`CollectSystime::Nsec`], callgrind additionally collects the cpu time spent in the
+    /// system calls ([`EventKind::SysCpuTime`]) with nanosecond resolution. For I/O-heavy
+    /// benchmarks, this separates the time waiting for the kernel ([`EventKind::SysTime`]) from
+    /// the actual cpu work. This is a shortcut for `--collect-systime=nsec` in [`Callgrind::args`].
+    /// The metrics are shown in the terminal output with [`CallgrindMetrics::SystemCalls`] in
+    /// [`Callgrind::format`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use iai_callgrind::{Callgrind, CallgrindMetrics, CollectSystime};
+    ///
+    /// let config = Callgrind::default()
+    ///     .collect_systime(CollectSystime::Nsec)
+    ///     .format([CallgrindMetrics::Default, CallgrindMetrics::SystemCalls]);
+    /// ```
+    pub fn collect_systime(&mut self, value: CollectSystime) -> &mut Self {
+        self.0
+            .raw_args
+            .extend_ignore_flag([format!("collect-systime={value}")]);
+        self
+    }
+
+    /// Select the threads which are included in the total of the benchmark
+    ///
+    /// Per default, the metrics of all threads are summed up in the total. In a benchmark of a
+    /// multi-threaded function, this means the bookkeeping of the main thread (spawning and joining
+    /// the threads, ...) dilutes the metrics of the worker thread you're actually interested in.
+    /// With a [`ThreadSelection`] other than [`ThreadSelection::All`], the metrics of all threads
+    /// are still recorded and shown but only the selected threads are included in the total. The
+    /// total is also the base for the regression checks.
+    ///
+    /// Threads are numbered by callgrind in the order of their creation starting with `1` for the
+    /// main thread. Alternatively, [`ThreadSelection::Function`] selects the threads in which a
+    /// function matching the glob pattern was executed. This option implies
+    /// `--separate-threads=yes`.
+    ///
+    /// Note the metrics of threads spawned in the benchmark function are only collected if the
+    /// [`EntryPoint`] covers the threads, for example with [`EntryPoint::None`] or an
+    /// [`EntryPoint::Custom`] matching the function executed by the thread.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use iai_callgrind::{library_benchmark, library_benchmark_group};
+    /// # #[library_benchmark]
+    /// # fn some_func() {}
+    /// # library_benchmark_group!(name = some_group; benchmarks = some_func);
+    /// use iai_callgrind::{main, Callgrind, EntryPoint, LibraryBenchmarkConfig, ThreadSelection};
+    ///
+    /// # fn main() {
+    /// main!(
+    ///     config = LibraryBenchmarkConfig::default()
+    ///         .tool(Callgrind::default()
+    ///             .entry_point(EntryPoint::None)
+    ///             .measure_threads(ThreadSelection::Only(2))
+    ///         );
+    ///     library_benchmark_groups = some_group
+    /// );
+    /// # }
+    /// ```
+    ///
+    /// Or by the name of the function executed in the worker thread:
+    ///
+    /// ```rust
+    /// # use iai_callgrind::{library_benchmark, library_benchmark_group};
+    /// # #[library_benchmark]
+    /// # fn some_func() {}
+    /// # library_benchmark_group!(name = some_group; benchmarks = some_func);
+    /// use iai_callgrind::{main, Callgrind, EntryPoint, LibraryBenchmarkConfig, ThreadSelection};
+    ///
+    /// # fn main() {
+    /// main!(
+    ///     config = LibraryBenchmarkConfig::default()
+    ///         .tool(Callgrind::default()
+    ///             .entry_point(EntryPoint::None)
+    ///             .measure_threads(ThreadSelection::Function("my_lib::worker".to_owned()))
+    ///         );
+    ///     library_benchmark_groups = some_group
+    /// );
+    /// # }
+    /// ```
+    pub fn measure_threads(&mut self, selection: ThreadSelection) -> &mut Self {
+        self.0.thread_selection = Some(selection);
+        self
+    }
+
+    /// If false, the subprocesses of the benchmark are not measured
+    ///
+    /// Per default, iai-callgrind runs callgrind with `--trace-children=yes`, so the metrics of
+    /// all subprocesses spawned by the benchmark are recorded and included in the total. If a
+    /// benchmark only incidentally spawns an external helper, the metrics of this helper distort
+    /// the total. With `trace_children(false)`, only the benchmark process itself is measured. This
+    /// is a shortcut for `--trace-children=no` in [`Callgrind::args`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use iai_callgrind::{library_benchmark, library_benchmark_group, main};
+    /// # fn my_lib_calling_git() -> u64 { 10 }
+    /// use iai_callgrind::{Callgrind, LibraryBenchmarkConfig};
+    ///
+    /// #[library_benchmark(
+    ///     config = LibraryBenchmarkConfig::default()
+    ///         .tool(Callgrind::default().trace_children(false))
+    /// )]
+    /// fn bench_library() -> u64 {
+    ///     std::hint::black_box(my_lib_calling_git())
+    /// }
+    /// # library_benchmark_group!(name = some_group; benchmarks = bench_library);
+    /// # fn main() {
+    /// # main!(library_benchmark_groups = some_group);
+    /// # }
+    /// ```
+    pub fn trace_children(&mut self, value: bool) -> &mut Self {
+        self.0.raw_args.extend_ignore_flag([format!(
+            "trace-children={}",
+            if value { "yes" } else { "no" }
+        )]);
+        self
+    }
+
+    /// Configure how the total of a benchmark is computed
+    ///
+    /// A benchmark can consist of multiple parts, for example the threads with
+    /// `--separate-threads=yes` (the default). Per default, the metrics of all parts are summed up
+    /// in the total ([`TotalAggregation::Sum`]). For parallel workloads, the maximum cost of a
+    /// single part is often more meaningful than the sum, which can be configured with
+    /// [`TotalAggregation::Max`]. [`TotalAggregation::ExcludeMainThread`] sums up all threads but
+    /// the main thread, which usually only spawns and joins the worker threads. The total is also
+    /// the base for the regression checks. See also [`Callgrind::measure_threads`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use iai_callgrind::{Callgrind, TotalAggregation};
+    ///
+    /// let config = Callgrind::default().aggregate(TotalAggregation::Max);
+    /// ```
+    pub fn aggregate(&mut self, aggregation: TotalAggregation) -> &mut Self {
+        self.0.total_aggregation = Some(aggregation);
+        self
+    }
 }
 
 impl Default for Callgrind {
@@ -1420,6 +1760,26 @@ impl Dhat {
         }
         self
     }
+
+    /// Configure how the total of a benchmark is computed
+    ///
+    /// A benchmark can consist of multiple parts, for example the processes of a benchmark run with
+    /// `--trace-children=yes`. Per default, the metrics of all parts are summed up in the total
+    /// ([`TotalAggregation::Sum`]). For parallel workloads, the maximum cost of a single part is
+    /// often more meaningful than the sum, which can be configured with [`TotalAggregation::Max`].
+    /// The total is also the base for the regression checks.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use iai_callgrind::{Dhat, TotalAggregation};
+    ///
+    /// let config = Dhat::default().aggregate(TotalAggregation::Max);
+    /// ```
+    pub fn aggregate(&mut self, aggregation: TotalAggregation) -> &mut Self {
+        self.0.total_aggregation = Some(aggregation);
+        self
+    }
 }
 
 impl Default for Dhat {
@@ -1488,6 +1848,27 @@ impl Drd {
         self
     }
 
+    /// Collect the errors of `DRD` and report them after all benchmarks
+    ///
+    /// By default, `DRD` runs with `--error-exitcode=201` and a benchmark with errors fails
+    /// immediately. If `fail_on_errors` is configured, the benchmarks with errors don't fail
+    /// immediately but the errors of all benchmarks are reported in a separate section of the
+    /// summary after all benchmarks. If `true`, the benchmark run fails after all benchmarks if
+    /// there were any errors. If `false`, the errors are only reported. An explicitly given
+    /// `--error-exitcode` still takes precedence.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use iai_callgrind::Drd;
+    ///
+    /// let config = Drd::default().fail_on_errors(true);
+    /// ```
+    pub fn fail_on_errors(&mut self, value: bool) -> &mut Self {
+        self.0.fail_on_errors = Some(value);
+        self
+    }
+
     /// Customize the format of the `DRD` output
     ///
     /// See also [`Callgrind::format`] for more details and [`ErrorMetric`] for valid metrics.
@@ -1515,6 +1896,50 @@ impl Drd {
 
         self
     }
+
+    /// Generate a suppression for each error and collect them in a suppression file
+    ///
+    /// This passes `--gen-suppressions=all` to `DRD`. The suppressions printed into the log
+    /// files are collected into the file `drd.<name>.suppressions.supp` next to the log
+    /// files in the benchmark output directory. After reviewing the suppressions, the file can be
+    /// used with [`Drd::suppressions`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use iai_callgrind::Drd;
+    ///
+    /// let config = Drd::default().gen_suppressions(true);
+    /// ```
+    pub fn gen_suppressions(&mut self, value: bool) -> &mut Self {
+        self.0.gen_suppressions = Some(value);
+        self
+    }
+
+    /// Add suppression files to the `DRD` configuration
+    ///
+    /// Relative paths are interpreted relative to the workspace root (the directory of the
+    /// `Cargo.toml` of the workspace), so the suppression files don't depend on the current
+    /// directory of the benchmark. Each path is passed to valgrind as `--suppressions=<path>`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use iai_callgrind::Drd;
+    ///
+    /// let config = Drd::default().suppressions(["benches/drd.supp"]);
+    /// ```
+    pub fn suppressions<I, T>(&mut self, paths: T) -> &mut Self
+    where
+        I: Into<PathBuf>,
+        T: IntoIterator<Item = I>,
+    {
+        self.0
+            .suppressions
+            .get_or_insert_with(Vec::new)
+            .extend(paths.into_iter().map(Into::into));
+        self
+    }
 }
 
 impl Default for Drd {
@@ -1762,6 +2187,27 @@ impl Helgrind {
         self
     }
 
+    /// Collect the errors of `Helgrind` and report them after all benchmarks
+    ///
+    /// By default, `Helgrind` runs with `--error-exitcode=201` and a benchmark with errors fails
+    /// immediately. If `fail_on_errors` is configured, the benchmarks with errors don't fail
+    /// immediately but the errors of all benchmarks are reported in a separate section of the
+    /// summary after all benchmarks. If `true`, the benchmark run fails after all benchmarks if
+    /// there were any errors. If `false`, the errors are only reported. An explicitly given
+    /// `--error-exitcode` still takes precedence.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use iai_callgrind::Helgrind;
+    ///
+    /// let config = Helgrind::default().fail_on_errors(true);
+    /// ```
+    pub fn fail_on_errors(&mut self, value: bool) -> &mut Self {
+        self.0.fail_on_errors = Some(value);
+        self
+    }
+
     /// Customize the format of the `Helgrind` output
     ///
     /// See also [`Callgrind::format`] for more details and [`ErrorMetric`] for valid metrics.
@@ -1789,6 +2235,50 @@ impl Helgrind {
 
         self
     }
+
+    /// Generate a suppression for each error and collect them in a suppression file
+    ///
+    /// This passes `--gen-suppressions=all` to `Helgrind`. The suppressions printed into the log
+    /// files are collected into the file `helgrind.<name>.suppressions.supp` next to the log
+    /// files in the benchmark output directory. After reviewing the suppressions, the file can be
+    /// used with [`Helgrind::suppressions`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use iai_callgrind::Helgrind;
+    ///
+    /// let config = Helgrind::default().gen_suppressions(true);
+    /// ```
+    pub fn gen_suppressions(&mut self, value: bool) -> &mut Self {
+        self.0.gen_suppressions = Some(value);
+        self
+    }
+
+    /// Add suppression files to the `Helgrind` configuration
+    ///
+    /// Relative paths are interpreted relative to the workspace root (the directory of the
+    /// `Cargo.toml` of the workspace), so the suppression files don't depend on the current
+    /// directory of the benchmark. Each path is passed to valgrind as `--suppressions=<path>`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use iai_callgrind::Helgrind;
+    ///
+    /// let config = Helgrind::default().suppressions(["benches/helgrind.supp"]);
+    /// ```
+    pub fn suppressions<I, T>(&mut self, paths: T) -> &mut Self
+    where
+        I: Into<PathBuf>,
+        T: IntoIterator<Item = I>,
+    {
+        self.0
+            .suppressions
+            .get_or_insert_with(Vec::new)
+            .extend(paths.into_iter().map(Into::into));
+        self
+    }
 }
 
 impl Default for Helgrind {
@@ -1932,6 +2422,27 @@ impl Memcheck {
         self
     }
 
+    /// Collect the errors of `Memcheck` and report them after all benchmarks
+    ///
+    /// By default, `Memcheck` runs with `--error-exitcode=201` and a benchmark with errors fails
+    /// immediately. If `fail_on_errors` is configured, the benchmarks with errors don't fail
+    /// immediately but the errors of all benchmarks are reported in a separate section of the
+    /// summary after all benchmarks. If `true`, the benchmark run fails after all benchmarks if
+    /// there were any errors. If `false`, the errors are only reported. An explicitly given
+    /// `--error-exitcode` still takes precedence.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use iai_callgrind::Memcheck;
+    ///
+    /// let config = Memcheck::default().fail_on_errors(true);
+    /// ```
+    pub fn fail_on_errors(&mut self, value: bool) -> &mut Self {
+        self.0.fail_on_errors = Some(value);
+        self
+    }
+
     /// Customize the format of the `Memcheck` output
     ///
     /// See also [`Callgrind::format`] for more details and [`ErrorMetric`] for valid metrics.
@@ -1959,12 +2470,56 @@ impl Memcheck {
 
         self
     }
-}
 
-impl Default for Memcheck {
-    fn default() -> Self {
-        Self(__internal::InternalTool::new(ValgrindTool::Memcheck))
-    }
+    /// Generate a suppression for each error and collect them in a suppression file
+    ///
+    /// This passes `--gen-suppressions=all` to `Memcheck`. The suppressions printed into the log
+    /// files are collected into the file `memcheck.<name>.suppressions.supp` next to the log
+    /// files in the benchmark output directory. After reviewing the suppressions, the file can be
+    /// used with [`Memcheck::suppressions`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use iai_callgrind::Memcheck;
+    ///
+    /// let config = Memcheck::default().gen_suppressions(true);
+    /// ```
+    pub fn gen_suppressions(&mut self, value: bool) -> &mut Self {
+        self.0.gen_suppressions = Some(value);
+        self
+    }
+
+    /// Add suppression files to the `Memcheck` configuration
+    ///
+    /// Relative paths are interpreted relative to the workspace root (the directory of the
+    /// `Cargo.toml` of the workspace), so the suppression files don't depend on the current
+    /// directory of the benchmark. Each path is passed to valgrind as `--suppressions=<path>`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use iai_callgrind::Memcheck;
+    ///
+    /// let config = Memcheck::default().suppressions(["benches/memcheck.supp"]);
+    /// ```
+    pub fn suppressions<I, T>(&mut self, paths: T) -> &mut Self
+    where
+        I: Into<PathBuf>,
+        T: IntoIterator<Item = I>,
+    {
+        self.0
+            .suppressions
+            .get_or_insert_with(Vec::new)
+            .extend(paths.into_iter().map(Into::into));
+        self
+    }
+}
+
+impl Default for Memcheck {
+    fn default() -> Self {
+        Self(__internal::InternalTool::new(ValgrindTool::Memcheck))
+    }
 }
 
 impl OutputFormat {
@@ -1972,7 +2527,9 @@ impl OutputFormat {
     ///
     /// The default is to truncate the description to the size of 50 ascii characters. A `None`
     /// value disables the truncation entirely and a `Some` value will truncate the description to
-    /// the given amount of characters excluding the ellipsis.
+    /// the given amount of characters excluding the ellipsis. Where the description is truncated
+    /// and the ellipsis can be changed with [`OutputFormat::truncate_position`] and
+    /// [`OutputFormat::truncate_ellipsis`].
     ///
     /// To clearify which part of the output is meant by `DESCRIPTION`:
     ///
@@ -2015,6 +2572,48 @@ impl OutputFormat {
         self
     }
 
+    /// Set the ellipsis which replaces the truncated part of the description (Default: `...`)
+    ///
+    /// See also [`OutputFormat::truncate_description`] and [`OutputFormat::truncate_position`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use iai_callgrind::OutputFormat;
+    ///
+    /// let output_format = OutputFormat::default().truncate_ellipsis("…");
+    /// ```
+    pub fn truncate_ellipsis<T>(&mut self, value: T) -> &mut Self
+    where
+        T: Into<String>,
+    {
+        self.0.truncate_ellipsis = Some(value.into());
+        self
+    }
+
+    /// Set the position at which the description is truncated (Default: [`TruncatePosition::End`])
+    ///
+    /// Descriptions which only differ in their tail, like long argument lists, are
+    /// indistinguishable after the default truncation of the end. With [`TruncatePosition::Start`]
+    /// the end of the description is kept and with [`TruncatePosition::Middle`] both, the start
+    /// and the end.
+    ///
+    /// See also [`OutputFormat::truncate_description`] and [`OutputFormat::truncate_ellipsis`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use iai_callgrind::{OutputFormat, TruncatePosition};
+    ///
+    /// let output_format = OutputFormat::default()
+    ///     .truncate_description(Some(20))
+    ///     .truncate_position(TruncatePosition::Middle);
+    /// ```
+    pub fn truncate_position(&mut self, value: TruncatePosition) -> &mut Self {
+        self.0.truncate_position = Some(value);
+        self
+    }
+
     /// Show intermediate metrics from parts, subprocesses, threads, ... (Default: false)
     ///
     /// In callgrind, threads are treated as separate units (similar to subprocesses) and the
@@ -2216,6 +2815,295 @@ impl OutputFormat {
         self
     }
 
+    /// Show the totals of a library benchmark group (Default: false)
+    ///
+    /// After all benchmarks of a `library_benchmark_group!` have run, the primary metrics
+    /// (`Instructions` for callgrind and cachegrind, `Total bytes` for DHAT and `Errors` for the
+    /// error tools) of the benchmarks are summed up and compared with the summed up metrics of the
+    /// old or baseline run. This is useful to track a budget per group. The totals are shown if
+    /// this option is set for any benchmark of the group. The command-line argument
+    /// `--show-group-totals` overrides this value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use iai_callgrind::OutputFormat;
+    ///
+    /// let output_format = OutputFormat::default().show_group_totals(true);
+    /// ```
+    pub fn show_group_totals(&mut self, value: bool) -> &mut Self {
+        self.0.show_group_totals = Some(value);
+        self
+    }
+
+    /// Show the lines of the entry point with the most instructions (Default: false)
+    ///
+    /// Callgrind attributes the costs to source lines (`--dump-line=yes` is the default). With this
+    /// option, the lines of the entry point (the benchmark function by default) with the most
+    /// instructions, including the instructions of the calls from these lines, are shown in the
+    /// terminal output. This is a first hint which line of the benchmark regressed without having
+    /// to run `callgrind_annotate`. The command-line argument `--show-lines` overrides this value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use iai_callgrind::OutputFormat;
+    ///
+    /// let output_format = OutputFormat::default().show_lines(true);
+    /// ```
+    pub fn show_lines(&mut self, value: bool) -> &mut Self {
+        self.0.show_lines = Some(value);
+        self
+    }
+
+    /// Show the valgrind version and the resolved arguments of each tool (Default: false)
+    ///
+    /// The arguments are the final arguments of a tool after merging the arguments of all
+    /// configuration levels and the command-line with the defaults of Iai-Callgrind. Only the
+    /// arguments for the output and log files are not shown. This makes the effect of layered
+    /// configurations auditable. The command-line argument `--show-tool-args` overrides this
+    /// value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use iai_callgrind::OutputFormat;
+    ///
+    /// let output_format = OutputFormat::default().show_tool_args(true);
+    /// ```
+    pub fn show_tool_args(&mut self, value: bool) -> &mut Self {
+        self.0.show_tool_args = Some(value);
+        self
+    }
+
+    /// Show this amount of functions with the most instructions (Default: 0)
+    ///
+    /// The functions of the callgrind output files with the most exclusive (self) instructions are
+    /// shown below the metrics of callgrind together with their inclusive instructions, giving a
+    /// first impression where the instructions are spent without opening the output files in
+    /// `KCachegrind`. A value of `0` disables this option. The command-line argument
+    /// `--show-top-functions` overrides this value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use iai_callgrind::OutputFormat;
+    ///
+    /// let output_format = OutputFormat::default().show_top_functions(5);
+    /// ```
+    pub fn show_top_functions(&mut self, value: usize) -> &mut Self {
+        self.0.show_top_functions = Some(value);
+        self
+    }
+
+    /// Show the page faults and context switches of the benchmark (Default: false)
+    ///
+    /// The major and minor page faults and the voluntary and involuntary context switches of the
+    /// valgrind processes running the benchmark are collected with `getrusage` and shown below the
+    /// metrics of each tool. These numbers are not compared against the old run, but may help to
+    /// explain occasional shifts of the simulated `RAM Hits`. The resource usage is always stored
+    /// in the json summary.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use iai_callgrind::OutputFormat;
+    ///
+    /// let output_format = OutputFormat::default().show_resource_usage(true);
+    /// ```
+    pub fn show_resource_usage(&mut self, value: bool) -> &mut Self {
+        self.0.show_resource_usage = Some(value);
+        self
+    }
+
+    /// Hide the terminal output of benchmarks with unchanged metrics (Default: false)
+    ///
+    /// The output of benchmarks whose metrics are identical to the metrics of the old or baseline
+    /// run is collapsed into a single line with the number of these benchmarks, which is shown
+    /// after all benchmarks have run. Benchmarks without an old run to compare with are always
+    /// shown. The command-line argument `--changed-only` overrides this value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use iai_callgrind::OutputFormat;
+    ///
+    /// let output_format = OutputFormat::default().hide_unchanged(true);
+    /// ```
+    pub fn hide_unchanged(&mut self, value: bool) -> &mut Self {
+        self.0.hide_unchanged = Some(value);
+        self
+    }
+
+    /// The style of the differences between the new and the old metrics (Default: Both)
+    ///
+    /// With [`DeltaStyle::Percent`] only the difference in percent is shown like `(-0.06475%)`,
+    /// with [`DeltaStyle::Factor`] only the factor like `[-1.00065x]`. [`DeltaStyle::Both`] shows
+    /// both. The style applies to the metrics of the benchmark and the comparisons with other
+    /// benchmarks. The command-line argument `--delta-style` overrides this value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use iai_callgrind::{DeltaStyle, OutputFormat};
+    ///
+    /// let output_format = OutputFormat::default().delta_style(DeltaStyle::Factor);
+    /// ```
+    pub fn delta_style(&mut self, value: DeltaStyle) -> &mut Self {
+        self.0.delta_style = Some(value);
+        self
+    }
+
+    /// Show the absolute difference between the new and the old metrics (Default: false)
+    ///
+    /// The absolute difference like `+12345` is shown in addition to the difference in percent
+    /// and/or the factor of the [`DeltaStyle`]. For low-level optimizations, the absolute number of
+    /// instructions is often more meaningful than a percentage. The command-line argument
+    /// `--show-absolute-diff` overrides this value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use iai_callgrind::OutputFormat;
+    ///
+    /// let output_format = OutputFormat::default().show_absolute_diff(true);
+    /// ```
+    pub fn show_absolute_diff(&mut self, value: bool) -> &mut Self {
+        self.0.show_absolute_diff = Some(value);
+        self
+    }
+
+    /// Show the call tree of the functions with the inclusive instructions (Default: false)
+    ///
+    /// Similar to `callgrind_annotate --tree=both`, the called functions are shown indented below
+    /// the calling function with the inclusive instructions and the amount of calls, which is
+    /// useful for a quick structural comparison between benchmark runs. Only functions with at
+    /// least 1% of the total instructions are shown in the terminal but the complete call tree is
+    /// always written next to the callgrind output files. This option has only an effect on
+    /// callgrind. The command-line argument `--show-call-tree` overrides this value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use iai_callgrind::OutputFormat;
+    ///
+    /// let output_format = OutputFormat::default().show_call_tree(true);
+    /// ```
+    pub fn show_call_tree(&mut self, value: bool) -> &mut Self {
+        self.0.show_call_tree = Some(value);
+        self
+    }
+
+    /// The maximum number of fractional-part digits of the differences (Default: 5)
+    ///
+    /// The differences in percent and the factors are shown with at most this amount of digits
+    /// after the decimal point, for example `(-0.06%) [-1.00x]` with a precision of `2` instead of
+    /// `(-0.06475%) [-1.00065x]`. The higher the integer-part of a difference, the lower the number
+    /// of fractional-part digits, so a precision greater than `5` has no effect. The
+    /// command-line argument `--precision` overrides this value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use iai_callgrind::OutputFormat;
+    ///
+    /// let output_format = OutputFormat::default().precision(2);
+    /// ```
+    pub fn precision(&mut self, value: usize) -> &mut Self {
+        self.0.precision = Some(value);
+        self
+    }
+
+    /// The thresholds in percent at which the differences change the color (Default: 0, 0)
+    ///
+    /// Differences within the `neutral` threshold (inclusive) are not colored. Regressions above
+    /// the `neutral` threshold up to the `warning` threshold (inclusive) are colored yellow and
+    /// regressions above the `warning` threshold red. Improvements above the `neutral` threshold
+    /// are colored green. Both thresholds are converted to their absolute value and a `warning`
+    /// threshold below the `neutral` threshold is raised to the `neutral` threshold. The default
+    /// colors every regression red and every improvement green. The command-line argument
+    /// `--color-thresholds` overrides this value.
+    ///
+    /// # Examples
+    ///
+    /// Changes up to 1% are not colored, regressions up to 5% are yellow and regressions above 5%
+    /// are red:
+    ///
+    /// ```rust
+    /// use iai_callgrind::OutputFormat;
+    ///
+    /// let output_format = OutputFormat::default().color_thresholds(1.0, 5.0);
+    /// ```
+    pub fn color_thresholds(&mut self, neutral: f64, warning: f64) -> &mut Self {
+        self.0.color_thresholds = Some(__internal::InternalColorThresholds {
+            neutral: neutral.abs(),
+            warning: warning.abs().max(neutral.abs()),
+        });
+        self
+    }
+
+    /// Restrict the terminal output to ASCII characters (Default: false)
+    ///
+    /// The decorations of the terminal output like the grid are ASCII anyway, but the
+    /// descriptions of the benchmarks, the ellipsis of truncated descriptions or file names may
+    /// contain non-ASCII characters. If `true`, the unicode ellipsis `…` is replaced with `...`
+    /// and all other non-ASCII characters with `?`. This is useful in environments or with log
+    /// processors which don't handle unicode characters well. The command-line argument `--ascii`
+    /// overrides this value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use iai_callgrind::OutputFormat;
+    ///
+    /// let output_format = OutputFormat::default().ascii(true);
+    /// ```
+    pub fn ascii(&mut self, value: bool) -> &mut Self {
+        self.0.ascii = Some(value);
+        self
+    }
+
+    /// The order of the benchmarks in the summary of the regressions (Default: Declaration)
+    ///
+    /// The benchmarks are printed while they are running, so only the summary of the regressions
+    /// after all benchmarks can be sorted. With [`Sort::DeltaDesc`], the benchmarks with the worst
+    /// regression are shown first. Only the `OutputFormat` of the [`crate::main`] macro is
+    /// considered. The command-line argument `--sort-by` overrides this value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use iai_callgrind::{OutputFormat, Sort};
+    ///
+    /// let output_format = OutputFormat::default().sort_by(Sort::DeltaDesc);
+    /// ```
+    pub fn sort_by(&mut self, value: Sort) -> &mut Self {
+        self.0.sort_by = Some(value);
+        self
+    }
+
+    /// Subtract the overhead of the benchmark harness from the callgrind metrics (Default: false)
+    ///
+    /// Even with the default entry point, the metrics of a library benchmark include a small
+    /// constant cost of the benchmark harness, for example the call of the benchmark function
+    /// and the `black_box` of its return value. This overhead is measured once per benchmark run
+    /// with an empty benchmark function and, if this option is enabled, subtracted from the
+    /// metrics of the main process (new and old). This makes the metrics of tiny functions
+    /// comparable across versions of the benchmark harness. Only library benchmarks with callgrind
+    /// are affected and there's no calibration with `--load-baseline`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use iai_callgrind::OutputFormat;
+    ///
+    /// let output_format = OutputFormat::default().subtract_overhead(true);
+    /// ```
+    pub fn subtract_overhead(&mut self, value: bool) -> &mut Self {
+        self.0.subtract_overhead = Some(value);
+        self
+    }
+
     /// Shows changes only when they are above the `tolerance` level
     ///
     /// Changes whose percentage is below the specified tolerance are not marked as changes.
@@ -2245,3 +3133,270 @@ impl OutputFormat {
         self
     }
 }
+
+impl ValgrindArgs {
+    /// The maximum size of the redzones of `--redzone-size` and `--core-redzone-size`
+    const MAX_REDZONE_SIZE: usize = 4096;
+
+    /// Add a raw valgrind argument which is passed through as is
+    ///
+    /// The leading `--` is optional.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use iai_callgrind::ValgrindArgs;
+    ///
+    /// let args = ValgrindArgs::default().arg("--trace-children=no").clone();
+    /// assert_eq!(args.into_iter().collect::<Vec<_>>(), ["--trace-children=no"]);
+    /// ```
+    pub fn arg<T>(&mut self, arg: T) -> &mut Self
+    where
+        T: AsRef<str>,
+    {
+        self.0.extend_ignore_flag([arg]);
+        self
+    }
+
+    /// Add multiple raw valgrind arguments which are passed through as they are
+    ///
+    /// See also [`ValgrindArgs::arg`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use iai_callgrind::ValgrindArgs;
+    ///
+    /// let args = ValgrindArgs::default().args(["--trace-children=no", "num-callers=30"]).clone();
+    /// ```
+    pub fn args<I, T>(&mut self, args: T) -> &mut Self
+    where
+        I: AsRef<str>,
+        T: IntoIterator<Item = I>,
+    {
+        self.0.extend_ignore_flag(args);
+        self
+    }
+
+    /// Set the minimum size of the redzones of the heap blocks of the valgrind core
+    /// (`--core-redzone-size`)
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `size` is greater than 4096 bytes
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use iai_callgrind::ValgrindArgs;
+    ///
+    /// let args = ValgrindArgs::default().core_redzone_size(32).clone();
+    /// ```
+    pub fn core_redzone_size(&mut self, size: usize) -> &mut Self {
+        self.set("core-redzone-size", Self::validate_redzone_size(size))
+    }
+
+    /// Set the exit code valgrind returns if errors were detected (`--error-exitcode`)
+    ///
+    /// A value of `0` disables the special exit code.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use iai_callgrind::ValgrindArgs;
+    ///
+    /// let args = ValgrindArgs::default().error_exitcode(42).clone();
+    /// assert_eq!(args.into_iter().collect::<Vec<_>>(), ["--error-exitcode=42"]);
+    /// ```
+    pub fn error_exitcode(&mut self, code: u8) -> &mut Self {
+        self.set("error-exitcode", code)
+    }
+
+    /// Return an iterator over the valgrind arguments
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use iai_callgrind::ValgrindArgs;
+    ///
+    /// let args = ValgrindArgs::default().error_exitcode(42).clone();
+    /// assert_eq!(args.iter().collect::<Vec<_>>(), ["--error-exitcode=42"]);
+    /// ```
+    pub fn iter(&self) -> std::slice::Iter<'_, String> {
+        self.0 .0.iter()
+    }
+
+    /// Return an iterator over the valgrind arguments which allows modifying each argument
+    ///
+    /// Note that iterating over a `&mut ValgrindArgs` returns copies of the arguments, so the
+    /// `ValgrindArgs` builder can be used without `clone()` wherever an `IntoIterator` of
+    /// arguments is expected.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use iai_callgrind::ValgrindArgs;
+    ///
+    /// let mut args = ValgrindArgs::default().error_exitcode(42).clone();
+    /// for arg in args.iter_mut() {
+    ///     arg.push('1');
+    /// }
+    /// assert_eq!(args.iter().collect::<Vec<_>>(), ["--error-exitcode=421"]);
+    /// ```
+    pub fn iter_mut(&mut self) -> std::slice::IterMut<'_, String> {
+        self.0 .0.iter_mut()
+    }
+
+    /// Set the maximum size of a stack frame (`--max-stackframe`)
+    ///
+    /// Valgrind assumes a stack switch if the stack pointer moves by more than this amount of
+    /// bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use iai_callgrind::ValgrindArgs;
+    ///
+    /// let args = ValgrindArgs::default().max_stackframe(4_000_000).clone();
+    /// ```
+    pub fn max_stackframe(&mut self, size: u64) -> &mut Self {
+        self.set("max-stackframe", size)
+    }
+
+    /// Set the minimum size of the redzones of the heap blocks of the tool (`--redzone-size`)
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `size` is greater than 4096 bytes
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use iai_callgrind::ValgrindArgs;
+    ///
+    /// let args = ValgrindArgs::default().redzone_size(64).clone();
+    /// ```
+    pub fn redzone_size(&mut self, size: usize) -> &mut Self {
+        self.set("redzone-size", Self::validate_redzone_size(size))
+    }
+
+    /// Set the detection of self-modifying code (`--smc-check`)
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use iai_callgrind::{SmcCheck, ValgrindArgs};
+    ///
+    /// let args = ValgrindArgs::default().smc_check(SmcCheck::AllNonFile).clone();
+    /// assert_eq!(args.into_iter().collect::<Vec<_>>(), ["--smc-check=all-non-file"]);
+    /// ```
+    pub fn smc_check(&mut self, value: SmcCheck) -> &mut Self {
+        self.set("smc-check", value)
+    }
+
+    /// If true, prefix the messages of valgrind with the elapsed time (`--time-stamp`)
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use iai_callgrind::ValgrindArgs;
+    ///
+    /// let args = ValgrindArgs::default().time_stamp(true).clone();
+    /// assert_eq!(args.into_iter().collect::<Vec<_>>(), ["--time-stamp=yes"]);
+    /// ```
+    pub fn time_stamp(&mut self, value: bool) -> &mut Self {
+        self.set("time-stamp", if value { "yes" } else { "no" })
+    }
+
+    /// Enable or disable the gdbserver of valgrind (`--vgdb`)
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use iai_callgrind::{ValgrindArgs, Vgdb};
+    ///
+    /// let args = ValgrindArgs::default().vgdb(Vgdb::Full).clone();
+    /// ```
+    pub fn vgdb(&mut self, value: Vgdb) -> &mut Self {
+        self.set("vgdb", value)
+    }
+
+    /// Wait for a connection of gdb after this number of errors (`--vgdb-error`)
+    ///
+    /// A value of `0` waits for gdb before the program starts.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use iai_callgrind::{ValgrindArgs, Vgdb};
+    ///
+    /// let args = ValgrindArgs::default().vgdb(Vgdb::Yes).vgdb_error(0).clone();
+    /// ```
+    pub fn vgdb_error(&mut self, num_errors: u64) -> &mut Self {
+        self.set("vgdb-error", num_errors)
+    }
+
+    /// Set the prefix of the FIFOs used for the communication between gdb and valgrind
+    /// (`--vgdb-prefix`)
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use iai_callgrind::ValgrindArgs;
+    ///
+    /// let args = ValgrindArgs::default().vgdb_prefix("/tmp/vgdb").clone();
+    /// ```
+    pub fn vgdb_prefix<T>(&mut self, prefix: T) -> &mut Self
+    where
+        T: Into<PathBuf>,
+    {
+        self.set("vgdb-prefix", prefix.into().display())
+    }
+
+    /// Set the option `--name` to the `value` replacing any previous value of this option
+    fn set<T>(&mut self, name: &str, value: T) -> &mut Self
+    where
+        T: std::fmt::Display,
+    {
+        let prefix = format!("--{name}=");
+        self.0 .0.retain(|arg| !arg.starts_with(&prefix));
+        self.0 .0.push(format!("{prefix}{value}"));
+        self
+    }
+
+    fn validate_redzone_size(size: usize) -> usize {
+        assert!(
+            size <= Self::MAX_REDZONE_SIZE,
+            "The redzone size must be in the range 0..={} bytes but was: {size}",
+            Self::MAX_REDZONE_SIZE
+        );
+        size
+    }
+}
+
+impl IntoIterator for ValgrindArgs {
+    type Item = String;
+    type IntoIter = std::vec::IntoIter<String>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0 .0.into_iter()
+    }
+}
+
+impl IntoIterator for &mut ValgrindArgs {
+    type Item = String;
+    type IntoIter = std::vec::IntoIter<String>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0 .0.clone().into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a ValgrindArgs {
+    type Item = &'a String;
+    type IntoIter = std::slice::Iter<'a, String>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0 .0.iter()
+    }
+}
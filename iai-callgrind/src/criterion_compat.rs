@@ -0,0 +1,234 @@
+//! A compatibility layer to ease the migration of [criterion](https://docs.rs/criterion) benchmarks
+//!
+//! This module provides [`criterion_group`] and [`criterion_main`] macros shaped like the
+//! criterion macros of the same name together with a minimal [`Criterion`] and [`Bencher`]. The
+//! benchmark functions are mapped onto library benchmarks, so large criterion suites can be moved
+//! over incrementally, without having to rewrite every benchmark at once.
+//!
+//! Usually, it's enough to replace the `use criterion::...` statements with
+//! `use iai_callgrind::criterion_compat::...`:
+//!
+//! ```rust
+//! # macro_rules! env { ($m:tt) => {{ "/some/path" }} }
+//! use iai_callgrind::criterion_compat::{black_box, criterion_group, criterion_main, Criterion};
+//!
+//! fn fibonacci(n: u64) -> u64 {
+//!     match n {
+//!         0 | 1 => 1,
+//!         n => fibonacci(n - 1) + fibonacci(n - 2),
+//!     }
+//! }
+//!
+//! fn bench_fibonacci(c: &mut Criterion) {
+//!     c.bench_function("fib 10", |b| b.iter(|| fibonacci(black_box(10))));
+//!     c.bench_function("fib 20", |b| b.iter(|| fibonacci(black_box(20))));
+//! }
+//!
+//! criterion_group!(benches, bench_fibonacci);
+//! # fn main() {
+//! criterion_main!(benches);
+//! # }
+//! ```
+//!
+//! Each `criterion_group!` is a `library_benchmark_group!` and each target function is a library
+//! benchmark. Every call of [`Criterion::bench_function`] within a target function is run as a
+//! separate benchmark. The benchmark id is derived from the id of the criterion benchmark: All
+//! characters which are not ascii alphanumeric or `_` are replaced with `_`. For example, the `fib
+//! 20` benchmark above is shown as `my_bench::benches::bench_fibonacci fib_20`. If the id is empty
+//! or the same id was already used within the target function, the position of the call (starting
+//! with `0`) is appended to the id, like in `fib_20_1`.
+//!
+//! Only the routine passed to [`Bencher::iter`] is measured. In contrast to criterion, the routine
+//! is executed exactly once. Everything else in the target function and in the closure of
+//! [`Criterion::bench_function`] is not attributed to the benchmark metrics and can be used for
+//! setup code. Criterion's configuration, measurement and plotting features are not available.
+//! Use the [`LibraryBenchmarkConfig`](crate::LibraryBenchmarkConfig) of the `config` argument of
+//! the [`criterion_main`] macro instead.
+
+pub use std::hint::black_box;
+
+pub use crate::{criterion_group, criterion_main};
+
+/// The mode of a [`Criterion`] instance
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    /// Collect the ids of the benchmarks without running them
+    Count,
+    /// Run the benchmark at this index
+    Run(usize),
+}
+
+/// The timer-free replacement of criterion's `Bencher`
+///
+/// It's passed to the closure of [`Criterion::bench_function`].
+#[derive(Debug)]
+pub struct Bencher {
+    _private: (),
+}
+
+/// A minimal replacement of criterion's `Criterion` struct
+///
+/// The benchmarks are registered with [`Criterion::bench_function`].
+#[derive(Debug)]
+pub struct Criterion {
+    count: usize,
+    ids: Vec<String>,
+    mode: Mode,
+}
+
+impl Bencher {
+    /// Run and measure the `routine` exactly once
+    ///
+    /// The return value of the `routine` is passed through [`black_box`], so the compiler can't
+    /// optimize away the benchmarked code.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use iai_callgrind::criterion_compat::{black_box, Criterion};
+    ///
+    /// fn bench_sum(c: &mut Criterion) {
+    ///     c.bench_function("sum", |b| b.iter(|| (0..black_box(100u64)).sum::<u64>()));
+    /// }
+    /// ```
+    #[allow(clippy::iter_not_returning_iterator)]
+    pub fn iter<O, R>(&mut self, routine: R)
+    where
+        R: FnMut() -> O,
+    {
+        black_box(__iai_callgrind_wrapper_mod::run_routine(routine));
+    }
+}
+
+impl Criterion {
+    /// Register a benchmark with an id
+    ///
+    /// The benchmark id is the `id` with all characters which are not ascii alphanumeric or `_`
+    /// replaced with `_`. An empty or already used id gets the position of this call within the
+    /// target function appended.
+    pub fn bench_function<F>(&mut self, id: &str, mut f: F) -> &mut Self
+    where
+        F: FnMut(&mut Bencher),
+    {
+        match self.mode {
+            Mode::Count => {
+                let id = sanitize_id(id, self.count, &self.ids);
+                self.ids.push(id);
+            }
+            Mode::Run(index) if index == self.count => f(&mut Bencher { _private: () }),
+            Mode::Run(_) => {}
+        }
+        self.count += 1;
+        self
+    }
+}
+
+impl Default for Criterion {
+    fn default() -> Self {
+        Self {
+            count: 0,
+            ids: Vec::new(),
+            mode: Mode::Count,
+        }
+    }
+}
+
+/// Only used internally to attribute the costs of the routine to the benchmark
+///
+/// The module name has to match the default entry point of library benchmarks.
+mod __iai_callgrind_wrapper_mod {
+    #[inline(never)]
+    pub(super) fn run_routine<O, R>(mut routine: R) -> O
+    where
+        R: FnMut() -> O,
+    {
+        #[cfg(feature = "cachegrind")]
+        crate::client_requests::cachegrind::start_instrumentation();
+        let output = routine();
+        #[cfg(feature = "cachegrind")]
+        crate::client_requests::cachegrind::stop_instrumentation();
+        output
+    }
+}
+
+/// Convert the criterion `id` of the benchmark at `index` into a valid benchmark id
+///
+/// The `index` is appended if the converted id is empty or already in `ids`.
+fn sanitize_id(id: &str, index: usize, ids: &[String]) -> String {
+    let sanitized = id
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect::<String>();
+
+    if sanitized.is_empty() {
+        format!("bench_{index}")
+    } else if ids.contains(&sanitized) {
+        format!("{sanitized}_{index}")
+    } else {
+        sanitized
+    }
+}
+
+/// Used internally by the [`criterion_group`] macro to run a target function
+///
+/// If `index` is `None`, the benchmarks of the `target` are not run but the ids of the benchmarks
+/// are returned. Otherwise, only the benchmark at the `index` is run and nothing is returned.
+#[doc(hidden)]
+pub fn __run(
+    target: fn(&mut Criterion),
+    mut criterion: Criterion,
+    index: Option<usize>,
+) -> Vec<String> {
+    criterion.count = 0;
+    criterion.ids.clear();
+    criterion.mode = index.map_or(Mode::Count, Mode::Run);
+    target(&mut criterion);
+
+    criterion.ids
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    fn bench_three(c: &mut Criterion) {
+        c.bench_function("fib 10", |b| b.iter(|| 10));
+        c.bench_function("fib-10", |b| b.iter(|| 10));
+        c.bench_function("", |b| b.iter(|| 10));
+    }
+
+    #[rstest]
+    #[case::simple("fib_10", 0, &[], "fib_10")]
+    #[case::space("fib 10", 0, &[], "fib_10")]
+    #[case::special_chars("fib(10)/µs", 0, &[], "fib_10___s")]
+    #[case::empty("", 2, &[], "bench_2")]
+    #[case::duplicate("fib 10", 1, &["fib_10"], "fib_10_1")]
+    fn test_sanitize_id(
+        #[case] id: &str,
+        #[case] index: usize,
+        #[case] ids: &[&str],
+        #[case] expected: &str,
+    ) {
+        let ids = ids.iter().map(|s| (*s).to_owned()).collect::<Vec<_>>();
+        assert_eq!(sanitize_id(id, index, &ids), expected);
+    }
+
+    #[test]
+    fn test_run_when_count() {
+        assert_eq!(
+            __run(bench_three, Criterion::default(), None),
+            vec![
+                "fib_10".to_owned(),
+                "fib_10_1".to_owned(),
+                "bench_2".to_owned()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_run_when_index() {
+        assert!(__run(bench_three, Criterion::default(), Some(1)).is_empty());
+    }
+}
@@ -399,32 +399,37 @@ pub mod client_requests;
 #[cfg(feature = "default")]
 mod common;
 #[cfg(feature = "default")]
+pub mod criterion_compat;
+#[cfg(feature = "default")]
 mod lib_bench;
 #[cfg(feature = "default")]
 mod macros;
 #[cfg(feature = "default")]
+pub mod paths;
+#[cfg(feature = "default")]
 pub use bin_bench::{
     Bench, BenchmarkId, BinaryBenchmark, BinaryBenchmarkConfig, BinaryBenchmarkGroup, Command,
-    Delay, Sandbox,
+    Delay, InputScript, Sandbox,
 };
 #[cfg(feature = "default")]
 pub use bincode;
 #[cfg(feature = "default")]
 pub use common::{
-    Bbv, Cachegrind, Callgrind, Dhat, Drd, FlamegraphConfig, Helgrind, Massif, Memcheck,
-    OutputFormat,
+    Bbv, BenchmarkOutcome, Cachegrind, Callgrind, Dhat, Drd, FlamegraphConfig, Helgrind, Massif,
+    Memcheck, OutputFormat, ValgrindArgs,
 };
 #[cfg(feature = "client_requests_defs")]
 pub use cty;
 #[cfg(feature = "default")]
-pub use iai_callgrind_macros::{binary_benchmark, library_benchmark};
+pub use iai_callgrind_macros::{binary_benchmark, library_benchmark, library_benchmark_main};
 // Only add enums here. Do not re-export structs from the runner api directly. See the
 // documentation in `__internal::mod` for more details.
 #[cfg(feature = "default")]
 pub use iai_callgrind_runner::api::{
-    CachegrindMetric, CachegrindMetrics, CallgrindMetrics, DelayKind, DhatMetric, DhatMetrics,
-    Direction, EntryPoint, ErrorMetric, EventKind, ExitWith, FlamegraphKind, Limit, Pipe, Stdin,
-    Stdio, ValgrindTool,
+    CachegrindMetric, CachegrindMetrics, CallgrindMetrics, CollectSystime, DelayKind, DeltaStyle,
+    DhatMetric, DhatMetrics, Direction, EntryPoint, ErrorMetric, EventKind, ExitWith,
+    FlamegraphKind, Limit, PathResolution, Pipe, Signal, SmcCheck, Sort, Stdin, Stdio,
+    ThreadSelection, TotalAggregation, TruncatePosition, ValgrindTool, Vgdb, VgdbMode,
 };
 #[cfg(feature = "default")]
-pub use lib_bench::LibraryBenchmarkConfig;
+pub use lib_bench::{BenchContext, LibraryBenchmarkConfig};
@@ -2,10 +2,65 @@ use std::ffi::OsString;
 
 use derive_more::AsRef;
 use iai_callgrind_macros::IntoInner;
-use iai_callgrind_runner::api::ValgrindTool;
+use iai_callgrind_runner::api::{ValgrindTool, VgdbMode};
 
 use crate::__internal;
 
+/// The context of a library benchmark passed to the `setup` and `teardown` functions
+///
+/// The `setup` and `teardown` functions receive the `BenchContext` as first argument if the
+/// `#[library_benchmark]` attribute has the `context = true` parameter. This is useful for example
+/// to create temporary files which are named after the benchmark without having to repeat the id
+/// in the arguments.
+///
+/// # Examples
+///
+/// ```rust
+/// use iai_callgrind::{library_benchmark, library_benchmark_group, main, BenchContext};
+/// use std::hint::black_box;
+///
+/// fn setup_file(context: BenchContext, size: usize) -> std::path::PathBuf {
+///     let path = std::env::temp_dir().join(format!(
+///         "{}.{}.{}",
+///         context.group,
+///         context.function,
+///         context.id.unwrap_or("default")
+///     ));
+///     std::fs::write(&path, vec![b'a'; size]).unwrap();
+///     path
+/// }
+///
+/// fn teardown_file(context: BenchContext, path: std::path::PathBuf) {
+///     std::fs::remove_file(path).unwrap();
+/// }
+///
+/// #[library_benchmark(context = true, setup = setup_file, teardown = teardown_file)]
+/// #[bench::small(10)]
+/// #[bench::big(1000)]
+/// fn bench_read(path: std::path::PathBuf) -> std::path::PathBuf {
+///     black_box(std::fs::read(&path).unwrap());
+///     path
+/// }
+///
+/// library_benchmark_group!(name = my_group; benchmarks = bench_read);
+/// # fn main() {
+/// main!(library_benchmark_groups = my_group);
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BenchContext {
+    /// The name of the benchmark function annotated with `#[library_benchmark]`
+    pub function: &'static str,
+    /// The name of the `library_benchmark_group!`
+    pub group: &'static str,
+    /// The id of the `#[bench]` or `#[benches]` attribute
+    ///
+    /// The id is `None` if the benchmark function has no `#[bench]` or `#[benches]` attribute.
+    /// The ids of the `#[benches]` attribute have the index of the benchmark appended like in
+    /// `my_id_0`.
+    pub id: Option<&'static str>,
+}
+
 /// The main configuration of a library benchmark.
 ///
 /// # Examples
@@ -106,6 +161,9 @@ impl LibraryBenchmarkConfig {
     /// arguments](https://valgrind.org/docs/manual/manual-core.html#manual-core.options) are
     /// allowed.
     ///
+    /// Instead of plain strings, the arguments can be built with the validated setters of
+    /// [`crate::ValgrindArgs`].
+    ///
     /// These arguments can be overwritten by tool specific arguments for example with
     /// [`crate::Callgrind::args`]
     ///
@@ -425,4 +483,159 @@ impl LibraryBenchmarkConfig {
         self.0.output_format = Some(output_format.into());
         self
     }
+
+    /// Configure benchmarks to run in a [`crate::Sandbox`] (Default: false)
+    ///
+    /// If specified, we create a temporary directory with the fixtures of the [`crate::Sandbox`] in
+    /// which the benchmark process is run. The `setup` and `teardown` functions of the
+    /// `#[library_benchmark]` (`#[bench]`, `#[benches]`) are part of the benchmark process, so they
+    /// are run in the temporary directory, too. Library code which reads or writes files relative
+    /// to the current directory gets a reproducible working directory this way. See also
+    /// [`crate::BinaryBenchmarkConfig::sandbox`].
+    ///
+    /// Note the `setup` and `teardown` functions of the `main!` and the `library_benchmark_group!`
+    /// macros run in their own process and are not run in the sandbox.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use iai_callgrind::{library_benchmark, library_benchmark_group};
+    /// # #[library_benchmark]
+    /// # fn some_func() {}
+    /// # library_benchmark_group!(
+    /// #    name = some_group;
+    /// #    benchmarks = some_func
+    /// # );
+    /// use iai_callgrind::{main, LibraryBenchmarkConfig, Sandbox};
+    ///
+    /// # fn main() {
+    /// main!(
+    ///     config = LibraryBenchmarkConfig::default()
+    ///         .sandbox(Sandbox::new(true).fixtures(["benches/fixtures"]));
+    ///     library_benchmark_groups = some_group
+    /// );
+    /// # }
+    /// ```
+    pub fn sandbox<T>(&mut self, sandbox: T) -> &mut Self
+    where
+        T: Into<__internal::InternalSandbox>,
+    {
+        self.0.sandbox = Some(sandbox.into());
+        self
+    }
+
+    /// Add a named profile which can be selected with `--profile=<name>`
+    ///
+    /// A profile is a [`LibraryBenchmarkConfig`] (the tools, valgrind arguments, flamegraph and
+    /// regression settings, etc.) which is applied on top of this configuration if selected with
+    /// the command-line argument `--profile=<name>` (or the environment variable
+    /// `IAI_CALLGRIND_PROFILE`). This way the same benchmarks can be run for example with a quick
+    /// configuration locally and with a memory and full configuration in the CI without changing
+    /// the benchmark code. Profiles are only considered in the configuration of the `main!` macro
+    /// and a profile with the same name in the `iai-callgrind.json` file in the workspace root
+    /// takes precedence. The names `fast` and `full` are reserved for the builtin profiles of
+    /// `--profile`.
+    ///
+    /// # Examples
+    ///
+    /// Run the benchmarks with `cargo bench -- --profile=memory` to run DHAT and Massif in
+    /// addition to callgrind:
+    ///
+    /// ```rust
+    /// # use iai_callgrind::{library_benchmark, library_benchmark_group};
+    /// # #[library_benchmark]
+    /// # fn some_func() {}
+    /// # library_benchmark_group!(
+    /// #    name = some_group;
+    /// #    benchmarks = some_func
+    /// # );
+    /// use iai_callgrind::{main, Dhat, LibraryBenchmarkConfig, Massif};
+    ///
+    /// # fn main() {
+    /// main!(
+    ///     config = LibraryBenchmarkConfig::default()
+    ///         .profile(
+    ///             "memory",
+    ///             LibraryBenchmarkConfig::default()
+    ///                 .tool(Dhat::default())
+    ///                 .tool(Massif::default())
+    ///         );
+    ///     library_benchmark_groups = some_group
+    /// );
+    /// # }
+    /// ```
+    pub fn profile<T, U>(&mut self, name: T, config: U) -> &mut Self
+    where
+        T: Into<String>,
+        U: Into<__internal::InternalLibraryBenchmarkConfig>,
+    {
+        self.0.profiles.push((name.into(), config.into()));
+        self
+    }
+
+    /// Add tags to the benchmarks
+    ///
+    /// Tags don't change how the benchmarks are run. They are meant to categorize the benchmarks
+    /// for other tools and are part of the json output of `--list`. The tags of all configuration
+    /// levels are collected, so a benchmark has the tags of the `main!` macro, its group and its
+    /// own configuration. Duplicate tags are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use iai_callgrind::{library_benchmark, library_benchmark_group};
+    /// # #[library_benchmark]
+    /// # fn some_func() {}
+    /// # library_benchmark_group!(
+    /// #    name = some_group;
+    /// #    benchmarks = some_func
+    /// # );
+    /// use iai_callgrind::{main, LibraryBenchmarkConfig};
+    ///
+    /// # fn main() {
+    /// main!(
+    ///     config = LibraryBenchmarkConfig::default().tags(["slow", "io"]);
+    ///     library_benchmark_groups = some_group
+    /// );
+    /// # }
+    /// ```
+    pub fn tags<I, T>(&mut self, tags: I) -> &mut Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        self.0.tags.extend(tags.into_iter().map(Into::into));
+        self
+    }
+
+    /// Run the benchmarks under the gdbserver of valgrind to debug them with gdb
+    ///
+    /// Only the default tool is run with the [`crate::VgdbMode`] and the command to attach gdb is
+    /// printed to the terminal. The output of the benchmarks is not parsed or compared. This is the
+    /// same as the command-line argument `--vgdb`, which takes precedence. Usually, this option is
+    /// best used in the configuration of a single benchmark.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use iai_callgrind::{library_benchmark, library_benchmark_group};
+    /// # #[library_benchmark]
+    /// # fn some_func() {}
+    /// # library_benchmark_group!(
+    /// #    name = some_group;
+    /// #    benchmarks = some_func
+    /// # );
+    /// use iai_callgrind::{main, LibraryBenchmarkConfig, VgdbMode};
+    ///
+    /// # fn main() {
+    /// main!(
+    ///     config = LibraryBenchmarkConfig::default().vgdb(VgdbMode::FullAt(0));
+    ///     library_benchmark_groups = some_group
+    /// );
+    /// # }
+    /// ```
+    pub fn vgdb(&mut self, mode: VgdbMode) -> &mut Self {
+        self.0.vgdb = Some(mode);
+        self
+    }
 }
@@ -105,10 +105,26 @@ macro_rules! binary_benchmark_attribute {
 ///
 /// * __`config`__ (optional): Optionally specify a [`crate::LibraryBenchmarkConfig`] valid for all
 ///   benchmark groups
+/// * __`metadata`__ (optional): A list of `(key, value)` string pairs like `metadata = [("ci_job",
+///   env!("CI_JOB_ID"))]` which is stored in the `metadata` of the json summaries. Use it to
+///   slice the results by arbitrary dimensions like feature flags or the version of a dataset.
+///   Metadata given with `--metadata=KEY=VALUE` on the command-line takes precedence.
+/// * __`args_filter`__ (optional): A function `fn(Vec<OsString>) -> Vec<OsString>` which receives
+///   the command-line arguments of the benchmark binary (`cargo bench -- ARGS`) before they are
+///   parsed by the runner. Use it to translate project-specific flags or add defaults on top of
+///   the built-in command-line arguments. The returned arguments have to be valid unicode.
 /// * __`setup`__ (optional): A setup function or any valid expression which is run before all
 ///   benchmarks
 /// * __`teardown`__ (optional): A setup function or any valid expression which is run after all
-///   benchmarks
+///   benchmarks. The outcomes of all benchmarks are available with
+///   [`BenchmarkOutcome::all`](crate::BenchmarkOutcome::all)
+/// * __`process_setup`__ (optional): A function or any valid expression which is run in the
+///   benchmark process right before the benchmark function. Contrary to `setup`, which is run only
+///   once in its own process, the `process_setup` is run in every process which runs a benchmark.
+///   Use it for global initialization which has to happen in the same process as the benchmark
+///   like initializing the logging or starting shared fixtures.
+/// * __`process_teardown`__ (optional): Like `process_setup` but run in the benchmark process
+///   right after the benchmark function
 /// * __`library_benchmark_groups`__ (mandatory): The __name__ of one or more
 ///   [`library_benchmark_group!`](crate::library_benchmark_group) macros. Multiple __names__ are
 ///   expected to be a comma separated list
@@ -173,6 +189,39 @@ macro_rules! binary_benchmark_attribute {
 /// See also [Callgrind Command-line
 /// options](https://valgrind.org/docs/manual/cl-manual.html#cl-manual.options).
 ///
+/// The command-line arguments of the benchmark binary can be pre-processed with `args_filter`
+/// before the runner parses them, for example to translate a project-specific `--ci` flag:
+///
+/// ```rust
+/// # use iai_callgrind::{main, library_benchmark_group, library_benchmark};
+/// # #[library_benchmark]
+/// # fn bench_fibonacci() { }
+/// # library_benchmark_group!(
+/// #    name = some_group;
+/// #    benchmarks = bench_fibonacci
+/// # );
+/// use std::ffi::OsString;
+///
+/// fn ci_args(args: Vec<OsString>) -> Vec<OsString> {
+///     args.into_iter()
+///         .map(|arg| {
+///             if arg == "--ci" {
+///                 OsString::from("--output-format=json")
+///             } else {
+///                 arg
+///             }
+///         })
+///         .collect()
+/// }
+///
+/// # fn main() {
+/// main!(
+///     args_filter = ci_args;
+///     library_benchmark_groups = some_group
+/// );
+/// # }
+/// ```
+///
 /// For an in-depth description of library benchmarks and more examples see the
 /// [guide](https://iai-callgrind.github.io/iai-callgrind/latest/html/benchmarks/library_benchmarks.html).
 ///
@@ -229,6 +278,8 @@ macro_rules! main {
     };
     (
         $( config = $config:expr; $(;)* )?
+        $( metadata = [ $( ( $meta_key:expr, $meta_value:expr ) ),* $(,)* ] ; $(;)* )?
+        $( args_filter = $args_filter:expr ; $(;)* )?
         $( setup = $setup:expr ; $(;)* )?
         $( teardown = $teardown:expr ; $(;)* )?
         binary_benchmark_groups =
@@ -237,6 +288,8 @@ macro_rules! main {
     };
     (
         $( config = $config:expr; $(;)* )?
+        $( metadata = [ $( ( $meta_key:expr, $meta_value:expr ) ),* $(,)* ] ; $(;)* )?
+        $( args_filter = $args_filter:expr ; $(;)* )?
         $( setup = $setup:expr ; $(;)* )?
         $( teardown = $teardown:expr ; $(;)* )?
         binary_benchmark_groups = $( $group:ident ),+ $(,)*
@@ -260,10 +313,21 @@ macro_rules! main {
                 config = Some($config.into());
             )?
 
+            let mut args: Vec<String> = this_args.collect();
+            $(
+                args = $crate::__internal::filter_args($args_filter, args);
+            )?
+
             let mut groups_builder = $crate::__internal::bin_bench::GroupsBuilder::new(
-                config, this_args.collect(), __run_setup(false), __run_teardown(false),
+                config, args, __run_setup(false), __run_teardown(false),
             );
 
+            $(
+                $(
+                    groups_builder.add_metadata($meta_key, $meta_value);
+                )*
+            )?
+
             $(
                 let mut group = $crate::BinaryBenchmarkGroup::default();
                 $group::$group(&mut group);
@@ -378,16 +442,24 @@ macro_rules! main {
     };
     (
         $( config = $config:expr; $(;)* )?
+        $( metadata = [ $( ( $meta_key:expr, $meta_value:expr ) ),* $(,)* ] ; $(;)* )?
+        $( args_filter = $args_filter:expr ; $(;)* )?
         $( setup = $setup:expr ; $(;)* )?
         $( teardown = $teardown:expr ; $(;)* )?
+        $( process_setup = $process_setup:expr ; $(;)* )?
+        $( process_teardown = $process_teardown:expr ; $(;)* )?
         library_benchmark_groups =
     ) => {
         compile_error!("The library_benchmark_groups argument needs at least one `name` of a `library_benchmark_group!`");
     };
     (
         $( config = $config:expr ; $(;)* )?
+        $( metadata = [ $( ( $meta_key:expr, $meta_value:expr ) ),* $(,)* ] ; $(;)* )?
+        $( args_filter = $args_filter:expr ; $(;)* )?
         $( setup = $setup:expr ; $(;)* )?
         $( teardown = $teardown:expr ; $(;)* )?
+        $( process_setup = $process_setup:expr ; $(;)* )?
+        $( process_teardown = $process_teardown:expr ; $(;)* )?
         library_benchmark_groups = $( $group:ident ),+ $(,)*
     ) => {
         #[inline(never)]
@@ -410,15 +482,27 @@ macro_rules! main {
                 config = Some($config.into());
             )?
 
+            let mut args: Vec<String> = this_args.collect();
+            $(
+                args = $crate::__internal::filter_args($args_filter, args);
+            )?
+
             let mut groups_builder = $crate::__internal::lib_bench::GroupsBuilder::new(
-                config, this_args.collect(), __run_setup(false), __run_teardown(false),
+                config, args, __run_setup(false), __run_teardown(false),
             );
 
+            $(
+                $(
+                    groups_builder.add_metadata($meta_key, $meta_value);
+                )*
+            )?
+
             $(
                 groups_builder.add_group(
                     stringify!($group).to_owned(),
                     $group::__get_config(),
                     $group::__compare_by_id(),
+                    $group::__regression_budget(),
                     $group::__run_setup(false),
                     $group::__run_teardown(false),
                     $group::__BENCHES
@@ -458,6 +542,20 @@ macro_rules! main {
             __has_teardown
         }
 
+        #[inline(never)]
+        fn __run_process_setup() {
+            $(
+                $process_setup;
+            )?
+        }
+
+        #[inline(never)]
+        fn __run_process_teardown() {
+            $(
+                $process_teardown;
+            )?
+        }
+
         /// Keep the logic to run the benchmark function within the main function to avoid heap
         /// allocations in functions other than main which are part of the benchmarking framework.
         /// DHAT needs a fallback (matched with `file::*::*`) to the benchmark function (matched
@@ -479,6 +577,11 @@ macro_rules! main {
                     "teardown" if next.is_none() => {
                         __run_teardown(true);
                     },
+                    "__calibration" if next.is_none() => {
+                        std::hint::black_box(
+                            $crate::__internal::__iai_callgrind_wrapper_mod::calibration()
+                        );
+                    },
                     $(
                         stringify!($group) => {
                             match std::hint::black_box(
@@ -505,12 +608,22 @@ macro_rules! main {
                                             .parse::<usize>()
                                             .expect("Expecting a valid bench index")
                                     );
-                                    let iter_index = std::hint::black_box(
-                                        args_iter
-                                            .next()
-                                            .and_then(|a| a.parse::<usize>().ok())
+                                    // The module path follows if this isn't an iter benchmark
+                                    let iter_run = std::hint::black_box(
+                                        match args_iter.next().as_deref() {
+                                            Some("all") => $crate::__internal::InternalIterRun::All,
+                                            Some(value) => value.parse::<usize>().map_or(
+                                                $crate::__internal::InternalIterRun::Count,
+                                                $crate::__internal::InternalIterRun::Index
+                                            ),
+                                            None => $crate::__internal::InternalIterRun::Count,
+                                        }
                                     );
-                                    $group::__run(group_index, bench_index, iter_index);
+                                    __run_process_setup();
+                                    $crate::__internal::run_lib_bench(stringify!($group), || {
+                                        $group::__run(group_index, bench_index, iter_run);
+                                    });
+                                    __run_process_teardown();
                                 }
                             }
                         }
@@ -580,7 +693,9 @@ macro_rules! main {
 ///   specified in the `benchmarks` argument, are compared with each other as long as the ids (the
 ///   part after the `::` in `#[bench::id(...)]`) match.
 /// * __`setup`__ (optional): A function which is executed before all benchmarks in this group
-/// * __`teardown`__ (optional): A function which is executed after all benchmarks in this group
+/// * __`teardown`__ (optional): A function which is executed after all benchmarks in this group.
+///   The outcomes of the benchmarks of this group are available with
+///   [`BenchmarkOutcome::all`](crate::BenchmarkOutcome::all)
 /// * __`benchmarks`__ (mandatory): A `,`-separated list of `#[binary_benchmark]` annotated function
 ///   names you want to put into this group. Or, if you want to use the low level api
 ///
@@ -1184,6 +1299,7 @@ macro_rules! binary_benchmark_group {
 ///     name = my_group;
 ///     config = LibraryBenchmarkConfig::default();
 ///     compare_by_id = false;
+///     regression_budget = 3.0;
 ///     setup = group_setup();
 ///     teardown = group_teardown();
 ///     benchmarks = some_func
@@ -1198,10 +1314,17 @@ macro_rules! binary_benchmark_group {
 /// * __`compare_by_id`__ (optional): The default is false. If true, all benches in the benchmark
 ///   functions specified with the `benchmarks` argument, across any benchmark groups, are compared
 ///   with each other as long as the ids (the part after the `::` in `#[bench::id(...)]`) match.
+/// * __`regression_budget`__ (optional): The regression budget in percent (an `f64`) of the totals
+///   of this group. After all benchmarks of this group have run, the primary metrics
+///   (Instructions for callgrind and cachegrind, Total bytes for DHAT) of the benchmarks are
+///   summed up. If the sum regressed by more than the budget compared to the old or baseline
+///   run, the group is reported as regressed. The command-line argument
+///   `--group-regression-budget` overrides this value.
 /// * __`setup`__ (optional): A setup function or any valid expression which is run before all
 ///   benchmarks of this group
 /// * __`teardown`__ (optional): A teardown function or any valid expression which is run after all
-///   benchmarks of this group
+///   benchmarks of this group. The outcomes of the benchmarks of this group are available with
+///   [`BenchmarkOutcome::all`](crate::BenchmarkOutcome::all)
 /// * __`benchmarks`__ (mandatory): A list of comma separated benchmark functions which must be
 ///   annotated with `#[library_benchmark]`
 #[macro_export]
@@ -1209,6 +1332,7 @@ macro_rules! library_benchmark_group {
     (
         $( config = $config:expr ; $(;)* )?
         $( compare_by_id = $compare:literal ; $(;)* )?
+        $( regression_budget = $budget:expr ; $(;)* )?
         $( setup = $setup:expr ; $(;)* )?
         $( teardown = $teardown:expr ; $(;)* )?
         benchmarks = $( $function:ident ),+
@@ -1219,6 +1343,7 @@ macro_rules! library_benchmark_group {
         name = $name:ident;
         $( config = $config:expr ; $(;)* )?
         $( compare_by_id = $compare:literal ; $(;)* )?
+        $( regression_budget = $budget:expr ; $(;)* )?
         $( setup = $setup:expr ; $(;)* )?
         $( teardown = $teardown:expr ; $(;)* )?
         benchmarks =
@@ -1232,6 +1357,7 @@ macro_rules! library_benchmark_group {
         name = $name:ident; $(;)*
         $( config = $config:expr ; $(;)* )?
         $( compare_by_id = $compare:literal ; $(;)* )?
+        $( regression_budget = $budget:expr ; $(;)* )?
         $( setup = $setup:expr ; $(;)* )?
         $( teardown = $teardown:expr ; $(;)* )?
         benchmarks = $( $function:ident ),+ $(,)*
@@ -1271,6 +1397,15 @@ macro_rules! library_benchmark_group {
                 comp
             }
 
+            #[inline(never)]
+            pub fn __regression_budget() -> Option<f64> {
+                let mut budget = None;
+                $(
+                    budget = Some($budget);
+                )?
+                budget
+            }
+
             #[inline(never)]
             pub fn __run_setup(__run: bool) -> bool {
                 let mut __has_setup = false;
@@ -1296,10 +1431,17 @@ macro_rules! library_benchmark_group {
             }
 
             #[inline(never)]
-            pub fn __run(group_index: usize, bench_index: usize, iter_index: Option<usize>) {
+            pub fn __run(
+                group_index: usize,
+                bench_index: usize,
+                iter_run: $crate::__internal::InternalIterRun
+            ) {
                 match __BENCHES[group_index].2[bench_index].func {
                     $crate::__internal::InternalLibFunctionKind::Iter(func) => {
-                        (func)(iter_index);
+                        (func)(iter_run);
+                    }
+                    $crate::__internal::InternalLibFunctionKind::IterIds(func) => {
+                        (func)(iter_run.index());
                     }
                     $crate::__internal::InternalLibFunctionKind::Default(func) => {
                         (func)();
@@ -1309,3 +1451,167 @@ macro_rules! library_benchmark_group {
         }
     };
 }
+
+/// [criterion compatibility](crate::criterion_compat) only: Define a group of criterion-style
+/// benchmark functions
+///
+/// The group is a `library_benchmark_group!` and each of the `targets` is a library benchmark. The
+/// targets are functions with the signature `fn(&mut Criterion)` with the
+/// [`crate::criterion_compat::Criterion`]. The `config` is an expression evaluating to a
+/// [`crate::criterion_compat::Criterion`] and only exists to be compatible with criterion.
+///
+/// # Examples
+///
+/// ```rust
+/// use iai_callgrind::criterion_compat::{black_box, criterion_group, Criterion};
+///
+/// fn bench_sum(c: &mut Criterion) {
+///     c.bench_function("sum", |b| b.iter(|| (0..black_box(100u64)).sum::<u64>()));
+/// }
+///
+/// fn bench_product(c: &mut Criterion) {
+///     c.bench_function("product", |b| b.iter(|| (1..black_box(10u64)).product::<u64>()));
+/// }
+///
+/// criterion_group!(benches, bench_sum, bench_product);
+///
+/// // or with the long form
+/// criterion_group!(
+///     name = other_benches;
+///     config = Criterion::default();
+///     targets = bench_sum, bench_product
+/// );
+/// # fn main() {}
+/// ```
+#[macro_export]
+macro_rules! criterion_group {
+    (
+        name = $name:ident; $(;)*
+        config = $config:expr; $(;)*
+        targets = $( $target:ident ),+ $(,)*
+    ) => {
+        pub mod $name {
+            use super::*;
+
+            pub const __BENCHES: &[&(
+                &'static str,
+                fn() -> Option<$crate::__internal::InternalLibraryBenchmarkConfig>,
+                &[$crate::__internal::InternalMacroLibBench]
+            )]= &[
+                $(
+                    &(
+                        stringify!($target),
+                        || None,
+                        &[
+                            $crate::__internal::InternalMacroLibBench {
+                                id_display: None,
+                                args_display: None,
+                                description: None,
+                                expect_regression: None,
+                                func: $crate::__internal::InternalLibFunctionKind::IterIds(
+                                    |index| $crate::criterion_compat::__run(
+                                        super::$target, $config, index
+                                    )
+                                ),
+                                config: None,
+                                key: None,
+                                runs_on: None,
+                                skip: None,
+                            }
+                        ]
+                    )
+                ),+
+            ];
+
+            #[inline(never)]
+            pub fn __get_config() -> Option<$crate::__internal::InternalLibraryBenchmarkConfig> {
+                None
+            }
+
+            #[inline(never)]
+            pub fn __compare_by_id() -> Option<bool> {
+                None
+            }
+
+            #[inline(never)]
+            pub fn __regression_budget() -> Option<f64> {
+                None
+            }
+
+            #[inline(never)]
+            pub fn __run_setup(__run: bool) -> bool {
+                false
+            }
+
+            #[inline(never)]
+            pub fn __run_teardown(__run: bool) -> bool {
+                false
+            }
+
+            #[inline(never)]
+            pub fn __run(
+                group_index: usize,
+                bench_index: usize,
+                iter_run: $crate::__internal::InternalIterRun
+            ) {
+                match __BENCHES[group_index].2[bench_index].func {
+                    $crate::__internal::InternalLibFunctionKind::Iter(func) => {
+                        (func)(iter_run);
+                    }
+                    $crate::__internal::InternalLibFunctionKind::IterIds(func) => {
+                        (func)(iter_run.index());
+                    }
+                    $crate::__internal::InternalLibFunctionKind::Default(func) => {
+                        (func)();
+                    }
+                }
+            }
+        }
+    };
+    ( $name:ident, $( $target:ident ),+ $(,)* ) => {
+        $crate::criterion_group!(
+            name = $name;
+            config = $crate::criterion_compat::Criterion::default();
+            targets = $( $target ),+
+        );
+    };
+}
+
+/// [criterion compatibility](crate::criterion_compat) only: Create the `main` function of the
+/// benchmark harness for groups defined with [`crate::criterion_group`]
+///
+/// This macro is a shortcut for the [`crate::main`] macro with `library_benchmark_groups`. As an
+/// extension to criterion, an optional [`crate::LibraryBenchmarkConfig`] can be applied to all
+/// groups with the `config` argument.
+///
+/// # Examples
+///
+/// ```rust
+/// use iai_callgrind::criterion_compat::{black_box, criterion_group, criterion_main, Criterion};
+/// use iai_callgrind::{Callgrind, LibraryBenchmarkConfig};
+///
+/// fn bench_sum(c: &mut Criterion) {
+///     c.bench_function("sum", |b| b.iter(|| (0..black_box(100u64)).sum::<u64>()));
+/// }
+///
+/// criterion_group!(benches, bench_sum);
+/// # fn main() {
+/// criterion_main!(
+///     config = LibraryBenchmarkConfig::default()
+///         .tool(Callgrind::with_args(["--branch-sim=yes"]));
+///     benches
+/// );
+/// # }
+/// ```
+#[macro_export]
+macro_rules! criterion_main {
+    ( config = $config:expr ; $(;)* $( $group:ident ),+ $(,)* ) => {
+        $crate::main!(
+            config = $config;
+            library_benchmark_groups = $( $group ),+
+        );
+    };
+    ( $( $group:ident ),+ $(,)* ) => {
+        $crate::main!(library_benchmark_groups = $( $group ),+);
+    };
+}
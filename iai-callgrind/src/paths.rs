@@ -0,0 +1,186 @@
+//! Helpers to resolve the paths of the workspace in benchmarks
+//!
+//! The functions of this module can be used everywhere in the benchmark file, for example in the
+//! `setup` and `teardown` functions of binary benchmarks or when building the
+//! [`Command`](crate::Command), to compute the locations of fixtures and other artifacts without
+//! having to hard-code paths relative to the current directory:
+//!
+//! ```rust
+//! # macro_rules! env { ($m:tt) => {{ "/some/path" }} }
+//! use iai_callgrind::{binary_benchmark, binary_benchmark_group, main, paths};
+//!
+//! #[binary_benchmark]
+//! fn bench_binary() -> iai_callgrind::Command {
+//!     let fixture = paths::workspace_root().join("benches/fixtures/foo.txt");
+//!     iai_callgrind::Command::new(env!("CARGO_BIN_EXE_my-foo"))
+//!         .arg(fixture)
+//!         .build()
+//! }
+//!
+//! binary_benchmark_group!(name = my_group; benchmarks = bench_binary);
+//! # fn main() {
+//! main!(binary_benchmark_groups = my_group);
+//! # }
+//! ```
+//!
+//! In the `setup` and `teardown` functions of binary benchmarks, the paths are passed from
+//! `iai-callgrind-runner` to the benchmark in environment variables. Everywhere else, the paths are
+//! resolved with `cargo metadata` once and then cached.
+
+use std::ffi::{OsStr, OsString};
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::OnceLock;
+
+/// The environment variable of the benchmark output directory set by the runner
+const BENCH_OUTPUT_DIR_ENV: &str = "_BENCH_OUTPUT_DIR";
+/// The environment variable of the cargo target directory set by the runner
+const TARGET_DIR_ENV: &str = "_TARGET_DIR";
+/// The environment variable of the workspace root set by the runner
+const WORKSPACE_ROOT_ENV: &str = "_WORKSPACE_ROOT";
+
+/// The paths of the workspace as reported by `cargo metadata`
+#[derive(Debug)]
+struct CargoMetadata {
+    target_directory: PathBuf,
+    workspace_root: PathBuf,
+}
+
+impl CargoMetadata {
+    /// Return the cached `CargoMetadata` and query `cargo metadata` if not cached yet
+    fn get() -> &'static Self {
+        static METADATA: OnceLock<CargoMetadata> = OnceLock::new();
+        METADATA.get_or_init(Self::query)
+    }
+
+    fn query() -> Self {
+        let cargo = std::env::var_os("CARGO").unwrap_or_else(|| OsString::from("cargo"));
+        let mut command = Command::new(cargo);
+        command.args(["metadata", "--no-deps", "--format-version", "1"]);
+        if let Some(manifest_dir) = std::env::var_os("CARGO_MANIFEST_DIR") {
+            command
+                .arg("--manifest-path")
+                .arg(PathBuf::from(manifest_dir).join("Cargo.toml"));
+        }
+
+        let output = command
+            .output()
+            .expect("Running 'cargo metadata' should succeed");
+        assert!(
+            output.status.success(),
+            "'cargo metadata' failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+
+        let json = String::from_utf8_lossy(&output.stdout);
+        let value = |key: &str| {
+            json_string_value(&json, key).map_or_else(
+                || panic!("The output of 'cargo metadata' should contain '{key}'"),
+                PathBuf::from,
+            )
+        };
+
+        Self {
+            target_directory: value("target_directory"),
+            workspace_root: value("workspace_root"),
+        }
+    }
+}
+
+/// Return the output directory of the benchmark file
+///
+/// This is the directory in which `iai-callgrind` stores the output files of all benchmarks of the
+/// current benchmark file, per default `$TARGET_DIR/iai/$PACKAGE_NAME/$BENCHMARK_FILE`. Outside of
+/// the `setup` and `teardown` functions of binary benchmarks, the command-line arguments
+/// `--home` and `--separate-targets` are not taken into account.
+pub fn bench_output_dir() -> PathBuf {
+    if let Some(dir) = std::env::var_os(BENCH_OUTPUT_DIR_ENV) {
+        return PathBuf::from(dir);
+    }
+
+    let package = std::env::var_os("CARGO_PKG_NAME").unwrap_or_default();
+    let bench = std::env::current_exe()
+        .ok()
+        .as_deref()
+        .and_then(|exe| exe.file_stem())
+        .and_then(OsStr::to_str)
+        .map(|stem| stem.rsplit_once('-').map_or(stem, |(name, _hash)| name))
+        .unwrap_or_default()
+        .replace('-', "_");
+
+    target_dir().join("iai").join(package).join(bench)
+}
+
+/// Return the target directory of cargo
+///
+/// This is the directory in which cargo stores all build artifacts, per default
+/// `$WORKSPACE_ROOT/target`. A custom target directory (for example with `CARGO_TARGET_DIR`) is
+/// taken into account.
+pub fn target_dir() -> PathBuf {
+    std::env::var_os(TARGET_DIR_ENV).map_or_else(
+        || CargoMetadata::get().target_directory.clone(),
+        PathBuf::from,
+    )
+}
+
+/// Return the root directory of the workspace
+///
+/// In a multi-crate workspace this is the directory with the top-level `Cargo.toml`. Otherwise, it
+/// is simply the directory with your `Cargo.toml` file in it.
+pub fn workspace_root() -> PathBuf {
+    std::env::var_os(WORKSPACE_ROOT_ENV).map_or_else(
+        || CargoMetadata::get().workspace_root.clone(),
+        PathBuf::from,
+    )
+}
+
+/// Return the value of the string `key` in the `json` output of `cargo metadata`
+///
+/// The top-level keys like `workspace_root` are the last keys in the output, so the last
+/// occurrence of the `key` is used.
+fn json_string_value(json: &str, key: &str) -> Option<String> {
+    let needle = format!("\"{key}\":\"");
+    let start = json.rfind(&needle)? + needle.len();
+
+    let mut value = String::new();
+    let mut chars = json[start..].chars();
+    loop {
+        match chars.next()? {
+            '"' => return Some(value),
+            '\\' => match chars.next()? {
+                'n' => value.push('\n'),
+                't' => value.push('\t'),
+                'r' => value.push('\r'),
+                'u' => {
+                    let hex = chars.by_ref().take(4).collect::<String>();
+                    value.push(char::from_u32(u32::from_str_radix(&hex, 16).ok()?)?);
+                }
+                c => value.push(c),
+            },
+            c => value.push(c),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case::simple(r#"{"workspace_root":"/some/path"}"#, Some("/some/path"))]
+    #[case::escaped(r#"{"workspace_root":"/some/\"pa\\thä"}"#, Some("/some/\"pa\\thä"))]
+    #[case::nested_is_ignored(
+        r#"{"packages":[{"workspace_root":"/nested"}],"workspace_root":"/root"}"#,
+        Some("/root")
+    )]
+    #[case::missing(r#"{"target_directory":"/some/path"}"#, None)]
+    #[case::unterminated(r#"{"workspace_root":"/some/path"#, None)]
+    fn test_json_string_value(#[case] json: &str, #[case] expected: Option<&str>) {
+        assert_eq!(
+            json_string_value(json, "workspace_root"),
+            expected.map(ToOwned::to_owned)
+        );
+    }
+}
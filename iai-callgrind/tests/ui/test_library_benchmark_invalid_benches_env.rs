@@ -0,0 +1,27 @@
+use iai_callgrind::library_benchmark;
+
+#[library_benchmark]
+#[benches::invalid(args = env!("IAI_CALLGRIND_UI_TEST_INVALID"))]
+fn bench_invalid(value: u64) -> u64 {
+    value
+}
+
+#[library_benchmark]
+#[benches::empty(args = env!("IAI_CALLGRIND_UI_TEST_EMPTY"))]
+fn bench_empty(value: u64) -> u64 {
+    value
+}
+
+#[library_benchmark]
+#[benches::unset(args = env!("IAI_CALLGRIND_UI_TEST_UNSET"))]
+fn bench_unset(value: u64) -> u64 {
+    value
+}
+
+#[library_benchmark]
+#[benches::wrong_type(args = env!("IAI_CALLGRIND_UI_TEST_STRINGS"))]
+fn bench_wrong_type(value: u64) -> u64 {
+    value
+}
+
+fn main() {}
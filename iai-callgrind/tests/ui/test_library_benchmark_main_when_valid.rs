@@ -0,0 +1,21 @@
+use iai_callgrind::{library_benchmark_main, LibraryBenchmarkConfig};
+
+#[library_benchmark_main(config = LibraryBenchmarkConfig::default())]
+mod benches {
+    use std::hint::black_box;
+
+    use iai_callgrind::{library_benchmark, library_benchmark_group};
+
+    #[library_benchmark]
+    fn bench_without_group() -> u64 {
+        black_box(1)
+    }
+
+    #[library_benchmark]
+    #[bench::some(2)]
+    fn bench_in_group(value: u64) -> u64 {
+        black_box(value)
+    }
+
+    library_benchmark_group!(name = my_group; benchmarks = bench_in_group);
+}
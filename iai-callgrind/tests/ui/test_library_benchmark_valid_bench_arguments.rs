@@ -71,4 +71,32 @@ pub fn bench10(my: u64) -> u64 {
     }
 }
 
+#[library_benchmark]
+#[bench::id1(args = (8), runs_on = "x86_64-unknown-linux-gnu")]
+#[bench::id2(args = (8), runs_on = "!x86_64-unknown-linux-gnu")]
+fn bench11(arg: u8) -> u8 {
+    arg
+}
+
+#[library_benchmark]
+#[bench::id1(args = (8), rust_version = ">=1.0")]
+#[bench::id2(args = (does_not_exist()), rust_version = "<1.0")]
+fn bench12(arg: u8) -> u8 {
+    arg
+}
+
+#[library_benchmark(rust_version = "<1.0")]
+#[bench::id1(args = (8))]
+fn bench13(arg: u8) -> u8 {
+    does_not_exist(arg)
+}
+
+#[library_benchmark(key = "parser/hot")]
+#[bench::id1(args = (8), key = "first")]
+#[bench::id2(args = (8))]
+#[benches::id3(args = [8, 9], key = "multiple")]
+fn bench14(arg: u8) -> u8 {
+    arg
+}
+
 fn main() {}
@@ -0,0 +1,20 @@
+use iai_callgrind::library_benchmark;
+
+fn some_setup(value: u64) -> u64 {
+    value + 1
+}
+
+#[library_benchmark]
+#[benches::sizes(args = env!("IAI_CALLGRIND_UI_TEST_SIZES"))]
+#[benches::with_setup(args = env!("IAI_CALLGRIND_UI_TEST_SIZES"), setup = some_setup)]
+fn bench_sizes(value: u64) -> u64 {
+    value
+}
+
+#[library_benchmark]
+#[benches::strings(args = env!("IAI_CALLGRIND_UI_TEST_STRINGS"))]
+fn bench_strings(value: &str) -> usize {
+    value.len()
+}
+
+fn main() {}
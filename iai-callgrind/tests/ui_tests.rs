@@ -41,6 +41,14 @@ fn ui() {
     }
     fs_extra::copy_items(&[from], &to, &CopyOptions::default()).unwrap();
 
+    // The environment variables of the `#[benches::id(args = env!("NAME"))]` tests. The build
+    // of the test cases inherits the environment of this process.
+    std::env::set_var("IAI_CALLGRIND_UI_TEST_SIZES", "1, 2 + 3, (4),");
+    std::env::set_var("IAI_CALLGRIND_UI_TEST_STRINGS", r#""a", "bc""#);
+    std::env::set_var("IAI_CALLGRIND_UI_TEST_INVALID", "1, +");
+    std::env::set_var("IAI_CALLGRIND_UI_TEST_EMPTY", "");
+    std::env::remove_var("IAI_CALLGRIND_UI_TEST_UNSET");
+
     let t = trybuild::TestCases::new();
     t.compile_fail("tests/ui/test_*_invalid*.rs");
     t.pass("tests/ui/test_*_valid*.rs");